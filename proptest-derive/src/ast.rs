@@ -16,6 +16,7 @@ use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, TokenStreamExt};
 use syn::spanned::Spanned;
 
+use crate::attr::Migration;
 use crate::error::{Ctx, DeriveResult};
 use crate::use_tracking::UseTracker;
 use crate::util::self_ty;
@@ -60,6 +61,18 @@ pub struct Impl {
     tracker: UseTracker,
     /// The three main parts, see description of `ImplParts` for details.
     parts: ImplParts,
+    /// The newer version the type migrates to, if any.
+    migration: Option<Migration>,
+}
+
+/// What linearising an `Impl` produces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Output {
+    /// An impl of `Arbitrary`, for `#[derive(Arbitrary)]`.
+    Arbitrary,
+    /// An inherent `fn transitions() -> BoxedStrategy<Self>`, for
+    /// `#[derive(Transition)]`.
+    Transitions,
 }
 
 /// The three main parts to deriving `Arbitrary` for a type.
@@ -75,16 +88,28 @@ impl Impl {
             typ,
             tracker,
             parts,
+            migration: None,
         }
     }
 
+    /// Sets the newer version the type migrates to, for which an impl of
+    /// `MigratesTo` is produced along with the rest.
+    pub fn with_migration(self, migration: Option<Migration>) -> Self {
+        Self { migration, ..self }
+    }
+
     /// Linearises the impl into a sequence of tokens.
     /// This produces the actual Rust code for the impl.
-    pub fn into_tokens(self, ctx: Ctx) -> DeriveResult<TokenStream> {
+    pub fn into_tokens(
+        self,
+        ctx: Ctx,
+        output: Output,
+    ) -> DeriveResult<TokenStream> {
         let Impl {
             typ,
             mut tracker,
             parts: (params, strategy, ctor),
+            migration,
         } = self;
 
         /// A `Debug` bound on a type variable.
@@ -105,6 +130,54 @@ impl Impl {
 
         let _top = call_site_ident(TOP_PARAM_NAME);
 
+        let item = match output {
+            Output::Arbitrary => quote! {
+                impl #impl_generics _proptest::arbitrary::Arbitrary
+                for #typ #ty_generics #where_clause {
+                    type Parameters = #params;
+
+                    type Strategy = #strategy;
+
+                    fn arbitrary_with(#_top: Self::Parameters) -> Self::Strategy {
+                        #ctor
+                    }
+                }
+            },
+            // The strategy is the same as for `Arbitrary` with default
+            // parameters, but boxed so that it can be returned from
+            // `ReferenceStateMachine::transitions`.
+            Output::Transitions => quote! {
+                impl #impl_generics #typ #ty_generics #where_clause {
+                    /// Returns a strategy generating values of this type as
+                    /// configured by its `#[proptest(..)]` attributes, for
+                    /// use as the transitions of a state machine test.
+                    #[allow(dead_code)]
+                    pub fn transitions()
+                        -> _proptest::strategy::BoxedStrategy<Self>
+                    where
+                        Self: 'static,
+                    {
+                        let #_top: #params =
+                            ::core::default::Default::default();
+                        _proptest::strategy::Strategy::boxed(#ctor)
+                    }
+                }
+            },
+        };
+
+        // Upgrading to a newer version is independent of how the type is
+        // generated, so it is the same for all outputs.
+        let migration = migration.map(|Migration { to, via }| {
+            quote! {
+                impl #impl_generics _proptest::strategy::MigratesTo<#to>
+                for #typ #ty_generics #where_clause {
+                    fn migrate(&self) -> #to {
+                        #via(self)
+                    }
+                }
+            }
+        });
+
         // Linearise everything. We're done after this.
         //
         // NOTE: The clippy::arc_with_non_send_sync lint is disabled here because the strategies
@@ -119,16 +192,9 @@ impl Impl {
             const _: () = {
             use proptest as _proptest;
 
-            impl #impl_generics _proptest::arbitrary::Arbitrary
-            for #typ #ty_generics #where_clause {
-                type Parameters = #params;
+            #item
 
-                type Strategy = #strategy;
-
-                fn arbitrary_with(#_top: Self::Parameters) -> Self::Strategy {
-                    #ctor
-                }
-            }
+            #migration
 
             };
         };
@@ -231,6 +297,44 @@ pub fn pair_oneof(
     (Strategy::Union(strats.into()), Ctor::Union(ctors.into()))
 }
 
+/// A weighted `Ctor` of a lazy union, along with the parameter registers the
+/// `Ctor` moves from.
+pub type LazySummand = (u32, Ctor, Box<[usize]>);
+
+/// Like `pair_oneof`, but each summand is only constructed once it is picked.
+///
+/// Alongside its weight, each summand lists the parameter registers its
+/// constructor moves from, whose types are looked up in `params`. These are
+/// moved into the deferred constructor of the summand.
+#[cfg(not(feature = "boxed_union"))]
+pub fn pair_lazy_oneof(
+    (strats, ctors): (Vec<Strategy>, Vec<LazySummand>),
+    params: &Params,
+) -> StratPair {
+    let strats = strats
+        .into_iter()
+        .zip(&ctors)
+        .map(|(strat, (_, _, regs))| {
+            (
+                strat,
+                regs.iter().map(|&reg| params.0[reg].clone()).collect(),
+            )
+        })
+        .collect();
+    (Strategy::LazyUnion(strats), Ctor::LazyUnion(ctors.into()))
+}
+
+/// Boxed unions construct all of their summands upfront, so this is just
+/// `pair_oneof`.
+#[cfg(feature = "boxed_union")]
+pub fn pair_lazy_oneof(
+    (strats, ctors): (Vec<Strategy>, Vec<LazySummand>),
+    _params: &Params,
+) -> StratPair {
+    let ctors = ctors.into_iter().map(|(w, ctor, _)| (w, ctor)).collect();
+    pair_oneof((strats, ctors))
+}
+
 /// Potentially apply a filter to a strategy type and its constructor.
 pub fn pair_filter(
     filter: Vec<syn::Expr>,
@@ -328,6 +432,10 @@ pub enum Strategy {
     /// other words randomly pick one strategy with probabilities based on the
     /// specified weights.
     Union(Box<[Strategy]>),
+    /// Like `Union`, but each strategy is wrapped in a `LazyStrategy` taking
+    /// parameters of the given types.
+    #[cfg(not(feature = "boxed_union"))]
+    LazyUnion(Box<[(Strategy, Box<[syn::Type]>)]>),
     /// A filtered strategy with `.prop_filter`.
     Filter(Box<Strategy>, syn::Type),
 }
@@ -348,6 +456,10 @@ impl Strategy {
             Value(ty) => vec![ty.clone()],
             Map(strats) => strats.iter().flat_map(|s| s.types()).collect(),
             Union(strats) => strats.iter().flat_map(|s| s.types()).collect(),
+            #[cfg(not(feature = "boxed_union"))]
+            LazyUnion(strats) => {
+                strats.iter().flat_map(|(s, _)| s.types()).collect()
+            }
             Filter(_, ty) => vec![ty.clone()],
         }
     }
@@ -383,6 +495,8 @@ impl ToTokens for Strategy {
             Union(strats) => union_strat_to_tokens(tokens, strats),
             #[cfg(feature = "boxed_union")]
             Union(strats) => union_strat_to_tokens_boxed(tokens, strats),
+            #[cfg(not(feature = "boxed_union"))]
+            LazyUnion(strats) => lazy_union_strat_to_tokens(tokens, strats),
             Filter(strat, ty) => quote_append!(tokens,
                 _proptest::strategy::Filter<#strat, fn(&#ty) -> bool>
             ),
@@ -437,6 +551,10 @@ pub enum Ctor {
     /// A strategy that randomly selects one of the given relative-weighted
     /// strategies.
     Union(Box<[(u32, Ctor)]>),
+    /// Like `Union`, but each strategy is only constructed once picked, by a
+    /// `LazyStrategy` which is given the listed parameter registers.
+    #[cfg(not(feature = "boxed_union"))]
+    LazyUnion(Box<[LazySummand]>),
     /// A let binding that moves to and declares the `ToReg` from the `FromReg`
     /// as well as the strategy that uses the `ToReg`.
     Extract(Box<Ctor>, ToReg, FromReg),
@@ -521,6 +639,8 @@ impl ToTokens for Ctor {
             Union(ctors) => union_ctor_to_tokens(tokens, ctors),
             #[cfg(feature = "boxed_union")]
             Union(ctors) => union_ctor_to_tokens_boxed(tokens, ctors),
+            #[cfg(not(feature = "boxed_union"))]
+            LazyUnion(ctors) => lazy_union_ctor_to_tokens(tokens, ctors),
         }
     }
 }
@@ -721,6 +841,165 @@ fn union_strat_to_tokens(tokens: &mut TokenStream, strats: &[Strategy]) {
     }
 }
 
+/// Tokenizes a weighted list of `Ctor` into a `LazyTupleUnion`, where each
+/// `Ctor` is deferred into a `LazyStrategy` given its parameter registers.
+///
+/// The nesting is the same as in `union_ctor_to_tokens`, except that each
+/// nested union is deferred as well, taking the registers of all the `Ctor`s
+/// in it. Thus only the summands that are picked are ever constructed.
+#[cfg(not(feature = "boxed_union"))]
+fn lazy_union_ctor_to_tokens(tokens: &mut TokenStream, ctors: &[LazySummand]) {
+    if ctors.is_empty() {
+        return;
+    }
+
+    if let [(_, ctor, _)] = ctors {
+        // This is not a union at all - user provided an enum with one variant.
+        ctor.to_tokens(tokens);
+        return;
+    }
+
+    let mut chunks = ctors.chunks(UNION_CHUNK_SIZE);
+    let chunk = chunks.next().unwrap();
+    let head = chunk.iter().map(wrap_lazy);
+    let tail = Recurse(weight_sum(ctors) - weight_sum(chunk), chunks);
+
+    quote_append!(tokens,
+        _proptest::strategy::LazyTupleUnion::new(( #(#head,)* #tail ))
+    );
+
+    struct Recurse<'a>(u32, ::std::slice::Chunks<'a, LazySummand>);
+
+    impl<'a> ToTokens for Recurse<'a> {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let (tweight, mut chunks) = (self.0, self.1.clone());
+
+            if let Some(chunk) = chunks.next() {
+                if let [summand] = chunk {
+                    // Only one element left - no need to nest.
+                    wrap_lazy(summand).to_tokens(tokens);
+                } else {
+                    let regs = chunk
+                        .iter()
+                        .chain(chunks.clone().flatten())
+                        .flat_map(|(_, _, regs)| regs.iter().cloned());
+                    let head = chunk.iter().map(wrap_lazy);
+                    let tail = Recurse(tweight - weight_sum(chunk), chunks);
+                    let lazy = lazy_arc(
+                        regs,
+                        quote!(
+                            _proptest::strategy::LazyTupleUnion::new((
+                                #(#head,)* #tail
+                            ))
+                        ),
+                    );
+                    quote_append!(tokens, (#tweight, #lazy));
+                }
+            }
+        }
+    }
+
+    fn weight_sum(ctors: &[LazySummand]) -> u32 {
+        use std::num::Wrapping;
+        let Wrapping(x) = ctors.iter().map(|&(w, _, _)| Wrapping(w)).sum();
+        x
+    }
+
+    fn wrap_lazy(arg: &LazySummand) -> TokenStream {
+        let (w, c, regs) = arg;
+        let lazy = lazy_arc(regs.iter().cloned(), c.to_token_stream());
+        quote!( (#w, #lazy) )
+    }
+
+    fn lazy_arc(
+        regs: impl Iterator<Item = usize>,
+        ctor: TokenStream,
+    ) -> TokenStream {
+        let regs: Vec<_> = regs.map(param).collect();
+        quote!(
+            ::std::sync::Arc::new(_proptest::strategy::LazyStrategy::new(
+                ( #(#regs,)* ),
+                |( #(#regs,)* )| #ctor
+            ))
+        )
+    }
+}
+
+/// Tokenizes a weighted list of `Strategy` with the types of their
+/// parameters. For details, see `lazy_union_ctor_to_tokens`.
+#[cfg(not(feature = "boxed_union"))]
+fn lazy_union_strat_to_tokens(
+    tokens: &mut TokenStream,
+    strats: &[(Strategy, Box<[syn::Type]>)],
+) {
+    if strats.is_empty() {
+        return;
+    }
+
+    if let [(strat, _)] = strats {
+        // This is not a union at all - user provided an enum with one variant.
+        strat.to_tokens(tokens);
+        return;
+    }
+
+    let mut chunks = strats.chunks(UNION_CHUNK_SIZE);
+    let chunk = chunks.next().unwrap();
+    let head = chunk.iter().map(wrap_lazy);
+    let tail = Recurse(chunks);
+
+    quote_append!(tokens,
+        _proptest::strategy::LazyTupleUnion<( #(#head,)* #tail )>
+    );
+
+    struct Recurse<'a>(::std::slice::Chunks<'a, (Strategy, Box<[syn::Type]>)>);
+
+    impl<'a> ToTokens for Recurse<'a> {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            let mut chunks = self.0.clone();
+
+            if let Some(chunk) = chunks.next() {
+                if let [summand] = chunk {
+                    // Only one element left - no need to nest.
+                    wrap_lazy(summand).to_tokens(tokens);
+                } else {
+                    let tys = chunk
+                        .iter()
+                        .chain(chunks.clone().flatten())
+                        .flat_map(|(_, tys)| tys.iter());
+                    let head = chunk.iter().map(wrap_lazy);
+                    let tail = Recurse(chunks.clone());
+                    lazy_arc(
+                        tys,
+                        quote!(
+                            _proptest::strategy::LazyTupleUnion<(
+                                #(#head,)* #tail
+                            )>
+                        ),
+                    )
+                    .to_tokens(tokens);
+                }
+            }
+        }
+    }
+
+    fn wrap_lazy(arg: &(Strategy, Box<[syn::Type]>)) -> TokenStream {
+        let (s, tys) = arg;
+        lazy_arc(tys.iter(), s.to_token_stream())
+    }
+
+    fn lazy_arc<'a>(
+        tys: impl Iterator<Item = &'a syn::Type>,
+        strat: TokenStream,
+    ) -> TokenStream {
+        quote!(
+            (u32, ::std::sync::Arc<_proptest::strategy::LazyStrategy<
+                ( #(#tys,)* ),
+                #strat
+            >>)
+        )
+    }
+}
+
 /// Tokenizes a weighted list of `Ctor`.
 ///
 /// This can be used instead of `union_ctor_to_tokens` to generate a boxing
@@ -789,12 +1068,41 @@ fn param<'a>(fv: usize) -> FreshVar<'a> {
 
 /// Constructs a `MapClosure` for the given `path` and a list of fields.
 pub fn map_closure(path: syn::Path, fs: &[syn::Field]) -> MapClosure {
-    MapClosure(path, fs.to_owned())
+    MapClosure(path, fs.to_owned(), Vec::new())
 }
 
 /// A `MapClosure` models the closure part inside a `.prop_map(..)` call.
 #[derive(Debug)]
-pub struct MapClosure(syn::Path, Vec<syn::Field>);
+pub struct MapClosure(syn::Path, Vec<syn::Field>, Vec<DerivedField>);
+
+/// A field whose value is not generated by a strategy of its own, but
+/// computed inside the `MapClosure` by a `value` expression which refers to
+/// earlier fields by name. The strategy at its position produces `()`.
+#[derive(Debug)]
+struct DerivedField {
+    /// The position of the field.
+    index: usize,
+    /// The earlier fields the expression refers to, and their positions.
+    siblings: Vec<(usize, syn::Ident)>,
+    expr: syn::Expr,
+}
+
+impl MapClosure {
+    /// Compute the field at `index` with `expr` inside the closure, binding
+    /// each of `siblings` to a reference to the value of the earlier field.
+    pub fn derive_field(
+        &mut self,
+        index: usize,
+        siblings: Vec<(usize, syn::Ident)>,
+        expr: syn::Expr,
+    ) {
+        self.2.push(DerivedField {
+            index,
+            siblings,
+            expr,
+        });
+    }
+}
 
 impl ToTokens for MapClosure {
     fn to_tokens(&self, tokens: &mut TokenStream) {
@@ -802,7 +1110,7 @@ impl ToTokens for MapClosure {
             fresh_var("tmp", idx)
         }
 
-        let MapClosure(path, fields) = self;
+        let MapClosure(path, fields, derived) = self;
         let count = fields.len();
         let tmps: Vec<_> = (0..count).map(tmp_var).collect();
         let inits = fields.iter().enumerate().map(|(idx, field)| {
@@ -814,8 +1122,37 @@ impl ToTokens for MapClosure {
                 quote_spanned!(field.span()=> #name: #tv )
             }
         });
-        let tmps = NestedTuple(&tmps);
-        quote_append!(tokens, | #tmps | #path { #(#inits),* } );
+        if derived.is_empty() {
+            let tmps = NestedTuple(&tmps);
+            quote_append!(tokens, | #tmps | #path { #(#inits),* } );
+        } else {
+            let lets = derived.iter().map(|d| {
+                let tv = tmp_var(d.index);
+                let ty = &fields[d.index].ty;
+                let expr = &d.expr;
+                let binds = d.siblings.iter().map(|(idx, name)| {
+                    let sv = tmp_var(*idx);
+                    quote!( let #name = &#sv; )
+                });
+                quote!( let #tv: #ty = { #(#binds)* #expr }; )
+            });
+            // Derived fields are only `()` placeholders in the input.
+            let pats: Vec<_> = tmps
+                .iter()
+                .enumerate()
+                .map(|(idx, tv)| {
+                    if derived.iter().any(|d| d.index == idx) {
+                        quote!(_)
+                    } else {
+                        quote!(#tv)
+                    }
+                })
+                .collect();
+            let pats = NestedTuple(&pats);
+            quote_append!(tokens,
+                | #pats | { #(#lets)* #path { #(#inits),* } }
+            );
+        }
     }
 }
 
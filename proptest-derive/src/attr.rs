@@ -11,7 +11,7 @@
 use quote::ToTokens;
 use syn::parse::Parser;
 use syn::punctuated::Punctuated;
-use syn::{self, Attribute, Expr, Ident, Lit, Meta, Type};
+use syn::{self, Attribute, Expr, Ident, Lit, Meta, Path, Type};
 
 use crate::error::{self, Ctx, DeriveResult};
 use crate::interp;
@@ -38,6 +38,19 @@ pub struct ParsedAttributes {
     pub filter: Vec<syn::Expr>,
     /// True if no_bound was specified.
     pub no_bound: bool,
+    /// The newer version of the type and how to upgrade to it, if any.
+    /// This is only valid for type definitions.
+    pub migration: Option<Migration>,
+}
+
+/// The type a type migrates to and the function upgrading a value to it,
+/// as given by `#[proptest(migrates_to = "<type>", via = "<path>")]`.
+#[derive(Clone)]
+pub struct Migration {
+    /// The newer version of the type.
+    pub to: Type,
+    /// The function of type `fn(&Self) -> <to>` upgrading a value.
+    pub via: Path,
 }
 
 /// The mode for the associated item `Strategy` to use.
@@ -127,6 +140,9 @@ pub fn parse_attributes(
     if attrs.no_bound {
         error::no_bound_set_on_non_tyvar(ctx);
     }
+    if attrs.migration.is_some() {
+        error::migration_set_on_non_type(ctx);
+    }
     Ok(attrs)
 }
 
@@ -145,6 +161,9 @@ pub fn parse_top_attributes(
 pub fn has_no_bound(ctx: Ctx, attrs: &[Attribute]) -> DeriveResult<bool> {
     let attrs = parse_attributes_base(ctx, attrs)?;
     error::if_anything_specified(ctx, &attrs, error::TY_VAR);
+    if attrs.migration.is_some() {
+        error::migration_set_on_non_type(ctx);
+    }
     Ok(attrs.no_bound)
 }
 
@@ -163,8 +182,16 @@ fn parse_attributes_base(
         // Process params and no_params together to see which one to use.
         params: parse_params_mode(ctx, acc.no_params, acc.params)?,
         // Process strategy and value together to see which one to use.
-        strategy: parse_strat_mode(ctx, acc.strategy, acc.value, acc.regex)?,
+        strategy: parse_strat_mode(
+            ctx,
+            acc.strategy,
+            acc.value,
+            acc.regex,
+            acc.finite,
+        )?,
         no_bound: acc.no_bound.is_some(),
+        // Process migrates_to and via together as they go in pairs.
+        migration: parse_migration(ctx, acc.migrates_to, acc.via)?,
     })
 }
 
@@ -182,8 +209,11 @@ struct ParseAcc {
     strategy: Option<Expr>,
     value: Option<Expr>,
     regex: Option<Expr>,
+    finite: Option<()>,
     filter: Vec<Expr>,
     no_bound: Option<()>,
+    migrates_to: Option<Type>,
+    via: Option<Path>,
 }
 
 //==============================================================================
@@ -267,8 +297,11 @@ fn dispatch_attribute(ctx: Ctx, mut acc: ParseAcc, meta: Meta) -> ParseAcc {
             "strategy" => parse_strategy(ctx, &mut acc, &meta),
             "value" => parse_value(ctx, &mut acc, &meta),
             "regex" => parse_regex(ctx, &mut acc, &meta),
+            "finite" => parse_finite(ctx, &mut acc, meta),
             "filter" => parse_filter(ctx, &mut acc, &meta),
             "no_bound" => parse_no_bound(ctx, &mut acc, meta),
+            "migrates_to" => parse_migrates_to(ctx, &mut acc, meta),
+            "via" => parse_via(ctx, &mut acc, meta),
             // Invalid modifiers:
             name => dispatch_unknown_mod(ctx, name),
         }
@@ -292,6 +325,9 @@ fn dispatch_unknown_mod(ctx: Ctx, name: &str) {
         "no_param" | "no_parameters" => {
             error::did_you_mean(ctx, name, "no_params")
         }
+        "migrate_to" | "migrates" | "migration" => {
+            error::did_you_mean(ctx, name, "migrates_to")
+        }
         name => error::unkown_modifier(ctx, name),
         // TODO: consider levenshtein distance.
     }
@@ -308,6 +344,73 @@ fn parse_no_bound(ctx: Ctx, acc: &mut ParseAcc, meta: Meta) {
     parse_bare_modifier(ctx, &mut acc.no_bound, meta, error::no_bound_malformed)
 }
 
+//==============================================================================
+// Internals: Migration
+//==============================================================================
+
+/// Combines a potentially set `migrates_to` and `via` into a single value
+/// and fails if only one of them has been set.
+fn parse_migration(
+    ctx: Ctx,
+    migrates_to: Option<Type>,
+    via: Option<Path>,
+) -> DeriveResult<Option<Migration>> {
+    Ok(match (migrates_to, via) {
+        (None, None) => None,
+        (Some(to), Some(via)) => Some(Migration { to, via }),
+        (Some(_), None) | (None, Some(_)) => error::migration_incomplete(ctx)?,
+    })
+}
+
+/// Parses the newer version of a type.
+/// Valid forms are:
+/// + `#[proptest(migrates_to(<type>)]`
+/// + `#[proptest(migrates_to("<type>")]`
+/// + `#[proptest(migrates_to = "<type>"]`
+fn parse_migrates_to(ctx: Ctx, acc: &mut ParseAcc, meta: Meta) {
+    error_if_set(ctx, &acc.migrates_to, &meta);
+
+    if let typ @ Some(_) = match normalize_meta(meta) {
+        Some(NormMeta::Word(ident)) => Some(ident_to_type(ident)),
+        Some(NormMeta::Lit(Lit::Str(lit))) => lit.parse().ok(),
+        _ => None,
+    } {
+        acc.migrates_to = typ;
+    } else {
+        error::migrates_to_malformed(ctx)
+    }
+}
+
+/// Parses the function upgrading a value to the newer version of its type.
+/// Valid forms are:
+/// + `#[proptest(via(<ident>)]`
+/// + `#[proptest(via("<path>")]`
+/// + `#[proptest(via = "<path>"]`
+fn parse_via(ctx: Ctx, acc: &mut ParseAcc, meta: Meta) {
+    error_if_set(ctx, &acc.via, &meta);
+
+    if let path @ Some(_) = match normalize_meta(meta) {
+        Some(NormMeta::Word(ident)) => Some(ident.into()),
+        Some(NormMeta::Lit(Lit::Str(lit))) => lit.parse().ok(),
+        _ => None,
+    } {
+        acc.via = path;
+    } else {
+        error::via_malformed(ctx)
+    }
+}
+
+//==============================================================================
+// Internals: Finite
+//==============================================================================
+
+/// Parse a finite attribute.
+/// Valid forms are:
+/// + `#[proptest(finite)]`
+fn parse_finite(ctx: Ctx, acc: &mut ParseAcc, meta: Meta) {
+    parse_bare_modifier(ctx, &mut acc.finite, meta, error::finite_malformed)
+}
+
 //==============================================================================
 // Internals: Skip
 //==============================================================================
@@ -440,20 +543,26 @@ fn parse_strategy_base(ctx: Ctx, loc: &mut Option<Expr>, meta: &Meta) {
     }
 }
 
-/// Combines any parsed explicit strategy, value, and regex into a single
-/// value and fails if both an explicit strategy / value / regex was set.
+/// Combines any parsed explicit strategy, value, regex, and finite into a
+/// single value and fails if more than one of them was set.
 /// Only one of them can be set, or none.
+///
+/// `finite` is sugar for the strategy `proptest::num::finite()`.
 fn parse_strat_mode(
     ctx: Ctx,
     strat: Option<Expr>,
     value: Option<Expr>,
     regex: Option<Expr>,
+    finite: Option<()>,
 ) -> DeriveResult<StratMode> {
-    Ok(match (strat, value, regex) {
-        (None, None, None) => StratMode::Arbitrary,
-        (None, None, Some(re)) => StratMode::Regex(re),
-        (None, Some(vl), None) => StratMode::Value(vl),
-        (Some(st), None, None) => StratMode::Strategy(st),
+    Ok(match (strat, value, regex, finite) {
+        (None, None, None, None) => StratMode::Arbitrary,
+        (None, None, Some(re), None) => StratMode::Regex(re),
+        (None, Some(vl), None, None) => StratMode::Value(vl),
+        (Some(st), None, None, None) => StratMode::Strategy(st),
+        (None, None, None, Some(())) => {
+            StratMode::Strategy(parse_quote!(_proptest::num::finite()))
+        }
         _ => error::overspecified_strat(ctx)?,
     })
 }
@@ -16,7 +16,9 @@ use crate::ast::*;
 use crate::attr::{self, ParamsMode, ParsedAttributes, StratMode};
 use crate::error::{self, Context, Ctx, DeriveResult};
 use crate::use_tracking::{UseMarkable, UseTracker};
-use crate::util::{fields_to_vec, is_unit_type, self_ty};
+use crate::util::{
+    extract_simple_path, fields_to_vec, is_bitflags, is_unit_type, self_ty,
+};
 use crate::void::IsUninhabited;
 
 //==============================================================================
@@ -24,8 +26,18 @@ use crate::void::IsUninhabited;
 //==============================================================================
 
 pub fn impl_proptest_arbitrary(ast: DeriveInput) -> TokenStream {
+    impl_proptest(ast, Output::Arbitrary)
+}
+
+/// Entry point for `#[derive(Transition)]`, which reuses the logic of
+/// `#[derive(Arbitrary)]` but emits an inherent `transitions()` instead.
+pub fn impl_proptest_transition(ast: DeriveInput) -> TokenStream {
+    impl_proptest(ast, Output::Transitions)
+}
+
+fn impl_proptest(ast: DeriveInput, output: Output) -> TokenStream {
     let mut ctx = Context::default();
-    let result = derive_proptest_arbitrary(&mut ctx, ast);
+    let result = derive_proptest_arbitrary(&mut ctx, ast, output);
     match (result, ctx.check()) {
         (Ok(derive), Ok(())) => derive,
         (_, Err(err)) => err,
@@ -50,6 +62,7 @@ struct DeriveData<B> {
 fn derive_proptest_arbitrary(
     ctx: Ctx,
     ast: DeriveInput,
+    output: Output,
 ) -> DeriveResult<TokenStream> {
     use syn::Data::*;
 
@@ -59,6 +72,9 @@ fn derive_proptest_arbitrary(
     // Parse top level attributes:
     let attrs = attr::parse_top_attributes(ctx, &ast.attrs)?;
 
+    // The migration is linearised with the impl, whatever the body is:
+    let migration = attrs.migration.clone();
+
     // Initialize tracker:
     let mut tracker = UseTracker::new(ast.generics);
     if attrs.no_bound {
@@ -92,7 +108,9 @@ fn derive_proptest_arbitrary(
     }?;
 
     // Linearise the IR into Rust code:
-    let q = the_impl.into_tokens(ctx)?;
+    let q = the_impl
+        .with_migration(migration)
+        .into_tokens(ctx, output)?;
 
     // We're done!
     Ok(q)
@@ -114,7 +132,14 @@ fn derive_struct(
     error::if_strategy_present(ctx, &ast.attrs, error::STRUCT);
 
     let v_path = ast.ident.clone().into();
-    let parts = if ast.body.is_empty() {
+    let parts = if is_bitflags(&ast.body) {
+        // Deriving for a type defined with `bitflags!`, whose field is private
+        // to the macro, so only combine the flags it defines.
+        let (strat, ctor) = pair_existential_self(parse_quote!(
+            _proptest::bits::flags::<Self>()
+        ));
+        add_top_filter(ast.attrs.filter, (Params::empty(), strat, ctor))
+    } else if ast.body.is_empty() {
         // Deriving for a unit struct.
         error::if_present_on_unit_struct(ctx, &ast.attrs);
         let (strat, ctor) = pair_unit_self(&v_path);
@@ -132,7 +157,7 @@ fn derive_struct(
         }
 
         // Construct the closure for `.prop_map`:
-        let closure = map_closure(v_path, &ast.body);
+        let mut closure = map_closure(v_path, &ast.body);
 
         // The complexity of the logic depends mostly now on whether
         // parameters were set directly on the type or not.
@@ -153,6 +178,7 @@ fn derive_struct(
             derive_product_no_params(
                 ctx,
                 &mut ast.tracker,
+                &mut closure,
                 ast.body,
                 error::STRUCT_FIELD,
             )?
@@ -201,16 +227,18 @@ fn derive_product_has_params(
     ctx: Ctx,
     ut: &mut UseTracker,
     item: &str,
-    closure: MapClosure,
+    mut closure: MapClosure,
     fields: Vec<Field>,
 ) -> DeriveResult<StratPair> {
     // Fold into an accumulator of the strategy types and the expressions
     // that produces the strategy. Finally turn the accumulator into
     // a `.prop_map(..)` that produces the composite strategy.
     let len = fields.len();
+    let names = field_names(&fields);
     fields
         .into_iter()
-        .try_fold(StratAcc::new(len), |acc, field| {
+        .enumerate()
+        .try_fold(StratAcc::new(len), |acc, (idx, field)| {
             let attrs = attr::parse_attributes(ctx, &field.attrs)?;
 
             // Deny attributes that are only for enum variants:
@@ -219,6 +247,13 @@ fn derive_product_has_params(
             // Deny setting parameters on the field since it has been set on parent:
             error::if_specified_params(ctx, &attrs, item);
 
+            // A value computed from earlier fields is done in the closure:
+            if let Some(pair) =
+                derive_from_siblings(&mut closure, &names, idx, &attrs)
+            {
+                return Ok(acc.add(pair));
+            }
+
             // Determine the strategy for this field and add it to acc.
             let span = field.span();
             let ty = field.ty.clone();
@@ -258,6 +293,7 @@ fn product_handle_default_params(
 fn derive_product_no_params(
     ctx: Ctx,
     ut: &mut UseTracker,
+    closure: &mut MapClosure,
     fields: Vec<Field>,
     item: &str,
 ) -> DeriveResult<PartsAcc<Ctor>> {
@@ -265,12 +301,19 @@ fn derive_product_no_params(
     // that produces the strategy. We then just return that accumulator
     // and let the caller of this function determine what to do with it.
     let acc = PartsAcc::new(fields.len());
-    fields.into_iter().try_fold(acc, |mut acc, field| {
+    let names = field_names(&fields);
+    let mut fields = fields.into_iter().enumerate();
+    fields.try_fold(acc, |mut acc, (idx, field)| {
         let attrs = attr::parse_attributes(ctx, &field.attrs)?;
 
         // Deny attributes that are only for enum variants:
         error::if_enum_attrs_present(ctx, &attrs, item);
 
+        // A value computed from earlier fields is done in the closure:
+        if let Some(pair) = derive_from_siblings(closure, &names, idx, &attrs) {
+            return Ok(acc.add_strat(pair));
+        }
+
         let span = field.span();
         let ty = field.ty;
 
@@ -337,6 +380,156 @@ fn derive_product_no_params(
     })
 }
 
+/// The names of the given fields, `None` for tuple fields.
+fn field_names(fields: &[Field]) -> Vec<Option<Ident>> {
+    fields.iter().map(|field| field.ident.clone()).collect()
+}
+
+/// If the field at `idx` has a fixed `value` which refers to earlier fields
+/// by name, registers it to be computed by the `closure` from those fields
+/// and returns a placeholder strategy producing `()` in its stead.
+///
+/// Filters and `params` on such a field are left to the usual logic.
+fn derive_from_siblings(
+    closure: &mut MapClosure,
+    names: &[Option<Ident>],
+    idx: usize,
+    attrs: &ParsedAttributes,
+) -> Option<StratPair> {
+    let value = match &attrs.strategy {
+        StratMode::Value(value) => value,
+        _ => return None,
+    };
+    if !attrs.filter.is_empty()
+        || matches!(attrs.params, ParamsMode::Specified(_))
+    {
+        return None;
+    }
+
+    let siblings = sibling_refs(value, &names[..idx]);
+    if siblings.is_empty() {
+        return None;
+    }
+
+    closure.derive_field(idx, siblings, value.clone());
+    Some(pair_value(parse_quote!(()), parse_quote!(())))
+}
+
+/// Finds the fields among `names` which `expr` refers to by name,
+/// together with their positions.
+///
+/// The callee of a call is never treated as a field since `value(foo)`
+/// means `foo()`. Macro invocations are scanned for identifiers as well
+/// so that e.g. `format!("{}", name)` works.
+fn sibling_refs(expr: &Expr, names: &[Option<Ident>]) -> Vec<(usize, Ident)> {
+    use proc_macro2::{Delimiter, TokenTree};
+    use syn::visit::{self, Visit};
+
+    struct Refs<'a> {
+        names: &'a [Option<Ident>],
+        found: Vec<(usize, Ident)>,
+    }
+
+    impl Refs<'_> {
+        fn note(&mut self, ident: &Ident) {
+            let pos = self.names.iter().position(|n| n.as_ref() == Some(ident));
+            if let Some(idx) = pos {
+                if self.found.iter().all(|(found, _)| *found != idx) {
+                    self.found.push((idx, ident.clone()));
+                }
+            }
+        }
+
+        fn note_tokens(&mut self, tokens: TokenStream) {
+            let mut after_dot = false;
+            let mut tokens = tokens.into_iter().peekable();
+            while let Some(tt) = tokens.next() {
+                match &tt {
+                    TokenTree::Ident(ident) => {
+                        let is_call = matches!(
+                            tokens.peek(),
+                            Some(TokenTree::Group(g))
+                                if g.delimiter() == Delimiter::Parenthesis
+                        );
+                        if !after_dot && !is_call {
+                            self.note(ident);
+                        }
+                    }
+                    TokenTree::Group(group) => self.note_tokens(group.stream()),
+                    _ => {}
+                }
+                after_dot = matches!(
+                    &tt,
+                    TokenTree::Punct(p) if p.as_char() == '.'
+                );
+            }
+        }
+    }
+
+    impl<'ast> Visit<'ast> for Refs<'_> {
+        fn visit_expr_path(&mut self, expr: &'ast syn::ExprPath) {
+            if expr.qself.is_none() {
+                if let Some(ident) = extract_simple_path(&expr.path) {
+                    self.note(ident);
+                }
+            }
+            visit::visit_expr_path(self, expr);
+        }
+
+        fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+            if !matches!(&*call.func, Expr::Path(_)) {
+                self.visit_expr(&call.func);
+            }
+            call.args.iter().for_each(|arg| self.visit_expr(arg));
+        }
+
+        fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+            self.note_tokens(mac.tokens.clone());
+        }
+    }
+
+    let mut refs = Refs {
+        names,
+        found: Vec::new(),
+    };
+    refs.visit_expr(expr);
+    refs.found
+}
+
+/// Desugars struct update syntax in a fixed `value` for the variant
+/// `v_path`, which enum variants do not support natively, e.g.:
+///
+/// ```ignore
+/// Self::Variant { a: 1, ..Default::default() }
+/// ```
+///
+/// The rest expression is used as is for each field not listed, so it must
+/// be valid for each of their types, as `Default::default()` is.
+fn desugar_struct_update(v_path: &Path, fields: &[Field], value: Expr) -> Expr {
+    let mut expr = match value {
+        Expr::Struct(expr) if expr.rest.is_some() => expr,
+        value => return value,
+    };
+    let last_ident =
+        |path: &Path| path.segments.last().map(|s| s.ident.clone());
+    if last_ident(&expr.path) != last_ident(v_path) {
+        return Expr::Struct(expr);
+    }
+
+    let rest = expr.rest.take();
+    expr.dot2_token = None;
+    for name in fields.iter().filter_map(|field| field.ident.as_ref()) {
+        let listed = expr.fields.iter().any(|fv| match &fv.member {
+            syn::Member::Named(member) => member == name,
+            syn::Member::Unnamed(_) => false,
+        });
+        if !listed {
+            expr.fields.push(parse_quote!( #name: #rest ));
+        }
+    }
+    Expr::Struct(expr)
+}
+
 /// Wrap the given constructor with a let binding
 /// moving `param_<x>` into `params`.
 fn extract_nparam<C>(
@@ -410,6 +603,7 @@ fn derive_enum_no_params(
             keep_inhabited_variant(ctx, _self, variant)?
         {
             let path = parse_quote!( #_self::#ident );
+            let first_param = acc.params.len();
             let (strat, ctor) = if fields.is_empty() {
                 // Unit variant:
                 pair_unit_variant(ctx, &attrs, path)
@@ -419,13 +613,16 @@ fn derive_enum_no_params(
                     ctx, ut, path, attrs, fields, &mut acc,
                 )?
             };
-            acc = acc.add_strat((strat, (weight, ctor)));
+            // Each variant only uses the parameters it added, so its
+            // strategy can be constructed lazily from just those.
+            let params = (first_param..acc.params.len()).collect();
+            acc = acc.add_strat((strat, (weight, ctor, params)));
         }
     }
 
     ensure_union_has_strategies(ctx, &acc.strats);
 
-    // Package the strategies into a union.
+    // Package the strategies into a lazy union.
     Ok(acc.finish(ctx))
 }
 
@@ -460,6 +657,7 @@ fn derive_variant_with_fields<C>(
             }
             // Specific value - use the given expr:
             StratMode::Value(value) => {
+                let value = desugar_struct_update(&v_path, &fields, value);
                 deny_all_attrs_on_fields(ctx, fields)?;
                 pair_value_self(value)
             }
@@ -488,6 +686,7 @@ fn derive_variant_with_fields<C>(
                 }
                 // Specific value - use the given expr in a closure and erase:
                 StratMode::Value(value) => {
+                    let value = desugar_struct_update(&v_path, &fields, value);
                     deny_all_attrs_on_fields(ctx, fields)?;
                     pair_value_exist_self(value)
                 }
@@ -525,9 +724,14 @@ fn variant_no_explicit_strategy<C>(
     acc: &mut PartsAcc<C>,
 ) -> DeriveResult<StratPair> {
     // Compute parts for the inner product:
-    let closure = map_closure(v_path, &fields);
-    let fields_acc =
-        derive_product_no_params(ctx, ut, fields, error::ENUM_VARIANT_FIELD)?;
+    let mut closure = map_closure(v_path, &fields);
+    let fields_acc = derive_product_no_params(
+        ctx,
+        ut,
+        &mut closure,
+        fields,
+        error::ENUM_VARIANT_FIELD,
+    )?;
     let (params, count) = fields_acc.params.consume();
     let (strat, ctor) = fields_acc.strats.finish(closure);
 
@@ -561,6 +765,7 @@ fn variant_handle_default_params(
         }
         // Specific value - use the given expr:
         StratMode::Value(value) => {
+            let value = desugar_struct_update(&v_path, &fields, value);
             deny_all_attrs_on_fields(ctx, fields)?;
             pair_value_self(value)
         }
@@ -744,13 +949,15 @@ impl PartsAcc<Ctor> {
     }
 }
 
-impl PartsAcc<(u32, Ctor)> {
+impl PartsAcc<LazySummand> {
     /// Finishes off the accumulator by returning the parts needed for
     /// deriving. The resultant strategy is one that randomly picks
-    /// one of the parts based on the relative weights in the `u32`.
+    /// one of the parts based on the relative weights in the `u32`,
+    /// only constructing the parts that are picked. The `Box<[usize]>`
+    /// lists the parameter registers used by each part.
     fn finish(self, ctx: Ctx) -> ImplParts {
         let (params, count) = self.params.consume();
-        let (strat, ctor) = self.strats.finish(ctx);
+        let (strat, ctor) = self.strats.finish(ctx, &params);
         (params, strat, extract_all(ctor, count, FromReg::Top))
     }
 }
@@ -773,6 +980,11 @@ impl ParamAcc {
         }
     }
 
+    /// Returns the number of types accumulated so far.
+    fn len(&self) -> usize {
+        self.types.len()
+    }
+
     /// Adds a type to the accumulator and returns the type count before adding.
     fn add(&mut self, ty: Type) -> usize {
         let var = self.types.len();
@@ -843,17 +1055,27 @@ impl StratAcc<(u32, Ctor)> {
     /// strategies where the resultant strategy randomly picks
     /// one of the summands based on the relative weights provided.
     fn finish(self, ctx: Ctx) -> StratPair {
-        // Check that the weight sum <= u32::MAX
-        if self
-            .ctors
-            .iter()
-            .map(|&(w, _)| w)
-            .try_fold(0u32, |acc, w| acc.checked_add(w))
-            .is_none()
-        {
-            error::weight_overflowing(ctx)
-        }
-
+        ensure_weight_sum_fits(ctx, self.ctors.iter().map(|&(w, _)| w));
         pair_oneof(self.consume())
     }
 }
+
+impl StratAcc<LazySummand> {
+    /// Finishes off the accumulator by returning a lazy union of the
+    /// strategies, where the types of the parameter registers of each
+    /// strategy are looked up in `params`.
+    fn finish(self, ctx: Ctx, params: &Params) -> StratPair {
+        ensure_weight_sum_fits(ctx, self.ctors.iter().map(|&(w, _, _)| w));
+        pair_lazy_oneof(self.consume(), params)
+    }
+}
+
+/// Checks that the sum of the weights of a union is <= u32::MAX.
+fn ensure_weight_sum_fits(ctx: Ctx, mut weights: impl Iterator<Item = u32>) {
+    if weights
+        .try_fold(0u32, |acc, w| acc.checked_add(w))
+        .is_none()
+    {
+        error::weight_overflowing(ctx)
+    }
+}
@@ -506,15 +506,16 @@ error!(
 );
 
 // Happens when more than one of `#[proptest(strategy..)]`,
-// `#[proptest(value..)]`, or `#[proptest(regex..)]` were specified.
+// `#[proptest(value..)]`, `#[proptest(regex..)]`, or `#[proptest(finite)]`
+// were specified.
 // They are mutually exclusive choices.
 // The user can resolve this by picking one.
 fatal!(
     overspecified_strat,
     E0025,
     "Cannot set more than one of `#[proptest(value = \"<expr>\")]`,
-    `#[proptest(strategy = \"<expr>\")]`, `#[proptest(regex = \"<string>\")]` \
-    simultaneously. Please pick one of these attributes."
+    `#[proptest(strategy = \"<expr>\")]`, `#[proptest(regex = \"<string>\")]`, \
+    `#[proptest(finite)]` simultaneously. Please pick one of these attributes."
 );
 
 // Happens when `#[proptest(strategy..)]` or `#[proptest(value..)]` is
@@ -677,3 +678,52 @@ error!(
      since `params` cannot be used in `<string>`.",
     item
 );
+
+// Happens when `#[proptest(finite)]` is malformed.
+error!(
+    finite_malformed,
+    E0036,
+    "The attribute modifier `finite` inside `#[proptest(..)]` does not \
+     support any further configuration and must be a plain modifier as in \
+     `#[proptest(finite)]`."
+);
+
+// Happens when `#[proptest(migrates_to..)]` is malformed.
+// For example, `#[proptest(migrates_to = 1)]` is not a valid form.
+error!(
+    migrates_to_malformed,
+    E0037,
+    "The attribute modifier `migrates_to` inside `#[proptest(..)]` must have \
+     the format `#[proptest(migrates_to = \"<type>\")]` where `<type>` is a \
+     valid type in Rust. An example: `#[proptest(migrates_to = \"ConfigV2\")]`."
+);
+
+// Happens when `#[proptest(via..)]` is malformed.
+// For example, `#[proptest(via = 1)]` is not a valid form.
+error!(
+    via_malformed,
+    E0038,
+    "The attribute modifier `via` inside `#[proptest(..)]` must have the \
+     format `#[proptest(via = \"<path>\")]` where `<path>` is the path to a \
+     function. An example: `#[proptest(via = \"upgrade::config\")]`."
+);
+
+// Happens when only one of `#[proptest(migrates_to = "<type>")]` and
+// `#[proptest(via = "<path>")]` is set on an item.
+fatal!(
+    migration_incomplete,
+    E0039,
+    "`#[proptest(migrates_to = \"<type>\")]` and \
+     `#[proptest(via = \"<path>\")]` must be used together, since the latter \
+     says how to upgrade a value to the type given by the former."
+);
+
+// Happens when `#[proptest(migrates_to = "<type>", via = "<path>")]` is set
+// on something else than the type definition deriving `Arbitrary`.
+error!(
+    migration_set_on_non_type,
+    E0040,
+    "Setting `#[proptest(migrates_to = \"<type>\", via = \"<path>\")]` on \
+     something else than the type definition `Arbitrary` is derived for is \
+     not allowed, since only whole types migrate to newer versions."
+);
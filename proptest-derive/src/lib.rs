@@ -56,5 +56,18 @@ pub fn derive_proptest_arbitrary(input: pm::TokenStream) -> pm::TokenStream {
     derive::impl_proptest_arbitrary(syn::parse(input).unwrap()).into()
 }
 
+/// Generates an inherent `fn transitions() -> BoxedStrategy<Self>` for a
+/// state machine transition enum, for use in `ReferenceStateMachine` impls.
+///
+/// The strategy is the one `#[derive(Arbitrary)]` would produce with default
+/// parameters, so the same `#[proptest(..)]` attributes, such as `weight` and
+/// `strategy`, are supported. See the [`proptest-derive` section of the
+/// Proptest Book](https://proptest-rs.github.io/proptest/proptest-derive/index.html)
+/// for details.
+#[proc_macro_derive(Transition, attributes(proptest))]
+pub fn derive_proptest_transition(input: pm::TokenStream) -> pm::TokenStream {
+    derive::impl_proptest_transition(syn::parse(input).unwrap()).into()
+}
+
 #[cfg(test)]
 mod tests;
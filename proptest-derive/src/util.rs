@@ -122,6 +122,24 @@ pub fn is_phantom_data(path: &syn::Path) -> bool {
         )
 }
 
+/// Returns true iff the given fields are those of a type defined with the
+/// `bitflags!` macro of the `bitflags` crate (version 2), which is the single
+/// field `<Self as $crate::__private::PublicFlags>::Internal`.
+pub fn is_bitflags(fields: &[syn::Field]) -> bool {
+    let field = match match_singleton(fields) {
+        Some(field) if field.ident.is_none() => field,
+        _ => return false,
+    };
+    let ty = match &field.ty {
+        syn::Type::Path(ty) if ty.qself.is_some() => ty,
+        _ => return false,
+    };
+    let segs = ty.path.segments.iter().rev().collect::<Vec<_>>();
+    segs.len() >= 2
+        && segs[0].ident == "Internal"
+        && segs[1].ident == "PublicFlags"
+}
+
 /// Extracts a simple non-global path of length 1.
 pub fn extract_simple_path(path: &syn::Path) -> Option<&syn::Ident> {
     match_singleton(&path.segments)
@@ -0,0 +1,69 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use bitflags::bitflags;
+use proptest::prelude::*;
+use proptest_derive::Arbitrary;
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Arbitrary)]
+    struct Permissions: u8 {
+        const READ = 0b0001;
+        const WRITE = 0b0010;
+        const EXECUTE = 0b0100;
+        const _ = 0b1000_0000;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Arbitrary)]
+    #[proptest(filter = "|m| !m.is_empty()")]
+    pub struct Mode: u32 {
+        const SYNC = 1 << 8;
+        const APPEND = 1 << 20;
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+struct File {
+    owner: Permissions,
+    others: Option<Permissions>,
+    mode: Mode,
+}
+
+#[test]
+fn asserting_arbitrary() {
+    fn assert_arbitrary<T: Arbitrary>() {}
+
+    assert_arbitrary::<Permissions>();
+    assert_arbitrary::<Mode>();
+    assert_arbitrary::<File>();
+}
+
+proptest! {
+    #[test]
+    fn only_defined_flags_are_set(file: File) {
+        prop_assert!(Permissions::all().contains(file.owner));
+        prop_assert_eq!(0, file.owner.bits() & 0x80);
+        prop_assert!(!file.mode.is_empty());
+        prop_assert_eq!(file.mode.bits(), file.mode.bits() & Mode::all().bits());
+    }
+}
+
+#[test]
+fn shrinks_to_empty() {
+    use proptest::strategy::ValueTree;
+    use proptest::test_runner::TestRunner;
+
+    let mut runner = TestRunner::deterministic();
+    for _ in 0..64 {
+        let mut tree = any::<Permissions>().new_tree(&mut runner).unwrap();
+        while tree.simplify() {}
+        assert_eq!(Permissions::empty(), tree.current());
+    }
+}
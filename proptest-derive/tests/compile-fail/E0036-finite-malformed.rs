@@ -0,0 +1,31 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate proptest_derive;
+use proptest_derive::Arbitrary;
+
+fn main() {}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+struct T0 {
+    #[proptest(finite = "yes")]
+    field: f64,
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0036]
+struct T1(
+    #[proptest(finite(true))]
+    f32
+);
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0025]
+struct T2 {
+    #[proptest(finite, strategy = "0.0..1.0")]
+    field: f64,
+}
@@ -0,0 +1,48 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate proptest_derive;
+use proptest_derive::Arbitrary;
+
+fn main() {}
+
+#[derive(Debug)]
+struct New;
+
+fn upgrade<T>(_: &T) -> New {
+    New
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0037]
+#[proptest(migrates_to = 1, via = "upgrade")]
+struct T0;
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0038]
+#[proptest(migrates_to = "New", via = 1)]
+struct T1;
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0039]
+#[proptest(migrates_to = "New")]
+struct T2;
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0039]
+#[proptest(via = "upgrade")]
+struct T3;
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0040]
+struct T4 {
+    #[proptest(migrates_to = "New", via = "upgrade")]
+    field: u8,
+}
+
+#[derive(Debug, Arbitrary)] //~ ERROR: [proptest_derive, E0040]
+enum T5 {
+    #[proptest(migrates_to = "New", via = "upgrade")]
+    V,
+}
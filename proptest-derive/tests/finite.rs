@@ -0,0 +1,56 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use proptest::prelude::*;
+use proptest_derive::Arbitrary;
+
+#[derive(Debug, Arbitrary)]
+struct Sample {
+    #[proptest(finite)]
+    weight: f64,
+    #[proptest(finite)]
+    offset: f32,
+    raw: f64,
+}
+
+#[derive(Debug, Arbitrary)]
+enum Shape {
+    Circle(#[proptest(finite)] f64),
+    Rect {
+        #[proptest(finite)]
+        width: f32,
+        #[proptest(finite)]
+        height: f32,
+    },
+}
+
+#[test]
+fn asserting_arbitrary() {
+    fn assert_arbitrary<T: Arbitrary>() {}
+
+    assert_arbitrary::<Sample>();
+    assert_arbitrary::<Shape>();
+}
+
+proptest! {
+    #[test]
+    fn finite_fields_are_finite(sample: Sample) {
+        prop_assert!(sample.weight.is_finite());
+        prop_assert!(sample.offset.is_finite());
+    }
+
+    #[test]
+    fn finite_variant_fields_are_finite(shape: Shape) {
+        match shape {
+            Shape::Circle(radius) => prop_assert!(radius.is_finite()),
+            Shape::Rect { width, height } => {
+                prop_assert!(width.is_finite() && height.is_finite());
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+use proptest::prelude::*;
+use proptest::strategy::Map;
+use proptest_derive::Arbitrary;
+
+static CONSTRUCTED: AtomicUsize = AtomicUsize::new(0);
+
+/// A type which counts how often its strategy is constructed.
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+struct Counted(u8);
+
+impl Arbitrary for Counted {
+    type Parameters = ();
+    type Strategy = Map<std::ops::Range<u8>, fn(u8) -> Self>;
+
+    fn arbitrary_with((): ()) -> Self::Strategy {
+        CONSTRUCTED.fetch_add(1, SeqCst);
+        (0..10).prop_map(Counted)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Arbitrary)]
+enum Big {
+    V1(Counted),
+    V2(Counted),
+    V3(Counted),
+    V4(Counted),
+    V5(Counted),
+    V6(Counted),
+    V7(Counted),
+    V8(Counted),
+    V9(Counted),
+    V10(Counted),
+    V11(Counted),
+    V12,
+    V13(Vec<Counted>),
+    V14 { a: u8, b: Option<Counted> },
+}
+
+// Unlike a boxed union, a lazy one does not need `T: 'static`.
+#[cfg(not(feature = "boxed_union"))]
+#[allow(dead_code)]
+#[derive(Debug, Arbitrary)]
+enum Generic<T> {
+    V1(T),
+    V2(T),
+    V3(T),
+    V4(T),
+    V5(T),
+    V6(T),
+    V7(T),
+    V8(T),
+    V9(T),
+    V10(T),
+    V11(Vec<T>),
+}
+
+#[test]
+fn asserting_arbitrary() {
+    fn assert_arbitrary<T: Arbitrary>() {}
+
+    assert_arbitrary::<Big>();
+    #[cfg(not(feature = "boxed_union"))]
+    assert_arbitrary::<Generic<Big>>();
+}
+
+// Boxed unions are neither lazy nor `Send`.
+#[cfg(not(feature = "boxed_union"))]
+#[test]
+fn lazy_union_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<<Big as Arbitrary>::Strategy>();
+}
+
+#[cfg(not(feature = "boxed_union"))]
+#[test]
+fn variants_are_constructed_once_picked() {
+    use proptest::strategy::ValueTree;
+    use proptest::test_runner::TestRunner;
+
+    let strategy = any::<Big>();
+    assert_eq!(0, CONSTRUCTED.load(SeqCst));
+
+    let mut runner = TestRunner::deterministic();
+    strategy.new_tree(&mut runner).unwrap();
+    assert!(CONSTRUCTED.load(SeqCst) <= 1);
+
+    for _ in 0..1000 {
+        let mut tree = strategy.new_tree(&mut runner).unwrap();
+        while tree.simplify() {}
+        assert!(matches!(tree.current(), Big::V1(Counted(0))));
+    }
+    // Every variant with a `Counted` has been picked by now, but none was
+    // constructed more than once.
+    assert_eq!(13, CONSTRUCTED.load(SeqCst));
+}
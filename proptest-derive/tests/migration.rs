@@ -0,0 +1,101 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use proptest::prelude::*;
+use proptest::strategy::{any_migration, MigratesTo};
+use proptest_derive::Arbitrary;
+
+#[derive(Debug, PartialEq)]
+struct UserV2 {
+    name: String,
+    age: u16,
+    admin: bool,
+}
+
+#[derive(Debug, Arbitrary)]
+#[proptest(migrates_to = "UserV2", via = "upgrade_user")]
+struct UserV1 {
+    #[proptest(regex = "[a-z]{1,8}")]
+    name: String,
+    age: u8,
+}
+
+fn upgrade_user(old: &UserV1) -> UserV2 {
+    UserV2 {
+        name: old.name.clone(),
+        age: u16::from(old.age),
+        admin: false,
+    }
+}
+
+mod upgrade {
+    pub fn shape(old: &super::ShapeV1) -> super::ShapeV2 {
+        match *old {
+            super::ShapeV1::Square(side) => super::ShapeV2::Rect(side, side),
+            super::ShapeV1::Rect(w, h) => super::ShapeV2::Rect(w, h),
+        }
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+#[proptest(migrates_to(ShapeV2), via = "upgrade::shape")]
+enum ShapeV1 {
+    Square(u8),
+    Rect(u8, u8),
+}
+
+#[derive(Debug, PartialEq)]
+enum ShapeV2 {
+    Rect(u8, u8),
+}
+
+#[derive(Debug, Arbitrary)]
+#[proptest(migrates_to = "Vec<T>", via = "Wrapper::to_vec")]
+struct Wrapper<T: Clone> {
+    inner: Vec<T>,
+}
+
+impl<T: Clone> Wrapper<T> {
+    fn to_vec(&self) -> Vec<T> {
+        self.inner.clone()
+    }
+}
+
+#[test]
+fn migrates_via_the_given_function() {
+    let old = UserV1 {
+        name: "ann".to_owned(),
+        age: 42,
+    };
+    assert_eq!(upgrade_user(&old), old.migrate());
+}
+
+proptest! {
+    #[test]
+    fn migration_pairs_are_consistent(
+        (old, new) in any_migration::<UserV1, UserV2>()
+    ) {
+        prop_assert_eq!(&old.name, &new.name);
+        prop_assert_eq!(u16::from(old.age), new.age);
+        prop_assert!(!new.admin);
+    }
+
+    #[test]
+    fn enums_migrate(
+        (old, new) in any_migration::<ShapeV1, ShapeV2>()
+    ) {
+        prop_assert_eq!(upgrade::shape(&old), new);
+    }
+
+    #[test]
+    fn generic_types_migrate(
+        (old, new) in any_migration::<Wrapper<u8>, Vec<u8>>()
+    ) {
+        prop_assert_eq!(old.inner, new);
+    }
+}
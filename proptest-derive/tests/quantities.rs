@@ -0,0 +1,39 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use proptest::prelude::*;
+use proptest::units::{self, Cents, Meters, Millimeters, Seconds};
+use proptest_derive::Arbitrary;
+
+#[derive(Debug, Arbitrary)]
+struct Board {
+    #[proptest(strategy = "units::millimeters(1.0..5e3)")]
+    length: Millimeters<f64>,
+    #[proptest(strategy = "units::meters(0.0..1.0)")]
+    offcut: Meters<f64>,
+    price: Cents<u32>,
+    #[proptest(strategy = "units::seconds(0u64..60)")]
+    cutting_time: Seconds<u64>,
+}
+
+#[test]
+fn asserting_arbitrary() {
+    fn assert_arbitrary<T: Arbitrary>() {}
+
+    assert_arbitrary::<Board>();
+}
+
+proptest! {
+    #[test]
+    fn fields_use_their_strategies(board: Board) {
+        prop_assert!(board.length >= Millimeters(1.0));
+        prop_assert!(board.length < Millimeters(5e3));
+        prop_assert!(Millimeters::from(board.offcut) < Millimeters(1e3));
+        prop_assert!(board.cutting_time < Seconds(60));
+    }
+}
@@ -6,7 +6,9 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use proptest::prelude::{proptest, Arbitrary, Strategy};
+use std::time::Duration;
+
+use proptest::prelude::{arbitrary_remote, proptest, Arbitrary, Strategy};
 use proptest_derive::Arbitrary;
 
 fn make_strategy(start: usize) -> impl Strategy<Value = usize> {
@@ -53,6 +55,19 @@ enum T2 {
     },
 }
 
+arbitrary_remote! {
+    struct DurationDef for Duration {
+        secs in 0u64..60,
+        nanos in 0u32..1_000_000_000,
+    } => Duration::new(secs, nanos)
+}
+
+#[derive(Debug, Arbitrary)]
+struct T3 {
+    #[proptest(strategy = "DurationDef::arbitrary()")]
+    timeout: Duration,
+}
+
 fn assert_consistency(start: usize, val: usize) {
     assert!(val % 2 == 0 && val < 200 && val >= (start * 2));
 }
@@ -83,6 +98,11 @@ proptest! {
             T2::V5 { field } => assert_consistency(88, field),
         }
     }
+
+    #[test]
+    fn t3_test(v: T3) {
+        assert!(v.timeout < Duration::from_secs(60));
+    }
 }
 
 #[test]
@@ -92,4 +112,5 @@ fn asserting_arbitrary() {
     assert_arbitrary::<T0>();
     assert_arbitrary::<T1>();
     assert_arbitrary::<T2>();
+    assert_arbitrary::<T3>();
 }
@@ -0,0 +1,67 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+use proptest_derive::{Arbitrary, Transition};
+
+#[derive(Clone, Debug, Transition)]
+enum Op {
+    #[proptest(weight = 3)]
+    Push(#[proptest(strategy = "0..10u8")] u8),
+    Pop,
+    #[proptest(skip)]
+    #[allow(dead_code)]
+    Clear,
+}
+
+#[derive(Clone, Debug, Transition)]
+enum Generic<T: Clone> {
+    Set(T),
+    Reset,
+}
+
+// Deriving both is fine since `Transition` only adds an inherent method.
+#[derive(Clone, Debug, Arbitrary, Transition)]
+enum Both {
+    A(#[proptest(strategy = "Just(5u32)")] u32),
+}
+
+proptest! {
+    #[test]
+    fn op_transitions(op in Op::transitions()) {
+        match op {
+            Op::Push(v) => prop_assert!(v < 10),
+            Op::Pop => {}
+            Op::Clear => prop_assert!(false, "skipped variant generated"),
+        }
+    }
+
+    #[test]
+    fn generic_transitions(op in Generic::<bool>::transitions()) {
+        prop_assert!(matches!(op, Generic::Set(_) | Generic::Reset));
+    }
+
+    #[test]
+    fn both_agree(a in Both::transitions(), b in any::<Both>()) {
+        let (Both::A(a), Both::A(b)) = (a, b);
+        prop_assert_eq!(a, b);
+    }
+}
+
+#[test]
+fn op_transitions_respect_weights() {
+    let mut runner = TestRunner::deterministic();
+    let strategy = Op::transitions();
+    let pushes = (0..1000)
+        .map(|_| strategy.new_tree(&mut runner).unwrap().current())
+        .filter(|op| matches!(op, Op::Push(_)))
+        .count();
+    assert!((650..850).contains(&pushes), "{} pushes", pushes);
+}
@@ -80,6 +80,46 @@ struct CallFun {
     bar: usize,
 }
 
+#[derive(Debug, Arbitrary)]
+struct Siblings {
+    #[proptest(strategy = "1..10usize")]
+    len: usize,
+    #[proptest(value = "vec![0u8; *len]")]
+    data: Vec<u8>,
+    #[proptest(value = "format!(\"{}:{}\", len, data.len())")]
+    label: String,
+}
+
+#[derive(Debug, Arbitrary)]
+enum SiblingsVariant {
+    V0,
+    V1 {
+        #[proptest(strategy = "0..100u32")]
+        lo: u32,
+        #[proptest(value = "lo + 1")]
+        hi: u32,
+    },
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Inner(u8);
+
+#[derive(Debug, Arbitrary)]
+enum StructUpdate {
+    V0,
+    #[proptest(value = "StructUpdate::V1 { b: 5, ..Default::default() }")]
+    V1 {
+        a: usize,
+        b: usize,
+        c: Inner,
+    },
+    #[proptest(no_params)]
+    #[proptest(value = "Self::V2 { ..Default::default() }")]
+    V2 {
+        a: String,
+    },
+}
+
 proptest! {
     #[test]
     fn t0_fixed_fields(v: T0) {
@@ -135,6 +175,34 @@ proptest! {
         assert_eq!(v.foo, 42);
         assert_eq!(v.bar, 42);
     }
+
+    #[test]
+    fn siblings_refer_to_earlier_fields(v: Siblings) {
+        prop_assert!((1..10).contains(&v.len));
+        prop_assert_eq!(v.data, vec![0u8; v.len]);
+        prop_assert_eq!(v.label, format!("{}:{}", v.len, v.len));
+    }
+
+    #[test]
+    fn siblings_in_variant(v: SiblingsVariant) {
+        if let SiblingsVariant::V1 { lo, hi } = v {
+            prop_assert!(lo < 100);
+            prop_assert_eq!(hi, lo + 1);
+        }
+    }
+
+    #[test]
+    fn struct_update_fills_rest(v: StructUpdate) {
+        match v {
+            StructUpdate::V0 => {}
+            StructUpdate::V1 { a, b, c } => {
+                prop_assert_eq!(a, 0);
+                prop_assert_eq!(b, 5);
+                prop_assert_eq!(c, Inner(0));
+            }
+            StructUpdate::V2 { a } => prop_assert_eq!(a, ""),
+        }
+    }
 }
 
 #[test]
@@ -149,4 +217,7 @@ fn asserting_arbitrary() {
     assert_arbitrary::<T5>();
     assert_arbitrary::<T6>();
     assert_arbitrary::<CallFun>();
+    assert_arbitrary::<Siblings>();
+    assert_arbitrary::<SiblingsVariant>();
+    assert_arbitrary::<StructUpdate>();
 }
@@ -66,6 +66,89 @@ mod property_test;
 /// ```
 /// Multiple `#[strategy = <expr>]` attributes on an argument are not allowed.
 ///
+/// ## Grouping parameters
+///
+/// The parameters are generated as fields of one struct, which is shrunk one field at a time.
+/// With `group(...)`, you can instead collect some parameters into a nested struct of their own,
+/// which also keeps them together in the `Debug` output of failing cases:
+///
+/// ```rust,ignore
+/// #[property_test(group(width, height), group(x, y))]
+/// fn foo(width: u32, height: u32, x: u32, y: u32, name: String) {
+///     // ...
+/// }
+/// ```
+///
+/// Parameters not listed in any group (here `name`) are collected into groups after the listed
+/// ones. Since `Arbitrary` is implemented for tuples of at most 10 elements, a group can have at
+/// most 10 parameters, and functions with more than 10 parameters are split into groups of 10
+/// automatically. Only parameters bound to a plain name (not a pattern like `(a, b)`) can be
+/// listed in a group.
+///
+/// ## Exposing the strategy
+///
+/// With `expose_strategy`, the macro additionally defines a public module with the same name as
+/// the test, containing the struct the parameters are generated as (named after the test, e.g.
+/// `FooArgs`, with a field `field0`, `field1`, ... per parameter) and a function `strategy()`
+/// returning the strategy of the test, so it can be reused elsewhere, e.g. in a debugging binary:
+///
+/// ```rust,ignore
+/// #[property_test(expose_strategy)]
+/// fn foo(#[strategy = 0..10] x: u32, name: String) {
+///     // ...
+/// }
+///
+/// fn debug() {
+///     let strategy = foo::strategy();
+///     // ...
+/// }
+/// ```
+///
+/// The module imports the items of the enclosing module with `use super::*`.
+///
+/// ## Testing small values exhaustively
+///
+/// With `also_exhaustive_small`, the macro additionally defines a test named after the test with
+/// an `_exhaustive_small` suffix, which deterministically runs the body once for every
+/// combination of a few edge values of each parameter, such as `0`, `1` and `MAX` for integers or
+/// the empty string:
+///
+/// ```rust,ignore
+/// #[property_test(also_exhaustive_small)]
+/// fn foo(x: u32, name: String) {
+///     // ...
+/// }
+///
+/// // also defines `foo_exhaustive_small`, running `x` = 0, 1, u32::MAX
+/// // against `name` = "", "a", "é"
+/// ```
+///
+/// The edge values come from the `proptest::arbitrary::SmallValues` trait. Parameters of types
+/// not implementing it, and parameters with a custom strategy, only take the simplest value of
+/// their strategy. The number of cases is the product of the numbers of values of each
+/// parameter, so this is best suited to tests with few parameters. Failing cases are reported
+/// as they are, without shrinking.
+///
+/// ## Async tests
+///
+/// The test may be an `async fn`, in which case the generated test runs its body to completion
+/// for each case. By default, this uses `proptest::test_runner::block_on_case`, a minimal
+/// executor on the test thread, which suits futures that don't depend on the reactor of a
+/// specific runtime. With `async = "tokio"`, each case is instead run on a current-thread tokio
+/// runtime built once for the whole test, which requires a dependency on `tokio` with the `rt`
+/// and `time` features:
+///
+/// ```rust,ignore
+/// #[property_test(async = "tokio")]
+/// async fn foo(x: u32) {
+///     tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+///     // ...
+/// }
+/// ```
+///
+/// In both cases, if the config has a `timeout`, a case whose future is still pending when it
+/// expires is dropped, cancelling whatever it was waiting on, and fails.
+///
 #[proc_macro_attribute]
 pub fn property_test(attr: TokenStream, item: TokenStream) -> TokenStream {
     property_test::property_test(attr.into(), item.into()).into()
@@ -2,32 +2,65 @@ use super::*;
 use quote::quote_spanned;
 
 /// Generate the arbitrary impl for the struct
+#[cfg(test)]
 pub(super) fn gen_arbitrary_impl(
     fn_name: &Ident,
     args: &[Argument],
 ) -> TokenStream {
-    if args.iter().all(|arg| arg.strategy.is_none()) {
-        no_custom_strategies(fn_name, args)
+    let fields: Vec<_> = args.iter().enumerate().collect();
+    gen_fields_impl(&struct_name(fn_name), &fields)
+}
+
+/// Generate the arbitrary impl for a struct with a field per arg
+pub(super) fn gen_fields_impl(
+    struct_name: &Ident,
+    fields: &[Field],
+) -> TokenStream {
+    if fields.iter().all(|(_, arg)| arg.strategy.is_none()) {
+        no_custom_strategies(struct_name, fields)
     } else {
-        custom_strategies(fn_name, args)
+        custom_strategies(struct_name, fields)
     }
 }
 
+/// Generate the arbitrary impl for a struct with a field per group of args, each of which is a
+/// struct with its own arbitrary impl
+pub(super) fn gen_groups_impl(
+    struct_name: &Ident,
+    groups: &[(Ident, Ident)],
+) -> TokenStream {
+    let group_types: TokenStream =
+        groups.iter().map(|(_, ty)| quote!(#ty,)).collect();
+    let group_names: TokenStream =
+        groups.iter().map(|(name, _)| quote!(#name,)).collect();
+
+    let strategy_type = quote! {
+        ::proptest::strategy::Map<::proptest::arbitrary::StrategyFor<(#group_types)>, fn((#group_types)) -> Self>
+    };
+
+    let strategy_expr = quote! {
+        use ::proptest::strategy::Strategy;
+        ::proptest::prelude::any::<(#group_types)>().prop_map(|(#group_names)| Self { #group_names })
+    };
+
+    arbitrary_shared(struct_name, strategy_type, strategy_expr)
+}
+
 // we can avoid boxing strategies if there are no custom strategies, since we have types written
 // out in function args
 //
 // If there are custom strategies, we can't write the type, because we're only provided the
 // expression for the strategy (e.g. `#[strategy = my_custom_strategy()]` doesn't tell us the
 // return type of `my_custom_strategy`). In these cases, we just use `BoxedStrategy<Self>`
-fn no_custom_strategies(fn_name: &Ident, args: &[Argument]) -> TokenStream {
-    let arg_types = args.iter().map(|arg| {
+fn no_custom_strategies(struct_name: &Ident, fields: &[Field]) -> TokenStream {
+    let arg_types = fields.iter().map(|(_, arg)| {
         let ty = &arg.pat_ty.ty;
         quote!(#ty,)
     });
 
     let arg_types = quote! { #(#arg_types)* };
 
-    let arg_names = args.iter().enumerate().map(|(index, arg)| {
+    let arg_names = fields.iter().map(|&(index, arg)| {
         let name = nth_field_name(arg.pat_ty.span(), index);
         quote!(#name,)
     });
@@ -43,7 +76,7 @@ fn no_custom_strategies(fn_name: &Ident, args: &[Argument]) -> TokenStream {
         ::proptest::prelude::any::<(#arg_types)>().prop_map(|(#arg_names)| Self { #arg_names })
     };
 
-    arbitrary_shared(fn_name, strategy_type, strategy_expr)
+    arbitrary_shared(struct_name, strategy_type, strategy_expr)
 }
 
 // if we have `fn foo(#[strategy = x] a: i32, b: i32) {}`, we want to generate something like this:
@@ -57,25 +90,25 @@ fn no_custom_strategies(fn_name: &Ident, args: &[Argument]) -> TokenStream {
 //   }
 // }
 // ```
-fn custom_strategies(fn_name: &Ident, args: &[Argument]) -> TokenStream {
-    let arg_strategies: TokenStream =
-        args.iter()
-            .map(|arg| {
-                arg.strategy.as_ref().map(|s| quote! {#s,}).unwrap_or_else(
-                    || {
-                        let ty = &arg.pat_ty.ty;
-                        quote_spanned! {
-                            ty.span() => ::proptest::prelude::any::<#ty>(),
-                        }
-                    },
-                )
-            })
-            .collect();
-
-    let arg_names: TokenStream = args
+fn custom_strategies(struct_name: &Ident, fields: &[Field]) -> TokenStream {
+    let arg_strategies: TokenStream = fields
+        .iter()
+        .map(|(_, arg)| {
+            arg.strategy
+                .as_ref()
+                .map(|s| quote! {#s,})
+                .unwrap_or_else(|| {
+                    let ty = &arg.pat_ty.ty;
+                    quote_spanned! {
+                        ty.span() => ::proptest::prelude::any::<#ty>(),
+                    }
+                })
+        })
+        .collect();
+
+    let arg_names: TokenStream = fields
         .iter()
-        .enumerate()
-        .map(|(index, arg)| {
+        .map(|&(index, arg)| {
             let name = nth_field_name(arg.pat_ty.span(), index);
             quote!(#name,)
         })
@@ -90,17 +123,15 @@ fn custom_strategies(fn_name: &Ident, args: &[Argument]) -> TokenStream {
     let strategy_type = quote! {
         ::proptest::strategy::BoxedStrategy<Self>
     };
-    arbitrary_shared(fn_name, strategy_type, strategy_expr)
+    arbitrary_shared(struct_name, strategy_type, strategy_expr)
 }
 
 /// shared code between both boxed and unboxed paths
 fn arbitrary_shared(
-    fn_name: &Ident,
+    struct_name: &Ident,
     strategy_type: TokenStream,
     strategy_expr: TokenStream,
 ) -> TokenStream {
-    let struct_name = struct_name(fn_name);
-
     quote! {
         impl ::proptest::prelude::Arbitrary for #struct_name {
             type Parameters = ();
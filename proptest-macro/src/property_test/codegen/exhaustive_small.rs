@@ -0,0 +1,122 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse2, spanned::Spanned, Block, Index, ItemFn};
+
+use crate::property_test::{options::Options, utils::Argument};
+
+use super::{
+    test_attr,
+    test_body::{async_setup, make_config, run_case},
+};
+
+/// Generate the sibling test of `#[property_test(also_exhaustive_small)]`, which runs the test
+/// body once for every combination of the small values of the parameters
+///
+/// Parameters with a custom strategy only take the simplest value of that strategy, since the
+/// small values of their type may be outside of what the strategy generates. E.g. for
+/// `fn foo(x: u8, #[strategy = 1..10u8] y: u8)`, we generate something like this:
+/// ```ignore
+/// #[test]
+/// fn foo_exhaustive_small() {
+///     let values0 = || -> Vec<u8> { let of = &SmallValuesOf::<u8>::new(); of.small_values() };
+///     let values1 = || -> Vec<u8> { vec![simplest_value(&(1..10u8))] };
+///     run_small_values(
+///         &[values0().len(), values1().len()],
+///         |indices| (values0().into_iter().nth(indices[0]).unwrap(), /* ... */),
+///         |values| describe_args(&[("x", &values.0), ("y", &values.1)]),
+///         |(x, y): (u8, u8)| { /* the original body */ },
+///     );
+/// }
+/// ```
+pub(super) fn sibling_test(
+    mut item_fn: ItemFn,
+    args: &[Argument],
+    options: &Options,
+) -> ItemFn {
+    let runtime = options.async_runtime;
+    let types: TokenStream = args
+        .iter()
+        .map(|arg| {
+            let ty = &arg.pat_ty.ty;
+            quote!(#ty,)
+        })
+        .collect();
+    let patterns: TokenStream = args
+        .iter()
+        .map(|arg| {
+            let pat = &arg.pat_ty.pat;
+            quote!(#pat,)
+        })
+        .collect();
+
+    let values_fns: Vec<_> = (0..args.len())
+        .map(|index| format_ident!("values{index}"))
+        .collect();
+    let define_values = args.iter().zip(&values_fns).map(|(arg, values)| {
+        let ty = &arg.pat_ty.ty;
+        let expr = match &arg.strategy {
+            Some(strategy) => quote! {
+                ::std::vec![::proptest::sugar::simplest_value(&(#strategy))]
+            },
+            None => quote_spanned! { ty.span() =>
+                #[allow(unused_imports)]
+                use ::proptest::sugar::{ViaSimplestValue as _, ViaSmallValues as _};
+                let of = &::proptest::sugar::SmallValuesOf::<#ty>::new();
+                of.small_values()
+            },
+        };
+        quote! {
+            let #values = || -> ::std::vec::Vec<#ty> { #expr };
+        }
+    });
+
+    // without parameters, the closures below ignore their argument
+    let (indices, values) = if args.is_empty() {
+        (format_ident!("_indices"), format_ident!("_values"))
+    } else {
+        (format_ident!("indices"), format_ident!("values"))
+    };
+
+    let field_indices = (0..args.len()).map(Index::from);
+    let make_args =
+        field_indices.clone().zip(&values_fns).map(|(index, values_fn)| {
+            quote!(#values_fn().into_iter().nth(#indices[#index]).unwrap(),)
+        });
+    let describe_args = field_indices.zip(args).map(|(index, arg)| {
+        let pat = &arg.pat_ty.pat;
+        quote!((stringify!(#pat), &#values.#index as &dyn ::core::fmt::Debug),)
+    });
+
+    let run_case = run_case(&item_fn.block, &item_fn.sig.output, runtime);
+
+    // only `async fn` tests need the config, for their timeout
+    let setup = if runtime.is_some() {
+        let config = make_config(options.config.as_ref());
+        let async_setup = async_setup(runtime);
+        quote!(#config #async_setup)
+    } else {
+        TokenStream::new()
+    };
+
+    let tokens = quote! ( {
+        #setup
+
+        #(#define_values)*
+
+        ::proptest::sugar::run_small_values(
+            &[#(#values_fns().len(),)*],
+            |#indices: &[usize]| -> (#types) { (#(#make_args)*) },
+            |#values: &(#types)| ::proptest::sugar::describe_args(&[#(#describe_args)*]),
+            |(#patterns): (#types)| {
+                #run_case
+            },
+        );
+    } );
+
+    // unwrap here is fine because the double braces create a block
+    let block: Block = parse2(tokens).unwrap();
+    *item_fn.block = block;
+    item_fn.sig.ident = format_ident!("{}_exhaustive_small", item_fn.sig.ident);
+    item_fn.attrs.push(test_attr());
+    item_fn
+}
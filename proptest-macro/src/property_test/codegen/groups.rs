@@ -0,0 +1,161 @@
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::{spanned::Spanned, Ident, Pat};
+
+use crate::property_test::utils::Argument;
+
+/// The largest tuple `Arbitrary` is implemented for, which bounds both the size of a group and the
+/// number of groups
+pub(super) const MAX_GROUP_SIZE: usize = 10;
+
+/// Partition the args into groups of arg indices, each of which becomes its own struct
+///
+/// Each explicit `group(...)` becomes one group, in order. The remaining args are then chunked into
+/// groups of up to `MAX_GROUP_SIZE`, so that a function with many args compiles without any
+/// `group(...)` at all.
+///
+/// Mistakes in the explicit groups are pushed to `errors`, and the offending names are skipped
+pub(super) fn partition(
+    args: &[Argument],
+    explicit: &[Vec<Ident>],
+    errors: &mut Vec<TokenStream>,
+) -> Vec<Vec<usize>> {
+    let mut grouped = vec![false; args.len()];
+    let mut groups = Vec::with_capacity(explicit.len());
+
+    for names in explicit {
+        if names.len() > MAX_GROUP_SIZE {
+            let message =
+                format!("groups can have at most {MAX_GROUP_SIZE} parameters");
+            errors.push(error(&names[MAX_GROUP_SIZE], &message));
+        }
+
+        let mut group = Vec::with_capacity(names.len());
+        for name in names.iter().take(MAX_GROUP_SIZE) {
+            match args.iter().position(|arg| arg_ident(arg) == Some(name)) {
+                None => {
+                    let message = format!("unknown parameter `{name}`");
+                    errors.push(error(name, &message));
+                }
+                Some(index) if grouped[index] => {
+                    let message =
+                        format!("parameter `{name}` is in more than one group");
+                    errors.push(error(name, &message));
+                }
+                Some(index) => {
+                    grouped[index] = true;
+                    group.push(index);
+                }
+            }
+        }
+
+        if !group.is_empty() {
+            groups.push(group);
+        }
+    }
+
+    let rest: Vec<_> = (0..args.len()).filter(|&i| !grouped[i]).collect();
+    groups.extend(rest.chunks(MAX_GROUP_SIZE).map(<[usize]>::to_vec));
+
+    if groups.len() > MAX_GROUP_SIZE {
+        let message = format!(
+            "too many parameters: they can be split into at most {MAX_GROUP_SIZE} groups"
+        );
+        errors.push(error(&args[groups[MAX_GROUP_SIZE][0]].pat_ty, &message));
+        groups.truncate(MAX_GROUP_SIZE);
+    }
+
+    // no args still needs a (unit-like) struct
+    if groups.is_empty() {
+        groups.push(Vec::new());
+    }
+
+    groups
+}
+
+/// The name of an arg, if its pattern is a plain binding (e.g. `x` or `mut x`, but not `(x, y)`)
+fn arg_ident(arg: &Argument) -> Option<&Ident> {
+    match arg.pat_ty.pat.as_ref() {
+        Pat::Ident(pat_ident) => Some(&pat_ident.ident),
+        _ => None,
+    }
+}
+
+fn error(span: impl Spanned, message: &str) -> TokenStream {
+    quote_spanned! { span.span() => compile_error!(#message); }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, parse_str, ItemFn};
+
+    use super::*;
+    use crate::property_test::utils::strip_args;
+
+    fn groups_of(
+        fn_def: &str,
+        explicit: &[&[&str]],
+    ) -> (Vec<Vec<usize>>, usize) {
+        let f: ItemFn = parse_str(fn_def).unwrap();
+        let (_, args) = strip_args(f);
+        let explicit: Vec<Vec<Ident>> = explicit
+            .iter()
+            .map(|names| {
+                names
+                    .iter()
+                    .map(|name| {
+                        Ident::new(name, proc_macro2::Span::call_site())
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut errors = Vec::new();
+        let groups = partition(&args, &explicit, &mut errors);
+        (groups, errors.len())
+    }
+
+    #[test]
+    fn ungrouped_args_form_one_group() {
+        assert_eq!(groups_of("fn foo() {}", &[]), (vec![vec![]], 0));
+        assert_eq!(
+            groups_of("fn foo(a: i32, (b, c): (i32, i32)) {}", &[]),
+            (vec![vec![0, 1]], 0)
+        );
+    }
+
+    #[test]
+    fn explicit_groups_come_first() {
+        assert_eq!(
+            groups_of(
+                "fn foo(a: i32, b: i32, c: i32, d: i32) {}",
+                &[&["d", "b"]]
+            ),
+            (vec![vec![3, 1], vec![0, 2]], 0)
+        );
+    }
+
+    #[test]
+    fn many_args_are_chunked() {
+        let f: ItemFn = parse_quote! {
+            fn foo(
+                a0: u8, a1: u8, a2: u8, a3: u8, a4: u8, a5: u8, a6: u8,
+                a7: u8, a8: u8, a9: u8, a10: u8, a11: u8,
+            ) {}
+        };
+        let (_, args) = strip_args(f);
+        let mut errors = Vec::new();
+        let groups = partition(&args, &[], &mut errors);
+
+        assert_eq!(groups, [(0..10).collect::<Vec<_>>(), vec![10, 11]]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn mistakes_are_reported() {
+        assert_eq!(
+            groups_of("fn foo(a: i32, b: i32) {}", &[&["a", "x"], &["a"]]),
+            (vec![vec![0], vec![1]], 2)
+        );
+    }
+}
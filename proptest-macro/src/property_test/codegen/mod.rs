@@ -3,38 +3,75 @@ use quote::{quote, ToTokens};
 use syn::{parse_quote, spanned::Spanned, Attribute, Ident, ItemFn};
 
 use super::{
-    options::Options,
+    options::{AsyncRuntime, Options},
     utils::{strip_args, Argument},
 };
 
 mod arbitrary;
+mod exhaustive_small;
+mod groups;
 mod test_body;
 
 /// Generate the modified test function
 ///
 /// The rough process is:
 ///  - strip out the function args from the provided function
-///  - turn them into a struct
+///  - turn them into a struct (or a struct of structs, one per group of args)
 ///  - implement `Arbitrary` for that struct (simple field-wise impl)
 ///  - create a runner, do the rest
 ///
 ///  Currently, any attributes on parameters are ignored - in the future, we probably want to read
 ///  these for things like customizing strategies
-pub(super) fn generate(item_fn: ItemFn, options: Options) -> TokenStream {
+pub(super) fn generate(item_fn: ItemFn, mut options: Options) -> TokenStream {
     let (mut argless_fn, args) = strip_args(item_fn);
 
-    let struct_tokens = generate_struct(&argless_fn.sig.ident, &args);
-    let arb_tokens =
-        arbitrary::gen_arbitrary_impl(&argless_fn.sig.ident, &args);
+    // the generated tests are synchronous, and run the body of an `async fn` on the runtime
+    options.async_runtime = match (
+        argless_fn.sig.asyncness.take(),
+        options.async_runtime,
+    ) {
+        (Some(_), runtime) => Some(runtime.unwrap_or(AsyncRuntime::Builtin)),
+        (None, Some(_)) => {
+            options.errors.push(quote! {
+                compile_error!("`async = ...` requires the test to be an `async fn`");
+            });
+            None
+        }
+        (None, None) => None,
+    };
+
+    // the sibling test needs the original body, so take it before it's replaced below
+    let sibling = if options.also_exhaustive_small {
+        exhaustive_small::sibling_test(argless_fn.clone(), &args, &options)
+            .into_token_stream()
+    } else {
+        TokenStream::new()
+    };
+
+    let groups = groups::partition(&args, &options.groups, &mut options.errors);
+    let vis = if options.expose_strategy {
+        quote! { pub }
+    } else {
+        TokenStream::new()
+    };
+    let mut struct_and_arb =
+        generate_structs(&argless_fn.sig.ident, &args, &groups, &vis);
 
-    let struct_and_arb = quote! {
-        #struct_tokens
-        #arb_tokens
+    // with `expose_strategy`, the structs move into a module of the same name as the test, so
+    // they can be named from the outside
+    let exposed = if options.expose_strategy {
+        let fn_name = &argless_fn.sig.ident;
+        let exposed = expose_strategy(fn_name, struct_and_arb);
+        struct_and_arb = quote! { use self::#fn_name::*; };
+        exposed
+    } else {
+        TokenStream::new()
     };
 
     let new_body = test_body::body(
         *argless_fn.block,
         &args,
+        &groups,
         struct_and_arb,
         &argless_fn.sig.ident,
         &argless_fn.sig.output,
@@ -44,28 +81,126 @@ pub(super) fn generate(item_fn: ItemFn, options: Options) -> TokenStream {
     *argless_fn.block = new_body;
     argless_fn.attrs.push(test_attr());
 
-    argless_fn.to_token_stream()
+    let mut tokens = argless_fn.to_token_stream();
+    tokens.extend(sibling);
+    tokens.extend(exposed);
+    tokens
+}
+
+/// Generate the module exposing the strategy of the test as `test_name::strategy()`, containing
+/// the structs that represent its arguments
+fn expose_strategy(
+    fn_name: &Ident,
+    struct_and_arb: TokenStream,
+) -> TokenStream {
+    let struct_name = struct_name(fn_name);
+
+    quote! {
+        #[allow(dead_code)]
+        pub mod #fn_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #struct_and_arb
+
+            /// Returns the strategy generating the arguments of the test of the same name
+            pub fn strategy() -> impl ::proptest::strategy::Strategy<Value = #struct_name> {
+                ::proptest::prelude::any::<#struct_name>()
+            }
+        }
+    }
+}
+
+/// An arg, along with its index in the function's args
+type Field<'a> = (usize, &'a Argument);
+
+/// Generate the structs that represent the arguments of the function, and their `Arbitrary` impls
+///
+/// With a single group of args, this is one struct with a field per arg. Otherwise, each group
+/// gets its own struct, and the outer struct has a field per group, so that each group is
+/// generated (and shrunk) as a unit
+fn generate_structs(
+    fn_name: &Ident,
+    args: &[Argument],
+    groups: &[Vec<usize>],
+    vis: &TokenStream,
+) -> TokenStream {
+    let struct_name = struct_name(fn_name);
+
+    if let [group] = groups {
+        let fields = group_fields(args, group);
+        let struct_tokens = fields_struct(&struct_name, &fields, vis);
+        let arb_tokens = arbitrary::gen_fields_impl(&struct_name, &fields);
+
+        return quote! {
+            #struct_tokens
+            #arb_tokens
+        };
+    }
+
+    let mut tokens = TokenStream::new();
+    let mut group_fields_and_types = Vec::with_capacity(groups.len());
+
+    for (index, group) in groups.iter().enumerate() {
+        let fields = group_fields(args, group);
+        let group_struct_name = group_struct_name(fn_name, index);
+
+        tokens.extend(fields_struct(&group_struct_name, &fields, vis));
+        tokens.extend(arbitrary::gen_fields_impl(&group_struct_name, &fields));
+        group_fields_and_types
+            .push((nth_group_name(fn_name, index), group_struct_name));
+    }
+
+    let fields = group_fields_and_types
+        .iter()
+        .map(|(field_name, ty)| quote! { #vis #field_name: #ty, });
+
+    tokens.extend(quote! {
+        #[derive(Debug)]
+        #vis struct #struct_name {
+            #(#fields)*
+        }
+    });
+    tokens.extend(arbitrary::gen_groups_impl(
+        &struct_name,
+        &group_fields_and_types,
+    ));
+
+    tokens
 }
 
 /// Generate the inner struct that represents the arguments of the function
+#[cfg(test)]
 fn generate_struct(fn_name: &Ident, args: &[Argument]) -> TokenStream {
-    let struct_name = struct_name(fn_name);
+    let fields: Vec<_> = args.iter().enumerate().collect();
+    fields_struct(&struct_name(fn_name), &fields, &TokenStream::new())
+}
 
-    let fields = args.iter().enumerate().map(|(index, arg)| {
+/// Generate a struct with a field per arg
+fn fields_struct(
+    struct_name: &Ident,
+    fields: &[Field],
+    vis: &TokenStream,
+) -> TokenStream {
+    let fields = fields.iter().map(|&(index, arg)| {
         let field_name = nth_field_name(&arg.pat_ty.pat, index);
         let ty = &arg.pat_ty.ty;
 
-        quote! { #field_name: #ty, }
+        quote! { #vis #field_name: #ty, }
     });
 
     quote! {
         #[derive(Debug)]
-        struct #struct_name {
+        #vis struct #struct_name {
             #(#fields)*
         }
     }
 }
 
+fn group_fields<'a>(args: &'a [Argument], group: &[usize]) -> Vec<Field<'a>> {
+    group.iter().map(|&index| (index, &args[index])).collect()
+}
+
 /// Convert the name of a function to the name of a struct representing its args
 ///
 /// E.g. `some_function` -> `SomeFunctionArgs`
@@ -78,6 +213,19 @@ fn struct_name(fn_name: &Ident) -> Ident {
     Ident::new(&name, fn_name.span())
 }
 
+/// Convert the name of a function to the name of a struct representing one group of its args
+///
+/// E.g. `some_function`, 1 -> `SomeFunctionArgsGroup1`
+fn group_struct_name(fn_name: &Ident, index: usize) -> Ident {
+    let name = format!("{}Group{index}", struct_name(fn_name));
+    Ident::new(&name, fn_name.span())
+}
+
+/// Like `nth_field_name`, but for the fields holding groups of args
+fn nth_group_name(span: impl Spanned, index: usize) -> Ident {
+    Ident::new(&format!("group{index}"), span.span())
+}
+
 /// We convert all fields to `"field0"`, etc. to account for various different patterns that can
 /// exist in function args. We restore the patterns/bindings when we destructure the struct in the
 /// test body
@@ -1,18 +1,22 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
 use syn::{
     parse2, spanned::Spanned, Block, Expr, Ident, ReturnType, Type, TypeTuple,
 };
 
-use crate::property_test::{options::Options, utils::Argument};
+use crate::property_test::{
+    options::{AsyncRuntime, Options},
+    utils::Argument,
+};
 
-use super::{nth_field_name, struct_name};
+use super::{group_struct_name, nth_field_name, nth_group_name, struct_name};
 
 /// Generate the new test body by putting the struct and arbitrary impl at the start, then adding
 /// the usual glue that `proptest!` adds
 pub(super) fn body(
     block: Block,
     args: &[Argument],
+    groups: &[Vec<usize>],
     struct_and_impl: TokenStream,
     fn_name: &Ident,
     ret_ty: &ReturnType,
@@ -22,21 +26,45 @@ pub(super) fn body(
 
     let errors = &options.errors;
 
-    // convert each arg to `field0: x`
-    let struct_fields = args.iter().enumerate().map(|(index, arg)| {
-        let pat = &arg.pat_ty.pat;
-        let field_name = nth_field_name(arg.pat_ty.pat.span(), index);
-        quote!(#field_name: #pat,)
-    });
+    // convert each arg in a group to `field0: x`
+    let group_fields = |group: &[usize]| -> TokenStream {
+        group
+            .iter()
+            .map(|&index| {
+                let pat = &args[index].pat_ty.pat;
+                let field_name = nth_field_name(pat.span(), index);
+                quote!(#field_name: #pat,)
+            })
+            .collect()
+    };
 
-    // e.g. FooArgs { field0: x, field1: (y, z), }
-    let struct_pattern = quote! {
-        #struct_name { #(#struct_fields)* }
+    let struct_pattern = match groups {
+        // e.g. FooArgs { field0: x, field1: (y, z), }
+        [group] => {
+            let struct_fields = group_fields(group);
+            quote! {
+                #struct_name { #struct_fields }
+            }
+        }
+        // e.g. FooArgs { group0: FooArgsGroup0 { field0: x, }, group1: FooArgsGroup1 { ... }, }
+        _ => {
+            let struct_groups =
+                groups.iter().enumerate().map(|(index, group)| {
+                    let group_name = nth_group_name(fn_name, index);
+                    let group_struct_name = group_struct_name(fn_name, index);
+                    let struct_fields = group_fields(group);
+                    quote!(#group_name: #group_struct_name { #struct_fields },)
+                });
+            quote! {
+                #struct_name { #(#struct_groups)* }
+            }
+        }
     };
 
-    let handle_result = handle_result(ret_ty);
+    let run_case = run_case(&block, ret_ty, options.async_runtime);
 
     let config = make_config(options.config.as_ref());
+    let async_setup = async_setup(options.async_runtime);
 
     let tokens = quote! ( {
 
@@ -46,6 +74,8 @@ pub(super) fn body(
 
         #config
 
+        #async_setup
+
         let mut runner = ::proptest::test_runner::TestRunner::new(config);
         
         let result = runner.run(
@@ -53,8 +83,7 @@ pub(super) fn body(
                 ::proptest::sugar::NamedArguments(stringify!(#struct_name), values)
             }),
             |::proptest::sugar::NamedArguments(_, #struct_pattern)| {
-                let result = #block;
-                #handle_result
+                #run_case
             },
         );
 
@@ -90,7 +119,75 @@ fn handle_result(ret_ty: &ReturnType) -> TokenStream {
     }
 }
 
-fn make_config(config: Option<&Expr>) -> TokenStream {
+/// Generate the statements of a single test case, running `block` to completion on `runtime` if
+/// the test is an `async fn`
+pub(super) fn run_case(
+    block: &Block,
+    ret_ty: &ReturnType,
+    runtime: Option<AsyncRuntime>,
+) -> TokenStream {
+    let handle_result = handle_result(ret_ty);
+    let case = quote! {
+        let result = #block;
+        #handle_result
+    };
+    let (timeout, runtime_var) = async_idents();
+
+    match runtime {
+        None => case,
+        Some(AsyncRuntime::Builtin) => quote! {
+            ::proptest::test_runner::block_on_case(#timeout, async move { #case })
+        },
+        // the future is dropped when it times out, so whatever it was waiting on is cancelled
+        Some(AsyncRuntime::Tokio) => quote! {
+            let case = async move { #case };
+            #runtime_var.block_on(async move {
+                if #timeout == 0 {
+                    return case.await;
+                }
+                let duration = ::std::time::Duration::from_millis(#timeout.into());
+                match ::tokio::time::timeout(duration, case).await {
+                    Ok(result) => result,
+                    Err(_) => Err(::proptest::test_runner::TestCaseError::fail(::std::format!(
+                        "Timeout of {} ms exceeded: the test future was cancelled",
+                        #timeout,
+                    ))),
+                }
+            })
+        },
+    }
+}
+
+/// Generate the statements preparing to run the test cases of an `async fn` test on `runtime`,
+/// which must follow the definition of `config`
+pub(super) fn async_setup(runtime: Option<AsyncRuntime>) -> TokenStream {
+    let (timeout, runtime_var) = async_idents();
+
+    match runtime {
+        None => TokenStream::new(),
+        Some(AsyncRuntime::Builtin) => quote! {
+            let #timeout = config.timeout();
+        },
+        Some(AsyncRuntime::Tokio) => quote! {
+            let #timeout = config.timeout();
+            let #runtime_var = ::tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("failed to build the tokio runtime");
+        },
+    }
+}
+
+/// The variables holding the timeout and runtime of `async fn` tests, which are hygienic so that
+/// they can't clash with the parameters of the test
+fn async_idents() -> (Ident, Ident) {
+    (
+        Ident::new("timeout", Span::mixed_site()),
+        Ident::new("runtime", Span::mixed_site()),
+    )
+}
+
+pub(super) fn make_config(config: Option<&Expr>) -> TokenStream {
     let trailing = match config {
         None => quote! { ::proptest::test_runner::Config::default() },
         Some(config) => config.to_token_stream(),
@@ -1,8 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::quote_spanned;
 use syn::{
-    parse::Parse, punctuated::Punctuated, spanned::Spanned, Expr, Ident,
-    LitStr, MetaNameValue, Token,
+    parse::Parse, punctuated::Punctuated, spanned::Spanned, Expr, ExprLit,
+    Ident, Lit, LitStr, Meta, MetaList, MetaNameValue, Token,
 };
 
 /// Options parsed from the attribute itself (e.g. the config from `#[property_test(config = ...)]`)
@@ -11,26 +11,81 @@ pub(super) struct Options {
     /// Collect compiler errors and emit them later, since errors here are largely recoverable
     pub errors: Vec<TokenStream>,
     pub config: Option<Expr>,
+    /// Parameters to generate (and shrink) together, from
+    /// `#[property_test(group(a, b), group(c, d))]`
+    pub groups: Vec<Vec<Ident>>,
+    /// Whether to expose the strategy of the test as `test_name::strategy()`, from
+    /// `#[property_test(expose_strategy)]`
+    pub expose_strategy: bool,
+    /// Whether to also emit a test enumerating small edge values of the parameters, from
+    /// `#[property_test(also_exhaustive_small)]`
+    pub also_exhaustive_small: bool,
+    /// The runtime to run the body of an `async fn` test on, from
+    /// `#[property_test(async = "tokio")]`. Before generating the test, this is set to `Builtin`
+    /// for an `async fn` without a runtime, and checked to be `None` otherwise
+    pub async_runtime: Option<AsyncRuntime>,
+}
+
+/// How the body of an `async fn` test is run to completion
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum AsyncRuntime {
+    /// `proptest::test_runner::block_on_case`, when no runtime is given
+    Builtin,
+    /// A current-thread tokio runtime
+    Tokio,
 }
 
 impl Parse for Options {
     // note: this impl takes only the contents of the attr, not the attr itself
     // e.g. it will get `foo = bar, baz = qux`, not `#[macro(foo = bar, baz = qux)]`
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let pairs =
-            Punctuated::<MetaNameValue, Token![,]>::parse_terminated(input)?;
+        let args = Punctuated::<Arg, Token![,]>::parse_terminated(input)?;
 
         let mut errors = Vec::new();
 
         let mut config = None;
+        let mut groups = Vec::new();
+        let mut expose_strategy = false;
+        let mut also_exhaustive_small = false;
+        let mut async_runtime = None;
 
-        for MetaNameValue { path, value, .. } in pairs {
-            let path_string = path.get_ident().map(Ident::to_string);
+        for arg in args {
+            let meta = match arg {
+                Arg::Meta(meta) => meta,
+                Arg::Async(token, runtime) => {
+                    match parse_async_runtime(token, runtime) {
+                        Ok(runtime) => async_runtime = Some(runtime),
+                        Err(e) => errors.push(e.into_compile_error()),
+                    }
+                    continue;
+                }
+            };
+            let path_string = meta.path().get_ident().map(Ident::to_string);
 
-            match path_string.as_deref() {
-                None => errors.push(quote_spanned!(path.span() => compile_error!("unknown argument"))),
-                Some("config") => config = Some(value),
-                Some(other) => {
+            match (path_string.as_deref(), meta) {
+                (None, meta) => errors.push(quote_spanned!(meta.path().span() => compile_error!("unknown argument"))),
+                (Some("config"), Meta::NameValue(MetaNameValue { value, .. })) => config = Some(value),
+                (Some("group"), Meta::List(list)) => match parse_group(&list) {
+                    Ok(group) => groups.push(group),
+                    Err(e) => errors.push(e.into_compile_error()),
+                },
+                (Some("expose_strategy"), Meta::Path(_)) => expose_strategy = true,
+                (Some("also_exhaustive_small"), Meta::Path(_)) => also_exhaustive_small = true,
+                (
+                    Some(
+                        other @ ("config" | "group" | "expose_strategy" | "also_exhaustive_small"),
+                    ),
+                    meta,
+                ) => {
+                    let error_message = match other {
+                        "config" => "`config` must have the form `config = <expr>`",
+                        "group" => "`group` must have the form `group(a, b, ...)`",
+                        "expose_strategy" => "`expose_strategy` takes no arguments",
+                        _ => "`also_exhaustive_small` takes no arguments",
+                    };
+                    errors.push(quote_spanned!(meta.span() => compile_error!(#error_message)));
+                }
+                (Some(other), _) => {
                     let error_message = format!("unknown argument: {other}");
                     let error_message = LitStr::new(&error_message, other.span());
                     let error = quote_spanned!(other.span() => compile_error!(#error_message));
@@ -39,10 +94,75 @@ impl Parse for Options {
             }
         }
 
-        Ok(Self { errors, config })
+        Ok(Self {
+            errors,
+            config,
+            groups,
+            expose_strategy,
+            also_exhaustive_small,
+            async_runtime,
+        })
+    }
+}
+
+/// A single argument of the attribute
+enum Arg {
+    Meta(Meta),
+    /// `async = <expr>`, which isn't a `Meta` since `async` is a keyword
+    Async(Token![async], Option<Expr>),
+}
+
+impl Parse for Arg {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![async]) {
+            let token = input.parse()?;
+            let runtime = if input.peek(Token![=]) {
+                input.parse::<Token![=]>()?;
+                Some(input.parse()?)
+            } else {
+                None
+            };
+            Ok(Arg::Async(token, runtime))
+        } else {
+            input.parse().map(Arg::Meta)
+        }
+    }
+}
+
+/// Parse the runtime of `async = "<runtime>"`
+fn parse_async_runtime(
+    token: Token![async],
+    runtime: Option<Expr>,
+) -> syn::Result<AsyncRuntime> {
+    match runtime {
+        Some(Expr::Lit(ExprLit {
+            lit: Lit::Str(runtime),
+            ..
+        })) => match runtime.value().as_str() {
+            "tokio" => Ok(AsyncRuntime::Tokio),
+            other => Err(syn::Error::new(
+                runtime.span(),
+                format!("unknown async runtime: {other}, expected \"tokio\""),
+            )),
+        },
+        Some(runtime) => Err(syn::Error::new(
+            runtime.span(),
+            "`async` must have the form `async = \"<runtime>\"`",
+        )),
+        None => Err(syn::Error::new(
+            token.span(),
+            "`async` must have the form `async = \"<runtime>\"`",
+        )),
     }
 }
 
+/// Parse the parameter names of `group(a, b, ...)`
+fn parse_group(list: &MetaList) -> syn::Result<Vec<Ident>> {
+    let names =
+        list.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+    Ok(names.into_iter().collect())
+}
+
 #[cfg(test)]
 mod tests {
     use syn::parse_str;
@@ -51,10 +171,75 @@ mod tests {
 
     #[test]
     fn simple_parse_example() {
-        let Options { errors, config } =
-            parse_str("config = (), random = 123").unwrap();
+        let Options {
+            errors,
+            config,
+            groups,
+            expose_strategy,
+            also_exhaustive_small,
+            async_runtime,
+        } = parse_str("config = (), random = 123").unwrap();
+
+        assert!(config.is_some());
+        assert!(groups.is_empty());
+        assert!(!expose_strategy);
+        assert!(!also_exhaustive_small);
+        assert!(async_runtime.is_none());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_groups() {
+        let Options {
+            errors,
+            config,
+            groups,
+            ..
+        } = parse_str("group(a, b), config = (), group(c), group = 1").unwrap();
 
         assert!(config.is_some());
+        let groups: Vec<Vec<_>> = groups
+            .iter()
+            .map(|group| group.iter().map(Ident::to_string).collect())
+            .collect();
+        assert_eq!(groups, [vec!["a", "b"], vec!["c"]]);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_expose_strategy() {
+        let Options {
+            errors,
+            expose_strategy,
+            ..
+        } = parse_str("expose_strategy, expose_strategy = true").unwrap();
+
+        assert!(expose_strategy);
         assert_eq!(errors.len(), 1);
     }
+
+    #[test]
+    fn parse_also_exhaustive_small() {
+        let Options {
+            errors,
+            also_exhaustive_small,
+            ..
+        } = parse_str("also_exhaustive_small, also_exhaustive_small(1)")
+            .unwrap();
+
+        assert!(also_exhaustive_small);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_async() {
+        let Options {
+            errors,
+            async_runtime,
+            ..
+        } = parse_str(r#"async = "tokio", async = "smol", async"#).unwrap();
+
+        assert_eq!(async_runtime, Some(AsyncRuntime::Tokio));
+        assert_eq!(errors.len(), 2);
+    }
 }
@@ -8,12 +8,18 @@ use crate::property_test::{codegen, options::Options};
 /// to it. The generated code is then formatted, and passed to the snapshot testing library
 ///
 /// If `fails` is supplied, then the output will be
+///
+/// Options for the macro can be supplied in parentheses before the block
 macro_rules! snapshot_test {
     ($name:ident {$($t:tt)*}) => {
+        snapshot_test!($name () {$($t)*});
+    };
+    ($name:ident ($($options:tt)*) {$($t:tt)*}) => {
         #[test]
         fn $name() {
             let input = parse_quote! { $($t)* };
-            let tokens = codegen::generate(input, Options::default());
+            let options: Options = parse_quote! { $($options)* };
+            let tokens = codegen::generate(input, options);
             let file = syn::parse_file(&tokens.to_string()).unwrap();
             let formatted = prettyplease::unparse(&file);
 
@@ -45,3 +51,42 @@ snapshot_test!(mix_custom_and_default_strategies {
         let x = 1;
     }
 });
+
+snapshot_test!(many_args_are_grouped {
+    fn foo(
+        a0: u8, a1: u8, a2: u8, a3: u8, a4: u8, a5: u8,
+        a6: u8, a7: u8, a8: u8, a9: u8, a10: u8, a11: u8,
+    ) {
+        let x = 1;
+    }
+});
+
+snapshot_test!(explicit_groups (group(y, z)) {
+    fn foo(x: i32, #[strategy = 0..10] y: i32, z: String) {
+        let x = 1;
+    }
+});
+
+snapshot_test!(exposed_strategy (expose_strategy) {
+    fn foo(x: i32, #[strategy = 0..10] y: i32) {
+        let x = 1;
+    }
+});
+
+snapshot_test!(exhaustive_small (also_exhaustive_small) {
+    fn foo(x: u8, #[strategy = 1..10u8] y: u8) {
+        let x = 1;
+    }
+});
+
+snapshot_test!(async_fn {
+    async fn foo(x: i32) {
+        let y = async { x }.await;
+    }
+});
+
+snapshot_test!(async_tokio (async = "tokio") {
+    async fn foo(x: i32) {
+        tokio::task::yield_now().await;
+    }
+});
@@ -11,7 +11,13 @@
 //!
 //! Please refer to the Proptest Book chapter "State Machine testing" to learn
 //! when and how to use this and how it's made.
+//!
+//! With the `html-report` feature, a failing state machine test also writes
+//! a step by step HTML report of its minimal failing case to
+//! `target/proptest-reports/`, showing each transition with the changes of
+//! the reference state and the outcome of the SUT checks.
 
+mod report;
 pub mod strategy;
 pub mod test_runner;
 
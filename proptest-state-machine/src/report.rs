@@ -0,0 +1,311 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Step by step HTML reports of failing state machine test cases, enabled by
+//! the `html-report` feature.
+//!
+//! Every test case records its transitions along with the reference states
+//! and the outcome of the SUT checks. When a step fails, the report of the
+//! case is written to `proptest-reports/<test name>.html` in the target
+//! directory (`CARGO_TARGET_DIR`, or `target` in the working directory).
+//! Since each failure overwrites the report of the previous one, the report
+//! left after shrinking is that of the minimal failing case.
+
+use std::fmt::Debug;
+
+use proptest::test_runner::Config;
+
+/// The report of the test case being run.
+#[cfg(feature = "html-report")]
+pub(crate) struct Report {
+    test_name: String,
+    steps: Vec<Step>,
+}
+
+/// A step of a test case: the initial state, or a transition.
+#[cfg(feature = "html-report")]
+struct Step {
+    /// The `Debug` output of the transition, or `None` for the initial state
+    transition: Option<String>,
+    /// The pretty `Debug` output of the reference state after the step
+    ref_state: String,
+    /// The panic message of the SUT, if the step failed
+    failure: Option<String>,
+}
+
+#[cfg(feature = "html-report")]
+impl Report {
+    /// Starts the report of a test case from the initial `ref_state`.
+    pub(crate) fn new(config: &Config, ref_state: &impl Debug) -> Self {
+        let thread = std::thread::current();
+        let test_name = config
+            .test_name
+            .or_else(|| thread.name())
+            .unwrap_or("state_machine")
+            .to_owned();
+        Report {
+            test_name,
+            steps: vec![Step {
+                transition: None,
+                ref_state: format!("{:#?}", ref_state),
+                failure: None,
+            }],
+        }
+    }
+
+    /// Starts the step of applying `transition`.
+    pub(crate) fn transition(&mut self, transition: &impl Debug) {
+        self.steps.push(Step {
+            transition: Some(format!("{:?}", transition)),
+            ref_state: String::new(),
+            failure: None,
+        });
+    }
+
+    /// Records the reference state after the current transition.
+    pub(crate) fn reference_state(&mut self, ref_state: &impl Debug) {
+        self.current().ref_state = format!("{:#?}", ref_state);
+    }
+
+    /// Runs the SUT side of the current step. If it panics, the report is
+    /// written before resuming the panic.
+    pub(crate) fn step<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        use std::panic::{self, AssertUnwindSafe};
+
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| (*s).to_owned())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "<non-string panic payload>".to_owned());
+                self.current().failure = Some(message);
+                self.write();
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    fn current(&mut self) -> &mut Step {
+        self.steps.last_mut().expect("a report has an initial step")
+    }
+
+    /// Writes the report, only warning if this fails since the test is
+    /// failing anyway.
+    fn write(&self) {
+        let dir = std::env::var_os("CARGO_TARGET_DIR")
+            .map_or_else(|| "target".into(), std::path::PathBuf::from)
+            .join("proptest-reports");
+        let file_name: String = self
+            .test_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = dir.join(format!("{}.html", file_name));
+        match std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&path, self.render()))
+        {
+            Ok(()) => eprintln!(
+                "State machine test report written to {}",
+                path.display()
+            ),
+            Err(err) => eprintln!(
+                "Failed to write state machine test report to {}: {}",
+                path.display(),
+                err
+            ),
+        }
+    }
+
+    /// Renders the report as a standalone HTML page.
+    fn render(&self) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>\n\
+             body {{ font-family: sans-serif; }}\n\
+             pre {{ background: #f6f8fa; padding: 0.5em; }}\n\
+             .added {{ background: #e6ffec; }}\n\
+             .removed {{ background: #ffebe9; }}\n\
+             .passed {{ color: #1a7f37; }}\n\
+             .failed {{ color: #cf222e; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+            title = escape(&self.test_name)
+        );
+        let mut previous = "";
+        for (ix, step) in self.steps.iter().enumerate() {
+            match step.transition {
+                None => html.push_str("<h2>Initial state</h2>\n"),
+                Some(ref transition) => html.push_str(&format!(
+                    "<h2>Transition {}/{}: <code>{}</code></h2>\n",
+                    ix,
+                    self.steps.len() - 1,
+                    escape(transition)
+                )),
+            }
+            html.push_str("<pre>");
+            for (change, line) in diff_lines(previous, &step.ref_state) {
+                let line = escape(line);
+                match change {
+                    Change::Same => html.push_str(&format!("  {}\n", line)),
+                    Change::Added => html.push_str(&format!(
+                        "<span class=\"added\">+ {}</span>\n",
+                        line
+                    )),
+                    Change::Removed => html.push_str(&format!(
+                        "<span class=\"removed\">- {}</span>\n",
+                        line
+                    )),
+                }
+            }
+            html.push_str("</pre>\n");
+            match step.failure {
+                None => {
+                    html.push_str("<p class=\"passed\">SUT checks passed</p>\n")
+                }
+                Some(ref failure) => html.push_str(&format!(
+                    "<p class=\"failed\">SUT checks failed:</p>\n\
+                     <pre class=\"failed\">{}</pre>\n",
+                    escape(failure)
+                )),
+            }
+            previous = &step.ref_state;
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// The report of the test case being run, which records nothing without the
+/// `html-report` feature.
+#[cfg(not(feature = "html-report"))]
+pub(crate) struct Report;
+
+#[cfg(not(feature = "html-report"))]
+impl Report {
+    pub(crate) fn new(_: &Config, _: &impl Debug) -> Self {
+        Report
+    }
+
+    pub(crate) fn transition(&mut self, _: &impl Debug) {}
+
+    pub(crate) fn reference_state(&mut self, _: &impl Debug) {}
+
+    pub(crate) fn step<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+}
+
+/// The change of a line between two reference states
+#[cfg(feature = "html-report")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Change {
+    Same,
+    Added,
+    Removed,
+}
+
+/// The lines of `before` and `after` with how they changed, by their longest
+/// common subsequence.
+#[cfg(feature = "html-report")]
+fn diff_lines<'a>(before: &'a str, after: &'a str) -> Vec<(Change, &'a str)> {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+
+    // The length of the longest common subsequence of the suffixes of the
+    // lines from each index on
+    let mut common = vec![vec![0usize; after.len() + 1]; before.len() + 1];
+    for i in (0..before.len()).rev() {
+        for j in (0..after.len()).rev() {
+            common[i][j] = if before[i] == after[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(before.len().max(after.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < before.len() || j < after.len() {
+        if i < before.len() && j < after.len() && before[i] == after[j] {
+            lines.push((Change::Same, after[j]));
+            i += 1;
+            j += 1;
+        } else if j < after.len()
+            && (i == before.len() || common[i][j + 1] >= common[i + 1][j])
+        {
+            lines.push((Change::Added, after[j]));
+            j += 1;
+        } else {
+            lines.push((Change::Removed, before[i]));
+            i += 1;
+        }
+    }
+    lines
+}
+
+/// Escapes `text` for HTML.
+#[cfg(feature = "html-report")]
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(all(test, feature = "html-report"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn diff_marks_changed_lines() {
+        assert_eq!(
+            vec![
+                (Change::Same, "["),
+                (Change::Removed, "    1,"),
+                (Change::Same, "    2,"),
+                (Change::Added, "    3,"),
+                (Change::Same, "]"),
+            ],
+            diff_lines("[\n    1,\n    2,\n]", "[\n    2,\n    3,\n]")
+        );
+    }
+
+    #[test]
+    fn renders_steps_and_failure() {
+        let mut report = Report::new(&Config::default(), &Vec::<u8>::new());
+        report.transition(&Some("<push>"));
+        report.reference_state(&vec![1u8]);
+        report.steps.last_mut().unwrap().failure = Some("boom".to_owned());
+
+        let html = report.render();
+        assert!(html.contains("<h2>Initial state</h2>"), "{}", html);
+        assert!(
+            html.contains(
+                "Transition 1/1: <code>Some(&quot;&lt;push&gt;&quot;)"
+            ),
+            "{}",
+            html
+        );
+        assert!(html.contains("<span class=\"added\">+     1,</span>"));
+        assert!(
+            html.contains("<pre class=\"failed\">boom</pre>"),
+            "{}",
+            html
+        );
+    }
+}
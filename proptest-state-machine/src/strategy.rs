@@ -9,17 +9,20 @@
 
 //! Strategies used for abstract state machine testing.
 
+use std::collections::BTreeMap;
 use std::sync::atomic::{self, AtomicUsize};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use proptest::bits::{BitSetLike, VarBitSet};
 use proptest::collection::SizeRange;
 use proptest::num::sample_uniform_incl;
-use proptest::std_facade::fmt::{Debug, Formatter, Result};
-use proptest::std_facade::Vec;
+use proptest::std_facade::fmt::{Debug, Display, Formatter, Result};
+use proptest::std_facade::{String, ToOwned, Vec};
 use proptest::strategy::BoxedStrategy;
 use proptest::strategy::{NewTree, Strategy, ValueTree};
 use proptest::test_runner::TestRunner;
+#[cfg(feature = "std")]
+use proptest::test_runner::INFO_LOG;
 
 /// This trait is used to model system under test as an abstract state machine.
 ///
@@ -66,6 +69,76 @@ pub trait ReferenceStateMachine {
     /// Generate the initial transitions.
     fn transitions(state: &Self::State) -> BoxedStrategy<Self::Transition>;
 
+    /// Optionally, alternative transitions with weights that adapt to the
+    /// current state, which take the place of [`Self::transitions`] whenever
+    /// this returns `Some`.
+    ///
+    /// For each transition, the sequential strategy chooses one of the
+    /// alternatives with a probability proportional to its weight and only
+    /// builds the strategy of that alternative. Unlike with weights given to
+    /// `prop_oneof!` in `transitions`, this avoids building the strategies of
+    /// all the alternatives for every transition. A weight of 0 disables an
+    /// alternative in the current state, but at least one weight must be
+    /// non-zero.
+    ///
+    /// The chosen alternative is kept while shrinking, so a transition only
+    /// shrinks within its alternative.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest_state_machine::{ReferenceStateMachine, WeightedTransitions};
+    ///
+    /// struct StackModel;
+    ///
+    /// #[derive(Clone, Debug)]
+    /// enum Transition {
+    ///     Push(u32),
+    ///     Pop,
+    /// }
+    ///
+    /// impl ReferenceStateMachine for StackModel {
+    ///     type State = Vec<u32>;
+    ///     type Transition = Transition;
+    ///
+    ///     fn init_state() -> BoxedStrategy<Self::State> {
+    ///         Just(Vec::new()).boxed()
+    ///     }
+    ///
+    ///     fn transitions(_: &Self::State) -> BoxedStrategy<Transition> {
+    ///         any::<u32>().prop_map(Transition::Push).boxed()
+    ///     }
+    ///
+    ///     fn transition_weights(
+    ///         state: &Self::State,
+    ///     ) -> Option<WeightedTransitions<Self::State, Transition>> {
+    ///         // Favour popping as the stack grows.
+    ///         let pop = state.len().min(10) as u32;
+    ///         Some(vec![
+    ///             (4, |_| any::<u32>().prop_map(Transition::Push).boxed()),
+    ///             (pop, |_| Just(Transition::Pop).boxed()),
+    ///         ])
+    ///     }
+    ///
+    ///     fn apply(mut state: Vec<u32>, transition: &Transition) -> Vec<u32> {
+    ///         match transition {
+    ///             Transition::Push(v) => state.push(*v),
+    ///             Transition::Pop => {
+    ///                 state.pop();
+    ///             }
+    ///         }
+    ///         state
+    ///     }
+    /// }
+    /// ```
+    fn transition_weights(
+        state: &Self::State,
+    ) -> Option<WeightedTransitions<Self::State, Self::Transition>> {
+        // This is to avoid `unused_variables` warning
+        let _ = state;
+
+        None
+    }
+
     /// Apply a transition in the reference state.
     fn apply(state: Self::State, transition: &Self::Transition) -> Self::State;
 
@@ -88,6 +161,13 @@ pub trait ReferenceStateMachine {
         true
     }
 
+    /// Optionally, the names of all the variants of [`Self::Transition`], so
+    /// that a [`TransitionCoverage`] also reports the variants which were
+    /// never generated.
+    fn transition_variants() -> &'static [&'static str] {
+        &[]
+    }
+
     /// A sequential strategy runs the state machine transitions generated from
     /// the reference model sequentially in a test over a concrete state, which
     /// can be implemented with the help of
@@ -109,9 +189,85 @@ pub trait ReferenceStateMachine {
             Self::transitions,
             Self::apply,
         )
+        .with_transition_weights(Self::transition_weights)
+    }
+
+    /// Like [`Self::sequential_strategy`], but half of the test cases start
+    /// from one of the `corpus` states instead of one generated by
+    /// [`Self::init_state`].
+    ///
+    /// For long-lived systems, bugs may only be reachable from states which
+    /// take many more transitions to build up than a test case has. The
+    /// `corpus` is typically deserialized from snapshots of such states, e.g.
+    /// reference states recorded alongside a production system. States taken
+    /// from the corpus are not shrunk, but the transitions applied to them
+    /// are.
+    ///
+    /// The snapshot is picked by the test runner's RNG, so a persisted
+    /// failure replays with the same snapshot as long as the order of the
+    /// `corpus` doesn't change. The index of the snapshot is printed when
+    /// the test runner is at least at `INFO_LOG` verbosity and is available
+    /// from [`SequentialValueTree::initial_snapshot`].
+    fn sequential_strategy_with_initial_corpus(
+        size: impl Into<SizeRange>,
+        corpus: Vec<Self::State>,
+    ) -> Sequential<
+        Self::State,
+        Self::Transition,
+        BoxedStrategy<Self::State>,
+        BoxedStrategy<Self::Transition>,
+    > {
+        Self::sequential_strategy(size).with_initial_corpus(corpus)
+    }
+
+    /// A parallel strategy generates a prefix of transitions to apply
+    /// sequentially like [`Self::sequential_strategy`], followed by a suffix
+    /// of transitions for each of `threads` concurrent threads, to be run by
+    /// [`crate::ConcurrentStateMachineTest::test_concurrent`].
+    ///
+    /// The pre-conditions of the suffixes must hold in every interleaving of
+    /// their transitions, and plans which don't are rejected. As this checks
+    /// every interleaving, keep the `suffix_size` small, typically no more
+    /// than a handful of transitions.
+    ///
+    /// You typically never need to override this method.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `threads` is less than 2.
+    fn parallel_strategy(
+        prefix_size: impl Into<SizeRange>,
+        suffix_size: impl Into<SizeRange>,
+        threads: usize,
+    ) -> Parallel<
+        Self::State,
+        Self::Transition,
+        BoxedStrategy<Self::State>,
+        BoxedStrategy<Self::Transition>,
+    > {
+        Parallel::new(
+            Self::sequential_strategy(prefix_size),
+            suffix_size.into(),
+            threads,
+        )
     }
 }
 
+/// Alternative transitions of a reference state machine, each with a weight
+/// and a function building the strategy for the alternative from the current
+/// state, as returned by [`ReferenceStateMachine::transition_weights`].
+pub type WeightedTransitions<State, Transition> =
+    Alternatives<State, BoxedStrategy<Transition>>;
+
+/// Like `WeightedTransitions`, for any transition strategy.
+type Alternatives<State, TransitionStrategy> =
+    Vec<(u32, fn(&State) -> TransitionStrategy)>;
+
+/// The type of [`ReferenceStateMachine::transition_weights`], for any
+/// transition strategy.
+type TransitionWeights<State, TransitionStrategy> =
+    fn(state: &State) -> Option<Alternatives<State, TransitionStrategy>>;
+
 /// In a sequential state machine strategy, we first generate an acceptable
 /// sequence of transitions. That is a sequence that satisfies the given
 /// pre-conditions. The acceptability of each transition in the sequence depends
@@ -143,7 +299,10 @@ pub struct Sequential<State, Transition, StateStrategy, TransitionStrategy> {
     init_state: fn() -> StateStrategy,
     preconditions: fn(state: &State, transition: &Transition) -> bool,
     transitions: fn(state: &State) -> TransitionStrategy,
+    transition_weights: TransitionWeights<State, TransitionStrategy>,
     next: fn(state: State, transition: &Transition) -> State,
+    initial_corpus: Vec<State>,
+    transition_coverage: Option<TransitionCoverage>,
 }
 
 impl<State, Transition, StateStrategy, TransitionStrategy>
@@ -161,7 +320,207 @@ impl<State, Transition, StateStrategy, TransitionStrategy>
             init_state,
             preconditions,
             transitions,
+            transition_weights: |_| None,
             next,
+            initial_corpus: Vec::new(),
+            transition_coverage: None,
+        }
+    }
+
+    /// Sets the alternative transitions with state-dependent weights, which
+    /// take the place of `transitions` in the states where
+    /// `transition_weights` returns `Some`. See
+    /// [`ReferenceStateMachine::transition_weights`].
+    pub fn with_transition_weights(
+        mut self,
+        transition_weights: TransitionWeights<State, TransitionStrategy>,
+    ) -> Self {
+        self.transition_weights = transition_weights;
+        self
+    }
+
+    /// Sets the snapshots of initial states which half of the test cases
+    /// start from instead of a state generated by `init_state`. See
+    /// [`ReferenceStateMachine::sequential_strategy_with_initial_corpus`].
+    pub fn with_initial_corpus(mut self, initial_corpus: Vec<State>) -> Self {
+        self.initial_corpus = initial_corpus;
+        self
+    }
+
+    /// Records how often each variant of the transitions is generated and
+    /// accepted by the pre-conditions in `coverage`. See
+    /// [`TransitionCoverage`].
+    pub fn with_transition_coverage(
+        mut self,
+        coverage: TransitionCoverage,
+    ) -> Self {
+        self.transition_coverage = Some(coverage);
+        self
+    }
+}
+
+/// Picks one of `alternatives` with a probability proportional to its weight.
+fn pick_weighted<T: Copy>(
+    runner: &mut TestRunner,
+    alternatives: &[(u32, T)],
+) -> T {
+    let total: u64 = alternatives.iter().map(|&(w, _)| u64::from(w)).sum();
+    assert!(total > 0, "All transition weights are 0");
+
+    let mut pick = sample_uniform_incl(runner, 0, total - 1);
+    for &(weight, alternative) in alternatives {
+        if pick < u64::from(weight) {
+            return alternative;
+        }
+        pick -= u64::from(weight);
+    }
+    unreachable!()
+}
+
+/// How often the transitions of each variant were generated and accepted by
+/// the pre-conditions, as counted by [`TransitionCoverage`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransitionCounts {
+    /// The number of generated transitions, including the ones rejected by
+    /// the pre-conditions.
+    pub generated: u64,
+    /// The number of generated transitions accepted by the pre-conditions.
+    pub accepted: u64,
+}
+
+/// Counts how often each variant of the transitions of a reference state
+/// machine is generated and accepted by the pre-conditions, to detect
+/// transitions which are unreachable or always filtered out by a
+/// pre-condition.
+///
+/// Transitions are told apart by the leading identifier of their `Debug`
+/// output, which is the name of the variant for an enum with a derived
+/// `Debug`. The variants listed by
+/// [`ReferenceStateMachine::transition_variants`] are reported even if they
+/// were never generated.
+///
+/// Clones share the same counts. Pass one to
+/// [`Sequential::with_transition_coverage`], or add `, transition_coverage`
+/// to a test in `prop_state_machine!` to print the report after the test has
+/// run.
+#[derive(Clone)]
+pub struct TransitionCoverage(Arc<CoverageCounts>);
+
+struct CoverageCounts {
+    counts: Mutex<BTreeMap<String, TransitionCounts>>,
+    report_on_drop: bool,
+}
+
+impl TransitionCoverage {
+    /// Creates empty counts, reporting the given `variants` even if they are
+    /// never generated.
+    pub fn new(variants: &[&str]) -> Self {
+        Self::with_report_on_drop(variants, false)
+    }
+
+    /// Like [`Self::new`], but prints the report to stderr once the last
+    /// clone is dropped, i.e. once the strategy holding it is dropped after
+    /// the test has run.
+    pub fn reporting(variants: &[&str]) -> Self {
+        Self::with_report_on_drop(variants, true)
+    }
+
+    fn with_report_on_drop(variants: &[&str], report_on_drop: bool) -> Self {
+        let counts = variants
+            .iter()
+            .map(|&variant| (variant.to_owned(), TransitionCounts::default()))
+            .collect();
+        TransitionCoverage(Arc::new(CoverageCounts {
+            counts: Mutex::new(counts),
+            report_on_drop,
+        }))
+    }
+
+    /// Returns the counts of each variant.
+    pub fn counts(&self) -> BTreeMap<String, TransitionCounts> {
+        self.0.counts.lock().unwrap().clone()
+    }
+
+    /// Returns the variants which were never generated, or always rejected
+    /// by the pre-conditions.
+    pub fn unreachable(&self) -> Vec<String> {
+        self.0
+            .counts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, counts)| 0 == counts.accepted)
+            .map(|(variant, _)| variant.clone())
+            .collect()
+    }
+
+    fn record(&self, transition: &impl Debug, accepted: bool) {
+        let debug = format!("{:?}", transition);
+        let mut counts = self.0.counts.lock().unwrap();
+        let counts = counts.entry(variant_name(&debug).to_owned()).or_default();
+        counts.generated += 1;
+        counts.accepted += u64::from(accepted);
+    }
+}
+
+/// The leading identifier (or path) of the `Debug` output of a transition,
+/// or all of it if it doesn't start with one.
+fn variant_name(debug: &str) -> &str {
+    let end = debug
+        .find(|c: char| !(c.is_alphanumeric() || '_' == c || ':' == c))
+        .unwrap_or(debug.len());
+    if 0 == end {
+        debug
+    } else {
+        &debug[..end]
+    }
+}
+
+impl Display for CoverageCounts {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        writeln!(f, "Transition coverage:")?;
+        for (variant, counts) in self.counts.lock().unwrap().iter() {
+            if 0 == counts.generated {
+                writeln!(f, "  {}: never generated", variant)?;
+            } else if 0 == counts.accepted {
+                writeln!(
+                    f,
+                    "  {}: generated {} times, always rejected by \
+                     pre-conditions",
+                    variant, counts.generated
+                )?;
+            } else {
+                writeln!(
+                    f,
+                    "  {}: generated {} times, accepted {} times",
+                    variant, counts.generated, counts.accepted
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for TransitionCoverage {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Debug for TransitionCoverage {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        f.debug_tuple("TransitionCoverage")
+            .field(&self.counts())
+            .finish()
+    }
+}
+
+impl Drop for CoverageCounts {
+    fn drop(&mut self) {
+        #[cfg(feature = "std")]
+        if self.report_on_drop {
+            eprintln!();
+            eprint!("{}", self);
         }
     }
 }
@@ -172,6 +531,7 @@ impl<State, Transition, StateStrategy, TransitionStrategy> Debug
     fn fmt(&self, f: &mut Formatter) -> Result {
         f.debug_struct("Sequential")
             .field("size", &self.size)
+            .field("initial_corpus_len", &self.initial_corpus.len())
             .finish()
     }
 }
@@ -193,8 +553,22 @@ impl<
     type Value = (State, Vec<Transition>, Option<Arc<AtomicUsize>>);
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-        // Generate the initial state value tree
-        let initial_state = (self.init_state)().new_tree(runner)?;
+        // Generate the initial state value tree, or pick a snapshot from the
+        // initial corpus for half of the cases if there is one
+        let initial_state = if !self.initial_corpus.is_empty()
+            && sample_uniform_incl(runner, 0, 1) == 1
+        {
+            let ix =
+                sample_uniform_incl(runner, 0, self.initial_corpus.len() - 1);
+            #[cfg(feature = "std")]
+            if runner.config().verbose >= INFO_LOG {
+                eprintln!();
+                eprintln!("Starting from initial corpus snapshot {}.", ix);
+            }
+            InitialState::Snapshot(ix, self.initial_corpus[ix].clone())
+        } else {
+            InitialState::Generated((self.init_state)().new_tree(runner)?)
+        };
         let last_valid_initial_state = initial_state.current();
 
         let (min_size, end) = self.size.start_end_incl();
@@ -207,16 +581,30 @@ impl<
 
         // Sample the transitions until we reach the `max_size`
         let mut state = initial_state.current();
+        // The weights only change with the state, so they're not recomputed
+        // when a transition is rejected
+        let mut alternatives = (self.transition_weights)(&state);
         while transitions.len() < max_size {
             // Apply the current state to find the current transition
-            let transition_tree =
-                (self.transitions)(&state).new_tree(runner)?;
+            let transition_strategy = match alternatives {
+                Some(ref alternatives) => {
+                    pick_weighted(runner, alternatives)(&state)
+                }
+                None => (self.transitions)(&state),
+            };
+            let transition_tree = transition_strategy.new_tree(runner)?;
             let transition = transition_tree.current();
 
+            let accepted = (self.preconditions)(&state, &transition);
+            if let Some(ref coverage) = self.transition_coverage {
+                coverage.record(&transition, accepted);
+            }
+
             // If the pre-conditions are satisfied, use the transition
-            if (self.preconditions)(&state, &transition) {
+            if accepted {
                 transitions.push(transition_tree);
                 state = (self.next)(state, &transition);
+                alternatives = (self.transition_weights)(&state);
                 acceptable_transitions
                     .push((TransitionState::Accepted, transition));
             } else {
@@ -247,6 +635,43 @@ impl<
     }
 }
 
+/// The value tree of the initial state of a sequential state machine.
+#[derive(Clone, Debug)]
+enum InitialState<State, StateValueTree> {
+    /// A state generated by the `init_state` strategy
+    Generated(StateValueTree),
+    /// The snapshot at the given index of the initial corpus, which cannot be
+    /// shrunk
+    Snapshot(usize, State),
+}
+
+impl<State: Clone + Debug, StateValueTree: ValueTree<Value = State>> ValueTree
+    for InitialState<State, StateValueTree>
+{
+    type Value = State;
+
+    fn current(&self) -> State {
+        match self {
+            InitialState::Generated(tree) => tree.current(),
+            InitialState::Snapshot(_, state) => state.clone(),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        match self {
+            InitialState::Generated(tree) => tree.simplify(),
+            InitialState::Snapshot(..) => false,
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self {
+            InitialState::Generated(tree) => tree.complicate(),
+            InitialState::Snapshot(..) => false,
+        }
+    }
+}
+
 /// A shrinking operation
 #[derive(Clone, Copy, Debug)]
 enum Shrink {
@@ -280,7 +705,7 @@ pub struct SequentialValueTree<
     TransitionValueTree,
 > {
     /// The initial state value tree
-    initial_state: StateValueTree,
+    initial_state: InitialState<State, StateValueTree>,
     /// Can the `initial_state` be shrunk any further?
     is_initial_state_shrinkable: bool,
     /// The last initial state that has been accepted by the pre-conditions.
@@ -322,6 +747,16 @@ impl<
     >
     SequentialValueTree<State, Transition, StateValueTree, TransitionValueTree>
 {
+    /// The index into the initial corpus of the snapshot this case started
+    /// from, or `None` if the initial state was generated by `init_state`.
+    /// See [`ReferenceStateMachine::sequential_strategy_with_initial_corpus`].
+    pub fn initial_snapshot(&self) -> Option<usize> {
+        match self.initial_state {
+            InitialState::Generated(_) => None,
+            InitialState::Snapshot(ix, _) => Some(ix),
+        }
+    }
+
     /// Try to apply the next `self.shrink`. Returns `true` if a shrink has been
     /// applied.
     fn try_simplify(&mut self) -> bool {
@@ -653,6 +1088,359 @@ impl<
     }
 }
 
+/// The transitions of a parallel state machine test: a `prefix` applied
+/// sequentially, followed by the `suffixes` applied concurrently, each from
+/// its own thread.
+#[derive(Clone, Debug)]
+pub struct ParallelPlan<Transition> {
+    /// The transitions applied sequentially from the initial state
+    pub prefix: Vec<Transition>,
+    /// The transitions applied from each thread after the `prefix`
+    pub suffixes: Vec<Vec<Transition>>,
+}
+
+/// In a parallel state machine strategy, we first generate an acceptable
+/// prefix of transitions with a [`Sequential`] strategy. Then, from the state
+/// the prefix leads to, we generate a suffix of transitions for each thread,
+/// such that the pre-conditions hold in every interleaving of the suffixes.
+///
+/// The shrinking strategy is to shrink the prefix as a [`Sequential`] value
+/// tree as long as the suffixes remain acceptable after it, then to delete
+/// the transitions of the suffixes from the back of each one. The transitions
+/// of the suffixes themselves are not shrunk.
+pub struct Parallel<State, Transition, StateStrategy, TransitionStrategy> {
+    prefix: Sequential<State, Transition, StateStrategy, TransitionStrategy>,
+    suffix_size: SizeRange,
+    threads: usize,
+}
+
+impl<State, Transition, StateStrategy, TransitionStrategy>
+    Parallel<State, Transition, StateStrategy, TransitionStrategy>
+{
+    /// Creates a parallel strategy with the `prefix` strategy and a suffix of
+    /// `suffix_size` transitions for each of the `threads`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `threads` is less than 2.
+    pub fn new(
+        prefix: Sequential<
+            State,
+            Transition,
+            StateStrategy,
+            TransitionStrategy,
+        >,
+        suffix_size: SizeRange,
+        threads: usize,
+    ) -> Self {
+        assert!(threads >= 2, "a parallel plan needs at least two threads");
+        Self {
+            prefix,
+            suffix_size,
+            threads,
+        }
+    }
+}
+
+impl<State, Transition, StateStrategy, TransitionStrategy> Debug
+    for Parallel<State, Transition, StateStrategy, TransitionStrategy>
+{
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        f.debug_struct("Parallel")
+            .field("prefix", &self.prefix)
+            .field("suffix_size", &self.suffix_size)
+            .field("threads", &self.threads)
+            .finish()
+    }
+}
+
+impl<
+        State: Clone + Debug,
+        Transition: Clone + Debug,
+        StateStrategy: Strategy<Value = State>,
+        TransitionStrategy: Strategy<Value = Transition>,
+    > Strategy
+    for Parallel<State, Transition, StateStrategy, TransitionStrategy>
+{
+    type Tree = ParallelValueTree<
+        State,
+        Transition,
+        StateStrategy::Tree,
+        TransitionStrategy::Tree,
+    >;
+    type Value = (State, ParallelPlan<Transition>, Option<Arc<AtomicUsize>>);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let prefix = self.prefix.new_tree(runner)?;
+        let (initial_state, prefix_transitions, _) = prefix.current();
+        let state = prefix_transitions
+            .iter()
+            .fold(initial_state, |state, t| (self.prefix.next)(state, t));
+
+        let (min_size, end) = self.suffix_size.start_end_incl();
+        loop {
+            let mut suffixes = Vec::with_capacity(self.threads);
+            for _ in 0..self.threads {
+                // Sample the suffix of each thread from the state after the
+                // prefix, as if it were the only one
+                let size = sample_uniform_incl(runner, min_size, end);
+                let mut suffix = Vec::with_capacity(size);
+                let mut thread_state = state.clone();
+                while suffix.len() < size {
+                    let transition_strategy =
+                        match (self.prefix.transition_weights)(&thread_state) {
+                            Some(ref alternatives) => {
+                                pick_weighted(runner, alternatives)(
+                                    &thread_state,
+                                )
+                            }
+                            None => (self.prefix.transitions)(&thread_state),
+                        };
+                    let transition =
+                        transition_strategy.new_tree(runner)?.current();
+                    if (self.prefix.preconditions)(&thread_state, &transition) {
+                        thread_state =
+                            (self.prefix.next)(thread_state, &transition);
+                        suffix.push(transition);
+                    } else {
+                        runner.reject_local(
+                            "Pre-conditions were not satisfied",
+                        )?;
+                    }
+                }
+                suffixes.push(suffix);
+            }
+
+            if all_interleavings_acceptable(
+                state.clone(),
+                &suffixes,
+                &mut vec![0; self.threads],
+                self.prefix.preconditions,
+                self.prefix.next,
+            ) {
+                return Ok(ParallelValueTree {
+                    prefix,
+                    suffixes,
+                    shrink: ParallelShrink::Prefix,
+                    last_shrink: None,
+                });
+            }
+            runner.reject_local(
+                "Pre-conditions were not satisfied in every interleaving",
+            )?;
+        }
+    }
+}
+
+/// Check that the pre-conditions hold in every interleaving of the remaining
+/// transitions of the `suffixes`, from the `positions` of each one on.
+fn all_interleavings_acceptable<State: Clone, Transition>(
+    state: State,
+    suffixes: &[Vec<Transition>],
+    positions: &mut Vec<usize>,
+    preconditions: fn(&State, &Transition) -> bool,
+    next: fn(State, &Transition) -> State,
+) -> bool {
+    for thread in 0..suffixes.len() {
+        let position = positions[thread];
+        if let Some(transition) = suffixes[thread].get(position) {
+            if !preconditions(&state, transition) {
+                return false;
+            }
+            positions[thread] += 1;
+            let acceptable = all_interleavings_acceptable(
+                next(state.clone(), transition),
+                suffixes,
+                positions,
+                preconditions,
+                next,
+            );
+            positions[thread] -= 1;
+            if !acceptable {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// A shrinking operation of a parallel state machine
+#[derive(Clone, Copy, Debug)]
+enum ParallelShrink {
+    /// Shrink the prefix
+    Prefix,
+    /// Delete the transition at the given index of the suffix of the given
+    /// thread
+    DeleteSuffixTransition(usize, usize),
+    /// Nothing left to shrink
+    Done,
+}
+
+/// The generated value tree for a parallel state machine.
+pub struct ParallelValueTree<
+    State,
+    Transition,
+    StateValueTree,
+    TransitionValueTree,
+> {
+    /// The value tree of the prefix
+    prefix: SequentialValueTree<
+        State,
+        Transition,
+        StateValueTree,
+        TransitionValueTree,
+    >,
+    /// The transitions of each thread after the prefix
+    suffixes: Vec<Vec<Transition>>,
+    /// The next shrink operation to apply
+    shrink: ParallelShrink,
+    /// The last applied shrink operation, if any, along with the deleted
+    /// transition to restore on `complicate`
+    last_shrink: Option<(ParallelShrink, Option<Transition>)>,
+}
+
+impl<
+        State: Clone + Debug,
+        Transition: Clone + Debug,
+        StateValueTree: ValueTree<Value = State>,
+        TransitionValueTree: ValueTree<Value = Transition>,
+    >
+    ParallelValueTree<State, Transition, StateValueTree, TransitionValueTree>
+{
+    /// Check that the suffixes are acceptable in every interleaving after the
+    /// current prefix.
+    fn suffixes_acceptable(&self) -> bool {
+        let (mut state, prefix, _) = self.prefix.current();
+        for transition in &prefix {
+            state = (self.prefix.next)(state, transition);
+        }
+        all_interleavings_acceptable(
+            state,
+            &self.suffixes,
+            &mut vec![0; self.suffixes.len()],
+            self.prefix.preconditions,
+            self.prefix.next,
+        )
+    }
+
+    /// The shrink after deleting the transition at `ix` of the suffix of
+    /// `thread`: the previous transition of the same suffix, or the last one
+    /// of the next non-empty suffix.
+    fn next_suffix_shrink(&self, thread: usize, ix: usize) -> ParallelShrink {
+        if ix > 0 {
+            return ParallelShrink::DeleteSuffixTransition(thread, ix - 1);
+        }
+        self.last_suffix_shrink(thread + 1)
+    }
+
+    /// The shrink deleting the last transition of the first non-empty suffix
+    /// from `thread` on.
+    fn last_suffix_shrink(&self, thread: usize) -> ParallelShrink {
+        (thread..self.suffixes.len())
+            .find(|&thread| !self.suffixes[thread].is_empty())
+            .map_or(ParallelShrink::Done, |thread| {
+                ParallelShrink::DeleteSuffixTransition(
+                    thread,
+                    self.suffixes[thread].len() - 1,
+                )
+            })
+    }
+}
+
+impl<
+        State: Clone + Debug,
+        Transition: Clone + Debug,
+        StateValueTree: ValueTree<Value = State>,
+        TransitionValueTree: ValueTree<Value = Transition>,
+    > ValueTree
+    for ParallelValueTree<
+        State,
+        Transition,
+        StateValueTree,
+        TransitionValueTree,
+    >
+{
+    type Value = (State, ParallelPlan<Transition>, Option<Arc<AtomicUsize>>);
+
+    fn current(&self) -> Self::Value {
+        let (state, prefix, seen_counter) = self.prefix.current();
+        (
+            state,
+            ParallelPlan {
+                prefix,
+                suffixes: self.suffixes.clone(),
+            },
+            seen_counter,
+        )
+    }
+
+    fn simplify(&mut self) -> bool {
+        loop {
+            match self.shrink {
+                ParallelShrink::Prefix => {
+                    if !self.prefix.simplify() {
+                        self.shrink = self.last_suffix_shrink(0);
+                    } else if self.suffixes_acceptable() {
+                        self.last_shrink = Some((ParallelShrink::Prefix, None));
+                        return true;
+                    } else {
+                        // Undo the shrink of the prefix, which invalidated
+                        // the suffixes, and try the next one
+                        let mut undone = false;
+                        while !undone && self.prefix.complicate() {
+                            undone = self.suffixes_acceptable();
+                        }
+                        if !undone {
+                            // The prefix can't be brought back to an
+                            // acceptable one, so stop here
+                            self.shrink = ParallelShrink::Done;
+                        }
+                    }
+                }
+                ParallelShrink::DeleteSuffixTransition(thread, ix) => {
+                    let transition = self.suffixes[thread].remove(ix);
+                    let shrink = self.shrink;
+                    self.shrink = self.next_suffix_shrink(thread, ix);
+                    if self.suffixes_acceptable() {
+                        self.last_shrink = Some((shrink, Some(transition)));
+                        return true;
+                    }
+                    self.suffixes[thread].insert(ix, transition);
+                }
+                ParallelShrink::Done => {
+                    self.last_shrink = None;
+                    return false;
+                }
+            }
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.last_shrink.take() {
+            None => false,
+            Some((ParallelShrink::Prefix, _)) => {
+                while self.prefix.complicate() {
+                    if self.suffixes_acceptable() {
+                        // We may be able to complicate the prefix again
+                        self.last_shrink = Some((ParallelShrink::Prefix, None));
+                        return true;
+                    }
+                }
+                false
+            }
+            Some((
+                ParallelShrink::DeleteSuffixTransition(thread, ix),
+                Some(transition),
+            )) => {
+                // Undo the deletion. Can't complicate any further.
+                self.suffixes[thread].insert(ix, transition);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -838,6 +1626,207 @@ mod test {
         assert_eq!(result.downcast_ref::<&str>(), Some(&s));
     }
 
+    #[test]
+    fn initial_corpus_snapshots_are_picked_and_not_shrunk() {
+        let snapshot = vec![3, 1, 4];
+        let sequential = <HeapStateMachine as ReferenceStateMachine>::sequential_strategy_with_initial_corpus(
+            TRANSITIONS,
+            vec![snapshot.clone()],
+        );
+
+        let mut runner = TestRunner::deterministic();
+        let (mut generated, mut picked) = (0, 0);
+        for _ in 0..64 {
+            let mut value_tree = sequential.new_tree(&mut runner).unwrap();
+            let (initial_state, _, _) = value_tree.current();
+            match value_tree.initial_snapshot() {
+                Some(ix) => {
+                    assert_eq!(0, ix);
+                    assert_eq!(snapshot, initial_state);
+                    picked += 1;
+                }
+                None => {
+                    assert!(initial_state.is_empty());
+                    generated += 1;
+                    continue;
+                }
+            }
+
+            while value_tree.simplify() {
+                let (initial_state, _, _) = value_tree.current();
+                assert_eq!(snapshot, initial_state);
+            }
+        }
+        assert!(generated > 0 && picked > 0);
+    }
+
+    #[test]
+    fn transition_weights_adapt_to_state() {
+        struct BoundedStack;
+
+        impl ReferenceStateMachine for BoundedStack {
+            type State = Vec<bool>;
+            type Transition = Option<bool>;
+
+            fn init_state() -> BoxedStrategy<Self::State> {
+                Just(vec![]).boxed()
+            }
+
+            fn transitions(_: &Self::State) -> BoxedStrategy<Option<bool>> {
+                unreachable!("weights are always given")
+            }
+
+            fn transition_weights(
+                state: &Self::State,
+            ) -> Option<WeightedTransitions<Self::State, Option<bool>>>
+            {
+                // Never pops an empty stack or pushes to a full one.
+                let push = if state.len() < 5 { 1 } else { 0 };
+                let pop = if state.is_empty() { 0 } else { 1 };
+                Some(vec![
+                    (push, |_| any::<bool>().prop_map(Some).boxed()),
+                    (pop, |_| Just(None).boxed()),
+                ])
+            }
+
+            fn apply(
+                mut state: Self::State,
+                transition: &Option<bool>,
+            ) -> Self::State {
+                match *transition {
+                    Some(value) => state.push(value),
+                    None => assert!(state.pop().is_some()),
+                }
+                assert!(state.len() <= 5);
+                state
+            }
+        }
+
+        let sequential = BoundedStack::sequential_strategy(100);
+        let mut runner = TestRunner::deterministic();
+        let mut pops = 0;
+        for _ in 0..16 {
+            let (mut state, transitions, _) =
+                sequential.new_tree(&mut runner).unwrap().current();
+            for transition in &transitions {
+                pops += usize::from(transition.is_none());
+                state = BoundedStack::apply(state, transition);
+            }
+        }
+        assert!(pops > 16 * 25, "{}", pops);
+    }
+
+    #[test]
+    fn transition_coverage_reports_unreachable_variants() {
+        struct NeverNone;
+
+        impl ReferenceStateMachine for NeverNone {
+            type State = ();
+            type Transition = Option<bool>;
+
+            fn init_state() -> BoxedStrategy<Self::State> {
+                Just(()).boxed()
+            }
+
+            fn transitions(_: &Self::State) -> BoxedStrategy<Option<bool>> {
+                any::<Option<bool>>().boxed()
+            }
+
+            fn apply(state: Self::State, _: &Option<bool>) -> Self::State {
+                state
+            }
+
+            fn preconditions(
+                _: &Self::State,
+                transition: &Option<bool>,
+            ) -> bool {
+                transition.is_some()
+            }
+        }
+
+        let coverage = TransitionCoverage::new(&["None", "Some", "Unused"]);
+        let sequential = NeverNone::sequential_strategy(10)
+            .with_transition_coverage(coverage.clone());
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..16 {
+            sequential.new_tree(&mut runner).unwrap();
+        }
+
+        let counts = coverage.counts();
+        assert_eq!(160, counts["Some"].accepted);
+        assert_eq!(counts["Some"].generated, counts["Some"].accepted);
+        assert!(counts["None"].generated > 0);
+        assert_eq!(0, counts["None"].accepted);
+        assert_eq!(TransitionCounts::default(), counts["Unused"]);
+        assert_eq!(vec!["None", "Unused"], coverage.unreachable());
+
+        let report = coverage.to_string();
+        assert!(report.contains("  None: generated "), "{}", report);
+        assert!(report.contains("always rejected"), "{}", report);
+        assert!(report.contains("  Unused: never generated"), "{}", report);
+    }
+
+    #[test]
+    fn parallel_plans_are_acceptable_in_every_interleaving() {
+        struct Counter;
+        impl ReferenceStateMachine for Counter {
+            type State = u32;
+            type Transition = bool;
+
+            fn init_state() -> BoxedStrategy<Self::State> {
+                Just(0).boxed()
+            }
+
+            fn transitions(_: &Self::State) -> BoxedStrategy<bool> {
+                any::<bool>().boxed()
+            }
+
+            fn apply(state: Self::State, increment: &bool) -> Self::State {
+                if *increment {
+                    state + 1
+                } else {
+                    state - 1
+                }
+            }
+
+            fn preconditions(state: &Self::State, increment: &bool) -> bool {
+                *increment || *state > 0
+            }
+        }
+
+        let acceptable = |plan: &ParallelPlan<bool>| {
+            let state = plan.prefix.iter().fold(0, Counter::apply);
+            all_interleavings_acceptable(
+                state,
+                &plan.suffixes,
+                &mut vec![0; plan.suffixes.len()],
+                Counter::preconditions,
+                Counter::apply,
+            )
+        };
+
+        let strategy = Counter::parallel_strategy(1..8, 0..4, 3);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut value_tree = strategy.new_tree(&mut runner).unwrap();
+            let (_, plan, seen_counter) = value_tree.current();
+            assert_eq!(3, plan.suffixes.len());
+            assert!(acceptable(&plan), "{:?}", plan);
+            seen_counter
+                .unwrap()
+                .store(plan.prefix.len(), atomic::Ordering::SeqCst);
+
+            // Shrinking a failure that doesn't depend on the plan deletes
+            // all the transitions of the suffixes
+            while value_tree.simplify() {
+                let (_, plan, _) = value_tree.current();
+                assert!(acceptable(&plan), "{:?}", plan);
+            }
+            let (_, plan, _) = value_tree.current();
+            assert!(plan.suffixes.iter().all(Vec::is_empty), "{:?}", plan);
+        }
+    }
+
     /// The following is a definition of an reference state machine used for the
     /// tests.
     mod heap_state_machine {
@@ -9,11 +9,15 @@
 
 //! Test declaration helpers and runners for abstract state machine testing.
 
+use std::fmt::Debug;
 use std::sync::atomic::{self, AtomicUsize};
 use std::sync::Arc;
 
-use crate::strategy::ReferenceStateMachine;
+use crate::report::Report;
+use crate::strategy::{ParallelPlan, ReferenceStateMachine};
 use proptest::test_runner::Config;
+#[cfg(feature = "std")]
+use proptest::test_runner::INFO_LOG;
 
 /// State machine test that relies on a reference state machine model
 pub trait StateMachineTest {
@@ -70,66 +74,336 @@ pub trait StateMachineTest {
     /// method.
     fn test_sequential(
         config: Config,
-        mut ref_state: <Self::Reference as ReferenceStateMachine>::State,
+        ref_state: <Self::Reference as ReferenceStateMachine>::State,
         transitions: Vec<
             <Self::Reference as ReferenceStateMachine>::Transition,
         >,
-        mut seen_counter: Option<Arc<AtomicUsize>>,
+        seen_counter: Option<Arc<AtomicUsize>>,
     ) {
-        #[cfg(feature = "std")]
-        use proptest::test_runner::INFO_LOG;
+        let (concrete_state, _) = run_sequential::<Self>(
+            config,
+            ref_state,
+            transitions,
+            seen_counter,
+            |_, _| {},
+        );
+        Self::teardown(concrete_state)
+    }
+}
+
+/// State machine test whose SUT state can be compared with its reference
+/// state, by projecting both on a common representation.
+///
+/// Instead of asserting in [`StateMachineTest::check_invariants`] that the
+/// SUT corresponds to the reference state, implement this trait and add
+/// `, check_projection` after the name of the test in
+/// [`prop_state_machine!`]. The observation of the SUT is then checked to be
+/// equal to the projection of the reference state on the initial state and
+/// after every transition, right after the invariants.
+pub trait StateMachineProjection: StateMachineTest {
+    /// The common representation of the SUT and reference states.
+    type Projection: PartialEq + Debug;
+
+    /// Project the reference state on the common representation.
+    fn project(
+        ref_state: &<Self::Reference as ReferenceStateMachine>::State,
+    ) -> Self::Projection;
+
+    /// Observe the SUT state in the common representation.
+    fn observe(state: &Self::SystemUnderTest) -> Self::Projection;
+
+    /// Run the test sequentially like [`StateMachineTest::test_sequential`],
+    /// also checking that the observation of the SUT is equal to the
+    /// projection of the reference state after every transition.
+    fn test_sequential_projected(
+        config: Config,
+        ref_state: <Self::Reference as ReferenceStateMachine>::State,
+        transitions: Vec<
+            <Self::Reference as ReferenceStateMachine>::Transition,
+        >,
+        seen_counter: Option<Arc<AtomicUsize>>,
+    ) {
+        let (concrete_state, _) = run_sequential::<Self>(
+            config,
+            ref_state,
+            transitions,
+            seen_counter,
+            |state, ref_state| {
+                assert_eq!(
+                    Self::observe(state),
+                    Self::project(ref_state),
+                    "The observed SUT state differs from the projection of \
+                     the reference state"
+                );
+            },
+        );
+        Self::teardown(concrete_state)
+    }
+}
+
+/// State machine test whose SUT can also apply transitions concurrently from
+/// several threads, to check that it is linearizable with respect to the
+/// reference state machine.
+///
+/// A concurrent test applies the prefix of a [`ParallelPlan`] sequentially
+/// like [`StateMachineTest::test_sequential`], then each of its suffixes from
+/// its own thread with [`Self::apply_concurrent`], all sharing the SUT. The
+/// test then passes if some interleaving of the suffixes, keeping the order
+/// of the transitions of each thread, satisfies the pre-conditions and the
+/// [`Self::postconditions`] of every transition, as if the transitions had
+/// been applied atomically in that order.
+///
+/// The plans are generated by
+/// [`ReferenceStateMachine::parallel_strategy`]:
+///
+/// ```rust,ignore
+/// proptest! {
+///     #[test]
+///     fn run_concurrently(
+///         (initial_state, plan, seen_counter) in
+///             MyModel::parallel_strategy(1..10, 1..4, 2)
+///     ) {
+///         MyTest::test_concurrent(
+///             Config::default(), initial_state, plan, seen_counter)
+///     }
+/// }
+/// ```
+pub trait ConcurrentStateMachineTest: StateMachineTest
+where
+    Self::SystemUnderTest: Sync,
+    <Self::Reference as ReferenceStateMachine>::Transition: Send,
+{
+    /// The result of a transition applied concurrently, checked by
+    /// [`Self::postconditions`].
+    type Output: Debug + Send;
+
+    /// Apply a transition in the SUT state shared with other threads and
+    /// return its result.
+    fn apply_concurrent(
+        state: &Self::SystemUnderTest,
+        transition: <Self::Reference as ReferenceStateMachine>::Transition,
+    ) -> Self::Output;
+
+    /// Check that `output` is a valid result of applying `transition` in the
+    /// reference state `ref_state`.
+    ///
+    /// Note that unlike in [`StateMachineTest::apply`], the `ref_state` is
+    /// the state *before* the `transition` is applied.
+    fn postconditions(
+        ref_state: &<Self::Reference as ReferenceStateMachine>::State,
+        transition: &<Self::Reference as ReferenceStateMachine>::Transition,
+        output: &Self::Output,
+    ) -> bool;
+
+    /// Run the test concurrently. You typically don't need to override this
+    /// method.
+    fn test_concurrent(
+        config: Config,
+        ref_state: <Self::Reference as ReferenceStateMachine>::State,
+        plan: ParallelPlan<
+            <Self::Reference as ReferenceStateMachine>::Transition,
+        >,
+        seen_counter: Option<Arc<AtomicUsize>>,
+    ) {
+        let ParallelPlan { prefix, suffixes } = plan;
+        let (concrete_state, ref_state) = run_sequential::<Self>(
+            config.clone(),
+            ref_state,
+            prefix,
+            seen_counter,
+            |_, _| {},
+        );
 
-        let trans_len = transitions.len();
         #[cfg(feature = "std")]
         if config.verbose >= INFO_LOG {
             eprintln!();
-            eprintln!("Running a test case with {} transitions.", trans_len);
+            eprintln!(
+                "Applying transitions concurrently from {} threads: {:?}",
+                suffixes.len(),
+                suffixes
+            );
         }
         #[cfg(not(feature = "std"))]
-        let _ = (config, trans_len);
+        let _ = config;
 
-        let mut concrete_state = Self::init_test(&ref_state);
+        let results = std::thread::scope(|scope| {
+            let concrete_state = &concrete_state;
+            let threads = suffixes
+                .into_iter()
+                .map(|suffix| {
+                    scope.spawn(move || {
+                        suffix
+                            .into_iter()
+                            .map(|transition| {
+                                let output = Self::apply_concurrent(
+                                    concrete_state,
+                                    transition.clone(),
+                                );
+                                (transition, output)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            threads
+                .into_iter()
+                .map(|thread| match thread.join() {
+                    Ok(results) => results,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect::<Vec<_>>()
+        });
 
-        // Check the invariants on the initial state
+        let ref_state = match linearize::<Self>(
+            ref_state,
+            &results,
+            &mut vec![0; results.len()],
+        ) {
+            Some(ref_state) => ref_state,
+            None => panic!(
+                "No interleaving of the concurrent transitions satisfies \
+                 the post-conditions of the reference state machine. The \
+                 transitions and outputs of each thread were: {:#?}",
+                results
+            ),
+        };
+
+        // Check the invariants after all the transitions are applied
         Self::check_invariants(&concrete_state, &ref_state);
 
-        for (ix, transition) in transitions.into_iter().enumerate() {
-            // The counter is `Some` only before shrinking. When it's `Some` it
-            // must be incremented before every transition that's being applied
-            // to inform the strategy that the transition has been applied for
-            // the first step of its shrinking process which removes any unseen
-            // transitions.
-            if let Some(seen_counter) = seen_counter.as_mut() {
-                seen_counter.fetch_add(1, atomic::Ordering::SeqCst);
-            }
-
-            #[cfg(feature = "std")]
-            if config.verbose >= INFO_LOG {
-                eprintln!();
-                eprintln!(
-                    "Applying transition {}/{}: {:?}",
-                    ix + 1,
-                    trans_len,
-                    transition
-                );
-            }
-            #[cfg(not(feature = "std"))]
-            let _ = ix;
+        Self::teardown(concrete_state)
+    }
+}
+
+/// The transitions applied from a thread in a concurrent test, along with
+/// their outputs.
+type ThreadResults<T> = Vec<(
+    <<T as StateMachineTest>::Reference as ReferenceStateMachine>::Transition,
+    <T as ConcurrentStateMachineTest>::Output,
+)>;
 
-            // Apply the transition on the states
-            ref_state = <Self::Reference as ReferenceStateMachine>::apply(
-                ref_state,
-                &transition,
+/// Find an interleaving of the transitions applied concurrently, from the
+/// `positions` of each thread on, which satisfies their pre-conditions and
+/// post-conditions, and return the reference state it leads to.
+fn linearize<T: ConcurrentStateMachineTest + ?Sized>(
+    ref_state: <T::Reference as ReferenceStateMachine>::State,
+    results: &[ThreadResults<T>],
+    positions: &mut Vec<usize>,
+) -> Option<<T::Reference as ReferenceStateMachine>::State>
+where
+    T::SystemUnderTest: Sync,
+    <T::Reference as ReferenceStateMachine>::Transition: Send,
+{
+    if (0..results.len())
+        .all(|thread| positions[thread] == results[thread].len())
+    {
+        return Some(ref_state);
+    }
+    for thread in 0..results.len() {
+        let position = positions[thread];
+        if let Some((transition, output)) = results[thread].get(position) {
+            if !<T::Reference as ReferenceStateMachine>::preconditions(
+                &ref_state, transition,
+            ) || !T::postconditions(&ref_state, transition, output)
+            {
+                continue;
+            }
+            positions[thread] += 1;
+            let linearized = linearize::<T>(
+                <T::Reference as ReferenceStateMachine>::apply(
+                    ref_state.clone(),
+                    transition,
+                ),
+                results,
+                positions,
             );
-            concrete_state =
-                Self::apply(concrete_state, &ref_state, transition);
+            positions[thread] -= 1;
+            if linearized.is_some() {
+                return linearized;
+            }
+        }
+    }
+    None
+}
 
-            // Check the invariants after the transition is applied
-            Self::check_invariants(&concrete_state, &ref_state);
+/// Run the transitions sequentially, calling `check` along with
+/// [`StateMachineTest::check_invariants`] on the initial state and after
+/// every transition, and return the resulting SUT and reference states.
+fn run_sequential<T: StateMachineTest + ?Sized>(
+    config: Config,
+    mut ref_state: <T::Reference as ReferenceStateMachine>::State,
+    transitions: Vec<<T::Reference as ReferenceStateMachine>::Transition>,
+    mut seen_counter: Option<Arc<AtomicUsize>>,
+    check: impl Fn(
+        &T::SystemUnderTest,
+        &<T::Reference as ReferenceStateMachine>::State,
+    ),
+) -> (
+    T::SystemUnderTest,
+    <T::Reference as ReferenceStateMachine>::State,
+) {
+    let trans_len = transitions.len();
+    #[cfg(feature = "std")]
+    if config.verbose >= INFO_LOG {
+        eprintln!();
+        eprintln!("Running a test case with {} transitions.", trans_len);
+    }
+    #[cfg(not(feature = "std"))]
+    let _ = (config, trans_len);
+
+    let mut report = Report::new(&config, &ref_state);
+
+    let mut concrete_state = report.step(|| {
+        let concrete_state = T::init_test(&ref_state);
+
+        // Check the invariants on the initial state
+        T::check_invariants(&concrete_state, &ref_state);
+        check(&concrete_state, &ref_state);
+        concrete_state
+    });
+
+    for (ix, transition) in transitions.into_iter().enumerate() {
+        // The counter is `Some` only before shrinking. When it's `Some` it
+        // must be incremented before every transition that's being applied
+        // to inform the strategy that the transition has been applied for
+        // the first step of its shrinking process which removes any unseen
+        // transitions.
+        if let Some(seen_counter) = seen_counter.as_mut() {
+            seen_counter.fetch_add(1, atomic::Ordering::SeqCst);
         }
 
-        Self::teardown(concrete_state)
+        #[cfg(feature = "std")]
+        if config.verbose >= INFO_LOG {
+            eprintln!();
+            eprintln!(
+                "Applying transition {}/{}: {:?}",
+                ix + 1,
+                trans_len,
+                transition
+            );
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = ix;
+
+        // Apply the transition on the states
+        report.transition(&transition);
+        ref_state = <T::Reference as ReferenceStateMachine>::apply(
+            ref_state,
+            &transition,
+        );
+        report.reference_state(&ref_state);
+        concrete_state = report.step(|| {
+            let concrete_state =
+                T::apply(concrete_state, &ref_state, transition);
+
+            // Check the invariants after the transition is applied
+            T::check_invariants(&concrete_state, &ref_state);
+            check(&concrete_state, &ref_state);
+            concrete_state
+        });
     }
+
+    (concrete_state, ref_state)
 }
 
 /// This macro helps to turn a state machine test implementation into a runnable
@@ -169,24 +443,224 @@ pub trait StateMachineTest {
 ///     }
 /// }
 /// ```
+///
+/// ## Inline reference state machines
+///
+/// For simple models, the [`ReferenceStateMachine`] can be defined inline
+/// with a `reference` item before the tests, instead of implementing the
+/// trait by hand. It generates a unit struct with the given name, so that the
+/// [`StateMachineTest`] implementation can refer to it as its `Reference`.
+///
+/// `init` is a strategy for the initial state. `transitions`, `apply` and the
+/// optional `preconditions` are closures in place of the trait functions of
+/// the same name; they must not capture anything.
+///
+/// ```rust,ignore
+/// #[derive(Clone, Debug)]
+/// enum Op {
+///     Push(u8),
+///     Pop,
+/// }
+///
+/// prop_state_machine! {
+///     reference StackModel {
+///         state: Vec<u8>,
+///         transition: Op,
+///         init: Just(vec![]),
+///         transitions: |_state| prop_oneof![
+///             any::<u8>().prop_map(Op::Push),
+///             Just(Op::Pop),
+///         ],
+///         apply: |mut state, op| {
+///             match op {
+///                 Op::Push(v) => state.push(*v),
+///                 Op::Pop => { state.pop(); }
+///             }
+///             state
+///         },
+///         preconditions: |state, op| {
+///             !matches!(op, Op::Pop) || !state.is_empty()
+///         },
+///     }
+///
+///     #[test]
+///     fn run_stack(sequential 1..20 => StackTest);
+/// }
+///
+/// impl StateMachineTest for StackTest {
+///     type SystemUnderTest = MyStack;
+///     type Reference = StackModel;
+///     // ...
+/// }
+/// ```
+///
+/// ## Transition coverage
+///
+/// Adding `, transition_coverage` after the name of a test prints a report
+/// after the test has run of how often each variant of the transitions was
+/// generated and accepted by the pre-conditions. This highlights transitions
+/// which are never generated or which a pre-condition always filters out.
+/// See [`crate::TransitionCoverage`].
+///
+/// ```rust,ignore
+/// prop_state_machine! {
+///     #[test]
+///     fn run_stack(sequential 1..20 => StackTest, transition_coverage);
+/// }
+/// ```
+///
+/// ## Projection checks
+///
+/// Adding `, check_projection` after the name of a test whose type
+/// implements [`StateMachineProjection`] checks after every transition that
+/// the observation of the SUT is equal to the projection of the reference
+/// state. It can be combined with `transition_coverage`.
+///
+/// ```rust,ignore
+/// impl StateMachineProjection for StackTest {
+///     type Projection = Vec<u8>;
+///
+///     fn project(ref_state: &Vec<u8>) -> Vec<u8> {
+///         ref_state.clone()
+///     }
+///
+///     fn observe(state: &MyStack) -> Vec<u8> {
+///         state.to_vec()
+///     }
+/// }
+///
+/// prop_state_machine! {
+///     #[test]
+///     fn run_stack(sequential 1..20 => StackTest, check_projection);
+/// }
+/// ```
 #[macro_export]
 macro_rules! prop_state_machine {
+    // An inline reference state machine, with proptest config annotation
+    (#![proptest_config($config:expr)]
+    $(#[$ref_meta:meta])*
+    $ref_vis:vis reference $($rest:tt)*) => {
+        $crate::prop_state_machine! {
+            @reference [#![proptest_config($config)]]
+            $(#[$ref_meta])* $ref_vis reference $($rest)*
+        }
+    };
+
+    // An inline reference state machine, without proptest config annotation
+    ($(#[$ref_meta:meta])*
+    $ref_vis:vis reference $($rest:tt)*) => {
+        $crate::prop_state_machine! {
+            @reference []
+            $(#[$ref_meta])* $ref_vis reference $($rest)*
+        }
+    };
+
+    (@reference [$($config:tt)*]
+    $(#[$ref_meta:meta])*
+    $ref_vis:vis reference $reference:ident {
+        state: $state:ty,
+        transition: $transition:ty,
+        init: $init:expr,
+        transitions: $transitions:expr,
+        apply: $apply:expr
+        $(, preconditions: $preconditions:expr)?
+        $(,)?
+    }
+    $($rest:tt)*) => {
+        $(#[$ref_meta])*
+        $ref_vis struct $reference;
+
+        impl $crate::ReferenceStateMachine for $reference {
+            type State = $state;
+            type Transition = $transition;
+
+            fn init_state(
+            ) -> ::proptest::strategy::BoxedStrategy<Self::State> {
+                ::proptest::strategy::Strategy::boxed($init)
+            }
+
+            fn transitions(
+                state: &Self::State,
+            ) -> ::proptest::strategy::BoxedStrategy<Self::Transition> {
+                let transitions: fn(&Self::State) -> _ = $transitions;
+                ::proptest::strategy::Strategy::boxed(transitions(state))
+            }
+
+            fn apply(
+                state: Self::State,
+                transition: &Self::Transition,
+            ) -> Self::State {
+                let apply: fn(Self::State, &Self::Transition) -> Self::State =
+                    $apply;
+                apply(state, transition)
+            }
+
+            $(
+                fn preconditions(
+                    state: &Self::State,
+                    transition: &Self::Transition,
+                ) -> bool {
+                    let preconditions: fn(
+                        &Self::State,
+                        &Self::Transition,
+                    ) -> bool = $preconditions;
+                    preconditions(state, transition)
+                }
+            )?
+        }
+
+        $crate::prop_state_machine! {
+            $($config)*
+            $($rest)*
+        }
+    };
+
+    // The strategy of a test, with the transition coverage report if
+    // requested
+    (@strategy $reference:ty, $size:expr) => {
+        <$reference as $crate::ReferenceStateMachine>::sequential_strategy($size)
+    };
+
+    (@strategy $reference:ty, $size:expr, transition_coverage $(, $flag:ident)*) => {
+        $crate::prop_state_machine!(@strategy $reference, $size $(, $flag)*)
+            .with_transition_coverage($crate::TransitionCoverage::reporting(
+                <$reference as $crate::ReferenceStateMachine>::transition_variants(),
+            ))
+    };
+
+    (@strategy $reference:ty, $size:expr, check_projection $(, $flag:ident)*) => {
+        $crate::prop_state_machine!(@strategy $reference, $size $(, $flag)*)
+    };
+
+    // The run of a test, with the projection checks if requested
+    (@run $test:ty; ($($args:tt)*)) => {
+        <$test as $crate::StateMachineTest>::test_sequential($($args)*)
+    };
+
+    (@run $test:ty; ($($args:tt)*), check_projection $(, $flag:ident)*) => {
+        <$test as $crate::StateMachineProjection>::test_sequential_projected($($args)*)
+    };
+
+    (@run $test:ty; ($($args:tt)*), transition_coverage $(, $flag:ident)*) => {
+        $crate::prop_state_machine!(@run $test; ($($args)*) $(, $flag)*)
+    };
+
     // With proptest config annotation
     (#![proptest_config($config:expr)]
     $(
         $(#[$meta:meta])*
-        fn $test_name:ident(sequential $size:expr => $test:ident $(< $( $ty_param:tt ),+ >)?);
+        fn $test_name:ident(sequential $size:expr => $test:ident $(< $( $ty_param:tt ),+ >)? $(, $flag:ident)*);
     )*) => {
         $(
             ::proptest::proptest! {
                 #![proptest_config($config)]
                 $(#[$meta])*
                 fn $test_name(
-                    (initial_state, transitions, seen_counter) in <<$test $(< $( $ty_param ),+ >)? as $crate::StateMachineTest>::Reference as $crate::ReferenceStateMachine>::sequential_strategy($size)
+                    (initial_state, transitions, seen_counter) in $crate::prop_state_machine!(@strategy <$test $(< $( $ty_param ),+ >)? as $crate::StateMachineTest>::Reference, $size $(, $flag)*)
                 ) {
 
                     let config = $config.__sugar_to_owned();
-                    <$test $(::< $( $ty_param ),+ >)? as $crate::StateMachineTest>::test_sequential(config, initial_state, transitions, seen_counter)
+                    $crate::prop_state_machine!(@run $test $(< $( $ty_param ),+ >)?; (config, initial_state, transitions, seen_counter) $(, $flag)*)
                 }
             }
         )*
@@ -195,16 +669,16 @@ macro_rules! prop_state_machine {
     // Without proptest config annotation
     ($(
         $(#[$meta:meta])*
-        fn $test_name:ident(sequential $size:expr => $test:ident $(< $( $ty_param:tt ),+ >)?);
+        fn $test_name:ident(sequential $size:expr => $test:ident $(< $( $ty_param:tt ),+ >)? $(, $flag:ident)*);
     )*) => {
         $(
             ::proptest::proptest! {
                 $(#[$meta])*
                 fn $test_name(
-                    (initial_state, transitions, seen_counter) in <<$test $(< $( $ty_param ),+ >)? as $crate::StateMachineTest>::Reference as $crate::ReferenceStateMachine>::sequential_strategy($size)
+                    (initial_state, transitions, seen_counter) in $crate::prop_state_machine!(@strategy <$test $(< $( $ty_param ),+ >)? as $crate::StateMachineTest>::Reference, $size $(, $flag)*)
                 ) {
-                    <$test $(::< $( $ty_param ),+ >)? as $crate::StateMachineTest>::test_sequential(
-                        ::proptest::test_runner::Config::default(), initial_state, transitions, seen_counter)
+                    $crate::prop_state_machine!(@run $test $(< $( $ty_param ),+ >)?; (
+                        ::proptest::test_runner::Config::default(), initial_state, transitions, seen_counter) $(, $flag)*)
                 }
             }
         )*
@@ -279,5 +753,297 @@ mod tests {
             #[test]
             fn with_config_annotation(sequential 1..2 => Test);
         }
+
+        #[derive(Clone, Debug)]
+        enum Op {
+            Inc,
+            Reset,
+        }
+
+        // Invocation of the `prop_state_machine` macro with an inline
+        // reference state machine
+        prop_state_machine! {
+            #![proptest_config(::proptest::test_runner::Config::with_cases(8))]
+
+            /// Counts up to 3, after which it must be reset.
+            reference Counter {
+                state: u8,
+                transition: Op,
+                init: proptest::strategy::Just(0),
+                transitions: |_| proptest::prop_oneof![
+                    proptest::strategy::Just(Op::Inc),
+                    proptest::strategy::Just(Op::Reset),
+                ],
+                apply: |state, op| match op {
+                    Op::Inc => state + 1,
+                    Op::Reset => 0,
+                },
+                preconditions: |state, op| {
+                    !matches!(op, Op::Inc) || *state < 3
+                },
+            }
+
+            #[test]
+            fn inline_reference(sequential 1..20 => CounterTest);
+        }
+
+        // Invocation with an inline reference state machine without
+        // preconditions and without a `![proptest_config]` annotation
+        prop_state_machine! {
+            reference Unit {
+                state: (),
+                transition: (),
+                init: proptest::strategy::Just(()),
+                transitions: |_| proptest::strategy::Just(()),
+                apply: |state, _| state
+            }
+
+            #[test]
+            fn inline_reference_no_config(sequential 1..2 => UnitTest);
+        }
+
+        // Invocation with the transition coverage report
+        prop_state_machine! {
+            #![proptest_config(::proptest::test_runner::Config::with_cases(8))]
+
+            #[test]
+            fn with_transition_coverage(
+                sequential 1..20 => CounterTest, transition_coverage);
+        }
+
+        prop_state_machine! {
+            #[test]
+            fn with_transition_coverage_no_config(
+                sequential 1..2 => Test, transition_coverage);
+        }
+
+        // Invocation with the projection checks, alone and combined with
+        // the transition coverage report
+        prop_state_machine! {
+            #![proptest_config(::proptest::test_runner::Config::with_cases(8))]
+
+            #[test]
+            fn with_check_projection(
+                sequential 1..20 => CounterTest, check_projection);
+
+            #[test]
+            fn with_check_projection_and_transition_coverage(
+                sequential 1..20 => CounterTest, check_projection,
+                transition_coverage);
+        }
+
+        prop_state_machine! {
+            #[test]
+            fn with_check_projection_no_config(
+                sequential 1..2 => CounterTest, transition_coverage,
+                check_projection);
+        }
+
+        struct CounterTest;
+        impl crate::StateMachineTest for CounterTest {
+            type SystemUnderTest = u8;
+
+            type Reference = Counter;
+
+            fn init_test(ref_state: &u8) -> u8 {
+                *ref_state
+            }
+
+            fn apply(state: u8, ref_state: &u8, op: Op) -> u8 {
+                let state = match op {
+                    Op::Inc => state + 1,
+                    Op::Reset => 0,
+                };
+                assert!(state <= 3);
+                assert_eq!(*ref_state, state);
+                state
+            }
+        }
+
+        impl crate::StateMachineProjection for CounterTest {
+            type Projection = u8;
+
+            fn project(ref_state: &u8) -> u8 {
+                *ref_state
+            }
+
+            fn observe(state: &u8) -> u8 {
+                *state
+            }
+        }
+
+        /// A counter which can't be reset.
+        struct BrokenCounterTest;
+        impl crate::StateMachineTest for BrokenCounterTest {
+            type SystemUnderTest = u8;
+
+            type Reference = Counter;
+
+            fn init_test(ref_state: &u8) -> u8 {
+                *ref_state
+            }
+
+            fn apply(state: u8, _: &u8, op: Op) -> u8 {
+                match op {
+                    Op::Inc => state + 1,
+                    Op::Reset => state,
+                }
+            }
+        }
+
+        impl crate::StateMachineProjection for BrokenCounterTest {
+            type Projection = u8;
+
+            fn project(ref_state: &u8) -> u8 {
+                *ref_state
+            }
+
+            fn observe(state: &u8) -> u8 {
+                *state
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "differs from the projection")]
+        fn check_projection_detects_divergence() {
+            <BrokenCounterTest as crate::StateMachineProjection>::test_sequential_projected(
+                proptest::test_runner::Config::default(),
+                0,
+                std::vec![Op::Inc, Op::Reset],
+                None,
+            );
+        }
+
+        struct UnitTest;
+        impl crate::StateMachineTest for UnitTest {
+            type SystemUnderTest = ();
+
+            type Reference = Unit;
+
+            fn init_test(_: &()) {}
+
+            fn apply(_: (), _: &(), _: ()) {}
+        }
+    }
+
+    mod concurrent_test {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        use proptest::prelude::*;
+        use proptest::test_runner::Config;
+
+        use crate::{
+            ConcurrentStateMachineTest, ParallelPlan, ReferenceStateMachine,
+            StateMachineTest,
+        };
+
+        /// A counter whose transitions increment it, returning the previous
+        /// count.
+        struct Counter;
+        impl ReferenceStateMachine for Counter {
+            type State = u32;
+            type Transition = ();
+
+            fn init_state() -> BoxedStrategy<Self::State> {
+                Just(0).boxed()
+            }
+
+            fn transitions(_: &Self::State) -> BoxedStrategy<()> {
+                Just(()).boxed()
+            }
+
+            fn apply(state: Self::State, _: &()) -> Self::State {
+                state + 1
+            }
+        }
+
+        struct AtomicCounterTest;
+        impl StateMachineTest for AtomicCounterTest {
+            type SystemUnderTest = AtomicU32;
+
+            type Reference = Counter;
+
+            fn init_test(ref_state: &u32) -> AtomicU32 {
+                AtomicU32::new(*ref_state)
+            }
+
+            fn apply(state: AtomicU32, ref_state: &u32, _: ()) -> AtomicU32 {
+                state.fetch_add(1, Ordering::SeqCst);
+                assert_eq!(*ref_state, state.load(Ordering::SeqCst));
+                state
+            }
+
+            fn check_invariants(state: &AtomicU32, ref_state: &u32) {
+                assert_eq!(*ref_state, state.load(Ordering::SeqCst));
+            }
+        }
+
+        impl ConcurrentStateMachineTest for AtomicCounterTest {
+            type Output = u32;
+
+            fn apply_concurrent(state: &AtomicU32, _: ()) -> u32 {
+                state.fetch_add(1, Ordering::SeqCst)
+            }
+
+            fn postconditions(ref_state: &u32, _: &(), output: &u32) -> bool {
+                ref_state == output
+            }
+        }
+
+        proptest! {
+            #![proptest_config(Config::with_cases(32))]
+
+            #[test]
+            fn atomic_counter_is_linearizable(
+                (initial_state, plan, seen_counter) in
+                    Counter::parallel_strategy(1..5, 1..4, 2)
+            ) {
+                AtomicCounterTest::test_concurrent(
+                    Config::default(), initial_state, plan, seen_counter)
+            }
+        }
+
+        /// Like `AtomicCounterTest`, but every increment returns 0.
+        struct BrokenCounterTest;
+        impl StateMachineTest for BrokenCounterTest {
+            type SystemUnderTest = AtomicU32;
+
+            type Reference = Counter;
+
+            fn init_test(ref_state: &u32) -> AtomicU32 {
+                AtomicU32::new(*ref_state)
+            }
+
+            fn apply(state: AtomicU32, _: &u32, _: ()) -> AtomicU32 {
+                state
+            }
+        }
+
+        impl ConcurrentStateMachineTest for BrokenCounterTest {
+            type Output = u32;
+
+            fn apply_concurrent(_: &AtomicU32, _: ()) -> u32 {
+                0
+            }
+
+            fn postconditions(ref_state: &u32, _: &(), output: &u32) -> bool {
+                ref_state == output
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "No interleaving")]
+        fn non_linearizable_results_fail() {
+            BrokenCounterTest::test_concurrent(
+                Config::default(),
+                0,
+                ParallelPlan {
+                    prefix: vec![()],
+                    suffixes: vec![vec![()], vec![()]],
+                },
+                Some(Arc::default()),
+            );
+        }
     }
 }
@@ -0,0 +1,75 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Shrinking `[u32; 32]` in the worst case for plain array strategies,
+//! where the test only fails because of a couple of elements.
+//!
+//! Run with `cargo bench --bench array_shrink`. Besides the time taken, this
+//! prints the number of iterations needed to shrink each case, as that is
+//! what matters when the test itself is expensive.
+
+use std::time::Instant;
+
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::TestRunner;
+
+const CASES: u32 = 10_000;
+
+fn fails(a: &[u32; 32]) -> bool {
+    a[7] >= 300 && a[25] > 100
+}
+
+/// Shrinks a failing case drawn from `strategy` as the test runner would,
+/// returning the number of iterations it took.
+fn shrink<S>(strategy: &S, runner: &mut TestRunner) -> u32
+where
+    S: Strategy<Value = [u32; 32]>,
+{
+    let mut case = loop {
+        let case = strategy.new_tree(runner).unwrap();
+        if fails(&case.current()) {
+            break case;
+        }
+    };
+
+    let mut iterations = 0;
+    loop {
+        iterations += 1;
+        let more = if fails(&case.current()) {
+            case.simplify()
+        } else {
+            case.complicate()
+        };
+        if !more {
+            return iterations;
+        }
+    }
+}
+
+fn bench<S>(name: &str, strategy: &S)
+where
+    S: Strategy<Value = [u32; 32]>,
+{
+    let mut runner = TestRunner::deterministic();
+    let start = Instant::now();
+    let iterations: u32 =
+        (0..CASES).map(|_| shrink(strategy, &mut runner)).sum();
+    println!(
+        "{:<20} {:>6} iterations/case {:>10.2?}/case",
+        name,
+        iterations / CASES,
+        start.elapsed() / CASES
+    );
+}
+
+fn main() {
+    let plain = prop::array::uniform32(0u32..1000);
+    bench("uniform32", &plain);
+    bench("uniform32 batched", &plain.clone().batched());
+}
@@ -0,0 +1,60 @@
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Generating medium-sized collections and strings, which is dominated by
+//! building the values when the test itself is cheap.
+//!
+//! Run with `cargo bench --bench generate_collections`.
+
+use std::time::Instant;
+
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::string::{from_dictionary, utf8_with_byte_len};
+use proptest::test_runner::TestRunner;
+
+const CASES: u32 = 100_000;
+
+fn bench<S: Strategy>(name: &str, strategy: &S) {
+    let mut runner = TestRunner::deterministic();
+    let start = Instant::now();
+    // Keep the values around for a while, so that building them can't be
+    // optimised away.
+    let mut values = Vec::with_capacity(1024);
+    for _ in 0..CASES {
+        if values.len() == values.capacity() {
+            values.clear();
+        }
+        values.push(strategy.new_tree(&mut runner).unwrap().current());
+    }
+    println!(
+        "{:<20} size hint {:<16} {:>10.2?}/case",
+        name,
+        format!("{:?}", strategy.value_size_hint()),
+        start.elapsed() / CASES
+    );
+}
+
+fn main() {
+    bench("vec", &prop::collection::vec(any::<u32>(), 64..256));
+    bench(
+        "vec of vecs",
+        &prop::collection::vec(prop::collection::vec(any::<u8>(), 8..32), 16),
+    );
+    bench(
+        "btree_set",
+        &prop::collection::btree_set(any::<u32>(), 64..256),
+    );
+    bench("utf8_with_byte_len", &utf8_with_byte_len(64..256));
+    bench(
+        "tokens",
+        &from_dictionary(["SELECT", "FROM", "WHERE", "x", "="])
+            .with_count(16..64)
+            .with_separator(" "),
+    );
+}
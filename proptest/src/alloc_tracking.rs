@@ -0,0 +1,257 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Counting the allocations made by the code under test.
+//!
+//! Install `TrackingAllocator` as the global allocator of the test binary,
+//! and `count_allocations()` reports how many allocations the current thread
+//! made while running a closure. `prop_assert_allocates_at_most!` builds on
+//! this to write property tests about allocation behaviour, such as parsing
+//! never allocating more than a fixed number of blocks per input byte. When
+//! such a test fails, shrinking finds a minimal input which allocates too
+//! much.
+//!
+//! ```
+//! use std::alloc::System;
+//!
+//! use proptest::alloc_tracking::TrackingAllocator;
+//! use proptest::prelude::*;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: TrackingAllocator<System> = TrackingAllocator(System);
+//!
+//! fn split_words(s: &str) -> Vec<&str> {
+//!     s.split(' ').collect()
+//! }
+//!
+//! proptest! {
+//!     # /*
+//!     #[test]
+//!     # */
+//!     fn splitting_allocates_once_per_growth(s in "[a-z ]{0,64}") {
+//!         // The vector doubles its capacity as it grows, so there are at
+//!         // most log2(65) + 1 allocations.
+//!         let words = prop_assert_allocates_at_most!(8, {
+//!             split_words(&s)
+//!         });
+//!         prop_assert_eq!(s.matches(' ').count() + 1, words.len());
+//!     }
+//! }
+//! # fn main() { splitting_allocates_once_per_growth(); }
+//! ```
+
+use core::fmt;
+use std::alloc::{GlobalAlloc, Layout};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The allocations counted by `count_allocations()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Allocations {
+    /// The number of allocations, including reallocations.
+    pub count: usize,
+    /// The total number of bytes requested by those allocations.
+    ///
+    /// This counts the new size of each reallocation, not just the growth,
+    /// and isn't reduced by deallocations.
+    pub bytes: usize,
+}
+
+impl fmt::Display for Allocations {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} allocations of {} bytes", self.count, self.bytes)
+    }
+}
+
+/// Whether any allocation went through a `TrackingAllocator`, i.e. whether
+/// one is installed as the global allocator.
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// The allocations counted on this thread, if it is counting.
+    static COUNTING: Cell<Option<Allocations>> = const { Cell::new(None) };
+}
+
+fn record(size: usize) {
+    INSTALLED.store(true, Ordering::Relaxed);
+    // The thread-local may already be gone while the thread exits.
+    let _ = COUNTING.try_with(|counting| {
+        if let Some(mut allocations) = counting.get() {
+            allocations.count += 1;
+            allocations.bytes = allocations.bytes.saturating_add(size);
+            counting.set(Some(allocations));
+        }
+    });
+}
+
+/// Restores the counts of an enclosing `count_allocations()`, even if the
+/// closure panics.
+struct Restore(Option<Allocations>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        COUNTING.with(|counting| {
+            let inner = counting.get().unwrap_or_default();
+            counting.set(self.0.map(|outer| Allocations {
+                count: outer.count + inner.count,
+                bytes: outer.bytes.saturating_add(inner.bytes),
+            }));
+        });
+    }
+}
+
+/// Runs `f` and returns its result along with the allocations the current
+/// thread made meanwhile.
+///
+/// Allocations of other threads, such as ones spawned by `f`, aren't
+/// counted. Calls may be nested, in which case the allocations of the inner
+/// call are also counted by the outer one.
+///
+/// ## Panics
+///
+/// Panics if no `TrackingAllocator` is installed as the global allocator,
+/// since nothing could be counted then.
+pub fn count_allocations<R>(f: impl FnOnce() -> R) -> (R, Allocations) {
+    let outer = COUNTING
+        .with(|counting| counting.replace(Some(Allocations::default())));
+    let restore = Restore(outer);
+    let result = f();
+    let allocations = COUNTING.with(Cell::get).unwrap_or_default();
+    drop(restore);
+
+    assert!(
+        INSTALLED.load(Ordering::Relaxed),
+        "proptest: counting allocations requires a `TrackingAllocator` as \
+         the global allocator"
+    );
+    (result, allocations)
+}
+
+/// A global allocator which lets `count_allocations()` count the allocations
+/// it makes, and forwards everything to the wrapped allocator.
+///
+/// Counting only costs a thread-local lookup per allocation, and nothing is
+/// counted outside of `count_allocations()`.
+///
+/// ```rust,ignore
+/// use std::alloc::System;
+/// use proptest::alloc_tracking::TrackingAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator<System> = TrackingAllocator(System);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TrackingAllocator<A>(pub A);
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        self.0.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        self.0.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        record(new_size);
+        self.0.realloc(ptr, layout, new_size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::alloc::System;
+    use std::string::ToString;
+
+    use super::*;
+
+    /// Allocates and frees `size` bytes through a `TrackingAllocator`, as if
+    /// it were installed.
+    fn allocate(size: usize) {
+        let allocator = TrackingAllocator(System);
+        let layout = Layout::from_size_align(size, 1).unwrap();
+        let grown = Layout::from_size_align(size * 2, 1).unwrap();
+        unsafe {
+            let ptr = allocator.alloc(layout);
+            let ptr = allocator.realloc(ptr, layout, grown.size());
+            allocator.dealloc(ptr, grown);
+        }
+    }
+
+    #[test]
+    fn counts_allocations_of_nested_calls() {
+        let ((), outer) = count_allocations(|| {
+            allocate(8);
+            let ((), inner) = count_allocations(|| allocate(16));
+            assert_eq!((2, 48), (inner.count, inner.bytes));
+        });
+        assert_eq!((4, 72), (outer.count, outer.bytes));
+        assert_eq!("4 allocations of 72 bytes", outer.to_string());
+    }
+
+    #[test]
+    fn counts_nothing_outside_of_calls() {
+        allocate(8);
+        let ((), allocations) = count_allocations(|| ());
+        assert_eq!(Allocations::default(), allocations);
+        assert!(COUNTING.with(Cell::get).is_none());
+    }
+
+    #[test]
+    fn restores_outer_counts_on_panic() {
+        let ((), outer) = count_allocations(|| {
+            let panicked = std::panic::catch_unwind(|| {
+                count_allocations(|| {
+                    allocate(4);
+                    panic!("boom");
+                })
+            });
+            assert!(panicked.is_err());
+        });
+        assert_eq!(2, outer.count);
+    }
+
+    #[test]
+    fn assertion_fails_on_too_many_allocations() {
+        use crate::test_runner::{TestCaseError, TestCaseResult};
+
+        let check = |max: usize| -> TestCaseResult {
+            let size = prop_assert_allocates_at_most!(max, {
+                allocate(8);
+                8
+            });
+            prop_assert_eq!(8, size);
+            Ok(())
+        };
+
+        assert!(check(2).is_ok());
+        match check(1) {
+            Err(TestCaseError::Fail(why)) => assert!(
+                why.message().starts_with(
+                    "assertion failed: `(allocations <= max)`\n  \
+                     allocations: 2 allocations of 24 bytes,\n  max: 1"
+                ),
+                "{}",
+                why.message()
+            ),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+}
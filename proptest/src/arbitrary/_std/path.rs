@@ -67,7 +67,7 @@ impl Arbitrary for PathParamsOutput {
 ///
 /// * Paths that are not valid UTF-8 (this is unlikely to change)
 /// * Paths with a [`PrefixComponent`](std::path::PrefixComponent) on Windows, e.g. `C:\` (this may
-///   change in the future)
+///   change in the future; see [`windows_path`](crate::path::windows_path) for generating those)
 impl Arbitrary for PathBuf {
     type Parameters = PathParams;
     type Strategy = SMapped<PathParamsOutput, Self>;
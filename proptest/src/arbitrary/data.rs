@@ -0,0 +1,21 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::arbitrary::Arbitrary;
+use crate::strategy::{data, Data, DataStrategy};
+
+impl Arbitrary for Data {
+    type Parameters = ();
+
+    type Strategy = DataStrategy;
+
+    fn arbitrary_with(_: ()) -> DataStrategy {
+        data()
+    }
+}
@@ -30,8 +30,10 @@ pub mod functor;
 mod macros;
 
 mod arrays;
+mod data;
 mod primitives;
 mod sample;
+mod small_values;
 mod tuples;
 
 mod _core;
@@ -42,6 +44,7 @@ mod _alloc;
 #[cfg(feature = "std")]
 mod _std;
 
+pub use self::small_values::SmallValues;
 pub use self::traits::*;
 
 //==============================================================================
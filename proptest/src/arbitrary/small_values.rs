@@ -0,0 +1,111 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Defines the `SmallValues` trait and its implementations for primitive
+//! types and common containers.
+
+use crate::std_facade::{vec, String, ToOwned, Vec};
+
+/// A type with a small, fixed set of edge values, such as `0`, `1` and `MAX`
+/// for integers or the empty string, which are worth testing
+/// deterministically.
+///
+/// This is what `#[property_test(also_exhaustive_small)]` enumerates for each
+/// parameter of a test. Implement it for your own types to have them take
+/// part in that grid; parameters of types which don't implement it only take
+/// their simplest value.
+pub trait SmallValues: Sized {
+    /// Returns the edge values of this type, simplest first.
+    ///
+    /// The values should be ones `any::<Self>()` can generate, and there
+    /// should only be a handful of them, since a test enumerates every
+    /// combination of the values of its parameters.
+    fn small_values() -> Vec<Self>;
+}
+
+impl SmallValues for () {
+    fn small_values() -> Vec<Self> {
+        vec![()]
+    }
+}
+
+impl SmallValues for bool {
+    fn small_values() -> Vec<Self> {
+        vec![false, true]
+    }
+}
+
+impl SmallValues for char {
+    fn small_values() -> Vec<Self> {
+        vec!['\0', 'a', char::MAX]
+    }
+}
+
+macro_rules! unsigned_small_values {
+    ($($typ:ty),*) => {$(
+        impl SmallValues for $typ {
+            fn small_values() -> Vec<Self> {
+                vec![0, 1, <$typ>::MAX]
+            }
+        }
+    )*};
+}
+
+macro_rules! signed_small_values {
+    ($($typ:ty),*) => {$(
+        impl SmallValues for $typ {
+            fn small_values() -> Vec<Self> {
+                vec![0, 1, -1, <$typ>::MIN, <$typ>::MAX]
+            }
+        }
+    )*};
+}
+
+macro_rules! float_small_values {
+    ($($typ:ty),*) => {$(
+        impl SmallValues for $typ {
+            fn small_values() -> Vec<Self> {
+                vec![
+                    0.0,
+                    -0.0,
+                    1.0,
+                    -1.0,
+                    <$typ>::MIN_POSITIVE,
+                    <$typ>::MIN,
+                    <$typ>::MAX,
+                ]
+            }
+        }
+    )*};
+}
+
+unsigned_small_values!(u8, u16, u32, u64, u128, usize);
+signed_small_values!(i8, i16, i32, i64, i128, isize);
+float_small_values!(f32, f64);
+
+impl SmallValues for String {
+    fn small_values() -> Vec<Self> {
+        vec![String::new(), "a".to_owned(), "\u{e9}".to_owned()]
+    }
+}
+
+impl<T: SmallValues> SmallValues for Option<T> {
+    fn small_values() -> Vec<Self> {
+        let mut values = vec![None];
+        values.extend(T::small_values().into_iter().map(Some));
+        values
+    }
+}
+
+/// The empty `Vec` and one holding all small values of `T`.
+impl<T: SmallValues> SmallValues for Vec<T> {
+    fn small_values() -> Vec<Self> {
+        vec![Vec::new(), T::small_values()]
+    }
+}
@@ -14,7 +14,9 @@
 //! corresponding input strategies.
 //!
 //! See also [`UniformArrayStrategy`](struct.UniformArrayStrategy.html) for
-//! easily making a strategy for an array drawn from one strategy.
+//! easily making a strategy for an array drawn from one strategy, and
+//! [`BatchedArrayStrategy`](struct.BatchedArrayStrategy.html) for shrinking
+//! many elements of an array at once.
 //!
 //! General implementations are available for sizes 1 through 32.
 
@@ -70,6 +72,13 @@ impl<S, T> UniformArrayStrategy<S, T> {
             _marker: PhantomData,
         }
     }
+
+    /// Shrink the generated arrays by simplifying many of their elements at
+    /// once. See
+    /// [`BatchedArrayStrategy`](struct.BatchedArrayStrategy.html).
+    pub fn batched(self) -> BatchedArrayStrategy<Self> {
+        BatchedArrayStrategy(self)
+    }
 }
 
 /// A `ValueTree` operating over a fixed-size array.
@@ -78,6 +87,79 @@ pub struct ArrayValueTree<T> {
     tree: T,
     shrinker: usize,
     last_shrinker: Option<usize>,
+    /// Bit `i` is set once element `i` can't be simplified any further, so
+    /// that it is skipped when shrinking revisits it. Only the first 64
+    /// elements are tracked; later ones are merely asked again.
+    exhausted: u64,
+}
+
+impl<T> ArrayValueTree<T> {
+    fn new(tree: T) -> Self {
+        ArrayValueTree {
+            tree,
+            shrinker: 0,
+            last_shrinker: None,
+            exhausted: 0,
+        }
+    }
+
+    fn is_exhausted(&self, element: usize) -> bool {
+        element < 64 && 0 != self.exhausted & (1 << element)
+    }
+
+    fn set_exhausted(&mut self, element: usize) {
+        if element < 64 {
+            self.exhausted |= 1 << element;
+        }
+    }
+}
+
+/// A `Strategy` for fixed-size arrays which shrinks many elements at once.
+///
+/// Plain array strategies shrink one element at a time, each all the way
+/// down before moving on to the next one, which takes many iterations for
+/// large arrays of which the test only cares about a few elements. This
+/// instead simplifies a whole batch of elements in each step, starting with
+/// all of them. When a step makes the test pass, the batch is undone and
+/// split in half, down to single elements, and once the elements of a batch
+/// can't be simplified any further the next batch again spans all the
+/// remaining elements. Elements which are already minimal are skipped.
+///
+/// This needs to clone the value trees of the elements, and is normally
+/// created with [`UniformArrayStrategy::batched`].
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn test_something(a in prop::array::uniform32(0u32..1000).batched()) {
+///     prop_assert!(a.iter().filter(|&&x| x > 500).count() < 32);
+///   }
+/// }
+/// # fn main() { test_something(); }
+/// ```
+///
+/// [`UniformArrayStrategy::batched`]:
+/// struct.UniformArrayStrategy.html#method.batched
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Copy, Debug)]
+pub struct BatchedArrayStrategy<A>(A);
+
+/// `ValueTree` corresponding to `BatchedArrayStrategy`.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchedArrayValueTree<T> {
+    /// Its `shrinker` is the first element of the current batch, and its
+    /// `last_shrinker` the element simplified by the last step if that was
+    /// of a single element.
+    inner: ArrayValueTree<T>,
+    batch_len: usize,
+    /// The elements before the last step, if that was of several elements.
+    prev: Option<T>,
 }
 
 /// Create a strategy to generate fixed-length arrays.
@@ -122,11 +204,9 @@ impl<S: Strategy, const N: usize> Strategy for [S; N] {
     type Value = [S::Value; N];
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-        Ok(ArrayValueTree {
-            tree: unarray::build_array_result(|i| self[i].new_tree(runner))?,
-            shrinker: 0,
-            last_shrinker: None,
-        })
+        Ok(ArrayValueTree::new(unarray::build_array_result(|i| {
+            self[i].new_tree(runner)
+        })?))
     }
 }
 impl<S: Strategy, const N: usize> Strategy
@@ -136,12 +216,24 @@ impl<S: Strategy, const N: usize> Strategy
     type Value = [S::Value; N];
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-        Ok(ArrayValueTree {
-            tree: unarray::build_array_result(|_| {
-                self.strategy.new_tree(runner)
-            })?,
-            shrinker: 0,
-            last_shrinker: None,
+        Ok(ArrayValueTree::new(unarray::build_array_result(|_| {
+            self.strategy.new_tree(runner)
+        })?))
+    }
+}
+impl<A, T, const N: usize> Strategy for BatchedArrayStrategy<A>
+where
+    A: Strategy<Tree = ArrayValueTree<[T; N]>>,
+    T: ValueTree + Clone,
+{
+    type Tree = BatchedArrayValueTree<[T; N]>;
+    type Value = [T::Value; N];
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(BatchedArrayValueTree {
+            inner: self.0.new_tree(runner)?,
+            batch_len: N,
+            prev: None,
         })
     }
 }
@@ -154,10 +246,13 @@ impl<T: ValueTree, const N: usize> ValueTree for ArrayValueTree<[T; N]> {
 
     fn simplify(&mut self) -> bool {
         while self.shrinker < N {
-            if self.tree[self.shrinker].simplify() {
+            if !self.is_exhausted(self.shrinker)
+                && self.tree[self.shrinker].simplify()
+            {
                 self.last_shrinker = Some(self.shrinker);
                 return true;
             } else {
+                self.set_exhausted(self.shrinker);
                 self.shrinker += 1;
             }
         }
@@ -179,6 +274,77 @@ impl<T: ValueTree, const N: usize> ValueTree for ArrayValueTree<[T; N]> {
     }
 }
 
+impl<T: ValueTree + Clone, const N: usize> ValueTree
+    for BatchedArrayValueTree<[T; N]>
+{
+    type Value = [T::Value; N];
+
+    fn current(&self) -> [T::Value; N] {
+        self.inner.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.prev = None;
+        self.inner.last_shrinker = None;
+
+        while self.inner.shrinker < N {
+            let start = self.inner.shrinker;
+            let end = N.min(start + self.batch_len);
+            let prev = (end - start > 1).then(|| self.inner.tree.clone());
+
+            let mut simplified = false;
+            for i in start..end {
+                if self.inner.is_exhausted(i) {
+                    continue;
+                }
+                if self.inner.tree[i].simplify() {
+                    simplified = true;
+                } else {
+                    self.inner.set_exhausted(i);
+                }
+            }
+
+            if simplified {
+                match prev {
+                    Some(prev) => self.prev = Some(prev),
+                    None => self.inner.last_shrinker = Some(start),
+                }
+                return true;
+            }
+
+            // The whole batch is minimal, so move on to all the rest.
+            self.inner.shrinker = end;
+            self.batch_len = N;
+        }
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        if let Some(prev) = self.prev.take() {
+            // There is no telling which elements of the batch made the test
+            // pass, so undo it and simplify the first half of it instead,
+            // which is still simpler than the value before the batch.
+            self.inner.tree = prev;
+            let len = N.min(self.inner.shrinker + self.batch_len)
+                - self.inner.shrinker;
+            self.batch_len = len / 2;
+            self.simplify();
+            return true;
+        }
+
+        if let Some(shrinker) = self.inner.last_shrinker {
+            if self.inner.tree[shrinker].complicate() {
+                true
+            } else {
+                self.inner.last_shrinker = None;
+                false
+            }
+        } else {
+            false
+        }
+    }
+}
+
 small_array!(1 uniform1);
 small_array!(2 uniform2);
 small_array!(3 uniform3);
@@ -261,4 +427,93 @@ mod test {
     fn test_sanity() {
         check_strategy_sanity([(0i32..1000), (1i32..1000)], None);
     }
+
+    /// Shrink `case` as the test runner would, returning the number of
+    /// iterations and the final value.
+    fn shrink<T: ValueTree>(
+        mut case: T,
+        pass: impl Fn(&T::Value) -> bool,
+    ) -> (u32, T::Value) {
+        let mut iterations = 0;
+        loop {
+            iterations += 1;
+            let more = if pass(&case.current()) {
+                case.complicate()
+            } else {
+                case.simplify()
+            };
+            if !more {
+                return (iterations, case.current());
+            }
+        }
+    }
+
+    #[test]
+    fn batched_shrinks_fully_in_fewer_iterations() {
+        fn pass(a: &[u32; 32]) -> bool {
+            a[7] < 300 || a[25] <= 100
+        }
+
+        let mut runner = TestRunner::deterministic();
+        let (mut plain_iterations, mut batched_iterations) = (0, 0);
+        let mut cases_tested = 0;
+        for _ in 0..256 {
+            let plain = uniform32(0u32..1000).new_tree(&mut runner).unwrap();
+            let batched = BatchedArrayValueTree {
+                inner: plain,
+                batch_len: 32,
+                prev: None,
+            };
+            if pass(&plain.current()) {
+                continue;
+            }
+
+            let (iterations, plain) = shrink(plain, pass);
+            plain_iterations += iterations;
+            let (iterations, batched) = shrink(batched, pass);
+            batched_iterations += iterations;
+            // Both end up with the same minimal value.
+            let mut minimal = [0; 32];
+            minimal[7] = 300;
+            minimal[25] = 101;
+            assert_eq!(minimal, plain);
+            assert_eq!(minimal, batched);
+
+            cases_tested += 1;
+        }
+
+        assert!(cases_tested > 32, "Didn't find enough test cases");
+        assert!(
+            batched_iterations * 2 < plain_iterations,
+            "{} batched vs {} plain iterations",
+            batched_iterations,
+            plain_iterations
+        );
+    }
+
+    #[test]
+    fn batched_shrinks_fully_ltr() {
+        fn pass(a: &[i32; 2]) -> bool {
+            a[0] * a[1] <= 9
+        }
+
+        let mut runner = TestRunner::deterministic();
+        let input = BatchedArrayStrategy([0..32, 0..32]);
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            if pass(&case.current()) {
+                continue;
+            }
+
+            let (_, last) = shrink(case, pass);
+            assert!(!pass(&last));
+            assert!(pass(&[last[0] - 1, last[1]]));
+            assert!(pass(&[last[0], last[1] - 1]));
+        }
+    }
+
+    #[test]
+    fn test_batched_sanity() {
+        check_strategy_sanity(uniform8(0i32..1000).batched(), None);
+    }
 }
@@ -437,6 +437,64 @@ minimal_api!(isize, isize);
 minimal_api!(bitset, BitSet);
 minimal_api!(bool_vec, Vec<bool>);
 
+/// `Strategy` returned by `flags()`.
+pub type FlagsStrategy<F> = Map<BitSetStrategy<Vec<bool>>, fn(Vec<bool>) -> F>;
+
+/// Generates values of a type defined with the `bitflags!` macro (version 2)
+/// of the `bitflags` crate.
+///
+/// Each value is a combination of the flags named in the definition of `F`,
+/// each of which is included with equal probability, so values never have
+/// undefined bits set. Shrinking removes flags one at a time, towards
+/// `F::empty()`.
+///
+/// This is also what `#[derive(Arbitrary)]` uses for types defined with
+/// `bitflags!`, so that other types containing them can derive `Arbitrary`
+/// too.
+///
+/// ## Example
+///
+/// ```
+/// use bitflags::bitflags;
+/// use proptest::prelude::*;
+///
+/// bitflags! {
+///     #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+///     struct Permissions: u8 {
+///         const READ = 0b001;
+///         const WRITE = 0b010;
+///         const EXECUTE = 0b100;
+///     }
+/// }
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn never_has_undefined_bits(p in prop::bits::flags::<Permissions>()) {
+///         prop_assert_eq!(Permissions::empty(), p - Permissions::all());
+///     }
+/// }
+/// # fn main() { never_has_undefined_bits(); }
+/// ```
+pub fn flags<F: bitflags::Flags + fmt::Debug>() -> FlagsStrategy<F> {
+    let mask = F::FLAGS
+        .iter()
+        .map(|flag| !flag.name().is_empty() && !flag.value().is_empty())
+        .collect();
+    BitSetStrategy::masked(mask).prop_map(flags_from_set)
+}
+
+fn flags_from_set<F: bitflags::Flags>(set: Vec<bool>) -> F {
+    F::FLAGS
+        .iter()
+        .zip(set)
+        .filter(|&(_, included)| included)
+        .fold(F::empty(), |flags, (flag, _)| {
+            flags.union(F::from_bits_retain(flag.value().bits()))
+        })
+}
+
 pub(crate) mod varsize {
     use super::*;
     use core::iter::FromIterator;
@@ -685,8 +743,49 @@ mod test {
         }
     }
 
+    bitflags! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        struct TestFlags: u8 {
+            const A = 0b0000_0001;
+            const B = 0b0000_0100;
+            const C = 0b0001_0000;
+            const AB = Self::A.bits() | Self::B.bits();
+            const _ = 0b1000_0000;
+        }
+    }
+
+    #[test]
+    fn flags_only_sets_named_flags() {
+        let mut seen = TestFlags::empty();
+
+        let mut runner = TestRunner::deterministic();
+        let input = flags::<TestFlags>();
+        for _ in 0..256 {
+            let value = input.new_tree(&mut runner).unwrap().current();
+            assert!(TestFlags::all().contains(value), "Generated {:?}", value);
+            assert!(!value.contains(TestFlags::from_bits_retain(0b1000_0000)));
+            seen |= value;
+        }
+
+        assert_eq!(TestFlags::A | TestFlags::B | TestFlags::C, seen);
+    }
+
+    #[test]
+    fn flags_shrink_to_empty() {
+        let input = flags::<TestFlags>();
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut value = input.new_tree(&mut runner).unwrap();
+            while value.simplify() {}
+
+            assert_eq!(TestFlags::empty(), value.current());
+        }
+    }
+
     #[test]
     fn test_sanity() {
         check_strategy_sanity(u32::masked(0xdeadbeef), None);
+        check_strategy_sanity(flags::<TestFlags>(), None);
     }
 }
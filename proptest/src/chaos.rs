@@ -0,0 +1,399 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Opt-in "chaos mode", which perturbs the environment of test cases to
+//! shake out code which only works under friendly conditions.
+//!
+//! When `Config::chaos` is set, every test case gets its own chaos RNG,
+//! derived from the case's seed. Each call to `point()` while the case runs
+//! may then yield the thread, sleep for a little while or panic, and each
+//! call to `should_fail_alloc()` may report that an allocation is to fail.
+//! The `TestRunner` calls `point()` once right before each test body.
+//!
+//! Since the RNG only depends on the case, a case sees the same injections
+//! every time it is run, including while shrinking and when replaying a
+//! persisted failure, as long as the test reaches the same chaos points in
+//! the same order. The injections of a failing case are appended to its
+//! failure message.
+//!
+//! Outside of test cases run with chaos mode, `point()` does nothing and
+//! `should_fail_alloc()` returns `false`, so chaos points can stay in
+//! production code at the cost of a thread-local lookup.
+//!
+//! ```
+//! use proptest::chaos::{self, Chaos};
+//! use proptest::prelude::*;
+//! use proptest::test_runner::{Config, TestError, TestRunner};
+//!
+//! let mut runner = TestRunner::new(Config {
+//!     failure_persistence: None,
+//!     chaos: Some(Chaos {
+//!         panic_probability: 0.5,
+//!         ..Chaos::default()
+//!     }),
+//!     ..Config::default()
+//! });
+//! let result = runner.run(&(0..10u32), |_| {
+//!     chaos::point();
+//!     Ok(())
+//! });
+//!
+//! match result {
+//!     Err(TestError::Fail(reason, _)) => {
+//!         assert!(reason.message().contains("chaos: injected panic"));
+//!     }
+//!     r => panic!("unexpected result: {:?}", r),
+//! }
+//! ```
+
+use core::fmt;
+use core::time::Duration;
+use std::alloc::{GlobalAlloc, Layout};
+use std::cell::RefCell;
+use std::thread;
+use std::vec::Vec;
+
+use rand::Rng;
+
+use crate::test_runner::TestRng;
+
+/// Configures which perturbations chaos mode injects, and how often.
+///
+/// Probabilities are per chaos point, or per checked allocation. At most one
+/// perturbation is injected at a chaos point, with panics taking precedence
+/// over sleeps and sleeps over yields.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chaos {
+    /// The probability of yielding the thread at a chaos point.
+    ///
+    /// The default is `0.2`.
+    pub yield_probability: f64,
+    /// The probability of sleeping at a chaos point, for a duration chosen
+    /// uniformly up to `max_sleep`.
+    ///
+    /// The default is `0.05`.
+    pub sleep_probability: f64,
+    /// The longest sleep injected at a chaos point.
+    ///
+    /// The default is one millisecond.
+    pub max_sleep: Duration,
+    /// The probability of `should_fail_alloc()` returning `true`.
+    ///
+    /// The default is `0.0`.
+    pub alloc_failure_probability: f64,
+    /// The probability of panicking at a chaos point.
+    ///
+    /// The default is `0.0`.
+    pub panic_probability: f64,
+    // Needs to be public so FRU syntax can be used.
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Default for Chaos {
+    fn default() -> Self {
+        Chaos {
+            yield_probability: 0.2,
+            sleep_probability: 0.05,
+            max_sleep: Duration::from_millis(1),
+            alloc_failure_probability: 0.0,
+            panic_probability: 0.0,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+/// A perturbation injected by chaos mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Injection {
+    /// The thread yielded at a chaos point.
+    Yield,
+    /// The thread slept at a chaos point.
+    Sleep(Duration),
+    /// `should_fail_alloc()` was told to fail an allocation of this many
+    /// bytes.
+    AllocFailure(usize),
+    /// A chaos point panicked.
+    Panic,
+}
+
+impl fmt::Display for Injection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Injection::Yield => write!(f, "yield"),
+            Injection::Sleep(duration) => write!(f, "sleep {:?}", duration),
+            Injection::AllocFailure(size) => {
+                write!(f, "fail allocation of {} bytes", size)
+            }
+            Injection::Panic => write!(f, "panic"),
+        }
+    }
+}
+
+/// The injections made during one test case, each along with the index of
+/// the chaos point or checked allocation it was made at.
+///
+/// The `Display` form is what gets appended to failure messages.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Injections(Vec<(usize, Injection)>);
+
+impl Injections {
+    /// Return whether nothing was injected.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterate over the injections in the order they were made.
+    pub fn iter(&self) -> impl Iterator<Item = &(usize, Injection)> {
+        self.0.iter()
+    }
+}
+
+impl fmt::Display for Injections {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "chaos injections:")?;
+        for &(index, injection) in self.iter() {
+            write!(f, "\n  #{}: {}", index, injection)?;
+        }
+        Ok(())
+    }
+}
+
+/// The chaos state of the test case running on a thread.
+struct CaseChaos {
+    chaos: Chaos,
+    rng: TestRng,
+    /// The number of chaos points and checked allocations so far.
+    count: usize,
+    injections: Injections,
+}
+
+impl CaseChaos {
+    fn next(&mut self) -> (usize, f64) {
+        let index = self.count;
+        self.count += 1;
+        (index, self.rng.gen())
+    }
+}
+
+thread_local! {
+    static CASE: RefCell<Option<CaseChaos>> = const { RefCell::new(None) };
+}
+
+/// Start injecting perturbations according to `chaos` on this thread,
+/// driven by `rng`.
+pub(crate) fn begin(chaos: Chaos, rng: TestRng) {
+    let case = CaseChaos {
+        chaos,
+        rng,
+        count: 0,
+        // Reserve up front so that recording a failed allocation rarely needs
+        // to allocate itself.
+        injections: Injections(Vec::with_capacity(64)),
+    };
+    CASE.with(|cell| *cell.borrow_mut() = Some(case));
+}
+
+/// Stop injecting perturbations on this thread, returning what was injected
+/// since `begin()`.
+pub(crate) fn end() -> Injections {
+    CASE.with(|cell| cell.borrow_mut().take())
+        .map(|case| case.injections)
+        .unwrap_or_default()
+}
+
+/// A chaos point, which may yield the thread, sleep or panic when called
+/// within a test case run with chaos mode. Does nothing otherwise.
+pub fn point() {
+    let injection = CASE.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let case = cell.as_mut()?;
+        let (index, roll) = case.next();
+        let chaos = case.chaos;
+
+        let sleep_threshold = chaos.panic_probability + chaos.sleep_probability;
+        let injection = if roll < chaos.panic_probability {
+            Injection::Panic
+        } else if roll < sleep_threshold {
+            let nanos = chaos.max_sleep.as_nanos() as u64;
+            let nanos = case.rng.gen_range(0..=nanos);
+            Injection::Sleep(Duration::from_nanos(nanos))
+        } else if roll < sleep_threshold + chaos.yield_probability {
+            Injection::Yield
+        } else {
+            return None;
+        };
+        case.injections.0.push((index, injection));
+        Some((index, injection))
+    });
+
+    // Act outside of the borrow, so that a panic leaves the state usable.
+    match injection {
+        None => (),
+        Some((_, Injection::Yield)) => thread::yield_now(),
+        Some((_, Injection::Sleep(duration))) => thread::sleep(duration),
+        Some((index, Injection::Panic)) => {
+            panic!("chaos: injected panic at #{}", index)
+        }
+        Some((_, Injection::AllocFailure(_))) => unreachable!(),
+    }
+}
+
+/// Return whether an allocation of `size` bytes is to fail, for use by test
+/// allocators such as `ChaosAllocator`. Always `false` outside of test cases
+/// run with chaos mode.
+///
+/// This never allocates unless it returns `true`, and returns `false` when
+/// called while it is already running, e.g. from an allocator hook invoked by
+/// its own bookkeeping.
+pub fn should_fail_alloc(size: usize) -> bool {
+    CASE.try_with(|cell| {
+        let mut cell = match cell.try_borrow_mut() {
+            Ok(cell) => cell,
+            Err(_) => return false,
+        };
+        let case = match cell.as_mut() {
+            Some(case) => case,
+            None => return false,
+        };
+
+        let (index, roll) = case.next();
+        let fail = roll < case.chaos.alloc_failure_probability;
+        if fail {
+            let injection = Injection::AllocFailure(size);
+            case.injections.0.push((index, injection));
+        }
+        fail
+    })
+    .unwrap_or(false)
+}
+
+/// A global allocator wrapper which fails allocations as decided by
+/// `should_fail_alloc()`.
+///
+/// Failing an allocation makes the standard collections abort the process,
+/// so this is only useful for code which allocates fallibly, e.g. through
+/// `Vec::try_reserve`, or together with the `fork` feature.
+///
+/// ```rust,ignore
+/// use std::alloc::System;
+/// use proptest::chaos::ChaosAllocator;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: ChaosAllocator<System> = ChaosAllocator(System);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChaosAllocator<A>(pub A);
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for ChaosAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if should_fail_alloc(layout.size()) {
+            core::ptr::null_mut()
+        } else {
+            self.0.alloc(layout)
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.0.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        if should_fail_alloc(layout.size()) {
+            core::ptr::null_mut()
+        } else {
+            self.0.alloc_zeroed(layout)
+        }
+    }
+
+    unsafe fn realloc(
+        &self,
+        ptr: *mut u8,
+        layout: Layout,
+        new_size: usize,
+    ) -> *mut u8 {
+        if should_fail_alloc(new_size) {
+            core::ptr::null_mut()
+        } else {
+            self.0.realloc(ptr, layout, new_size)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::string::ToString;
+
+    use super::*;
+    use crate::test_runner::{
+        Config, RngAlgorithm, TestCaseError, TestError, TestRunner,
+    };
+
+    #[test]
+    fn nothing_is_injected_outside_of_cases() {
+        point();
+        assert!(!should_fail_alloc(1));
+        assert!(end().is_empty());
+    }
+
+    #[test]
+    fn alloc_failures_are_recorded() {
+        let chaos = Chaos {
+            yield_probability: 0.0,
+            sleep_probability: 0.0,
+            alloc_failure_probability: 1.0,
+            ..Chaos::default()
+        };
+        begin(chaos, TestRng::deterministic_rng(RngAlgorithm::ChaCha));
+        point();
+        assert!(should_fail_alloc(42));
+
+        let injections = end();
+        assert_eq!(1, injections.iter().count());
+        assert_eq!(
+            "chaos injections:\n  #1: fail allocation of 42 bytes",
+            injections.to_string()
+        );
+        assert!(!should_fail_alloc(42));
+    }
+
+    #[test]
+    fn failures_replay_identically() {
+        let run = || {
+            let config = Config {
+                failure_persistence: None,
+                chaos: Some(Chaos {
+                    max_sleep: Duration::from_micros(10),
+                    panic_probability: 0.02,
+                    ..Chaos::default()
+                }),
+                ..Config::default()
+            };
+            let rng = TestRng::deterministic_rng(config.rng_algorithm);
+            TestRunner::new_with_rng(config, rng).run(&(0..1000u32), |v| {
+                for _ in 0..v % 8 {
+                    point();
+                }
+                if v >= 500 {
+                    return Err(TestCaseError::fail("too big"));
+                }
+                Ok(())
+            })
+        };
+
+        match run() {
+            Err(TestError::Fail(why, v)) => {
+                assert!(why.message().contains("chaos injections:"));
+                assert_eq!(Err(TestError::Fail(why, v)), run());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+}
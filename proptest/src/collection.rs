@@ -15,7 +15,7 @@ use core::ops::{Add, Range, RangeInclusive, RangeTo, RangeToInclusive};
 use core::usize;
 
 use crate::std_facade::{
-    fmt, BTreeMap, BTreeSet, BinaryHeap, LinkedList, Vec, VecDeque,
+    fmt, Arc, BTreeMap, BTreeSet, BinaryHeap, LinkedList, Vec, VecDeque,
 };
 
 #[cfg(feature = "std")]
@@ -304,6 +304,135 @@ where
     BinaryHeapStrategy(statics::Map::new(vec(element, size), VecToBinHeap))
 }
 
+/// A conversion of a `Vec` into a set or map, which keeps only one of the
+/// elements with equal keys.
+trait ToDistinct<T>: statics::MapFn<Vec<T>> {
+    /// The part of an element which must be unique in the collection.
+    type Key: Eq;
+
+    /// Returns the key of `element`.
+    fn key(element: &T) -> &Self::Key;
+
+    /// Returns the indices of the elements of `vec` which the conversion
+    /// leaves out in favour of another element with an equal key.
+    fn shadowed(&self, vec: &[T]) -> Vec<usize>;
+}
+
+/// Strategy to create sets and maps through `F` from `Vec`s whose included
+/// elements never share a key, so that the collections have as many elements
+/// as the `Vec`s.
+#[derive(Clone, Debug)]
+struct DistinctStrategy<T: Strategy, F> {
+    source: VecStrategy<T>,
+    whence: &'static str,
+    fun: F,
+}
+
+impl<T: Strategy, F> DistinctStrategy<T, F> {
+    fn new(source: VecStrategy<T>, whence: &'static str, fun: F) -> Self {
+        DistinctStrategy {
+            source,
+            whence,
+            fun,
+        }
+    }
+}
+
+impl<T, F> Strategy for DistinctStrategy<T, F>
+where
+    T: Strategy,
+    F: ToDistinct<T::Value> + Clone + fmt::Debug,
+{
+    type Tree = DistinctValueTree<T::Tree, F>;
+    type Value = F::Output;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            let mut source = self.source.new_tree(runner)?;
+            // Leaving out the shadowed elements up front doesn't change the
+            // collection, but keeps shrinking from deleting them to no effect.
+            for ix in self.fun.shadowed(&source.current()) {
+                source.included_elements.clear(ix);
+            }
+
+            if source.included_elements.count() >= source.min_size {
+                return Ok(DistinctValueTree {
+                    source,
+                    fun: self.fun.clone(),
+                });
+            }
+            runner.reject_local(self.whence)?;
+        }
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.source.value_size_hint()
+    }
+}
+
+/// `ValueTree` corresponding to `DistinctStrategy`.
+///
+/// Since the included elements never share a key, the underlying `Vec` never
+/// shrinks below the minimum size of the collection. Shrinking an element
+/// onto the key of another is backed out of.
+#[derive(Clone, Debug)]
+struct DistinctValueTree<T: ValueTree, F> {
+    source: VecValueTree<T>,
+    fun: F,
+}
+
+impl<T: ValueTree, F: ToDistinct<T::Value>> DistinctValueTree<T, F> {
+    fn has_keys_of(&self, vec: &[T::Value]) -> bool {
+        let current = self.source.current();
+        current.len() == vec.len()
+            && current.iter().zip(vec).all(|(a, b)| F::key(a) == F::key(b))
+    }
+}
+
+impl<T: ValueTree, F: ToDistinct<T::Value>> ValidatedValueTree
+    for DistinctValueTree<T, F>
+{
+    type Source = VecValueTree<T>;
+
+    fn source_mut(&mut self) -> &mut VecValueTree<T> {
+        &mut self.source
+    }
+
+    fn is_valid(&self) -> bool {
+        self.fun.shadowed(&self.source.current()).is_empty()
+    }
+}
+
+impl<T: ValueTree, F: ToDistinct<T::Value>> ValueTree
+    for DistinctValueTree<T, F>
+{
+    type Value = F::Output;
+
+    fn current(&self) -> F::Output {
+        self.fun.apply(self.source.current())
+    }
+
+    fn simplify(&mut self) -> bool {
+        let prev = self.source.current();
+        while self.source.simplify() {
+            if self.is_valid() {
+                return true;
+            }
+
+            // Only shrinking a key can cause a collision, so backing out of it
+            // may lead straight back to the value the test last saw.
+            if self.complicate_until_valid() && !self.has_keys_of(&prev) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.complicate_valid()
+    }
+}
+
 mapfn! {
     {#[cfg(feature = "std")]}
     [] fn VecToHashSet[<T : fmt::Debug + Hash + Eq>](vec: Vec<T>)
@@ -312,13 +441,19 @@ mapfn! {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct MinSize(usize);
-
 #[cfg(feature = "std")]
-impl<T: Eq + Hash> statics::FilterFn<HashSet<T>> for MinSize {
-    fn apply(&self, set: &HashSet<T>) -> bool {
-        set.len() >= self.0
+impl<T: fmt::Debug + Hash + Eq> ToDistinct<T> for VecToHashSet {
+    type Key = T;
+
+    fn key(element: &T) -> &T {
+        element
+    }
+
+    fn shadowed(&self, vec: &[T]) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        (0..vec.len())
+            .filter(|&ix| !seen.insert(&vec[ix]))
+            .collect()
     }
 }
 
@@ -330,12 +465,12 @@ opaque_strategy_wrapper! {
     /// Created by the `hash_set()` function in the same module.
     #[derive(Clone, Debug)]
     pub struct HashSetStrategy[<T>][where T : Strategy, T::Value : Hash + Eq](
-        statics::Filter<statics::Map<VecStrategy<T>, VecToHashSet>, MinSize>)
+        DistinctStrategy<T, VecToHashSet>)
         -> HashSetValueTree<T::Tree>;
     /// `ValueTree` corresponding to `HashSetStrategy`.
     #[derive(Clone, Debug)]
     pub struct HashSetValueTree[<T>][where T : ValueTree, T::Value : Hash + Eq](
-        statics::Filter<statics::Map<VecValueTree<T>, VecToHashSet>, MinSize>)
+        DistinctValueTree<T, VecToHashSet>)
         -> HashSet<T::Value>;
 }
 
@@ -354,11 +489,10 @@ pub fn hash_set<T: Strategy>(
 where
     T::Value: Hash + Eq,
 {
-    let size = size.into();
-    HashSetStrategy(statics::Filter::new(
-        statics::Map::new(vec(element, size.clone()), VecToHashSet),
-        "HashSet minimum size".into(),
-        MinSize(size.start()),
+    HashSetStrategy(DistinctStrategy::new(
+        vec(element, size),
+        "HashSet minimum size",
+        VecToHashSet,
     ))
 }
 
@@ -369,9 +503,18 @@ mapfn! {
     }
 }
 
-impl<T: Ord> statics::FilterFn<BTreeSet<T>> for MinSize {
-    fn apply(&self, set: &BTreeSet<T>) -> bool {
-        set.len() >= self.0
+impl<T: fmt::Debug + Ord> ToDistinct<T> for VecToBTreeSet {
+    type Key = T;
+
+    fn key(element: &T) -> &T {
+        element
+    }
+
+    fn shadowed(&self, vec: &[T]) -> Vec<usize> {
+        let mut seen = BTreeSet::new();
+        (0..vec.len())
+            .filter(|&ix| !seen.insert(&vec[ix]))
+            .collect()
     }
 }
 
@@ -381,12 +524,12 @@ opaque_strategy_wrapper! {
     /// Created by the `btree_set()` function in the same module.
     #[derive(Clone, Debug)]
     pub struct BTreeSetStrategy[<T>][where T : Strategy, T::Value : Ord](
-        statics::Filter<statics::Map<VecStrategy<T>, VecToBTreeSet>, MinSize>)
+        DistinctStrategy<T, VecToBTreeSet>)
         -> BTreeSetValueTree<T::Tree>;
     /// `ValueTree` corresponding to `BTreeSetStrategy`.
     #[derive(Clone, Debug)]
     pub struct BTreeSetValueTree[<T>][where T : ValueTree, T::Value : Ord](
-        statics::Filter<statics::Map<VecValueTree<T>, VecToBTreeSet>, MinSize>)
+        DistinctValueTree<T, VecToBTreeSet>)
         -> BTreeSet<T::Value>;
 }
 
@@ -403,11 +546,10 @@ pub fn btree_set<T: Strategy>(
 where
     T::Value: Ord,
 {
-    let size = size.into();
-    BTreeSetStrategy(statics::Filter::new(
-        statics::Map::new(vec(element, size.clone()), VecToBTreeSet),
-        "BTreeSet minimum size".into(),
-        MinSize(size.start()),
+    BTreeSetStrategy(DistinctStrategy::new(
+        vec(element, size),
+        "BTreeSet minimum size",
+        VecToBTreeSet,
     ))
 }
 
@@ -421,9 +563,24 @@ mapfn! {
 }
 
 #[cfg(feature = "std")]
-impl<K: Hash + Eq, V> statics::FilterFn<HashMap<K, V>> for MinSize {
-    fn apply(&self, map: &HashMap<K, V>) -> bool {
-        map.len() >= self.0
+impl<K, V> ToDistinct<(K, V)> for VecToHashMap
+where
+    K: fmt::Debug + Hash + Eq,
+    V: fmt::Debug,
+{
+    type Key = K;
+
+    fn key(element: &(K, V)) -> &K {
+        &element.0
+    }
+
+    fn shadowed(&self, vec: &[(K, V)]) -> Vec<usize> {
+        // Later entries overwrite earlier ones with the same key.
+        let mut seen = HashSet::new();
+        (0..vec.len())
+            .rev()
+            .filter(|&ix| !seen.insert(&vec[ix].0))
+            .collect()
     }
 }
 
@@ -436,15 +593,13 @@ opaque_strategy_wrapper! {
     #[derive(Clone, Debug)]
     pub struct HashMapStrategy[<K, V>]
         [where K : Strategy, V : Strategy, K::Value : Hash + Eq](
-            statics::Filter<statics::Map<VecStrategy<(K,V)>,
-            VecToHashMap>, MinSize>)
+            DistinctStrategy<(K, V), VecToHashMap>)
         -> HashMapValueTree<K::Tree, V::Tree>;
     /// `ValueTree` corresponding to `HashMapStrategy`.
     #[derive(Clone, Debug)]
     pub struct HashMapValueTree[<K, V>]
         [where K : ValueTree, V : ValueTree, K::Value : Hash + Eq](
-            statics::Filter<statics::Map<VecValueTree<TupleValueTree<(K, V)>>,
-            VecToHashMap>, MinSize>)
+            DistinctValueTree<TupleValueTree<(K, V)>, VecToHashMap>)
         -> HashMap<K::Value, V::Value>;
 }
 
@@ -465,11 +620,10 @@ pub fn hash_map<K: Strategy, V: Strategy>(
 where
     K::Value: Hash + Eq,
 {
-    let size = size.into();
-    HashMapStrategy(statics::Filter::new(
-        statics::Map::new(vec((key, value), size.clone()), VecToHashMap),
-        "HashMap minimum size".into(),
-        MinSize(size.start()),
+    HashMapStrategy(DistinctStrategy::new(
+        vec((key, value), size),
+        "HashMap minimum size",
+        VecToHashMap,
     ))
 }
 
@@ -481,9 +635,24 @@ mapfn! {
     }
 }
 
-impl<K: Ord, V> statics::FilterFn<BTreeMap<K, V>> for MinSize {
-    fn apply(&self, map: &BTreeMap<K, V>) -> bool {
-        map.len() >= self.0
+impl<K, V> ToDistinct<(K, V)> for VecToBTreeMap
+where
+    K: fmt::Debug + Ord,
+    V: fmt::Debug,
+{
+    type Key = K;
+
+    fn key(element: &(K, V)) -> &K {
+        &element.0
+    }
+
+    fn shadowed(&self, vec: &[(K, V)]) -> Vec<usize> {
+        // Later entries overwrite earlier ones with the same key.
+        let mut seen = BTreeSet::new();
+        (0..vec.len())
+            .rev()
+            .filter(|&ix| !seen.insert(&vec[ix].0))
+            .collect()
     }
 }
 
@@ -494,15 +663,13 @@ opaque_strategy_wrapper! {
     #[derive(Clone, Debug)]
     pub struct BTreeMapStrategy[<K, V>]
         [where K : Strategy, V : Strategy, K::Value : Ord](
-            statics::Filter<statics::Map<VecStrategy<(K,V)>,
-            VecToBTreeMap>, MinSize>)
+            DistinctStrategy<(K, V), VecToBTreeMap>)
         -> BTreeMapValueTree<K::Tree, V::Tree>;
     /// `ValueTree` corresponding to `BTreeMapStrategy`.
     #[derive(Clone, Debug)]
     pub struct BTreeMapValueTree[<K, V>]
         [where K : ValueTree, V : ValueTree, K::Value : Ord](
-            statics::Filter<statics::Map<VecValueTree<TupleValueTree<(K, V)>>,
-            VecToBTreeMap>, MinSize>)
+            DistinctValueTree<TupleValueTree<(K, V)>, VecToBTreeMap>)
         -> BTreeMap<K::Value, V::Value>;
 }
 
@@ -521,14 +688,396 @@ pub fn btree_map<K: Strategy, V: Strategy>(
 where
     K::Value: Ord,
 {
-    let size = size.into();
-    BTreeMapStrategy(statics::Filter::new(
-        statics::Map::new(vec((key, value), size.clone()), VecToBTreeMap),
-        "BTreeMap minimum size".into(),
-        MinSize(size.start()),
+    BTreeMapStrategy(DistinctStrategy::new(
+        vec((key, value), size),
+        "BTreeMap minimum size",
+        VecToBTreeMap,
     ))
 }
 
+/// Strategy to create maps through `F` with exactly as many entries as sampled
+/// from `size`, by drawing the key of each entry again while it collides with
+/// the key of an earlier entry.
+#[derive(Clone, Debug)]
+struct DistinctKeysStrategy<K, V, F> {
+    key: K,
+    value: V,
+    size: SizeRange,
+    whence: &'static str,
+    fun: F,
+}
+
+impl<K, V, F> Strategy for DistinctKeysStrategy<K, V, F>
+where
+    K: Strategy,
+    K::Value: Eq,
+    V: Strategy,
+    F: ToDistinct<(K::Value, V::Value), Key = K::Value> + Clone + fmt::Debug,
+{
+    type Tree = DistinctValueTree<TupleValueTree<(K::Tree, V::Tree)>, F>;
+    type Value = F::Output;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (start, end) = self.size.start_end_incl();
+        'map: loop {
+            let len = sample_uniform_incl(runner, start, end);
+            let len = runner.budget().draw_size(start, len);
+            let mut entries = Vec::with_capacity(len);
+            let mut keys = Vec::with_capacity(len);
+            while entries.len() < len {
+                let mut duplicates = 0;
+                let key = loop {
+                    let key = self.key.new_tree(runner)?;
+                    let current = key.current();
+                    if !keys.contains(&current) {
+                        keys.push(current);
+                        break key;
+                    }
+
+                    duplicates += 1;
+                    if duplicates >= MAX_DUPLICATE_KEYS {
+                        // `key` is running out of distinct keys, so try again
+                        // with a new size.
+                        runner.reject_local(self.whence)?;
+                        continue 'map;
+                    }
+                };
+                let value = self.value.new_tree(runner)?;
+                entries.push(TupleValueTree::new((key, value)));
+            }
+
+            return Ok(DistinctValueTree {
+                source: VecValueTree {
+                    elements: entries,
+                    included_elements: VarBitSet::saturated(len),
+                    min_size: start,
+                    shrink: Shrink::DeleteElement(0),
+                    prev_shrink: None,
+                },
+                fun: self.fun.clone(),
+            });
+        }
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        let (start, end) = self.size.start_end_incl();
+        (start, Some(end))
+    }
+}
+
+/// Strategy to create `HashMap`s with exactly the number of entries sampled
+/// from a size range.
+///
+/// Created by the `hash_map_distinct()` function in the same module.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct HashMapDistinctStrategy<K, V>(
+    DistinctKeysStrategy<K, V, VecToHashMap>,
+);
+
+#[cfg(feature = "std")]
+impl<K: Strategy, V: Strategy> Strategy for HashMapDistinctStrategy<K, V>
+where
+    K::Value: Hash + Eq,
+{
+    type Tree = HashMapValueTree<K::Tree, V::Tree>;
+    type Value = HashMap<K::Value, V::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.0.new_tree(runner).map(HashMapValueTree)
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.0.value_size_hint()
+    }
+}
+
+/// Create a strategy to generate `HashMap`s containing keys and values drawn
+/// from `key` and `value` respectively, with exactly as many entries as
+/// sampled from `size`.
+///
+/// Unlike `hash_map()`, which keeps the map if colliding keys only leave it
+/// smaller than intended but at least of the minimum size, this draws the key
+/// of an entry again whenever it collides with the key of an earlier entry.
+/// Only if `key` produces a duplicate key many times in a row is the map
+/// locally rejected and generated again, which eventually aborts the test if
+/// `key` cannot produce enough distinct keys.
+///
+/// The resulting value trees are the same as for `hash_map()`, so shrinking
+/// deletes entries down to the minimum size and then shrinks the remaining
+/// ones, skipping over any simplification of a key onto another key.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn hash_map_distinct<K: Strategy, V: Strategy>(
+    key: K,
+    value: V,
+    size: impl Into<SizeRange>,
+) -> HashMapDistinctStrategy<K, V>
+where
+    K::Value: Hash + Eq,
+{
+    let size = size.into();
+    size.assert_nonempty();
+    HashMapDistinctStrategy(DistinctKeysStrategy {
+        key,
+        value,
+        size,
+        whence: "hash_map_distinct running out of distinct keys",
+        fun: VecToHashMap,
+    })
+}
+
+/// Strategy to create `BTreeMap`s with exactly the number of entries sampled
+/// from a size range.
+///
+/// Created by the `btree_map_distinct()` function in the same module.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct BTreeMapDistinctStrategy<K, V>(
+    DistinctKeysStrategy<K, V, VecToBTreeMap>,
+);
+
+impl<K: Strategy, V: Strategy> Strategy for BTreeMapDistinctStrategy<K, V>
+where
+    K::Value: Ord,
+{
+    type Tree = BTreeMapValueTree<K::Tree, V::Tree>;
+    type Value = BTreeMap<K::Value, V::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.0.new_tree(runner).map(BTreeMapValueTree)
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.0.value_size_hint()
+    }
+}
+
+/// Create a strategy to generate `BTreeMap`s containing keys and values drawn
+/// from `key` and `value` respectively, with exactly as many entries as
+/// sampled from `size`.
+///
+/// This is to `btree_map()` what `hash_map_distinct()` is to `hash_map()`.
+///
+/// ```
+/// use proptest::collection::btree_map_distinct;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn has_exact_size(
+///         // Only 8 possible keys, yet the map always has 6 entries.
+///         map in btree_map_distinct(0..8u8, any::<bool>(), 6)
+///     ) {
+///         prop_assert_eq!(6, map.len());
+///     }
+/// }
+/// # fn main() { has_exact_size(); }
+/// ```
+pub fn btree_map_distinct<K: Strategy, V: Strategy>(
+    key: K,
+    value: V,
+    size: impl Into<SizeRange>,
+) -> BTreeMapDistinctStrategy<K, V>
+where
+    K::Value: Ord,
+{
+    let size = size.into();
+    size.assert_nonempty();
+    BTreeMapDistinctStrategy(DistinctKeysStrategy {
+        key,
+        value,
+        size,
+        whence: "btree_map_distinct running out of distinct keys",
+        fun: VecToBTreeMap,
+    })
+}
+
+/// The number of duplicate keys in a row after which
+/// `VecDistinctByKeyStrategy` stops growing a `Vec` that has already reached
+/// the minimum size.
+const MAX_DUPLICATE_KEYS: usize = 64;
+
+/// Strategy to create `Vec`s whose elements are distinct by a key function.
+///
+/// Created by the `vec_distinct_by_key()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+pub struct VecDistinctByKeyStrategy<T: Strategy, F> {
+    element: T,
+    key: Arc<F>,
+    size: SizeRange,
+}
+
+impl<T: Strategy, F> fmt::Debug for VecDistinctByKeyStrategy<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VecDistinctByKeyStrategy")
+            .field("element", &self.element)
+            .field("key", &"<function>")
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl<T: Strategy + Clone, F> Clone for VecDistinctByKeyStrategy<T, F> {
+    fn clone(&self) -> Self {
+        VecDistinctByKeyStrategy {
+            element: self.element.clone(),
+            key: Arc::clone(&self.key),
+            size: self.size.clone(),
+        }
+    }
+}
+
+/// Create a strategy to generate `Vec`s containing elements drawn from
+/// `element` which are distinct by `key`, with a size range given by `size`.
+///
+/// This is useful for things like records with unique IDs but otherwise
+/// random payloads. Elements are generated one at a time, and an element whose
+/// key was already seen is discarded (with a local reject) and drawn again,
+/// so the first occurrence of each key is the one that is kept.
+///
+/// If `element` keeps producing duplicate keys once the minimum size has been
+/// reached, the `Vec` may end up shorter than the size sampled from `size`.
+///
+/// Shrinking deletes elements as for `vec()`, then shrinks each element in
+/// turn, but skips over any simplification of an element which would give it
+/// the key of another element.
+pub fn vec_distinct_by_key<T: Strategy, K: Ord, F: Fn(&T::Value) -> K>(
+    element: T,
+    key: F,
+    size: impl Into<SizeRange>,
+) -> VecDistinctByKeyStrategy<T, F> {
+    let size = size.into();
+    size.assert_nonempty();
+    VecDistinctByKeyStrategy {
+        element,
+        key: Arc::new(key),
+        size,
+    }
+}
+
+impl<T, K, F> Strategy for VecDistinctByKeyStrategy<T, F>
+where
+    T: Strategy,
+    K: Ord,
+    F: Fn(&T::Value) -> K,
+{
+    type Tree = VecDistinctByKeyValueTree<T::Tree, F>;
+    type Value = Vec<T::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (start, end) = self.size.start_end_incl();
+        let max_size = sample_uniform_incl(runner, start, end);
+        let max_size = runner.budget().draw_size(start, max_size);
+        let mut elements = Vec::with_capacity(max_size);
+        let mut keys = BTreeSet::new();
+        let mut duplicates = 0;
+        while elements.len() < max_size {
+            let element = self.element.new_tree(runner)?;
+            if keys.insert((self.key)(&element.current())) {
+                elements.push(element);
+                duplicates = 0;
+                continue;
+            }
+
+            runner.reject_local("vec_distinct_by_key duplicate key")?;
+            duplicates += 1;
+            if elements.len() >= start && duplicates >= MAX_DUPLICATE_KEYS {
+                break;
+            }
+        }
+
+        let len = elements.len();
+        Ok(VecDistinctByKeyValueTree {
+            source: VecValueTree {
+                elements,
+                included_elements: VarBitSet::saturated(len),
+                min_size: start,
+                shrink: Shrink::DeleteElement(0),
+                prev_shrink: None,
+            },
+            key: Arc::clone(&self.key),
+        })
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        let (start, end) = self.size.start_end_incl();
+        (start, Some(end))
+    }
+}
+
+/// `ValueTree` corresponding to `VecDistinctByKeyStrategy`.
+pub struct VecDistinctByKeyValueTree<T: ValueTree, F> {
+    source: VecValueTree<T>,
+    key: Arc<F>,
+}
+
+impl<T: ValueTree + fmt::Debug, F> fmt::Debug
+    for VecDistinctByKeyValueTree<T, F>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VecDistinctByKeyValueTree")
+            .field("source", &self.source)
+            .field("key", &"<function>")
+            .finish()
+    }
+}
+
+impl<T: ValueTree + Clone, F> Clone for VecDistinctByKeyValueTree<T, F> {
+    fn clone(&self) -> Self {
+        VecDistinctByKeyValueTree {
+            source: self.source.clone(),
+            key: Arc::clone(&self.key),
+        }
+    }
+}
+
+impl<T, K, F> ValidatedValueTree for VecDistinctByKeyValueTree<T, F>
+where
+    T: ValueTree,
+    K: Ord,
+    F: Fn(&T::Value) -> K,
+{
+    type Source = VecValueTree<T>;
+
+    fn source_mut(&mut self) -> &mut VecValueTree<T> {
+        &mut self.source
+    }
+
+    fn is_valid(&self) -> bool {
+        let mut keys = BTreeSet::new();
+        self.source
+            .current()
+            .iter()
+            .all(|element| keys.insert((self.key)(element)))
+    }
+}
+
+impl<T, K, F> ValueTree for VecDistinctByKeyValueTree<T, F>
+where
+    T: ValueTree,
+    K: Ord,
+    F: Fn(&T::Value) -> K,
+{
+    type Value = Vec<T::Value>;
+
+    fn current(&self) -> Vec<T::Value> {
+        self.source.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.simplify_valid()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.complicate_valid()
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 enum Shrink {
     DeleteElement(usize),
@@ -552,6 +1101,7 @@ impl<T: Strategy> Strategy for VecStrategy<T> {
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         let (start, end) = self.size.start_end_incl();
         let max_size = sample_uniform_incl(runner, start, end);
+        let max_size = runner.budget().draw_size(start, max_size);
         let mut elements = Vec::with_capacity(max_size);
         while elements.len() < max_size {
             elements.push(self.element.new_tree(runner)?);
@@ -565,6 +1115,11 @@ impl<T: Strategy> Strategy for VecStrategy<T> {
             prev_shrink: None,
         })
     }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        let (start, end) = self.size.start_end_incl();
+        (start, Some(end))
+    }
 }
 
 impl<T: Strategy> Strategy for Vec<T> {
@@ -586,18 +1141,27 @@ impl<T: Strategy> Strategy for Vec<T> {
             prev_shrink: None,
         })
     }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
 }
 
 impl<T: ValueTree> ValueTree for VecValueTree<T> {
     type Value = Vec<T::Value>;
 
     fn current(&self) -> Vec<T::Value> {
-        self.elements
-            .iter()
-            .enumerate()
-            .filter(|&(ix, _)| self.included_elements.test(ix))
-            .map(|(_, element)| element.current())
-            .collect()
+        // `filter()` loses the length of the iterator, so `collect()` would
+        // otherwise grow the `Vec` one reallocation at a time.
+        let mut current = Vec::with_capacity(self.included_elements.count());
+        current.extend(
+            self.elements
+                .iter()
+                .enumerate()
+                .filter(|&(ix, _)| self.included_elements.test(ix))
+                .map(|(_, element)| element.current()),
+        );
+        current
     }
 
     fn simplify(&mut self) -> bool {
@@ -607,7 +1171,14 @@ impl<T: ValueTree> ValueTree for VecValueTree<T> {
         //
         // For `complicate()`, we simply undo the last shrink operation, if
         // there was any.
-        if let Shrink::DeleteElement(ix) = self.shrink {
+        if let Shrink::DeleteElement(mut ix) = self.shrink {
+            // Skip elements which were left out from the start, as sets and
+            // maps do with elements sharing a key.
+            while ix < self.elements.len() && !self.included_elements.test(ix) {
+                ix += 1;
+            }
+            self.shrink = Shrink::DeleteElement(ix);
+
             // Can't delete an element if beyond the end of the vec or if it
             // would put us under the minimum length.
             if ix >= self.elements.len()
@@ -672,14 +1243,397 @@ impl<T: ValueTree> ValueTree for VecValueTree<T> {
 }
 
 //==============================================================================
-// Tests
+// Streamed vectors
 //==============================================================================
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    use crate::bits;
+/// Strategy to create `VecStream`s with a length in a certain range.
+///
+/// Created by the `vec_streamed()` function in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Debug)]
+pub struct VecStreamedStrategy<T> {
+    element: Arc<T>,
+    size: SizeRange,
+}
+
+impl<T> Clone for VecStreamedStrategy<T> {
+    fn clone(&self) -> Self {
+        VecStreamedStrategy {
+            element: Arc::clone(&self.element),
+            size: self.size.clone(),
+        }
+    }
+}
+
+/// Create a strategy to generate sequences of elements drawn from `element`
+/// and with a size range given by `size`, without ever holding the whole
+/// sequence in memory.
+///
+/// Instead of a `Vec`, this generates a `VecStream`, which regenerates its
+/// elements from the same random seed each time it is iterated over or read
+/// from. This makes it possible to test I/O code with inputs of hundreds of
+/// megabytes, while a `vec()` of that size would hold every element and its
+/// value tree at once.
+///
+/// Shrinking is coarse: only the length is shrunk, by truncating the
+/// sequence, while the elements themselves are never simplified.
+///
+/// ## Panics
+///
+/// Iterating over the generated value panics if `element` fails to generate
+/// an element, so strategies that reject values, such as filters, should be
+/// avoided.
+///
+/// ```rust,no_run
+/// use std::io::Read;
+///
+/// use proptest::collection::vec_streamed;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn counts_all_bytes(input in vec_streamed(any::<u8>(), 0..100_000_000)) {
+///         let mut buf = [0u8; 8192];
+///         let mut reader = input.reader();
+///         let mut total = 0;
+///         loop {
+///             let read = reader.read(&mut buf).unwrap();
+///             if 0 == read {
+///                 break;
+///             }
+///             total += read;
+///         }
+///         prop_assert_eq!(input.len(), total);
+///     }
+/// }
+/// # fn main() { counts_all_bytes(); }
+/// ```
+pub fn vec_streamed<T: Strategy>(
+    element: T,
+    size: impl Into<SizeRange>,
+) -> VecStreamedStrategy<T> {
+    let size = size.into();
+    size.assert_nonempty();
+    VecStreamedStrategy {
+        element: Arc::new(element),
+        size,
+    }
+}
+
+/// A sequence of elements generated by `vec_streamed()`, which are
+/// regenerated deterministically each time the sequence is traversed.
+///
+/// Its `Debug` form only shows the length of the sequence.
+pub struct VecStream<T> {
+    element: Arc<T>,
+    runner: TestRunner,
+    len: usize,
+}
+
+impl<T> Clone for VecStream<T> {
+    fn clone(&self) -> Self {
+        VecStream {
+            element: Arc::clone(&self.element),
+            runner: self.runner.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T> fmt::Debug for VecStream<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VecStream")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Strategy> VecStream<T> {
+    /// Returns the number of elements in the sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+
+    /// Returns an iterator which generates the elements of the sequence.
+    ///
+    /// Every iterator returned by this yields the same elements.
+    pub fn iter(&self) -> VecStreamIter<'_, T> {
+        VecStreamIter {
+            element: &self.element,
+            runner: self.runner.clone(),
+            remaining: self.len,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Strategy<Value = u8>> VecStream<T> {
+    /// Returns a reader which generates the bytes of the sequence.
+    ///
+    /// Every reader returned by this reads the same bytes.
+    pub fn reader(&self) -> VecStreamReader<'_, T> {
+        VecStreamReader { iter: self.iter() }
+    }
+}
+
+impl<'a, T: Strategy> IntoIterator for &'a VecStream<T> {
+    type Item = T::Value;
+    type IntoIter = VecStreamIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of a `VecStream`, returned by
+/// `VecStream::iter()`.
+#[derive(Debug)]
+pub struct VecStreamIter<'a, T> {
+    element: &'a T,
+    runner: TestRunner,
+    remaining: usize,
+}
+
+impl<'a, T: Strategy> Iterator for VecStreamIter<'a, T> {
+    type Item = T::Value;
+
+    fn next(&mut self) -> Option<T::Value> {
+        if 0 == self.remaining {
+            return None;
+        }
+
+        self.remaining -= 1;
+        match self.element.new_tree(&mut self.runner) {
+            Ok(tree) => Some(tree.current()),
+            Err(why) => panic!("Failed to generate streamed element: {}", why),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T: Strategy> ExactSizeIterator for VecStreamIter<'a, T> {}
+
+/// Reader over the bytes of a `VecStream`, returned by
+/// `VecStream::reader()`.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct VecStreamReader<'a, T> {
+    iter: VecStreamIter<'a, T>,
+}
+
+#[cfg(feature = "std")]
+impl<'a, T: Strategy<Value = u8>> std::io::Read for VecStreamReader<'a, T> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut read = 0;
+        for (dst, byte) in buf.iter_mut().zip(&mut self.iter) {
+            *dst = byte;
+            read += 1;
+        }
+        Ok(read)
+    }
+}
+
+impl<T: Strategy> Strategy for VecStreamedStrategy<T> {
+    type Tree = VecStreamedValueTree<T>;
+    type Value = VecStream<T>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (start, end) = self.size.start_end_incl();
+        let len = sample_uniform_incl(runner, start, end);
+        let len = runner.budget().draw_size(start, len);
+
+        Ok(VecStreamedValueTree {
+            stream: VecStream {
+                element: Arc::clone(&self.element),
+                runner: runner.partial_clone(),
+                len,
+            },
+            len: crate::num::usize::BinarySearch::new_above(start, len),
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `VecStreamedStrategy`.
+#[derive(Clone, Debug)]
+pub struct VecStreamedValueTree<T> {
+    stream: VecStream<T>,
+    len: crate::num::usize::BinarySearch,
+}
+
+impl<T: Strategy> ValueTree for VecStreamedValueTree<T> {
+    type Value = VecStream<T>;
+
+    fn current(&self) -> VecStream<T> {
+        // The elements don't depend on the length, so a shorter stream is a
+        // prefix of the original one.
+        VecStream {
+            len: self.len.current(),
+            ..self.stream.clone()
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.len.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.len.complicate()
+    }
+}
+
+/// Strategy to plant guaranteed elements into `Vec`s.
+///
+/// Created by the `ensure_contains()` and `vec_with_at_least_one()` functions
+/// in the same module.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct EnsureContainsStrategy<S, T: Strategy> {
+    base: S,
+    required: VecStrategy<T>,
+}
+
+/// Create a strategy which inserts `count` elements drawn from `required` at
+/// random positions into each `Vec` generated by `base`.
+///
+/// This states "contains at least `count` elements satisfying some property"
+/// constructively, where filtering `base` for such `Vec`s could reject most
+/// of what it generates. For example, to test that a parser finds a marker
+/// byte somewhere in its input:
+///
+/// ```
+/// use proptest::collection::{ensure_contains, vec};
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     fn finds_marker(
+///         input in ensure_contains(vec(any::<u8>(), 0..100), Just(0xFF), 1)
+///     ) {
+///         prop_assert!(input.contains(&0xFF));
+///     }
+/// }
+/// # fn main() { finds_marker(); }
+/// ```
+///
+/// Shrinking first shrinks the `Vec` from `base`, then each planted element
+/// in turn. The planted elements are never removed, so every shrunken value
+/// still contains `count` values of `required`.
+pub fn ensure_contains<S, T>(
+    base: S,
+    required: T,
+    count: usize,
+) -> EnsureContainsStrategy<S, T>
+where
+    S: Strategy<Value = Vec<T::Value>>,
+    T: Strategy,
+{
+    EnsureContainsStrategy {
+        base,
+        required: vec(required, count..=count),
+    }
+}
+
+/// Create a strategy to generate `Vec`s with a length in `size` of which at
+/// least one element is drawn from `special`, while the others are drawn from
+/// `filler`.
+///
+/// This is `ensure_contains()` planting one element of `special` into a
+/// `Vec` of `filler`, with sizes adjusted so the total length is in `size`.
+///
+/// Panics if `size` does not allow any non-empty `Vec`.
+pub fn vec_with_at_least_one<S, F>(
+    special: S,
+    filler: F,
+    size: impl Into<SizeRange>,
+) -> EnsureContainsStrategy<VecStrategy<F>, S>
+where
+    S: Strategy,
+    F: Strategy<Value = S::Value>,
+{
+    let (start, end) = size.into().start_end_incl();
+    assert!(
+        end >= 1,
+        "vec_with_at_least_one() needs room for at least one element"
+    );
+    ensure_contains(vec(filler, start.saturating_sub(1)..=end - 1), special, 1)
+}
+
+impl<S, T> Strategy for EnsureContainsStrategy<S, T>
+where
+    S: Strategy<Value = Vec<T::Value>>,
+    T: Strategy,
+{
+    type Tree = EnsureContainsValueTree<S::Tree, T::Tree>;
+    type Value = Vec<T::Value>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let base = self.base.new_tree(runner)?;
+        let required = self.required.new_tree(runner)?;
+        let len = base.current().len();
+        let positions = required
+            .elements
+            .iter()
+            .map(|_| sample_uniform_incl(runner, 0, len))
+            .collect();
+
+        Ok(EnsureContainsValueTree {
+            inner: TupleValueTree::new((base, required)),
+            positions,
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `EnsureContainsStrategy`.
+#[derive(Clone, Debug)]
+pub struct EnsureContainsValueTree<B, T: ValueTree> {
+    inner: TupleValueTree<(B, VecValueTree<T>)>,
+    /// Where each planted element goes, clamped to the length of the `Vec`
+    /// once the base has shrunk.
+    positions: Vec<usize>,
+}
+
+impl<B, T> ValueTree for EnsureContainsValueTree<B, T>
+where
+    B: ValueTree<Value = Vec<T::Value>>,
+    T: ValueTree,
+{
+    type Value = Vec<T::Value>;
+
+    fn current(&self) -> Vec<T::Value> {
+        let (mut value, required) = self.inner.current();
+        for (&position, element) in self.positions.iter().zip(required) {
+            value.insert(position.min(value.len()), element);
+        }
+        value
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.inner.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.inner.complicate()
+    }
+}
+
+//==============================================================================
+// Tests
+//==============================================================================
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::bits;
 
     #[test]
     fn test_vec() {
@@ -751,6 +1705,74 @@ mod test {
         }
     }
 
+    #[test]
+    fn vec_distinct_by_key_keeps_keys_distinct_while_shrinking() {
+        let input = vec_distinct_by_key((0u32..20, 0u32..1000), |v| v.0, 3..10);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            let start = case.current();
+            assert!(start.len() >= 3 && start.len() < 10);
+
+            loop {
+                let current = case.current();
+                assert!(current.len() >= 3);
+                let keys = current.iter().map(|v| v.0).collect::<BTreeSet<_>>();
+                assert_eq!(current.len(), keys.len());
+
+                if !case.simplify() {
+                    break;
+                }
+            }
+
+            // Keys can't always shrink all the way past each other, but
+            // everything else can.
+            let minimal = case.current();
+            assert_eq!(3, minimal.len());
+            assert!(minimal.iter().all(|v| 0 == v.1));
+        }
+    }
+
+    #[test]
+    fn vec_distinct_by_key_stops_complicating_without_distinct_keys() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let distinct = Rc::new(Cell::new(true));
+        let key_distinct = Rc::clone(&distinct);
+        let input = vec_distinct_by_key(
+            0u32..1000,
+            move |&v| if key_distinct.get() { v } else { 0 },
+            2..10,
+        );
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..64 {
+            distinct.set(true);
+            let mut case = input.new_tree(&mut runner).unwrap();
+            if !case.simplify() {
+                continue;
+            }
+
+            distinct.set(false);
+            assert!(!case.complicate());
+        }
+    }
+
+    #[test]
+    fn vec_distinct_by_key_sanity() {
+        check_strategy_sanity(
+            vec_distinct_by_key(0i32..1000, |&v| v / 10, 5..10),
+            Some(CheckStrategySanityOptions {
+                // Skipping states with duplicate keys may land back where
+                // `complicate()` would have gone.
+                strict_complicate_after_simplify: false,
+                ..CheckStrategySanityOptions::default()
+            }),
+        );
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_map() {
@@ -776,4 +1798,235 @@ mod test {
             assert_eq!(2, v.len());
         }
     }
+
+    /// Simplifies `case` all the way down as if the test always failed,
+    /// checking that every step changes the value without going below `min`.
+    fn assert_shrinks_above_min<T>(mut case: T, min: usize) -> T::Value
+    where
+        T: ValueTree,
+        T::Value: PartialEq + fmt::Debug,
+        for<'a> &'a T::Value: IntoIterator,
+    {
+        let mut current = case.current();
+        while case.simplify() {
+            let next = case.current();
+            assert!(next.into_iter().count() >= min);
+            assert!(current != next, "Shrinking made no progress: {:?}", next);
+            current = next;
+        }
+        current
+    }
+
+    #[test]
+    fn vec_deque_shrinks_to_min_size() {
+        let input = vec_deque(0u32..1000, 3..10);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let minimal = assert_shrinks_above_min(case, 3);
+            assert_eq!(VecDeque::from(vec![0, 0, 0]), minimal);
+        }
+    }
+
+    #[test]
+    fn btree_set_shrinks_without_collisions() {
+        let input = btree_set(0u32..20, 3..10);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let minimal = assert_shrinks_above_min(case, 3);
+            assert_eq!(3, minimal.len());
+        }
+    }
+
+    #[test]
+    fn value_size_hint_bounds_generated_sizes() {
+        let input = btree_set(0u32..20, 3..10)
+            .prop_filter("even", |set| set.len() % 2 == 0);
+        assert_eq!((3, Some(9)), input.value_size_hint());
+        assert_eq!((2, Some(2)), vec![Just(0), Just(1)].value_size_hint());
+        assert_eq!(
+            (0, None),
+            vec(0u32..20, 3..10).prop_map(|v| v.len()).value_size_hint()
+        );
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let len = input.new_tree(&mut runner).unwrap().current().len();
+            assert!((3..10).contains(&len), "{}", len);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_set_shrinks_without_collisions() {
+        let input = hash_set(0u32..20, 3..10);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let minimal = assert_shrinks_above_min(case, 3);
+            assert_eq!(3, minimal.len());
+        }
+    }
+
+    #[test]
+    fn btree_map_shrinks_without_collisions() {
+        let input = btree_map(0u32..20, 0u32..1000, 3..10);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let minimal = assert_shrinks_above_min(case, 3);
+            assert_eq!(3, minimal.len());
+            assert!(minimal.values().all(|&v| 0 == v));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_map_shrinks_without_collisions() {
+        let input = hash_map(0u32..20, 0u32..1000, 3..10);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let minimal = assert_shrinks_above_min(case, 3);
+            assert_eq!(3, minimal.len());
+            assert!(minimal.values().all(|&v| 0 == v));
+        }
+    }
+
+    #[test]
+    fn btree_map_sanity() {
+        check_strategy_sanity(btree_map(0u32..20, 0u32..1000, 3..10), None);
+    }
+
+    #[test]
+    fn btree_map_distinct_has_exact_size() {
+        // Only 8 possible keys, so plain `btree_map()` would often come up
+        // short of 7 entries.
+        let input = btree_map_distinct(0u32..8, 0u32..1000, 7);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let case = input.new_tree(&mut runner).unwrap();
+            assert_eq!(7, case.current().len());
+            let minimal = assert_shrinks_above_min(case, 7);
+            assert!(minimal.values().all(|&v| 0 == v));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn hash_map_distinct_has_sampled_size() {
+        let input = hash_map_distinct("[ab]{3}", "a", 2..8);
+        let mut runner = TestRunner::deterministic();
+
+        let mut sizes = BTreeSet::new();
+        for _ in 0..256 {
+            sizes.insert(input.new_tree(&mut runner).unwrap().current().len());
+        }
+        assert_eq!((2..8).collect::<BTreeSet<_>>(), sizes);
+    }
+
+    #[test]
+    fn btree_map_distinct_rejects_without_enough_keys() {
+        let input = btree_map_distinct(0u32..4, 0u32..1000, 5);
+        let mut runner = TestRunner::new(Config {
+            max_local_rejects: 4,
+            ..Config::default()
+        });
+        assert!(input.new_tree(&mut runner).is_err());
+    }
+
+    #[test]
+    fn btree_map_distinct_sanity() {
+        check_strategy_sanity(
+            btree_map_distinct(0u32..20, 0u32..1000, 3..10),
+            None,
+        );
+    }
+
+    #[test]
+    fn vec_streamed_regenerates_same_elements() {
+        let input = vec_streamed(0u32..1000, 10..10_000);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..16 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let stream = tree.current();
+            let elements: Vec<_> = stream.iter().collect();
+            assert!((10..10_000).contains(&elements.len()));
+            assert!(elements.iter().all(|&v| v < 1000));
+            assert_eq!(elements, stream.clone().iter().collect::<Vec<_>>());
+
+            // Shrinking truncates the stream
+            while tree.simplify() {
+                let shrunk: Vec<_> = tree.current().iter().collect();
+                assert_eq!(&elements[..shrunk.len()], &shrunk[..]);
+            }
+            assert_eq!(10, tree.current().len());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn vec_streamed_shrinks_length_and_reads_bytes() {
+        use std::io::Read;
+
+        let input = vec_streamed(crate::num::u8::ANY, 0..1_000_000);
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&input, |stream| {
+            let mut bytes = Vec::new();
+            stream.reader().read_to_end(&mut bytes).unwrap();
+            prop_assert_eq!(stream.iter().collect::<Vec<_>>(), bytes);
+            prop_assert!(stream.len() < 1000);
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, stream)) => assert_eq!(1000, stream.len()),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn vec_with_at_least_one_always_contains_special() {
+        let input = vec_with_at_least_one(100u8..200, 0u8..10, 0..5);
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let v = input.new_tree(&mut runner).unwrap().current();
+            assert!((1..5).contains(&v.len()), "{:?}", v);
+            assert_eq!(1, v.iter().filter(|&&e| e >= 100).count(), "{:?}", v);
+        }
+    }
+
+    #[test]
+    fn ensure_contains_keeps_guarantee_while_shrinking() {
+        let input = ensure_contains(vec(0u32..1000, 0..50), 1000u32..2000, 3);
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&input, |v| {
+            prop_assert!(v.len() < 3 || v.iter().sum::<u32>() < 1000);
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, v)) => {
+                assert_eq!(vec![1000, 1000, 1000], v);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn ensure_contains_sanity() {
+        check_strategy_sanity(
+            ensure_contains(vec(0i32..1000, 0..10), 0i32..1000, 2),
+            None,
+        );
+    }
 }
@@ -0,0 +1,166 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(feature = "std")]
+use crate::std_facade::Box;
+use crate::std_facade::{fmt, String};
+
+use crate::test_runner::{Reason, TestError};
+
+/// The crate-level error type, aggregating the errors of the individual
+/// modules of proptest.
+///
+/// Every error proptest produces can be converted into this type with `?`,
+/// which is convenient for code juggling several kinds of them. New fallible
+/// public APIs return this type directly.
+///
+/// Since a `TestError<T>` is generic over the failing input, converting it
+/// renders the input with its `{:#?}` form.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A strategy could not be constructed from a regular expression. (Boxed
+    /// since it is much larger than the other variants.)
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    Regex(Box<crate::string::Error>),
+    /// A strategy failed to generate a value, for example because it rejected
+    /// too many values itself.
+    Generate(Reason),
+    /// A test run was aborted, for example due to too many inputs having been
+    /// rejected.
+    Abort(Reason),
+    /// A test run found a failing case. The string is the `{:#?}` form of the
+    /// minimal failing input.
+    Fail(Reason, String),
+    /// Reading or writing a file, such as a failure persistence file, failed.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            #[cfg(feature = "std")]
+            Error::Regex(ref err) => write!(f, "Invalid regex: {}", err),
+            Error::Generate(ref why) => {
+                write!(f, "Failed to generate value: {}", why)
+            }
+            Error::Abort(ref why) => write!(f, "Test aborted: {}", why),
+            Error::Fail(ref why, ref what) => {
+                writeln!(f, "Test failed: {}.", why)?;
+                write!(f, "minimal failing input: {}", what)
+            }
+            #[cfg(feature = "std")]
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Error::Regex(ref err) => Some(&**err),
+            Error::Io(ref err) => Some(err),
+            Error::Generate(_) | Error::Abort(_) | Error::Fail(..) => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<crate::string::Error> for Error {
+    fn from(err: crate::string::Error) -> Self {
+        Error::Regex(Box::new(err))
+    }
+}
+
+impl From<Reason> for Error {
+    fn from(why: Reason) -> Self {
+        Error::Generate(why)
+    }
+}
+
+impl<T: fmt::Debug> From<TestError<T>> for Error {
+    fn from(err: TestError<T>) -> Self {
+        match err {
+            TestError::Abort(why) => Error::Abort(why),
+            TestError::Fail(why, what) => {
+                Error::Fail(why, format!("{:#?}", what))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::Error as _;
+    use std::string::ToString;
+
+    use super::*;
+    use crate::strategy::{Strategy, ValueTree};
+    use crate::test_runner::TestRunner;
+
+    fn generate_regex(pattern: &str) -> Result<String, Error> {
+        let strategy = crate::string::string_regex(pattern)?;
+        Ok(strategy
+            .new_tree(&mut TestRunner::deterministic())?
+            .current())
+    }
+
+    fn run_failing() -> Result<(), Error> {
+        TestRunner::deterministic().run(&(0u32..10), |v| {
+            prop_assert!(v < 5, "too big");
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn errors_convert_with_question_mark() {
+        assert_eq!("a", generate_regex("a").unwrap());
+
+        let err = generate_regex("(").unwrap_err();
+        assert!(matches!(err, Error::Regex(_)));
+        assert!(err.source().is_some());
+
+        match run_failing().unwrap_err() {
+            Error::Fail(why, what) => {
+                assert!(why.message().starts_with("too big"));
+                assert_eq!("5", what);
+            }
+            err => panic!("Unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn display_describes_each_error() {
+        assert_eq!(
+            "Test aborted: Too many local rejects",
+            Error::from(TestError::<()>::Abort(
+                "Too many local rejects".into()
+            ))
+            .to_string()
+        );
+        assert_eq!(
+            "Failed to generate value: nope",
+            Error::from(Reason::from("nope")).to_string()
+        );
+        let io = std::io::Error::new(std::io::ErrorKind::Other, "disk full");
+        assert_eq!("I/O error: disk full", Error::from(io).to_string());
+    }
+}
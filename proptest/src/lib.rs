@@ -65,10 +65,16 @@ mod macros;
 #[macro_use]
 pub mod sugar;
 
+#[cfg(feature = "alloc-tracking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc-tracking")))]
+pub mod alloc_tracking;
 pub mod arbitrary;
 pub mod array;
 pub mod bits;
 pub mod bool;
+#[cfg(feature = "chaos")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chaos")))]
+pub mod chaos;
 pub mod char;
 pub mod collection;
 pub mod num;
@@ -80,14 +86,32 @@ pub mod option;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod path;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod probe;
 pub mod result;
 pub mod sample;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod string;
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+pub mod time;
+#[cfg(feature = "units")]
+#[cfg_attr(docsrs, doc(cfg(feature = "units")))]
+pub mod units;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod workload;
 
 pub mod prelude;
 
+mod error;
+pub use crate::error::Error;
+
+mod verify;
+pub use crate::verify::{verify, IntoTestCaseResult};
+
 #[cfg(feature = "attr-macro")]
 pub use proptest_macro::property_test; 
 
@@ -71,6 +71,10 @@ macro_rules! opaque_strategy_wrapper {
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
                 self.0.new_tree(runner).map($vtname)
             }
+
+            fn value_size_hint(&self) -> (usize, Option<usize>) {
+                self.0.value_size_hint()
+            }
         }
 
         $(#[$allmeta])*
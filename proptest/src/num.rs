@@ -10,11 +10,17 @@
 //! Strategies to generate numeric values (as opposed to integers used as bit
 //! fields).
 //!
-//! All strategies in this module shrink by binary searching towards 0.
+//! All strategies in this module shrink by binary searching towards 0. The
+//! integer strategies can alternatively be made to shrink adaptively; see
+//! `IntShrinkMode`.
 
+pub mod float_cmp;
 mod float_samplers;
 
+use crate::std_facade::fmt;
+use crate::strategy::{NewTree, Strategy, ValueTree};
 use crate::test_runner::TestRunner;
+use core::marker::PhantomData;
 use rand::distributions::uniform::{SampleUniform, Uniform};
 use rand::distributions::{Distribution, Standard};
 
@@ -38,6 +44,148 @@ pub fn sample_uniform_incl<X: SampleUniform>(
     Uniform::new_inclusive(start, end).sample(run.rng())
 }
 
+/// How the integer `BinarySearch` value trees in this module shrink.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IntShrinkMode {
+    /// Binary search between zero (or the closest bound of the range) and
+    /// the failing value. This is the default.
+    #[default]
+    Bisect,
+    /// Bisect towards zero only while the test keeps failing. Once a value
+    /// passes, step down from the smallest known failure instead, with the
+    /// step halving after every pass.
+    ///
+    /// Plain binary search assumes that every value closer to zero than a
+    /// passing value also passes. Properties which only fail within a certain
+    /// magnitude region break that assumption, and bisection then keeps
+    /// jumping out of the region and has to complicate back. Stepping down
+    /// from a known failure stays within the region instead.
+    Adaptive,
+}
+
+/// An integer value tree whose shrinking behaviour can be configured.
+pub trait IntValueTree: ValueTree {
+    /// Switch the shrinking of this value tree to `mode`.
+    ///
+    /// This should be called before shrinking starts.
+    fn set_shrink_mode(&mut self, mode: IntShrinkMode);
+}
+
+/// `Strategy` returned by `with_shrink_mode()`.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct WithShrinkMode<S> {
+    source: S,
+    mode: IntShrinkMode,
+}
+
+/// Wrap the integer strategy `source` so that the values it produces shrink
+/// according to `mode`.
+///
+/// ```
+/// use proptest::num::{with_shrink_mode, IntShrinkMode};
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn fits_in_buffer(
+///         len in with_shrink_mode(0..100_000usize, IntShrinkMode::Adaptive)
+///     ) {
+///         prop_assert!(len < 100_000);
+///     }
+/// }
+/// # fn main() { fits_in_buffer(); }
+/// ```
+pub fn with_shrink_mode<S>(source: S, mode: IntShrinkMode) -> WithShrinkMode<S>
+where
+    S: Strategy,
+    S::Tree: IntValueTree,
+{
+    WithShrinkMode { source, mode }
+}
+
+impl<S> Strategy for WithShrinkMode<S>
+where
+    S: Strategy,
+    S::Tree: IntValueTree,
+{
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let mut tree = self.source.new_tree(runner)?;
+        tree.set_shrink_mode(self.mode);
+        Ok(tree)
+    }
+}
+
+/// Floating-point types which can generate only finite values; see
+/// `finite()`.
+pub trait FiniteFloat: Copy + fmt::Debug {
+    /// The type of the strategy returned by `strategy()`.
+    type Strategy: Strategy<Value = Self>;
+
+    /// Returns the `finite()` strategy in the module of this type, e.g.,
+    /// `num::f64::finite()`.
+    fn strategy() -> Self::Strategy;
+}
+
+impl FiniteFloat for f32 {
+    type Strategy = f32::Any;
+
+    fn strategy() -> f32::Any {
+        f32::finite()
+    }
+}
+
+impl FiniteFloat for f64 {
+    type Strategy = f64::Any;
+
+    fn strategy() -> f64::Any {
+        f64::finite()
+    }
+}
+
+/// `Strategy` returned by `finite()`.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Finite<T>(PhantomData<T>);
+
+/// Generates any finite value of the floating-point type `T`, i.e., anything
+/// but NaN and the infinities. Values shrink towards 0.0.
+///
+/// This is the same as `num::f32::finite()` or `num::f64::finite()`, but
+/// usable where only the type of the value is known, and is what
+/// `#[proptest(finite)]` of `#[derive(Arbitrary)]` expands to.
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn square_is_not_negative(x in prop::num::finite::<f64>()) {
+///         prop_assert!(x * x >= 0.0);
+///     }
+/// }
+/// # fn main() { square_is_not_negative(); }
+/// ```
+pub fn finite<T: FiniteFloat>() -> Finite<T> {
+    Finite(PhantomData)
+}
+
+impl<T: FiniteFloat> Strategy for Finite<T> {
+    type Tree = <T::Strategy as Strategy>::Tree;
+    type Value = T;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        T::strategy().new_tree(runner)
+    }
+}
+
 macro_rules! int_any {
     ($typ: ident) => {
         /// Type of the `ANY` constant.
@@ -171,14 +319,360 @@ macro_rules! numeric_api {
     };
 }
 
+macro_rules! int_pair_api {
+    ($typ:ident) => {
+        /// Returns the inclusive bounds of `range`, panicking if it is empty.
+        fn inclusive_bounds(
+            range: impl ::core::ops::RangeBounds<$typ>,
+        ) -> ($typ, $typ) {
+            use core::ops::Bound::*;
+
+            let lo = match range.start_bound() {
+                Included(&lo) => Some(lo),
+                Excluded(&lo) => lo.checked_add(1),
+                Unbounded => Some($typ::MIN),
+            };
+            let hi = match range.end_bound() {
+                Included(&hi) => Some(hi),
+                Excluded(&hi) => hi.checked_sub(1),
+                Unbounded => Some($typ::MAX),
+            };
+            match (lo, hi) {
+                (Some(lo), Some(hi)) if lo <= hi => (lo, hi),
+                _ => panic!("Invalid use of empty range"),
+            }
+        }
+
+        /// `Strategy` returned by `ordered_pair()`.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct OrderedPair {
+            lo: $typ,
+            hi: $typ,
+        }
+
+        /// Generates pairs `(a, b)` of values within `range` such that
+        /// `a <= b`.
+        ///
+        /// Both values shrink like the values of `range` do, and the pair
+        /// stays ordered while they do.
+        ///
+        /// Panics if `range` is empty.
+        pub fn ordered_pair(
+            range: impl ::core::ops::RangeBounds<$typ>,
+        ) -> OrderedPair {
+            let (lo, hi) = inclusive_bounds(range);
+            OrderedPair { lo, hi }
+        }
+
+        impl Strategy for OrderedPair {
+            type Tree = OrderedPairValueTree;
+            type Value = ($typ, $typ);
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let range = self.lo..=self.hi;
+                Ok(OrderedPairValueTree(TupleValueTree::new((
+                    range.new_tree(runner)?,
+                    range.new_tree(runner)?,
+                ))))
+            }
+        }
+
+        /// `ValueTree` corresponding to `OrderedPair`.
+        #[derive(Clone, Copy, Debug)]
+        pub struct OrderedPairValueTree(
+            TupleValueTree<(BinarySearch, BinarySearch)>,
+        );
+
+        impl ValueTree for OrderedPairValueTree {
+            type Value = ($typ, $typ);
+
+            fn current(&self) -> ($typ, $typ) {
+                let (a, b) = self.0.current();
+                if a <= b {
+                    (a, b)
+                } else {
+                    (b, a)
+                }
+            }
+
+            fn simplify(&mut self) -> bool {
+                self.0.simplify()
+            }
+
+            fn complicate(&mut self) -> bool {
+                self.0.complicate()
+            }
+        }
+    };
+}
+
+macro_rules! uint_pair_api {
+    ($typ:ident) => {
+        fn gcd(mut a: $typ, mut b: $typ) -> $typ {
+            while 0 != b {
+                (a, b) = (b, a % b);
+            }
+            a
+        }
+
+        fn div_ceil(a: $typ, b: $typ) -> $typ {
+            if 0 == a % b {
+                a / b
+            } else {
+                a / b + 1
+            }
+        }
+
+        /// Samples a value from `1..=max` whose bit length is uniformly
+        /// distributed, so that small values are as likely as large ones.
+        fn sample_log_uniform(runner: &mut TestRunner, max: $typ) -> $typ {
+            let bits = $typ::BITS - max.leading_zeros();
+            let bits = runner.rng().gen_range(1..=bits);
+            let lo: $typ = 1 << (bits - 1);
+            let hi = core::cmp::min(max, lo - 1 + lo);
+            runner.rng().gen_range(lo..=hi)
+        }
+
+        /// `Strategy` returned by `divisor_pair()`.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct DivisorPair {
+            lo: $typ,
+            hi: $typ,
+        }
+
+        /// Generates pairs `(n, d)` where `n` is within `range` and `d` is a
+        /// non-zero divisor of `n`.
+        ///
+        /// The pairs are built as `n = d * k`, with small and large divisors
+        /// being about equally likely. They shrink by first shrinking the
+        /// quotient `k` and then the divisor `d`, keeping `n` within `range`.
+        ///
+        /// Panics if `range` is empty.
+        pub fn divisor_pair(
+            range: impl ::core::ops::RangeBounds<$typ>,
+        ) -> DivisorPair {
+            let (lo, hi) = inclusive_bounds(range);
+            DivisorPair { lo, hi }
+        }
+
+        impl Strategy for DivisorPair {
+            type Tree = DivisorPairValueTree;
+            type Value = ($typ, $typ);
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let (lo, hi) = (self.lo, self.hi);
+                let max = core::cmp::max(hi, 1);
+                // Sample either the divisor or the quotient and fit the other
+                // into the range. Narrow ranges far from 0 may not have a
+                // multiple of the sampled value, so try a few times.
+                let mut pair = None;
+                for _ in 0..16 {
+                    let x = sample_log_uniform(runner, max);
+                    if runner.rng().gen() {
+                        let (k_lo, k_hi) = (div_ceil(lo, x), hi / x);
+                        if k_lo <= k_hi {
+                            let k = runner.rng().gen_range(k_lo..=k_hi);
+                            pair = Some((x, k));
+                            break;
+                        }
+                    } else {
+                        let d_lo = core::cmp::max(div_ceil(lo, x), 1);
+                        let d_hi = hi / x;
+                        if d_lo <= d_hi {
+                            let d = runner.rng().gen_range(d_lo..=d_hi);
+                            pair = Some((d, x));
+                            break;
+                        }
+                    }
+                }
+                let (d, k) = pair.unwrap_or_else(|| {
+                    let n = runner.rng().gen_range(lo..=hi);
+                    if 0 == n || runner.rng().gen() {
+                        (1, n)
+                    } else {
+                        (n, 1)
+                    }
+                });
+
+                Ok(DivisorPairValueTree {
+                    lo,
+                    divisor: BinarySearch::new_above(1, d),
+                    quotient: BinarySearch::new_above(div_ceil(lo, d), k),
+                    quotient_done: false,
+                })
+            }
+        }
+
+        /// `ValueTree` corresponding to `DivisorPair`.
+        #[derive(Clone, Copy, Debug)]
+        pub struct DivisorPairValueTree {
+            lo: $typ,
+            divisor: BinarySearch,
+            quotient: BinarySearch,
+            quotient_done: bool,
+        }
+
+        impl ValueTree for DivisorPairValueTree {
+            type Value = ($typ, $typ);
+
+            fn current(&self) -> ($typ, $typ) {
+                let d = self.divisor.current();
+                (d * self.quotient.current(), d)
+            }
+
+            fn simplify(&mut self) -> bool {
+                if !self.quotient_done {
+                    if self.quotient.simplify() {
+                        return true;
+                    }
+
+                    // Now that the quotient is final, the divisor can shrink
+                    // as long as their product stays within the range.
+                    self.quotient_done = true;
+                    let k = self.quotient.current();
+                    let min = if 0 == k {
+                        1
+                    } else {
+                        core::cmp::max(div_ceil(self.lo, k), 1)
+                    };
+                    self.divisor =
+                        BinarySearch::new_above(min, self.divisor.current());
+                }
+
+                self.divisor.simplify()
+            }
+
+            fn complicate(&mut self) -> bool {
+                if self.quotient_done {
+                    self.divisor.complicate()
+                } else {
+                    self.quotient.complicate()
+                }
+            }
+        }
+
+        /// `Strategy` returned by `coprime_pair()`.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct CoprimePair {
+            lo: $typ,
+            hi: $typ,
+        }
+
+        /// Generates pairs `(a, b)` of coprime values within `range`, i.e.
+        /// values whose greatest common divisor is 1.
+        ///
+        /// Both values shrink like the values of `range` do, skipping over
+        /// candidates which are not coprime.
+        ///
+        /// Panics if `range` contains no coprime pair, which is only the case
+        /// for empty ranges and ranges of a single value other than 1.
+        pub fn coprime_pair(
+            range: impl ::core::ops::RangeBounds<$typ>,
+        ) -> CoprimePair {
+            let (lo, hi) = inclusive_bounds(range);
+            if lo == hi && 1 != lo {
+                panic!("Range {}..={} contains no coprime pair", lo, hi);
+            }
+            CoprimePair { lo, hi }
+        }
+
+        impl Strategy for CoprimePair {
+            type Tree = CoprimePairValueTree;
+            type Value = ($typ, $typ);
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let (lo, hi) = (self.lo, self.hi);
+                // About 60% of random pairs are coprime, so this rarely needs
+                // to fall back to a pair of consecutive values.
+                let mut pair = None;
+                for _ in 0..16 {
+                    let a = runner.rng().gen_range(lo..=hi);
+                    let b = runner.rng().gen_range(lo..=hi);
+                    if 1 == gcd(a, b) {
+                        pair = Some((a, b));
+                        break;
+                    }
+                }
+                let (a, b) = pair.unwrap_or_else(|| {
+                    if lo == hi {
+                        (lo, hi)
+                    } else {
+                        let a = runner.rng().gen_range(lo..hi);
+                        (a, a + 1)
+                    }
+                });
+
+                Ok(CoprimePairValueTree(TupleValueTree::new((
+                    BinarySearch::new_above(lo, a),
+                    BinarySearch::new_above(lo, b),
+                ))))
+            }
+        }
+
+        /// `ValueTree` corresponding to `CoprimePair`.
+        #[derive(Clone, Copy, Debug)]
+        pub struct CoprimePairValueTree(
+            TupleValueTree<(BinarySearch, BinarySearch)>,
+        );
+
+        impl CoprimePairValueTree {
+            fn ensure_acceptable(&mut self) {
+                loop {
+                    let (a, b) = self.0.current();
+                    if 1 == gcd(a, b) {
+                        break;
+                    }
+                    if !self.0.complicate() {
+                        panic!(
+                            "Unable to complicate coprime pair back into \
+                             coprime values"
+                        );
+                    }
+                }
+            }
+        }
+
+        impl ValueTree for CoprimePairValueTree {
+            type Value = ($typ, $typ);
+
+            fn current(&self) -> ($typ, $typ) {
+                self.0.current()
+            }
+
+            fn simplify(&mut self) -> bool {
+                if self.0.simplify() {
+                    self.ensure_acceptable();
+                    true
+                } else {
+                    false
+                }
+            }
+
+            fn complicate(&mut self) -> bool {
+                if self.0.complicate() {
+                    self.ensure_acceptable();
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    };
+}
+
 macro_rules! signed_integer_bin_search {
     ($typ:ident) => {
         #[allow(missing_docs)]
         pub mod $typ {
             use rand::Rng;
 
+            use super::{IntShrinkMode, IntValueTree};
             use crate::strategy::*;
             use crate::test_runner::TestRunner;
+            use crate::tuple::TupleValueTree;
 
             int_any!($typ);
 
@@ -189,6 +683,8 @@ macro_rules! signed_integer_bin_search {
                 lo: $typ,
                 curr: $typ,
                 hi: $typ,
+                mode: IntShrinkMode,
+                step: $typ,
             }
             impl BinarySearch {
                 /// Creates a new binary searcher starting at the given value.
@@ -197,13 +693,19 @@ macro_rules! signed_integer_bin_search {
                         lo: 0,
                         curr: start,
                         hi: start,
+                        mode: IntShrinkMode::Bisect,
+                        step: 0,
                     }
                 }
 
                 /// Creates a new binary searcher which will not produce values
                 /// on the other side of `lo` or `hi` from `start`. `lo` is
                 /// inclusive, `hi` is exclusive.
-                fn new_clamped(lo: $typ, start: $typ, hi: $typ) -> Self {
+                pub(crate) fn new_clamped(
+                    lo: $typ,
+                    start: $typ,
+                    hi: $typ,
+                ) -> Self {
                     use core::cmp::{max, min};
 
                     BinarySearch {
@@ -214,9 +716,32 @@ macro_rules! signed_integer_bin_search {
                         },
                         hi: start,
                         curr: start,
+                        mode: IntShrinkMode::Bisect,
+                        step: 0,
                     }
                 }
 
+                /// Returns this searcher with its shrinking switched to
+                /// `mode`. This should be done before shrinking starts.
+                pub fn with_shrink_mode(mut self, mode: IntShrinkMode) -> Self {
+                    self.mode = mode;
+                    self
+                }
+
+                /// Moves `curr` from `hi` towards `lo` by `step`, without
+                /// passing `lo`. Returns whether `curr` changed.
+                fn step_towards_lo(&mut self) -> bool {
+                    let prev = self.curr;
+                    self.curr = if self.hi < 0 {
+                        let next = self.hi.saturating_add(self.step);
+                        core::cmp::min(next, self.lo)
+                    } else {
+                        let next = self.hi.saturating_sub(self.step);
+                        core::cmp::max(next, self.lo)
+                    };
+                    self.curr != prev
+                }
+
                 fn reposition(&mut self) -> bool {
                     // Won't ever overflow since lo starts at 0 and advances
                     // towards hi.
@@ -254,7 +779,11 @@ macro_rules! signed_integer_bin_search {
                     }
 
                     self.hi = self.curr;
-                    self.reposition()
+                    if 0 == self.step {
+                        self.reposition()
+                    } else {
+                        self.step_towards_lo()
+                    }
                 }
 
                 fn complicate(&mut self) -> bool {
@@ -262,13 +791,41 @@ macro_rules! signed_integer_bin_search {
                         return false;
                     }
 
+                    if IntShrinkMode::Adaptive == self.mode {
+                        // The first pass ends the bisection; from then on,
+                        // step down from the smallest failure, halving the
+                        // step on every pass.
+                        let distance = if self.hi < 0 {
+                            self.curr - self.hi
+                        } else {
+                            self.hi - self.curr
+                        };
+                        let step = if 0 == self.step {
+                            distance / 2
+                        } else {
+                            self.step / 2
+                        };
+                        self.step = core::cmp::max(step, 1);
+                    }
+
                     self.lo = self.curr + if self.hi < 0 { -1 } else { 1 };
 
-                    self.reposition()
+                    if 0 == self.step {
+                        self.reposition()
+                    } else {
+                        self.step_towards_lo()
+                    }
+                }
+            }
+
+            impl IntValueTree for BinarySearch {
+                fn set_shrink_mode(&mut self, mode: IntShrinkMode) {
+                    self.mode = mode;
                 }
             }
 
             numeric_api!($typ, 1);
+            int_pair_api!($typ);
         }
     };
 }
@@ -279,8 +836,10 @@ macro_rules! unsigned_integer_bin_search {
         pub mod $typ {
             use rand::Rng;
 
+            use super::{IntShrinkMode, IntValueTree};
             use crate::strategy::*;
             use crate::test_runner::TestRunner;
+            use crate::tuple::TupleValueTree;
 
             int_any!($typ);
 
@@ -291,6 +850,8 @@ macro_rules! unsigned_integer_bin_search {
                 lo: $typ,
                 curr: $typ,
                 hi: $typ,
+                mode: IntShrinkMode,
+                step: $typ,
             }
             impl BinarySearch {
                 /// Creates a new binary searcher starting at the given value.
@@ -299,6 +860,8 @@ macro_rules! unsigned_integer_bin_search {
                         lo: 0,
                         curr: start,
                         hi: start,
+                        mode: IntShrinkMode::Bisect,
+                        step: 0,
                     }
                 }
 
@@ -309,6 +872,8 @@ macro_rules! unsigned_integer_bin_search {
                         lo: lo,
                         curr: start,
                         hi: start,
+                        mode: IntShrinkMode::Bisect,
+                        step: 0,
                     }
                 }
 
@@ -318,6 +883,24 @@ macro_rules! unsigned_integer_bin_search {
                     BinarySearch::new_clamped(lo, start, start)
                 }
 
+                /// Returns this searcher with its shrinking switched to
+                /// `mode`. This should be done before shrinking starts.
+                pub fn with_shrink_mode(mut self, mode: IntShrinkMode) -> Self {
+                    self.mode = mode;
+                    self
+                }
+
+                /// Moves `curr` from `hi` down by `step`, without passing
+                /// `lo`. Returns whether `curr` changed.
+                fn step_towards_lo(&mut self) -> bool {
+                    let prev = self.curr;
+                    self.curr = core::cmp::max(
+                        self.hi.saturating_sub(self.step),
+                        self.lo,
+                    );
+                    self.curr != prev
+                }
+
                 fn reposition(&mut self) -> bool {
                     let interval = self.hi - self.lo;
                     let new_mid = self.lo + interval / 2;
@@ -343,7 +926,11 @@ macro_rules! unsigned_integer_bin_search {
                     }
 
                     self.hi = self.curr;
-                    self.reposition()
+                    if 0 == self.step {
+                        self.reposition()
+                    } else {
+                        self.step_towards_lo()
+                    }
                 }
 
                 fn complicate(&mut self) -> bool {
@@ -351,12 +938,36 @@ macro_rules! unsigned_integer_bin_search {
                         return false;
                     }
 
+                    if IntShrinkMode::Adaptive == self.mode {
+                        // The first pass ends the bisection; from then on,
+                        // step down from the smallest failure, halving the
+                        // step on every pass.
+                        let step = if 0 == self.step {
+                            (self.hi - self.curr) / 2
+                        } else {
+                            self.step / 2
+                        };
+                        self.step = core::cmp::max(step, 1);
+                    }
+
                     self.lo = self.curr + 1;
-                    self.reposition()
+                    if 0 == self.step {
+                        self.reposition()
+                    } else {
+                        self.step_towards_lo()
+                    }
+                }
+            }
+
+            impl IntValueTree for BinarySearch {
+                fn set_shrink_mode(&mut self, mode: IntShrinkMode) {
+                    self.mode = mode;
                 }
             }
 
             numeric_api!($typ, 1);
+            int_pair_api!($typ);
+            uint_pair_api!($typ);
         }
     };
 }
@@ -696,8 +1307,264 @@ macro_rules! float_any {
     }
 }
 
+macro_rules! float_precision {
+    ($typ:ident, $ityp:ident) => {
+        /// Maps `v` to an integer such that adjacent floats map to adjacent
+        /// integers in the same order. Both zeros map to 0.
+        pub(crate) fn to_ordered(v: $typ) -> i64 {
+            let bits = v.to_bits() as $ityp;
+            i64::from(if bits < 0 { $ityp::MIN - bits } else { bits })
+        }
+
+        /// Inverse of `to_ordered()`.
+        fn from_ordered(key: i64) -> $typ {
+            let key = key as $ityp;
+            let bits = if key < 0 { $ityp::MIN - key } else { key };
+            $typ::from_bits(bits as _)
+        }
+
+        /// Strategy returned by `within_ulps_of()`.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct WithinUlps {
+            target: $typ,
+            ulps: u32,
+        }
+
+        /// Generates finite values at most `ulps` units in the last place
+        /// away from `target`, i.e., values reached by stepping from `target`
+        /// to an adjacent representable value at most `ulps` times.
+        ///
+        /// This is useful for exercising numerics code in the neighbourhood
+        /// of an interesting value, which uniform sampling over a range will
+        /// almost never hit. Values shrink towards `target`.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if `target` is not finite.
+        pub fn within_ulps_of(target: $typ, ulps: u32) -> WithinUlps {
+            if !target.is_finite() {
+                panic!("Invalid use of non-finite target {}.", target);
+            }
+
+            WithinUlps { target, ulps }
+        }
+
+        impl Strategy for WithinUlps {
+            type Tree = WithinUlpsValueTree;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let ulps = i64::from(self.ulps);
+                let offset = runner.rng().gen_range(-ulps..=ulps);
+                Ok(WithinUlpsValueTree {
+                    target: self.target,
+                    offset: crate::num::i64::BinarySearch::new(offset),
+                })
+            }
+        }
+
+        /// `ValueTree` corresponding to `WithinUlps`.
+        #[derive(Clone, Copy, Debug)]
+        pub struct WithinUlpsValueTree {
+            target: $typ,
+            offset: crate::num::i64::BinarySearch,
+        }
+
+        impl ValueTree for WithinUlpsValueTree {
+            type Value = $typ;
+
+            fn current(&self) -> $typ {
+                let offset = self.offset.current();
+                if 0 == offset {
+                    return self.target;
+                }
+
+                // Stop at the largest finite values rather than stepping into
+                // infinity or NaN.
+                let key = (to_ordered(self.target) + offset)
+                    .clamp(to_ordered(-$typ::MAX), to_ordered($typ::MAX));
+                from_ordered(key)
+            }
+
+            fn simplify(&mut self) -> bool {
+                self.offset.simplify()
+            }
+
+            fn complicate(&mut self) -> bool {
+                self.offset.complicate()
+            }
+        }
+
+        /// Strategy returned by `with_decimal_digits()`.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct DecimalDigits {
+            min: u32,
+            max: u32,
+        }
+
+        /// Generates finite values which are printed (via `Display`) with at
+        /// least `min` and at most `max` digits after the decimal point, and
+        /// with at most `DIGITS` significant digits in total.
+        ///
+        /// This is useful for code parsing, formatting or rounding decimal
+        /// numbers, e.g. prices or measurements, where arbitrary floats almost
+        /// always have the maximum precision. Values shrink towards fewer
+        /// digits, smaller magnitudes, and positive values.
+        ///
+        /// ## Panics
+        ///
+        /// Panics if `min > max` or `max > DIGITS`.
+        pub fn with_decimal_digits(min: u32, max: u32) -> DecimalDigits {
+            if min > max || max > $typ::DIGITS {
+                panic!(
+                    "Invalid decimal digits {}..={} (at most {} supported).",
+                    min,
+                    max,
+                    $typ::DIGITS
+                );
+            }
+
+            DecimalDigits { min, max }
+        }
+
+        /// The number of decimals, the mantissa without its last digit, the
+        /// last digit, and whether the value is negative.
+        type DecimalParts = (
+            ops::RangeInclusive<u32>,
+            ops::Range<u64>,
+            ops::RangeInclusive<u8>,
+            crate::bool::Any,
+        );
+
+        impl Strategy for DecimalDigits {
+            type Tree = DecimalDigitsValueTree;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                let parts: DecimalParts = (
+                    self.min..=self.max,
+                    0..10u64.pow($typ::DIGITS - 1),
+                    0..=9,
+                    crate::bool::ANY,
+                );
+                parts.new_tree(runner).map(DecimalDigitsValueTree)
+            }
+        }
+
+        /// `ValueTree` corresponding to `DecimalDigits`.
+        #[derive(Clone, Debug)]
+        pub struct DecimalDigitsValueTree(<DecimalParts as Strategy>::Tree);
+
+        impl ValueTree for DecimalDigitsValueTree {
+            type Value = $typ;
+
+            fn current(&self) -> $typ {
+                let (decimals, high, last, negative) = self.0.current();
+                // A trailing zero would not be printed, and so would reduce
+                // the number of digits after the decimal point.
+                let last = if decimals > 0 && 0 == last { 1 } else { last };
+                let mantissa = high * 10 + u64::from(last);
+
+                // Both the mantissa and the power of ten are exactly
+                // representable, so the division rounds to the float closest
+                // to the decimal value, which is printed as exactly that
+                // decimal since it has at most `DIGITS` significant digits.
+                let mut scale: $typ = 1.0;
+                for _ in 0..decimals {
+                    scale *= 10.0;
+                }
+                let value = mantissa as $typ / scale;
+                if negative && 0 != mantissa {
+                    -value
+                } else {
+                    value
+                }
+            }
+
+            fn simplify(&mut self) -> bool {
+                self.0.simplify()
+            }
+
+            fn complicate(&mut self) -> bool {
+                self.0.complicate()
+            }
+        }
+    };
+}
+
+macro_rules! float_finite {
+    ($typ:ident) => {
+        /// Generates any finite value, i.e., anything but NaN and the
+        /// infinities. Values shrink towards 0.0.
+        ///
+        /// This is a shorthand for `POSITIVE | NEGATIVE | NORMAL | SUBNORMAL
+        /// | ZERO`, and is usually what tests of numeric code want instead of
+        /// `any::<_>()`, which also generates NaN and the infinities.
+        pub fn finite() -> Any {
+            POSITIVE | NEGATIVE | NORMAL | SUBNORMAL | ZERO
+        }
+
+        /// Generates any finite value greater than 0.0. Values shrink towards
+        /// the smallest positive value.
+        ///
+        /// This is a shorthand for `POSITIVE | NORMAL | SUBNORMAL`.
+        pub fn positive_finite() -> Any {
+            POSITIVE | NORMAL | SUBNORMAL
+        }
+
+        /// Strategy returned by `normal_in()`.
+        #[derive(Clone, Copy, Debug)]
+        #[must_use = "strategies do nothing unless used"]
+        pub struct NormalIn<R> {
+            range: R,
+        }
+
+        /// Generates values of `range`, which is one of the range types
+        /// (e.g., `-1.0..1.0`), except for subnormal values. That is, values
+        /// are either zero or have the full precision of the type.
+        ///
+        /// Subnormal values generated by `range` are locally rejected, and
+        /// shrinking skips them. Values shrink towards 0.0 if `range`
+        /// contains it, and towards the bound of `range` closest to 0.0
+        /// otherwise.
+        ///
+        /// Generation fails if `range` contains only subnormal values.
+        pub fn normal_in<R>(range: R) -> NormalIn<R>
+        where
+            R: Strategy<Tree = BinarySearch, Value = $typ>,
+        {
+            NormalIn { range }
+        }
+
+        impl<R> Strategy for NormalIn<R>
+        where
+            R: Strategy<Tree = BinarySearch, Value = $typ>,
+        {
+            type Tree = BinarySearch;
+            type Value = $typ;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                loop {
+                    let mut tree = self.range.new_tree(runner)?;
+                    if !tree.current().is_subnormal() {
+                        tree.allowed = FloatTypes::POSITIVE
+                            | FloatTypes::NEGATIVE
+                            | FloatTypes::NORMAL
+                            | FloatTypes::ZERO;
+                        return Ok(tree);
+                    }
+
+                    runner.reject_local("subnormal value in normal_in()")?;
+                }
+            }
+        }
+    };
+}
+
 macro_rules! float_bin_search {
-    ($typ:ident, $sample_typ:ident) => {
+    ($typ:ident, $sample_typ:ident, $ityp:ident) => {
         #[allow(missing_docs)]
         pub mod $typ {
             use super::float_samplers::$sample_typ;
@@ -878,15 +1745,19 @@ macro_rules! float_bin_search {
             }
 
             numeric_api!($typ, $sample_typ, 0.0);
+            float_precision!($typ, $ityp);
+            float_finite!($typ);
         }
     };
 }
 
-float_bin_search!(f32, F32U);
-float_bin_search!(f64, F64U);
+float_bin_search!(f32, F32U, i32);
+float_bin_search!(f64, F64U, i64);
 
 #[cfg(test)]
 mod test {
+    use rand::Rng;
+
     use crate::strategy::*;
     use crate::test_runner::*;
 
@@ -989,6 +1860,218 @@ mod test {
         }
     }
 
+    #[test]
+    fn i8_adaptive_search_always_converges() {
+        fn assert_converges<P: Fn(i32) -> bool>(start: i8, pass: P) {
+            let mut state = i8::BinarySearch::new(start)
+                .with_shrink_mode(IntShrinkMode::Adaptive);
+            loop {
+                if !pass(state.current() as i32) {
+                    if !state.simplify() {
+                        break;
+                    }
+                } else {
+                    if !state.complicate() {
+                        break;
+                    }
+                }
+            }
+
+            assert!(!pass(state.current() as i32));
+            assert!(
+                pass(state.current() as i32 - 1)
+                    || pass(state.current() as i32 + 1)
+            );
+        }
+
+        for start in -128..0 {
+            for target in start + 1..1 {
+                assert_converges(start as i8, |v| v > target);
+            }
+        }
+
+        for start in 0..128 {
+            for target in 0..start {
+                assert_converges(start as i8, |v| v < target);
+            }
+        }
+    }
+
+    #[test]
+    fn u8_adaptive_search_always_converges() {
+        fn assert_converges<P: Fn(u32) -> bool>(start: u8, pass: P) {
+            let mut state = u8::BinarySearch::new(start)
+                .with_shrink_mode(IntShrinkMode::Adaptive);
+            loop {
+                if !pass(state.current() as u32) {
+                    if !state.simplify() {
+                        break;
+                    }
+                } else {
+                    if !state.complicate() {
+                        break;
+                    }
+                }
+            }
+
+            assert!(!pass(state.current() as u32));
+            assert!(pass(state.current() as u32 - 1));
+        }
+
+        for start in 0..255 {
+            for target in 0..start {
+                assert_converges(start as u8, |v| v <= target);
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_shrinking_finds_lower_edge_of_failing_region() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..32 {
+            let tree =
+                with_shrink_mode(-5000i32..-1000i32, IntShrinkMode::Adaptive)
+                    .new_tree(&mut runner)
+                    .unwrap();
+            let start = tree.current();
+            let result = runner.run_one(tree, |v| {
+                prop_assert!(v > -2000 || v <= -3000);
+                Ok(())
+            });
+
+            match result {
+                Err(TestError::Fail(_, v)) => {
+                    assert!(start <= -2000 && start > -3000);
+                    assert_eq!(-2000, v);
+                }
+                result => assert_eq!(Ok(true), result),
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_shrinking_upholds_contract() {
+        for &mode in &[IntShrinkMode::Bisect, IntShrinkMode::Adaptive] {
+            check_strategy_sanity(with_shrink_mode(0u32..1000u32, mode), None);
+            check_strategy_sanity(with_shrink_mode(-500i32..500, mode), None);
+            check_strategy_sanity(with_shrink_mode(i64::ANY, mode), None);
+        }
+    }
+
+    /// Generates pairs from `strat` and shrinks them with random passes and
+    /// failures, checking that every pair upholds `valid`.
+    fn check_pairs<S, T>(strat: S, valid: impl Fn(T, T) -> bool)
+    where
+        S: Strategy<Value = (T, T)>,
+        T: Copy + ::std::fmt::Debug,
+    {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = strat.new_tree(&mut runner).unwrap();
+            loop {
+                let (a, b) = tree.current();
+                assert!(valid(a, b), "Invalid pair ({:?}, {:?})", a, b);
+                if !tree.simplify() {
+                    break;
+                }
+
+                let (a, b) = tree.current();
+                assert!(valid(a, b), "Invalid pair ({:?}, {:?})", a, b);
+                if runner.rng().gen() {
+                    tree.complicate();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ordered_pairs_are_ordered_and_within_range() {
+        check_pairs(i32::ordered_pair(-100..100), |a, b| {
+            -100 <= a && a <= b && b < 100
+        });
+        check_pairs(u8::ordered_pair(..), |a, b| a <= b);
+        check_strategy_sanity(i32::ordered_pair(-100..100), None);
+
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&i32::ordered_pair(-100..100), |(a, b)| {
+            prop_assert!(b - a < 50);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, (a, b))) => assert_eq!(50, b - a),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn divisor_pairs_divide_and_stay_within_range() {
+        check_pairs(u64::divisor_pair(1000..2000), |n, d| {
+            (1000..2000).contains(&n) && 0 == n % d
+        });
+        check_pairs(u8::divisor_pair(..=100), |n, d| {
+            n <= 100 && d > 0 && 0 == n % d
+        });
+        check_pairs(u32::divisor_pair(1_000_000..=1_000_010), |n, d| {
+            (1_000_000..=1_000_010).contains(&n) && 0 == n % d
+        });
+        check_strategy_sanity(u64::divisor_pair(1000..2000), None);
+
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&u64::divisor_pair(0..1000), |(n, d)| {
+            prop_assert!(n < 100 || d < 3);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, (n, d))) => {
+                assert!(n >= 100 && d >= 3 && 0 == n % d);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn coprime_pairs_are_coprime() {
+        fn gcd(a: u32, b: u32) -> u32 {
+            if 0 == b {
+                a
+            } else {
+                gcd(b, a % b)
+            }
+        }
+
+        check_pairs(u32::coprime_pair(..), |a, b| 1 == gcd(a, b));
+        check_pairs(u32::coprime_pair(6..=7), |a, b| 1 == gcd(a, b));
+        check_pairs(u32::coprime_pair(1..=1), |a, b| (1, 1) == (a, b));
+        // Like filtering, skipping pairs which are not coprime can complicate
+        // back to the last failing pair right away.
+        check_strategy_sanity(
+            u32::coprime_pair(..),
+            Some(CheckStrategySanityOptions {
+                strict_complicate_after_simplify: false,
+                ..CheckStrategySanityOptions::default()
+            }),
+        );
+
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&u32::coprime_pair(10..1000), |(a, b)| {
+            prop_assert!(a + b < 100);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, (a, b))) => {
+                assert!(a + b >= 100 && a >= 10 && b >= 10);
+                assert_eq!(1, gcd(a, b));
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "contains no coprime pair")]
+    fn coprime_pair_panics_without_coprime_values() {
+        let _ = u64::coprime_pair(4..=4);
+    }
+
     #[test]
     fn signed_integer_range_including_zero_converges_to_zero() {
         let mut runner = TestRunner::default();
@@ -1404,6 +2487,129 @@ mod test {
         }
     }
 
+    macro_rules! float_precision_tests {
+        ($typ:ident, $within:ident, $decimals:ident) => {
+            #[test]
+            fn $within() {
+                let mut runner = TestRunner::deterministic();
+                for &target in &[0.0, 1.0, -0.1, $typ::MAX] {
+                    let strat = $typ::within_ulps_of(target, 3);
+                    for _ in 0..256 {
+                        let mut tree = strat.new_tree(&mut runner).unwrap();
+                        let v = tree.current();
+                        assert!(v.is_finite(), "{} is not finite", v);
+                        let distance = ($typ::to_ordered(v)
+                            - $typ::to_ordered(target))
+                        .abs();
+                        assert!(distance <= 3, "{} too far from {}", v, target);
+
+                        while tree.simplify() {}
+                        assert_eq!(target, tree.current());
+                    }
+                }
+            }
+
+            #[test]
+            fn $decimals() {
+                let strat = $typ::with_decimal_digits(1, 3);
+                let mut runner = TestRunner::deterministic();
+                for _ in 0..1024 {
+                    let mut tree = strat.new_tree(&mut runner).unwrap();
+                    let printed = format!("{}", tree.current());
+                    let decimals = printed
+                        .split('.')
+                        .nth(1)
+                        .unwrap_or_else(|| {
+                            panic!("{} has no decimals", printed)
+                        })
+                        .len();
+                    assert!((1..=3).contains(&decimals), "{}", printed);
+
+                    while tree.simplify() {}
+                    assert_eq!(0.1, tree.current());
+                }
+
+                check_strategy_sanity(strat, None);
+            }
+        };
+    }
+
+    float_precision_tests!(f32, f32_within_ulps_of, f32_with_decimal_digits);
+    float_precision_tests!(f64, f64_within_ulps_of, f64_with_decimal_digits);
+
+    #[test]
+    fn within_ulps_of_steps_to_adjacent_values() {
+        let mut runner = TestRunner::deterministic();
+        let strat = f64::within_ulps_of(1.0, 1);
+        let mut seen = ::std::collections::BTreeSet::new();
+        for _ in 0..256 {
+            seen.insert(
+                strat.new_tree(&mut runner).unwrap().current().to_bits(),
+            );
+        }
+        let expected: ::std::collections::BTreeSet<_> =
+            [1.0 - f64::EPSILON / 2.0, 1.0, 1.0 + f64::EPSILON]
+                .iter()
+                .map(|v| v.to_bits())
+                .collect();
+        assert_eq!(expected, seen);
+    }
+
+    #[test]
+    fn finite_floats_are_finite_and_shrink_to_zero() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = finite::<f64>().new_tree(&mut runner).unwrap();
+            assert!(tree.current().is_finite());
+            while tree.simplify() {
+                assert!(tree.current().is_finite());
+            }
+            assert_eq!(0.0, tree.current());
+        }
+    }
+
+    #[test]
+    fn positive_finite_floats_are_positive_and_finite() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree =
+                f32::positive_finite().new_tree(&mut runner).unwrap();
+            loop {
+                let value = tree.current();
+                assert!(value.is_finite() && value > 0.0, "{}", value);
+                if !tree.simplify() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn normal_in_never_yields_subnormals() {
+        let mut runner = TestRunner::deterministic();
+        let strat = f64::normal_in(-1e-300..1e-300);
+        for _ in 0..256 {
+            let mut tree = strat.new_tree(&mut runner).unwrap();
+            loop {
+                let value = tree.current();
+                assert!(!value.is_subnormal(), "{:e}", value);
+                assert!((-1e-300..1e-300).contains(&value));
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!(0.0, tree.current());
+        }
+    }
+
+    #[test]
+    fn normal_in_fails_on_subnormal_range() {
+        let mut runner = TestRunner::deterministic();
+        assert!(f64::normal_in(1e-310..2e-310)
+            .new_tree(&mut runner)
+            .is_err());
+    }
+
     mod panic_on_empty {
         macro_rules! panic_on_empty {
             ($t:tt) => {
@@ -0,0 +1,176 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Approximate equality of floats, as used by `prop_assert_close!`.
+//!
+//! Two values are always close if they are equal (including infinities of
+//! the same sign), and never if either is NaN. Otherwise, closeness is
+//! decided by one of the measures of `Tolerance`.
+
+use core::fmt;
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// The float types which can be compared with this module, i.e. `f32` and
+/// `f64`.
+pub trait Float: sealed::Sealed + Copy + PartialEq + fmt::Debug {
+    /// Returns the number of representable values of this type between
+    /// `self` and `other`, or `None` if either is NaN.
+    fn ulps_between(self, other: Self) -> Option<u64>;
+
+    /// Converts `self` to `f64` without loss.
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! float_impl {
+    ($typ:ident) => {
+        impl Float for $typ {
+            fn ulps_between(self, other: Self) -> Option<u64> {
+                if self.is_nan() || other.is_nan() {
+                    return None;
+                }
+                let a = crate::num::$typ::to_ordered(self);
+                let b = crate::num::$typ::to_ordered(other);
+                Some((i128::from(a) - i128::from(b)).unsigned_abs() as u64)
+            }
+
+            fn to_f64(self) -> f64 {
+                f64::from(self)
+            }
+        }
+    };
+}
+
+float_impl!(f32);
+float_impl!(f64);
+
+/// The largest difference for which two floats are still considered close.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tolerance {
+    /// At most this many representable values apart.
+    Ulps(u64),
+    /// The absolute difference is at most this.
+    Abs(f64),
+    /// The absolute difference, divided by the larger magnitude of the two
+    /// values, is at most this.
+    Rel(f64),
+}
+
+impl fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Tolerance::Ulps(ulps) => write!(f, "ulps <= {}", ulps),
+            Tolerance::Abs(abs) => write!(f, "abs <= {:e}", abs),
+            Tolerance::Rel(rel) => write!(f, "rel <= {:e}", rel),
+        }
+    }
+}
+
+/// The differences between two floats under every measure of `Tolerance`.
+///
+/// The `Display` form lists all of them, for use in failure messages.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Difference {
+    /// The distance in ULPs, or `None` if either value is NaN.
+    pub ulps: Option<u64>,
+    /// The absolute difference.
+    pub abs: f64,
+    /// The absolute difference relative to the larger magnitude.
+    pub rel: f64,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.ulps {
+            Some(ulps) => write!(f, "ulps = {}", ulps)?,
+            None => write!(f, "ulps = NaN")?,
+        }
+        write!(f, ", abs = {:e}, rel = {:e}", self.abs, self.rel)
+    }
+}
+
+/// `f64::abs()`, which is not available in `core`.
+fn abs(v: f64) -> f64 {
+    f64::from_bits(v.to_bits() & !(1 << 63))
+}
+
+/// Computes the differences between `a` and `b`.
+pub fn difference<F: Float>(a: F, b: F) -> Difference {
+    let (x, y) = (a.to_f64(), b.to_f64());
+    let abs_diff = if a == b { 0.0 } else { abs(x - y) };
+    let magnitude = if abs(x) > abs(y) { abs(x) } else { abs(y) };
+    Difference {
+        ulps: a.ulps_between(b),
+        abs: abs_diff,
+        rel: if 0.0 == abs_diff {
+            0.0
+        } else {
+            abs_diff / magnitude
+        },
+    }
+}
+
+/// Returns whether `a` and `b` are within `tolerance` of each other.
+pub fn is_close<F: Float>(a: F, b: F, tolerance: Tolerance) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let diff = difference(a, b);
+    match tolerance {
+        Tolerance::Ulps(ulps) => diff.ulps.map_or(false, |d| d <= ulps),
+        Tolerance::Abs(abs) => diff.abs <= abs,
+        Tolerance::Rel(rel) => diff.rel <= rel,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn ulps_between_counts_representable_values() {
+        assert_eq!(Some(0), 0.0f64.ulps_between(-0.0));
+        assert_eq!(Some(1), 1.0f64.ulps_between(1.0 + f64::EPSILON));
+        assert_eq!(Some(2), f32::from_bits(1).ulps_between(-f32::from_bits(1)));
+        assert_eq!(None, f64::NAN.ulps_between(1.0));
+    }
+
+    #[test]
+    fn is_close_honours_each_tolerance() {
+        assert!(is_close(
+            1.0f64,
+            1.0 + 2.0 * f64::EPSILON,
+            Tolerance::Ulps(2)
+        ));
+        assert!(!is_close(
+            1.0f64,
+            1.0 + 3.0 * f64::EPSILON,
+            Tolerance::Ulps(2)
+        ));
+        assert!(is_close(100.0f64, 100.5, Tolerance::Abs(0.5)));
+        assert!(!is_close(100.0f64, 100.5, Tolerance::Rel(1e-3)));
+        assert!(is_close(1e10f64, 1e10 + 1.0, Tolerance::Rel(1e-9)));
+        assert!(is_close(f64::INFINITY, f64::INFINITY, Tolerance::Abs(0.0)));
+        assert!(!is_close(f64::NAN, f64::NAN, Tolerance::Abs(f64::INFINITY)));
+    }
+
+    #[test]
+    fn difference_display_shows_all_measures() {
+        assert_eq!(
+            "ulps = 2251799813685248, abs = 5e-1, rel = 3.333333333333333e-1",
+            difference(1.0f64, 1.5).to_string()
+        );
+    }
+}
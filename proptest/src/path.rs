@@ -2,8 +2,17 @@
 //!
 //! [`PathParams`] in this module is used as the argument to the
 //! [`Arbitrary`](crate::arbitrary::Arbitrary) implementation for [`PathBuf`].
+//!
+//! [`windows_path`] generates Windows-style paths as strings on any host.
 
-use crate::{collection::SizeRange, string::StringParam};
+use crate::{
+    arbitrary::any_with,
+    collection::{vec, SizeRange},
+    option,
+    prelude::{any, Just, Strategy},
+    std_facade::String,
+    string::StringParam,
+};
 
 /// Parameters for the [`Arbitrary`] implementation for [`PathBuf`].
 ///
@@ -53,3 +62,140 @@ impl Default for PathParams {
         }
     }
 }
+
+/// Generates Windows-style paths as `String`s, independently of the host
+/// platform, so that code handling Windows paths can be tested anywhere.
+///
+/// The components are generated according to `params`, as for the
+/// `Arbitrary` implementation for [`PathBuf`]. In addition, the paths
+/// feature:
+///
+/// * Prefixes: drive letters (`C:` and `C:\`), rooted paths (`\`), UNC shares
+///   (`\\server\share\`), and the verbatim (`\\?\`) and device (`\\.\`)
+///   namespaces
+/// * Reserved device names such as `CON`, `nul.txt` or `LPT1` as components
+/// * `.` and `..` components
+/// * Components with trailing dots and spaces, which Windows strips
+/// * Both `\` and `/` as separators, mixed within a single path, as well as
+///   trailing separators
+///
+/// Shrinking removes the prefix and components, and the rest of the
+/// features, as far as possible.
+///
+/// [`PathBuf`]: std::path::PathBuf
+pub fn windows_path(params: PathParams) -> impl Strategy<Value = String> {
+    let name = any_with::<String>(params.component_regex()).prop_map(|s| {
+        s.chars()
+            .filter(|&c| c != '\\' && c != '/')
+            .collect::<String>()
+    });
+    let component = (
+        prop_oneof![
+            6 => name,
+            1 => r"(?i:CON|PRN|AUX|NUL|COM[1-9]|LPT[1-9])(\.[a-z]{1,3})?",
+            1 => prop_oneof![Just(".".into()), Just("..".into())],
+        ],
+        prop_oneof![4 => Just(String::new()), 1 => "[. ]{1,3}"],
+    )
+        .prop_map(|(name, trailing)| name + &trailing);
+
+    (
+        windows_prefix(),
+        vec((component, windows_separator()), params.components()),
+        any::<bool>(),
+    )
+        .prop_map(|(prefix, components, trailing_separator)| {
+            let mut out = prefix;
+            let count = components.len();
+            for (i, (component, separator)) in
+                components.into_iter().enumerate()
+            {
+                out.push_str(&component);
+                if i + 1 < count || trailing_separator {
+                    out.push(separator);
+                }
+            }
+            out
+        })
+}
+
+fn windows_separator() -> impl Strategy<Value = char> {
+    prop_oneof![3 => Just('\\'), 2 => Just('/')]
+}
+
+fn windows_prefix() -> impl Strategy<Value = String> {
+    let host = "[a-zA-Z0-9_-]{1,8}";
+    prop_oneof![
+        3 => Just(String::new()),
+        2 => ("[a-zA-Z]:", option::of(windows_separator())).prop_map(
+            |(mut drive, separator)| {
+                drive.extend(separator);
+                drive
+            }
+        ),
+        1 => windows_separator().prop_map(String::from),
+        1 => (windows_separator(), windows_separator(), host, host).prop_map(
+            |(a, b, server, share)| format!("{}{}{}\\{}\\", a, b, server, share)
+        ),
+        1 => prop_oneof![
+            r"\\\\\?\\[a-zA-Z]:\\",
+            (host, host).prop_map(|(server, share)| {
+                format!(r"\\?\UNC\{}\{}\", server, share)
+            }),
+        ],
+        1 => r"\\\\\.\\(COM[1-9]|PhysicalDrive[0-9]|pipe\\[a-z]{1,8})\\",
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::strategy::ValueTree;
+    use crate::test_runner::TestRunner;
+
+    fn device_paths() -> Vec<String> {
+        let strategy =
+            windows_path(PathParams::default().with_components(1..4));
+        let mut runner = TestRunner::deterministic();
+        (0..1024)
+            .map(|_| strategy.new_tree(&mut runner).unwrap().current())
+            .filter(|path| path.starts_with(r"\\.\"))
+            .collect()
+    }
+
+    #[test]
+    fn device_names_are_followed_by_separator() {
+        let device = regex::Regex::new(
+            r"^\\\\\.\\(COM[1-9]|PhysicalDrive[0-9]|pipe\\[a-z]{1,8})\\",
+        )
+        .unwrap();
+        let paths = device_paths();
+        assert!(!paths.is_empty());
+        for path in paths {
+            assert!(device.is_match(&path), "{:?}", path);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn device_paths_parse_as_prefix_and_root() {
+        use std::path::{Component, Path};
+
+        for path in device_paths() {
+            let mut components = Path::new(&path).components();
+            assert!(
+                matches!(components.next(), Some(Component::Prefix(_))),
+                "{:?}",
+                path
+            );
+            assert_eq!(
+                Some(Component::RootDir),
+                components.next(),
+                "{:?}",
+                path
+            );
+        }
+    }
+}
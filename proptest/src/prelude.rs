@@ -18,15 +18,19 @@
 //! functionality that exposes random number generators. Please note that this
 //! is will always be a direct reexport; using these in preference to using the
 //! `rand` crate directly will not provide insulation from the upcoming
-//! revision to the `rand` crate.
+//! revision to the `rand` crate. Use `test_runner::ProptestRng` for that.
 
 pub use crate::arbitrary::{any, any_with, Arbitrary};
+#[cfg(feature = "alloc-tracking")]
+pub use crate::prop_assert_allocates_at_most;
 pub use crate::strategy::{BoxedStrategy, Just, SBoxedStrategy, Strategy};
 pub use crate::test_runner::Config as ProptestConfig;
 pub use crate::test_runner::TestCaseError;
 pub use crate::{
-    prop_assert, prop_assert_eq, prop_assert_ne, prop_assume, prop_compose,
-    prop_oneof, proptest,
+    arbitrary_remote, case, prop_assert, prop_assert_close, prop_assert_eq,
+    prop_assert_ne, prop_assert_permutation, prop_assert_sorted,
+    prop_assert_subset, prop_assume, prop_compose, prop_oneof, prop_oneof_into,
+    proptest, proptest_default_config,
 };
 
 pub use rand::{Rng, RngCore};
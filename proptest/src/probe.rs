@@ -0,0 +1,148 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Lightweight "println debugging" for property tests.
+//!
+//! Values recorded with the `probe!` macro while a test case runs are
+//! buffered per thread. The `TestRunner` discards the buffer before each case
+//! and, if the case fails, appends the last `MAX_PROBES` probes to the failure
+//! message. Since the reported failure is the minimal one found by shrinking,
+//! only the probes of the minimal failing case end up in the final report,
+//! while verbose shrink logs show the probes of every failing step.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use proptest::test_runner::{Config, TestError, TestRunner};
+//!
+//! let mut runner = TestRunner::new(Config {
+//!     failure_persistence: None,
+//!     ..Config::default()
+//! });
+//! let result = runner.run(&(0..1000u32), |v| {
+//!     let doubled = v * 2;
+//!     proptest::probe!("doubled", doubled);
+//!     prop_assert!(doubled < 600);
+//!     Ok(())
+//! });
+//!
+//! match result {
+//!     Err(TestError::Fail(reason, 300)) => {
+//!         assert!(reason.message().contains("doubled = 600"));
+//!     }
+//!     r => panic!("unexpected result: {:?}", r),
+//! }
+//! ```
+
+use core::fmt;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::string::String;
+
+/// The number of most recent probes kept for a single test case.
+pub const MAX_PROBES: usize = 16;
+
+thread_local! {
+    static PROBES: RefCell<Probes> = RefCell::new(Probes::default());
+}
+
+/// The probes recorded during one test case.
+///
+/// The `Display` form is what gets appended to failure messages.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Probes {
+    entries: VecDeque<(String, String)>,
+    dropped: usize,
+}
+
+impl Probes {
+    /// Return whether no probes were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && 0 == self.dropped
+    }
+
+    /// Iterate over the retained `(label, value)` pairs, oldest first. Values
+    /// are rendered with their `Debug` form.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(l, v)| (&**l, &**v))
+    }
+
+    /// Return how many probes were discarded because more than `MAX_PROBES`
+    /// were recorded.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    fn push(&mut self, label: &str, value: String) {
+        if MAX_PROBES == self.entries.len() {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+        self.entries.push_back((label.into(), value));
+    }
+}
+
+impl fmt::Display for Probes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "probes (most recent last):")?;
+        if self.dropped > 0 {
+            write!(f, "\n  ... {} earlier probes omitted", self.dropped)?;
+        }
+        for (label, value) in self.iter() {
+            write!(f, "\n  {} = {}", label, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Record `value` under `label` for the test case currently running on this
+/// thread. Usually invoked through the `probe!` macro.
+pub fn record(label: &str, value: &dyn fmt::Debug) {
+    let value = format!("{:?}", value);
+    PROBES.with(|probes| probes.borrow_mut().push(label, value));
+}
+
+/// Remove and return all probes recorded on this thread since the last call
+/// to `take()`.
+pub fn take() -> Probes {
+    PROBES.with(|probes| core::mem::take(&mut *probes.borrow_mut()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn keeps_most_recent_probes() {
+        let _ = take();
+        for i in 0..MAX_PROBES + 2 {
+            crate::probe!("i", i);
+        }
+
+        let probes = take();
+        assert_eq!(2, probes.dropped());
+        assert_eq!(Some(("i", "2")), probes.iter().next());
+        assert_eq!(MAX_PROBES, probes.iter().count());
+        assert!(probes.to_string().contains("2 earlier probes omitted"));
+        assert!(take().is_empty());
+    }
+
+    #[test]
+    fn label_defaults_to_expression() {
+        let _ = take();
+        let foo = [1u8];
+        crate::probe!(foo.len());
+
+        assert_eq!(
+            "probes (most recent last):\n  foo.len() = 1",
+            take().to_string()
+        );
+    }
+}
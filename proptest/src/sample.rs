@@ -286,12 +286,13 @@ impl IndexStrategy {
 /// [`Index`](struct.Index.html) in that it can operate on arbitrary
 /// `IntoIterator` values.
 ///
-/// Initially, the selection is roughly uniform, with a very slight bias
-/// towards items earlier in the iterator.
+/// Initially, the selection is uniform, or proportional to the weights of
+/// the items for `select_weighted()`.
 ///
-/// Shrinking causes the selection to move toward items earlier in the
-/// iterator, ultimately settling on the very first, but this currently happens
-/// in a very haphazard way that may fail to find the earliest failing input.
+/// Shrinking causes the selection to move monotonically toward items earlier
+/// in the iterator, ultimately settling on the very first. Since the choice
+/// only depends on a position within the iterator, dependent structures built
+/// from several selections shrink coherently.
 ///
 /// ## Example
 ///
@@ -317,8 +318,9 @@ impl IndexStrategy {
 /// ```
 #[derive(Clone, Debug)]
 pub struct Selector {
-    rng: TestRng,
-    bias_increment: u64,
+    /// The selected position as a fraction of the whole, in 64-bit fixed
+    /// point.
+    position: u64,
 }
 
 /// Strategy to create `Selector`s.
@@ -332,8 +334,7 @@ pub struct SelectorStrategy {
 /// `ValueTree` corresponding to `SelectorStrategy`.
 #[derive(Debug)]
 pub struct SelectorValueTree {
-    rng: TestRng,
-    reverse_bias_increment: num::u64::BinarySearch,
+    position: num::u64::BinarySearch,
 }
 
 impl SelectorStrategy {
@@ -348,8 +349,7 @@ impl Strategy for SelectorStrategy {
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         Ok(SelectorValueTree {
-            rng: runner.new_rng(),
-            reverse_bias_increment: num::u64::BinarySearch::new(u64::MAX),
+            position: num::u64::BinarySearch::new(runner.rng().gen()),
         })
     }
 }
@@ -359,17 +359,16 @@ impl ValueTree for SelectorValueTree {
 
     fn current(&self) -> Selector {
         Selector {
-            rng: self.rng.clone(),
-            bias_increment: u64::MAX - self.reverse_bias_increment.current(),
+            position: self.position.current(),
         }
     }
 
     fn simplify(&mut self) -> bool {
-        self.reverse_bias_increment.simplify()
+        self.position.simplify()
     }
 
     fn complicate(&mut self) -> bool {
-        self.reverse_bias_increment.complicate()
+        self.position.complicate()
     }
 }
 
@@ -397,22 +396,66 @@ impl Selector {
     ///
     /// `it` is always iterated completely.
     pub fn try_select<T: IntoIterator>(&self, it: T) -> Option<T::Item> {
-        let mut bias = 0u64;
-        let mut min_score = 0;
-        let mut best = None;
-        let mut rng = self.rng.clone();
-
-        for item in it {
-            let score = bias.saturating_add(rng.gen());
-            if best.is_none() || score < min_score {
-                best = Some(item);
-                min_score = score;
-            }
+        let items: Vec<_> = it.into_iter().collect();
+        let ix = self.scale(items.len() as u64);
+        items.into_iter().nth(ix as usize)
+    }
+
+    /// Pick a random element from iterable `it` of `(weight, element)`
+    /// pairs, with probability proportional to its weight.
+    ///
+    /// The selection is unaffected by the elements themselves, and is
+    /// dependent only on their weights. Elements with weight 0 are never
+    /// selected.
+    ///
+    /// `it` is always iterated completely.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the weights of `it` sum to 0 (including if it is empty).
+    pub fn select_weighted<T, I: IntoIterator<Item = (u32, T)>>(
+        &self,
+        it: I,
+    ) -> T {
+        self.try_select_weighted(it)
+            .expect("weighted select with no positive weights")
+    }
 
-            bias = bias.saturating_add(self.bias_increment);
+    /// Pick a random element from iterable `it` of `(weight, element)`
+    /// pairs, with probability proportional to its weight.
+    ///
+    /// Returns `None` if the weights of `it` sum to 0 (including if it is
+    /// empty).
+    ///
+    /// The selection is unaffected by the elements themselves, and is
+    /// dependent only on their weights. Elements with weight 0 are never
+    /// selected.
+    ///
+    /// `it` is always iterated completely.
+    pub fn try_select_weighted<T, I: IntoIterator<Item = (u32, T)>>(
+        &self,
+        it: I,
+    ) -> Option<T> {
+        let items: Vec<_> = it.into_iter().collect();
+        let total = items.iter().map(|&(w, _)| u64::from(w)).sum::<u64>();
+        if 0 == total {
+            return None;
         }
 
-        best
+        let mut target = self.scale(total);
+        for (weight, item) in items {
+            if target < u64::from(weight) {
+                return Some(item);
+            }
+            target -= u64::from(weight);
+        }
+
+        unreachable!("target is less than the total weight")
+    }
+
+    /// Maps the position onto `0..size`, or returns 0 if `size` is 0.
+    fn scale(&self, size: u64) -> u64 {
+        ((u128::from(size) * u128::from(self.position)) >> 64) as u64
     }
 }
 
@@ -552,6 +595,50 @@ mod test {
         assert_eq!(col.into_iter().collect::<BTreeSet<_>>(), seen);
     }
 
+    #[test]
+    fn selector_shrinks_monotonically() {
+        let mut runner = TestRunner::deterministic();
+        let input = any::<Selector>();
+        let col: Vec<u32> = (0..100).collect();
+
+        for _ in 0..64 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let mut prev = tree.current().select(&col);
+            while tree.simplify() {
+                let curr = tree.current().select(&col);
+                assert!(curr <= prev, "{} > {}", curr, prev);
+                prev = curr;
+            }
+            assert_eq!(0, *tree.current().select(&col));
+        }
+    }
+
+    #[test]
+    fn selector_weighted_works() {
+        let mut runner = TestRunner::deterministic();
+        let input = any::<Selector>();
+        let col = [(1, "rare"), (0, "never"), (9, "common")];
+        let mut counts = [0; 3];
+
+        for _ in 0..1024 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let picked = tree.current().select_weighted(col.iter().copied());
+            counts[col.iter().position(|&(_, v)| v == picked).unwrap()] += 1;
+
+            while tree.simplify() {}
+            assert_eq!(
+                "rare",
+                tree.current().select_weighted(col.iter().copied())
+            );
+        }
+
+        assert_eq!(0, counts[1]);
+        assert!(counts[0] >= 50 && counts[0] < 160, "{:?}", counts);
+
+        let selector = input.new_tree(&mut runner).unwrap().current();
+        assert_eq!(None, selector.try_select_weighted(vec![(0, ())]));
+    }
+
     #[test]
     fn selector_works() {
         let mut runner = TestRunner::deterministic();
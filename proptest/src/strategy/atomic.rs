@@ -0,0 +1,223 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc};
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// How many values are regenerated per call to `simplify()` in search of a
+/// simpler one.
+const REGEN_TRIES: u32 = 32;
+
+/// The most steps `complexity()` follows before giving up, which bounds its
+/// cost for strategies which shrink for a very long time.
+const MAX_COMPLEXITY: u32 = 4096;
+
+/// `Strategy` returned by `Strategy::prop_atomic()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct Atomic<S> {
+    pub(super) source: Arc<S>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for Atomic<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Atomic")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl<S> Clone for Atomic<S> {
+    fn clone(&self) -> Self {
+        Atomic {
+            source: Arc::clone(&self.source),
+        }
+    }
+}
+
+impl<S: Strategy> Strategy for Atomic<S>
+where
+    S::Tree: Clone,
+{
+    type Tree = AtomicValueTree<S>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let current = self.source.new_tree(runner)?;
+        Ok(AtomicValueTree {
+            source: Arc::clone(&self.source),
+            complexity: complexity(&current),
+            current,
+            // Replacements are drawn from a fork of the case's RNG, so
+            // replaying the case replays its shrinking too.
+            runner: runner.partial_clone(),
+            prev: None,
+            exhausted: false,
+        })
+    }
+}
+
+/// The number of calls to `simplify()` it takes `tree` to reach its simplest
+/// value, which serves to compare how complex values of the same strategy
+/// are.
+fn complexity<T: ValueTree + Clone>(tree: &T) -> u32 {
+    let mut tree = tree.clone();
+    let mut steps = 0;
+    while steps < MAX_COMPLEXITY && tree.simplify() {
+        steps += 1;
+    }
+    steps
+}
+
+/// `ValueTree` corresponding to `Atomic`.
+///
+/// This never shrinks the parts of its value independently. Instead, each
+/// call to `simplify()` generates whole new values from the source strategy
+/// until one is simpler than the current value, as measured by how many
+/// steps the source's own value tree would take to shrink it completely.
+pub struct AtomicValueTree<S: Strategy> {
+    source: Arc<S>,
+    runner: TestRunner,
+    current: S::Tree,
+    complexity: u32,
+    prev: Option<(S::Tree, u32)>,
+    exhausted: bool,
+}
+
+impl<S: Strategy> fmt::Debug for AtomicValueTree<S>
+where
+    S::Tree: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AtomicValueTree")
+            .field("runner", &self.runner)
+            .field("current", &self.current)
+            .field("complexity", &self.complexity)
+            .field("prev", &self.prev)
+            .field("exhausted", &self.exhausted)
+            .finish()
+    }
+}
+
+impl<S: Strategy> Clone for AtomicValueTree<S>
+where
+    S::Tree: Clone,
+{
+    fn clone(&self) -> Self {
+        AtomicValueTree {
+            source: Arc::clone(&self.source),
+            runner: self.runner.clone(),
+            current: self.current.clone(),
+            complexity: self.complexity,
+            prev: self.prev.clone(),
+            exhausted: self.exhausted,
+        }
+    }
+}
+
+impl<S: Strategy> ValueTree for AtomicValueTree<S>
+where
+    S::Tree: Clone,
+{
+    type Value = S::Value;
+
+    fn current(&self) -> S::Value {
+        self.current.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.exhausted || 0 == self.complexity {
+            return false;
+        }
+
+        for _ in 0..REGEN_TRIES {
+            let tree = match self.source.new_tree(&mut self.runner) {
+                Ok(tree) => tree,
+                Err(_) => continue,
+            };
+            let complexity = complexity(&tree);
+            if complexity < self.complexity {
+                let current = core::mem::replace(&mut self.current, tree);
+                self.prev = Some((current, self.complexity));
+                self.complexity = complexity;
+                return true;
+            }
+        }
+
+        // Once no simpler value turns up, stop looking so that `simplify()`
+        // keeps returning false.
+        self.exhausted = true;
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev.take() {
+            Some((current, complexity)) => {
+                self.current = current;
+                self.complexity = complexity;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::strategy::check_strategy_sanity;
+
+    #[test]
+    fn parts_are_never_shrunk_independently() {
+        let input = (1u32..1_000_000, 1u32..1_000_000).prop_atomic();
+        let seen = RefCell::new(Vec::new());
+
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&input, |(a, b)| {
+            seen.borrow_mut().push((a, b));
+            prop_assert!(a < 1000 && b < 1000);
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, (a, b))) => assert!(a >= 1000 || b >= 1000),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        // Shrinking either part on its own would lead towards 1, which random
+        // pairs are all but certain to stay clear of.
+        let seen = seen.into_inner();
+        assert!(seen.len() > 1);
+        assert!(seen.iter().all(|&(a, b)| a > 1 && b > 1), "{:?}", seen);
+    }
+
+    #[test]
+    fn atomic_shrinking_makes_progress() {
+        let mut runner = TestRunner::deterministic();
+        let mut tree = (0u32..1_000_000)
+            .prop_atomic()
+            .new_tree(&mut runner)
+            .unwrap();
+        let start = tree.current();
+        let mut steps = 0;
+        while tree.simplify() {
+            steps += 1;
+        }
+        assert!(tree.current() <= start);
+        assert!(steps > 0 || start < 1 << 16, "{} {}", start, steps);
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity((0i32..1000, 0i32..1000).prop_atomic(), None);
+    }
+}
@@ -0,0 +1,354 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Box, Rc, Vec};
+use core::any::Any;
+use core::cell::RefCell;
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// Returns a strategy whose values let the test draw further values from any
+/// strategy while it runs, for tests which only know what to generate after
+/// looking at earlier values.
+///
+/// All values drawn during a test case belong to that case: they are shrunk
+/// along with it, one draw at a time, and are regenerated when the case is
+/// replayed, e.g. from a persisted failure. This relies on the test drawing
+/// the same way given the same values, as any other test should behave the
+/// same given the same inputs.
+///
+/// `Data` also implements `Arbitrary`, so the simplest way to use this is to
+/// take a `Data` parameter in `proptest!`.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::Data;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn index_is_in_bounds(data: Data, v in prop::collection::vec(0u8.., 1..10)) {
+///         let index = data.draw(0..v.len())?;
+///         prop_assert!(v.get(index).is_some());
+///     }
+/// }
+/// # fn main() { index_is_in_bounds(); }
+/// ```
+pub fn data() -> DataStrategy {
+    DataStrategy(())
+}
+
+/// `Strategy` returned by `data()`.
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Copy, Debug)]
+pub struct DataStrategy(());
+
+impl Strategy for DataStrategy {
+    type Tree = DataValueTree;
+    type Value = Data;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(DataValueTree {
+            draws: Rc::new(RefCell::new(Draws {
+                // Draws come from their own fork of the case's RNG, so
+                // replaying the case replays them too.
+                runner: runner.partial_clone(),
+                draws: Vec::new(),
+                next: 0,
+            })),
+            shrink: 0,
+            prev_shrink: None,
+        })
+    }
+}
+
+/// The value tree of a single draw, with its value type erased.
+trait DrawnTree {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn simplify(&mut self) -> bool;
+    fn complicate(&mut self) -> bool;
+    fn fmt_current(&self, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+impl<T: ValueTree + 'static> DrawnTree for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn simplify(&mut self) -> bool {
+        ValueTree::simplify(self)
+    }
+
+    fn complicate(&mut self) -> bool {
+        ValueTree::complicate(self)
+    }
+
+    fn fmt_current(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.current(), f)
+    }
+}
+
+/// A draw made by a test case.
+struct Draw {
+    /// The runner as it was before the draw, to generate it again from.
+    before: TestRunner,
+    tree: Box<dyn DrawnTree>,
+}
+
+/// The draws of a test case, shared between its value tree and its values.
+struct Draws {
+    runner: TestRunner,
+    draws: Vec<Draw>,
+    /// The index in `draws` of the next draw of the running test.
+    next: usize,
+}
+
+impl Draws {
+    /// Drops the draws from `index` on, rewinding the RNG so that the test
+    /// draws the same values again if it draws from the same strategies.
+    fn truncate(&mut self, index: usize) {
+        if let Some(first) = self.draws.drain(index..).next() {
+            self.runner = first.before;
+        }
+    }
+}
+
+impl fmt::Debug for Draws {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Current<'a>(&'a dyn DrawnTree);
+
+        impl fmt::Debug for Current<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.0.fmt_current(f)
+            }
+        }
+
+        f.debug_list()
+            .entries(self.draws.iter().map(|draw| Current(&*draw.tree)))
+            .finish()
+    }
+}
+
+/// Lets a test draw values from strategies while it runs. See `data()`.
+///
+/// The `Debug` form lists the values drawn in the test case, so that they
+/// appear in failure reports.
+pub struct Data {
+    draws: Rc<RefCell<Draws>>,
+}
+
+impl Data {
+    /// Draws a value from `strategy`.
+    ///
+    /// Within a test case, the `n`th call returns the value of the `n`th draw
+    /// the last time the case ran, as shrunk since, if `strategy` has the same
+    /// type of value tree. Otherwise, this and all later draws are generated
+    /// again from the RNG they were generated from before.
+    ///
+    /// Generation only fails if `strategy` rejects too many values, in which
+    /// case the whole test case is rejected.
+    pub fn draw<S>(&self, strategy: S) -> Result<S::Value, TestCaseError>
+    where
+        S: Strategy,
+        S::Tree: 'static,
+    {
+        let mut draws = self.draws.borrow_mut();
+        let index = draws.next;
+        let recorded = draws.draws.get_mut(index).and_then(|draw| {
+            draw.tree
+                .as_any_mut()
+                .downcast_mut::<S::Tree>()
+                .map(|tree| tree.current())
+        });
+
+        let value = match recorded {
+            Some(value) => value,
+            None => {
+                draws.truncate(index);
+                let before = draws.runner.clone();
+                let tree = strategy
+                    .new_tree(&mut draws.runner)
+                    .map_err(TestCaseError::Reject)?;
+                let value = tree.current();
+                draws.draws.push(Draw {
+                    before,
+                    tree: Box::new(tree),
+                });
+                value
+            }
+        };
+        draws.next += 1;
+        Ok(value)
+    }
+}
+
+impl fmt::Debug for Data {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Data").field(&*self.draws.borrow()).finish()
+    }
+}
+
+/// `ValueTree` corresponding to `DataStrategy`.
+///
+/// This shrinks the draws of the test case in the order they were made. Each
+/// time a draw changes, the later draws are generated again from the same
+/// RNG, since the test may draw them differently now, until that draw can't
+/// be simplified any further. Draws the test no longer makes are dropped.
+pub struct DataValueTree {
+    draws: Rc<RefCell<Draws>>,
+    shrink: usize,
+    prev_shrink: Option<usize>,
+}
+
+impl fmt::Debug for DataValueTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DataValueTree")
+            .field("draws", &*self.draws.borrow())
+            .field("shrink", &self.shrink)
+            .field("prev_shrink", &self.prev_shrink)
+            .finish()
+    }
+}
+
+impl DataValueTree {
+    /// Drops the draws the test didn't make the last time it ran, if it ran.
+    fn forget_unused_draws(&self) {
+        let mut draws = self.draws.borrow_mut();
+        // A test which made no draws may not have run at all since the last
+        // call, such as when the runner reports the value.
+        if draws.next > 0 {
+            let used = draws.next;
+            draws.truncate(used);
+            draws.next = 0;
+        }
+    }
+}
+
+impl ValueTree for DataValueTree {
+    type Value = Data;
+
+    fn current(&self) -> Data {
+        self.forget_unused_draws();
+        Data {
+            draws: Rc::clone(&self.draws),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.forget_unused_draws();
+        let mut draws = self.draws.borrow_mut();
+        while self.shrink < draws.draws.len() {
+            if draws.draws[self.shrink].tree.simplify() {
+                draws.truncate(self.shrink + 1);
+                self.prev_shrink = Some(self.shrink);
+                return true;
+            }
+            self.shrink += 1;
+        }
+
+        self.prev_shrink = None;
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.forget_unused_draws();
+        let mut draws = self.draws.borrow_mut();
+        match self.prev_shrink {
+            Some(shrink) if shrink < draws.draws.len() => {
+                draws.truncate(shrink + 1);
+                if draws.draws[shrink].tree.complicate() {
+                    self.shrink = shrink;
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection;
+
+    #[test]
+    fn draws_shrink_with_the_case() {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&data(), |data| {
+            let len = data.draw(1usize..10)?;
+            let values = data.draw(collection::vec(0u32..1000, len..=len))?;
+            prop_assert!(values.iter().all(|&v| v < 500));
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, data)) => {
+                assert_eq!("Data([1, [500]])", format!("{:?}", data));
+                assert_eq!(1, data.draw(1usize..10).unwrap());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn later_draws_follow_earlier_ones() {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&data(), |data| {
+            if data.draw(crate::bool::ANY)? {
+                let s = data.draw("[a-z]{1,5}")?;
+                prop_assert!(!s.contains('z'));
+            } else {
+                let n = data.draw(0u64..100)?;
+                prop_assert!(n < 90);
+            }
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, data)) => {
+                let shown = format!("{:?}", data);
+                assert!(
+                    shown == "Data([false, 90])"
+                        || shown == "Data([true, \"z\"])",
+                    "Shrank to {}",
+                    shown
+                );
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn draws_replay_within_a_case() {
+        let mut runner = TestRunner::deterministic();
+        let tree = data().new_tree(&mut runner).unwrap();
+        let first = {
+            let data = tree.current();
+            (
+                data.draw(0u32..1000).unwrap(),
+                data.draw(0u32..1000).unwrap(),
+            )
+        };
+        let again = {
+            let data = tree.current();
+            (
+                data.draw(0u32..1000).unwrap(),
+                data.draw(0u32..1000).unwrap(),
+            )
+        };
+        assert_eq!(first, again);
+    }
+}
@@ -20,6 +20,8 @@ pub struct Filter<S, F> {
     pub(super) source: S,
     pub(super) whence: Reason,
     pub(super) fun: Arc<F>,
+    /// The name and maximum number of rejections of a filter with a budget.
+    pub(super) budget: Option<(&'static str, u32)>,
 }
 
 impl<S, F> Filter<S, F> {
@@ -28,6 +30,19 @@ impl<S, F> Filter<S, F> {
             source,
             whence,
             fun: Arc::new(fun),
+            budget: None,
+        }
+    }
+
+    pub(super) fn with_budget(
+        source: S,
+        name: &'static str,
+        max_rejects: u32,
+        fun: F,
+    ) -> Self {
+        Self {
+            budget: Some((name, max_rejects)),
+            ..Self::new(source, name.into(), fun)
         }
     }
 }
@@ -38,6 +53,7 @@ impl<S: fmt::Debug, F> fmt::Debug for Filter<S, F> {
             .field("source", &self.source)
             .field("whence", &self.whence)
             .field("fun", &"<function>")
+            .field("budget", &self.budget)
             .finish()
     }
 }
@@ -48,6 +64,7 @@ impl<S: Clone, F> Clone for Filter<S, F> {
             source: self.source.clone(),
             whence: "unused".into(),
             fun: Arc::clone(&self.fun),
+            budget: self.budget,
         }
     }
 }
@@ -60,16 +77,26 @@ impl<S: Strategy, F: Fn(&S::Value) -> bool> Strategy for Filter<S, F> {
         loop {
             let val = self.source.new_tree(runner)?;
             if !(self.fun)(&val.current()) {
-                runner.reject_local(self.whence.clone())?;
+                match self.budget {
+                    Some((name, max_rejects)) => {
+                        runner.reject_filtered(name, max_rejects)?
+                    }
+                    None => runner.reject_local(self.whence.clone())?,
+                }
             } else {
                 return Ok(Filter {
                     source: val,
                     whence: self.whence.clone(),
                     fun: Arc::clone(&self.fun),
+                    budget: self.budget,
                 });
             }
         }
     }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.source.value_size_hint()
+    }
 }
 
 impl<S: ValueTree, F: Fn(&S::Value) -> bool> Filter<S, F> {
@@ -144,4 +171,37 @@ mod test {
             }),
         );
     }
+
+    #[test]
+    fn budgeted_filters_count_their_rejects() {
+        let input = (0..100u32)
+            .prop_filter_with_budget("even", 1000, |v| v % 2 == 0)
+            .prop_filter_with_budget("small", 1000, |v| *v < 10);
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..16 {
+            assert!(input.new_tree(&mut runner).unwrap().current() < 10);
+        }
+        let rejects = runner.filter_rejects();
+        assert!(rejects["even"] > 0);
+        assert!(rejects["small"] > 0);
+    }
+
+    #[test]
+    fn exhausted_budget_aborts_with_breakdown() {
+        let input = (0..100u32)
+            .prop_filter_with_budget("even", 1000, |v| v % 2 == 0)
+            .prop_filter_with_budget("impossible", 5, |_| false);
+
+        let mut runner = TestRunner::deterministic();
+        let reason = input.new_tree(&mut runner).unwrap_err();
+        let message = reason.message();
+        assert!(
+            message.starts_with("Too many rejects by filter impossible"),
+            "{}",
+            message
+        );
+        assert!(message.contains("impossible: 5"), "{}", message);
+        assert!(message.contains("even: "), "{}", message);
+    }
 }
@@ -20,7 +20,7 @@ use crate::test_runner::*;
 #[derive(Debug, Clone, Copy)]
 #[must_use = "strategies do nothing unless used"]
 pub struct Flatten<S> {
-    source: S,
+    pub(super) source: S,
 }
 
 impl<S: Strategy> Flatten<S> {
@@ -107,7 +107,10 @@ impl<S: ValueTree> FlattenValueTree<S>
 where
     S::Value: Strategy,
 {
-    fn new(runner: &mut TestRunner, meta: S) -> Result<Self, Reason> {
+    pub(super) fn new(
+        runner: &mut TestRunner,
+        meta: S,
+    ) -> Result<Self, Reason> {
         let current = meta.current().new_tree(runner)?;
         Ok(FlattenValueTree {
             meta: Fuse::new(meta),
@@ -156,7 +159,7 @@ where
                 &mut self.current,
             );
             // Initially complicate by regenerating the chosen value.
-            self.complicate_regen_remaining = self.runner.config().cases;
+            self.complicate_regen_remaining = self.runner.config().cases();
             true
         } else {
             false
@@ -181,7 +184,7 @@ where
             return true;
         } else if self.meta.complicate() {
             if let Ok(v) = self.meta.current().new_tree(&mut self.runner) {
-                self.complicate_regen_remaining = self.runner.config().cases;
+                self.complicate_regen_remaining = self.runner.config().cases();
                 self.current = Fuse::new(v);
                 return true;
             }
@@ -196,6 +199,173 @@ where
     }
 }
 
+/// Similar to `Flatten`, but shrinks the input strategy first and derives the
+/// inner value from the same randomness every time.
+///
+/// See `Strategy::prop_dependent_map()` for more details.
+#[derive(Debug, Clone, Copy)]
+#[must_use = "strategies do nothing unless used"]
+pub struct DependentFlatten<S> {
+    source: S,
+}
+
+impl<S: Strategy> DependentFlatten<S> {
+    /// Wrap `source` to flatten it, shrinking it first.
+    pub fn new(source: S) -> Self {
+        DependentFlatten { source }
+    }
+}
+
+impl<S: Strategy> Strategy for DependentFlatten<S>
+where
+    S::Value: Strategy,
+{
+    type Tree = DependentFlattenValueTree<S::Tree>;
+    type Value = <S::Value as Strategy>::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let meta = self.source.new_tree(runner)?;
+        DependentFlattenValueTree::new(runner, meta)
+    }
+}
+
+/// The `ValueTree` produced by `DependentFlatten`.
+pub struct DependentFlattenValueTree<S: ValueTree>
+where
+    S::Value: Strategy,
+{
+    meta: Fuse<S>,
+    current: Fuse<<S::Value as Strategy>::Tree>,
+    // The value to go back to when complicating the meta value does not
+    // produce a new one, i.e., the last one known to fail.
+    final_complication: Option<Fuse<<S::Value as Strategy>::Tree>>,
+    // Every inner value is generated by `runner` with its RNG reset to `rng`,
+    // so that a simpler meta value produces a correspondingly simpler inner
+    // value (e.g., a prefix of the same elements for a shorter collection)
+    // rather than an unrelated one.
+    runner: TestRunner,
+    rng: TestRng,
+    // Whether the meta value is still being shrunk. Once it cannot be
+    // simplified any further, only the inner value is.
+    shrink_meta: bool,
+}
+
+impl<S: ValueTree> Clone for DependentFlattenValueTree<S>
+where
+    S::Value: Strategy + Clone,
+    S: Clone,
+    <S::Value as Strategy>::Tree: Clone,
+{
+    fn clone(&self) -> Self {
+        DependentFlattenValueTree {
+            meta: self.meta.clone(),
+            current: self.current.clone(),
+            final_complication: self.final_complication.clone(),
+            runner: self.runner.clone(),
+            rng: self.rng.clone(),
+            shrink_meta: self.shrink_meta,
+        }
+    }
+}
+
+impl<S: ValueTree> fmt::Debug for DependentFlattenValueTree<S>
+where
+    S::Value: Strategy,
+    S: fmt::Debug,
+    <S::Value as Strategy>::Tree: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DependentFlattenValueTree")
+            .field("meta", &self.meta)
+            .field("current", &self.current)
+            .field("final_complication", &self.final_complication)
+            .field("shrink_meta", &self.shrink_meta)
+            .finish()
+    }
+}
+
+impl<S: ValueTree> DependentFlattenValueTree<S>
+where
+    S::Value: Strategy,
+{
+    fn new(runner: &mut TestRunner, meta: S) -> Result<Self, Reason> {
+        let rng = runner.new_rng();
+        let mut runner = runner.partial_clone();
+        *runner.rng() = rng.clone();
+        let current = meta.current().new_tree(&mut runner)?;
+        Ok(DependentFlattenValueTree {
+            meta: Fuse::new(meta),
+            current: Fuse::new(current),
+            final_complication: None,
+            runner,
+            rng,
+            shrink_meta: true,
+        })
+    }
+
+    /// Generates the inner value for the current meta value.
+    fn regenerate(&mut self) -> Option<Fuse<<S::Value as Strategy>::Tree>> {
+        *self.runner.rng() = self.rng.clone();
+        self.meta
+            .current()
+            .new_tree(&mut self.runner)
+            .ok()
+            .map(Fuse::new)
+    }
+}
+
+impl<S: ValueTree> ValueTree for DependentFlattenValueTree<S>
+where
+    S::Value: Strategy,
+{
+    type Value = <S::Value as Strategy>::Value;
+
+    fn current(&self) -> Self::Value {
+        self.current.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.shrink_meta {
+            while self.meta.simplify() {
+                // Meta values the inner strategy cannot be generated from
+                // are skipped over.
+                if let Some(v) = self.regenerate() {
+                    self.final_complication =
+                        Some(mem::replace(&mut self.current, v));
+                    return true;
+                }
+            }
+
+            // `self.current` is the simplest failing value derived from the
+            // meta value, so go on from there.
+            self.shrink_meta = false;
+            self.final_complication = None;
+        }
+
+        self.current.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        if !self.shrink_meta {
+            return self.current.complicate();
+        }
+
+        if self.meta.complicate() {
+            if let Some(v) = self.regenerate() {
+                self.current = v;
+                return true;
+            }
+        }
+
+        if let Some(v) = self.final_complication.take() {
+            self.current = v;
+            true
+        } else {
+            false
+        }
+    }
+}
+
 /// Similar to `Flatten`, but does not shrink the input strategy.
 ///
 /// See `Strategy::prop_ind_flat_map()` fore more details.
@@ -341,6 +511,65 @@ mod test {
         });
     }
 
+    #[test]
+    fn regen_budget_follows_effective_case_count() {
+        let mut runner = TestRunner::new(Config {
+            cases: 1000,
+            cases_overrides: vec![("my_test".into(), 3)],
+            test_name: Some("my_crate::my_test"),
+            ..Config::default()
+        });
+        let input = (1..1000u32).prop_flat_map(|x| 0..x);
+
+        let mut regenerated = false;
+        for _ in 0..16 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            while case.simplify() {
+                let remaining = case.complicate_regen_remaining;
+                assert!(remaining == 0 || remaining == 3, "{}", remaining);
+                regenerated |= remaining == 3;
+            }
+        }
+        assert!(regenerated);
+    }
+
+    #[test]
+    fn dependent_map_shrinks_outer_value_first() {
+        use crate::collection::vec;
+
+        // Fail whenever some element is at least 200: the length should
+        // shrink to just past the first such element, since shorter lengths
+        // produce prefixes of the same elements.
+        let input = (0..100usize).prop_dependent_map(|len| vec(0..256u32, len));
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let case = input.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(case, |v| {
+                prop_assert!(v.iter().all(|&e| e < 200));
+                Ok(())
+            });
+
+            match result {
+                Ok(_) => {}
+                Err(TestError::Fail(_, v)) => {
+                    let (last, rest) = v.split_last().unwrap();
+                    assert_eq!(200, *last);
+                    assert!(rest.iter().all(|&e| e == 0), "{:?}", v);
+                }
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn test_dependent_map_sanity() {
+        check_strategy_sanity(
+            (0..65536).prop_dependent_map(|a| (Just(a), (a - 5..a + 5))),
+            None,
+        );
+    }
+
     #[test]
     fn test_ind_flat_map_sanity() {
         check_strategy_sanity(
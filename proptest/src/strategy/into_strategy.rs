@@ -0,0 +1,193 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, String, Vec};
+
+use crate::sample::{select, Select};
+use crate::strategy::{Just, Strategy};
+
+/// Conversion into a `Strategy`.
+///
+/// Every strategy converts into itself. Literal values of the primitive types
+/// and `String` convert into a `Just` of that value, and arrays, `Vec`s and
+/// static slices of them convert into a strategy selecting one of their
+/// elements, as with `prop::sample::select()`.
+///
+/// `prop_oneof_into!`, `Union::new_into()` and `Strategy::prop_flat_map_into()`
+/// accept anything implementing this trait, which saves wrapping each
+/// constant alternative in `Just`:
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn port_is_valid(
+///         port in prop_oneof_into![80u16, 443u16, 8000..9000u16]
+///     ) {
+///         prop_assert!(port == 80 || port == 443 || port >= 8000);
+///     }
+/// }
+/// # fn main() { port_is_valid(); }
+/// ```
+///
+/// Since each alternative is converted on its own, integer literals need a
+/// suffix unless they are meant to be `i32`s. Values of other types, such as
+/// your own enums, still need an explicit `Just`, since they can't be told
+/// apart from strategies in general.
+pub trait IntoStrategy {
+    /// The strategy this converts into.
+    type Strategy: Strategy;
+
+    /// Converts `self` into a strategy.
+    fn into_strategy(self) -> Self::Strategy;
+}
+
+impl<S: Strategy> IntoStrategy for S {
+    type Strategy = S;
+
+    fn into_strategy(self) -> S {
+        self
+    }
+}
+
+/// Selects one of the elements of the slice.
+impl<T: Clone + fmt::Debug + 'static> IntoStrategy for &'static [T] {
+    type Strategy = Select<T>;
+
+    fn into_strategy(self) -> Select<T> {
+        select(self)
+    }
+}
+
+macro_rules! literal_into_strategy {
+    ($($typ:ty),*) => {$(
+        impl IntoStrategy for $typ {
+            type Strategy = Just<$typ>;
+
+            fn into_strategy(self) -> Just<$typ> {
+                Just(self)
+            }
+        }
+
+        /// Selects one of the elements of the `Vec`.
+        impl IntoStrategy for Vec<$typ> {
+            type Strategy = Select<$typ>;
+
+            fn into_strategy(self) -> Select<$typ> {
+                select(self)
+            }
+        }
+
+        /// Selects one of the elements of the array.
+        impl<const N: usize> IntoStrategy for [$typ; N] {
+            type Strategy = Select<$typ>;
+
+            fn into_strategy(self) -> Select<$typ> {
+                select(Vec::from(self))
+            }
+        }
+    )*};
+}
+
+literal_into_strategy!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String
+);
+
+#[cfg(test)]
+mod test {
+    use crate::std_facade::{vec, ToOwned};
+
+    use super::*;
+    use crate::strategy::{Union, ValueTree};
+    use crate::test_runner::TestRunner;
+
+    fn values_of<S: IntoStrategy>(s: S) -> Vec<<S::Strategy as Strategy>::Value>
+    where
+        <S::Strategy as Strategy>::Value: PartialEq,
+    {
+        let strategy = s.into_strategy();
+        let mut runner = TestRunner::deterministic();
+        let mut values = Vec::new();
+        for _ in 0..64 {
+            let value = strategy.new_tree(&mut runner).unwrap().current();
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn literals_convert_into_just() {
+        assert_eq!(vec![42u8], values_of(42u8));
+        assert_eq!(vec!["foo".to_owned()], values_of("foo".to_owned()));
+        assert_eq!(vec![()], values_of(()));
+    }
+
+    #[test]
+    fn collections_convert_into_select() {
+        let mut values = values_of([1, 2, 3]);
+        values.sort();
+        assert_eq!(vec![1, 2, 3], values);
+
+        let mut values = values_of(vec!['a', 'b']);
+        values.sort();
+        assert_eq!(vec!['a', 'b'], values);
+
+        static SLICE: &[bool] = &[true];
+        assert_eq!(vec![true], values_of(SLICE));
+    }
+
+    #[test]
+    fn strategies_convert_into_themselves() {
+        let mut values = values_of(0..3i32);
+        values.sort();
+        assert_eq!(vec![0, 1, 2], values);
+    }
+
+    #[test]
+    fn opt_in_combinators_accept_literals() {
+        let mut values = values_of(prop_oneof_into![1, 5..7, [10, 11]]);
+        values.sort();
+        assert_eq!(vec![1, 5, 6, 10, 11], values);
+
+        let mut values = values_of(prop_oneof_into![1 => 1, 2 => [2, 3]]);
+        values.sort();
+        assert_eq!(vec![1, 2, 3], values);
+
+        let mut values = values_of(Union::new_into([3u8, 4]));
+        values.sort();
+        assert_eq!(vec![3, 4], values);
+
+        let mut values =
+            values_of((0..2u8).prop_flat_map_into(|x| [x * 10, x * 10 + 1]));
+        values.sort();
+        assert_eq!(vec![0, 1, 10, 11], values);
+    }
+}
@@ -0,0 +1,247 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::iter::FusedIterator;
+
+use crate::collection::{self, SizeRange, VecStrategy, VecValueTree};
+use crate::std_facade::{fmt, vec, Vec};
+use crate::strategy::statics;
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// An iterator over values generated by the strategy returned by
+/// `iter_of()`.
+///
+/// The `Debug` form lists the items which are yet to be yielded, so a failure
+/// report shows the whole sequence the test was given.
+#[derive(Clone)]
+pub struct GeneratedIter<T>(vec::IntoIter<T>);
+
+impl<T: fmt::Debug> fmt::Debug for GeneratedIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GeneratedIter")
+            .field(&self.0.as_slice())
+            .finish()
+    }
+}
+
+impl<T> Iterator for GeneratedIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for GeneratedIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.0.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for GeneratedIter<T> {}
+
+impl<T> FusedIterator for GeneratedIter<T> {}
+
+mapfn! {
+    [] fn VecToIter[<T : fmt::Debug>](vec: Vec<T>) -> GeneratedIter<T> {
+        GeneratedIter(vec.into_iter())
+    }
+}
+
+opaque_strategy_wrapper! {
+    /// `Strategy` returned by `iter_of()`.
+    #[derive(Clone, Debug)]
+    pub struct IterOf[<T>][where T : Strategy](
+        statics::Map<VecStrategy<T>, VecToIter>)
+        -> IterOfValueTree<T::Tree>;
+    /// `ValueTree` corresponding to `IterOf`.
+    #[derive(Clone, Debug)]
+    pub struct IterOfValueTree[<T>][where T : ValueTree](
+        statics::Map<VecValueTree<T>, VecToIter>)
+        -> GeneratedIter<T::Value>;
+}
+
+/// Create a strategy to generate iterators yielding elements drawn from
+/// `element`, with a number of items given by `size`.
+///
+/// This is useful for testing APIs which consume an `Iterator` without first
+/// collecting the test input into a `Vec`. The iterator is `Clone`, and is
+/// backed by a generated `Vec`, so it shrinks exactly like
+/// `collection::vec(element, size)`.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::iter_of;
+///
+/// proptest! {
+///     fn sum_is_order_independent(items in iter_of(0u32..100, 0..10)) {
+///         let forward: u32 = items.clone().sum();
+///         let backward: u32 = items.rev().sum();
+///         prop_assert_eq!(forward, backward);
+///     }
+/// }
+/// # fn main() { sum_is_order_independent(); }
+/// ```
+pub fn iter_of<T: Strategy>(
+    element: T,
+    size: impl Into<SizeRange>,
+) -> IterOf<T> {
+    IterOf(statics::Map::new(collection::vec(element, size), VecToIter))
+}
+
+/// A stream of values generated by the strategy returned by `stream_of()`.
+///
+/// Every item is ready as soon as it is polled for.
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+#[derive(Clone, Debug)]
+pub struct GeneratedStream<T>(GeneratedIter<T>);
+
+// The items are never pinned, so moving the stream is fine whatever `T` is.
+#[cfg(feature = "futures")]
+impl<T> Unpin for GeneratedStream<T> {}
+
+#[cfg(feature = "futures")]
+impl<T> futures_core::Stream for GeneratedStream<T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: core::pin::Pin<&mut Self>,
+        _: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Option<T>> {
+        core::task::Poll::Ready(self.0.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> futures_core::FusedStream for GeneratedStream<T> {
+    fn is_terminated(&self) -> bool {
+        0 == self.0.len()
+    }
+}
+
+#[cfg(feature = "futures")]
+mapfn! {
+    [] fn VecToStream[<T : fmt::Debug>](vec: Vec<T>) -> GeneratedStream<T> {
+        GeneratedStream(GeneratedIter(vec.into_iter()))
+    }
+}
+
+#[cfg(feature = "futures")]
+opaque_strategy_wrapper! {
+    {#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]}
+    /// `Strategy` returned by `stream_of()`.
+    #[derive(Clone, Debug)]
+    pub struct StreamOf[<T>][where T : Strategy](
+        statics::Map<VecStrategy<T>, VecToStream>)
+        -> StreamOfValueTree<T::Tree>;
+    /// `ValueTree` corresponding to `StreamOf`.
+    #[derive(Clone, Debug)]
+    pub struct StreamOfValueTree[<T>][where T : ValueTree](
+        statics::Map<VecValueTree<T>, VecToStream>)
+        -> GeneratedStream<T::Value>;
+}
+
+/// Create a strategy to generate `futures::Stream`s yielding elements drawn
+/// from `element`, with a number of items given by `size`.
+///
+/// This is the asynchronous counterpart of `iter_of()`, and shrinks the same
+/// way.
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+pub fn stream_of<T: Strategy>(
+    element: T,
+    size: impl Into<SizeRange>,
+) -> StreamOf<T> {
+    StreamOf(statics::Map::new(
+        collection::vec(element, size),
+        VecToStream,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn iter_of_yields_generated_items() {
+        let mut runner = TestRunner::deterministic();
+        let tree = iter_of(0u32..10, 3..5).new_tree(&mut runner).unwrap();
+
+        let mut iter = tree.current();
+        let items: Vec<u32> = iter.clone().collect();
+        assert!(items.len() >= 3 && items.len() < 5);
+        assert!(items.iter().all(|&v| v < 10));
+        assert_eq!(items.len(), iter.len());
+
+        iter.next();
+        assert_eq!(
+            format!("GeneratedIter({:?})", &items[1..]),
+            format!("{:?}", iter)
+        );
+    }
+
+    #[test]
+    fn iter_of_shrinks_like_vec() {
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&iter_of(0u32..1000, 0..100), |mut items| {
+            prop_assert!(items.all(|v| v < 500));
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, items)) => {
+                assert_eq!(vec![500], items.collect::<Vec<_>>());
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn stream_of_yields_items_immediately() {
+        use core::pin::Pin;
+        use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+        use futures_core::{FusedStream, Stream};
+
+        fn noop_raw_waker() -> RawWaker {
+            fn clone(_: *const ()) -> RawWaker {
+                noop_raw_waker()
+            }
+            fn noop(_: *const ()) {}
+            static VTABLE: RawWakerVTable =
+                RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(core::ptr::null(), &VTABLE)
+        }
+
+        let mut runner = TestRunner::deterministic();
+        let tree = stream_of(0u32..10, 2..=2).new_tree(&mut runner).unwrap();
+        let mut stream = tree.current();
+        let waker = unsafe { Waker::from_raw(noop_raw_waker()) };
+        let mut cx = Context::from_waker(&waker);
+
+        let mut items = Vec::new();
+        while let Poll::Ready(Some(v)) =
+            Pin::new(&mut stream).poll_next(&mut cx)
+        {
+            items.push(v);
+        }
+        assert_eq!(2, items.len());
+        assert!(stream.is_terminated());
+    }
+}
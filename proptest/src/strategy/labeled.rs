@@ -0,0 +1,97 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` which counts the values it generates under a label, usually
+/// created with the `case!` macro.
+///
+/// Each time a value is generated, the count of `label` in
+/// `TestRunner::label_counts()` goes up by one. Within `prop_oneof!`, this
+/// tells how many values came from each alternative, so that alternatives
+/// which are never or rarely picked, e.g. because their values are mostly
+/// rejected, stand out. The counts are part of the report of a failing test,
+/// and are logged at the end of a passing run if `Config::verbose` is at
+/// least 1.
+///
+/// Values are counted when they are generated, even if the test case is
+/// then rejected. Values generated inside other values, e.g. the elements of
+/// a collection, are counted individually.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Labeled<S> {
+    label: &'static str,
+    source: S,
+}
+
+impl<S> Labeled<S> {
+    /// Count the values generated by `source` under `label`.
+    pub fn new(label: &'static str, source: S) -> Self {
+        Labeled { label, source }
+    }
+
+    /// Return the label values are counted under.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+}
+
+impl<S: Strategy> Strategy for Labeled<S> {
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let tree = self.source.new_tree(runner)?;
+        runner.count_label(self.label);
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::Just;
+
+    #[test]
+    fn counts_values_of_each_alternative() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = prop_oneof![
+            1 => case!("small", 0u32..10),
+            3 => case!("large", 1000u32..2000),
+            1 => Just(500u32),
+        ];
+        for _ in 0..1000 {
+            strategy.new_tree(&mut runner).unwrap();
+        }
+
+        let counts = runner.label_counts();
+        assert_eq!(2, counts.len());
+        assert!(counts["small"] > 100);
+        assert!(counts["large"] > 3 * counts["small"] / 2);
+        assert!(counts["small"] + counts["large"] < 1000);
+    }
+
+    #[test]
+    fn labels_are_reported_on_failure() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = prop_oneof![
+            case!("empty", Just(crate::std_facade::Vec::new())),
+            case!("big", crate::collection::vec(0u8.., 1000)),
+        ];
+        let result = runner.run(&strategy, |v| {
+            prop_assert!(v.is_empty());
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        let report = format!("{}", runner);
+        assert!(report.contains("times from big"), "{}", report);
+    }
+}
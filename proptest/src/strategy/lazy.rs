@@ -8,7 +8,9 @@
 // except according to those terms.
 
 use crate::std_facade::{fmt, Arc};
+use core::cell::UnsafeCell;
 use core::mem;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 use crate::strategy::traits::*;
 use crate::test_runner::*;
@@ -173,3 +175,133 @@ where
         }
     }
 }
+
+/// A strategy which is only constructed, by calling `make` with `params`, the
+/// first time a value tree is generated from it.
+///
+/// This is useful where building a strategy is expensive and it may not be
+/// needed at all, such as for the options of a `LazyTupleUnion`. `make` is a
+/// plain function rather than a closure so that the type can be named; pass
+/// everything it needs through `params`.
+///
+/// Once constructed, the strategy is kept and shared by all later uses.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::{LazyStrategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let strategy = LazyStrategy::new(100u32, |max| 0..max);
+/// assert!(!strategy.is_constructed());
+///
+/// let mut runner = TestRunner::default();
+/// assert!(strategy.new_tree(&mut runner).unwrap().current() < 100);
+/// assert!(strategy.is_constructed());
+/// ```
+#[must_use = "strategies do nothing unless used"]
+pub struct LazyStrategy<P, S> {
+    locked: AtomicBool,
+    state: UnsafeCell<LazyStrategyState<P, S>>,
+}
+
+enum LazyStrategyState<P, S> {
+    Pending(P, fn(P) -> S),
+    // Only observable if `make` panicked.
+    Constructing,
+    Constructed(Arc<S>),
+}
+
+// The state is only ever accessed while holding the lock, so this is `Sync`
+// under the same conditions as a `Mutex` of the state.
+unsafe impl<P: Send, S: Send + Sync> Sync for LazyStrategy<P, S> {}
+
+struct LazyStrategyGuard<'a>(&'a AtomicBool);
+
+impl Drop for LazyStrategyGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+impl<P, S> LazyStrategy<P, S> {
+    /// Create a strategy which will be constructed by `make(params)` when it
+    /// is first used.
+    pub fn new(params: P, make: fn(P) -> S) -> Self {
+        LazyStrategy {
+            locked: AtomicBool::new(false),
+            state: UnsafeCell::new(LazyStrategyState::Pending(params, make)),
+        }
+    }
+
+    /// Returns whether the strategy has been constructed yet.
+    pub fn is_constructed(&self) -> bool {
+        self.with_state(|state| {
+            matches!(state, LazyStrategyState::Constructed(_))
+        })
+    }
+
+    fn with_state<R>(
+        &self,
+        f: impl FnOnce(&mut LazyStrategyState<P, S>) -> R,
+    ) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(
+                false,
+                true,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        let _guard = LazyStrategyGuard(&self.locked);
+        // Safety: holding the lock gives exclusive access to the state.
+        f(unsafe { &mut *self.state.get() })
+    }
+
+    /// Returns the strategy, constructing it if this is the first use.
+    fn strategy(&self) -> Arc<S> {
+        self.with_state(|state| {
+            let pending = mem::replace(state, LazyStrategyState::Constructing);
+            let strategy = match pending {
+                LazyStrategyState::Pending(params, make) => {
+                    Arc::new(make(params))
+                }
+                LazyStrategyState::Constructed(strategy) => strategy,
+                LazyStrategyState::Constructing => {
+                    panic!("constructing LazyStrategy panicked earlier")
+                }
+            };
+            *state = LazyStrategyState::Constructed(Arc::clone(&strategy));
+            strategy
+        })
+    }
+}
+
+impl<P, S: Strategy> Strategy for LazyStrategy<P, S> {
+    type Tree = S::Tree;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.strategy().new_tree(runner)
+    }
+}
+
+impl<P, S: fmt::Debug> fmt::Debug for LazyStrategy<P, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let strategy = self.with_state(|state| match state {
+            LazyStrategyState::Constructed(strategy) => {
+                Some(Arc::clone(strategy))
+            }
+            _ => None,
+        });
+        match strategy {
+            Some(strategy) => {
+                f.debug_tuple("LazyStrategy").field(&strategy).finish()
+            }
+            None => f.write_str("LazyStrategy(<not constructed>)"),
+        }
+    }
+}
@@ -0,0 +1,528 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{Arc, Vec};
+use core::ops::RangeInclusive;
+
+use crate::num::{i64::BinarySearch, sample_uniform_incl};
+use crate::strategy::*;
+use crate::test_runner::*;
+
+/// Upper bound on the number of bound propagation passes over the
+/// constraints. Propagation almost always reaches a fixpoint within a handful
+/// of passes; this only guards against constraints like `a < b, b < a` which
+/// would otherwise tighten the bounds one unit at a time.
+const MAX_PROPAGATION_ROUNDS: usize = 64;
+
+/// How the two sides of a `LinearConstraint` relate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    /// The left-hand side equals the right-hand side.
+    Eq,
+    /// The left-hand side is less than or equal to the right-hand side.
+    Le,
+    /// The left-hand side is greater than or equal to the right-hand side.
+    Ge,
+}
+
+/// A linear constraint `c[0]*x[0] + ... + c[N-1]*x[N-1] <relation> rhs` over
+/// the `N` variables generated by `linear_solutions()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinearConstraint<const N: usize> {
+    coefficients: [i64; N],
+    relation: Relation,
+    rhs: i64,
+}
+
+impl<const N: usize> LinearConstraint<N> {
+    /// Create the constraint `coefficients · x <relation> rhs`.
+    pub fn new(coefficients: [i64; N], relation: Relation, rhs: i64) -> Self {
+        LinearConstraint {
+            coefficients,
+            relation,
+            rhs,
+        }
+    }
+
+    /// Create the constraint `coefficients · x == rhs`.
+    pub fn eq(coefficients: [i64; N], rhs: i64) -> Self {
+        Self::new(coefficients, Relation::Eq, rhs)
+    }
+
+    /// Create the constraint `coefficients · x <= rhs`.
+    pub fn le(coefficients: [i64; N], rhs: i64) -> Self {
+        Self::new(coefficients, Relation::Le, rhs)
+    }
+
+    /// Create the constraint `coefficients · x >= rhs`.
+    pub fn ge(coefficients: [i64; N], rhs: i64) -> Self {
+        Self::new(coefficients, Relation::Ge, rhs)
+    }
+
+    /// Returns whether `values` satisfy this constraint.
+    pub fn is_satisfied_by(&self, values: &[i64; N]) -> bool {
+        let lhs = self
+            .coefficients
+            .iter()
+            .zip(values)
+            .map(|(&c, &x)| i128::from(c) * i128::from(x))
+            .fold(0i128, i128::saturating_add);
+        let rhs = i128::from(self.rhs);
+
+        match self.relation {
+            Relation::Eq => lhs == rhs,
+            Relation::Le => lhs <= rhs,
+            Relation::Ge => lhs >= rhs,
+        }
+    }
+
+    /// Narrows `bounds` so that they only exclude values which cannot
+    /// satisfy this constraint. Returns whether any bound changed.
+    fn narrow(&self, bounds: &mut [(i128, i128); N]) -> bool {
+        let mut changed = false;
+
+        for var in 0..N {
+            let c = i128::from(self.coefficients[var]);
+            if 0 == c {
+                continue;
+            }
+
+            let (mut others_min, mut others_max) = (0i128, 0i128);
+            for (other, &(lo, hi)) in bounds.iter().enumerate() {
+                if other != var {
+                    let co = i128::from(self.coefficients[other]);
+                    let (a, b) = (co.saturating_mul(lo), co.saturating_mul(hi));
+                    others_min = others_min.saturating_add(a.min(b));
+                    others_max = others_max.saturating_add(a.max(b));
+                }
+            }
+
+            let rhs = i128::from(self.rhs);
+            // Bounds on `c * x[var]`.
+            let (mut term_lo, mut term_hi) = (i128::MIN, i128::MAX);
+            if Relation::Le != self.relation {
+                term_lo = rhs.saturating_sub(others_max);
+            }
+            if Relation::Ge != self.relation {
+                term_hi = rhs.saturating_sub(others_min);
+            }
+
+            let (lo, hi) = if c > 0 {
+                (div_ceil(term_lo, c), div_floor(term_hi, c))
+            } else {
+                (div_ceil(term_hi, c), div_floor(term_lo, c))
+            };
+
+            let bound = &mut bounds[var];
+            if lo > bound.0 {
+                bound.0 = lo;
+                changed = true;
+            }
+            if hi < bound.1 {
+                bound.1 = hi;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+fn div_floor(a: i128, b: i128) -> i128 {
+    if i128::MIN == a || i128::MAX == a {
+        // Unbounded stays unbounded, in the direction given by the sign.
+        return if (a > 0) == (b > 0) {
+            i128::MAX
+        } else {
+            i128::MIN
+        };
+    }
+    a.div_euclid(b) - if b < 0 && 0 != a.rem_euclid(b) { 1 } else { 0 }
+}
+
+fn div_ceil(a: i128, b: i128) -> i128 {
+    if i128::MIN == a || i128::MAX == a {
+        return if (a > 0) == (b > 0) {
+            i128::MAX
+        } else {
+            i128::MIN
+        };
+    }
+    -div_floor(-a, b)
+}
+
+/// Narrows `bounds` according to all of `constraints`. Returns the narrowed
+/// bounds as `i64`, or `None` if some variable is left without any possible
+/// value.
+fn propagate<const N: usize>(
+    constraints: &[LinearConstraint<N>],
+    mut bounds: [(i128, i128); N],
+) -> Option<[(i64, i64); N]> {
+    for _ in 0..MAX_PROPAGATION_ROUNDS {
+        let mut changed = false;
+        for constraint in constraints {
+            changed |= constraint.narrow(&mut bounds);
+            if bounds.iter().any(|&(lo, hi)| lo > hi) {
+                return None;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let clamp = |v: i128| v.max(i64::MIN.into()).min(i64::MAX.into()) as i64;
+    Some(core::array::from_fn(|var| {
+        (clamp(bounds[var].0), clamp(bounds[var].1))
+    }))
+}
+
+/// Returns the range of values `values[var]` may take given that all
+/// variables before `var` are fixed to the values in `values`, or `None` if
+/// there is none.
+fn feasible_range<const N: usize>(
+    constraints: &[LinearConstraint<N>],
+    domain: &[(i64, i64); N],
+    values: &[i64; N],
+    var: usize,
+) -> Option<(i64, i64)> {
+    let bounds = core::array::from_fn(|ix| {
+        if ix < var {
+            (values[ix].into(), values[ix].into())
+        } else {
+            (domain[ix].0.into(), domain[ix].1.into())
+        }
+    });
+    propagate(constraints, bounds).map(|bounds| bounds[var])
+}
+
+/// Strategy returned by `linear_solutions()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct LinearSolutions<const N: usize> {
+    constraints: Arc<[LinearConstraint<N>]>,
+    /// The bounds of each variable implied by the constraints as a whole, or
+    /// `None` if the constraints are found to be unsatisfiable.
+    domain: Option<[(i64, i64); N]>,
+}
+
+impl<const N: usize> LinearSolutions<N> {
+    /// Additionally restrict each variable `x[i]` to `bounds[i]`.
+    pub fn with_bounds(self, bounds: [RangeInclusive<i64>; N]) -> Self {
+        let mut constraints = self.constraints.to_vec();
+        for (var, range) in bounds.iter().enumerate() {
+            let mut coefficients = [0; N];
+            coefficients[var] = 1;
+            constraints
+                .push(LinearConstraint::ge(coefficients, *range.start()));
+            constraints.push(LinearConstraint::le(coefficients, *range.end()));
+        }
+
+        linear_solutions(constraints)
+    }
+}
+
+/// Create a strategy generating `[i64; N]` arrays satisfying all of the
+/// given linear `constraints`.
+///
+/// Rather than generating arbitrary values and filtering out those which
+/// violate the constraints, the variables are generated one at a time, each
+/// within the range still compatible with the constraints given the values of
+/// the variables before it. A constraint such as `a + b + c == total` thus
+/// never causes a rejection: `a` and `b` are drawn so that the remainder fits
+/// `c`, and `c` itself is derived from them. Only when constraints interact in
+/// ways the per-variable ranges cannot capture (for example, coefficients
+/// other than ±1 requiring divisibility) are some draws rejected.
+///
+/// Variables not bounded by any constraint range over all of `i64`, so
+/// usually each variable should be bounded, for example with
+/// `LinearSolutions::with_bounds()`.
+///
+/// When shrinking, each variable is moved towards zero (or its bound closest
+/// to zero), in order, while the following variables are adjusted to keep the
+/// constraints satisfied.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::{linear_solutions, LinearConstraint};
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn budget_is_preserved(
+///         [a, b, c] in linear_solutions(vec![
+///             LinearConstraint::eq([1, 1, 1], 100),
+///             LinearConstraint::le([1, -1, 0], 0),
+///         ]).with_bounds([0..=100, 0..=100, 0..=100])
+///     ) {
+///         prop_assert_eq!(100, a + b + c);
+///         prop_assert!(0 <= a && a <= b && 0 <= c);
+///     }
+/// }
+/// # fn main() { budget_is_preserved(); }
+/// ```
+pub fn linear_solutions<const N: usize>(
+    constraints: impl Into<Vec<LinearConstraint<N>>>,
+) -> LinearSolutions<N> {
+    let constraints: Arc<[LinearConstraint<N>]> = constraints.into().into();
+    let unbounded = [(i128::from(i64::MIN), i128::from(i64::MAX)); N];
+    let domain = propagate(&constraints, unbounded);
+
+    LinearSolutions {
+        constraints,
+        domain,
+    }
+}
+
+impl<const N: usize> Strategy for LinearSolutions<N> {
+    type Tree = LinearSolutionsValueTree<N>;
+    type Value = [i64; N];
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let domain =
+            self.domain.ok_or("linear constraints are unsatisfiable")?;
+
+        'generate: loop {
+            let mut values = [0i64; N];
+            for var in 0..N {
+                match feasible_range(&self.constraints, &domain, &values, var) {
+                    Some((lo, hi)) => {
+                        values[var] = sample_uniform_incl(runner, lo, hi)
+                    }
+                    None => {
+                        runner.reject_local(
+                            "linear constraints unsatisfiable for generated \
+                             prefix",
+                        )?;
+                        continue 'generate;
+                    }
+                }
+            }
+
+            if !self.constraints.iter().all(|c| c.is_satisfied_by(&values)) {
+                runner.reject_local("linear constraints not satisfied")?;
+                continue;
+            }
+
+            let targets = core::array::from_fn(|var| {
+                let (lo, hi) = domain[var];
+                BinarySearch::new_clamped(lo, values[var], hi.saturating_add(1))
+            });
+
+            return Ok(LinearSolutionsValueTree {
+                constraints: Arc::clone(&self.constraints),
+                domain,
+                targets,
+                current: values,
+                shrinker: 0,
+                prev_shrinker: None,
+            });
+        }
+    }
+}
+
+/// `ValueTree` corresponding to `LinearSolutions`.
+///
+/// Each variable has a target value, which is what is shrunk. The current
+/// value is derived by moving each target, in order, to the closest value
+/// compatible with the constraints and the variables before it.
+#[derive(Clone, Debug)]
+pub struct LinearSolutionsValueTree<const N: usize> {
+    constraints: Arc<[LinearConstraint<N>]>,
+    domain: [(i64, i64); N],
+    targets: [BinarySearch; N],
+    current: [i64; N],
+    shrinker: usize,
+    prev_shrinker: Option<usize>,
+}
+
+impl<const N: usize> LinearSolutionsValueTree<N> {
+    /// Recompute `current` from the targets. Returns `false`, leaving
+    /// `current` untouched, if the targets do not lead to a solution.
+    fn solve(&mut self) -> bool {
+        let mut values = [0i64; N];
+        for var in 0..N {
+            match feasible_range(&self.constraints, &self.domain, &values, var)
+            {
+                Some((lo, hi)) => {
+                    values[var] = self.targets[var].current().max(lo).min(hi)
+                }
+                None => return false,
+            }
+        }
+
+        if self.constraints.iter().all(|c| c.is_satisfied_by(&values)) {
+            self.current = values;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Complicate the target at `var` until the targets lead to a solution
+    /// again, or the target cannot be complicated any further.
+    fn complicate_until_solved(&mut self, var: usize) -> bool {
+        while self.targets[var].complicate() {
+            if self.solve() {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<const N: usize> ValueTree for LinearSolutionsValueTree<N> {
+    type Value = [i64; N];
+
+    fn current(&self) -> [i64; N] {
+        self.current
+    }
+
+    fn simplify(&mut self) -> bool {
+        while self.shrinker < N {
+            let var = self.shrinker;
+            if self.targets[var].simplify() {
+                if self.solve() || self.complicate_until_solved(var) {
+                    self.prev_shrinker = Some(var);
+                    return true;
+                }
+            } else {
+                self.shrinker += 1;
+            }
+        }
+
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrinker {
+            Some(var) => {
+                self.shrinker = var;
+                if self.complicate_until_solved(var) {
+                    true
+                } else {
+                    self.prev_shrinker = None;
+                    false
+                }
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bounded<const N: usize>(
+        lo: i64,
+        hi: i64,
+        constraints: Vec<LinearConstraint<N>>,
+    ) -> LinearSolutions<N> {
+        linear_solutions(constraints).with_bounds([(); N].map(|_| lo..=hi))
+    }
+
+    #[test]
+    fn sum_constraint_never_rejects() {
+        let strategy =
+            bounded(0, 100, vec![LinearConstraint::eq([1, 1, 1], 100)]);
+
+        let mut runner = TestRunner::new_with_rng(
+            Config {
+                max_local_rejects: 0,
+                ..Config::default()
+            },
+            TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+        );
+        for _ in 0..1000 {
+            let [a, b, c] = strategy.new_tree(&mut runner).unwrap().current();
+            assert_eq!(100, a + b + c);
+            assert!([a, b, c].iter().all(|&v| (0..=100).contains(&v)));
+        }
+    }
+
+    #[test]
+    fn mixed_constraints_are_satisfied() {
+        let strategy = bounded(
+            -50,
+            50,
+            vec![
+                LinearConstraint::eq([2, -1, 0, 1], 7),
+                LinearConstraint::le([1, 1, 1, 0], 20),
+                LinearConstraint::ge([0, 1, -3, 0], -40),
+            ],
+        );
+        let constraints = strategy.constraints.clone();
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..1000 {
+            let values = strategy.new_tree(&mut runner).unwrap().current();
+            assert!(
+                constraints.iter().all(|c| c.is_satisfied_by(&values)),
+                "{:?} violates constraints",
+                values
+            );
+        }
+    }
+
+    #[test]
+    fn divisibility_is_handled_by_rejection() {
+        let strategy = bounded(0, 100, vec![LinearConstraint::eq([1, 3], 50)]);
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..100 {
+            let [a, b] = strategy.new_tree(&mut runner).unwrap().current();
+            assert_eq!(50, a + 3 * b);
+        }
+    }
+
+    #[test]
+    fn unsatisfiable_constraints_are_reported() {
+        let strategy = bounded(0, 10, vec![LinearConstraint::eq([1, 1], 30)]);
+        assert!(strategy.new_tree(&mut TestRunner::deterministic()).is_err());
+    }
+
+    #[test]
+    fn shrinks_while_preserving_constraints() {
+        let strategy =
+            bounded(0, 1000, vec![LinearConstraint::eq([1, 1, 1], 1000)]);
+        let constraints = strategy.constraints.clone();
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let tree = strategy.new_tree(&mut runner).unwrap();
+            let result = runner.run_one(tree, |[a, _, _]| {
+                prop_assert!(a < 300);
+                Ok(())
+            });
+
+            if let Err(TestError::Fail(_, values)) = result {
+                assert!(constraints.iter().all(|c| c.is_satisfied_by(&values)));
+                assert_eq!([300, 0, 700], values);
+            }
+        }
+    }
+
+    #[test]
+    fn contract_sanity() {
+        check_strategy_sanity(
+            bounded(
+                -20,
+                20,
+                vec![
+                    LinearConstraint::eq([1, 1, 1], 5),
+                    LinearConstraint::le([1, -1, 0], 3),
+                ],
+            ),
+            None,
+        );
+    }
+}
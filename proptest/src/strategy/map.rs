@@ -242,6 +242,153 @@ impl<S: ValueTree, O: fmt::Debug, F: Fn(S::Value, TestRng) -> O> ValueTree
     }
 }
 
+//==============================================================================
+// MapValidated
+//==============================================================================
+
+/// A `ValueTree` built on a source tree which only exposes the states of the
+/// source accepted by `is_valid()`. Invalid states are stepped over as if the
+/// test had passed on them, so the test never observes them.
+pub(crate) trait ValidatedValueTree {
+    /// The tree whose states are being validated.
+    type Source: ValueTree;
+
+    /// Returns the source tree.
+    fn source_mut(&mut self) -> &mut Self::Source;
+
+    /// Returns whether the current state of the source is valid.
+    fn is_valid(&self) -> bool;
+
+    /// Step back towards the last value shown to the test until the current
+    /// state is valid again, as if the test had passed on each invalid state.
+    ///
+    /// Returns `false` if the source ran out of complications before reaching
+    /// a valid state.
+    fn complicate_until_valid(&mut self) -> bool {
+        while !self.is_valid() {
+            if !self.source_mut().complicate() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Implements `ValueTree::simplify()`.
+    fn simplify_valid(&mut self) -> bool {
+        // If a simplification only leads to invalid states, backing out of it
+        // still narrows the source's search space, so keep trying until the
+        // source itself gives up.
+        while self.source_mut().simplify() {
+            if self.complicate_until_valid() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Implements `ValueTree::complicate()`.
+    fn complicate_valid(&mut self) -> bool {
+        self.source_mut().complicate() && self.complicate_until_valid()
+    }
+}
+
+/// `Strategy` and `ValueTree` map adaptor which keeps shrinking within the
+/// domain accepted by a validator.
+///
+/// See `Strategy::prop_map_validated()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct MapValidated<S, F, V> {
+    pub(super) source: S,
+    pub(super) fun: Arc<F>,
+    pub(super) validator: Arc<V>,
+}
+
+impl<S: fmt::Debug, F, V> fmt::Debug for MapValidated<S, F, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapValidated")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("validator", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F, V> Clone for MapValidated<S, F, V> {
+    fn clone(&self) -> Self {
+        MapValidated {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+            validator: Arc::clone(&self.validator),
+        }
+    }
+}
+
+impl<S, O, F, V> Strategy for MapValidated<S, F, V>
+where
+    S: Strategy,
+    O: fmt::Debug,
+    F: Fn(S::Value) -> O,
+    V: Fn(&O) -> bool,
+{
+    type Tree = MapValidated<S::Tree, F, V>;
+    type Value = O;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            let tree = MapValidated {
+                source: self.source.new_tree(runner)?,
+                fun: Arc::clone(&self.fun),
+                validator: Arc::clone(&self.validator),
+            };
+
+            if tree.is_valid() {
+                return Ok(tree);
+            }
+
+            runner.reject_local("value rejected by prop_map_validated")?;
+        }
+    }
+}
+
+impl<S, O, F, V> ValidatedValueTree for MapValidated<S, F, V>
+where
+    S: ValueTree,
+    F: Fn(S::Value) -> O,
+    V: Fn(&O) -> bool,
+{
+    type Source = S;
+
+    fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    fn is_valid(&self) -> bool {
+        (self.validator)(&(self.fun)(self.source.current()))
+    }
+}
+
+impl<S, O, F, V> ValueTree for MapValidated<S, F, V>
+where
+    S: ValueTree,
+    O: fmt::Debug,
+    F: Fn(S::Value) -> O,
+    V: Fn(&O) -> bool,
+{
+    type Value = O;
+
+    fn current(&self) -> O {
+        (self.fun)(self.source.current())
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.simplify_valid()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.complicate_valid()
+    }
+}
+
 //==============================================================================
 // Tests
 //==============================================================================
@@ -298,4 +445,82 @@ mod test {
 
         assert_eq!(64, seen.len());
     }
+
+    #[test]
+    fn map_validated_never_shows_invalid_values() {
+        let input = (0..10_000).prop_map_validated(|v| v * 3, |&v| v % 2 == 1);
+
+        let mut runner = TestRunner::default();
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            assert_eq!(1, case.current() % 2);
+
+            while case.simplify() {
+                assert_eq!(1, case.current() % 2);
+            }
+            while case.complicate() {
+                assert_eq!(1, case.current() % 2);
+            }
+        }
+    }
+
+    #[test]
+    fn map_validated_stops_complicating_without_valid_state() {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let accept = Rc::new(Cell::new(true));
+        let validator_accept = Rc::clone(&accept);
+        let input = (0..10_000)
+            .prop_map_validated(|v| v, move |_| validator_accept.get());
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            accept.set(true);
+            let mut case = input.new_tree(&mut runner).unwrap();
+            if !case.simplify() {
+                continue;
+            }
+
+            accept.set(false);
+            assert!(!case.complicate());
+        }
+    }
+
+    #[test]
+    fn map_validated_shrinks_to_valid_failure() {
+        let input = (0..10_000).prop_map_validated(|v| v * 3, |&v| v % 2 == 1);
+
+        for _ in 0..64 {
+            let mut runner = TestRunner::new(Config {
+                failure_persistence: None,
+                ..Config::default()
+            });
+            let result = runner.run(&input, |v| {
+                prop_assert!(v % 2 == 1, "invalid value {} shown to test", v);
+                prop_assert!(v < 300);
+                Ok(())
+            });
+
+            match result {
+                Err(TestError::Fail(why, value)) => {
+                    assert!(value >= 300 && 1 == value % 2, "{}", why)
+                }
+                result => panic!("Unexpected result: {:?}", result),
+            }
+        }
+    }
+
+    #[test]
+    fn map_validated_sanity() {
+        check_strategy_sanity(
+            (0..1000).prop_map_validated(|v| v * 2, |&v| v % 3 != 0),
+            Some(CheckStrategySanityOptions {
+                // Skipping invalid states may land back where `complicate()`
+                // would have gone.
+                strict_complicate_after_simplify: false,
+                ..CheckStrategySanityOptions::default()
+            }),
+        );
+    }
 }
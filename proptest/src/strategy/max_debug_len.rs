@@ -0,0 +1,157 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::fmt;
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// How many values `MaxDebugLen` generates before rejecting locally.
+const ATTEMPTS_PER_REJECTION: u32 = 16;
+
+/// `fmt::Write` sink which only counts bytes, and fails once there are more
+/// than `max_len` of them to stop formatting early.
+struct LenCounter {
+    len: usize,
+    max_len: usize,
+}
+
+impl fmt::Write for LenCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.len += s.len();
+        if self.len > self.max_len {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returns whether the `Debug` output of `value` is at most `max_len` bytes
+/// long.
+fn debug_len_within(value: &impl fmt::Debug, max_len: usize) -> bool {
+    let mut counter = LenCounter { len: 0, max_len };
+    fmt::write(&mut counter, format_args!("{:?}", value)).is_ok()
+}
+
+/// `Strategy` and `ValueTree` adaptor which regenerates values whose `Debug`
+/// output is too long.
+///
+/// See `Strategy::prop_max_debug_len()`.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct MaxDebugLen<S> {
+    source: S,
+    max_len: usize,
+}
+
+impl<S> MaxDebugLen<S> {
+    pub(super) fn new(source: S, max_len: usize) -> Self {
+        MaxDebugLen { source, max_len }
+    }
+}
+
+impl<S: Strategy> Strategy for MaxDebugLen<S> {
+    type Tree = MaxDebugLen<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        loop {
+            for _ in 0..ATTEMPTS_PER_REJECTION {
+                let source = self.source.new_tree(runner)?;
+                if debug_len_within(&source.current(), self.max_len) {
+                    return Ok(MaxDebugLen::new(source, self.max_len));
+                }
+            }
+            runner.reject_local("Debug output of values too long")?;
+        }
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.source.value_size_hint()
+    }
+}
+
+impl<S: ValueTree> MaxDebugLen<S> {
+    fn ensure_acceptable(&mut self) {
+        while !debug_len_within(&self.source.current(), self.max_len) {
+            if !self.source.complicate() {
+                panic!(
+                    "Unable to complicate strategy back into value with \
+                     short enough Debug output"
+                );
+            }
+        }
+    }
+}
+
+impl<S: ValueTree> ValueTree for MaxDebugLen<S> {
+    type Value = S::Value;
+
+    fn current(&self) -> S::Value {
+        self.source.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.source.simplify() {
+            self.ensure_acceptable();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        if self.source.complicate() {
+            self.ensure_acceptable();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection::vec;
+
+    #[test]
+    fn regenerates_values_with_long_debug_output() {
+        let input = vec(0..1000u32, 0..20).prop_max_debug_len(16);
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut case = input.new_tree(&mut runner).unwrap();
+            assert!(format!("{:?}", case.current()).len() <= 16);
+            while case.simplify() {
+                assert!(format!("{:?}", case.current()).len() <= 16);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_locally_when_no_value_fits() {
+        let input = vec(0..10u32, 5).prop_max_debug_len(4);
+
+        let mut runner = TestRunner::new(Config {
+            max_local_rejects: 8,
+            ..Config::default()
+        });
+        assert!(input.new_tree(&mut runner).is_err());
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(
+            vec(0..100u32, 0..8).prop_max_debug_len(12),
+            None,
+        );
+    }
+}
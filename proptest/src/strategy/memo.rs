@@ -0,0 +1,488 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt;
+use core::mem;
+use core::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+use crate::std_facade::{Arc, Vec};
+use crate::strategy::flatten::FlattenValueTree;
+use crate::strategy::traits::*;
+use crate::strategy::{Flatten, Map};
+use crate::test_runner::*;
+
+/// The number of inner value trees `Flatten::prop_memo()` keeps by default.
+pub const MEMO_CAPACITY: usize = 64;
+
+/// Counters of the lookups in the cache of a `MemoFlatten`.
+///
+/// The counters of every memoized flat map used by a test are logged at the
+/// end of a passing run if `Config::verbose` is at least 1.
+#[derive(Debug, Default)]
+pub struct MemoStats {
+    hits: AtomicU32,
+    misses: AtomicU32,
+    evictions: AtomicU32,
+}
+
+impl MemoStats {
+    /// Returns the number of inner value trees taken from the cache.
+    pub fn hits(&self) -> u32 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of inner value trees which had to be generated.
+    pub fn misses(&self) -> u32 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of inner value trees dropped from the cache to
+    /// make room for more recently used ones.
+    pub fn evictions(&self) -> u32 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+}
+
+/// The inner value trees of a `MemoFlatten` by key, the most recently used
+/// first.
+struct MemoCache<K, T> {
+    capacity: usize,
+    entries: Mutex<Vec<(K, T)>>,
+    stats: Arc<MemoStats>,
+}
+
+impl<K: PartialEq + Clone, T: Clone> MemoCache<K, T> {
+    /// Returns a copy of the tree cached under `key`, or generates it with
+    /// `generate` and caches a copy of it if there is none.
+    ///
+    /// The cache only ever holds trees as they were generated, so each use
+    /// shrinks a copy of its own.
+    fn get_or_generate(
+        &self,
+        key: &K,
+        generate: impl FnOnce() -> Result<T, Reason>,
+    ) -> Result<T, Reason> {
+        {
+            let mut entries = self.entries.lock().unwrap();
+            if let Some(ix) = entries.iter().position(|(k, _)| k == key) {
+                let entry = entries.remove(ix);
+                let tree = entry.1.clone();
+                entries.insert(0, entry);
+                self.stats.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(tree);
+            }
+        }
+
+        // Not holding the lock while generating, in case the inner strategy
+        // uses this cache too.
+        let tree = generate()?;
+        self.stats.misses.fetch_add(1, Ordering::Relaxed);
+        if self.capacity > 0 {
+            let mut entries = self.entries.lock().unwrap();
+            if entries.len() >= self.capacity {
+                entries.truncate(self.capacity - 1);
+                self.stats.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+            entries.insert(0, (key.clone(), tree.clone()));
+        }
+        Ok(tree)
+    }
+}
+
+/// `Strategy` returned by `Flatten::prop_memo()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct MemoFlatten<S, F, KF, K, R: Strategy> {
+    source: S,
+    fun: Arc<F>,
+    key_fn: Arc<KF>,
+    cache: Arc<MemoCache<K, R::Tree>>,
+}
+
+impl<S: fmt::Debug, F, KF, K, R: Strategy> fmt::Debug
+    for MemoFlatten<S, F, KF, K, R>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoFlatten")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("key_fn", &"<function>")
+            .field("capacity", &self.cache.capacity)
+            .field("stats", &self.cache.stats)
+            .finish()
+    }
+}
+
+impl<S: Clone, F, KF, K, R: Strategy> Clone for MemoFlatten<S, F, KF, K, R> {
+    fn clone(&self) -> Self {
+        MemoFlatten {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+            key_fn: Arc::clone(&self.key_fn),
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+impl<S: Strategy, F> Flatten<Map<S, F>> {
+    /// Memoizes the inner value trees of this flat map by the key `key_fn`
+    /// computes from the outer value.
+    ///
+    /// When generating the inner value is expensive and the outer values
+    /// repeat, e.g. because they come from a small domain, this avoids
+    /// generating the inner value again for every case and every shrinking
+    /// step which revisits an outer value. Instead, the value tree generated
+    /// the first time a key is seen is kept, and a copy of it is used each
+    /// time the key comes up again. Each copy starts from the value as it was
+    /// generated and shrinks on its own, so shrinking behaves as without
+    /// memoization.
+    ///
+    /// The value trees of the inner strategy must be `Clone`, as well as the
+    /// outer values, which are kept to generate the inner value on a miss.
+    ///
+    /// This trades randomness and memory for speed: every use of a key yields
+    /// the same initial inner value, and up to `MEMO_CAPACITY` inner value
+    /// trees are kept, dropping the least recently used ones beyond that.
+    /// So that whether a key is found in the cache doesn't change the value,
+    /// the inner value tree for a key is generated from an RNG derived from
+    /// the `Debug` output of the key alone, rather than from the RNG of the
+    /// test case. A failing case therefore replays from its persisted seed
+    /// even though the cache starts out empty, but the inner value for a key
+    /// is also the same in every run. This does not apply to runners whose
+    /// RNG replays recorded data or is backed by a `ChoiceSource`, which
+    /// generate the inner value from their own RNG on each miss.
+    /// The capacity can be changed with `MemoFlatten::with_capacity()`. The
+    /// cache is shared by the clones of the returned strategy.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// use proptest::collection::vec;
+    ///
+    /// proptest! {
+    ///     # /*
+    ///     #[test]
+    ///     # */
+    ///     fn grid_is_square(
+    ///         grid in (1..4usize)
+    ///             .prop_flat_map(|size| vec(vec(any::<u8>(), size), size))
+    ///             .prop_memo(|&size| size)
+    ///     ) {
+    ///         prop_assert!(grid.iter().all(|row| row.len() == grid.len()));
+    ///     }
+    /// }
+    /// # fn main() { grid_is_square(); }
+    /// ```
+    pub fn prop_memo<R, K, KF>(self, key_fn: KF) -> MemoFlatten<S, F, KF, K, R>
+    where
+        F: Fn(S::Value) -> R,
+        R: Strategy,
+        R::Tree: Clone,
+        KF: Fn(&S::Value) -> K,
+        K: PartialEq + Clone + fmt::Debug,
+        S::Value: Clone,
+    {
+        MemoFlatten {
+            source: self.source.source,
+            fun: self.source.fun,
+            key_fn: Arc::new(key_fn),
+            cache: Arc::new(MemoCache {
+                capacity: MEMO_CAPACITY,
+                entries: Mutex::new(Vec::new()),
+                stats: Arc::default(),
+            }),
+        }
+    }
+}
+
+impl<S, F, KF, K, R: Strategy> MemoFlatten<S, F, KF, K, R> {
+    /// Keep up to `capacity` inner value trees instead of `MEMO_CAPACITY`.
+    ///
+    /// This starts a new, empty cache.
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        MemoFlatten {
+            cache: Arc::new(MemoCache {
+                capacity,
+                entries: Mutex::new(Vec::new()),
+                stats: Arc::default(),
+            }),
+            ..self
+        }
+    }
+
+    /// Returns the counters of the lookups in the cache.
+    pub fn stats(&self) -> Arc<MemoStats> {
+        Arc::clone(&self.cache.stats)
+    }
+}
+
+impl<S, F, KF, K, R> Strategy for MemoFlatten<S, F, KF, K, R>
+where
+    S: Strategy,
+    S::Value: Clone,
+    F: Fn(S::Value) -> R,
+    R: Strategy,
+    R::Tree: Clone,
+    KF: Fn(&S::Value) -> K,
+    K: PartialEq + Clone + fmt::Debug,
+{
+    type Tree = FlattenValueTree<MemoMetaTree<S::Tree, F, KF, K, R>>;
+    type Value = R::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        runner.track_memo(&self.cache.stats);
+        let meta = MemoMetaTree {
+            source: self.source.new_tree(runner)?,
+            fun: Arc::clone(&self.fun),
+            key_fn: Arc::clone(&self.key_fn),
+            cache: Arc::clone(&self.cache),
+        };
+        FlattenValueTree::new(runner, meta)
+    }
+}
+
+/// The `ValueTree` of the outer values of a `MemoFlatten`, producing the
+/// strategies of the inner values.
+pub struct MemoMetaTree<T, F, KF, K, R: Strategy> {
+    source: T,
+    fun: Arc<F>,
+    key_fn: Arc<KF>,
+    cache: Arc<MemoCache<K, R::Tree>>,
+}
+
+impl<T: fmt::Debug, F, KF, K, R: Strategy> fmt::Debug
+    for MemoMetaTree<T, F, KF, K, R>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoMetaTree")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("key_fn", &"<function>")
+            .finish()
+    }
+}
+
+impl<T: Clone, F, KF, K, R: Strategy> Clone for MemoMetaTree<T, F, KF, K, R> {
+    fn clone(&self) -> Self {
+        MemoMetaTree {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+            key_fn: Arc::clone(&self.key_fn),
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+impl<T, F, KF, K, R> ValueTree for MemoMetaTree<T, F, KF, K, R>
+where
+    T: ValueTree,
+    T::Value: Clone,
+    F: Fn(T::Value) -> R,
+    R: Strategy,
+    R::Tree: Clone,
+    KF: Fn(&T::Value) -> K,
+    K: PartialEq + Clone + fmt::Debug,
+{
+    type Value = MemoInner<T::Value, F, K, R>;
+
+    fn current(&self) -> Self::Value {
+        let value = self.source.current();
+        MemoInner {
+            key: (self.key_fn)(&value),
+            value,
+            fun: Arc::clone(&self.fun),
+            cache: Arc::clone(&self.cache),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+/// The `Strategy` of the inner values of a `MemoFlatten` for one outer value,
+/// which takes the inner value tree from the cache if it has one for the key
+/// of the outer value.
+pub struct MemoInner<V, F, K, R: Strategy> {
+    key: K,
+    value: V,
+    fun: Arc<F>,
+    cache: Arc<MemoCache<K, R::Tree>>,
+}
+
+impl<V: fmt::Debug, F, K: fmt::Debug, R: Strategy> fmt::Debug
+    for MemoInner<V, F, K, R>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoInner")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<V: Clone, F, K: Clone, R: Strategy> Clone for MemoInner<V, F, K, R> {
+    fn clone(&self) -> Self {
+        MemoInner {
+            key: self.key.clone(),
+            value: self.value.clone(),
+            fun: Arc::clone(&self.fun),
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+impl<V, F, K, R> Strategy for MemoInner<V, F, K, R>
+where
+    V: Clone + fmt::Debug,
+    F: Fn(V) -> R,
+    R: Strategy,
+    R::Tree: Clone,
+    K: PartialEq + Clone + fmt::Debug,
+{
+    type Tree = R::Tree;
+    type Value = R::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.cache.get_or_generate(&self.key, || {
+            let inner = (self.fun)(self.value.clone());
+            // Generating from the key alone means a miss yields the same tree
+            // a hit would have, whatever the earlier cases put in the cache,
+            // so that a case replayed from its persisted seed is the same.
+            match runner.rng().detached(&format!("{:?}", self.key)) {
+                Some(rng) => {
+                    let rng = mem::replace(runner.rng(), rng);
+                    let tree = inner.new_tree(runner);
+                    *runner.rng() = rng;
+                    tree
+                }
+                None => inner.new_tree(runner),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+    use crate::collection::vec;
+    use crate::strategy::{check_strategy_sanity, Just};
+
+    #[test]
+    fn repeated_keys_reuse_inner_trees() {
+        let generated = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&generated);
+        let strategy = (0..4usize)
+            .prop_flat_map(move |len| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                vec(0..1000u32, len)
+            })
+            .prop_memo(|&len| len);
+        let stats = strategy.stats();
+
+        let mut runner = TestRunner::deterministic();
+        let mut first_by_len = crate::std_facade::BTreeMap::new();
+        for _ in 0..256 {
+            let value = strategy.new_tree(&mut runner).unwrap().current();
+            let first =
+                first_by_len.entry(value.len()).or_insert(value.clone());
+            assert_eq!(*first, value);
+        }
+
+        assert_eq!(4, generated.load(Ordering::SeqCst));
+        assert_eq!(4, stats.misses());
+        assert_eq!(252, stats.hits());
+        assert_eq!(0, stats.evictions());
+    }
+
+    #[test]
+    fn least_recently_used_trees_are_evicted() {
+        let strategy = (0..100u32)
+            .prop_flat_map(Just)
+            .prop_memo(|&x| x)
+            .with_capacity(2);
+        let stats = strategy.stats();
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let _ = strategy.new_tree(&mut runner).unwrap();
+        }
+        assert_eq!(2, strategy.cache.entries.lock().unwrap().len());
+        assert_eq!(stats.misses() - 2, stats.evictions());
+    }
+
+    #[test]
+    fn shrinking_starts_from_a_fresh_copy() {
+        let strategy = (0..2usize)
+            .prop_flat_map(|len| vec(0..1000u32, len + 4))
+            .prop_memo(|&len| len);
+        let mut runner = TestRunner::deterministic();
+
+        let mut tree = strategy.new_tree(&mut runner).unwrap();
+        let generated = tree.current();
+        while tree.simplify() {}
+        assert!(tree.current().iter().all(|&x| x == 0));
+
+        // the next tree for the same key starts from the generated value,
+        // not from where the previous one shrank to
+        loop {
+            let tree = strategy.new_tree(&mut runner).unwrap();
+            if tree.current().len() == generated.len() {
+                assert_eq!(generated, tree.current());
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn misses_generate_the_same_tree_as_hits() {
+        let strategy = (0..4usize)
+            .prop_flat_map(|len| vec(0..1000u32, len))
+            .prop_memo(|&len| len)
+            .with_capacity(1);
+        let stats = strategy.stats();
+
+        let mut runner = TestRunner::deterministic();
+        let mut first_by_len = crate::std_facade::BTreeMap::new();
+        for _ in 0..256 {
+            let value = strategy.new_tree(&mut runner).unwrap().current();
+            let first =
+                first_by_len.entry(value.len()).or_insert(value.clone());
+            assert_eq!(*first, value);
+        }
+        assert!(stats.evictions() > 0);
+
+        // A fresh cache, as when a persisted seed is replayed, generates the
+        // same trees too.
+        let fresh = strategy.clone().with_capacity(0);
+        let mut runner = TestRunner::default();
+        for _ in 0..64 {
+            let value = fresh.new_tree(&mut runner).unwrap().current();
+            assert_eq!(first_by_len[&value.len()], value);
+        }
+    }
+
+    #[test]
+    fn memo_sanity() {
+        check_strategy_sanity(
+            (0..8usize)
+                .prop_flat_map(|len| vec(0..10u8, len))
+                .prop_memo(|&len| len),
+            None,
+        );
+    }
+}
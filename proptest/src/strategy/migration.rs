@@ -0,0 +1,223 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::Arc;
+use core::fmt;
+
+use crate::arbitrary::{any, Arbitrary, StrategyFor};
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// Types with a newer version they can be upgraded to, such as the previous
+/// version of a persisted schema or wire format.
+///
+/// This is usually implemented with `#[derive(Arbitrary)]` and
+/// `#[proptest(migrates_to = "NewType", via = "path")]`, and used through
+/// `any_migration()`.
+pub trait MigratesTo<New> {
+    /// Upgrades this value to its counterpart of the newer version.
+    fn migrate(&self) -> New;
+}
+
+/// `Strategy` and `ValueTree` generating pairs of a value of an old version
+/// and its upgrade to the new version.
+///
+/// See `migration_pair()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct MigrationPair<S, F> {
+    source: S,
+    upgrade: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for MigrationPair<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MigrationPair")
+            .field("source", &self.source)
+            .field("upgrade", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for MigrationPair<S, F> {
+    fn clone(&self) -> Self {
+        MigrationPair {
+            source: self.source.clone(),
+            upgrade: Arc::clone(&self.upgrade),
+        }
+    }
+}
+
+/// Generates pairs of a value of `old` and the value `upgrade` turns it into,
+/// e.g. a record of the previous version of a schema along with the record
+/// of the current version it is expected to be migrated to.
+///
+/// Only the old value is generated and shrunk; the new value is always
+/// computed from it, so the two stay consistent with each other while a
+/// failing pair is minimised.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::migration_pair;
+///
+/// #[derive(Clone, Debug)]
+/// struct ConfigV1 { timeout_secs: u32 }
+/// #[derive(Clone, Debug, PartialEq)]
+/// struct ConfigV2 { timeout_millis: u64 }
+///
+/// fn upgrade(old: &ConfigV1) -> ConfigV2 {
+///     ConfigV2 { timeout_millis: u64::from(old.timeout_secs) * 1000 }
+/// }
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn migration_keeps_timeout((old, new) in migration_pair(
+///         any::<u32>().prop_map(|timeout_secs| ConfigV1 { timeout_secs }),
+///         upgrade,
+///     )) {
+///         prop_assert_eq!(u64::from(old.timeout_secs) * 1000, new.timeout_millis);
+///     }
+/// }
+/// # fn main() { migration_keeps_timeout(); }
+/// ```
+pub fn migration_pair<S, F, N>(old: S, upgrade: F) -> MigrationPair<S, F>
+where
+    S: Strategy,
+    F: Fn(&S::Value) -> N,
+    N: fmt::Debug,
+{
+    MigrationPair {
+        source: old,
+        upgrade: Arc::new(upgrade),
+    }
+}
+
+/// Generates pairs of an arbitrary value of type `Old` and its upgrade to
+/// `New` through `MigratesTo::migrate()`.
+///
+/// This is equivalent to `migration_pair(any::<Old>(), Old::migrate)`.
+pub fn any_migration<Old, New>(
+) -> MigrationPair<StrategyFor<Old>, fn(&Old) -> New>
+where
+    Old: Arbitrary + MigratesTo<New>,
+    New: fmt::Debug,
+{
+    migration_pair(any::<Old>(), Old::migrate as fn(&Old) -> New)
+}
+
+impl<S, F, N> Strategy for MigrationPair<S, F>
+where
+    S: Strategy,
+    F: Fn(&S::Value) -> N,
+    N: fmt::Debug,
+{
+    type Tree = MigrationPair<S::Tree, F>;
+    type Value = (S::Value, N);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.source.new_tree(runner).map(|source| MigrationPair {
+            source,
+            upgrade: Arc::clone(&self.upgrade),
+        })
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.source.value_size_hint()
+    }
+}
+
+impl<T, F, N> ValueTree for MigrationPair<T, F>
+where
+    T: ValueTree,
+    F: Fn(&T::Value) -> N,
+    N: fmt::Debug,
+{
+    type Value = (T::Value, N);
+
+    fn current(&self) -> Self::Value {
+        let old = self.source.current();
+        let new = (self.upgrade)(&old);
+        (old, new)
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.source.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::strategy::check_strategy_sanity;
+
+    #[derive(Clone, Copy, Debug)]
+    struct Celsius(i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Kelvin(i64);
+
+    impl Arbitrary for Celsius {
+        type Parameters = ();
+        type Strategy =
+            crate::strategy::Map<core::ops::Range<i32>, fn(i32) -> Celsius>;
+
+        fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+            (-273..1000).prop_map(Celsius as fn(i32) -> Celsius)
+        }
+    }
+
+    impl MigratesTo<Kelvin> for Celsius {
+        fn migrate(&self) -> Kelvin {
+            Kelvin(i64::from(self.0) + 273)
+        }
+    }
+
+    #[test]
+    fn pairs_stay_consistent_while_shrinking() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = migration_pair(0u32..1000, |&old| u64::from(old) * 2);
+        for _ in 0..32 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            loop {
+                let (old, new) = tree.current();
+                assert_eq!(u64::from(old) * 2, new);
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!((0, 0), tree.current());
+        }
+    }
+
+    #[test]
+    fn any_migration_upgrades_arbitrary_values() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = any_migration::<Celsius, Kelvin>();
+        for _ in 0..256 {
+            let (old, new) = strategy.new_tree(&mut runner).unwrap().current();
+            assert_eq!(Kelvin(i64::from(old.0) + 273), new);
+            assert!(new.0 >= 0);
+        }
+    }
+
+    #[test]
+    fn migration_pair_sanity() {
+        check_strategy_sanity(
+            migration_pair(crate::collection::vec(0u8..10, 0..8), |old| {
+                old.len()
+            }),
+            None,
+        );
+    }
+}
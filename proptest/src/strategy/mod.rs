@@ -9,27 +9,53 @@
 
 //! Defines the core traits used by Proptest.
 
+mod atomic;
+mod data;
 mod filter;
 mod filter_map;
 mod flatten;
 mod fuse;
+mod into_strategy;
+mod iter;
 mod just;
+mod labeled;
 mod lazy;
+mod linear;
 mod map;
+mod max_debug_len;
+#[cfg(feature = "std")]
+mod memo;
+mod migration;
 mod recursive;
+mod shrink_budget;
 mod shuffle;
+mod special_values;
+mod split;
 mod traits;
 mod unions;
 
+pub use self::atomic::*;
+pub use self::data::*;
 pub use self::filter::*;
 pub use self::filter_map::*;
 pub use self::flatten::*;
 pub use self::fuse::*;
+pub use self::into_strategy::*;
+pub use self::iter::*;
 pub use self::just::*;
+pub use self::labeled::*;
 pub use self::lazy::*;
+pub use self::linear::*;
 pub use self::map::*;
+pub use self::max_debug_len::*;
+#[cfg(feature = "std")]
+pub use self::memo::*;
+pub use self::migration::*;
 pub use self::recursive::*;
+pub use self::shrink_budget::*;
 pub use self::shuffle::*;
+pub use self::special_values::*;
+pub use self::split::*;
 pub use self::traits::*;
 pub use self::unions::*;
 
@@ -7,8 +7,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{fmt, Arc, Box, Vec};
+use crate::std_facade::{fmt, Arc, Box, Rc, Vec};
+use core::cell::{Cell, RefCell};
 
+use rand::Rng;
+
+use crate::option::Probability;
 use crate::strategy::traits::*;
 use crate::strategy::unions::float_to_weight;
 use crate::test_runner::*;
@@ -68,6 +72,57 @@ impl<
             expected_branch_size,
         }
     }
+
+    fn new_counting_tree(
+        &self,
+        runner: &mut TestRunner,
+    ) -> Result<CountingValueTree<T>, Reason> {
+        let counter = Rc::new(DepthCounter::default());
+        let base = &self.base;
+        let recurse = &self.recurse;
+        let strat = build_layers(
+            self.base.clone(),
+            self.depth,
+            self.desired_size,
+            self.expected_branch_size,
+            |strat, _| {
+                Counted {
+                    inner: recurse(strat).boxed(),
+                    base: base.clone(),
+                    counter: Rc::clone(&counter),
+                }
+                .boxed()
+            },
+        );
+        let new_tree = |runner: &mut TestRunner| {
+            strat.new_tree(runner).map(|tree| CountingValueTree {
+                tree,
+                counter: Rc::clone(&counter),
+            })
+        };
+
+        let min_depth = runner.config().min_recursive_depth.min(self.depth);
+        if 0 == min_depth || !runner.rng().gen_bool(0.5) {
+            return new_tree(runner);
+        }
+
+        let mut deepest = new_tree(runner)?;
+        let mut deepest_depth = deepest.current_tracked().depth;
+        for _ in 1..MAX_DEEP_ATTEMPTS {
+            if deepest_depth >= min_depth {
+                break;
+            }
+
+            let tree = new_tree(runner)?;
+            let depth = tree.current_tracked().depth;
+            if depth > deepest_depth {
+                deepest = tree;
+                deepest_depth = depth;
+            }
+        }
+
+        Ok(deepest)
+    }
 }
 
 impl<
@@ -80,60 +135,528 @@ impl<
     type Value = T;
 
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
-        // Since the generator is stateless, we can't implement any "absolutely
-        // X many items" rule. We _can_, however, with extremely high
-        // probability, obtain a value near what we want by using decaying
-        // probabilities of branching as we go down the tree.
-        //
-        // We are given a target size S and a branch size K (branch size =
-        // expected number of items immediately below each branch). We select
-        // some probability P for each level.
-        //
-        // A single level l is thus expected to hold PlK branches. Each of
-        // those will have P(l+1)K child branches of their own, so there are
-        // PlP(l+1)K² second-level branches. The total branches in the tree is
-        // thus (Σ PlK^l) for l from 0 to infinity. Each level is expected to
-        // hold K items, so the total number of items is simply K times the
-        // number of branches, or (K Σ PlK^l). So we want to find a P sequence
-        // such that (lim (K Σ PlK^l) = S), or more simply,
-        // (lim Σ PlK^l = S/K).
-        //
-        // Let Q be a second probability sequence such that Pl = Ql/K^l. This
-        // changes the formulation to (lim Σ Ql = S/K). The series Σ0.5^(l+1)
-        // converges on 1.0, so we can let Ql = S/K * 0.5^(l+1), and so
-        // Pl = S/K^(l+1) * 0.5^(l+1) = S / (2K) ^ (l+1)
-        //
-        // We don't actually have infinite levels here since we _can_ easily
-        // cap to a fixed max depth, so this will be a minor underestimate. We
-        // also clamp all probabilities to 0.9 to ensure that we can't end up
-        // with levels which are always pure branches, which further
-        // underestimates size.
-
-        let mut branch_probabilities = Vec::new();
-        let mut k2 = u64::from(self.expected_branch_size) * 2;
-        for _ in 0..self.depth {
-            branch_probabilities.push(f64::from(self.desired_size) / k2 as f64);
-            k2 = k2.saturating_mul(u64::from(self.expected_branch_size) * 2);
-        }
-
-        let mut strat = self.base.clone();
-        while let Some(branch_probability) = branch_probabilities.pop() {
-            let recursed = (self.recurse)(strat.clone());
-            let recursive_choice = recursed.boxed();
-            let non_recursive_choice = strat;
-            // Clamp the maximum branch probability to 0.9 to ensure we can
-            // generate non-recursive cases reasonably often.
-            let branch_probability = branch_probability.min(0.9);
-            let (weight_branch, weight_leaf) =
-                float_to_weight(branch_probability);
-            let branch = prop_oneof![
-                weight_leaf => non_recursive_choice,
-                weight_branch => recursive_choice,
-            ];
-            strat = branch.boxed();
-        }
-
-        strat.new_tree(runner)
+        self.new_counting_tree(runner)
+            .map(|tree| Box::new(tree) as Box<dyn ValueTree<Value = T>>)
+    }
+}
+
+/// How many values `Recursive` generates at most when trying to reach
+/// `Config::min_recursive_depth`.
+const MAX_DEEP_ATTEMPTS: u32 = 64;
+
+/// A value generated by `Strategy::prop_recursive_tracked()`, along with its
+/// depth.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DepthTracked<T> {
+    /// The generated value.
+    pub value: T,
+    /// The number of nested recursive choices in `value`, i.e. `0` for a
+    /// value of the base strategy, `1` for a value whose immediate children
+    /// are all from the base strategy, and so on.
+    pub depth: u32,
+}
+
+/// Return type from `Strategy::prop_recursive_tracked()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct RecursiveTracked<T, F>(Recursive<T, F>);
+
+impl<T: fmt::Debug, F> fmt::Debug for RecursiveTracked<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RecursiveTracked").field(&self.0).finish()
+    }
+}
+
+impl<T, F> Clone for RecursiveTracked<T, F> {
+    fn clone(&self) -> Self {
+        RecursiveTracked(self.0.clone())
+    }
+}
+
+impl<
+        T: fmt::Debug + 'static,
+        R: Strategy<Value = T> + 'static,
+        F: Fn(BoxedStrategy<T>) -> R,
+    > RecursiveTracked<T, F>
+{
+    pub(super) fn new(
+        base: impl Strategy<Value = T> + 'static,
+        depth: u32,
+        desired_size: u32,
+        expected_branch_size: u32,
+        recurse: F,
+    ) -> Self {
+        RecursiveTracked(Recursive::new(
+            base,
+            depth,
+            desired_size,
+            expected_branch_size,
+            recurse,
+        ))
+    }
+}
+
+impl<
+        T: fmt::Debug + 'static,
+        R: Strategy<Value = T> + 'static,
+        F: Fn(BoxedStrategy<T>) -> R,
+    > Strategy for RecursiveTracked<T, F>
+{
+    type Tree = RecursiveTrackedValueTree<T>;
+    type Value = DepthTracked<T>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.0
+            .new_counting_tree(runner)
+            .map(RecursiveTrackedValueTree)
+    }
+}
+
+/// `ValueTree` corresponding to `RecursiveTracked`.
+pub struct RecursiveTrackedValueTree<T>(CountingValueTree<T>);
+
+impl<T: fmt::Debug> fmt::Debug for RecursiveTrackedValueTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RecursiveTrackedValueTree")
+            .field(&"<tree>")
+            .finish()
+    }
+}
+
+impl<T: fmt::Debug> ValueTree for RecursiveTrackedValueTree<T> {
+    type Value = DepthTracked<T>;
+
+    fn current(&self) -> DepthTracked<T> {
+        self.0.current_tracked()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.0.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.0.complicate()
+    }
+}
+
+/// Counts the nesting of recursive choices while a value is produced.
+///
+/// Producing a value of a recursive choice produces the values of its
+/// children within it, so the deepest nesting seen is the depth of the value.
+#[derive(Default)]
+struct DepthCounter {
+    nesting: Cell<u32>,
+    max: Cell<u32>,
+}
+
+/// Strategy for the recursive choice of one layer, counting one level of
+/// depth for every value it produces.
+///
+/// Every level drawn costs one unit of the case's budget; once that is used
+/// up, values of `base` are produced instead.
+struct Counted<T> {
+    inner: BoxedStrategy<T>,
+    base: BoxedStrategy<T>,
+    counter: Rc<DepthCounter>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Counted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Counted")
+            .field("inner", &self.inner)
+            .field("base", &self.base)
+            .field("counter", &"<counter>")
+            .finish()
+    }
+}
+
+impl<T: fmt::Debug + 'static> Strategy for Counted<T> {
+    type Tree = Box<dyn ValueTree<Value = T>>;
+    type Value = T;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        if 0 == runner.budget().draw(1) {
+            return self.base.new_tree(runner);
+        }
+
+        Ok(Box::new(CountedValueTree {
+            tree: self.inner.new_tree(runner)?,
+            counter: Rc::clone(&self.counter),
+        }))
+    }
+}
+
+struct CountedValueTree<T> {
+    tree: Box<dyn ValueTree<Value = T>>,
+    counter: Rc<DepthCounter>,
+}
+
+impl<T: fmt::Debug> ValueTree for CountedValueTree<T> {
+    type Value = T;
+
+    fn current(&self) -> T {
+        let counter = &self.counter;
+        let nesting = counter.nesting.get() + 1;
+        counter.nesting.set(nesting);
+        counter.max.set(counter.max.get().max(nesting));
+        let current = self.tree.current();
+        counter.nesting.set(nesting - 1);
+        current
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.tree.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.tree.complicate()
+    }
+}
+
+/// The root `ValueTree` of a `Recursive`, which can tell the depth of its
+/// current value.
+struct CountingValueTree<T> {
+    tree: Box<dyn ValueTree<Value = T>>,
+    counter: Rc<DepthCounter>,
+}
+
+impl<T: fmt::Debug> CountingValueTree<T> {
+    fn current_tracked(&self) -> DepthTracked<T> {
+        self.counter.nesting.set(0);
+        self.counter.max.set(0);
+        let value = self.tree.current();
+        DepthTracked {
+            value,
+            depth: self.counter.max.get(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> ValueTree for CountingValueTree<T> {
+    type Value = T;
+
+    fn current(&self) -> T {
+        self.tree.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.tree.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.tree.complicate()
+    }
+}
+
+/// Builds the strategy for `depth` levels of recursion on top of `base`,
+/// where `recurse` produces the recursive choice of each level given the
+/// strategy for the level below it and its index, starting from 0.
+fn build_layers<T: fmt::Debug + 'static>(
+    base: BoxedStrategy<T>,
+    depth: u32,
+    desired_size: u32,
+    expected_branch_size: u32,
+    mut recurse: impl FnMut(BoxedStrategy<T>, u32) -> BoxedStrategy<T>,
+) -> BoxedStrategy<T> {
+    // Since the generator is stateless, we can't implement any "absolutely
+    // X many items" rule. We _can_, however, with extremely high
+    // probability, obtain a value near what we want by using decaying
+    // probabilities of branching as we go down the tree.
+    //
+    // We are given a target size S and a branch size K (branch size =
+    // expected number of items immediately below each branch). We select
+    // some probability P for each level.
+    //
+    // A single level l is thus expected to hold PlK branches. Each of
+    // those will have P(l+1)K child branches of their own, so there are
+    // PlP(l+1)K² second-level branches. The total branches in the tree is
+    // thus (Σ PlK^l) for l from 0 to infinity. Each level is expected to
+    // hold K items, so the total number of items is simply K times the
+    // number of branches, or (K Σ PlK^l). So we want to find a P sequence
+    // such that (lim (K Σ PlK^l) = S), or more simply,
+    // (lim Σ PlK^l = S/K).
+    //
+    // Let Q be a second probability sequence such that Pl = Ql/K^l. This
+    // changes the formulation to (lim Σ Ql = S/K). The series Σ0.5^(l+1)
+    // converges on 1.0, so we can let Ql = S/K * 0.5^(l+1), and so
+    // Pl = S/K^(l+1) * 0.5^(l+1) = S / (2K) ^ (l+1)
+    //
+    // We don't actually have infinite levels here since we _can_ easily
+    // cap to a fixed max depth, so this will be a minor underestimate. We
+    // also clamp all probabilities to 0.9 to ensure that we can't end up
+    // with levels which are always pure branches, which further
+    // underestimates size.
+
+    let mut branch_probabilities = Vec::new();
+    let mut k2 = u64::from(expected_branch_size) * 2;
+    for _ in 0..depth {
+        branch_probabilities.push(f64::from(desired_size) / k2 as f64);
+        k2 = k2.saturating_mul(u64::from(expected_branch_size) * 2);
+    }
+
+    let mut strat = base;
+    let mut layer = 0;
+    while let Some(branch_probability) = branch_probabilities.pop() {
+        let recursive_choice = recurse(strat.clone(), layer);
+        let non_recursive_choice = strat;
+        // Clamp the maximum branch probability to 0.9 to ensure we can
+        // generate non-recursive cases reasonably often.
+        let branch_probability = branch_probability.min(0.9);
+        let (weight_branch, weight_leaf) = float_to_weight(branch_probability);
+        let branch = prop_oneof![
+            weight_leaf => non_recursive_choice,
+            weight_branch => recursive_choice,
+        ];
+        strat = branch.boxed();
+        layer += 1;
+    }
+
+    strat
+}
+
+/// Return type from `Strategy::prop_recursive_shared()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct RecursiveShared<T, F> {
+    base: BoxedStrategy<T>,
+    recurse: Arc<F>,
+    depth: u32,
+    desired_size: u32,
+    expected_branch_size: u32,
+    sharing: Probability,
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for RecursiveShared<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RecursiveShared")
+            .field("base", &self.base)
+            .field("recurse", &"<function>")
+            .field("depth", &self.depth)
+            .field("desired_size", &self.desired_size)
+            .field("expected_branch_size", &self.expected_branch_size)
+            .field("sharing", &self.sharing)
+            .finish()
+    }
+}
+
+impl<T, F> Clone for RecursiveShared<T, F> {
+    fn clone(&self) -> Self {
+        RecursiveShared {
+            base: self.base.clone(),
+            recurse: Arc::clone(&self.recurse),
+            depth: self.depth,
+            desired_size: self.desired_size,
+            expected_branch_size: self.expected_branch_size,
+            sharing: self.sharing,
+        }
+    }
+}
+
+impl<
+        T: fmt::Debug + 'static,
+        R: Strategy<Value = T> + 'static,
+        F: Fn(BoxedStrategy<Arc<T>>) -> R,
+    > RecursiveShared<T, F>
+{
+    pub(super) fn new(
+        base: impl Strategy<Value = T> + 'static,
+        depth: u32,
+        desired_size: u32,
+        expected_branch_size: u32,
+        sharing: Probability,
+        recurse: F,
+    ) -> Self {
+        Self {
+            base: base.boxed(),
+            recurse: Arc::new(recurse),
+            depth,
+            desired_size,
+            expected_branch_size,
+            sharing,
+        }
+    }
+}
+
+impl<
+        T: fmt::Debug + 'static,
+        R: Strategy<Value = T> + 'static,
+        F: Fn(BoxedStrategy<Arc<T>>) -> R,
+    > Strategy for RecursiveShared<T, F>
+{
+    type Tree = Box<dyn ValueTree<Value = Arc<T>>>;
+    type Value = Arc<T>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        // Each generated value gets its own pool, so nodes are only ever
+        // shared within one DAG.
+        let pool = Rc::new(NodePool {
+            nodes: RefCell::new(Vec::new()),
+            epoch: Cell::new(0),
+        });
+        let sharing = f64::from(self.sharing);
+        let recurse = &self.recurse;
+        let strat = build_layers(
+            self.base.clone(),
+            self.depth,
+            self.desired_size,
+            self.expected_branch_size,
+            |strat, layer| {
+                recurse(
+                    Pooled {
+                        inner: strat,
+                        pool: Rc::clone(&pool),
+                        layer,
+                        sharing,
+                    }
+                    .boxed(),
+                )
+                .boxed()
+            },
+        );
+
+        // The root can't be shared with anything since it is generated
+        // before any other node.
+        let root = Pooled {
+            inner: strat,
+            pool,
+            layer: self.depth,
+            sharing: 0.0,
+        };
+        root.new_tree(runner)
+            .map(|tree| Box::new(tree) as Box<dyn ValueTree<Value = Arc<T>>>)
+    }
+}
+
+/// A node of a DAG generated by `RecursiveShared`, along with the value it
+/// produced for the current epoch of its pool, if any.
+struct Node<T> {
+    tree: Box<dyn ValueTree<Value = T>>,
+    current: Option<(u64, Arc<T>)>,
+}
+
+/// The nodes of one DAG generated by `RecursiveShared`.
+struct NodePool<T> {
+    /// Every complete node so far, with the layer it was generated at. A node
+    /// is only added once all its children are, so a node can't end up as its
+    /// own descendant.
+    nodes: RefCell<Vec<(u32, Rc<RefCell<Node<T>>>)>>,
+    /// Bumped whenever any part of the DAG shrinks. Within an epoch, every
+    /// reference to a node produces the same `Arc`.
+    epoch: Cell<u64>,
+}
+
+/// Strategy producing nodes for values of `inner`, or with probability
+/// `sharing`, reusing an existing node from `pool` no higher than `layer`.
+struct Pooled<T> {
+    inner: BoxedStrategy<T>,
+    pool: Rc<NodePool<T>>,
+    layer: u32,
+    sharing: f64,
+}
+
+impl<T: fmt::Debug> fmt::Debug for Pooled<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Pooled")
+            .field("inner", &self.inner)
+            .field("pool", &"<pool>")
+            .field("layer", &self.layer)
+            .field("sharing", &self.sharing)
+            .finish()
+    }
+}
+
+impl<T: fmt::Debug + 'static> Strategy for Pooled<T> {
+    type Tree = NodeValueTree<T>;
+    type Value = Arc<T>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let candidates = self
+            .pool
+            .nodes
+            .borrow()
+            .iter()
+            .filter(|&&(layer, _)| layer <= self.layer)
+            .count();
+        if candidates > 0 && runner.rng().gen_bool(self.sharing) {
+            let ix = runner.rng().gen_range(0..candidates);
+            let node = self
+                .pool
+                .nodes
+                .borrow()
+                .iter()
+                .filter(|&&(layer, _)| layer <= self.layer)
+                .nth(ix)
+                .map(|(_, node)| Rc::clone(node))
+                .expect("index is within candidates");
+            return Ok(NodeValueTree {
+                node,
+                pool: Rc::clone(&self.pool),
+                owner: false,
+            });
+        }
+
+        let tree = self.inner.new_tree(runner)?;
+        let node = Rc::new(RefCell::new(Node {
+            tree,
+            current: None,
+        }));
+        self.pool
+            .nodes
+            .borrow_mut()
+            .push((self.layer, Rc::clone(&node)));
+        Ok(NodeValueTree {
+            node,
+            pool: Rc::clone(&self.pool),
+            owner: true,
+        })
+    }
+}
+
+/// `ValueTree` for one reference to a node of a DAG generated by
+/// `RecursiveShared`.
+///
+/// Only the reference which generated the node (its owner) shrinks it; every
+/// other reference to it follows along.
+struct NodeValueTree<T> {
+    node: Rc<RefCell<Node<T>>>,
+    pool: Rc<NodePool<T>>,
+    owner: bool,
+}
+
+impl<T: fmt::Debug> NodeValueTree<T> {
+    fn shrink(
+        &mut self,
+        f: impl FnOnce(&mut dyn ValueTree<Value = T>) -> bool,
+    ) -> bool {
+        if !self.owner {
+            return false;
+        }
+        self.pool.epoch.set(self.pool.epoch.get() + 1);
+        f(&mut *self.node.borrow_mut().tree)
+    }
+}
+
+impl<T: fmt::Debug> ValueTree for NodeValueTree<T> {
+    type Value = Arc<T>;
+
+    fn current(&self) -> Arc<T> {
+        let epoch = self.pool.epoch.get();
+        let mut node = self.node.borrow_mut();
+        if let Some((at, ref current)) = node.current {
+            if at == epoch {
+                return Arc::clone(current);
+            }
+        }
+
+        let current = Arc::new(node.tree.current());
+        node.current = Some((epoch, Arc::clone(&current)));
+        current
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.shrink(|tree| tree.simplify())
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.shrink(|tree| tree.complicate())
     }
 }
 
@@ -206,4 +729,174 @@ mod test {
             assert_eq!(Tree::Leaf, value.current());
         }
     }
+
+    fn tree_strategy() -> RecursiveTracked<
+        Tree,
+        impl Fn(BoxedStrategy<Tree>) -> BoxedStrategy<Tree>,
+    > {
+        Just(Tree::Leaf).prop_recursive_tracked(4, 64, 4, |element| {
+            crate::collection::vec(element, 1..4)
+                .prop_map(Tree::Branch)
+                .boxed()
+        })
+    }
+
+    #[test]
+    fn tracked_depth_is_actual_depth() {
+        let strat = tree_strategy();
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let mut tree = strat.new_tree(&mut runner).unwrap();
+            loop {
+                let tracked = tree.current();
+                assert_eq!(tracked.value.stats().0, tracked.depth);
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!(0, tree.current().depth);
+        }
+    }
+
+    #[test]
+    fn min_recursive_depth_makes_values_deep() {
+        fn count_deep(min_recursive_depth: u32) -> u32 {
+            let config = Config {
+                min_recursive_depth,
+                ..Config::default()
+            };
+            let mut runner = TestRunner::new_with_rng(
+                config,
+                TestRng::deterministic_rng(RngAlgorithm::default()),
+            );
+            let strat = tree_strategy();
+            (0..1024)
+                .filter(|_| {
+                    strat.new_tree(&mut runner).unwrap().current().depth >= 4
+                })
+                .count() as u32
+        }
+
+        let shallow = count_deep(0);
+        let deep = count_deep(4);
+        assert!(deep >= 200, "Only got {} deep values", deep);
+        assert!(deep > 10 * shallow, "Got {} vs {}", deep, shallow);
+    }
+
+    #[test]
+    fn exhausted_budget_yields_leaves() {
+        let mut runner = TestRunner::new(Config {
+            budget: Some(0),
+            ..Config::default()
+        });
+        let strat = tree_strategy();
+        for _ in 0..256 {
+            runner.reset_budget();
+            let value = strat.new_tree(&mut runner).unwrap().current();
+            assert_eq!(0, value.depth);
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Dag {
+        Leaf(u8),
+        Branch(Vec<Arc<Dag>>),
+    }
+
+    impl Dag {
+        fn depth(&self) -> u32 {
+            match *self {
+                Dag::Leaf(_) => 0,
+                Dag::Branch(ref children) => {
+                    children.iter().map(|c| c.depth() + 1).max().unwrap_or(1)
+                }
+            }
+        }
+
+        /// Collects every `Arc` reachable from `node`, including repeats.
+        fn collect(node: &Arc<Dag>, into: &mut Vec<Arc<Dag>>) {
+            into.push(Arc::clone(node));
+            if let Dag::Branch(ref children) = **node {
+                for child in children {
+                    Dag::collect(child, into);
+                }
+            }
+        }
+
+        fn has_sharing(node: &Arc<Dag>) -> bool {
+            let mut all = Vec::new();
+            Dag::collect(node, &mut all);
+            all.iter()
+                .enumerate()
+                .any(|(ix, a)| all[ix + 1..].iter().any(|b| Arc::ptr_eq(a, b)))
+        }
+    }
+
+    fn dag_strategy(
+        sharing: f64,
+    ) -> RecursiveShared<
+        Dag,
+        impl Fn(BoxedStrategy<Arc<Dag>>) -> BoxedStrategy<Dag>,
+    > {
+        (0..10u8).prop_map(Dag::Leaf).prop_recursive_shared(
+            4,
+            32,
+            4,
+            sharing,
+            |node| {
+                crate::collection::vec(node, 1..4)
+                    .prop_map(Dag::Branch)
+                    .boxed()
+            },
+        )
+    }
+
+    #[test]
+    fn shared_recursive_generates_dags_within_depth() {
+        let strat = dag_strategy(0.5);
+        let mut runner = TestRunner::deterministic();
+        let mut shared = 0;
+        for _ in 0..1024 {
+            let dag = strat.new_tree(&mut runner).unwrap().current();
+            assert!(dag.depth() <= 4, "Got depth {}", dag.depth());
+            if Dag::has_sharing(&dag) {
+                shared += 1;
+            }
+        }
+
+        assert!(shared > 100, "Only got {} DAGs with sharing", shared);
+    }
+
+    #[test]
+    fn shared_recursive_without_sharing_generates_trees() {
+        let strat = dag_strategy(0.0);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..1024 {
+            let dag = strat.new_tree(&mut runner).unwrap().current();
+            assert!(!Dag::has_sharing(&dag));
+        }
+    }
+
+    #[test]
+    fn shared_recursive_keeps_sharing_while_shrinking() {
+        let strat = dag_strategy(0.9);
+        let mut runner = TestRunner::deterministic();
+        let mut tested = 0;
+        while tested < 64 {
+            let mut tree = strat.new_tree(&mut runner).unwrap();
+            if !Dag::has_sharing(&tree.current()) {
+                continue;
+            }
+            tested += 1;
+
+            // Shrink as long as the sharing is preserved.
+            while tree.simplify() {
+                let dag = tree.current();
+                if !Dag::has_sharing(&dag) && !tree.complicate() {
+                    break;
+                }
+            }
+            assert!(Dag::has_sharing(&tree.current()));
+        }
+    }
 }
@@ -0,0 +1,121 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` and `ValueTree` adaptor which stops simplifying values after a
+/// fixed number of steps.
+///
+/// See `Strategy::prop_shrink_budget()`.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct ShrinkBudgeted<S> {
+    source: S,
+    max_steps: u32,
+    steps: u32,
+}
+
+impl<S> ShrinkBudgeted<S> {
+    pub(super) fn new(source: S, max_steps: u32) -> Self {
+        ShrinkBudgeted {
+            source,
+            max_steps,
+            steps: 0,
+        }
+    }
+}
+
+impl<S: Strategy> Strategy for ShrinkBudgeted<S> {
+    type Tree = ShrinkBudgeted<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        self.source
+            .new_tree(runner)
+            .map(|source| ShrinkBudgeted::new(source, self.max_steps))
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.source.value_size_hint()
+    }
+}
+
+impl<S: ValueTree> ValueTree for ShrinkBudgeted<S> {
+    type Value = S::Value;
+
+    fn current(&self) -> S::Value {
+        self.source.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.steps >= self.max_steps {
+            return false;
+        }
+        self.steps += 1;
+        self.source.simplify()
+    }
+
+    // Never limited: it is how the source gets back to the last failing
+    // value after a simplification which made the test pass.
+    fn complicate(&mut self) -> bool {
+        self.source.complicate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::collection::vec;
+    use crate::std_facade::Vec;
+    use crate::strategy::check_strategy_sanity;
+
+    #[test]
+    fn limits_only_the_budgeted_part() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let input =
+            (vec(1u32..1000, 50..100).prop_shrink_budget(3), 1u32..1000);
+        let result = runner.run(&input, |(v, x)| {
+            prop_assert!(v.is_empty() || x == 0);
+            Ok(())
+        });
+
+        let (v, x) = match result {
+            Err(TestError::Fail(_, minimal)) => minimal,
+            result => panic!("Unexpected result: {:?}", result),
+        };
+        // Three steps can remove at most three elements, while the other
+        // part of the input still shrinks all the way.
+        assert!(v.len() >= 47);
+        assert_eq!(1, x);
+    }
+
+    #[test]
+    fn every_tree_gets_the_full_budget() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = (0u32..1000).prop_shrink_budget(2);
+        for _ in 0..8 {
+            let mut tree = strategy.new_tree(&mut runner).unwrap();
+            let mut values = Vec::new();
+            while tree.simplify() {
+                values.push(tree.current());
+            }
+            assert!(values.len() <= 2);
+            assert!(!tree.simplify());
+        }
+    }
+
+    #[test]
+    fn shrink_budgeted_sanity() {
+        check_strategy_sanity(vec(0u8..10, 0..8).prop_shrink_budget(4), None);
+    }
+}
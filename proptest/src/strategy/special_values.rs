@@ -0,0 +1,229 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{Arc, Vec};
+
+use rand::Rng;
+
+use crate::num;
+use crate::option::Probability;
+use crate::strategy::{NewTree, Strategy, ValueTree};
+use crate::test_runner::TestRunner;
+
+/// A list of domain-specific "magic" values, such as the empty string, the
+/// Unix epoch or `0x7fffffff`, along with the probability of injecting one of
+/// them in place of a generated value.
+///
+/// This is typically used through `Strategy::with_special_values()`, but can
+/// also be built once and shared between several strategies with
+/// `WithSpecialValues::new()`.
+#[derive(Debug)]
+pub struct SpecialValues<T> {
+    values: Arc<[T]>,
+    probability: Probability,
+}
+
+impl<T> Clone for SpecialValues<T> {
+    fn clone(&self) -> Self {
+        SpecialValues {
+            values: Arc::clone(&self.values),
+            probability: self.probability,
+        }
+    }
+}
+
+impl<T> SpecialValues<T> {
+    /// Creates the special values `values`, one of which is injected with the
+    /// given `probability`.
+    ///
+    /// Values earlier in the list are considered simpler.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `values` is empty, or if `probability` is outside
+    /// `[0.0, 1.0]`.
+    pub fn new(
+        values: impl Into<Vec<T>>,
+        probability: impl Into<Probability>,
+    ) -> Self {
+        let values: Vec<T> = values.into();
+        assert!(!values.is_empty(), "no special values given");
+        SpecialValues {
+            values: values.into(),
+            probability: probability.into(),
+        }
+    }
+
+    /// Returns the special values.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the probability of injecting one of the special values.
+    pub fn probability(&self) -> Probability {
+        self.probability
+    }
+}
+
+/// `Strategy` which injects one of some `SpecialValues` in place of the
+/// values of a source strategy from time to time.
+///
+/// Whether a special value is injected, and which one, is drawn from the
+/// test's random number generator, so persisted failures reproduce it like
+/// any other generated value.
+///
+/// Injected values shrink towards the earlier special values, and never to
+/// values of the source strategy. Values of the source strategy shrink as
+/// usual.
+///
+/// This is created by `Strategy::with_special_values()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct WithSpecialValues<S: Strategy> {
+    source: S,
+    special: SpecialValues<S::Value>,
+}
+
+impl<S: Strategy> WithSpecialValues<S> {
+    /// Wraps `source` to inject one of `special` from time to time.
+    pub fn new(source: S, special: SpecialValues<S::Value>) -> Self {
+        WithSpecialValues { source, special }
+    }
+}
+
+impl<S: Strategy> Strategy for WithSpecialValues<S>
+where
+    S::Value: Clone,
+{
+    type Tree = WithSpecialValuesValueTree<S::Tree>;
+    type Value = S::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let probability: f64 = self.special.probability.into();
+        if runner.rng().gen_bool(probability) {
+            let values = &self.special.values;
+            let index = runner.rng().gen_range(0..values.len());
+            Ok(WithSpecialValuesValueTree::Special(
+                num::usize::BinarySearch::new(index),
+                Arc::clone(values),
+            ))
+        } else {
+            self.source
+                .new_tree(runner)
+                .map(WithSpecialValuesValueTree::Source)
+        }
+    }
+}
+
+/// `ValueTree` corresponding to `WithSpecialValues`.
+#[derive(Clone, Debug)]
+pub enum WithSpecialValuesValueTree<T: ValueTree> {
+    /// A value of the source strategy.
+    Source(T),
+    /// One of the special values, selected by index.
+    Special(num::usize::BinarySearch, Arc<[T::Value]>),
+}
+
+impl<T: ValueTree> ValueTree for WithSpecialValuesValueTree<T>
+where
+    T::Value: Clone,
+{
+    type Value = T::Value;
+
+    fn current(&self) -> T::Value {
+        match *self {
+            WithSpecialValuesValueTree::Source(ref tree) => tree.current(),
+            WithSpecialValuesValueTree::Special(ref index, ref values) => {
+                values[index.current()].clone()
+            }
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        match *self {
+            WithSpecialValuesValueTree::Source(ref mut tree) => tree.simplify(),
+            WithSpecialValuesValueTree::Special(ref mut index, _) => {
+                index.simplify()
+            }
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        match *self {
+            WithSpecialValuesValueTree::Source(ref mut tree) => {
+                tree.complicate()
+            }
+            WithSpecialValuesValueTree::Special(ref mut index, _) => {
+                index.complicate()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::std_facade::vec;
+
+    use super::*;
+    use crate::test_runner::{Config, TestError};
+
+    #[test]
+    fn injects_special_values_at_the_given_rate() {
+        let strategy = (1000..2000u32).with_special_values(vec![0, 7], 0.25);
+        let mut runner = TestRunner::deterministic();
+        let mut special = 0;
+        for _ in 0..4096 {
+            let value = strategy.new_tree(&mut runner).unwrap().current();
+            if value < 1000 {
+                assert!(value == 0 || value == 7, "unexpected {}", value);
+                special += 1;
+            }
+        }
+        assert!(
+            special > 800 && special < 1250,
+            "{} special values",
+            special
+        );
+    }
+
+    #[test]
+    fn special_values_shrink_towards_earlier_ones() {
+        let strategy =
+            (1000..2000u32).with_special_values(vec![1, 2, 3, 0x7fff], 1.0);
+        let mut runner = TestRunner::new(Config::default());
+        let result = runner.run(&strategy, |v| {
+            prop_assert!(v == 1 || v == 2);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, v)) => assert_eq!(3, v),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn source_values_shrink_as_usual() {
+        let strategy = (0..1000u32).with_special_values(vec![u32::MAX], 0.0);
+        let mut runner = TestRunner::new(Config::default());
+        let result = runner.run(&strategy, |v| {
+            prop_assert!(v < 500);
+            Ok(())
+        });
+        match result {
+            Err(TestError::Fail(_, v)) => assert_eq!(500, v),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "no special values given")]
+    fn rejects_empty_special_values() {
+        SpecialValues::<u32>::new(vec![], 0.5);
+    }
+}
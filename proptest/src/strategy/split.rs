@@ -0,0 +1,281 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc, Rc};
+use core::cell::RefCell;
+
+use crate::strategy::traits::*;
+use crate::test_runner::*;
+
+/// `Strategy` returned by `Strategy::prop_split()`.
+#[must_use = "strategies do nothing unless used"]
+pub struct Staged<S, F> {
+    pub(super) source: S,
+    pub(super) fun: Arc<F>,
+}
+
+impl<S: fmt::Debug, F> fmt::Debug for Staged<S, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Staged")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .finish()
+    }
+}
+
+impl<S: Clone, F> Clone for Staged<S, F> {
+    fn clone(&self) -> Self {
+        Staged {
+            source: self.source.clone(),
+            fun: Arc::clone(&self.fun),
+        }
+    }
+}
+
+impl<S: Strategy, T: Strategy, F: Fn(&S::Value) -> T> Strategy
+    for Staged<S, F>
+{
+    type Tree = StagedValueTree<S::Tree, T, F>;
+    type Value = (S::Value, Stage<T>);
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        Ok(StagedValueTree {
+            source: self.source.new_tree(runner)?,
+            fun: Arc::clone(&self.fun),
+            // The second stage draws from its own fork of the case's RNG, so
+            // replaying the case replays both stages.
+            runner: runner.partial_clone(),
+            slot: Rc::new(RefCell::new(None)),
+            source_exhausted: false,
+            last_shrink: None,
+        })
+    }
+}
+
+/// The second stage of a value produced by `Strategy::prop_split()`, which is
+/// only generated once the test asks for it.
+///
+/// The `Debug` form shows the value generated for this stage, if any, so that
+/// it appears in failure reports.
+pub struct Stage<T: Strategy> {
+    strategy: T,
+    runner: TestRunner,
+    slot: Rc<RefCell<Option<T::Tree>>>,
+}
+
+impl<T: Strategy> Stage<T> {
+    /// Returns the value of this stage, generating it on first use.
+    ///
+    /// Every call within one test case returns the same value. Generation only
+    /// fails if the stage's strategy rejects too many values, in which case
+    /// the whole test case is rejected.
+    pub fn generate(&self) -> Result<T::Value, TestCaseError> {
+        let mut slot = self.slot.borrow_mut();
+        if slot.is_none() {
+            let tree = self
+                .strategy
+                .new_tree(&mut self.runner.clone())
+                .map_err(TestCaseError::Reject)?;
+            *slot = Some(tree);
+        }
+
+        Ok(slot.as_ref().map(ValueTree::current).unwrap())
+    }
+}
+
+impl<T: Strategy> fmt::Debug for Stage<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.slot.borrow() {
+            Some(ref tree) => {
+                f.debug_tuple("Stage").field(&tree.current()).finish()
+            }
+            None => f.write_str("Stage(<not generated>)"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum StageShrink {
+    Source,
+    Stage,
+}
+
+/// `ValueTree` corresponding to `Staged`.
+///
+/// This first shrinks the first stage, regenerating the second stage from the
+/// same RNG each time the first stage changes, and then shrinks the second
+/// stage as it was last generated by the test.
+pub struct StagedValueTree<S, T: Strategy, F> {
+    source: S,
+    fun: Arc<F>,
+    runner: TestRunner,
+    slot: Rc<RefCell<Option<T::Tree>>>,
+    source_exhausted: bool,
+    last_shrink: Option<StageShrink>,
+}
+
+impl<S: fmt::Debug, T: Strategy, F> fmt::Debug for StagedValueTree<S, T, F>
+where
+    T::Tree: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StagedValueTree")
+            .field("source", &self.source)
+            .field("fun", &"<function>")
+            .field("runner", &self.runner)
+            .field("slot", &self.slot)
+            .field("source_exhausted", &self.source_exhausted)
+            .field("last_shrink", &self.last_shrink)
+            .finish()
+    }
+}
+
+impl<S, T, F> ValueTree for StagedValueTree<S, T, F>
+where
+    S: ValueTree,
+    T: Strategy,
+    F: Fn(&S::Value) -> T,
+{
+    type Value = (S::Value, Stage<T>);
+
+    fn current(&self) -> Self::Value {
+        let value = self.source.current();
+        let stage = Stage {
+            strategy: (self.fun)(&value),
+            runner: self.runner.clone(),
+            slot: Rc::clone(&self.slot),
+        };
+        (value, stage)
+    }
+
+    fn simplify(&mut self) -> bool {
+        if !self.source_exhausted {
+            if self.source.simplify() {
+                *self.slot.borrow_mut() = None;
+                self.last_shrink = Some(StageShrink::Source);
+                return true;
+            }
+            self.source_exhausted = true;
+        }
+
+        let simplified = self
+            .slot
+            .borrow_mut()
+            .as_mut()
+            .map_or(false, ValueTree::simplify);
+        self.last_shrink = if simplified {
+            Some(StageShrink::Stage)
+        } else {
+            None
+        };
+        simplified
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.last_shrink {
+            None => false,
+            Some(StageShrink::Source) => {
+                // The second stage is regenerated from the restored value,
+                // even if the source can't complicate any further.
+                *self.slot.borrow_mut() = None;
+                if self.source.complicate() {
+                    true
+                } else {
+                    self.last_shrink = None;
+                    false
+                }
+            }
+            Some(StageShrink::Stage) => {
+                let complicated = self
+                    .slot
+                    .borrow_mut()
+                    .as_mut()
+                    .map_or(false, ValueTree::complicate);
+                if !complicated {
+                    self.last_shrink = None;
+                }
+                complicated
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::collection;
+    use crate::strategy::Just;
+
+    #[test]
+    fn second_stage_depends_on_first_and_shrinks() {
+        let input = (1usize..100)
+            .prop_split(|&len| collection::vec(0u32..1000, len..=len));
+        let generated = Cell::new(0);
+
+        let mut runner = TestRunner::deterministic();
+        let result = runner.run(&input, |(len, stage)| {
+            if len < 10 {
+                return Ok(());
+            }
+
+            let workload = stage.generate()?;
+            generated.set(generated.get() + 1);
+            prop_assert_eq!(len, workload.len());
+            prop_assert_eq!(&workload, &stage.generate()?);
+            prop_assert!(workload.iter().all(|&v| v < 500));
+            Ok(())
+        });
+
+        match result {
+            Err(TestError::Fail(_, (len, stage))) => {
+                // The first stage shrinks to the smallest failing length, and
+                // then the second stage to the smallest failing values.
+                assert_eq!(10, len);
+                let workload = stage.generate().unwrap();
+                assert_eq!(1, workload.iter().filter(|&&v| 500 == v).count());
+                assert!(workload.iter().all(|&v| 0 == v || 500 == v));
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert!(generated.get() > 0);
+    }
+
+    #[test]
+    fn second_stage_is_reproducible() {
+        let input = (0u32..10).prop_split(|&v| v..v + 1000);
+        let seed = [7u8; 32];
+
+        let generate = || {
+            let rng = TestRng::from_seed(RngAlgorithm::ChaCha, &seed);
+            let mut runner = TestRunner::new_with_rng(Config::default(), rng);
+            let (first, stage) = input.new_tree(&mut runner).unwrap().current();
+            (first, stage.generate().unwrap())
+        };
+
+        let (first, second) = generate();
+        assert!(second >= first && second < first + 1000);
+        assert_eq!((first, second), generate());
+    }
+
+    #[test]
+    fn stage_debug_shows_generated_value() {
+        let mut runner = TestRunner::deterministic();
+        let (_, stage) = (0u32..10)
+            .prop_split(|_| Just(42u8))
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+
+        assert_eq!("Stage(<not generated>)", format!("{:?}", stage));
+        stage.generate().unwrap();
+        assert_eq!("Stage(42)", format!("{:?}", stage));
+    }
+}
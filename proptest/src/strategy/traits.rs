@@ -7,9 +7,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{fmt, Arc, Box, Rc};
+#[cfg(feature = "unicode-normalization")]
+use crate::std_facade::String;
+use crate::std_facade::{fmt, Arc, Box, Rc, Vec};
 use core::cmp;
 
+use crate::option::Probability;
 use crate::strategy::*;
 use crate::test_runner::*;
 
@@ -59,6 +62,23 @@ pub trait Strategy: fmt::Debug {
     /// generate the test case.
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self>;
 
+    /// Returns bounds on the size of the values generated by this strategy,
+    /// in the same form as `Iterator::size_hint()`.
+    ///
+    /// The size is the number of elements of collections and the number of
+    /// bytes of strings. Strategies which generate other values, or which
+    /// cannot tell, return `(0, None)`, which is the default. This is only
+    /// used to pre-allocate buffers of the right capacity, so nothing breaks
+    /// if a value lies outside the bounds, but it does cost reallocations.
+    ///
+    /// Adaptors which pass values through unchanged, such as
+    /// `prop_filter()`, `no_shrink()` and boxing, forward the hint of their
+    /// source. `prop_map()` cannot know what the function does to the size
+    /// and returns the default.
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+
     /// Returns a strategy which produces values transformed by the function
     /// `fun`.
     ///
@@ -101,6 +121,40 @@ pub trait Strategy: fmt::Debug {
         MapInto::new(self)
     }
 
+    /// Returns a strategy which produces values transformed by the function
+    /// `fun`, where only values accepted by `validator` are ever produced.
+    ///
+    /// This is useful when `fun` can only uphold the invariants of its output
+    /// type for part of the input space, and shrinking the input could
+    /// otherwise walk out of that part. During shrinking, any state whose
+    /// mapped value is rejected by `validator` is skipped, exactly as if the
+    /// test had passed on it, so the test never observes it and the shrink
+    /// path stays inside the valid domain.
+    ///
+    /// If a freshly generated value is rejected, it is regenerated, counting
+    /// as a local rejection as with `prop_filter()`. As with filtering, this
+    /// should therefore only be used if relatively few values are invalid.
+    ///
+    /// Both `fun` and `validator` should be deterministic.
+    fn prop_map_validated<
+        O: fmt::Debug,
+        F: Fn(Self::Value) -> O,
+        V: Fn(&O) -> bool,
+    >(
+        self,
+        fun: F,
+        validator: V,
+    ) -> MapValidated<Self, F, V>
+    where
+        Self: Sized,
+    {
+        MapValidated {
+            source: self,
+            fun: Arc::new(fun),
+            validator: Arc::new(validator),
+        }
+    }
+
     /// Returns a strategy which produces values transformed by the function
     /// `fun`, which is additionally given a random number generator.
     ///
@@ -248,6 +302,45 @@ pub trait Strategy: fmt::Debug {
         })
     }
 
+    /// Like `prop_flat_map()`, but `fun` may also return anything
+    /// implementing `IntoStrategy`, such as a literal value or an array of
+    /// values to select from.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///     # /*
+    ///     #[test]
+    ///     # */
+    ///     fn picks_from_row(
+    ///         x in (0..3usize).prop_flat_map_into(|row| {
+    ///             [[1u8, 2, 3], [4, 5, 6], [7, 8, 9]][row]
+    ///         })
+    ///     ) {
+    ///         prop_assert!((1..=9).contains(&x));
+    ///     }
+    /// }
+    /// # fn main() { picks_from_row(); }
+    /// ```
+    fn prop_flat_map_into<I, F>(
+        self,
+        fun: F,
+    ) -> Flatten<Map<Map<Self, F>, fn(I) -> I::Strategy>>
+    where
+        Self: Sized,
+        I: IntoStrategy + fmt::Debug,
+        F: Fn(Self::Value) -> I,
+    {
+        Flatten::new(Map {
+            source: Map {
+                source: self,
+                fun: Arc::new(fun),
+            },
+            fun: Arc::new(I::into_strategy as fn(I) -> I::Strategy),
+        })
+    }
+
     /// Maps values produced by this strategy into new strategies and picks
     /// values from those strategies while considering the new strategies to be
     /// independent.
@@ -292,6 +385,96 @@ pub trait Strategy: fmt::Debug {
         }
     }
 
+    /// Like `prop_flat_map()`, but shrinks the input that produces the derived
+    /// strategies before the derived values themselves.
+    ///
+    /// With `prop_flat_map()`, the derived value shrinks first, and shrinking
+    /// the input afterwards picks a new, unrelated derived value which then
+    /// has to be shrunk again. This combinator instead shrinks the input all
+    /// the way first, and derives every value from the same randomness, so
+    /// that e.g. a shorter length produces a prefix of the same elements.
+    /// Only once the input cannot be shrunk any further is the derived value
+    /// shrunk. This makes sizes chosen by the input shrink effectively.
+    ///
+    /// ```
+    /// use proptest::collection::vec;
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_prefix(
+    ///     v in (0..100usize).prop_dependent_map(|len| vec(any::<u8>(), len))
+    ///   ) {
+    ///     prop_assert!(v.len() < 100);
+    ///   }
+    /// }
+    /// #
+    /// # fn main() { test_prefix(); }
+    /// ```
+    fn prop_dependent_map<S: Strategy, F: Fn(Self::Value) -> S>(
+        self,
+        fun: F,
+    ) -> DependentFlatten<Map<Self, F>>
+    where
+        Self: Sized,
+    {
+        DependentFlatten::new(Map {
+            source: self,
+            fun: Arc::new(fun),
+        })
+    }
+
+    /// Splits generation into two stages: produces 2-tuples of a value from
+    /// `self` in slot 0 and, in slot 1, a `Stage` handle for the strategy
+    /// `fun` derives from it.
+    ///
+    /// Unlike `prop_flat_map()`, the second stage is only generated when the
+    /// test body calls `Stage::generate()`, so a test can do some work with
+    /// the first value (and possibly return early) before deciding it needs
+    /// the second. Both stages still belong to the same test case: the second
+    /// stage draws from an RNG forked off the case's RNG, so persisted
+    /// failures replay both, and once the first stage has been shrunk as far
+    /// as possible the second stage is shrunk too.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_workload(
+    ///     (threads, stage) in (1usize..8).prop_split(
+    ///       |&threads| prop::collection::vec(any::<u8>(), threads..=threads))
+    ///   ) {
+    ///     if threads == 1 {
+    ///       // Nothing to test; the workload is never generated.
+    ///       return Ok(());
+    ///     }
+    ///
+    ///     let workload = stage.generate()?;
+    ///     prop_assert_eq!(threads, workload.len());
+    ///   }
+    /// }
+    /// # fn main() { test_workload(); }
+    /// ```
+    fn prop_split<S: Strategy, F: Fn(&Self::Value) -> S>(
+        self,
+        fun: F,
+    ) -> Staged<Self, F>
+    where
+        Self: Sized,
+    {
+        Staged {
+            source: self,
+            fun: Arc::new(fun),
+        }
+    }
+
     /// Returns a strategy which only produces values accepted by `fun`.
     ///
     /// This results in a very naïve form of rejection sampling and should only
@@ -323,6 +506,42 @@ pub trait Strategy: fmt::Debug {
         Filter::new(self, whence.into(), fun)
     }
 
+    /// Like `prop_filter()`, but the filter named `name` may reject at most
+    /// `max_rejects` values over the whole test run, on top of
+    /// `Config::max_local_rejects`.
+    ///
+    /// When nested filters reject too many values, the test is aborted with a
+    /// message breaking down the rejections by each named filter, pointing at
+    /// the guilty one. The counts are also available from
+    /// `TestRunner::filter_rejects()`.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::TestRunner;
+    ///
+    /// let small_even = (0..100u32)
+    ///     .prop_filter_with_budget("even", 1000, |v| v % 2 == 0)
+    ///     .prop_filter_with_budget("small", 1000, |v| *v < 10);
+    ///
+    /// let mut runner = TestRunner::deterministic();
+    /// for _ in 0..16 {
+    ///     small_even.new_tree(&mut runner).unwrap();
+    /// }
+    /// assert!(runner.filter_rejects()["even"] > 0);
+    /// assert!(runner.filter_rejects()["small"] > 0);
+    /// ```
+    fn prop_filter_with_budget<F: Fn(&Self::Value) -> bool>(
+        self,
+        name: &'static str,
+        max_rejects: u32,
+        fun: F,
+    ) -> Filter<Self, F>
+    where
+        Self: Sized,
+    {
+        Filter::with_budget(self, name, max_rejects, fun)
+    }
+
     /// Returns a strategy which only produces transformed values where `fun`
     /// returns `Some(value)` and rejects those where `fun` returns `None`.
     ///
@@ -357,6 +576,21 @@ pub trait Strategy: fmt::Debug {
         FilterMap::new(self, whence.into(), fun)
     }
 
+    /// Returns a strategy which regenerates values whose `Debug` output is
+    /// longer than `max_len` bytes.
+    ///
+    /// This keeps failure reports and verbose traces readable for strategies
+    /// which occasionally produce huge values. Oversized values are
+    /// regenerated a few times before the strategy rejects locally, as
+    /// `prop_filter()` does, so `max_len` should leave room for most values.
+    /// Shrinking never produces oversized values either.
+    fn prop_max_debug_len(self, max_len: usize) -> MaxDebugLen<Self>
+    where
+        Self: Sized,
+    {
+        MaxDebugLen::new(self, max_len)
+    }
+
     /// Returns a strategy which picks uniformly from `self` and `other`.
     ///
     /// When shrinking, if a value from `other` was originally chosen but that
@@ -401,6 +635,10 @@ pub trait Strategy: fmt::Debug {
     /// Shrinking shrinks both the inner values and attempts switching from
     /// recursive to non-recursive cases.
     ///
+    /// Most generated values are much shallower than `depth`. Set
+    /// `Config::min_recursive_depth` to make some of them deeper, and use
+    /// `prop_recursive_tracked()` to observe the depth of each value.
+    ///
     /// ## Example
     ///
     /// ```rust,no_run
@@ -461,6 +699,134 @@ pub trait Strategy: fmt::Debug {
         Recursive::new(self, depth, desired_size, expected_branch_size, recurse)
     }
 
+    /// Like `prop_recursive()`, but produces each value together with its
+    /// actual depth, i.e. the number of nested recursive choices in it.
+    ///
+    /// This lets tests assert on or filter by the depth of the generated
+    /// structures without computing it themselves. The depth is kept up to
+    /// date while shrinking.
+    ///
+    /// Since uniformly random recursive values are usually shallow, see also
+    /// `Config::min_recursive_depth` to make some of them deep.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::strategy::DepthTracked;
+    ///
+    /// #[derive(Clone, Debug)]
+    /// enum Expr {
+    ///     Num(i32),
+    ///     Neg(Box<Expr>),
+    /// }
+    ///
+    /// proptest! {
+    ///     # /*
+    ///     #[test]
+    ///     # */
+    ///     fn deep_expressions(
+    ///         DepthTracked { value: expr, depth } in any::<i32>()
+    ///             .prop_map(Expr::Num)
+    ///             .prop_recursive_tracked(8, 8, 1, |inner| {
+    ///                 inner.prop_map(|e| Expr::Neg(Box::new(e)))
+    ///             })
+    ///             .prop_filter("need nesting", |tracked| tracked.depth >= 2)
+    ///     ) {
+    ///         prop_assert!(depth >= 2);
+    ///         prop_assert!(matches!(expr, Expr::Neg(_)));
+    ///     }
+    /// }
+    /// # fn main() { deep_expressions(); }
+    /// ```
+    fn prop_recursive_tracked<
+        R: Strategy<Value = Self::Value> + 'static,
+        F: Fn(BoxedStrategy<Self::Value>) -> R,
+    >(
+        self,
+        depth: u32,
+        desired_size: u32,
+        expected_branch_size: u32,
+        recurse: F,
+    ) -> RecursiveTracked<Self::Value, F>
+    where
+        Self: Sized + 'static,
+    {
+        RecursiveTracked::new(
+            self,
+            depth,
+            desired_size,
+            expected_branch_size,
+            recurse,
+        )
+    }
+
+    /// Like `prop_recursive()`, but generates DAGs in which subtrees may be
+    /// shared, rather than trees.
+    ///
+    /// `recurse` is given a strategy for nodes wrapped in `Arc`. Each time
+    /// that strategy is asked for a node, with probability `sharing` it
+    /// returns a clone of an `Arc` generated earlier for the same value
+    /// (provided that node is not too deep for the current level) instead of
+    /// generating a fresh one. The produced values can thus be used to test
+    /// code sensitive to pointer identity, e.g. caching or hashing of
+    /// structural DAGs, via `Arc::ptr_eq`. A node is only available for reuse
+    /// once it is complete, so no cycles can arise.
+    ///
+    /// `depth`, `desired_size` and `expected_branch_size` have the same
+    /// meaning as for `prop_recursive()`, except that shared nodes are
+    /// counted once for each reference to them.
+    ///
+    /// Shrinking works as for `prop_recursive()`. A shared node is shrunk
+    /// through its first reference, and all other references follow along.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// # #![allow(unused_variables)]
+    /// use std::sync::Arc;
+    ///
+    /// use proptest::prelude::*;
+    ///
+    /// #[derive(Debug)]
+    /// enum Expr {
+    ///     Num(i32),
+    ///     Add(Arc<Expr>, Arc<Expr>),
+    /// }
+    ///
+    /// let dag = any::<i32>().prop_map(Expr::Num).prop_recursive_shared(
+    ///     4,   // No more than 4 levels deep
+    ///     32,  // Target around 32 total nodes
+    ///     2,   // Each node has 2 children
+    ///     0.3, // 30% chance to reuse an existing node
+    ///     |node| (node.clone(), node)
+    ///         .prop_map(|(lhs, rhs)| Expr::Add(lhs, rhs)),
+    /// );
+    /// ```
+    fn prop_recursive_shared<
+        R: Strategy<Value = Self::Value> + 'static,
+        F: Fn(BoxedStrategy<Arc<Self::Value>>) -> R,
+    >(
+        self,
+        depth: u32,
+        desired_size: u32,
+        expected_branch_size: u32,
+        sharing: impl Into<Probability>,
+        recurse: F,
+    ) -> RecursiveShared<Self::Value, F>
+    where
+        Self: Sized + 'static,
+    {
+        RecursiveShared::new(
+            self,
+            depth,
+            desired_size,
+            expected_branch_size,
+            sharing.into(),
+            recurse,
+        )
+    }
+
     /// Shuffle the contents of the values produced by this strategy.
     ///
     /// That is, this modifies a strategy producing a `Vec`, slice, etc, to
@@ -505,6 +871,95 @@ pub trait Strategy: fmt::Debug {
         Shuffle(self)
     }
 
+    /// Maps the strings generated by this strategy to their Unicode
+    /// Normalization Form C (canonical composition).
+    ///
+    /// Values shrink like those of this strategy.
+    ///
+    /// Requires the `unicode-normalization` feature.
+    #[cfg(feature = "unicode-normalization")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    fn prop_nfc(self) -> Map<Self, fn(String) -> String>
+    where
+        Self: Sized + Strategy<Value = String>,
+    {
+        self.prop_map(crate::string::to_nfc as fn(String) -> String)
+    }
+
+    /// Maps the strings generated by this strategy to their case folded
+    /// form, such that strings differing only by case map to the same
+    /// string.
+    ///
+    /// Folding follows the case mappings of the standard library, applying
+    /// the upper-case mapping before the lower-case one, which is how full
+    /// Unicode case folding treats all but a handful of characters. Combine
+    /// with `prop_nfc()` to compare strings caselessly and regardless of
+    /// their encoding.
+    ///
+    /// Values shrink like those of this strategy.
+    ///
+    /// Requires the `unicode-normalization` feature.
+    #[cfg(feature = "unicode-normalization")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    fn prop_casefold(self) -> Map<Self, fn(String) -> String>
+    where
+        Self: Sized + Strategy<Value = String>,
+    {
+        self.prop_map(crate::string::casefold as fn(String) -> String)
+    }
+
+    /// Pairs each string generated by this strategy with a canonically
+    /// equivalent variant, which usually differs byte-wise.
+    ///
+    /// The variant re-encodes a random selection of the characters of the
+    /// string, decomposing precomposed characters such as `é` into their base
+    /// character and combining marks, and replacing characters like `K` and
+    /// `Ω` with their canonically equivalent singletons, `KELVIN SIGN` and
+    /// `OHM SIGN`. Both strings of a pair therefore have the same normal
+    /// forms and should be treated as equal by code which compares or
+    /// deduplicates user-facing text.
+    ///
+    /// The string shrinks like those of this strategy, while the variant
+    /// shrinks towards re-encoding fewer characters.
+    ///
+    /// Requires the `unicode-normalization` feature.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// fn same_name(a: &str, b: &str) -> bool {
+    ///     use unicode_normalization::UnicodeNormalization;
+    ///     a.nfc().eq(b.nfc())
+    /// }
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_same_name(
+    ///     (name, variant) in "[a-zA-Zéåñ ]{1,16}".prop_with_confusables()
+    ///   ) {
+    ///     prop_assert!(same_name(&name, &variant));
+    ///   }
+    /// }
+    /// # fn main() { test_same_name(); }
+    /// ```
+    #[cfg(feature = "unicode-normalization")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "unicode-normalization")))]
+    fn prop_with_confusables(
+        self,
+    ) -> Map<(Self, crate::num::u64::Any), fn((String, u64)) -> (String, String)>
+    where
+        Self: Sized + Strategy<Value = String>,
+    {
+        (self, crate::num::u64::ANY).prop_map(
+            crate::string::with_confusable
+                as fn((String, u64)) -> (String, String),
+        )
+    }
+
     /// Erases the type of this `Strategy` so it can be passed around as a
     /// simple trait object.
     ///
@@ -550,6 +1005,136 @@ pub trait Strategy: fmt::Debug {
     {
         NoShrink(self)
     }
+
+    /// Returns a strategy whose values stop shrinking after `max_steps`
+    /// calls to `simplify()`, leaving the rest of the input free to shrink.
+    ///
+    /// `Config::max_shrink_iters` bounds shrinking of the whole input, so a
+    /// large part of it, such as a huge collection, can use up all of it
+    /// before the other parts get their turn. Capping that part with this
+    /// method bounds the time spent in it. Each value tree counts its own
+    /// steps, so every value generated by the strategy gets `max_steps` anew.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// proptest! {
+    ///     # /*
+    ///     #[test]
+    ///     # */
+    ///     fn insert_keeps_sorted(
+    ///         records in prop::collection::vec(any::<u32>(), 0..1000)
+    ///             .prop_shrink_budget(64),
+    ///         record in any::<u32>(),
+    ///     ) {
+    ///         let mut sorted = records.clone();
+    ///         sorted.sort();
+    ///         let at = sorted.partition_point(|&r| r < record);
+    ///         sorted.insert(at, record);
+    ///         prop_assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+    ///     }
+    /// }
+    /// # fn main() { insert_keeps_sorted(); }
+    /// ```
+    fn prop_shrink_budget(self, max_steps: u32) -> ShrinkBudgeted<Self>
+    where
+        Self: Sized,
+    {
+        ShrinkBudgeted::new(self, max_steps)
+    }
+
+    /// Returns a strategy which injects one of `values` in place of the
+    /// values of this strategy with the given `probability`.
+    ///
+    /// This is meant for domain-specific "magic" inputs which a strategy is
+    /// unlikely to generate on its own, such as the empty string, the Unix
+    /// epoch or `0x7fffffff`. See `WithSpecialValues` for how the injected
+    /// values shrink.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `values` is empty, or if `probability` is outside
+    /// `[0.0, 1.0]`.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// fn parse_timestamp(s: &str) -> Option<i64> {
+    ///     s.parse().ok()
+    /// }
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_timestamp(
+    ///     t in any::<i64>().with_special_values(vec![0, i64::MAX], 0.1)
+    ///   ) {
+    ///     prop_assert_eq!(Some(t), parse_timestamp(&t.to_string()));
+    ///   }
+    /// }
+    /// # fn main() { test_timestamp(); }
+    /// ```
+    fn with_special_values(
+        self,
+        values: impl Into<Vec<Self::Value>>,
+        probability: impl Into<Probability>,
+    ) -> WithSpecialValues<Self>
+    where
+        Self: Sized,
+        Self::Value: Clone,
+    {
+        WithSpecialValues::new(self, SpecialValues::new(values, probability))
+    }
+
+    /// Wraps this strategy so that its values shrink as a unit, e.g. for a
+    /// group of values which are only meaningful together, such as a payload
+    /// and its checksum.
+    ///
+    /// The parts of a value are never shrunk independently of each other.
+    /// Instead, shrinking generates whole new values from this strategy,
+    /// moving to one whenever it is simpler than the current value, so the
+    /// test only ever sees values this strategy could have produced at once.
+    /// A value counts as simpler if the value tree of this strategy would take
+    /// fewer steps to shrink it all the way. This is much coarser than normal
+    /// shrinking, so the minimal failing value is typically less minimal.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    ///
+    /// fn checksum(payload: &[u8]) -> u8 {
+    ///     payload.iter().fold(0, |sum, &byte| sum.wrapping_add(byte))
+    /// }
+    ///
+    /// proptest! {
+    ///   # /*
+    ///   #[test]
+    ///   # */
+    ///   fn test_frame(
+    ///     (payload, sum) in prop::collection::vec(any::<u8>(), 0..64)
+    ///       .prop_map(|payload| {
+    ///         let sum = checksum(&payload);
+    ///         (payload, sum)
+    ///       })
+    ///       .prop_atomic()
+    ///   ) {
+    ///     prop_assert_eq!(sum, checksum(&payload));
+    ///   }
+    /// }
+    /// # fn main() { test_frame(); }
+    /// ```
+    fn prop_atomic(self) -> Atomic<Self>
+    where
+        Self: Sized,
+    {
+        Atomic {
+            source: Arc::new(self),
+        }
+    }
 }
 
 /// A generated value and its associated shrinker.
@@ -618,6 +1203,118 @@ pub trait ValueTree {
     /// called even immediately after it had been called previously and
     /// returned `false`.
     fn complicate(&mut self) -> bool;
+
+    /// Returns an iterator over the values this tree shrinks through when
+    /// `is_failing` is the test.
+    ///
+    /// This drives `simplify()` and `complicate()` the same way the
+    /// `TestRunner` does while shrinking: each candidate value is passed to
+    /// `is_failing`, which returns `true` to accept it as a failure (after
+    /// which the tree is simplified further) or `false` to reject it (after
+    /// which the last simplification is partially undone). The iterator yields
+    /// each accepted value in turn, so the last value yielded is the minimal
+    /// failing value the runner would have reported. The current value of the
+    /// tree is assumed to fail and is not itself yielded.
+    ///
+    /// Unlike the runner, this imposes no limit on the number of shrinking
+    /// iterations; use `Iterator::take()` or similar to bound it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::strategy::ValueTree;
+    /// use proptest::test_runner::TestRunner;
+    ///
+    /// let mut runner = TestRunner::deterministic();
+    /// let tree = (100u32..1000).new_tree(&mut runner).unwrap();
+    /// let start = tree.current();
+    ///
+    /// // Everything in the range fails, so shrinking ends at the low bound.
+    /// let shrunk: Vec<u32> = tree.shrink_sequence(|&v| v >= 100).collect();
+    /// assert!(shrunk.iter().all(|&v| v < start));
+    /// assert_eq!(Some(&100), shrunk.last());
+    /// ```
+    fn shrink_sequence<F>(self, is_failing: F) -> ShrinkSequence<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Value) -> bool,
+    {
+        ShrinkSequence {
+            tree: self,
+            is_failing,
+            started: false,
+            done: false,
+        }
+    }
+}
+
+//==============================================================================
+// ShrinkSequence
+//==============================================================================
+
+/// Iterator over the values a `ValueTree` shrinks through.
+///
+/// See `ValueTree::shrink_sequence()` for more details.
+#[derive(Clone, Debug)]
+pub struct ShrinkSequence<T, F> {
+    tree: T,
+    is_failing: F,
+    started: bool,
+    done: bool,
+}
+
+impl<T, F> ShrinkSequence<T, F> {
+    /// Returns a reference to the underlying value tree.
+    ///
+    /// Once the iterator is exhausted, its current value is the last value
+    /// yielded, or the original value if nothing was.
+    pub fn tree(&self) -> &T {
+        &self.tree
+    }
+
+    /// Consumes the iterator, returning the underlying value tree.
+    pub fn into_tree(self) -> T {
+        self.tree
+    }
+}
+
+impl<T: ValueTree, F: FnMut(&T::Value) -> bool> Iterator
+    for ShrinkSequence<T, F>
+{
+    type Item = T::Value;
+
+    fn next(&mut self) -> Option<T::Value> {
+        if self.done {
+            return None;
+        }
+
+        if !self.started {
+            self.started = true;
+            if !self.tree.simplify() {
+                self.done = true;
+                return None;
+            }
+        }
+
+        loop {
+            let value = self.tree.current();
+            if (self.is_failing)(&value) {
+                if !self.tree.simplify() {
+                    self.done = true;
+                }
+                return Some(value);
+            } else if !self.tree.complicate() {
+                self.done = true;
+                return None;
+            }
+        }
+    }
+}
+
+impl<T: ValueTree, F: FnMut(&T::Value) -> bool> core::iter::FusedIterator
+    for ShrinkSequence<T, F>
+{
 }
 
 //==============================================================================
@@ -630,7 +1327,7 @@ pub trait ValueTree {
 /// See `Strategy::no_shrink()` for more details.
 #[derive(Clone, Copy, Debug)]
 #[must_use = "strategies do nothing unless used"]
-pub struct NoShrink<T>(T);
+pub struct NoShrink<T>(pub(crate) T);
 
 impl<T: Strategy> Strategy for NoShrink<T> {
     type Tree = NoShrink<T::Tree>;
@@ -639,6 +1336,10 @@ impl<T: Strategy> Strategy for NoShrink<T> {
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         self.0.new_tree(runner).map(NoShrink)
     }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.0.value_size_hint()
+    }
 }
 
 impl<T: ValueTree> ValueTree for NoShrink<T> {
@@ -669,6 +1370,10 @@ macro_rules! proxy_strategy {
             fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
                 (**self).new_tree(runner)
             }
+
+            fn value_size_hint(&self) -> (usize, Option<usize>) {
+                (**self).value_size_hint()
+            }
         }
     };
 }
@@ -733,6 +1438,10 @@ impl<T: fmt::Debug> Strategy for BoxedStrategy<T> {
         self.0.new_tree(runner)
     }
 
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.0.value_size_hint()
+    }
+
     // Optimization: Don't rebox the strategy.
 
     fn boxed(self) -> BoxedStrategy<Self::Value>
@@ -751,6 +1460,10 @@ impl<T: fmt::Debug> Strategy for SBoxedStrategy<T> {
         self.0.new_tree(runner)
     }
 
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.0.value_size_hint()
+    }
+
     // Optimization: Don't rebox the strategy.
 
     fn sboxed(self) -> SBoxedStrategy<Self::Value>
@@ -780,6 +1493,10 @@ where
     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
         Ok(Box::new(self.0.new_tree(runner)?))
     }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        self.0.value_size_hint()
+    }
 }
 
 //==============================================================================
@@ -800,6 +1517,9 @@ pub struct CheckStrategySanityOptions {
     /// Defaults to false. Useful for testing behaviors around error handling.
     pub error_on_local_rejects: bool,
 
+    /// The number of value trees to generate and check. Defaults to 1024.
+    pub cases: u32,
+
     // Needs to be public for FRU syntax.
     #[allow(missing_docs)]
     #[doc(hidden)]
@@ -811,6 +1531,7 @@ impl Default for CheckStrategySanityOptions {
         CheckStrategySanityOptions {
             strict_complicate_after_simplify: true,
             error_on_local_rejects: false,
+            cases: 1024,
             _non_exhaustive: (),
         }
     }
@@ -819,8 +1540,12 @@ impl Default for CheckStrategySanityOptions {
 /// Run some tests on the given `Strategy` to ensure that it upholds the
 /// simplify/complicate contracts.
 ///
-/// This is used to internally test proptest, but is made generally available
-/// for external implementations to use as well.
+/// This is used to internally test proptest, and is part of the public API so
+/// that crates implementing their own `Strategy` or `ValueTree` can check
+/// them the same way, typically from a unit test. Each of `options.cases`
+/// value trees generated by `strategy` is passed to
+/// `check_value_tree_sanity()`, which panics with a description of the
+/// problem if the tree misbehaves.
 ///
 /// `options` can be passed to configure the test; if `None`, the defaults are
 /// used. Note that the defaults check for certain properties which are **not**
@@ -830,6 +1555,24 @@ impl Default for CheckStrategySanityOptions {
 ///
 /// This can work with fallible strategies, but limits how many times it will
 /// retry failures.
+///
+/// ```
+/// use proptest::strategy::{
+///     check_strategy_sanity, CheckStrategySanityOptions, Strategy,
+/// };
+///
+/// // A strategy for even numbers, which can't always complicate back after
+/// // simplifying since it is built with `prop_filter()`.
+/// let evens = (0u32..1000).prop_filter("odd", |v| 0 == v % 2);
+/// check_strategy_sanity(
+///     evens,
+///     Some(CheckStrategySanityOptions {
+///         strict_complicate_after_simplify: false,
+///         cases: 64,
+///         ..CheckStrategySanityOptions::default()
+///     }),
+/// );
+/// ```
 pub fn check_strategy_sanity<S: Strategy>(
     strategy: S,
     options: Option<CheckStrategySanityOptions>,
@@ -837,18 +1580,6 @@ pub fn check_strategy_sanity<S: Strategy>(
     S::Tree: Clone + fmt::Debug,
     S::Value: cmp::PartialEq,
 {
-    // Like assert_eq!, but also pass if both values do not equal themselves.
-    // This allows the test to work correctly with things like NaN.
-    macro_rules! assert_same {
-        ($a:expr, $b:expr, $($stuff:tt)*) => { {
-            let a = $a;
-            let b = $b;
-            if a == a || b == b {
-                assert_eq!(a, b, $($stuff)*);
-            }
-        } }
-    }
-
     let options = options.unwrap_or_else(CheckStrategySanityOptions::default);
     let mut config = Config::default();
     if options.error_on_local_rejects {
@@ -856,9 +1587,9 @@ pub fn check_strategy_sanity<S: Strategy>(
     }
     let mut runner = TestRunner::new(config);
 
-    for _ in 0..1024 {
+    for _ in 0..options.cases {
         let mut gen_tries = 0;
-        let mut state;
+        let state;
         loop {
             let err = match strategy.new_tree(&mut runner) {
                 Ok(s) => {
@@ -879,176 +1610,286 @@ pub fn check_strategy_sanity<S: Strategy>(
             }
         }
 
-        {
-            let mut state = state.clone();
-            let mut count = 0;
-            while state.simplify() || state.complicate() {
-                count += 1;
-                if count > 65536 {
-                    panic!(
-                        "Failed to converge on any value. State:\n{:#?}",
-                        state
-                    );
-                }
-            }
-        }
+        check_value_tree_sanity(state, Some(options));
+    }
+}
 
-        let mut num_simplifies = 0;
-        let mut before_simplified;
-        loop {
-            before_simplified = state.clone();
-            if !state.simplify() {
-                break;
+/// Run some tests on the given `ValueTree` to ensure that it upholds the
+/// simplify/complicate contracts.
+///
+/// This is the part of `check_strategy_sanity()` which checks each generated
+/// value tree, for use with trees which weren't made by a `Strategy`, or to
+/// check a particular tree which is known to be troublesome. Only
+/// `options.strict_complicate_after_simplify` has any effect here.
+pub fn check_value_tree_sanity<T: ValueTree + Clone + fmt::Debug>(
+    mut state: T,
+    options: Option<CheckStrategySanityOptions>,
+) where
+    T::Value: cmp::PartialEq,
+{
+    // Like assert_eq!, but also pass if both values do not equal themselves.
+    // This allows the test to work correctly with things like NaN.
+    macro_rules! assert_same {
+        ($a:expr, $b:expr, $($stuff:tt)*) => { {
+            let a = $a;
+            let b = $b;
+            if a == a || b == b {
+                assert_eq!(a, b, $($stuff)*);
             }
+        } }
+    }
 
-            let mut complicated = state.clone();
-            let before_complicated = state.clone();
-            if options.strict_complicate_after_simplify {
-                assert!(
-                    complicated.complicate(),
-                    "complicate() returned false immediately after \
-                     simplify() returned true. internal state after \
-                     {} calls to simplify():\n\
-                     {:#?}\n\
-                     simplified to:\n\
-                     {:#?}\n\
-                     complicated to:\n\
-                     {:#?}",
-                    num_simplifies,
-                    before_simplified,
-                    state,
-                    complicated
-                );
-            }
+    let options = options.unwrap_or_default();
 
-            let mut prev_complicated = complicated.clone();
-            let mut num_complications = 0;
-            loop {
-                if !complicated.complicate() {
-                    break;
-                }
-                prev_complicated = complicated.clone();
-                num_complications += 1;
-
-                if num_complications > 65_536 {
-                    panic!(
-                        "complicate() returned true over 65536 times in a \
-                         row; aborting due to possible infinite loop. \
-                         If this is not an infinite loop, it may be \
-                         necessary to reconsider how shrinking is \
-                         implemented or use a simpler test strategy. \
-                         Internal state:\n{:#?}",
-                        state
-                    );
-                }
+    {
+        let mut state = state.clone();
+        let mut count = 0;
+        while state.simplify() || state.complicate() {
+            count += 1;
+            if count > 65536 {
+                panic!("Failed to converge on any value. State:\n{:#?}", state);
             }
+        }
+    }
 
-            assert_same!(
-                before_simplified.current(),
-                complicated.current(),
-                "Calling simplify(), then complicate() until it \
-                 returned false, did not return to the value before \
-                 simplify. Expected:\n\
-                 {:#?}\n\
-                 Actual:\n\
-                 {:#?}\n\
-                 Internal state after {} calls to simplify():\n\
+    let mut num_simplifies = 0;
+    let mut before_simplified;
+    loop {
+        before_simplified = state.clone();
+        if !state.simplify() {
+            break;
+        }
+
+        let mut complicated = state.clone();
+        let before_complicated = state.clone();
+        if options.strict_complicate_after_simplify {
+            assert!(
+                complicated.complicate(),
+                "complicate() returned false immediately after \
+                 simplify() returned true. internal state after \
+                 {} calls to simplify():\n\
                  {:#?}\n\
-                 Internal state after another call to simplify():\n\
+                 simplified to:\n\
                  {:#?}\n\
-                 Internal state after {} subsequent calls to \
-                 complicate():\n\
+                 complicated to:\n\
                  {:#?}",
-                before_simplified.current(),
-                complicated.current(),
                 num_simplifies,
                 before_simplified,
-                before_complicated,
-                num_complications + 1,
+                state,
                 complicated
             );
+        }
 
-            for iter in 1..16 {
-                assert_same!(
-                    prev_complicated.current(),
-                    complicated.current(),
-                    "complicate() returned false but changed the output \
-                     value anyway.\n\
-                     Old value:\n\
-                     {:#?}\n\
-                     New value:\n\
-                     {:#?}\n\
-                     Old internal state:\n\
-                     {:#?}\n\
-                     New internal state after {} calls to complicate()\
-                     including the :\n\
-                     {:#?}",
-                    prev_complicated.current(),
-                    complicated.current(),
-                    prev_complicated,
-                    iter,
-                    complicated
-                );
-
-                assert!(
-                    !complicated.complicate(),
-                    "complicate() returned true after having returned \
-                     false;\n\
-                     Internal state before:\n{:#?}\n\
-                     Internal state after calling complicate() {} times:\n\
-                     {:#?}",
-                    prev_complicated,
-                    iter + 1,
-                    complicated
-                );
+        let mut prev_complicated = complicated.clone();
+        let mut num_complications = 0;
+        loop {
+            if !complicated.complicate() {
+                break;
             }
+            prev_complicated = complicated.clone();
+            num_complications += 1;
 
-            num_simplifies += 1;
-            if num_simplifies > 65_536 {
+            if num_complications > 65_536 {
                 panic!(
-                    "simplify() returned true over 65536 times in a row, \
-                     aborting due to possible infinite loop. If this is not \
-                     an infinite loop, it may be necessary to reconsider \
-                     how shrinking is implemented or use a simpler test \
-                     strategy. Internal state:\n{:#?}",
+                    "complicate() returned true over 65536 times in a \
+                     row; aborting due to possible infinite loop. \
+                     If this is not an infinite loop, it may be \
+                     necessary to reconsider how shrinking is \
+                     implemented or use a simpler test strategy. \
+                     Internal state:\n{:#?}",
                     state
                 );
             }
         }
 
-        for iter in 0..16 {
+        assert_same!(
+            before_simplified.current(),
+            complicated.current(),
+            "Calling simplify(), then complicate() until it \
+             returned false, did not return to the value before \
+             simplify. Expected:\n\
+             {:#?}\n\
+             Actual:\n\
+             {:#?}\n\
+             Internal state after {} calls to simplify():\n\
+             {:#?}\n\
+             Internal state after another call to simplify():\n\
+             {:#?}\n\
+             Internal state after {} subsequent calls to \
+             complicate():\n\
+             {:#?}",
+            before_simplified.current(),
+            complicated.current(),
+            num_simplifies,
+            before_simplified,
+            before_complicated,
+            num_complications + 1,
+            complicated
+        );
+
+        for iter in 1..16 {
             assert_same!(
-                before_simplified.current(),
-                state.current(),
-                "simplify() returned false but changed the output \
+                prev_complicated.current(),
+                complicated.current(),
+                "complicate() returned false but changed the output \
                  value anyway.\n\
                  Old value:\n\
                  {:#?}\n\
                  New value:\n\
                  {:#?}\n\
+                 Old internal state:\n\
+                 {:#?}\n\
+                 New internal state after {} calls to complicate()\
+                 including the :\n\
+                 {:#?}",
+                prev_complicated.current(),
+                complicated.current(),
+                prev_complicated,
+                iter,
+                complicated
+            );
+
+            assert!(
+                !complicated.complicate(),
+                "complicate() returned true after having returned \
+                 false;\n\
+                 Internal state before:\n{:#?}\n\
+                 Internal state after calling complicate() {} times:\n\
+                 {:#?}",
+                prev_complicated,
+                iter + 1,
+                complicated
+            );
+        }
+
+        num_simplifies += 1;
+        if num_simplifies > 65_536 {
+            panic!(
+                "simplify() returned true over 65536 times in a row, \
+                 aborting due to possible infinite loop. If this is not \
+                 an infinite loop, it may be necessary to reconsider \
+                 how shrinking is implemented or use a simpler test \
+                 strategy. Internal state:\n{:#?}",
+                state
+            );
+        }
+    }
+
+    for iter in 0..16 {
+        assert_same!(
+            before_simplified.current(),
+            state.current(),
+            "simplify() returned false but changed the output \
+             value anyway.\n\
+             Old value:\n\
+             {:#?}\n\
+             New value:\n\
+             {:#?}\n\
+             Previous internal state:\n\
+             {:#?}\n\
+             New internal state after calling simplify() {} times:\n\
+             {:#?}",
+            before_simplified.current(),
+            state.current(),
+            before_simplified,
+            iter,
+            state
+        );
+
+        if state.simplify() {
+            panic!(
+                "simplify() returned true after having returned false. \
                  Previous internal state:\n\
                  {:#?}\n\
                  New internal state after calling simplify() {} times:\n\
                  {:#?}",
-                before_simplified.current(),
-                state.current(),
                 before_simplified,
-                iter,
+                iter + 1,
                 state
             );
+        }
+    }
+}
 
-            if state.simplify() {
-                panic!(
-                    "simplify() returned true after having returned false. \
-                     Previous internal state:\n\
-                     {:#?}\n\
-                     New internal state after calling simplify() {} times:\n\
-                     {:#?}",
-                    before_simplified,
-                    iter + 1,
-                    state
-                );
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::std_facade::{vec, Vec};
+
+    /// A value tree which forgets its original value once simplified.
+    #[derive(Clone, Debug)]
+    struct Forgetful(u32);
+
+    impl ValueTree for Forgetful {
+        type Value = u32;
+
+        fn current(&self) -> u32 {
+            self.0
+        }
+
+        fn simplify(&mut self) -> bool {
+            if 0 == self.0 {
+                false
+            } else {
+                self.0 /= 2;
+                true
             }
         }
+
+        fn complicate(&mut self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn shrink_sequence_ends_at_minimal_failure() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let tree = (0u32..10_000).new_tree(&mut runner).unwrap();
+            let start = tree.current();
+            if start < 4321 {
+                continue;
+            }
+
+            let mut prev = start;
+            let mut sequence = tree.shrink_sequence(|&v| v >= 4321);
+            for value in &mut sequence {
+                assert!(value >= 4321);
+                assert!(value <= prev);
+                prev = value;
+            }
+            assert_eq!(4321, sequence.tree().current());
+            if start > 4321 {
+                assert_eq!(4321, prev);
+            }
+        }
+    }
+
+    #[test]
+    fn shrink_sequence_follows_simplify() {
+        assert_eq!(None, Forgetful(0).shrink_sequence(|_| true).next());
+        assert_eq!(
+            vec![50, 25, 12, 6, 3, 1, 0],
+            Forgetful(100).shrink_sequence(|_| true).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn value_tree_sanity_accepts_well_behaved_tree() {
+        let mut runner = TestRunner::deterministic();
+        let tree = (0u32..1000).new_tree(&mut runner).unwrap();
+        check_value_tree_sanity(tree, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not return to the value before simplify")]
+    fn value_tree_sanity_rejects_forgetful_tree() {
+        check_value_tree_sanity(
+            Forgetful(100),
+            Some(CheckStrategySanityOptions {
+                strict_complicate_after_simplify: false,
+                ..CheckStrategySanityOptions::default()
+            }),
+        );
     }
 }
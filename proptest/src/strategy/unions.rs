@@ -9,13 +9,18 @@
 
 use crate::std_facade::{fmt, Arc, Vec};
 use core::cmp::{max, min};
+use core::sync::atomic::{AtomicU32, Ordering::Relaxed};
 use core::u32;
 
 #[cfg(not(feature = "std"))]
 use num_traits::float::FloatCore;
 
 use crate::num::sample_uniform;
-use crate::strategy::{lazy::LazyValueTree, traits::*};
+use crate::strategy::{
+    lazy::{LazyStrategy, LazyValueTree},
+    traits::*,
+    IntoStrategy,
+};
 use crate::test_runner::*;
 
 /// A **relative** `weight` of a particular `Strategy` corresponding to `T`
@@ -55,6 +60,18 @@ impl<T: Strategy> Union<T> {
         Self { options }
     }
 
+    /// Like `new()`, but also accepts anything implementing `IntoStrategy`,
+    /// such as literal values, which are then always chosen as is.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `options` is empty.
+    pub fn new_into<I: IntoStrategy<Strategy = T>>(
+        options: impl IntoIterator<Item = I>,
+    ) -> Self {
+        Self::new(options.into_iter().map(I::into_strategy))
+    }
+
     pub(crate) fn try_new<E>(
         it: impl Iterator<Item = Result<T, E>>,
     ) -> Result<Self, E> {
@@ -132,26 +149,247 @@ impl<T: Strategy> Strategy for Union<T> {
             self.options.iter().map(extract_weight::<T>),
         );
 
-        let mut options = Vec::with_capacity(pick);
+        union_value_tree(&self.options, pick, runner)
+    }
+}
+
+/// Creates the tree of a union of `options` which starts at `pick`.
+fn union_value_tree<T: Strategy>(
+    options: &[WA<T>],
+    pick: usize,
+    runner: &mut TestRunner,
+) -> Result<UnionValueTree<T>, Reason> {
+    let mut trees = Vec::with_capacity(pick + 1);
+
+    // Delay initialization for all options less than pick.
+    for option in &options[0..pick] {
+        trees.push(LazyValueTree::new(Arc::clone(&option.1), runner));
+    }
+
+    // Initialize the tree at pick so at least one value is available. Note
+    // that if generation for the value at pick fails, the entire strategy
+    // will fail. This seems like the right call.
+    trees.push(LazyValueTree::new_initialized(
+        options[pick].1.new_tree(runner)?,
+    ));
+
+    Ok(UnionValueTree {
+        options: trees,
+        pick,
+        min_pick: 0,
+        prev_pick: None,
+    })
+}
+
+/// The number of most recent picks of each option of an `AdaptiveUnion`
+/// which `OptionFeedback::recent_rejects` covers.
+pub const ADAPTIVE_UNION_WINDOW: u32 = 32;
+
+/// What an `AdaptiveUnion` has observed about one of its options.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OptionFeedback {
+    /// The number of times the option was picked.
+    pub picks: u32,
+    /// The number of picks of the option which were rejected, either locally
+    /// by a filter while generating its value, or globally by the test the
+    /// value was generated for.
+    pub rejects: u32,
+    /// The number of the last `ADAPTIVE_UNION_WINDOW` picks of the option
+    /// which were rejected.
+    pub recent_rejects: u32,
+}
+
+/// The feedback of the options of an `AdaptiveUnion`, shared between its
+/// clones and the `TestRunner`s generating values from it.
+#[derive(Debug)]
+pub(crate) struct UnionFeedback {
+    options: Vec<OptionCounters>,
+}
 
-        // Delay initialization for all options less than pick.
-        for option in &self.options[0..pick] {
-            options.push(LazyValueTree::new(Arc::clone(&option.1), runner));
+#[derive(Debug, Default)]
+struct OptionCounters {
+    picks: AtomicU32,
+    rejects: AtomicU32,
+    /// One bit per recent pick, the lowest being the last, which is set if
+    /// the pick was rejected.
+    recent: AtomicU32,
+}
+
+impl UnionFeedback {
+    fn new(options: usize) -> Self {
+        UnionFeedback {
+            options: (0..options).map(|_| OptionCounters::default()).collect(),
         }
+    }
 
-        // Initialize the tree at pick so at least one value is available. Note
-        // that if generation for the value at pick fails, the entire strategy
-        // will fail. This seems like the right call.
-        options.push(LazyValueTree::new_initialized(
-            self.options[pick].1.new_tree(runner)?,
-        ));
+    fn pick(&self, option: usize, rejected: bool) {
+        let counters = &self.options[option];
+        counters.picks.fetch_add(1, Relaxed);
+        let _ = counters.recent.fetch_update(Relaxed, Relaxed, |recent| {
+            Some(recent << 1 | u32::from(rejected))
+        });
+        if rejected {
+            counters.rejects.fetch_add(1, Relaxed);
+        }
+    }
+
+    /// Records that the value of the last pick of `option` was rejected by
+    /// the test.
+    pub(crate) fn reject(&self, option: usize) {
+        let counters = &self.options[option];
+        let previous = counters.recent.fetch_or(1, Relaxed);
+        if 0 == previous & 1 {
+            counters.rejects.fetch_add(1, Relaxed);
+        }
+    }
 
-        Ok(UnionValueTree {
+    fn get(&self, option: usize) -> OptionFeedback {
+        let counters = &self.options[option];
+        OptionFeedback {
+            picks: counters.picks.load(Relaxed),
+            rejects: counters.rejects.load(Relaxed),
+            recent_rejects: counters.recent.load(Relaxed).count_ones(),
+        }
+    }
+}
+
+type WeighFn = dyn Fn(u32, &OptionFeedback) -> u32 + Send + Sync;
+
+/// A `Strategy` which picks from one of several delegate `Strategy`s, like
+/// `Union`, but adjusts the weights of the options as values are generated.
+///
+/// Each time an option is picked, the `TestRunner` reports whether its value
+/// was rejected, either locally by a filter while generating it, or globally
+/// by the test case, e.g. with `prop_assume!`. By default, options are then
+/// favoured in proportion to how few of their recent picks were rejected, so
+/// that a union of several options one of which mostly generates rejected
+/// values spends most of its cases on the others. `with_weights()` replaces
+/// this by a custom adjustment.
+///
+/// The feedback is shared between clones of the strategy, so it keeps
+/// building up across the cases of a test.
+///
+/// Since the weights depend on the cases run before, the picks of an
+/// `AdaptiveUnion` are only deterministic for a given seed if the strategy is
+/// created afresh for the run.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::strategy::{AdaptiveUnion, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let union = AdaptiveUnion::new(vec![
+///     (1, (0..100u32).boxed()),
+///     (1, (0..100u32).prop_filter("odd", |v| v % 2 == 1).boxed()),
+/// ]);
+///
+/// let mut runner = TestRunner::default();
+/// for _ in 0..100 {
+///     union.new_tree(&mut runner).unwrap().current();
+/// }
+/// let feedback = union.feedback();
+/// assert!(feedback[0].picks > feedback[1].picks);
+/// ```
+#[derive(Clone)]
+#[must_use = "strategies do nothing unless used"]
+pub struct AdaptiveUnion<T: Strategy> {
+    options: Vec<WA<T>>,
+    feedback: Arc<UnionFeedback>,
+    weigh: Arc<WeighFn>,
+}
+
+impl<T: Strategy> fmt::Debug for AdaptiveUnion<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AdaptiveUnion")
+            .field("options", &self.options)
+            .field("feedback", &self.feedback)
+            .field("weigh", &"<function>")
+            .finish()
+    }
+}
+
+impl<T: Strategy> AdaptiveUnion<T> {
+    /// Create a strategy which selects from the given delegate strategies,
+    /// starting with the given weights, which are then adjusted by
+    /// `favor_unrejected()`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `options` is empty or any element has a weight of 0.
+    ///
+    /// Panics if the sum of the weights overflows a `u32`.
+    pub fn new(options: Vec<W<T>>) -> Self {
+        let Union { options } = Union::new_weighted(options);
+        AdaptiveUnion {
+            feedback: Arc::new(UnionFeedback::new(options.len())),
             options,
-            pick,
-            min_pick: 0,
-            prev_pick: None,
-        })
+            weigh: Arc::new(favor_unrejected),
+        }
+    }
+
+    /// Adjust the weights of the options with `weigh` rather than
+    /// `favor_unrejected()`.
+    ///
+    /// Each time a value is generated, `weigh` is called for each option with
+    /// its initial weight and its feedback so far, and returns the weight to
+    /// pick it with. An option with a weight of 0 is not picked, unless all
+    /// options have a weight of 0, in which case the initial weights are
+    /// used.
+    pub fn with_weights(
+        self,
+        weigh: impl Fn(u32, &OptionFeedback) -> u32 + Send + Sync + 'static,
+    ) -> Self {
+        AdaptiveUnion {
+            weigh: Arc::new(weigh),
+            ..self
+        }
+    }
+
+    /// Return the feedback on each option observed so far.
+    pub fn feedback(&self) -> Vec<OptionFeedback> {
+        (0..self.options.len())
+            .map(|option| self.feedback.get(option))
+            .collect()
+    }
+}
+
+/// The default adjustment of the weights of an `AdaptiveUnion`: `weight`
+/// multiplied by one more than the number of the last
+/// `ADAPTIVE_UNION_WINDOW` picks of the option which were not rejected, or
+/// which did not happen yet.
+///
+/// An option none of whose recent picks were rejected thus has 33 times the
+/// weight of one all of whose were, but never has a weight of 0.
+pub fn favor_unrejected(weight: u32, feedback: &OptionFeedback) -> u32 {
+    weight.saturating_mul(1 + ADAPTIVE_UNION_WINDOW - feedback.recent_rejects)
+}
+
+impl<T: Strategy> Strategy for AdaptiveUnion<T> {
+    type Tree = UnionValueTree<T>;
+    type Value = T::Value;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let mut weights: Vec<u32> = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(ix, &(w, _))| (self.weigh)(w, &self.feedback.get(ix)))
+            .collect();
+        if weights.iter().all(|&w| 0 == w) {
+            weights = self.options.iter().map(|&(w, _)| w).collect();
+        }
+        let pick = pick_weighted(
+            runner,
+            weights.iter().cloned(),
+            weights.iter().cloned(),
+        );
+
+        let local_rejects = runner.local_rejects();
+        let tree = union_value_tree(&self.options, pick, runner)?;
+        self.feedback
+            .pick(pick, runner.local_rejects() > local_rejects);
+        runner.track_union_pick(&self.feedback, pick);
+        Ok(tree)
     }
 }
 
@@ -406,6 +644,74 @@ tuple_union!(B 1 C 2 D 3 E 4 F 5 G 6 H 7);
 tuple_union!(B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8);
 tuple_union!(B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9);
 
+/// Like `TupleUnion`, but each option is a `LazyStrategy`, so that only the
+/// options which are actually picked, or shrunk to, are ever constructed.
+///
+/// This saves time and memory where constructing the options is expensive
+/// and most go unused, such as for `#[derive(Arbitrary)]` on enums with many
+/// large variants.
+///
+/// ```
+/// use std::sync::Arc;
+/// use proptest::prelude::*;
+/// use proptest::strategy::{LazyStrategy, LazyTupleUnion, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let small = Arc::new(LazyStrategy::new(10u32, |max| 0..max));
+/// let large = Arc::new(LazyStrategy::new(1000u32, |min| min..));
+/// let union = LazyTupleUnion::new((
+///     (1, Arc::clone(&small)),
+///     (0, Arc::clone(&large)),
+/// ));
+///
+/// let mut runner = TestRunner::default();
+/// assert!(union.new_tree(&mut runner).unwrap().current() < 10);
+/// assert!(small.is_constructed());
+/// assert!(!large.is_constructed());
+/// ```
+#[must_use = "strategies do nothing unless used"]
+#[derive(Clone, Debug)]
+pub struct LazyTupleUnion<T>(TupleUnion<T>);
+
+impl<T> LazyTupleUnion<T> {
+    /// Wrap `tuple` in a `LazyTupleUnion`.
+    ///
+    /// As for `TupleUnion::new()`, `tuple` must be a 2- to 10-tuple of
+    /// `(u32, Arc<S>)` pairs, but here every `S` must be a `LazyStrategy`.
+    pub fn new(tuple: T) -> Self {
+        LazyTupleUnion(TupleUnion::new(tuple))
+    }
+}
+
+macro_rules! lazy_tuple_union {
+    ($($gen:ident $par:ident $ix:tt)*) => {
+        impl<PA, A: Strategy, $($par, $gen: Strategy<Value = A::Value>),*>
+        Strategy for LazyTupleUnion<(
+            WA<LazyStrategy<PA, A>>, $(WA<LazyStrategy<$par, $gen>>),*
+        )> {
+            type Tree = <TupleUnion<(
+                WA<LazyStrategy<PA, A>>, $(WA<LazyStrategy<$par, $gen>>),*
+            )> as Strategy>::Tree;
+            type Value = A::Value;
+
+            fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+                self.0.new_tree(runner)
+            }
+        }
+    }
+}
+
+lazy_tuple_union!(B PB 1);
+lazy_tuple_union!(B PB 1 C PC 2);
+lazy_tuple_union!(B PB 1 C PC 2 D PD 3);
+lazy_tuple_union!(B PB 1 C PC 2 D PD 3 E PE 4);
+lazy_tuple_union!(B PB 1 C PC 2 D PD 3 E PE 4 F PF 5);
+lazy_tuple_union!(B PB 1 C PC 2 D PD 3 E PE 4 F PF 5 G PG 6);
+lazy_tuple_union!(B PB 1 C PC 2 D PD 3 E PE 4 F PF 5 G PG 6 H PH 7);
+lazy_tuple_union!(B PB 1 C PC 2 D PD 3 E PE 4 F PF 5 G PG 6 H PH 7 I PI 8);
+lazy_tuple_union!(
+    B PB 1 C PC 2 D PD 3 E PE 4 F PF 5 G PG 6 H PH 7 I PI 8 J PJ 9);
+
 /// `ValueTree` type produced by `TupleUnion`.
 #[derive(Clone, Copy, Debug)]
 pub struct TupleUnionValueTree<T> {
@@ -659,6 +965,45 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_lazy_tuple_union_sanity() {
+        check_strategy_sanity(
+            LazyTupleUnion::new((
+                (1, Arc::new(LazyStrategy::new(0, |min| min..100i32))),
+                (1, Arc::new(LazyStrategy::new(200, |min| min..1000i32))),
+                (1, Arc::new(LazyStrategy::new(2000, |min| min..3000i32))),
+            )),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_lazy_tuple_union_constructs_options_on_demand() {
+        let options = [
+            Arc::new(LazyStrategy::new(0, |min| min..100i32)),
+            Arc::new(LazyStrategy::new(100, |min| min..200i32)),
+            Arc::new(LazyStrategy::new(200, |min| min..300i32)),
+        ];
+        let union = LazyTupleUnion::new((
+            (0, Arc::clone(&options[0])),
+            (1, Arc::clone(&options[1])),
+            (0, Arc::clone(&options[2])),
+        ));
+
+        let mut runner = TestRunner::deterministic();
+        let mut tree = union.new_tree(&mut runner).unwrap();
+        assert!((100..200).contains(&tree.current()));
+        assert!(!options[0].is_constructed());
+        assert!(options[1].is_constructed());
+        assert!(!options[2].is_constructed());
+
+        while tree.current() >= 100 {
+            assert!(tree.simplify());
+        }
+        assert!(options[0].is_constructed());
+        assert!(!options[2].is_constructed());
+    }
+
     /// Test that unions work even if local filtering causes errors.
     #[test]
     fn test_filter_union_sanity() {
@@ -684,6 +1029,79 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_adaptive_union_sanity() {
+        check_strategy_sanity(
+            AdaptiveUnion::new(vec![
+                (1, 0i32..100),
+                (2, 200i32..300),
+                (1, 400i32..500),
+            ]),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_adaptive_union_favors_options_not_filtered() {
+        let union = AdaptiveUnion::new(vec![
+            (1, (0u32..100).boxed()),
+            (1, (0u32..100).prop_filter("< 5", |&v| v < 5).boxed()),
+        ]);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..1000 {
+            union.new_tree(&mut runner).unwrap();
+        }
+
+        let feedback = union.feedback();
+        assert_eq!(0, feedback[0].rejects);
+        assert!(
+            feedback[1].rejects > feedback[1].picks / 2,
+            "{:?}",
+            feedback
+        );
+        assert!(feedback[0].picks > 5 * feedback[1].picks, "{:?}", feedback);
+    }
+
+    #[test]
+    fn test_adaptive_union_favors_options_not_rejected_by_test() {
+        let union = AdaptiveUnion::new(vec![(1, 0u32..100), (1, 100u32..200)]);
+        let mut runner = TestRunner::new(Config {
+            cases: 500,
+            max_global_rejects: 10_000,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(&union, |v| {
+                prop_assume!(v < 100);
+                Ok(())
+            })
+            .unwrap();
+
+        let feedback = union.feedback();
+        assert_eq!(0, feedback[0].rejects);
+        assert_eq!(feedback[1].picks, feedback[1].rejects);
+        assert!(feedback[0].picks > 10 * feedback[1].picks, "{:?}", feedback);
+    }
+
+    #[test]
+    fn test_adaptive_union_custom_weights() {
+        // Never pick an option again once it was picked twice.
+        let union = AdaptiveUnion::new(vec![(1, 0u32..10), (1, 10u32..20)])
+            .with_weights(|w, feedback| if feedback.picks < 2 { w } else { 0 });
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..4 {
+            union.new_tree(&mut runner).unwrap();
+        }
+        assert!(union.feedback().iter().all(|f| 2 == f.picks));
+
+        // Once all weights are 0, the initial weights are used.
+        for _ in 0..100 {
+            union.new_tree(&mut runner).unwrap();
+        }
+        assert!(union.feedback().iter().all(|f| f.picks > 10));
+    }
+
     fn filter_sanity_options() -> CheckStrategySanityOptions {
         CheckStrategySanityOptions {
             // Due to internal rejection sampling, `simplify()` can
@@ -10,7 +10,7 @@
 //! Strategies for generating strings and byte strings from regular
 //! expressions.
 
-use crate::std_facade::{Box, Cow, String, ToOwned, Vec};
+use crate::std_facade::{Arc, Box, Cow, String, ToOwned, Vec};
 use core::fmt;
 use core::mem;
 use core::ops::RangeInclusive;
@@ -21,9 +21,10 @@ use regex_syntax::{Error as ParseError, ParserBuilder};
 
 use crate::bool;
 use crate::char;
-use crate::collection::{size_range, vec, SizeRange};
+use crate::collection::{size_range, vec, SizeRange, VecValueTree};
 use crate::strategy::*;
 use crate::test_runner::*;
+use crate::tuple::TupleValueTree;
 
 /// Wraps the regex that forms the `Strategy` for `String` so that a sensible
 /// `Default` can be given. The default is a string of non-control characters.
@@ -236,6 +237,281 @@ pub fn bytes_regex_parsed(expr: &Hir) -> ParseResult<Vec<u8>> {
     .map(RegexGeneratorStrategy)
 }
 
+/// Creates a strategy which generates strings matching the given regular
+/// expression, interpreting the anchors and word boundaries of regexes
+/// written to validate strings instead of rejecting them.
+///
+/// `^` and `$` (as well as `\A` and `\z`) at the start and end of the regex,
+/// or of its top-level alternatives, are taken to mean that the whole string
+/// matches, which is always the case for generated strings. Word boundaries
+/// such as `\b` are dropped where the characters on either side of them
+/// always satisfy them, as in `\b\w+\b` or `\d+\b-`, so that generating the
+/// two sides next to each other is enough. Any other anchor or boundary is
+/// still an error.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::string::string_regex_anchored;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn has_suffix(s in string_regex_anchored(r"^\w+\b-\d{2}$").unwrap()) {
+///         prop_assert!(s.contains('-'));
+///     }
+/// }
+/// # fn main() { has_suffix(); }
+/// ```
+pub fn string_regex_anchored(regex: &str) -> ParseResult<String> {
+    let hir = ParserBuilder::new().build().parse(regex)?;
+    string_regex_parsed(&strip_lookarounds(&hir, true, true)?)
+}
+
+/// Like `string_regex_anchored()`, but generates byte strings like
+/// `bytes_regex()`.
+pub fn bytes_regex_anchored(regex: &str) -> ParseResult<Vec<u8>> {
+    let hir = ParserBuilder::new().utf8(false).build().parse(regex)?;
+    bytes_regex_parsed(&strip_lookarounds(&hir, true, true)?)
+}
+
+/// Replaces the anchors and word boundaries in `hir` which hold for every
+/// string it matches with empty expressions, given whether `hir` is at the
+/// start and at the end of the string.
+fn strip_lookarounds(
+    hir: &Hir,
+    at_start: bool,
+    at_end: bool,
+) -> Result<Hir, Error> {
+    Ok(match hir.kind() {
+        Empty | Literal(_) | Class(_) => hir.clone(),
+
+        Look(_) => Hir::concat(strip_concat_lookarounds(
+            core::slice::from_ref(hir),
+            at_start,
+            at_end,
+        )?),
+
+        Concat(subs) => {
+            Hir::concat(strip_concat_lookarounds(subs, at_start, at_end)?)
+        }
+
+        Alternation(subs) => Hir::alternation(
+            subs.iter()
+                .map(|sub| strip_lookarounds(sub, at_start, at_end))
+                .collect::<Result<_, _>>()?,
+        ),
+
+        Capture(capture) => Hir::capture(hir::Capture {
+            index: capture.index,
+            name: capture.name.clone(),
+            sub: Box::new(strip_lookarounds(&capture.sub, at_start, at_end)?),
+        }),
+
+        // An assertion in a repetition would need to hold at every
+        // repetition, which is not worth telling apart.
+        Repetition(rep) => Hir::repetition(Repetition {
+            min: rep.min,
+            max: rep.max,
+            greedy: rep.greedy,
+            sub: Box::new(strip_lookarounds(&rep.sub, false, false)?),
+        }),
+    })
+}
+
+/// `strip_lookarounds()` for the concatenation of `subs`.
+fn strip_concat_lookarounds(
+    subs: &[Hir],
+    at_start: bool,
+    at_end: bool,
+) -> Result<Vec<Hir>, Error> {
+    let is_look = |hir: &Hir| matches!(hir.kind(), Look(_));
+
+    let mut stripped = Vec::with_capacity(subs.len());
+    for (ix, sub) in subs.iter().enumerate() {
+        // Assertions don't move the position in the string.
+        let sub_at_start = at_start && subs[..ix].iter().all(is_look);
+        let sub_at_end = at_end && subs[ix + 1..].iter().all(is_look);
+
+        let look = match sub.kind() {
+            Look(look) => *look,
+            _ => {
+                stripped.push(strip_lookarounds(
+                    sub,
+                    sub_at_start,
+                    sub_at_end,
+                )?);
+                continue;
+            }
+        };
+
+        let (unicode, holds): (bool, fn(bool, bool) -> bool) = match look {
+            hir::Look::Start | hir::Look::StartLF | hir::Look::StartCRLF => {
+                if sub_at_start {
+                    continue;
+                }
+                return unsupported(
+                    "start anchors are only supported at the start of the \
+                     regex",
+                );
+            }
+            hir::Look::End | hir::Look::EndLF | hir::Look::EndCRLF => {
+                if sub_at_end {
+                    continue;
+                }
+                return unsupported(
+                    "end anchors are only supported at the end of the regex",
+                );
+            }
+            hir::Look::WordAscii => (false, |before, after| before != after),
+            hir::Look::WordAsciiNegate => {
+                (false, |before, after| before == after)
+            }
+            hir::Look::WordUnicode => (true, |before, after| before != after),
+            hir::Look::WordUnicodeNegate => {
+                (true, |before, after| before == after)
+            }
+            hir::Look::WordStartAscii => {
+                (false, |before, after| !before && after)
+            }
+            hir::Look::WordEndAscii => {
+                (false, |before, after| before && !after)
+            }
+            hir::Look::WordStartUnicode => {
+                (true, |before, after| !before && after)
+            }
+            hir::Look::WordEndUnicode => {
+                (true, |before, after| before && !after)
+            }
+            hir::Look::WordStartHalfAscii => (false, |before, _| !before),
+            hir::Look::WordEndHalfAscii => (false, |_, after| !after),
+            hir::Look::WordStartHalfUnicode => (true, |before, _| !before),
+            hir::Look::WordEndHalfUnicode => (true, |_, after| !after),
+        };
+
+        // The ends of the string count as non-word characters.
+        let before = match subs[..ix].iter().rev().find(|s| !is_look(s)) {
+            Some(prev) => edge_is_word(prev, true, unicode),
+            None if sub_at_start => Some(false),
+            None => None,
+        };
+        let after = match subs[ix + 1..].iter().find(|s| !is_look(s)) {
+            Some(next) => edge_is_word(next, false, unicode),
+            None if sub_at_end => Some(false),
+            None => None,
+        };
+        match (before, after) {
+            (Some(before), Some(after)) if holds(before, after) => (),
+            (Some(_), Some(_)) => {
+                return unsupported("word boundary can never match")
+            }
+            _ => {
+                return unsupported(
+                    "word boundaries are only supported between characters \
+                     which are either all word or all non-word characters",
+                )
+            }
+        }
+    }
+    Ok(stripped)
+}
+
+/// Returns whether the first character, or the last one if `end`, of every
+/// string matched by `hir` is a word character, or `None` if this depends on
+/// the string.
+fn edge_is_word(hir: &Hir, end: bool, unicode: bool) -> Option<bool> {
+    if hir.properties().minimum_len() == Some(0) {
+        return None;
+    }
+
+    match hir.kind() {
+        Literal(lit) => {
+            let text = core::str::from_utf8(&lit.0).ok()?;
+            let edge = if end {
+                text.chars().next_back()
+            } else {
+                text.chars().next()
+            }?;
+            Some(if unicode {
+                regex_syntax::is_word_character(edge)
+            } else {
+                edge.is_ascii() && regex_syntax::is_word_byte(edge as u8)
+            })
+        }
+
+        Class(class) => {
+            let class = match class {
+                hir::Class::Unicode(class) => class.clone(),
+                hir::Class::Bytes(class) => {
+                    // Bytes beyond ASCII are not characters of their own.
+                    if unicode && !class.is_ascii() {
+                        return None;
+                    }
+                    hir::ClassUnicode::new(class.iter().map(|r| {
+                        hir::ClassUnicodeRange::new(
+                            char::from(r.start()),
+                            char::from(r.end()),
+                        )
+                    }))
+                }
+            };
+            let mut words = class.clone();
+            words.intersect(&word_class(unicode));
+            if words.ranges().is_empty() {
+                Some(false)
+            } else if words == class {
+                Some(true)
+            } else {
+                None
+            }
+        }
+
+        Capture(capture) => edge_is_word(&capture.sub, end, unicode),
+
+        Repetition(rep) => edge_is_word(&rep.sub, end, unicode),
+
+        Concat(subs) => {
+            let mut subs =
+                subs.iter().filter(|sub| !matches!(sub.kind(), Look(_)));
+            let edge = if end { subs.next_back() } else { subs.next() }?;
+            edge_is_word(edge, end, unicode)
+        }
+
+        Alternation(subs) => {
+            let mut edges =
+                subs.iter().map(|sub| edge_is_word(sub, end, unicode));
+            let first = edges.next()??;
+            if edges.all(|edge| edge == Some(first)) {
+                Some(first)
+            } else {
+                None
+            }
+        }
+
+        Empty | Look(_) => None,
+    }
+}
+
+/// The characters matched by `\w`, with or without Unicode support.
+fn word_class(unicode: bool) -> hir::ClassUnicode {
+    if unicode {
+        match ParserBuilder::new()
+            .build()
+            .parse(r"\w")
+            .map(Hir::into_kind)
+        {
+            Ok(Class(hir::Class::Unicode(class))) => class,
+            _ => unreachable!("\\w is a class of Unicode characters"),
+        }
+    } else {
+        hir::ClassUnicode::new(
+            [('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')]
+                .iter()
+                .map(|&(start, end)| hir::ClassUnicodeRange::new(start, end)),
+        )
+    }
+}
+
 fn unicode_class_strategy(
     class: &hir::ClassUnicode,
 ) -> char::CharStrategy<'static> {
@@ -357,6 +633,1038 @@ fn unsupported<T>(error: &'static str) -> Result<T, Error> {
     Err(Error::UnsupportedRegex(error))
 }
 
+/// Strategy returned by `utf8_with_byte_len()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Utf8WithByteLen {
+    byte_len: SizeRange,
+}
+
+/// Creates a strategy which generates strings whose UTF-8 encoding is within
+/// `byte_len` bytes long, e.g. for protocol fields with a byte-length limit.
+///
+/// Unlike filtering strings by `len()`, the strings are built constructively
+/// by first choosing a byte length and then packing characters of 1 to 4
+/// bytes into it, so nothing is ever rejected. Characters are drawn from the
+/// whole of Unicode, with each encoded width being equally likely.
+///
+/// Shrinking removes characters while the string stays at least as long as
+/// the lower bound of `byte_len`, then simplifies the remaining characters
+/// without changing their encoded width.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::string::utf8_with_byte_len;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn fits_in_field(s in utf8_with_byte_len(1..=16)) {
+///         prop_assert!((1..=16).contains(&s.len()));
+///     }
+/// }
+/// # fn main() { fits_in_field(); }
+/// ```
+pub fn utf8_with_byte_len(byte_len: impl Into<SizeRange>) -> Utf8WithByteLen {
+    Utf8WithByteLen {
+        byte_len: byte_len.into(),
+    }
+}
+
+/// The number of characters whose UTF-8 encoding is `width` bytes long.
+fn utf8_width_count(width: usize) -> u32 {
+    match width {
+        1 => 0x80,
+        2 => 0x800 - 0x80,
+        3 => 0x1_0000 - 0x800 - 0x800,
+        _ => 0x11_0000 - 0x1_0000,
+    }
+}
+
+/// The `offset`th character encoded with `width` bytes, starting at `a` for
+/// one-byte characters and at the lowest code point otherwise.
+fn utf8_char_of_width(width: usize, offset: u32) -> char {
+    let code = match width {
+        1 => (u32::from(b'a') + offset) % 0x80,
+        2 => 0x80 + offset,
+        3 if 0x800 + offset < 0xD800 => 0x800 + offset,
+        // Skip the surrogates.
+        3 => 0x1000 + offset,
+        _ => 0x1_0000 + offset,
+    };
+    char::from_u32(code).expect("offset out of range for width")
+}
+
+impl Strategy for Utf8WithByteLen {
+    type Tree = Utf8WithByteLenValueTree;
+    type Value = String;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (min, max) = self.byte_len.start_end_incl();
+        let byte_len = crate::num::sample_uniform_incl(runner, min, max);
+        let mut remaining = runner.budget().draw_size(min, byte_len);
+
+        let mut chars = Vec::new();
+        while remaining > 0 {
+            let width =
+                crate::num::sample_uniform_incl(runner, 1, remaining.min(4));
+            let offset =
+                crate::num::sample_uniform(runner, 0, utf8_width_count(width));
+            chars.push((width, crate::num::u32::BinarySearch::new(offset)));
+            remaining -= width;
+        }
+
+        Ok(Utf8WithByteLenValueTree {
+            included: vec![true; chars.len()],
+            byte_len: chars.iter().map(|&(width, _)| width).sum(),
+            chars,
+            min_byte_len: min,
+            shrink: Utf8Shrink::DeleteChar(0),
+            prev_shrink: None,
+        })
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        let (start, end) = self.byte_len.start_end_incl();
+        (start, Some(end))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Utf8Shrink {
+    DeleteChar(usize),
+    ShrinkChar(usize),
+}
+
+/// `ValueTree` corresponding to `Utf8WithByteLen`.
+#[derive(Clone, Debug)]
+pub struct Utf8WithByteLenValueTree {
+    chars: Vec<(usize, crate::num::u32::BinarySearch)>,
+    included: Vec<bool>,
+    byte_len: usize,
+    min_byte_len: usize,
+    shrink: Utf8Shrink,
+    prev_shrink: Option<Utf8Shrink>,
+}
+
+impl ValueTree for Utf8WithByteLenValueTree {
+    type Value = String;
+
+    fn current(&self) -> String {
+        let mut text = String::with_capacity(self.byte_len);
+        text.extend(
+            self.chars
+                .iter()
+                .zip(&self.included)
+                .filter(|&(_, &included)| included)
+                .map(|(&(width, ref offset), _)| {
+                    utf8_char_of_width(width, offset.current())
+                }),
+        );
+        text
+    }
+
+    fn simplify(&mut self) -> bool {
+        // As for `VecValueTree`, first delete characters, then shrink the
+        // remaining ones, except that deletions must keep the string within
+        // the minimum byte length.
+        while let Utf8Shrink::DeleteChar(ix) = self.shrink {
+            if ix >= self.chars.len() {
+                self.shrink = Utf8Shrink::ShrinkChar(0);
+            } else if self.byte_len - self.chars[ix].0 < self.min_byte_len {
+                self.shrink = Utf8Shrink::DeleteChar(ix + 1);
+            } else {
+                self.included[ix] = false;
+                self.byte_len -= self.chars[ix].0;
+                self.prev_shrink = Some(self.shrink);
+                self.shrink = Utf8Shrink::DeleteChar(ix + 1);
+                return true;
+            }
+        }
+
+        while let Utf8Shrink::ShrinkChar(ix) = self.shrink {
+            if ix >= self.chars.len() {
+                return false;
+            }
+
+            if self.included[ix] && self.chars[ix].1.simplify() {
+                self.prev_shrink = Some(self.shrink);
+                return true;
+            }
+
+            self.shrink = Utf8Shrink::ShrinkChar(ix + 1);
+        }
+
+        unreachable!()
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev_shrink {
+            None => false,
+            Some(Utf8Shrink::DeleteChar(ix)) => {
+                self.included[ix] = true;
+                self.byte_len += self.chars[ix].0;
+                self.prev_shrink = None;
+                true
+            }
+            Some(Utf8Shrink::ShrinkChar(ix)) => {
+                if self.chars[ix].1.complicate() {
+                    true
+                } else {
+                    self.prev_shrink = None;
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// Strategy returned by `rust_identifier()`, `c_identifier()` and
+/// `sql_identifier()`.
+///
+/// The keywords the identifiers must avoid can be adjusted with `avoiding()`
+/// and `with_keywords()`, and their length with `with_len()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Identifier {
+    first: &'static str,
+    rest: &'static str,
+    len: SizeRange,
+    keywords: Arc<[Cow<'static, str>]>,
+    ignore_case: bool,
+}
+
+impl Identifier {
+    fn new(
+        first: &'static str,
+        rest: &'static str,
+        keywords: impl Iterator<Item = &'static str>,
+        ignore_case: bool,
+    ) -> Self {
+        Identifier {
+            first,
+            rest,
+            len: size_range(1..=16),
+            keywords: keywords.map(Cow::Borrowed).collect(),
+            ignore_case,
+        }
+    }
+
+    /// Sets the length of the generated identifiers, which is `1..=16` by
+    /// default.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `len` allows empty identifiers.
+    pub fn with_len(mut self, len: impl Into<SizeRange>) -> Self {
+        let len = len.into();
+        assert!(len.start() > 0, "identifiers cannot be empty");
+        self.len = len;
+        self
+    }
+
+    /// Additionally avoids generating any of `words`, e.g. the names of
+    /// builtin types or functions of the generated code.
+    pub fn avoiding<W: Into<Cow<'static, str>>>(
+        mut self,
+        words: impl IntoIterator<Item = W>,
+    ) -> Self {
+        let mut keywords = self.keywords.to_vec();
+        keywords.extend(words.into_iter().map(Into::into));
+        self.keywords = keywords.into();
+        self
+    }
+
+    /// Replaces the keywords to avoid with `words`.
+    pub fn with_keywords<W: Into<Cow<'static, str>>>(
+        mut self,
+        words: impl IntoIterator<Item = W>,
+    ) -> Self {
+        self.keywords = words.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Returns whether `name` is one of the keywords this strategy avoids.
+    pub fn is_keyword(&self, name: &str) -> bool {
+        is_keyword(&self.keywords, self.ignore_case, name)
+    }
+}
+
+fn is_keyword(
+    keywords: &[Cow<'static, str>],
+    ignore_case: bool,
+    name: &str,
+) -> bool {
+    keywords.iter().any(|word| {
+        if ignore_case {
+            word.eq_ignore_ascii_case(name)
+        } else {
+            word == name
+        }
+    })
+}
+
+impl Strategy for Identifier {
+    type Tree = IdentifierValueTree;
+    type Value = String;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (min, max) = self.len.start_end_incl();
+        let chars = (
+            0..self.first.len(),
+            vec(0..self.rest.len(), min - 1..=max - 1),
+        );
+
+        loop {
+            let tree = IdentifierValueTree {
+                chars: chars.new_tree(runner)?,
+                first: self.first,
+                rest: self.rest,
+                keywords: Arc::clone(&self.keywords),
+                ignore_case: self.ignore_case,
+            };
+            if tree.is_acceptable() {
+                return Ok(tree);
+            }
+            runner.reject_local("generated identifier is a keyword")?;
+        }
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        let (start, end) = self.len.start_end_incl();
+        (start, Some(end))
+    }
+}
+
+/// `ValueTree` corresponding to `Identifier`.
+#[derive(Clone, Debug)]
+pub struct IdentifierValueTree {
+    chars: TupleValueTree<(
+        crate::num::usize::BinarySearch,
+        VecValueTree<crate::num::usize::BinarySearch>,
+    )>,
+    first: &'static str,
+    rest: &'static str,
+    keywords: Arc<[Cow<'static, str>]>,
+    ignore_case: bool,
+}
+
+impl IdentifierValueTree {
+    fn is_acceptable(&self) -> bool {
+        !is_keyword(&self.keywords, self.ignore_case, &self.current())
+    }
+
+    fn ensure_acceptable(&mut self) {
+        // As for `Filter`, the last identifier that was not a keyword is
+        // always reachable by complicating.
+        while !self.is_acceptable() {
+            if !self.chars.complicate() {
+                panic!("Unable to complicate identifier back into non-keyword");
+            }
+        }
+    }
+}
+
+impl ValueTree for IdentifierValueTree {
+    type Value = String;
+
+    fn current(&self) -> String {
+        let (first, rest) = self.chars.current();
+        let mut name = String::with_capacity(1 + rest.len());
+        name.push(char::from(self.first.as_bytes()[first]));
+        name.extend(
+            rest.into_iter()
+                .map(|ix| char::from(self.rest.as_bytes()[ix])),
+        );
+        name
+    }
+
+    fn simplify(&mut self) -> bool {
+        if self.chars.simplify() {
+            self.ensure_acceptable();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn complicate(&mut self) -> bool {
+        if self.chars.complicate() {
+            self.ensure_acceptable();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+// The alphabets are ordered so that identifiers shrink towards lowercase
+// letters.
+const LOWER_FIRST: &str = "abcdefghijklmnopqrstuvwxyz_";
+const LOWER_REST: &str = "abcdefghijklmnopqrstuvwxyz0123456789_";
+const MIXED_FIRST: &str =
+    "abcdefghijklmnopqrstuvwxyz_ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const MIXED_REST: &str =
+    "abcdefghijklmnopqrstuvwxyz0123456789_ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// The keywords are separated by whitespace.
+
+/// The strict and reserved keywords of all editions of Rust, as well as `_`,
+/// which on its own is a pattern rather than an identifier.
+const RUST_KEYWORDS: &str = "\
+    _ as async await break const continue crate dyn else enum extern false fn \
+    for if impl in let loop match mod move mut pub ref return self Self \
+    static struct super trait true type unsafe use where while abstract \
+    become box do final gen macro override priv try typeof unsized virtual \
+    yield";
+
+/// The keywords of C23 and earlier standards, and those of C++ so that the
+/// identifiers also work in headers shared with C++. Keywords starting with an
+/// underscore, such as `_Bool`, are left out since `c_identifier()` never
+/// generates such names.
+const C_KEYWORDS: &str = "\
+    alignas alignof auto bool break case char const constexpr continue \
+    default do double else enum extern false float for goto if inline int \
+    long nullptr register restrict return short signed sizeof static \
+    static_assert struct switch thread_local true typedef typeof \
+    typeof_unqual union unsigned void volatile while and and_eq asm bitand \
+    bitor catch char8_t char16_t char32_t class co_await co_return co_yield \
+    compl concept consteval constinit const_cast decltype delete dynamic_cast \
+    explicit export friend mutable namespace new noexcept not not_eq operator \
+    or or_eq private protected public reinterpret_cast requires static_cast \
+    template this throw try typeid typename using virtual wchar_t xor xor_eq";
+
+/// The reserved words of PostgreSQL.
+const POSTGRES_KEYWORDS: &str = "\
+    ALL ANALYSE ANALYZE AND ANY ARRAY AS ASC ASYMMETRIC AUTHORIZATION BINARY \
+    BOTH CASE CAST CHECK COLLATE COLLATION COLUMN CONCURRENTLY CONSTRAINT \
+    CREATE CROSS CURRENT_CATALOG CURRENT_DATE CURRENT_ROLE CURRENT_SCHEMA \
+    CURRENT_TIME CURRENT_TIMESTAMP CURRENT_USER DEFAULT DEFERRABLE DESC \
+    DISTINCT DO ELSE END EXCEPT FALSE FETCH FOR FOREIGN FREEZE FROM FULL \
+    GRANT GROUP HAVING ILIKE IN INITIALLY INNER INTERSECT INTO IS ISNULL JOIN \
+    LATERAL LEADING LEFT LIKE LIMIT LOCALTIME LOCALTIMESTAMP NATURAL NOT \
+    NOTNULL NULL OFFSET ON ONLY OR ORDER OUTER OVERLAPS PLACING PRIMARY \
+    REFERENCES RETURNING RIGHT SELECT SESSION_USER SIMILAR SOME SYMMETRIC \
+    SYSTEM_USER TABLE TABLESAMPLE THEN TO TRAILING TRUE UNION UNIQUE USER \
+    USING VARIADIC VERBOSE WHEN WHERE WINDOW WITH";
+
+/// The reserved words of MySQL.
+const MYSQL_KEYWORDS: &str = "\
+    ACCESSIBLE ADD ALL ALTER ANALYZE AND AS ASC ASENSITIVE BEFORE BETWEEN \
+    BIGINT BINARY BLOB BOTH BY CALL CASCADE CASE CHANGE CHAR CHARACTER CHECK \
+    COLLATE COLUMN CONDITION CONSTRAINT CONTINUE CONVERT CREATE CROSS CUBE \
+    CUME_DIST CURRENT_DATE CURRENT_TIME CURRENT_TIMESTAMP CURRENT_USER CURSOR \
+    DATABASE DATABASES DAY_HOUR DAY_MICROSECOND DAY_MINUTE DAY_SECOND DEC \
+    DECIMAL DECLARE DEFAULT DELAYED DELETE DENSE_RANK DESC DESCRIBE \
+    DETERMINISTIC DISTINCT DISTINCTROW DIV DOUBLE DROP DUAL EACH ELSE ELSEIF \
+    EMPTY ENCLOSED ESCAPED EXCEPT EXISTS EXIT EXPLAIN FALSE FETCH FIRST_VALUE \
+    FLOAT FLOAT4 FLOAT8 FOR FORCE FOREIGN FROM FULLTEXT FUNCTION GENERATED \
+    GET GRANT GROUP GROUPING GROUPS HAVING HIGH_PRIORITY HOUR_MICROSECOND \
+    HOUR_MINUTE HOUR_SECOND IF IGNORE IN INDEX INFILE INNER INOUT INSENSITIVE \
+    INSERT INT INT1 INT2 INT3 INT4 INT8 INTEGER INTERSECT INTERVAL INTO \
+    IO_AFTER_GTIDS IO_BEFORE_GTIDS IS ITERATE JOIN JSON_TABLE KEY KEYS KILL \
+    LAG LAST_VALUE LATERAL LEAD LEADING LEAVE LEFT LIKE LIMIT LINEAR LINES \
+    LOAD LOCALTIME LOCALTIMESTAMP LOCK LONG LONGBLOB LONGTEXT LOOP \
+    LOW_PRIORITY MASTER_BIND MASTER_SSL_VERIFY_SERVER_CERT MATCH MAXVALUE \
+    MEDIUMBLOB MEDIUMINT MEDIUMTEXT MIDDLEINT MINUTE_MICROSECOND \
+    MINUTE_SECOND MOD MODIFIES NATURAL NOT NO_WRITE_TO_BINLOG NTH_VALUE NTILE \
+    NULL NUMERIC OF ON OPTIMIZE OPTIMIZER_COSTS OPTION OPTIONALLY OR ORDER \
+    OUT OUTER OUTFILE OVER PARTITION PERCENT_RANK PRECISION PRIMARY PROCEDURE \
+    PURGE RANGE RANK READ READS READ_WRITE REAL RECURSIVE REFERENCES REGEXP \
+    RELEASE RENAME REPEAT REPLACE REQUIRE RESIGNAL RESTRICT RETURN REVOKE \
+    RIGHT RLIKE ROW ROWS ROW_NUMBER SCHEMA SCHEMAS SECOND_MICROSECOND SELECT \
+    SENSITIVE SEPARATOR SET SHOW SIGNAL SMALLINT SPATIAL SPECIFIC SQL \
+    SQLEXCEPTION SQLSTATE SQLWARNING SQL_BIG_RESULT SQL_CALC_FOUND_ROWS \
+    SQL_SMALL_RESULT SSL STARTING STORED STRAIGHT_JOIN SYSTEM TABLE \
+    TERMINATED THEN TINYBLOB TINYINT TINYTEXT TO TRAILING TRIGGER TRUE UNDO \
+    UNION UNIQUE UNLOCK UNSIGNED UPDATE USAGE USE USING UTC_DATE UTC_TIME \
+    UTC_TIMESTAMP VALUES VARBINARY VARCHAR VARCHARACTER VARYING VIRTUAL WHEN \
+    WHERE WHILE WINDOW WITH WRITE XOR YEAR_MONTH ZEROFILL";
+
+/// The keywords of SQLite.
+const SQLITE_KEYWORDS: &str = "\
+    ABORT ACTION ADD AFTER ALL ALTER ALWAYS ANALYZE AND AS ASC ATTACH \
+    AUTOINCREMENT BEFORE BEGIN BETWEEN BY CASCADE CASE CAST CHECK COLLATE \
+    COLUMN COMMIT CONFLICT CONSTRAINT CREATE CROSS CURRENT CURRENT_DATE \
+    CURRENT_TIME CURRENT_TIMESTAMP DATABASE DEFAULT DEFERRABLE DEFERRED \
+    DELETE DESC DETACH DISTINCT DO DROP EACH ELSE END ESCAPE EXCEPT EXCLUDE \
+    EXCLUSIVE EXISTS EXPLAIN FAIL FILTER FIRST FOLLOWING FOR FOREIGN FROM \
+    FULL GENERATED GLOB GROUP GROUPS HAVING IF IGNORE IMMEDIATE IN INDEX \
+    INDEXED INITIALLY INNER INSERT INSTEAD INTERSECT INTO IS ISNULL JOIN KEY \
+    LAST LEFT LIKE LIMIT MATCH MATERIALIZED NATURAL NO NOT NOTHING NOTNULL \
+    NULL NULLS OF OFFSET ON OR ORDER OTHERS OUTER OVER PARTITION PLAN PRAGMA \
+    PRECEDING PRIMARY QUERY RAISE RANGE RECURSIVE REFERENCES REGEXP REINDEX \
+    RELEASE RENAME REPLACE RESTRICT RETURNING RIGHT ROLLBACK ROW ROWS \
+    SAVEPOINT SELECT SET TABLE TEMP TEMPORARY THEN TIES TO TRANSACTION \
+    TRIGGER UNBOUNDED UNION UNIQUE UPDATE USING VACUUM VALUES VIEW VIRTUAL \
+    WHEN WHERE WINDOW WITH WITHOUT";
+
+/// Creates a strategy which generates Rust identifiers, such as names of
+/// variables, functions or types in generated code.
+///
+/// The identifiers consist of ASCII letters, digits and underscores, and
+/// never start with a digit or are a keyword of any edition, so they can be
+/// used without the `r#` prefix. They shrink towards short lowercase names.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::string::rust_identifier;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn emits_valid_bindings(name in rust_identifier().avoiding(["main"])) {
+///         let code = format!("let {} = 1;", name);
+///         prop_assert!(code.starts_with("let "));
+///     }
+/// }
+/// # fn main() { emits_valid_bindings(); }
+/// ```
+pub fn rust_identifier() -> Identifier {
+    Identifier::new(
+        MIXED_FIRST,
+        MIXED_REST,
+        RUST_KEYWORDS.split_whitespace(),
+        false,
+    )
+}
+
+/// Creates a strategy which generates C identifiers.
+///
+/// The identifiers consist of ASCII letters, digits and underscores, and
+/// never start with a digit or are a keyword of C or C++. Since names starting
+/// with an underscore are reserved to the implementation in various contexts,
+/// those are never generated either. They shrink towards short lowercase
+/// names.
+pub fn c_identifier() -> Identifier {
+    Identifier::new(LETTERS, MIXED_REST, C_KEYWORDS.split_whitespace(), false)
+}
+
+/// The SQL dialects supported by `sql_identifier()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SqlDialect {
+    /// PostgreSQL.
+    Postgres,
+    /// MySQL.
+    MySql,
+    /// SQLite.
+    Sqlite,
+}
+
+impl SqlDialect {
+    /// The reserved words of this dialect in uppercase, which
+    /// `sql_identifier()` avoids.
+    pub fn keywords(self) -> impl Iterator<Item = &'static str> {
+        match self {
+            SqlDialect::Postgres => POSTGRES_KEYWORDS,
+            SqlDialect::MySql => MYSQL_KEYWORDS,
+            SqlDialect::Sqlite => SQLITE_KEYWORDS,
+        }
+        .split_whitespace()
+    }
+}
+
+/// Creates a strategy which generates unquoted SQL identifiers valid in
+/// `dialect`, such as table or column names.
+///
+/// The identifiers consist of lowercase ASCII letters, digits and
+/// underscores, and never start with a digit or are one of the reserved
+/// words of `dialect`, compared case-insensitively. They are lowercase since
+/// dialects disagree on how the case of unquoted identifiers is folded. They
+/// shrink towards short names.
+pub fn sql_identifier(dialect: SqlDialect) -> Identifier {
+    Identifier::new(LOWER_FIRST, LOWER_REST, dialect.keywords(), true)
+}
+
+/// Parameters of the numbers generated by `decimal_number()`.
+///
+/// The default generates numbers like `-1234.5`: an optional minus sign, 1 to
+/// 10 integer digits without leading zeros, and up to 6 fraction digits after
+/// a `.`, with no exponent or digit grouping.
+#[derive(Clone, Debug)]
+pub struct DecimalParams {
+    /// The number of integer digits, which must be at least one. Unless
+    /// `leading_zeros` is set, leading zeros are removed, so the number may
+    /// end up with fewer integer digits.
+    pub integer_digits: SizeRange,
+    /// The number of fraction digits. The decimal separator is left out if
+    /// there are none.
+    pub fraction_digits: SizeRange,
+    /// The range of the exponent, which is written after an `e`, or `None`
+    /// for numbers without one.
+    pub exponent: Option<RangeInclusive<i32>>,
+    /// Whether the number may have a leading `-`.
+    pub negative: bool,
+    /// Whether the number may have a leading `+`.
+    pub explicit_plus: bool,
+    /// Whether the integer part may have leading zeros, like in `007`.
+    pub leading_zeros: bool,
+    /// The separator between the integer and the fraction digits, e.g. `,`
+    /// for many European locales.
+    pub decimal_separator: char,
+    /// The separator written between groups of three integer digits, e.g.
+    /// `,` for `1,000,000`, or `None` for no grouping.
+    pub group_separator: Option<char>,
+
+    // Needs to be public for FRU syntax.
+    #[allow(missing_docs)]
+    #[doc(hidden)]
+    pub _non_exhaustive: (),
+}
+
+impl Default for DecimalParams {
+    fn default() -> Self {
+        DecimalParams {
+            integer_digits: (1..=10).into(),
+            fraction_digits: (0..=6).into(),
+            exponent: None,
+            negative: true,
+            explicit_plus: false,
+            leading_zeros: false,
+            decimal_separator: '.',
+            group_separator: None,
+            _non_exhaustive: (),
+        }
+    }
+}
+
+/// A number generated by `decimal_number()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecimalNumber {
+    /// The number as text.
+    pub text: String,
+    /// The value of the number, rounded to the nearest `f64`, or `None` if
+    /// its magnitude is too large for an `f64`.
+    pub value: Option<f64>,
+}
+
+/// Strategy returned by `decimal_number()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct DecimalNumberStrategy {
+    params: Arc<DecimalParams>,
+}
+
+/// Creates a strategy which generates numbers written in decimal, optionally
+/// in scientific notation, along with their values, e.g. for testing number
+/// parsers.
+///
+/// The numbers are built from their digits rather than by formatting floats,
+/// so they can have any number of digits, and are formatted as described by
+/// `params`. The numbers shrink towards `0`, or `0e0` with an exponent.
+///
+/// ## Panics
+///
+/// Panics if `params` allows no integer digits, has an empty exponent range,
+/// or has the same decimal and group separators, or one which is a digit,
+/// a sign or an `e`.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::string::{decimal_number, DecimalParams};
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn parses_like_std(number in decimal_number(DecimalParams {
+///         exponent: Some(-20..=20),
+///         ..DecimalParams::default()
+///     })) {
+///         let parsed: f64 = number.text.parse().unwrap();
+///         prop_assert_eq!(Some(parsed), number.value);
+///     }
+/// }
+/// # fn main() { parses_like_std(); }
+/// ```
+pub fn decimal_number(params: DecimalParams) -> DecimalNumberStrategy {
+    params.integer_digits.assert_nonempty();
+    params.fraction_digits.assert_nonempty();
+    assert!(
+        params.integer_digits.start() >= 1,
+        "decimal_number() requires at least one integer digit"
+    );
+    if let Some(ref exponent) = params.exponent {
+        assert!(!exponent.is_empty(), "Invalid use of empty exponent range");
+    }
+    let is_reserved = |c: char| c.is_ascii_digit() || "+-eE".contains(c);
+    assert!(
+        !is_reserved(params.decimal_separator),
+        "Invalid decimal separator {:?}",
+        params.decimal_separator
+    );
+    if let Some(group) = params.group_separator {
+        assert!(
+            !is_reserved(group) && group != params.decimal_separator,
+            "Invalid group separator {:?}",
+            group
+        );
+    }
+
+    DecimalNumberStrategy {
+        params: Arc::new(params),
+    }
+}
+
+impl DecimalNumberStrategy {
+    /// The signs to choose from, the simplest first.
+    fn signs(&self) -> Vec<&'static str> {
+        let mut signs = vec![""];
+        if self.params.negative {
+            signs.push("-");
+        }
+        if self.params.explicit_plus {
+            signs.push("+");
+        }
+        signs
+    }
+}
+
+impl Strategy for DecimalNumberStrategy {
+    type Tree = DecimalNumberValueTree;
+    type Value = DecimalNumber;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let digit = 0u8..=9;
+        let exponent = self.params.exponent.clone().unwrap_or(0..=0);
+        let parts = (
+            0..self.signs().len(),
+            vec(digit.clone(), self.params.integer_digits.clone()),
+            vec(digit, self.params.fraction_digits.clone()),
+            exponent,
+        );
+
+        Ok(DecimalNumberValueTree {
+            parts: parts.new_tree(runner)?,
+            signs: self.signs(),
+            params: Arc::clone(&self.params),
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `DecimalNumberStrategy`.
+#[derive(Clone, Debug)]
+pub struct DecimalNumberValueTree {
+    parts: TupleValueTree<(
+        crate::num::usize::BinarySearch,
+        VecValueTree<crate::num::u8::BinarySearch>,
+        VecValueTree<crate::num::u8::BinarySearch>,
+        crate::num::i32::BinarySearch,
+    )>,
+    signs: Vec<&'static str>,
+    params: Arc<DecimalParams>,
+}
+
+impl ValueTree for DecimalNumberValueTree {
+    type Value = DecimalNumber;
+
+    fn current(&self) -> DecimalNumber {
+        let (sign, mut integer, fraction, exponent) = self.parts.current();
+        let sign = self.signs[sign];
+        if !self.params.leading_zeros {
+            let zeros = integer.iter().take_while(|&&d| 0 == d).count();
+            integer.drain(..zeros.min(integer.len() - 1));
+        }
+        let digits = |digits: &[u8]| -> String {
+            digits.iter().map(|&d| char::from(b'0' + d)).collect()
+        };
+
+        // The text in the syntax accepted by `f64::from_str()`.
+        let mut plain = format!("{}{}", sign, digits(&integer));
+        let mut text = String::from(sign);
+        for (ix, &d) in integer.iter().enumerate() {
+            let remaining = integer.len() - ix;
+            if let Some(group) = self.params.group_separator {
+                if ix > 0 && 0 == remaining % 3 {
+                    text.push(group);
+                }
+            }
+            text.push(char::from(b'0' + d));
+        }
+        if !fraction.is_empty() {
+            plain.push('.');
+            plain.push_str(&digits(&fraction));
+            text.push(self.params.decimal_separator);
+            text.push_str(&digits(&fraction));
+        }
+        if self.params.exponent.is_some() {
+            let exponent = format!("e{}", exponent);
+            plain.push_str(&exponent);
+            text.push_str(&exponent);
+        }
+
+        let value: f64 = plain.parse().expect("generated number should parse");
+        DecimalNumber {
+            text,
+            value: Some(value).filter(|v| v.is_finite()),
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.parts.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.parts.complicate()
+    }
+}
+
+/// Strategy returned by `tokens()` and `from_dictionary()`.
+///
+/// The number of tokens can be adjusted with `with_count()`, and the text
+/// put between them with `with_separator()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Tokens {
+    dictionary: Arc<[Cow<'static, str>]>,
+    cumulative_weights: Arc<[u64]>,
+    count: SizeRange,
+    separator: Cow<'static, str>,
+}
+
+impl Tokens {
+    /// Sets the number of tokens in the generated strings, which is `0..=8`
+    /// by default.
+    pub fn with_count(mut self, count: impl Into<SizeRange>) -> Self {
+        self.count = count.into();
+        self.count.assert_nonempty();
+        self
+    }
+
+    /// Sets the text put between adjacent tokens, which is empty by default.
+    pub fn with_separator(
+        mut self,
+        separator: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.separator = separator.into();
+        self
+    }
+}
+
+/// Creates a strategy which generates strings by concatenating tokens drawn
+/// from `dictionary`, each with a probability proportional to its weight.
+///
+/// This is useful for testing parsers with inputs made of their keywords,
+/// operators or other fragments, where a regex for the whole input would be
+/// awkward. The strings shrink towards fewer tokens, and each token towards
+/// those earlier in `dictionary` (regardless of weight), so put the simplest
+/// tokens first.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::string::tokens;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn parses_queries(
+///         query in tokens([
+///             (1, "x"), (1, "1"), (4, "SELECT"), (2, "FROM"), (2, "WHERE"),
+///             (1, "="), (1, "'it''s'"),
+///         ])
+///         .with_count(1..16)
+///         .with_separator(" ")
+///     ) {
+///         prop_assert!(!query.starts_with(' '));
+///     }
+/// }
+/// # fn main() { parses_queries(); }
+/// ```
+///
+/// ## Panics
+///
+/// Panics if `dictionary` is empty or all weights are 0.
+pub fn tokens<W: Into<Cow<'static, str>>>(
+    dictionary: impl IntoIterator<Item = (u32, W)>,
+) -> Tokens {
+    let mut total = 0u64;
+    let (cumulative_weights, dictionary): (Vec<u64>, Vec<_>) = dictionary
+        .into_iter()
+        .map(|(weight, token)| {
+            total += u64::from(weight);
+            (total, token.into())
+        })
+        .unzip();
+    assert!(total > 0, "tokens() needs a token with a positive weight");
+
+    Tokens {
+        dictionary: dictionary.into(),
+        cumulative_weights: cumulative_weights.into(),
+        count: size_range(0..=8),
+        separator: Cow::Borrowed(""),
+    }
+}
+
+/// Like `tokens()`, but draws every token of `dictionary` with the same
+/// probability.
+///
+/// ## Panics
+///
+/// Panics if `dictionary` is empty.
+pub fn from_dictionary<W: Into<Cow<'static, str>>>(
+    dictionary: impl IntoIterator<Item = W>,
+) -> Tokens {
+    tokens(dictionary.into_iter().map(|token| (1, token)))
+}
+
+/// Draws the index of a token from its cumulative weights.
+#[derive(Clone, Debug)]
+struct TokenIndex(Arc<[u64]>);
+
+impl Strategy for TokenIndex {
+    type Tree = crate::num::usize::BinarySearch;
+    type Value = usize;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        use rand::Rng;
+
+        let total = self.0[self.0.len() - 1];
+        let weight = runner.rng().gen_range(0..total);
+        let ix = self.0.partition_point(|&cumulative| cumulative <= weight);
+        Ok(crate::num::usize::BinarySearch::new(ix))
+    }
+}
+
+impl Strategy for Tokens {
+    type Tree = TokensValueTree;
+    type Value = String;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let indices = vec(
+            TokenIndex(Arc::clone(&self.cumulative_weights)),
+            self.count.clone(),
+        );
+        Ok(TokensValueTree {
+            indices: indices.new_tree(runner)?,
+            dictionary: Arc::clone(&self.dictionary),
+            separator: self.separator.clone(),
+        })
+    }
+
+    fn value_size_hint(&self) -> (usize, Option<usize>) {
+        let (min_count, max_count) = self.count.start_end_incl();
+        let token_lens = self.dictionary.iter().map(|token| token.len());
+        let min_len = token_lens.clone().min().unwrap_or(0);
+        let max_len = token_lens.max().unwrap_or(0);
+        let separators = |count: usize| count.saturating_sub(1);
+
+        let min = min_count
+            .saturating_mul(min_len)
+            .saturating_add(separators(min_count) * self.separator.len());
+        let max = max_count.checked_mul(max_len).and_then(|len| {
+            separators(max_count)
+                .checked_mul(self.separator.len())
+                .and_then(|seps| len.checked_add(seps))
+        });
+        (min, max)
+    }
+}
+
+/// `ValueTree` corresponding to `Tokens`.
+#[derive(Clone, Debug)]
+pub struct TokensValueTree {
+    indices: VecValueTree<crate::num::usize::BinarySearch>,
+    dictionary: Arc<[Cow<'static, str>]>,
+    separator: Cow<'static, str>,
+}
+
+impl ValueTree for TokensValueTree {
+    type Value = String;
+
+    fn current(&self) -> String {
+        let indices = self.indices.current();
+        let len = indices
+            .iter()
+            .map(|&ix| self.dictionary[ix].len())
+            .sum::<usize>()
+            + indices.len().saturating_sub(1) * self.separator.len();
+
+        let mut text = String::with_capacity(len);
+        for (n, ix) in indices.into_iter().enumerate() {
+            if n > 0 {
+                text.push_str(&self.separator);
+            }
+            text.push_str(&self.dictionary[ix]);
+        }
+        text
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.indices.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.indices.complicate()
+    }
+}
+
+/// Canonically equivalent encodings of characters which don't differ only by
+/// decomposition, as pairs of the NFC form and its singleton alternative.
+#[cfg(feature = "unicode-normalization")]
+const CANONICAL_SINGLETONS: &[(char, char)] = &[
+    ('K', '\u{212A}'),       // KELVIN SIGN
+    ('\u{C5}', '\u{212B}'),  // ANGSTROM SIGN
+    ('\u{3A9}', '\u{2126}'), // OHM SIGN
+    (';', '\u{37E}'),        // GREEK QUESTION MARK
+    ('`', '\u{1FEF}'),       // GREEK VARIA
+    ('\u{B7}', '\u{387}'),   // GREEK ANO TELEIA
+];
+
+/// Backs `Strategy::prop_nfc()`.
+#[cfg(feature = "unicode-normalization")]
+pub(crate) fn to_nfc(s: String) -> String {
+    use unicode_normalization::{
+        is_nfc_quick, IsNormalized, UnicodeNormalization,
+    };
+
+    if IsNormalized::Yes == is_nfc_quick(s.chars()) {
+        s
+    } else {
+        s.nfc().collect()
+    }
+}
+
+/// Backs `Strategy::prop_casefold()`.
+///
+/// Upper-casing before lower-casing maps characters like `ß` and `ﬀ` to
+/// what full case folding maps them to, which lower-casing alone doesn't.
+#[cfg(feature = "unicode-normalization")]
+pub(crate) fn casefold(s: String) -> String {
+    s.to_uppercase().to_lowercase()
+}
+
+/// Backs `Strategy::prop_with_confusables()`.
+///
+/// Each character of the NFC form of `original` is re-encoded as selected by
+/// the next two bits of `choices`, cycling through them for longer strings.
+/// A zero `choices` keeps every character as is.
+#[cfg(feature = "unicode-normalization")]
+pub(crate) fn with_confusable(
+    (original, choices): (String, u64),
+) -> (String, String) {
+    use unicode_normalization::char::decompose_canonical;
+
+    let mut variant = String::with_capacity(original.len() * 2);
+    for (ix, ch) in to_nfc(original.clone()).chars().enumerate() {
+        let singleton = CANONICAL_SINGLETONS
+            .iter()
+            .find(|&&(nfc, _)| nfc == ch)
+            .map(|&(_, alt)| alt);
+        match (choices >> (ix % 32 * 2) & 3, singleton) {
+            (0, _) => variant.push(ch),
+            (2, Some(alt)) => variant.push(alt),
+            _ => decompose_canonical(ch, |c| variant.push(c)),
+        }
+    }
+
+    (original, variant)
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
@@ -590,6 +1898,50 @@ mod test {
         | \xb0\x80 | \xbe\x80 | \xbf\xbf )"#, 15, 15, 120);
     }
 
+    #[test]
+    fn anchored_regexes_generate_matching_strings() {
+        let mut runner = TestRunner::deterministic();
+        for pattern in [
+            r"^[a-z]+$",
+            r"\A\d{3}\z",
+            r"(?m)^abc$",
+            r"^foo|bar$",
+            r"^\w+\b-\d{2}$",
+            r"\bword\b",
+            r"^(?:\d+\B\d|x\b)$",
+            r"(?-u:^\b[a-z]+\b \x7F)",
+        ] {
+            let strategy = string_regex_anchored(pattern).unwrap();
+            let rx = Regex::new(pattern).unwrap();
+            for _ in 0..256 {
+                let s = strategy.new_tree(&mut runner).unwrap().current();
+                assert!(rx.is_match(&s), "{:?} !~ {:?}", s, pattern);
+            }
+        }
+
+        assert!(bytes_regex_anchored(r"(?-u)^\xFF+$").is_ok());
+        assert!(string_regex(r"^[a-z]+$").is_err());
+    }
+
+    #[test]
+    fn anchored_regexes_reject_assertions_which_may_not_hold() {
+        for pattern in [
+            r"a^b",
+            r"a$b",
+            r"(?:^a)+",
+            r"\w\b\w",
+            r"-\B\d",
+            r".\b.",
+            r"\d*\b-",
+        ] {
+            assert!(
+                string_regex_anchored(pattern).is_err(),
+                "{:?} was accepted",
+                pattern
+            );
+        }
+    }
+
     fn assert_send_and_sync<T: Send + Sync>(_: T) {}
 
     #[test]
@@ -641,5 +1993,276 @@ mod test {
         }
     }
 
+    #[test]
+    fn utf8_with_byte_len_stays_in_bounds_while_shrinking() {
+        let mut runner = TestRunner::deterministic();
+        let input = utf8_with_byte_len(5..=12);
+        let mut widths = HashSet::new();
+
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            widths.extend(tree.current().chars().map(char::len_utf8));
+            loop {
+                let len = tree.current().len();
+                assert!((5..=12).contains(&len), "bad length {}", len);
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert!(tree.current().len() < 9);
+        }
+
+        assert_eq!(4, widths.len());
+    }
+
+    #[test]
+    fn utf8_with_byte_len_shrinks_chars_within_their_width() {
+        let mut runner = TestRunner::deterministic();
+        let input = utf8_with_byte_len(3..=40);
+
+        for _ in 0..64 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+
+            let s = tree.current();
+            assert!((3..7).contains(&s.len()), "{:?}", s);
+            assert!(
+                s.chars().all(|c| "a\u{80}\u{800}\u{10000}".contains(c)),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn utf8_char_of_width_covers_each_width_exactly() {
+        for width in 1..=4 {
+            let count = utf8_width_count(width);
+            for offset in [0, 1, count / 2, count - 1] {
+                let c = utf8_char_of_width(width, offset);
+                assert_eq!(width, c.len_utf8(), "{:?}", c);
+            }
+        }
+        assert_eq!('\u{D7FF}', utf8_char_of_width(3, 0xD7FF - 0x800));
+        assert_eq!('\u{E000}', utf8_char_of_width(3, 0xD800 - 0x800));
+    }
+
+    #[test]
+    fn identifiers_are_valid_and_avoid_keywords() {
+        let mut runner = TestRunner::deterministic();
+        let inputs = [
+            ("rust", rust_identifier()),
+            ("c", c_identifier()),
+            ("postgres", sql_identifier(SqlDialect::Postgres)),
+            ("mysql", sql_identifier(SqlDialect::MySql)),
+            ("sqlite", sql_identifier(SqlDialect::Sqlite).with_len(1..=3)),
+        ];
+
+        for (language, input) in &inputs {
+            for _ in 0..1024 {
+                let mut tree = input.new_tree(&mut runner).unwrap();
+                loop {
+                    let name = tree.current();
+                    assert!(!input.is_keyword(&name), "{}: {}", language, name);
+                    assert!((1..=16).contains(&name.len()));
+                    assert!(!name.starts_with(|c: char| c.is_ascii_digit()));
+                    // Leading underscores are reserved in C.
+                    assert!("c" != *language || !name.starts_with('_'));
+                    assert!(name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || '_' == c));
+                    if !tree.simplify() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        assert!(rust_identifier().is_keyword("_"));
+        assert!(!rust_identifier().is_keyword("SELF"));
+        assert!(sql_identifier(SqlDialect::Postgres).is_keyword("select"));
+    }
+
+    #[test]
+    fn identifiers_shrink_to_short_lowercase_names() {
+        let input = rust_identifier().with_len(3..=20).avoiding(["aaa", "aab"]);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            let name = tree.current();
+            assert_eq!(3, name.len());
+            assert!(name.starts_with('a') && !input.is_keyword(&name));
+            assert!(name.chars().all(|c| c.is_ascii_lowercase()), "{}", name);
+        }
+
+        let input = rust_identifier().with_keywords(["a"]);
+        let mut tree = input.new_tree(&mut runner).unwrap();
+        while tree.simplify() {}
+        assert_eq!("b", tree.current());
+    }
+
+    #[test]
+    fn decimal_numbers_have_their_value() {
+        let mut runner = TestRunner::deterministic();
+        let params = DecimalParams {
+            integer_digits: (1..=40).into(),
+            exponent: Some(-400..=400),
+            explicit_plus: true,
+            ..DecimalParams::default()
+        };
+        let input = decimal_number(params);
+
+        let mut saw_overflow = false;
+        for _ in 0..1024 {
+            let number = input.new_tree(&mut runner).unwrap().current();
+            let parsed: f64 = number.text.parse().unwrap();
+            match number.value {
+                Some(value) => assert_eq!(parsed, value, "{}", number.text),
+                None => {
+                    assert!(parsed.is_infinite(), "{}", number.text);
+                    saw_overflow = true;
+                }
+            }
+            let integer = number.text.trim_start_matches(['-', '+']);
+            assert!(!integer.starts_with('0') || integer.starts_with("0."));
+        }
+        assert!(saw_overflow);
+    }
+
+    #[test]
+    fn decimal_numbers_use_locale_separators() {
+        let mut runner = TestRunner::deterministic();
+        let params = DecimalParams {
+            integer_digits: (4..=12).into(),
+            fraction_digits: (1..=3).into(),
+            leading_zeros: true,
+            decimal_separator: ',',
+            group_separator: Some('.'),
+            ..DecimalParams::default()
+        };
+        let input = decimal_number(params);
+        let grouped = Regex::new(r"^-?\d{1,3}(\.\d{3})+,\d{1,3}$").unwrap();
+
+        for _ in 0..256 {
+            let number = input.new_tree(&mut runner).unwrap().current();
+            assert!(grouped.is_match(&number.text), "{}", number.text);
+            let plain = number.text.replace('.', "").replace(',', ".");
+            assert_eq!(Some(plain.parse().unwrap()), number.value);
+        }
+    }
+
+    #[test]
+    fn decimal_numbers_shrink_to_zero() {
+        let mut runner = TestRunner::deterministic();
+        for (exponent, zero) in [(None, "0"), (Some(-3..=3), "0e0")] {
+            let params = DecimalParams {
+                exponent,
+                ..DecimalParams::default()
+            };
+            let mut tree =
+                decimal_number(params).new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!(zero, tree.current().text);
+            assert_eq!(Some(0.0), tree.current().value);
+        }
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn nfc_and_casefold_map_equivalent_strings_together() {
+        use unicode_normalization::is_nfc;
+
+        let mut runner = TestRunner::deterministic();
+        let input = "[a-zA-Zß\u{e9}\u{301}\u{212a}]{0,16}";
+        for _ in 0..256 {
+            let nfc = input.prop_nfc().new_tree(&mut runner).unwrap();
+            assert!(is_nfc(&nfc.current()), "{:?}", nfc.current());
+        }
+
+        assert_eq!("\u{e9}", to_nfc("e\u{301}".to_owned()));
+        assert_eq!("strasse", casefold("STRAßE".to_owned()));
+        assert_eq!(
+            casefold("Straße".to_owned()),
+            casefold("STRASSE".to_owned())
+        );
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn confusables_are_canonically_equivalent() {
+        let mut runner = TestRunner::deterministic();
+        let input = "[a-zA-Z\u{e9}\u{c5};]{1,16}".prop_with_confusables();
+        let mut differing = 0;
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let (original, variant) = tree.current();
+            assert_eq!(to_nfc(original.clone()), to_nfc(variant.clone()));
+            if original != variant {
+                differing += 1;
+            }
+
+            while tree.simplify() {}
+            let (original, variant) = tree.current();
+            assert_eq!(to_nfc(original.clone()), variant);
+        }
+        assert!(differing > 200, "only {} pairs differ", differing);
+
+        let (_, variant) = with_confusable(("K\u{e9}".to_owned(), 0b0110));
+        assert_eq!("\u{212a}e\u{301}", variant);
+    }
+
+    #[test]
+    fn tokens_are_drawn_by_weight() {
+        let input = tokens([(0, "never"), (1, "a"), (3, "b")]).with_count(1);
+        let mut runner = TestRunner::deterministic();
+
+        let mut bs = 0;
+        for _ in 0..1024 {
+            let token = input.new_tree(&mut runner).unwrap().current();
+            assert!(token == "a" || token == "b", "{}", token);
+            if token == "b" {
+                bs += 1;
+            }
+        }
+        assert!(bs > 640 && bs < 896, "{} of 1024 tokens were b", bs);
+    }
+
+    #[test]
+    fn tokens_shrink_to_fewer_earlier_tokens() {
+        let input = from_dictionary(["SELECT", "FROM", "WHERE"])
+            .with_count(2..8)
+            .with_separator(" ");
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!("SELECT SELECT", tree.current());
+        }
+    }
+
+    #[test]
+    fn tokens_value_size_hint_bounds_byte_len() {
+        let input = tokens([(1, "a"), (2, "bc"), (3, "def")])
+            .with_count(1..4)
+            .with_separator(", ");
+        assert_eq!((1, Some(13)), input.value_size_hint());
+
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..256 {
+            let len = input.new_tree(&mut runner).unwrap().current().len();
+            assert!((1..=13).contains(&len), "{}", len);
+        }
+    }
+
+    #[test]
+    fn tokens_sanity() {
+        check_strategy_sanity(
+            tokens([(1, "a"), (2, "bc"), (3, "def")]).with_separator(","),
+            None,
+        );
+    }
+
     include!("regex-contrib/crates_regex.rs");
 }
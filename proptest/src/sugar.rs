@@ -7,7 +7,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::fmt;
+use crate::std_facade::{fmt, String, Vec};
 
 /// Easily define `proptest` tests.
 ///
@@ -96,6 +96,115 @@ use crate::std_facade::fmt;
 /// # fn main() { test_addition(); }
 /// ```
 ///
+/// If no `#![proptest_config]` is given, the default for the current module
+/// as registered by [`proptest_default_config!`] is used, falling back to
+/// `Config::default()` if there is none.
+///
+/// A test function may be followed by `cases: [...]`, listing specific input
+/// values which are always tested before the random cases, for example to pin
+/// known regressions independently of the RNG. Each case is written as a
+/// single value if the test has one argument, and as a tuple of all arguments
+/// otherwise. Literal cases are not shrunk; a failing case is reported as is.
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn test_midpoint(a in 0..1000u32, b in 0..1000u32) {
+///     let mid = a / 2 + b / 2 + (a % 2 + b % 2) / 2;
+///     prop_assert!(a.min(b) <= mid && mid <= a.max(b));
+///   }
+///   cases: [(0, 0), (1, 1), (999, 999)]
+/// }
+/// #
+/// # fn main() { test_midpoint(); }
+/// ```
+///
+/// The same cases can be listed in an `#[examples(...)]` attribute on the test
+/// instead, which keeps them next to its name. Examples are tested before the
+/// `cases` if a test has both.
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   #[examples((1, 2), (0, 0), (u32::MAX, 1))]
+///   fn test_wrapping_add(a in any::<u32>(), b in any::<u32>()) {
+///     prop_assert_eq!(a.wrapping_add(b), b.wrapping_add(a));
+///   }
+/// }
+/// #
+/// # fn main() { test_wrapping_add(); }
+/// ```
+///
+/// A test function may be declared `async fn`, in which case its body is an
+/// `async` block run to completion for each case by
+/// [`block_on_case`](crate::test_runner::block_on_case), a minimal executor on
+/// the test thread. If the configuration has a `timeout`, a case whose future
+/// is still pending by then is cancelled and fails. Tests relying on the
+/// timers or IO of a specific runtime, such as tokio, should use
+/// `#[property_test(async = "tokio")]` instead.
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// async fn double(x: u32) -> u32 {
+///   x * 2
+/// }
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   async fn test_double(x in 0..1000u32) {
+///     prop_assert_eq!(x + x, double(x).await);
+///   }
+/// }
+/// #
+/// # fn main() { test_double(); }
+/// ```
+///
+/// A test function may also be followed by `strategy: <type>;`, after the
+/// `cases` if there are any, to reuse its input strategy elsewhere, such as in
+/// a debugging binary. This additionally defines a public module with the same
+/// name as the test, containing a function `strategy()` which returns the
+/// strategy the arguments of the test are generated by. The values are
+/// written like the `cases`, so `<type>` is the type of the single argument,
+/// or a tuple of the types of all arguments, of which there may be at most 10.
+/// The module imports the items of the enclosing module with `use super::*`,
+/// which the strategies may refer to.
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn test_concat(a in "[a-z]*", b in "[a-z]*") {
+///     prop_assert_eq!(a.len() + b.len(), format!("{}{}", a, b).len());
+///   }
+///   strategy: (String, String);
+/// }
+///
+/// fn main() {
+///   use proptest::strategy::ValueTree;
+///   use proptest::test_runner::TestRunner;
+///
+///   let mut runner = TestRunner::default();
+///   let (a, b) = test_concat::strategy()
+///     .new_tree(&mut runner).unwrap().current();
+///   println!("{:?} {:?}", a, b);
+///   # test_concat();
+/// }
+/// ```
+///
 /// ## Closure-Style Invocation
 ///
 /// As of proptest 0.8.1, an alternative, "closure-style" invocation is
@@ -151,102 +260,174 @@ use crate::std_facade::fmt;
 macro_rules! proptest {
     (#![proptest_config($config:expr)]
      $(
-        $(#[$meta:meta])*
-       fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $body:block
+        $(#[$($meta:tt)*])*
+       $(async $(@$async:tt)?)? fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $body:block
+       $(cases: [$($case:expr),* $(,)?])?
+       $(strategy: $value:ty;)?
     )*) => {
         $(
-            $(#[$meta])*
-            fn $test_name() {
-                let mut config = $crate::test_runner::contextualize_config($config.clone());
-                config.test_name = Some(
-                    concat!(module_path!(), "::", stringify!($test_name)));
-                $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] $body);
-            }
+            $crate::proptest_helper!(
+                @_TEST [] [] [$(#[$($meta)*])*] $config, _BODY
+                [$(async $($async)?)? fn $test_name]
+                ($($parm in $strategy),+) $body [$($($case),*)?] [$($value)?]);
         )*
     };
     (#![proptest_config($config:expr)]
      $(
-        $(#[$meta:meta])*
-        fn $test_name:ident($($arg:tt)+) $body:block
+        $(#[$($meta:tt)*])*
+        $(async $(@$async:tt)?)? fn $test_name:ident($($arg:tt)+) $body:block
+        $(cases: [$($case:expr),* $(,)?])?
+        $(strategy: $value:ty;)?
     )*) => {
         $(
-            $(#[$meta])*
-            fn $test_name() {
-                let mut config = $crate::test_runner::contextualize_config($config.clone());
-                config.test_name = Some(
-                    concat!(module_path!(), "::", stringify!($test_name)));
-                $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] $body);
-            }
+            $crate::proptest_helper!(
+                @_TEST [] [] [$(#[$($meta)*])*] $config, _BODY2
+                [$(async $($async)?)? fn $test_name]
+                ($($arg)+) $body [$($($case),*)?] [$($value)?]);
         )*
     };
 
     ($(
-        $(#[$meta:meta])*
-        fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $body:block
+        $(#[$($meta:tt)*])*
+        $(async $(@$async:tt)?)? fn $test_name:ident($($parm:pat in $strategy:expr),+ $(,)?) $body:block
+        $(cases: [$($case:expr),* $(,)?])?
+        $(strategy: $value:ty;)?
     )*) => { $crate::proptest! {
-        #![proptest_config($crate::test_runner::Config::default())]
-        $($(#[$meta])*
-          fn $test_name($($parm in $strategy),+) $body)*
+        #![proptest_config($crate::proptest_helper!(@_DEFAULT_CONFIG))]
+        $($(#[$($meta)*])*
+          $(async $(@$async)?)? fn $test_name($($parm in $strategy),+) $body
+          $(cases: [$($case),*])?
+          $(strategy: $value;)?)*
     } };
 
     ($(
-        $(#[$meta:meta])*
-        fn $test_name:ident($($arg:tt)+) $body:block
+        $(#[$($meta:tt)*])*
+        $(async $(@$async:tt)?)? fn $test_name:ident($($arg:tt)+) $body:block
+        $(cases: [$($case:expr),* $(,)?])?
+        $(strategy: $value:ty;)?
     )*) => { $crate::proptest! {
-        #![proptest_config($crate::test_runner::Config::default())]
-        $($(#[$meta])*
-          fn $test_name($($arg)+) $body)*
+        #![proptest_config($crate::proptest_helper!(@_DEFAULT_CONFIG))]
+        $($(#[$($meta)*])*
+          $(async $(@$async)?)? fn $test_name($($arg)+) $body
+          $(cases: [$($case),*])?
+          $(strategy: $value;)?)*
     } };
 
     (|($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => {
         $crate::proptest!(
-            $crate::test_runner::Config::default(),
+            $crate::proptest_helper!(@_DEFAULT_CONFIG),
             |($($parm in $strategy),+)| $body)
     };
 
     (move |($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => {
         $crate::proptest!(
-            $crate::test_runner::Config::default(),
+            $crate::proptest_helper!(@_DEFAULT_CONFIG),
             move |($($parm in $strategy),+)| $body)
     };
 
     (|($($arg:tt)+)| $body:expr) => {
         $crate::proptest!(
-            $crate::test_runner::Config::default(),
+            $crate::proptest_helper!(@_DEFAULT_CONFIG),
             |($($arg)+)| $body)
     };
 
     (move |($($arg:tt)+)| $body:expr) => {
         $crate::proptest!(
-            $crate::test_runner::Config::default(),
+            $crate::proptest_helper!(@_DEFAULT_CONFIG),
             move |($($arg)+)| $body)
     };
 
     ($config:expr, |($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] $body)
+        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [] [] $body)
     } };
 
     ($config:expr, move |($($parm:pat in $strategy:expr),+ $(,)?)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [move] $body)
+        $crate::proptest_helper!(@_BODY config ($($parm in $strategy),+) [move] [] $body)
     } };
 
     ($config:expr, |($($arg:tt)+)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] $body);
+        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [] [] $body);
     } };
 
     ($config:expr, move |($($arg:tt)+)| $body:expr) => { {
         let mut config = $crate::test_runner::contextualize_config($config.__sugar_to_owned());
         $crate::sugar::force_no_fork(&mut config);
-        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [move] $body);
+        $crate::proptest_helper!(@_BODY2 config ($($arg)+) [move] [] $body);
     } };
 }
 
+/// Registers the default configuration for all `proptest!` invocations in the
+/// current module.
+///
+/// Large codebases often have a handful of modules whose tests are much more
+/// expensive than the rest. Rather than repeating `#![proptest_config]` in
+/// every `proptest!` block of such a module, invoke this macro once at item
+/// level. `proptest!` blocks without their own `#![proptest_config]`, as well
+/// as closure-style invocations without an explicit configuration, then use
+/// the registered configuration instead of `Config::default()`.
+///
+/// The argument is either an expression evaluating to a
+/// `proptest::test_runner::Config`, or a struct literal ending in a bare `..`,
+/// in which case the remaining fields are taken from `Config::default()`.
+///
+/// The registration is visible wherever the module's items are in scope, so
+/// child modules which `use super::*;` (as test modules commonly do) inherit
+/// it, and may in turn register their own. It can be registered at most once
+/// per module.
+///
+/// As with `#![proptest_config]`, environment variables such as
+/// `PROPTEST_CASES` still take precedence over the registered values.
+///
+/// ## Example
+///
+/// ```
+/// mod heavy {
+///     use proptest::prelude::*;
+///
+///     proptest_default_config!(ProptestConfig { cases: 16, .. });
+///
+///     proptest! {
+///         # /*
+///         #[test]
+///         # */
+///         fn expensive_check(a in 0..10) {
+///             // Runs 16 cases.
+///         }
+///     }
+/// }
+/// #
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! proptest_default_config {
+    ($config:ident { $($field:ident : $value:expr,)* .. }) => {
+        $crate::proptest_default_config!($config {
+            $($field: $value,)*
+            ..$crate::test_runner::Config::default()
+        });
+    };
+
+    ($config:expr) => {
+        #[doc(hidden)]
+        #[allow(non_camel_case_types)]
+        trait __ProptestModuleConfig {
+            fn __proptest_module_config(&self) -> $crate::test_runner::Config;
+        }
+
+        impl __ProptestModuleConfig for $crate::sugar::ModuleConfigKey {
+            fn __proptest_module_config(&self) -> $crate::test_runner::Config {
+                $config
+            }
+        }
+    };
+}
+
 /// Rejects the test input if assumptions are not met.
 ///
 /// Used directly within a function defined with `proptest!` or in any function
@@ -491,6 +672,81 @@ macro_rules! prop_oneof {
     };
 }
 
+/// Like `prop_oneof!`, but also accepts anything implementing
+/// [`IntoStrategy`](strategy/trait.IntoStrategy.html) as a choice, such as a
+/// literal value, which is then always chosen as is, or an array of them.
+///
+/// Each choice is converted on its own, so unlike with `prop_oneof!`, the
+/// choices don't coerce to a common type, such as a closure to a `fn`
+/// pointer, and integer literals need a suffix unless they are meant to be
+/// `i32`s.
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn port_is_valid(
+///         port in prop_oneof_into![80u16, 443u16, 8000..9000u16]
+///     ) {
+///         prop_assert!(port == 80 || port == 443 || port >= 8000);
+///     }
+/// }
+/// # fn main() { port_is_valid(); }
+/// ```
+#[macro_export]
+macro_rules! prop_oneof_into {
+    ($($item:expr),+ $(,)?) => {
+        $crate::prop_oneof![
+            $($crate::strategy::IntoStrategy::into_strategy($item)),+
+        ]
+    };
+
+    ($($weight:expr => $item:expr),+ $(,)?) => {
+        $crate::prop_oneof![
+            $($weight => $crate::strategy::IntoStrategy::into_strategy($item)),+
+        ]
+    };
+}
+
+/// Label an alternative of `prop_oneof!`, to count how many values it
+/// generates.
+///
+/// `case!(label, strategy)` is shorthand for
+/// `Labeled::new(label, strategy)`. The counts of all labels are part of the
+/// report of a failing test, are logged at the end of a passing run if
+/// `Config::verbose` is at least 1, and are returned by
+/// `TestRunner::label_counts()`. This helps to find alternatives which are
+/// never or rarely picked, e.g. because most of their values get rejected.
+/// See [`Labeled`](strategy/struct.Labeled.html) for details.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// let strategy = prop_oneof![
+///     case!("empty", Just(vec![])),
+///     case!("big", prop::collection::vec(any::<u8>(), 1000)),
+/// ];
+///
+/// let mut runner = prop::test_runner::TestRunner::default();
+/// runner.run(&strategy, |v| {
+///     prop_assert!(v.is_empty() || 1000 == v.len());
+///     Ok(())
+/// }).unwrap();
+/// assert!(runner.label_counts()["empty"] > 0);
+/// assert!(runner.label_counts()["big"] > 0);
+/// ```
+#[macro_export]
+macro_rules! case {
+    ($label:expr, $strategy:expr $(,)?) => {
+        $crate::strategy::Labeled::new($label, $strategy)
+    };
+}
+
 /// Convenience to define functions which produce new strategies.
 ///
 /// The macro has two general forms. In the first, you define a function with
@@ -607,6 +863,38 @@ macro_rules! prop_oneof {
 /// # fn main() { }
 /// ```
 ///
+/// ## Caching the strategies
+///
+/// Every call to a function defined by `prop_compose!` constructs its
+/// strategies anew, which is costly for strategies such as those made by
+/// `string_regex()`. Putting `#[cache]` before any other attribute makes the
+/// strategies of the first argument list be constructed only once per process
+/// instead, and shared by all calls.
+///
+/// ```rust,no_run
+/// # #![allow(dead_code)]
+/// use proptest::prelude::*;
+/// use proptest::string::string_regex;
+///
+/// prop_compose! {
+///   #[cache]
+///   fn identifier()
+///                (name in string_regex("[a-z][a-z0-9_]{0,15}").unwrap())
+///                -> String {
+///     name
+///   }
+/// }
+/// # fn main() { }
+/// ```
+///
+/// Since the strategies are constructed before any call's arguments are known,
+/// a cached function can't take any arguments, and its first argument list
+/// can't refer to any runtime values. The strategies must also be `Send` and
+/// `Sync`, which rules out e.g. `BoxedStrategy` (use `SBoxedStrategy`
+/// instead). Note that a string literal used as a strategy parses its regular
+/// expression each time a value is generated, whether or not it is cached. Only
+/// available with the `std` feature.
+///
 /// ## Comparison with Hypothesis' `@composite`
 ///
 /// `prop_compose!` makes it easy to do a lot of things you can do with
@@ -621,6 +909,74 @@ macro_rules! prop_oneof {
 /// `prop_flat_map()` by hand.
 #[macro_export]
 macro_rules! prop_compose {
+    (@_CACHED $strat:expr) => {{
+        static CACHE: $crate::sugar::StrategyCache =
+            $crate::sugar::StrategyCache::new();
+        CACHE.get_or_init(|| $strat)
+    }};
+
+    (#[cache]
+     $(#[$meta:meta])*
+     $vis:vis
+     $([$($modi:tt)*])? fn $name:ident ()
+     ($($var:pat in $strategy:expr),+ $(,)?)
+       -> $return_type:ty $body:block) =>
+    {
+        #[must_use = "strategies do nothing unless used"]
+        $(#[$meta])*
+        $vis
+        $($($modi)*)? fn $name()
+                 -> impl $crate::strategy::Strategy<Value = $return_type> {
+            let strat = $crate::prop_compose!(@_CACHED
+                $crate::proptest_helper!(@_WRAP ($($strategy)*)));
+            $crate::strategy::Strategy::prop_map(strat,
+                move |$crate::proptest_helper!(@_WRAPPAT ($($var),*))| $body)
+        }
+    };
+
+    (#[cache]
+     $(#[$meta:meta])*
+     $vis:vis
+     $([$($modi:tt)*])? fn $name:ident ()
+     ($($var:pat in $strategy:expr),+ $(,)?)
+     ($($var2:pat in $strategy2:expr),+ $(,)?)
+       -> $return_type:ty $body:block) =>
+    {
+        #[must_use = "strategies do nothing unless used"]
+        $(#[$meta])*
+        $vis
+        $($($modi)*)? fn $name()
+                 -> impl $crate::strategy::Strategy<Value = $return_type> {
+            let strat = $crate::prop_compose!(@_CACHED
+                $crate::proptest_helper!(@_WRAP ($($strategy)*)));
+            let strat = $crate::strategy::Strategy::prop_flat_map(
+                strat,
+                move |$crate::proptest_helper!(@_WRAPPAT ($($var),*))|
+                $crate::proptest_helper!(@_WRAP ($($strategy2)*)));
+            $crate::strategy::Strategy::prop_map(strat,
+                move |$crate::proptest_helper!(@_WRAPPAT ($($var2),*))| $body)
+        }
+    };
+
+    (#[cache]
+     $(#[$meta:meta])*
+     $vis:vis
+     $([$($modi:tt)*])? fn $name:ident ()
+     ($($arg:tt)+)
+       -> $return_type:ty $body:block) =>
+    {
+        #[must_use = "strategies do nothing unless used"]
+        $(#[$meta])*
+        $vis
+        $($($modi)*)? fn $name()
+                 -> impl $crate::strategy::Strategy<Value = $return_type> {
+            let strat = $crate::prop_compose!(@_CACHED
+                $crate::proptest_helper!(@_EXT _STRAT ($($arg)+)));
+            $crate::strategy::Strategy::prop_map(strat,
+                move |$crate::proptest_helper!(@_EXT _PAT ($($arg)+))| $body)
+        }
+    };
+
     ($(#[$meta:meta])*
      $vis:vis
      $([$($modi:tt)*])? fn $name:ident $params:tt
@@ -700,6 +1056,121 @@ macro_rules! prop_compose {
     };
 }
 
+/// Defines strategies for a type from another crate, which cannot implement
+/// `Arbitrary` itself, in the style of serde's remote derive.
+///
+/// Rust's orphan rule forbids implementing `Arbitrary` for a type unless
+/// either the trait or the type is defined in the current crate. Instead, this
+/// macro defines a local stand-in type with the given name, mirroring the
+/// fields of the remote type, whose `arbitrary()` function returns a strategy
+/// for the remote type itself. No newtype around the remote type is needed.
+///
+/// Each field is generated from its own strategy, using the `in strategy`
+/// syntax of `proptest!`. If the fields of the remote type are public, the
+/// value is built with a struct expression:
+///
+/// ```rust,no_run
+/// # #![allow(dead_code)]
+/// use proptest::prelude::*;
+///
+/// mod other_crate {
+///     #[derive(Clone, Debug)]
+///     pub struct Point {
+///         pub x: i32,
+///         pub y: i32,
+///     }
+/// }
+///
+/// arbitrary_remote! {
+///     /// Strategies for `other_crate::Point`.
+///     pub struct PointDef for other_crate::Point {
+///         x in -100..100,
+///         y in any::<i32>(),
+///     }
+/// }
+///
+/// proptest! {
+///     # /*
+///     #[test]
+///     # */
+///     fn points_are_near_the_axis(p in PointDef::arbitrary()) {
+///         prop_assert!(p.x.abs() < 100);
+///     }
+/// }
+/// # fn main() { }
+/// ```
+///
+/// If some fields are private, give an expression after `=>` constructing the
+/// value from the generated fields instead, typically a call to a constructor
+/// function of the remote type:
+///
+/// ```rust,no_run
+/// # #![allow(dead_code)]
+/// use std::time::Duration;
+///
+/// use proptest::prelude::*;
+///
+/// arbitrary_remote! {
+///     struct DurationDef for Duration {
+///         secs in 0u64..3600,
+///         nanos in 0u32..1_000_000_000,
+///     } => Duration::new(secs, nanos)
+/// }
+///
+/// // The stand-in also works with `#[derive(Arbitrary)]` from
+/// // `proptest-derive`, through the `strategy` attribute:
+/// //
+/// // #[derive(Debug, Arbitrary)]
+/// // struct Timeout {
+/// //     #[proptest(strategy = "DurationDef::arbitrary()")]
+/// //     after: Duration,
+/// // }
+/// # fn main() { }
+/// ```
+#[macro_export]
+macro_rules! arbitrary_remote {
+    ($(#[$meta:meta])*
+     $vis:vis struct $name:ident for $remote:path {
+         $($field:ident in $strategy:expr),+ $(,)?
+     } => $ctor:expr) =>
+    {
+        $crate::arbitrary_remote! {
+            @_IMPL [$(#[$meta])*] $vis $name [$remote]
+            ($($field in $strategy),+) { $ctor }
+        }
+    };
+
+    ($(#[$meta:meta])*
+     $vis:vis struct $name:ident for $remote:path {
+         $($field:ident in $strategy:expr),+ $(,)?
+     }) =>
+    {
+        $crate::arbitrary_remote! {
+            @_IMPL [$(#[$meta])*] $vis $name [$remote]
+            ($($field in $strategy),+) { $remote { $($field),+ } }
+        }
+    };
+
+    (@_IMPL [$(#[$meta:meta])*] $vis:vis $name:ident [$remote:path]
+     ($($field:ident in $strategy:expr),+) $body:block) =>
+    {
+        $(#[$meta])*
+        $vis struct $name;
+
+        impl $name {
+            /// Returns a strategy generating values of the remote type.
+            #[must_use = "strategies do nothing unless used"]
+            #[allow(dead_code)]
+            $vis fn arbitrary()
+                -> impl $crate::strategy::Strategy<Value = $remote> {
+                let strat = $crate::proptest_helper!(@_WRAP ($($strategy)*));
+                $crate::strategy::Strategy::prop_map(strat,
+                    |$crate::proptest_helper!(@_WRAPPAT ($($field),*))| $body)
+            }
+        }
+    };
+}
+
 /// Similar to `assert!` from std, but returns a test failure instead of
 /// panicking if the condition fails.
 ///
@@ -856,28 +1327,505 @@ macro_rules! prop_assert_ne {
     }};
 }
 
-#[doc(hidden)]
+/// Similar to `prop_assert_eq!`, but for floats which only need to be
+/// approximately equal.
+///
+/// The tolerance is given after the two values as one of `ulps <= N` (at
+/// most `N` representable values apart), `abs <= X` (absolute difference) or
+/// `rel <= X` (difference relative to the larger magnitude). Equal values,
+/// including infinities, are always close, and NaN is never close to
+/// anything. On failure, the message shows the difference in every measure.
+/// See the [`num::float_cmp`](num/float_cmp/index.html) module, which this
+/// delegates to, for using the same comparisons elsewhere.
+///
+/// See `prop_assert!` for a more in-depth discussion.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn sqrt_squared(x in 0.0f64..1e6) {
+///     prop_assert_close!(x.sqrt() * x.sqrt(), x, ulps <= 4);
+///     prop_assert_close!((x + 1.0) - 1.0, x, abs <= 1e-9);
+///     // Can also provide a custom message added after the common message
+///     prop_assert_close!(x.sqrt().powi(2), x, rel <= 1e-12, "x = {}", x);
+///   }
+/// }
+/// #
+/// # fn main() { sqrt_squared(); }
+/// ```
 #[macro_export]
-macro_rules! proptest_helper {
-    (@_WRAP ($a:tt)) => { $a };
-    (@_WRAP ($a0:tt $a1:tt)) => { ($a0, $a1) };
-    (@_WRAP ($a0:tt $a1:tt $a2:tt)) => { ($a0, $a1, $a2) };
-    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt)) => { ($a0, $a1, $a2, $a3) };
-    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt)) => {
-        ($a0, $a1, $a2, $a3, $a4)
+macro_rules! prop_assert_close {
+    ($left:expr, $right:expr, ulps <= $tol:expr $(, $($fmt:tt)*)?) => {
+        $crate::prop_assert_close!(
+            @tolerance $left, $right,
+            $crate::num::float_cmp::Tolerance::Ulps($tol) $(, $($fmt)*)?)
     };
-    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt $a5:tt)) => {
-        ($a0, $a1, $a2, $a3, $a4, $a5)
-    };
-    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt $a5:tt $a6:tt)) => {
-        ($a0, $a1, $a2, $a3, $a4, $a5, $a6)
+
+    ($left:expr, $right:expr, abs <= $tol:expr $(, $($fmt:tt)*)?) => {
+        $crate::prop_assert_close!(
+            @tolerance $left, $right,
+            $crate::num::float_cmp::Tolerance::Abs($tol) $(, $($fmt)*)?)
     };
-    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt
-             $a4:tt $a5:tt $a6:tt $a7:tt)) => {
-        ($a0, $a1, $a2, $a3, $a4, $a5, $a6, $a7)
+
+    ($left:expr, $right:expr, rel <= $tol:expr $(, $($fmt:tt)*)?) => {
+        $crate::prop_assert_close!(
+            @tolerance $left, $right,
+            $crate::num::float_cmp::Tolerance::Rel($tol) $(, $($fmt)*)?)
     };
-    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt
-             $a5:tt $a6:tt $a7:tt $a8:tt)) => {
+
+    (@tolerance $left:expr, $right:expr, $tol:expr $(,)?) => {{
+        let left = $left;
+        let right = $right;
+        let tolerance = $tol;
+        $crate::prop_assert!(
+            $crate::num::float_cmp::is_close(left, right, tolerance),
+            "assertion failed: `(left ≈ right)` ({}) \
+             \n  left: `{:?}`,\n right: `{:?}`,\n  diff: {}",
+            tolerance, left, right,
+            $crate::num::float_cmp::difference(left, right));
+    }};
+
+    (@tolerance $left:expr, $right:expr, $tol:expr, $fmt:tt $($args:tt)*) => {{
+        let left = $left;
+        let right = $right;
+        let tolerance = $tol;
+        $crate::prop_assert!(
+            $crate::num::float_cmp::is_close(left, right, tolerance),
+            concat!(
+                "assertion failed: `(left ≈ right)` ({}) \
+                 \n  left: `{:?}`,\n right: `{:?}`,\n  diff: {}: ", $fmt),
+            tolerance, left, right,
+            $crate::num::float_cmp::difference(left, right) $($args)*);
+    }};
+}
+
+/// Asserts that the items of a collection are sorted in ascending order,
+/// returning a test failure instead of panicking if they are not.
+///
+/// The argument is anything implementing `IntoIterator` whose items implement
+/// `PartialOrd` and `Debug`. Every item must be less than or equal to the one
+/// following it, so duplicates are allowed. On failure, the message shows the
+/// first adjacent pair of items which is out of order and their indices,
+/// rather than the whole collection.
+///
+/// See `prop_assert!` for a more in-depth discussion.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn sort_sorts(mut v in prop::collection::vec(0..100u32, 0..20)) {
+///     v.sort();
+///     prop_assert_sorted!(&v);
+///     // Can also provide custom message added after the common message
+///     prop_assert_sorted!(v.iter().rev().rev(), "len = {}", v.len());
+///   }
+/// }
+/// #
+/// # fn main() { sort_sorts(); }
+/// ```
+#[macro_export]
+macro_rules! prop_assert_sorted {
+    ($items:expr $(,)?) => {{
+        if let ::core::option::Option::Some(why) =
+            $crate::sugar::find_unsorted($items)
+        {
+            $crate::prop_assert!(
+                false,
+                "assertion failed: `(items are sorted)`\n  {}", why);
+        }
+    }};
+
+    ($items:expr, $fmt:tt $($args:tt)*) => {{
+        if let ::core::option::Option::Some(why) =
+            $crate::sugar::find_unsorted($items)
+        {
+            $crate::prop_assert!(
+                false,
+                concat!("assertion failed: `(items are sorted)`\n  {}: ", $fmt),
+                why $($args)*);
+        }
+    }};
+}
+
+/// Asserts that two collections contain the same items, in any order,
+/// returning a test failure instead of panicking if they do not.
+///
+/// Both arguments are anything implementing `IntoIterator`, whose items are
+/// compared with `PartialEq` and must implement `Debug`. Each item must occur
+/// as many times in `left` as in `right`. On failure, the message shows the
+/// first item of `left` which has no remaining match in `right`, or
+/// otherwise the first item of `right` which was not matched, with its index,
+/// rather than both whole collections.
+///
+/// This only requires `PartialEq`, so it takes time proportional to the
+/// product of the lengths of the collections.
+///
+/// See `prop_assert!` for a more in-depth discussion.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn sort_permutes(v in prop::collection::vec(0..100u32, 0..20)) {
+///     let mut sorted = v.clone();
+///     sorted.sort();
+///     prop_assert_permutation!(&sorted, &v);
+///     // Can also provide custom message added after the common message
+///     prop_assert_permutation!(&sorted, &v, "len = {}", v.len());
+///   }
+/// }
+/// #
+/// # fn main() { sort_permutes(); }
+/// ```
+#[macro_export]
+macro_rules! prop_assert_permutation {
+    ($left:expr, $right:expr $(,)?) => {{
+        if let ::core::option::Option::Some(why) =
+            $crate::sugar::find_permutation_mismatch($left, $right)
+        {
+            $crate::prop_assert!(
+                false,
+                "assertion failed: `(left is a permutation of right)`\n  {}",
+                why);
+        }
+    }};
+
+    ($left:expr, $right:expr, $fmt:tt $($args:tt)*) => {{
+        if let ::core::option::Option::Some(why) =
+            $crate::sugar::find_permutation_mismatch($left, $right)
+        {
+            $crate::prop_assert!(
+                false,
+                concat!(
+                    "assertion failed: `(left is a permutation of right)`\n  \
+                     {}: ", $fmt),
+                why $($args)*);
+        }
+    }};
+}
+
+/// Asserts that every item of one collection occurs in another, returning a
+/// test failure instead of panicking if one does not.
+///
+/// Both arguments are anything implementing `IntoIterator`, whose items are
+/// compared with `PartialEq` and must implement `Debug`. Unlike with
+/// `prop_assert_permutation!`, the number of occurrences does not matter. On
+/// failure, the message shows the first item of `left` which does not occur
+/// in `right`, with its index, rather than both whole collections.
+///
+/// This only requires `PartialEq`, so it takes time proportional to the
+/// product of the lengths of the collections.
+///
+/// See `prop_assert!` for a more in-depth discussion.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn dedup_keeps_items(v in prop::collection::vec(0..10u32, 0..20)) {
+///     let mut deduped = v.clone();
+///     deduped.dedup();
+///     prop_assert_subset!(&deduped, &v);
+///     // Can also provide custom message added after the common message
+///     prop_assert_subset!(&v, &deduped, "len = {}", v.len());
+///   }
+/// }
+/// #
+/// # fn main() { dedup_keeps_items(); }
+/// ```
+#[macro_export]
+macro_rules! prop_assert_subset {
+    ($left:expr, $right:expr $(,)?) => {{
+        if let ::core::option::Option::Some(why) =
+            $crate::sugar::find_missing($left, $right)
+        {
+            $crate::prop_assert!(
+                false,
+                "assertion failed: `(left is a subset of right)`\n  {}", why);
+        }
+    }};
+
+    ($left:expr, $right:expr, $fmt:tt $($args:tt)*) => {{
+        if let ::core::option::Option::Some(why) =
+            $crate::sugar::find_missing($left, $right)
+        {
+            $crate::prop_assert!(
+                false,
+                concat!(
+                    "assertion failed: `(left is a subset of right)`\n  \
+                     {}: ", $fmt),
+                why $($args)*);
+        }
+    }};
+}
+
+/// Asserts that evaluating an expression makes at most the given number of
+/// allocations, returning a test failure instead of panicking if it makes
+/// more. Otherwise, evaluates to the value of the expression.
+///
+/// Allocations are counted with `alloc_tracking::count_allocations()`, so
+/// `alloc_tracking::TrackingAllocator` needs to be the global allocator of
+/// the test binary, and only allocations of the current thread count. The
+/// expression is evaluated inside a closure, so `return` and `?` in it don't
+/// leave the test.
+///
+/// Requires the `alloc-tracking` feature.
+///
+/// See `prop_assert!` for a more in-depth discussion.
+///
+/// ## Example
+///
+/// ```
+/// use std::alloc::System;
+///
+/// use proptest::alloc_tracking::TrackingAllocator;
+/// use proptest::prelude::*;
+///
+/// #[global_allocator]
+/// static ALLOCATOR: TrackingAllocator<System> = TrackingAllocator(System);
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn uppercasing_allocates_once(s in "[a-z]{0,32}") {
+///     let upper = prop_assert_allocates_at_most!(1, s.to_uppercase());
+///     // Can also provide custom message added after the common message
+///     prop_assert_allocates_at_most!(0, upper.len(), "s = {:?}", s);
+///   }
+/// }
+/// #
+/// # fn main() { uppercasing_allocates_once(); }
+/// ```
+#[cfg(feature = "alloc-tracking")]
+#[macro_export]
+macro_rules! prop_assert_allocates_at_most {
+    ($max:expr, $body:expr $(,)?) => {{
+        let max: usize = $max;
+        let (value, allocations) =
+            $crate::alloc_tracking::count_allocations(|| $body);
+        $crate::prop_assert!(
+            allocations.count <= max,
+            "assertion failed: `(allocations <= max)`\n  \
+             allocations: {},\n  max: {}",
+            allocations, max);
+        value
+    }};
+
+    ($max:expr, $body:expr, $fmt:tt $($args:tt)*) => {{
+        let max: usize = $max;
+        let (value, allocations) =
+            $crate::alloc_tracking::count_allocations(|| $body);
+        $crate::prop_assert!(
+            allocations.count <= max,
+            concat!(
+                "assertion failed: `(allocations <= max)`\n  \
+                 allocations: {},\n  max: {}: ", $fmt),
+            allocations, max $($args)*);
+        value
+    }};
+}
+
+/// Tests that values survive a round trip through a serialization format.
+///
+/// This runs a property test, like the closure-style form of `proptest!`,
+/// which generates values from `strategy`, passes a reference to each to
+/// `serialize`, passes a reference to the result to `deserialize` and asserts
+/// that the outcome equals the original value. On mismatch, the failure shows
+/// the original value, its serialized form, the decoded value, and a line diff
+/// of the pretty-printed (`{:#?}`) original and decoded values.
+///
+/// Prefix `serialize` and/or `deserialize` with `fallible` if it returns a
+/// `Result`; an `Err` then fails the test, reporting the error. A `Config` may
+/// be passed as the first argument.
+///
+/// The value type must implement `PartialEq` and `Debug`, and so must the
+/// serialized form implement `Debug`.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::prop_roundtrip;
+///
+/// fn to_hex(bytes: &Vec<u8>) -> String {
+///     bytes.iter().map(|b| format!("{:02x}", b)).collect()
+/// }
+///
+/// fn from_hex(hex: &String) -> Result<Vec<u8>, std::num::ParseIntError> {
+///     (0..hex.len())
+///         .step_by(2)
+///         .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+///         .collect()
+/// }
+///
+/// # /*
+/// #[test]
+/// # */
+/// fn hex_roundtrip() {
+///     let bytes = prop::collection::vec(any::<u8>(), 0..64);
+///     prop_roundtrip!(bytes, to_hex, fallible from_hex);
+///     // Closures work too, and a `Config` can be passed first.
+///     prop_roundtrip!(
+///         ProptestConfig::with_cases(16),
+///         ".*",
+///         |s: &String| s.as_bytes().to_vec(),
+///         fallible |b: &Vec<u8>| String::from_utf8(b.clone()),
+///     );
+/// }
+/// # fn main() { hex_roundtrip(); }
+/// ```
+#[macro_export]
+macro_rules! prop_roundtrip {
+    (@_CALL plain $result:expr, $what:expr) => {
+        $result
+    };
+
+    (@_CALL fallible $result:expr, $what:expr) => {
+        match $result {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err(err) => {
+                return ::core::result::Result::Err(
+                    $crate::test_runner::TestCaseError::fail(format!(
+                        "{} failed: {:?} at {}:{}",
+                        $what, err, file!(), line!()
+                    )),
+                );
+            }
+        }
+    };
+
+    (@_RUN [$($config:expr)?] $strategy:expr,
+     $ser_kind:ident $ser:expr, $de_kind:ident $de:expr) => {
+        $crate::proptest!($($config,)? |(value in $strategy)| {
+            let serialized = $crate::prop_roundtrip!(
+                @_CALL $ser_kind ($ser)(&value), "serialize");
+            let decoded = $crate::prop_roundtrip!(
+                @_CALL $de_kind ($de)(&serialized), "deserialize");
+            $crate::sugar::check_roundtrip(&value, &serialized, &decoded)?;
+        })
+    };
+
+    ($config:expr, $strategy:expr,
+     fallible $ser:expr, fallible $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(
+            @_RUN [$config] $strategy, fallible $ser, fallible $de)
+    };
+
+    ($config:expr, $strategy:expr, fallible $ser:expr, $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(
+            @_RUN [$config] $strategy, fallible $ser, plain $de)
+    };
+
+    ($config:expr, $strategy:expr, $ser:expr, fallible $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(
+            @_RUN [$config] $strategy, plain $ser, fallible $de)
+    };
+
+    ($config:expr, $strategy:expr, $ser:expr, $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(
+            @_RUN [$config] $strategy, plain $ser, plain $de)
+    };
+
+    ($strategy:expr, fallible $ser:expr, fallible $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(
+            @_RUN [] $strategy, fallible $ser, fallible $de)
+    };
+
+    ($strategy:expr, fallible $ser:expr, $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(@_RUN [] $strategy, fallible $ser, plain $de)
+    };
+
+    ($strategy:expr, $ser:expr, fallible $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(@_RUN [] $strategy, plain $ser, fallible $de)
+    };
+
+    ($strategy:expr, $ser:expr, $de:expr $(,)?) => {
+        $crate::prop_roundtrip!(@_RUN [] $strategy, plain $ser, plain $de)
+    };
+}
+
+/// Records the `Debug` form of a value under a label, to be reported only if
+/// the current test case fails.
+///
+/// The label defaults to the text of the expression. The value is only
+/// borrowed. See the [`probe`](probe/index.html) module for details.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///   # /*
+///   #[test]
+///   # */
+///   fn sum_is_commutative(a in 0u32..1000, b in 0u32..1000) {
+///     let ab = a + b;
+///     proptest::probe!("a + b", ab);
+///     proptest::probe!(b + a);
+///     prop_assert_eq!(ab, b + a);
+///   }
+/// }
+/// #
+/// # fn main() { sum_is_commutative(); }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! probe {
+    ($value:expr $(,)?) => {
+        $crate::probe!(stringify!($value), $value)
+    };
+
+    ($label:expr, $value:expr $(,)?) => {
+        $crate::probe::record($label, &$value)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! proptest_helper {
+    (@_WRAP ($a:tt)) => { $a };
+    (@_WRAP ($a0:tt $a1:tt)) => { ($a0, $a1) };
+    (@_WRAP ($a0:tt $a1:tt $a2:tt)) => { ($a0, $a1, $a2) };
+    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt)) => { ($a0, $a1, $a2, $a3) };
+    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt)) => {
+        ($a0, $a1, $a2, $a3, $a4)
+    };
+    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt $a5:tt)) => {
+        ($a0, $a1, $a2, $a3, $a4, $a5)
+    };
+    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt $a5:tt $a6:tt)) => {
+        ($a0, $a1, $a2, $a3, $a4, $a5, $a6)
+    };
+    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt
+             $a4:tt $a5:tt $a6:tt $a7:tt)) => {
+        ($a0, $a1, $a2, $a3, $a4, $a5, $a6, $a7)
+    };
+    (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt
+             $a5:tt $a6:tt $a7:tt $a8:tt)) => {
         ($a0, $a1, $a2, $a3, $a4, $a5, $a6, $a7, $a8)
     };
     (@_WRAP ($a0:tt $a1:tt $a2:tt $a3:tt $a4:tt
@@ -959,42 +1907,137 @@ macro_rules! proptest_helper {
     (@_WRAPSTR ($a:pat, $($rest:pat),*)) => {
         (stringify!($a), $crate::proptest_helper!(@_WRAPSTR ($($rest),*)))
     };
+    // The default config for the invoking module. Method resolution prefers
+    // the `__ProptestModuleConfig` trait defined by `proptest_default_config!`
+    // (implemented for `ModuleConfigKey` itself) if it is in scope, and only
+    // then autorefs to reach the global fallback.
+    (@_DEFAULT_CONFIG) => {{
+        #[allow(unused_imports)]
+        use $crate::sugar::GlobalDefaultConfig as _;
+        (&$crate::sugar::ModuleConfigKey).__proptest_module_config()
+    }};
+    // Defines a test function, moving the cases of `#[examples(...)]`
+    // attributes in front of those of the `cases: [...]` clause. The body of
+    // an `async fn` is run to completion by `block_on_case`. (Tests match
+    // their optional `async` as `$(async $(@$async:tt)?)?`, where the
+    // `@$async` never present in practice only provides a metavariable to
+    // forward the keyword with.)
+    (@_TEST [$($kept:tt)*] [$($ex:expr,)*]
+     [#[examples($($case:expr),* $(,)?)] $($attrs:tt)*] $($rest:tt)*) => {
+        $crate::proptest_helper!(
+            @_TEST [$($kept)*] [$($ex,)* $($case,)*] [$($attrs)*] $($rest)*);
+    };
+    (@_TEST [$($kept:tt)*] [$($ex:expr,)*]
+     [#[$($attr:tt)*] $($attrs:tt)*] $($rest:tt)*) => {
+        $crate::proptest_helper!(
+            @_TEST [$($kept)* #[$($attr)*]] [$($ex,)*] [$($attrs)*] $($rest)*);
+    };
+    (@_TEST [$($kept:tt)*] [$($ex:expr,)*] [] $config:expr, $kind:ident
+     [fn $test_name:ident] ($($arg:tt)+) $body:block [$($case:expr),*]
+     [$($value:ty)?]) => {
+        $($kept)*
+        fn $test_name() {
+            let mut config = $crate::test_runner::contextualize_config($config.clone());
+            config.test_name = Some(
+                concat!(module_path!(), "::", stringify!($test_name)));
+            $crate::proptest_helper!(
+                @$kind config ($($arg)+) [] [$($ex,)* $($case),*] $body);
+        }
+        $crate::proptest_helper!(@_EXPOSE $test_name [$($value)?] ($($arg)+));
+    };
+    (@_TEST [$($kept:tt)*] [$($ex:expr,)*] [] $config:expr, $kind:ident
+     [async fn $test_name:ident] ($($arg:tt)+) $body:block [$($case:expr),*]
+     [$($value:ty)?]) => {
+        $($kept)*
+        fn $test_name() {
+            let mut config = $crate::test_runner::contextualize_config($config.clone());
+            config.test_name = Some(
+                concat!(module_path!(), "::", stringify!($test_name)));
+            let timeout = config.timeout();
+            $crate::proptest_helper!(
+                @$kind config ($($arg)+) [] [$($ex,)* $($case),*] {
+                    $crate::test_runner::block_on_case(timeout, async move {
+                        let (): () = $body;
+                        Ok(())
+                    })?;
+                });
+        }
+        $crate::proptest_helper!(@_EXPOSE $test_name [$($value)?] ($($arg)+));
+    };
+    // expose the strategy of a test as `$test_name::strategy()`, if requested
+    // with a `strategy: <type>;` clause.
+    (@_EXPOSE $test_name:ident [] ($($arg:tt)+)) => {};
+    (@_EXPOSE $test_name:ident [$value:ty] ($($arg:tt)+)) => {
+        #[allow(dead_code)]
+        pub mod $test_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            /// Returns the strategy generating the arguments of the test of
+            /// the same name.
+            pub fn strategy() -> impl $crate::strategy::Strategy<Value = $value> {
+                $crate::strategy::Strategy::prop_map(
+                    $crate::proptest_helper!(@_EXT _STRAT ($($arg)+)),
+                    $crate::sugar::FlattenCase::<$value>::flatten_case)
+            }
+        }
+    };
     // build a property testing block that when executed, executes the full property test.
-    (@_BODY $config:ident ($($parm:pat in $strategy:expr),+) [$($mod:tt)*] $body:expr) => {{
+    (@_BODY $config:ident ($($parm:pat in $strategy:expr),+) [$($mod:tt)*]
+     [$($case:expr),* $(,)?] $body:expr) => {{
         $config.source_file = Some(file!());
         let mut runner = $crate::test_runner::TestRunner::new($config);
         let names = $crate::proptest_helper!(@_WRAPSTR ($($parm),*));
-        match runner.run(
-            &$crate::strategy::Strategy::prop_map(
-                $crate::proptest_helper!(@_WRAP ($($strategy)*)),
-                |values| $crate::sugar::NamedArguments(names, values)),
+        let strategy = $crate::strategy::Strategy::prop_map(
+            $crate::proptest_helper!(@_WRAP ($($strategy)*)),
+            |values| $crate::sugar::NamedArguments(names, values));
+        let test = $crate::sugar::test_fn_for(
+            &strategy,
             $($mod)* |$crate::sugar::NamedArguments(
                 _, $crate::proptest_helper!(@_WRAPPAT ($($parm),*)))|
             {
                 let (): () = $body;
                 Ok(())
-            })
-        {
+            });
+        $(
+            if let Err(e) = runner.run_value(
+                $crate::sugar::NamedArguments(names, $case), &test)
+            {
+                panic!("{}\n{}", e, runner);
+            }
+        )*
+        match runner.run(&strategy, test) {
             Ok(()) => (),
             Err(e) => panic!("{}\n{}", e, runner),
         }
     }};
     // build a property testing block that when executed, executes the full property test.
-    (@_BODY2 $config:ident ($($arg:tt)+) [$($mod:tt)*] $body:expr) => {{
+    (@_BODY2 $config:ident ($($arg:tt)+) [$($mod:tt)*]
+     [$($case:expr),* $(,)?] $body:expr) => {{
         $config.source_file = Some(file!());
         let mut runner = $crate::test_runner::TestRunner::new($config);
         let names = $crate::proptest_helper!(@_EXT _STR ($($arg)*));
-        match runner.run(
-            &$crate::strategy::Strategy::prop_map(
-                $crate::proptest_helper!(@_EXT _STRAT ($($arg)*)),
-                |values| $crate::sugar::NamedArguments(names, values)),
+        let strategy = $crate::strategy::Strategy::prop_map(
+            $crate::proptest_helper!(@_EXT _STRAT ($($arg)*)),
+            |values| $crate::sugar::NamedArguments(names, values));
+        let test = $crate::sugar::test_fn_for(
+            &strategy,
             $($mod)* |$crate::sugar::NamedArguments(
                 _, $crate::proptest_helper!(@_EXT _PAT ($($arg)*)))|
             {
                 let (): () = $body;
                 Ok(())
-            })
-        {
+            });
+        $(
+            if let Err(e) = runner.run_value(
+                $crate::sugar::NamedArguments(
+                    names, $crate::sugar::NestCase::nest_case($case)),
+                &test)
+            {
+                panic!("{}\n{}", e, runner);
+            }
+        )*
+        match runner.run(&strategy, test) {
             Ok(()) => (),
             Err(e) => panic!("{}\n{}", e, runner),
         }
@@ -1067,6 +2110,70 @@ macro_rules! proptest_helper {
     };
 }
 
+/// The strategies of a `prop_compose!` function marked `#[cache]`, constructed
+/// on first use and leaked for the rest of the process.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub struct StrategyCache(
+    std::sync::Mutex<Option<&'static (dyn std::any::Any + Send + Sync)>>,
+);
+
+#[cfg(feature = "std")]
+impl StrategyCache {
+    #[doc(hidden)]
+    pub const fn new() -> Self {
+        StrategyCache(std::sync::Mutex::new(None))
+    }
+
+    #[doc(hidden)]
+    pub fn get_or_init<S: Send + Sync + 'static>(
+        &'static self,
+        init: impl FnOnce() -> S,
+    ) -> &'static S {
+        let cached = *self.lock();
+        let cached = cached.unwrap_or_else(|| {
+            // The lock isn't held while constructing the strategies, which may
+            // well use other cached functions. If two threads race to do so,
+            // the strategies of the first to finish are kept.
+            let strat = init();
+            *self.lock().get_or_insert_with(|| {
+                std::boxed::Box::leak(std::boxed::Box::new(strat))
+            })
+        });
+        cached
+            .downcast_ref()
+            .expect("Strategies of a cached function changed type")
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<
+        '_,
+        Option<&'static (dyn std::any::Any + Send + Sync)>,
+    > {
+        // The cache is only ever updated as a whole, so a panic while it's
+        // locked can't leave it inconsistent.
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct ModuleConfigKey;
+
+#[doc(hidden)]
+pub trait GlobalDefaultConfig {
+    fn __proptest_module_config(&self) -> crate::test_runner::Config;
+}
+
+impl GlobalDefaultConfig for &ModuleConfigKey {
+    fn __proptest_module_config(&self) -> crate::test_runner::Config {
+        crate::test_runner::Config::default()
+    }
+}
+
 #[doc(hidden)]
 #[derive(Clone, Copy)]
 pub struct NamedArguments<N, V>(#[doc(hidden)] pub N, #[doc(hidden)] pub V);
@@ -1078,64 +2185,344 @@ impl<V: fmt::Debug> fmt::Debug for NamedArguments<&'static str, V> {
     }
 }
 
-macro_rules! named_arguments_tuple {
-    ($($ix:tt $argn:ident $argv:ident)*) => {
-        impl<'a, $($argn : Copy),*, $($argv),*> fmt::Debug
-        for NamedArguments<($($argn,)*),&'a ($($argv,)*)>
-        where $(NamedArguments<$argn, &'a $argv> : fmt::Debug),*,
-              $($argv : 'a),*
+macro_rules! named_arguments_tuple {
+    ($($ix:tt $argn:ident $argv:ident)*) => {
+        impl<'a, $($argn : Copy),*, $($argv),*> fmt::Debug
+        for NamedArguments<($($argn,)*),&'a ($($argv,)*)>
+        where $(NamedArguments<$argn, &'a $argv> : fmt::Debug),*,
+              $($argv : 'a),*
+        {
+            #[allow(unused_assignments)]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let mut first = true;
+                $(
+                    if !first {
+                        write!(f, ", ")?;
+                    }
+                    first = false;
+                    fmt::Debug::fmt(
+                        &NamedArguments((self.0).$ix, &(self.1).$ix), f)?;
+                )*
+                Ok(())
+            }
+        }
+
+        impl<$($argn : Copy),*, $($argv),*> fmt::Debug
+        for NamedArguments<($($argn,)*), ($($argv,)*)>
+        where $(for<'a> NamedArguments<$argn, &'a $argv> : fmt::Debug),*
+        {
+            #[allow(unused_assignments)]
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let mut first = true;
+                $(
+                    if !first {
+                        write!(f, ", ")?;
+                    }
+                    first = false;
+                    fmt::Debug::fmt(
+                        &NamedArguments((self.0).$ix, &(self.1).$ix), f)?;
+                )*
+                Ok(())
+            }
+        }
+    }
+}
+
+named_arguments_tuple!(0 AN AV);
+named_arguments_tuple!(0 AN AV 1 BN BV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
+                       5 FN FV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
+                       5 FN FV 6 GN GV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
+                       5 FN FV 6 GN GV 7 HN HV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
+                       5 FN FV 6 GN GV 7 HN HV 8 IN IV);
+named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
+                       5 FN FV 6 GN GV 7 HN HV 8 IN IV 9 JN JV);
+
+/// Identity function which pins the argument type of the test closure to the
+/// value type of `strategy`, so that the closure can be defined once and then
+/// be used both for literal `cases` and the random run.
+#[doc(hidden)]
+pub fn test_fn_for<S, F>(_strategy: &S, test: F) -> F
+where
+    S: crate::strategy::Strategy,
+    F: Fn(S::Value) -> crate::test_runner::TestCaseResult,
+{
+    test
+}
+
+/// Selects the values of a parameter of a `#[property_test(also_exhaustive_small)]`
+/// test: `SmallValues` where the type implements it, and otherwise the
+/// simplest value of `any::<T>()`.
+///
+/// The choice is made by autoref specialisation: with both `ViaSmallValues`
+/// and `ViaSimplestValue` in scope, `small_values()` on a
+/// `&SmallValuesOf<T>` only falls back to the impl for `&SmallValuesOf<T>` if
+/// the one for `SmallValuesOf<T>` doesn't apply.
+#[doc(hidden)]
+pub struct SmallValuesOf<T>(core::marker::PhantomData<T>);
+
+impl<T> SmallValuesOf<T> {
+    pub fn new() -> Self {
+        SmallValuesOf(core::marker::PhantomData)
+    }
+}
+
+impl<T> Default for SmallValuesOf<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaSmallValues<T> {
+    fn small_values(&self) -> Vec<T>;
+}
+
+impl<T: crate::arbitrary::SmallValues> ViaSmallValues<T> for SmallValuesOf<T> {
+    fn small_values(&self) -> Vec<T> {
+        T::small_values()
+    }
+}
+
+#[doc(hidden)]
+pub trait ViaSimplestValue<T> {
+    fn small_values(&self) -> Vec<T>;
+}
+
+impl<T: crate::arbitrary::Arbitrary> ViaSimplestValue<T> for &SmallValuesOf<T> {
+    fn small_values(&self) -> Vec<T> {
+        vec![simplest_value(&crate::arbitrary::any::<T>())]
+    }
+}
+
+/// Returns the value `strategy` shrinks to when every simplification is
+/// accepted, starting from a deterministic case.
+#[doc(hidden)]
+pub fn simplest_value<S: crate::strategy::Strategy>(strategy: &S) -> S::Value {
+    use crate::strategy::ValueTree;
+
+    let mut runner = crate::test_runner::TestRunner::deterministic();
+    let mut tree = strategy
+        .new_tree(&mut runner)
+        .unwrap_or_else(|why| panic!("Failed to generate a value: {}", why));
+    while tree.simplify() {}
+    tree.current()
+}
+
+/// Runs `test` against every combination of small values, as generated by
+/// `#[property_test(also_exhaustive_small)]`.
+///
+/// `counts` holds the number of values of each parameter, and `make` creates
+/// the arguments for one index per parameter. Rejected cases are skipped,
+/// while the first failing or (with `std`) panicking case panics, described
+/// by `describe`.
+#[doc(hidden)]
+pub fn run_small_values<V>(
+    counts: &[usize],
+    make: impl Fn(&[usize]) -> V,
+    describe: impl Fn(&V) -> String,
+    test: impl Fn(V) -> crate::test_runner::TestCaseResult,
+) {
+    if counts.contains(&0) {
+        return;
+    }
+
+    let mut indices = vec![0; counts.len()];
+    loop {
+        let value = make(&indices);
+        let description = describe(&value);
+        #[cfg(feature = "std")]
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                test(value)
+            }))
+            .unwrap_or_else(|what| {
+                let why: String = what
+                    .downcast::<&'static str>()
+                    .map(|s| (*s).into())
+                    .or_else(|what| what.downcast::<String>().map(|b| *b))
+                    .unwrap_or_else(|_| "<unknown panic value>".into());
+                Err(crate::test_runner::TestCaseError::fail(why))
+            });
+        #[cfg(not(feature = "std"))]
+        let result = test(value);
+        if let Err(crate::test_runner::TestCaseError::Fail(why)) = result {
+            panic!("Test failed: {}; small value input: {}", why, description);
+        }
+
+        // Advance to the next combination, the last parameter varying fastest
+        let mut position = indices.len();
+        loop {
+            if 0 == position {
+                return;
+            }
+            position -= 1;
+            indices[position] += 1;
+            if indices[position] < counts[position] {
+                break;
+            }
+            indices[position] = 0;
+        }
+    }
+}
+
+/// Formats named arguments like the failure output of `proptest!`, e.g.
+/// `x = 1, y = "a"`.
+#[doc(hidden)]
+pub fn describe_args(args: &[(&str, &dyn fmt::Debug)]) -> String {
+    let mut description = String::new();
+    for (index, &(name, value)) in args.iter().enumerate() {
+        if index > 0 {
+            description.push_str(", ");
+        }
+        description.push_str(&format!("{} = {:?}", name, value));
+    }
+    description
+}
+
+/// Describes the first adjacent pair of `items` which is out of order, for
+/// `prop_assert_sorted!`.
+#[doc(hidden)]
+pub fn find_unsorted<I>(items: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: fmt::Debug + PartialOrd,
+{
+    let mut items = items.into_iter().enumerate();
+    let (_, mut prev) = items.next()?;
+    for (index, item) in items {
+        // Incomparable items, such as NaN, are out of order too.
+        if !matches!(
+            prev.partial_cmp(&item),
+            Some(core::cmp::Ordering::Less | core::cmp::Ordering::Equal)
+        ) {
+            return Some(format!(
+                "items[{}] = `{:?}` is not <= items[{}] = `{:?}`",
+                index - 1,
+                prev,
+                index,
+                item
+            ));
+        }
+        prev = item;
+    }
+
+    None
+}
+
+/// Describes the first item of `left` or `right` without a counterpart in
+/// the other, for `prop_assert_permutation!`.
+#[doc(hidden)]
+pub fn find_permutation_mismatch<L, R>(left: L, right: R) -> Option<String>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: fmt::Debug + PartialEq<R::Item>,
+    R::Item: fmt::Debug,
+{
+    let right = right.into_iter().collect::<Vec<_>>();
+    let mut matched = vec![false; right.len()];
+    for (index, item) in left.into_iter().enumerate() {
+        match (0..right.len()).find(|&i| !matched[i] && item == right[i]) {
+            Some(i) => matched[i] = true,
+            None => {
+                return Some(format!(
+                    "left[{}] = `{:?}` has no remaining match in right",
+                    index, item
+                ))
+            }
+        }
+    }
+
+    let index = matched.iter().position(|&matched| !matched)?;
+    Some(format!(
+        "right[{}] = `{:?}` has no match in left",
+        index, right[index]
+    ))
+}
+
+/// Describes the first item of `left` which does not occur in `right`, for
+/// `prop_assert_subset!`.
+#[doc(hidden)]
+pub fn find_missing<L, R>(left: L, right: R) -> Option<String>
+where
+    L: IntoIterator,
+    R: IntoIterator,
+    L::Item: fmt::Debug + PartialEq<R::Item>,
+{
+    let right = right.into_iter().collect::<Vec<_>>();
+    left.into_iter()
+        .enumerate()
+        .find(|(_, item)| !right.iter().any(|r| *item == *r))
+        .map(|(index, item)| {
+            format!("left[{}] = `{:?}` is not in right", index, item)
+        })
+}
+
+/// Converts a flat tuple as written in a `cases: [...]` clause into the
+/// right-nested tuple used to represent `pattern: type` arguments.
+#[doc(hidden)]
+pub trait NestCase<T> {
+    fn nest_case(self) -> T;
+}
+
+impl<T> NestCase<T> for T {
+    fn nest_case(self) -> T {
+        self
+    }
+}
+
+/// The inverse of `NestCase`, converting the right-nested tuple generated for
+/// the arguments of a test into a flat tuple, as returned by the strategy of a
+/// `strategy: <type>;` clause.
+#[doc(hidden)]
+pub trait FlattenCase<T> {
+    fn flatten_case(self) -> T;
+}
+
+impl<T> FlattenCase<T> for T {
+    fn flatten_case(self) -> T {
+        self
+    }
+}
+
+macro_rules! nest_case {
+    (@TY $a:ident $b:ident) => { ($a, $b) };
+    (@TY $a:ident $($rest:ident)+) => { ($a, nest_case!(@TY $($rest)+)) };
+
+    ($a:ident $b:ident) => {};
+    ($a:ident $($rest:ident)+) => {
+        #[allow(non_snake_case)]
+        impl<$a, $($rest),+> NestCase<nest_case!(@TY $a $($rest)+)>
+        for ($a, $($rest),+)
         {
-            #[allow(unused_assignments)]
-            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                let mut first = true;
-                $(
-                    if !first {
-                        write!(f, ", ")?;
-                    }
-                    first = false;
-                    fmt::Debug::fmt(
-                        &NamedArguments((self.0).$ix, &(self.1).$ix), f)?;
-                )*
-                Ok(())
+            fn nest_case(self) -> nest_case!(@TY $a $($rest)+) {
+                let ($a, $($rest),+) = self;
+                nest_case!(@TY $a $($rest)+)
             }
         }
 
-        impl<$($argn : Copy),*, $($argv),*> fmt::Debug
-        for NamedArguments<($($argn,)*), ($($argv,)*)>
-        where $(for<'a> NamedArguments<$argn, &'a $argv> : fmt::Debug),*
+        #[allow(non_snake_case)]
+        impl<$a, $($rest),+> FlattenCase<($a, $($rest),+)>
+        for nest_case!(@TY $a $($rest)+)
         {
-            #[allow(unused_assignments)]
-            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                let mut first = true;
-                $(
-                    if !first {
-                        write!(f, ", ")?;
-                    }
-                    first = false;
-                    fmt::Debug::fmt(
-                        &NamedArguments((self.0).$ix, &(self.1).$ix), f)?;
-                )*
-                Ok(())
+            fn flatten_case(self) -> ($a, $($rest),+) {
+                let nest_case!(@TY $a $($rest)+) = self;
+                ($a, $($rest),+)
             }
         }
-    }
+
+        nest_case!($($rest)+);
+    };
 }
 
-named_arguments_tuple!(0 AN AV);
-named_arguments_tuple!(0 AN AV 1 BN BV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
-                       5 FN FV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
-                       5 FN FV 6 GN GV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
-                       5 FN FV 6 GN GV 7 HN HV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
-                       5 FN FV 6 GN GV 7 HN HV 8 IN IV);
-named_arguments_tuple!(0 AN AV 1 BN BV 2 CN CV 3 DN DV 4 EN EV
-                       5 FN FV 6 GN GV 7 HN HV 8 IN IV 9 JN JV);
+nest_case!(A B C D E F G H I J);
 
 #[cfg(feature = "std")]
 #[doc(hidden)]
@@ -1161,6 +2548,81 @@ pub fn force_no_fork(config: &mut crate::test_runner::Config) {
 #[cfg(not(feature = "std"))]
 pub fn force_no_fork(_: &mut crate::test_runner::Config) {}
 
+/// The most lines of either side `check_roundtrip` diffs; beyond that, the
+/// diff would cost too much time and be too long to read anyway.
+const MAX_DIFF_LINES: usize = 1000;
+
+/// Fails unless `decoded` equals `value`, explaining the difference.
+#[doc(hidden)]
+pub fn check_roundtrip<T: PartialEq + fmt::Debug, S: fmt::Debug>(
+    value: &T,
+    serialized: &S,
+    decoded: &T,
+) -> Result<(), crate::test_runner::TestCaseError> {
+    if value == decoded {
+        return Ok(());
+    }
+
+    let diff = line_diff(&format!("{:#?}", value), &format!("{:#?}", decoded));
+    Err(crate::test_runner::TestCaseError::fail(format!(
+        "round trip changed the value\
+         \n  original: `{:?}`,\
+         \nserialized: `{:?}`,\
+         \n   decoded: `{:?}`\
+         \ndiff (- original, + decoded):\n{}",
+        value, serialized, decoded, diff
+    )))
+}
+
+/// Returns a line-by-line diff from `old` to `new`, marking removed lines with
+/// `-` and added lines with `+`.
+fn line_diff(old: &str, new: &str) -> crate::std_facade::String {
+    use crate::std_facade::{String, Vec};
+
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len() > MAX_DIFF_LINES || new.len() > MAX_DIFF_LINES {
+        return String::from("(values too large to diff)\n");
+    }
+
+    // lcs[i][j] is the length of the longest common subsequence of old[i..]
+    // and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        let (mark, line) = if i < old.len() && j < new.len() && old[i] == new[j]
+        {
+            i += 1;
+            j += 1;
+            (' ', old[i - 1])
+        } else if j == new.len()
+            || (i < old.len() && lcs[i + 1][j] >= lcs[i][j + 1])
+        {
+            i += 1;
+            ('-', old[i - 1])
+        } else {
+            j += 1;
+            ('+', new[j - 1])
+        };
+        diff.push(mark);
+        diff.push(' ');
+        diff.push_str(line);
+        diff.push('\n');
+    }
+    diff
+}
+
 #[cfg(test)]
 mod test {
     use crate::strategy::Just;
@@ -1241,6 +2703,358 @@ mod test {
         }
     }
 
+    #[cfg(feature = "std")]
+    mod cached {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use crate::strategy::{Just, Strategy, ValueTree};
+        use crate::test_runner::TestRunner;
+
+        static CONSTRUCTED: AtomicUsize = AtomicUsize::new(0);
+
+        fn counted() -> impl Strategy<Value = u32> + Send + Sync {
+            CONSTRUCTED.fetch_add(1, Ordering::SeqCst);
+            0u32..100
+        }
+
+        prop_compose! {
+            #[cache]
+            /// These are docs!
+            fn cached_once()(a in counted(), b in 0u32..10) -> u32 {
+                a + b
+            }
+        }
+
+        prop_compose! {
+            #[cache]
+            #[allow(dead_code)]
+            fn cached_two_layers()(b in 1..1000)(a in 0..b, b in Just(b))
+                                 -> (i32, i32) {
+                (a, b)
+            }
+        }
+
+        prop_compose! {
+            #[cache]
+            #[allow(dead_code)]
+            pub(crate) fn cached_typed()(a: u8, b in 0u8..10) -> u16 {
+                u16::from(a) + u16::from(b)
+            }
+        }
+
+        #[test]
+        fn strategies_are_constructed_once() {
+            let mut runner = TestRunner::deterministic();
+            for _ in 0..10 {
+                let value = cached_once().new_tree(&mut runner).unwrap();
+                assert!(value.current() < 110);
+            }
+            assert_eq!(1, CONSTRUCTED.load(Ordering::SeqCst));
+        }
+    }
+
+    mod remote {
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct Public {
+            pub a: u8,
+            pub b: bool,
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Private(u32);
+
+        impl Private {
+            pub fn new(a: u32, b: u32) -> Self {
+                Private(a * 10 + b)
+            }
+
+            pub fn get(&self) -> u32 {
+                self.0
+            }
+        }
+    }
+
+    arbitrary_remote! {
+        struct PublicDef for remote::Public {
+            a in 0u8..10,
+            b in crate::bool::ANY,
+        }
+    }
+
+    arbitrary_remote! {
+        struct PrivateDef for self::remote::Private {
+            a in 0u32..10,
+            b in 0u32..10,
+        } => remote::Private::new(a, b)
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_remote_generates_remote_types(
+            public in PublicDef::arbitrary(),
+            private in PrivateDef::arbitrary(),
+        ) {
+            prop_assert!(public.a < 10);
+            prop_assert!(private.get() < 100);
+        }
+    }
+
+    mod module_default_config {
+        use crate::test_runner::Config;
+
+        proptest_default_config!(Config {
+            cases: 7,
+            max_shrink_iters: 3,
+            ..
+        });
+
+        #[test]
+        fn registered_config_is_used() {
+            let config = proptest_helper!(@_DEFAULT_CONFIG);
+            assert_eq!(7, config.cases);
+            assert_eq!(3, config.max_shrink_iters);
+        }
+
+        proptest! {
+            #[test]
+            fn block_form_compiles_with_registered_config(a in 0..10) {
+                prop_assert!(a < 10);
+            }
+        }
+
+        mod inherited {
+            use super::*;
+
+            #[test]
+            fn child_module_inherits_config() {
+                assert_eq!(7, proptest_helper!(@_DEFAULT_CONFIG).cases);
+            }
+        }
+
+        mod overridden {
+            use super::*;
+
+            proptest_default_config!(Config::with_cases(9));
+
+            #[test]
+            fn child_module_can_override_config() {
+                assert_eq!(9, proptest_helper!(@_DEFAULT_CONFIG).cases);
+                proptest!(|(a in 0..10)| {
+                    prop_assert!(a < 10);
+                });
+            }
+        }
+
+        mod unrelated {
+            #[test]
+            fn modules_without_registration_use_default() {
+                assert_eq!(
+                    crate::test_runner::Config::default(),
+                    proptest_helper!(@_DEFAULT_CONFIG)
+                );
+            }
+        }
+    }
+
+    mod literal_cases {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        static SAW_LITERAL: AtomicBool = AtomicBool::new(false);
+
+        proptest! {
+            fn records_literal(x in 0..10u32) {
+                if 1234 == x {
+                    SAW_LITERAL.store(true, Ordering::SeqCst);
+                }
+            }
+            cases: [1234]
+
+            fn fails_on_literal(x in 0..10u32, y in 0..10u32) {
+                prop_assert!(x < 10 && y < 10);
+            }
+            cases: [(1, 2), (3, 77),]
+
+            fn fails_on_typed_literal(a: u8, (b): u8, c in 0..10u8) {
+                prop_assert!(c < 10, "{} {}", a, b);
+            }
+            cases: [(1, 2, 30)]
+        }
+
+        #[test]
+        fn literal_cases_are_run() {
+            records_literal();
+            assert!(SAW_LITERAL.load(Ordering::SeqCst));
+        }
+
+        #[test]
+        #[should_panic(expected = "x = 3, y = 77")]
+        fn failing_literal_case_is_reported() {
+            fails_on_literal();
+        }
+
+        #[test]
+        #[should_panic(expected = "a = 1, b = 2, c = 30")]
+        fn typed_arguments_accept_flat_cases() {
+            fails_on_typed_literal();
+        }
+    }
+
+    mod examples {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        static FIRST_SEEN: AtomicU32 = AtomicU32::new(0);
+
+        proptest! {
+            /// Attributes around `#[examples]` are kept.
+            #[allow(unused_variables)]
+            #[examples(1234, 5678)]
+            #[allow(unused_mut)]
+            fn records_first(x in 0..10u32) {
+                let _ = FIRST_SEEN.compare_exchange(
+                    0, x, Ordering::SeqCst, Ordering::SeqCst);
+            }
+            cases: [9999]
+
+            #[examples((1, 2), (0, 0), (u32::MAX, 1),)]
+            fn fails_on_example(a in 0..10u32, b in 0..10u32) {
+                prop_assert!(a < 10 && b < 10);
+            }
+
+            #[examples((7, false))]
+            fn fails_on_typed_example(a: u8, b in crate::bool::ANY) {
+                prop_assert!(a != 7 || b);
+            }
+        }
+
+        #[test]
+        fn examples_run_before_other_cases() {
+            records_first();
+            assert_eq!(1234, FIRST_SEEN.load(Ordering::SeqCst));
+        }
+
+        #[test]
+        #[should_panic(expected = "a = 4294967295, b = 1")]
+        fn failing_example_is_reported() {
+            fails_on_example();
+        }
+
+        #[test]
+        #[should_panic(expected = "a = 7, b = false")]
+        fn typed_arguments_accept_examples() {
+            fails_on_typed_example();
+        }
+    }
+
+    mod async_fn {
+        use core::future::Future;
+        use core::pin::Pin;
+        use core::task::{Context, Poll};
+
+        /// A future which is pending once before it is ready.
+        struct YieldOnce(bool);
+
+        impl Future for YieldOnce {
+            type Output = ();
+
+            fn poll(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+            ) -> Poll<()> {
+                if self.0 {
+                    Poll::Ready(())
+                } else {
+                    self.0 = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            }
+        }
+
+        async fn add(a: u32, b: u32) -> u32 {
+            YieldOnce(false).await;
+            a + b
+        }
+
+        proptest! {
+            async fn awaits_in_body(a in 0..1000u32, b in 0..1000u32) {
+                prop_assert_eq!(a + b, add(a, b).await);
+            }
+
+            #[examples((3, 4))]
+            async fn fails_in_body(a: u8, b in 0..10u32) {
+                prop_assert!(add(u32::from(a), b).await != 7);
+            }
+        }
+
+        #[test]
+        fn async_body_is_run() {
+            awaits_in_body();
+        }
+
+        #[test]
+        #[should_panic(expected = "a = 3, b = 4")]
+        fn async_failure_is_reported() {
+            fails_in_body();
+        }
+    }
+
+    mod exposed_strategy {
+        use crate::strategy::{Strategy, ValueTree};
+        use crate::test_runner::TestRunner;
+        use std::string::{String, ToString};
+
+        const LIMIT: u32 = 10;
+
+        proptest! {
+            fn single_arg(x in 0..LIMIT) {
+                prop_assert!(x < LIMIT);
+            }
+            strategy: u32;
+
+            fn literal_and_exposed(x in 0..10u32, y in "[a-z]") {
+                prop_assert!(x < 10 && 1 == y.len());
+            }
+            cases: [(1, "a".to_string())]
+            strategy: (u32, String);
+
+            fn typed_args(a: u8, (b): bool, c in 5..10i64) {
+                prop_assert!(c >= 5, "{} {}", a, b);
+            }
+            strategy: (u8, bool, i64);
+        }
+
+        #[test]
+        fn tests_still_run() {
+            single_arg();
+            literal_and_exposed();
+            typed_args();
+        }
+
+        #[test]
+        fn strategies_generate_test_arguments() {
+            let mut runner = TestRunner::deterministic();
+            for _ in 0..64 {
+                let x = single_arg::strategy()
+                    .new_tree(&mut runner)
+                    .unwrap()
+                    .current();
+                assert!(x < LIMIT);
+
+                let (x, y) = literal_and_exposed::strategy()
+                    .new_tree(&mut runner)
+                    .unwrap()
+                    .current();
+                assert!(x < 10 && 1 == y.len());
+
+                let (_, _, c): (u8, bool, i64) = typed_args::strategy()
+                    .new_tree(&mut runner)
+                    .unwrap()
+                    .current();
+                assert!((5..10).contains(&c));
+            }
+        }
+    }
+
     #[allow(unused_variables)]
     mod test_arg_counts {
         use crate::strategy::Just;
@@ -1342,6 +3156,187 @@ mod test {
         );
     }
 
+    #[test]
+    fn prop_assert_close_reports_differences() {
+        use crate::test_runner::{TestCaseError, TestCaseResult};
+
+        fn check(a: f64, b: f64) -> TestCaseResult {
+            prop_assert_close!(a, b, ulps <= 2);
+            prop_assert_close!(a, b, abs <= 1e-15, "a = {}", a);
+            Ok(())
+        }
+
+        assert!(check(1.0, 1.0 + 2.0 * f64::EPSILON).is_ok());
+        match check(1.0, 1.5) {
+            Err(TestCaseError::Fail(why)) => {
+                let message = why.message();
+                assert!(message.contains("(ulps <= 2)"), "{}", message);
+                assert!(
+                    message
+                        .contains("diff: ulps = 2251799813685248, abs = 5e-1"),
+                    "{}",
+                    message
+                );
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn collection_assertions_pinpoint_first_offender() {
+        use crate::test_runner::{TestCaseError, TestCaseResult};
+
+        fn message(result: TestCaseResult) -> std::string::String {
+            match result {
+                Err(TestCaseError::Fail(why)) => why.message().into(),
+                r => panic!("unexpected result: {:?}", r),
+            }
+        }
+
+        fn sorted(items: &[f64]) -> TestCaseResult {
+            prop_assert_sorted!(items);
+            Ok(())
+        }
+        fn permutation(left: &[u32], right: &[u32]) -> TestCaseResult {
+            prop_assert_permutation!(left, right, "n = {}", 3);
+            Ok(())
+        }
+        fn subset(left: &[u32], right: &[u32]) -> TestCaseResult {
+            prop_assert_subset!(left, right);
+            Ok(())
+        }
+
+        assert!(sorted(&[]).is_ok());
+        assert!(sorted(&[1.0, 1.0, 2.0]).is_ok());
+        let why = message(sorted(&[1.0, 3.0, 2.0, 0.0]));
+        assert!(why.contains("items[1] = `3.0` is not <= items[2] = `2.0`"));
+        let why = message(sorted(&[1.0, f64::NAN]));
+        assert!(why.contains("items[0] = `1.0` is not <= items[1] = `NaN`"));
+
+        assert!(permutation(&[1, 2, 2, 3], &[2, 3, 1, 2]).is_ok());
+        let why = message(permutation(&[1, 2, 2], &[2, 1, 1]));
+        assert!(why.contains("left[2] = `2` has no remaining match in right"));
+        assert!(why.contains(": n = 3 at "));
+        let why = message(permutation(&[1, 2], &[2, 1, 4]));
+        assert!(why.contains("right[2] = `4` has no match in left"));
+
+        assert!(subset(&[2, 2, 1], &[1, 2, 3]).is_ok());
+        let why = message(subset(&[2, 5, 1, 7], &[1, 2, 3]));
+        assert!(why.contains("left[1] = `5` is not in right"));
+    }
+
+    #[test]
+    fn prop_roundtrip_passes_on_faithful_codecs() {
+        use std::string::{String, ToString};
+        use std::vec::Vec;
+
+        prop_roundtrip!(
+            0..1000u32,
+            |n: &u32| n.to_string(),
+            fallible |s: &String| s.parse::<u32>(),
+        );
+        prop_roundtrip!(
+            crate::test_runner::Config::with_cases(8),
+            crate::collection::vec(0..10u8, 0..8),
+            |v: &Vec<u8>| v.iter().rev().cloned().collect::<Vec<_>>(),
+            |v: &Vec<u8>| v.iter().rev().cloned().collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "deserialize failed")]
+    fn prop_roundtrip_fails_on_deserialize_error() {
+        use std::string::{String, ToString};
+
+        prop_roundtrip!(
+            0..10u32,
+            |n: &u32| n.to_string(),
+            fallible |_: &String| "x".parse::<u32>(),
+        );
+    }
+
+    #[test]
+    fn check_roundtrip_reports_diff() {
+        use super::check_roundtrip;
+        use crate::test_runner::TestCaseError;
+
+        assert!(check_roundtrip(&vec![1, 2], &"1,2", &vec![1, 2]).is_ok());
+        match check_roundtrip(&vec![1, 2, 3], &"1,2,3", &vec![1, 4, 3]) {
+            Err(TestCaseError::Fail(why)) => {
+                let message = why.message();
+                assert!(
+                    message.contains("serialized: `\"1,2,3\"`"),
+                    "{}",
+                    message
+                );
+                assert!(
+                    message.ends_with(
+                        "[\n      1,\n-     2,\n+     4,\n      3,\n  ]\n"
+                    ),
+                    "{}",
+                    message
+                );
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn small_values_fall_back_to_simplest_value() {
+        use super::{
+            SmallValuesOf, ViaSimplestValue as _, ViaSmallValues as _,
+        };
+
+        let of = &SmallValuesOf::<u8>::new();
+        assert_eq!(vec![0u8, 1, 255], of.small_values());
+        let of = &SmallValuesOf::<Option<bool>>::new();
+        assert_eq!(vec![None, Some(false), Some(true)], of.small_values());
+        // Tuples don't implement `SmallValues`
+        let of = &SmallValuesOf::<(u8, bool)>::new();
+        assert_eq!(vec![(0u8, false)], of.small_values());
+        assert_eq!(3, super::simplest_value(&(3..10u32)));
+    }
+
+    #[test]
+    fn run_small_values_enumerates_all_combinations() {
+        use super::{describe_args, run_small_values};
+        use crate::std_facade::{String, ToOwned, Vec};
+        use std::cell::RefCell;
+
+        let seen = RefCell::new(Vec::new());
+        run_small_values(
+            &[2, 3],
+            |indices| (indices[0], indices[1]),
+            |&(a, b)| describe_args(&[("a", &a), ("b", &b)]),
+            |case| {
+                seen.borrow_mut().push(case);
+                prop_assume!(case.1 != 1);
+                Ok(())
+            },
+        );
+        assert_eq!(
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)],
+            seen.into_inner()
+        );
+
+        let failure = std::panic::catch_unwind(|| {
+            run_small_values(
+                &[3],
+                |indices| indices[0],
+                |a| describe_args(&[("a", a)]),
+                |a| {
+                    assert!(a < 2, "too big");
+                    Ok(())
+                },
+            )
+        })
+        .unwrap_err();
+        assert_eq!(
+            Some(&"Test failed: too big; small value input: a = 2".to_owned()),
+            failure.downcast_ref::<String>()
+        );
+    }
+
     #[test]
     fn oneof_all_counts() {
         use crate::strategy::{Just as J, Strategy, TupleUnion, Union};
@@ -0,0 +1,170 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A minimal executor for the bodies of `async` tests.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crate::std_facade::Box;
+use crate::test_runner::{TestCaseError, TestCaseResult};
+
+/// Wakes the test thread by unparking it.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs the future of an `async` test case to completion on the current
+/// thread.
+///
+/// If `timeout` is not 0 and the future is still pending after `timeout`
+/// milliseconds, it is dropped, cancelling whatever it was waiting on, and the
+/// test case fails. `timeout` is normally `Config::timeout()`.
+///
+/// This is what the `proptest!` macro and `#[property_test]` use to run the
+/// bodies of `async fn` tests. It drives the future by itself, so it suits
+/// futures which do not rely on the reactor of a specific runtime, such as
+/// channels or hand-written futures; tests needing e.g. tokio's timers or IO
+/// should use `#[property_test(async = "tokio")]` instead.
+pub fn block_on_case<F>(timeout: u32, future: F) -> TestCaseResult
+where
+    F: Future<Output = TestCaseResult>,
+{
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let deadline = if timeout > 0 {
+        Some(Instant::now() + Duration::from_millis(timeout.into()))
+    } else {
+        None
+    };
+
+    loop {
+        if let Poll::Ready(result) = future.as_mut().poll(&mut context) {
+            return result;
+        }
+
+        match deadline {
+            None => thread::park(),
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(TestCaseError::fail(format!(
+                        "Timeout of {} ms exceeded: the test future was \
+                         cancelled",
+                        timeout
+                    )));
+                }
+                thread::park_timeout(deadline - now);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+
+    /// A future which is ready once another thread has woken it.
+    struct WokenLater {
+        woken: Arc<AtomicBool>,
+        spawned: bool,
+    }
+
+    impl Future for WokenLater {
+        type Output = TestCaseResult;
+
+        fn poll(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Self::Output> {
+            if self.woken.load(Ordering::SeqCst) {
+                return Poll::Ready(Ok(()));
+            }
+            if !self.spawned {
+                self.spawned = true;
+                let woken = Arc::clone(&self.woken);
+                let waker = cx.waker().clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(10));
+                    woken.store(true, Ordering::SeqCst);
+                    waker.wake();
+                });
+            }
+            Poll::Pending
+        }
+    }
+
+    /// A future which is never ready, recording whether it was dropped.
+    struct Never(Arc<AtomicBool>);
+
+    impl Future for Never {
+        type Output = TestCaseResult;
+
+        fn poll(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Self::Output> {
+            Poll::Pending
+        }
+    }
+
+    impl Drop for Never {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn returns_result_of_ready_future() {
+        assert!(block_on_case(0, async { Ok(()) }).is_ok());
+        match block_on_case(0, async { Err(TestCaseError::fail("nope")) }) {
+            Err(TestCaseError::Fail(reason)) => {
+                assert_eq!("nope", reason.message())
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn waits_until_woken() {
+        let future = WokenLater {
+            woken: Arc::new(AtomicBool::new(false)),
+            spawned: false,
+        };
+        assert!(block_on_case(0, future).is_ok());
+    }
+
+    #[test]
+    fn timeout_cancels_future() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let result = block_on_case(20, Never(Arc::clone(&dropped)));
+        match result {
+            Err(TestCaseError::Fail(reason)) => {
+                assert!(reason.message().contains("Timeout of 20 ms"))
+            }
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,93 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::Arc;
+use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+
+/// A bound on the overall complexity of the value generated for one test
+/// case, shared by all the strategies taking part in generating it.
+///
+/// The budget of the current case is available from `TestRunner::budget()`.
+/// Strategies which can produce arbitrarily large values draw from it in
+/// proportion to the size of what they produce, and fall back to their
+/// minimal values once it is used up. The built-in collection strategies
+/// draw their length, and recursive strategies draw one unit per level of
+/// nesting.
+///
+/// Clones of a `Budget` draw from the same pool, so a runner forked while
+/// generating a value still draws from the budget of the case.
+///
+/// ```
+/// use proptest::test_runner::Budget;
+///
+/// let budget = Budget::limited(10);
+/// assert_eq!(4, budget.draw(4));
+/// assert_eq!(6, budget.clone().draw(100));
+/// assert_eq!(0, budget.draw(1));
+/// assert!(budget.is_exhausted());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    remaining: Option<Arc<AtomicUsize>>,
+}
+
+impl Budget {
+    /// Create a budget which never runs out.
+    pub fn unlimited() -> Self {
+        Budget { remaining: None }
+    }
+
+    /// Create a budget of `limit` units.
+    pub fn limited(limit: usize) -> Self {
+        Budget {
+            remaining: Some(Arc::new(AtomicUsize::new(limit))),
+        }
+    }
+
+    /// Return how many units are left, or `None` if the budget is unlimited.
+    pub fn remaining(&self) -> Option<usize> {
+        self.remaining
+            .as_ref()
+            .map(|remaining| remaining.load(SeqCst))
+    }
+
+    /// Return whether nothing is left to draw.
+    pub fn is_exhausted(&self) -> bool {
+        Some(0) == self.remaining()
+    }
+
+    /// Take up to `amount` units from the budget, returning how many were
+    /// actually granted.
+    ///
+    /// This is `amount` unless the budget has less than that left, in which
+    /// case everything that is left is granted.
+    pub fn draw(&self, amount: usize) -> usize {
+        match self.remaining {
+            None => amount,
+            Some(ref remaining) => {
+                let before = remaining
+                    .fetch_update(SeqCst, SeqCst, |left| {
+                        Some(left - left.min(amount))
+                    })
+                    .unwrap_or_else(|left| left);
+                before.min(amount)
+            }
+        }
+    }
+
+    /// Draw the size of a collection from the budget, where `min` is the
+    /// smallest size allowed and `wanted` the size which would be used
+    /// without a budget.
+    ///
+    /// Returns `wanted` if the budget covers it, and otherwise however much
+    /// the budget has left, but never less than `min`.
+    pub fn draw_size(&self, min: usize, wanted: usize) -> usize {
+        min.max(self.draw(wanted))
+    }
+}
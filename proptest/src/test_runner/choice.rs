@@ -0,0 +1,239 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Low-level choice sources underlying `TestRng`.
+
+use crate::std_facade::{fmt, Arc, Box};
+
+use rand::Rng;
+
+use crate::test_runner::rng::TestRng;
+
+/// A source of the individual choices made while generating a value.
+///
+/// Every random decision a strategy makes can be phrased as "pick an integer
+/// in `0..=max`". A `ChoiceSource` answers those questions. The default
+/// source is proptest's own `TestRng`, but any other implementation can be
+/// plugged in with `TestRng::from_choice_source()`, at which point every
+/// existing strategy is driven by that source instead. This is how external
+/// fuzzers (AFL, libFuzzer, etc) can steer generation deterministically; see
+/// `ByteChoiceSource` for a ready-made adapter over fuzzer-provided bytes.
+///
+/// Implementations must be deterministic: the same sequence of questions must
+/// produce the same sequence of answers, otherwise shrinking and reproduction
+/// break down.
+pub trait ChoiceSource: fmt::Debug + Send + Sync {
+    /// Choose an integer in the inclusive range `0..=max`.
+    ///
+    /// Sources which run out of input should return `0`, the simplest
+    /// possible choice, rather than panicking.
+    fn choose_int(&mut self, max: u64) -> u64;
+
+    /// Choose an index into `weights`, where each index is chosen with
+    /// probability proportional to its weight.
+    ///
+    /// The default implementation reduces this to a single `choose_int()`
+    /// call over the sum of the weights.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `weights` is empty or all weights are zero.
+    fn choose_weighted(&mut self, weights: &[u32]) -> usize {
+        let total = weights.iter().map(|&w| u64::from(w)).sum::<u64>();
+        assert!(total > 0, "choose_weighted() called without any weight");
+
+        let mut pick = self.choose_int(total - 1);
+        for (ix, &weight) in weights.iter().enumerate() {
+            if pick < u64::from(weight) {
+                return ix;
+            }
+            pick -= u64::from(weight);
+        }
+
+        unreachable!("pick exceeded total weight")
+    }
+
+    /// Split off a new, independent source from this one.
+    ///
+    /// This is used wherever proptest derives a fresh RNG from an existing
+    /// one, for example in `prop_perturb()` and `prop_flat_map()`.
+    fn fork(&mut self) -> Box<dyn ChoiceSource>;
+
+    /// Clone this source, including its current position.
+    fn box_clone(&self) -> Box<dyn ChoiceSource>;
+}
+
+impl Clone for Box<dyn ChoiceSource> {
+    fn clone(&self) -> Self {
+        (**self).box_clone()
+    }
+}
+
+/// The RNG as a choice source, i.e., proptest's behaviour without any
+/// external source plugged in.
+impl ChoiceSource for TestRng {
+    fn choose_int(&mut self, max: u64) -> u64 {
+        self.gen_range(0..=max)
+    }
+
+    fn fork(&mut self) -> Box<dyn ChoiceSource> {
+        Box::new(self.gen_rng())
+    }
+
+    fn box_clone(&self) -> Box<dyn ChoiceSource> {
+        Box::new(self.clone())
+    }
+}
+
+/// A `ChoiceSource` which answers every choice directly from a stream of
+/// bytes, typically provided by a fuzzer.
+///
+/// Each call to `choose_int(max)` consumes just enough little-endian bytes to
+/// cover `max` and reduces them modulo `max + 1`. Once the data is depleted,
+/// every choice is `0`. This means that small mutations of the input produce
+/// small changes in the generated value, and that truncating the input tends
+/// to produce simpler values, both of which fuzzers exploit well.
+///
+/// As with `RngAlgorithm::PassThrough`, forking splits the remaining data
+/// evenly between the parent and the child.
+#[derive(Clone, Debug)]
+pub struct ByteChoiceSource {
+    off: usize,
+    end: usize,
+    data: Arc<[u8]>,
+}
+
+impl ByteChoiceSource {
+    /// Create a source answering choices from `data`.
+    pub fn new(data: impl Into<Arc<[u8]>>) -> Self {
+        let data = data.into();
+        ByteChoiceSource {
+            off: 0,
+            end: data.len(),
+            data,
+        }
+    }
+
+    /// Returns the number of bytes not yet consumed by this source.
+    pub fn remaining(&self) -> usize {
+        self.end - self.off
+    }
+}
+
+impl ChoiceSource for ByteChoiceSource {
+    fn choose_int(&mut self, max: u64) -> u64 {
+        let bits = 64 - max.leading_zeros() as usize;
+        let want = (bits + 7) / 8;
+        let take = want.min(self.remaining());
+
+        let mut buf = [0u8; 8];
+        buf[..take].copy_from_slice(&self.data[self.off..self.off + take]);
+        self.off += take;
+
+        let raw = u64::from_le_bytes(buf);
+        match max.checked_add(1) {
+            Some(modulus) => raw % modulus,
+            None => raw,
+        }
+    }
+
+    fn fork(&mut self) -> Box<dyn ChoiceSource> {
+        let child_start = self.off + self.remaining() / 2;
+        let child = ByteChoiceSource {
+            off: child_start,
+            end: self.end,
+            data: Arc::clone(&self.data),
+        };
+        self.end = child_start;
+        Box::new(child)
+    }
+
+    fn box_clone(&self) -> Box<dyn ChoiceSource> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::RngCore;
+
+    use super::*;
+    use crate::std_facade::Vec;
+    use crate::strategy::{Strategy, ValueTree};
+    use crate::test_runner::{Config, RngAlgorithm, TestRunner};
+
+    #[test]
+    fn byte_source_reads_minimal_bytes() {
+        let mut source = ByteChoiceSource::new(vec![7u8, 0x34, 0x12, 200]);
+        assert_eq!(7, source.choose_int(255));
+        assert_eq!(0x1234, source.choose_int(0xFFFF));
+        assert_eq!(200 % 3, source.choose_int(2));
+        assert_eq!(0, source.remaining());
+        // Depleted sources always make the simplest choice.
+        assert_eq!(0, source.choose_int(u64::MAX));
+    }
+
+    #[test]
+    fn zero_max_consumes_nothing() {
+        let mut source = ByteChoiceSource::new(vec![1u8]);
+        assert_eq!(0, source.choose_int(0));
+        assert_eq!(1, source.remaining());
+    }
+
+    #[test]
+    fn choose_weighted_respects_weights() {
+        let mut source = ByteChoiceSource::new(vec![0u8, 1, 2, 3]);
+        let weights = [1, 0, 3];
+        assert_eq!(0, source.choose_weighted(&weights));
+        assert_eq!(2, source.choose_weighted(&weights));
+        assert_eq!(2, source.choose_weighted(&weights));
+        assert_eq!(2, source.choose_weighted(&weights));
+    }
+
+    #[test]
+    fn test_rng_choices_are_in_bounds() {
+        let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+        for max in 0..100 {
+            assert!(rng.choose_int(max) <= max);
+        }
+        for _ in 0..100 {
+            assert_eq!(3, rng.choose_weighted(&[0, 0, 0, 5, 0]));
+        }
+    }
+
+    #[test]
+    fn byte_source_rng_matches_passthrough() {
+        let data = (0u8..=255).collect::<Vec<_>>();
+        let mut choices =
+            TestRng::from_choice_source(ByteChoiceSource::new(data.clone()));
+        let mut passthrough =
+            TestRng::from_seed(RngAlgorithm::PassThrough, &data);
+
+        for _ in 0..16 {
+            assert_eq!(passthrough.next_u32(), choices.next_u32());
+            assert_eq!(passthrough.next_u64(), choices.next_u64());
+        }
+    }
+
+    #[test]
+    fn strategies_are_driven_by_byte_source() {
+        let strategy = (0u32..1000, crate::collection::vec(0u8..10, 0..10));
+        let generate = |data: &[u8]| {
+            let rng = TestRng::from_choice_source(ByteChoiceSource::new(
+                data.to_vec(),
+            ));
+            let mut runner = TestRunner::new_with_rng(Config::default(), rng);
+            strategy.new_tree(&mut runner).unwrap().current()
+        };
+
+        let data = [17u8, 99, 4, 200, 3, 42, 42, 42, 1, 2, 3, 4, 5, 6];
+        assert_eq!(generate(&data), generate(&data));
+        assert_eq!((0, vec![]), generate(&[]));
+    }
+}
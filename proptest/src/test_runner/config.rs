@@ -7,13 +7,27 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::Box;
-use core::u32;
+use crate::std_facade::{Box, String, Vec};
+use core::{fmt, str, u32};
 
+#[cfg(feature = "coverage-guided")]
+use crate::test_runner::coverage::CoverageGuided;
 use crate::test_runner::result_cache::{noop_result_cache, ResultCache};
 use crate::test_runner::rng::RngAlgorithm;
 use crate::test_runner::FailurePersistence;
 
+/// Parses a shard given as `index/total`, e.g. `2/8`.
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+fn parse_shard(value: &str) -> Option<(u32, u32)> {
+    let (index, total) = value.split_once('/')?;
+    let shard = (index.trim().parse().ok()?, total.trim().parse().ok()?);
+    if 0 < shard.0 && shard.0 <= shard.1 {
+        Some(shard)
+    } else {
+        None
+    }
+}
+
 /// Override the config fields from environment variables, if any are set.
 /// Without the `std` feature this function returns config unchanged.
 #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
@@ -24,19 +38,39 @@ pub fn contextualize_config(mut result: Config) -> Config {
     use std::str::FromStr;
 
     const CASES: &str = "PROPTEST_CASES";
+    const MIRI_CASES: &str = "PROPTEST_MIRI_CASES";
+    const CASES_OVERRIDES: &str = "PROPTEST_CASES_OVERRIDES";
 
     const MAX_LOCAL_REJECTS: &str = "PROPTEST_MAX_LOCAL_REJECTS";
     const MAX_GLOBAL_REJECTS: &str = "PROPTEST_MAX_GLOBAL_REJECTS";
     const MAX_FLAT_MAP_REGENS: &str = "PROPTEST_MAX_FLAT_MAP_REGENS";
     const MAX_SHRINK_TIME: &str = "PROPTEST_MAX_SHRINK_TIME";
     const MAX_SHRINK_ITERS: &str = "PROPTEST_MAX_SHRINK_ITERS";
+    const MAX_SHRINK_REPEATS: &str = "PROPTEST_MAX_SHRINK_REPEATS";
+    const FAILURE_STABILITY_CHECKS: &str = "PROPTEST_FAILURE_STABILITY_CHECKS";
+    const SHRINK_METRICS: &str = "PROPTEST_SHRINK_METRICS";
+    const FAILURE_REPORT: &str = "PROPTEST_FAILURE_REPORT";
     const MAX_DEFAULT_SIZE_RANGE: &str = "PROPTEST_MAX_DEFAULT_SIZE_RANGE";
+    const MIN_RECURSIVE_DEPTH: &str = "PROPTEST_MIN_RECURSIVE_DEPTH";
+    const BUDGET: &str = "PROPTEST_BUDGET";
     #[cfg(feature = "fork")]
     const FORK: &str = "PROPTEST_FORK";
+    #[cfg(feature = "fork")]
+    const FORK_BACKEND: &str = "PROPTEST_FORK_BACKEND";
     #[cfg(feature = "timeout")]
     const TIMEOUT: &str = "PROPTEST_TIMEOUT";
+    #[cfg(feature = "failure-registry")]
+    const SHRINK_DUPLICATE_FAILURES: &str =
+        "PROPTEST_SHRINK_DUPLICATE_FAILURES";
+    #[cfg(feature = "chaos")]
+    const CHAOS: &str = "PROPTEST_CHAOS";
+    #[cfg(feature = "coverage-guided")]
+    const COVERAGE_GUIDED: &str = "PROPTEST_COVERAGE_GUIDED";
     const VERBOSE: &str = "PROPTEST_VERBOSE";
     const RNG_ALGORITHM: &str = "PROPTEST_RNG_ALGORITHM";
+    const SEED_SET: &str = "PROPTEST_SEED_SET";
+    const SHARD: &str = "PROPTEST_SHARD";
+    const REPLAY: &str = "PROPTEST_REPLAY";
     const DISABLE_FAILURE_PERSISTENCE: &str =
         "PROPTEST_DISABLE_FAILURE_PERSISTENCE";
 
@@ -65,6 +99,7 @@ pub fn contextualize_config(mut result: Config) -> Config {
         }
     }
 
+    let extensions = registered_extensions();
     for (var, value) in
         env::vars_os().filter_map(|(k, v)| k.into_string().ok().map(|k| (k, v)))
     {
@@ -76,14 +111,83 @@ pub fn contextualize_config(mut result: Config) -> Config {
             continue;
         }
 
+        #[cfg(feature = "fork")]
+        if var == FORK_BACKEND {
+            parse_or_warn(
+                &value,
+                &mut result.fork_backend,
+                "ForkBackend",
+                FORK_BACKEND,
+            );
+            continue;
+        }
+
         #[cfg(feature = "timeout")]
         if var == TIMEOUT {
             parse_or_warn(&value, &mut result.timeout, "timeout", TIMEOUT);
             continue;
         }
 
+        #[cfg(feature = "failure-registry")]
+        if var == SHRINK_DUPLICATE_FAILURES {
+            parse_or_warn(
+                &value,
+                &mut result.shrink_duplicate_failures,
+                "bool",
+                SHRINK_DUPLICATE_FAILURES,
+            );
+            continue;
+        }
+
+        #[cfg(feature = "chaos")]
+        if var == CHAOS {
+            let mut enabled = result.chaos.is_some();
+            parse_or_warn(&value, &mut enabled, "bool", CHAOS);
+            if !enabled {
+                result.chaos = None;
+            } else if result.chaos.is_none() {
+                result.chaos = Some(crate::chaos::Chaos::default());
+            }
+            continue;
+        }
+
+        #[cfg(feature = "coverage-guided")]
+        if var == COVERAGE_GUIDED {
+            let mut enabled = result.coverage_guided.is_some();
+            parse_or_warn(&value, &mut enabled, "bool", COVERAGE_GUIDED);
+            if !enabled {
+                result.coverage_guided = None;
+            } else if result.coverage_guided.is_none() {
+                result.coverage_guided = Some(CoverageGuided::default());
+            }
+            continue;
+        }
+
         if var == CASES {
             parse_or_warn(&value, &mut result.cases, "u32", CASES);
+        } else if var == MIRI_CASES {
+            parse_or_warn(&value, &mut result.miri_cases, "u32", MIRI_CASES);
+        } else if var == CASES_OVERRIDES {
+            let overrides = value.to_str().and_then(|value| {
+                value
+                    .split(',')
+                    .filter(|entry| !entry.trim().is_empty())
+                    .map(|entry| {
+                        let (pattern, cases) = entry.split_once('=')?;
+                        let cases = cases.trim().parse().ok()?;
+                        Some((String::from(pattern.trim()), cases))
+                    })
+                    .collect::<Option<Vec<(String, u32)>>>()
+            });
+            if let Some(overrides) = overrides {
+                result.cases_overrides = overrides;
+            } else {
+                eprintln!(
+                    "proptest: The env-var {}={:?} can't be parsed as a \
+                     comma-separated list of pattern=u32, ignoring it.",
+                    CASES_OVERRIDES, value
+                );
+            }
         } else if var == MAX_LOCAL_REJECTS {
             parse_or_warn(
                 &value,
@@ -119,6 +223,24 @@ pub fn contextualize_config(mut result: Config) -> Config {
                 "u32",
                 MAX_SHRINK_ITERS,
             );
+        } else if var == MAX_SHRINK_REPEATS {
+            parse_or_warn(
+                &value,
+                &mut result.max_shrink_repeats,
+                "u32",
+                MAX_SHRINK_REPEATS,
+            );
+        } else if var == FAILURE_STABILITY_CHECKS {
+            parse_or_warn(
+                &value,
+                &mut result.failure_stability_checks,
+                "u32",
+                FAILURE_STABILITY_CHECKS,
+            );
+        } else if var == SHRINK_METRICS {
+            result.shrink_metrics_file = Some(value.into());
+        } else if var == FAILURE_REPORT {
+            result.failure_report_path = Some(value.into());
         } else if var == MAX_DEFAULT_SIZE_RANGE {
             parse_or_warn(
                 &value,
@@ -126,6 +248,23 @@ pub fn contextualize_config(mut result: Config) -> Config {
                 "usize",
                 MAX_DEFAULT_SIZE_RANGE,
             );
+        } else if var == MIN_RECURSIVE_DEPTH {
+            parse_or_warn(
+                &value,
+                &mut result.min_recursive_depth,
+                "u32",
+                MIN_RECURSIVE_DEPTH,
+            );
+        } else if var == BUDGET {
+            if let Some(budget) = value.to_str().and_then(|v| v.parse().ok()) {
+                result.budget = Some(budget);
+            } else {
+                eprintln!(
+                    "proptest: The env-var {}={:?} can't be parsed as u32, \
+                     ignoring it.",
+                    BUDGET, value
+                );
+            }
         } else if var == VERBOSE {
             parse_or_warn(&value, &mut result.verbose, "u32", VERBOSE);
         } else if var == RNG_ALGORITHM {
@@ -135,8 +274,53 @@ pub fn contextualize_config(mut result: Config) -> Config {
                 "RngAlgorithm",
                 RNG_ALGORITHM,
             );
+        } else if var == SEED_SET {
+            let seeds = value.to_str().and_then(|value| {
+                value
+                    .split(',')
+                    .map(|seed| seed.trim().parse().ok())
+                    .collect::<Option<Vec<u64>>>()
+            });
+            if let Some(seeds) = seeds {
+                result.seed_set = Some(seeds);
+            } else {
+                eprintln!(
+                    "proptest: The env-var {}={:?} can't be parsed as a \
+                     comma-separated list of u64, ignoring it.",
+                    SEED_SET, value
+                );
+            }
+        } else if var == SHARD {
+            if let Some(shard) = value.to_str().and_then(parse_shard) {
+                result.shard = Some(shard);
+            } else {
+                eprintln!(
+                    "proptest: The env-var {}={:?} can't be parsed as a shard \
+                     index/total with 1 <= index <= total, ignoring it.",
+                    SHARD, value
+                );
+            }
+        } else if var == REPLAY {
+            parse_or_warn(&value, &mut result.replay, "ReplayMode", REPLAY);
         } else if var == DISABLE_FAILURE_PERSISTENCE {
             result.failure_persistence = None;
+        } else if let Some(&(name, ref parse)) =
+            extensions.iter().find(|&&(name, _)| name == var)
+        {
+            match value.to_str().map(|value| parse(value)) {
+                Some(Ok(parsed)) => {
+                    result.extensions.values.insert(name, parsed);
+                }
+                Some(Err(why)) => eprintln!(
+                    "proptest: The env-var {}={:?} can't be parsed: {}, \
+                     ignoring it.",
+                    var, value, why
+                ),
+                None => eprintln!(
+                    "proptest: The env-var {} is not valid, ignoring it.",
+                    var
+                ),
+            }
         } else if var.starts_with("PROPTEST_") {
             eprintln!("proptest: Ignoring unknown env-var {}.", var);
         }
@@ -151,9 +335,149 @@ pub fn contextualize_config(result: Config) -> Config {
     result
 }
 
+/// A value parsed from an env-var registered with `register_extension()`.
+#[cfg(feature = "std")]
+trait ExtensionValue: fmt::Debug + Send + Sync {
+    fn as_any(&self) -> &dyn core::any::Any;
+    fn eq_value(&self, other: &dyn ExtensionValue) -> bool;
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug + PartialEq + Send + Sync + 'static> ExtensionValue for T {
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+
+    fn eq_value(&self, other: &dyn ExtensionValue) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+}
+
+#[cfg(feature = "std")]
+type ExtensionParser = std::sync::Arc<
+    dyn Fn(&str) -> Result<std::sync::Arc<dyn ExtensionValue>, String>
+        + Send
+        + Sync,
+>;
+
+#[cfg(feature = "std")]
+static EXTENSIONS: std::sync::Mutex<Vec<(&'static str, ExtensionParser)>> =
+    std::sync::Mutex::new(Vec::new());
+
+/// Register the env-var `var` as an extension of the config, e.g. for a crate
+/// building on proptest to add its own settings.
+///
+/// From then on, `contextualize_config()` parses the value of `var`, if it is
+/// set, with `parse` and stores it in `Config::extensions`, instead of warning
+/// that `var` is unknown. If `parse` fails, the error is reported and the
+/// variable ignored. Registering `var` again replaces its parser.
+///
+/// `Config::default()` is only contextualized once, so extensions should be
+/// registered before the first config is created, such as at the start of a
+/// test harness.
+///
+/// ```
+/// use proptest::test_runner::{contextualize_config, register_extension, Config};
+///
+/// register_extension("PROPTEST_MYLIB_DEPTH", |value| value.parse::<u32>());
+///
+/// # std::env::set_var("PROPTEST_MYLIB_DEPTH", "3");
+/// // With PROPTEST_MYLIB_DEPTH=3 in the environment:
+/// let config = contextualize_config(Config::default());
+/// let depth = config.extensions.get::<u32>("PROPTEST_MYLIB_DEPTH");
+/// assert_eq!(Some(&3), depth);
+/// ```
+///
+/// This is only available with the `std` feature, without which the config
+/// is never read from the environment.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn register_extension<T, E>(
+    var: &'static str,
+    parse: impl Fn(&str) -> Result<T, E> + Send + Sync + 'static,
+) where
+    T: fmt::Debug + PartialEq + Send + Sync + 'static,
+    E: fmt::Display,
+{
+    let parse: ExtensionParser =
+        std::sync::Arc::new(move |value| match parse(value) {
+            Ok(value) => Ok(std::sync::Arc::new(value) as _),
+            Err(why) => Err(format!("{}", why)),
+        });
+    let mut extensions = EXTENSIONS
+        .lock()
+        .unwrap_or_else(|poison| poison.into_inner());
+    extensions.retain(|&(name, _)| name != var);
+    extensions.push((var, parse));
+}
+
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+fn registered_extensions() -> Vec<(&'static str, ExtensionParser)> {
+    EXTENSIONS
+        .lock()
+        .unwrap_or_else(|poison| poison.into_inner())
+        .clone()
+}
+
+/// The values of env-vars registered with `register_extension()`, by name.
+/// See `Config::extensions`.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Clone, Default)]
+pub struct ConfigExtensions {
+    values: std::collections::BTreeMap<
+        &'static str,
+        std::sync::Arc<dyn ExtensionValue>,
+    >,
+}
+
+#[cfg(feature = "std")]
+impl ConfigExtensions {
+    /// Return the value of `var`, if it is set and of type `T`.
+    pub fn get<T: 'static>(&self, var: &str) -> Option<&T> {
+        self.values.get(var)?.as_any().downcast_ref()
+    }
+
+    /// Set the value of `var` to `value`, as if it had been parsed from the
+    /// environment.
+    pub fn insert<T>(&mut self, var: &'static str, value: T)
+    where
+        T: fmt::Debug + PartialEq + Send + Sync + 'static,
+    {
+        self.values.insert(var, std::sync::Arc::new(value));
+    }
+
+    /// Remove the value of `var`, returning whether it was set.
+    pub fn remove(&mut self, var: &str) -> bool {
+        self.values.remove(var).is_some()
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for ConfigExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.values.iter()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl PartialEq for ConfigExtensions {
+    fn eq(&self, other: &Self) -> bool {
+        self.values.len() == other.values.len()
+            && self
+                .values
+                .iter()
+                .zip(&other.values)
+                .all(|(a, b)| a.0 == b.0 && a.1.eq_value(&**b.1))
+    }
+}
+
 fn default_default_config() -> Config {
     Config {
         cases: 256,
+        miri_cases: 8,
+        cases_overrides: Vec::new(),
+        adaptive_cases: None,
         max_local_rejects: 65_536,
         max_global_rejects: 1024,
         max_flat_map_regens: 1_000_000,
@@ -162,20 +486,71 @@ fn default_default_config() -> Config {
         test_name: None,
         #[cfg(feature = "fork")]
         fork: false,
+        #[cfg(feature = "fork")]
+        fork_backend: ForkBackend::RustyFork,
         #[cfg(feature = "timeout")]
         timeout: 0,
         #[cfg(feature = "std")]
         max_shrink_time: 0,
         max_shrink_iters: u32::MAX,
+        max_shrink_repeats: 0,
+        shrink_predicate: None,
+        failure_stability_checks: 0,
+        #[cfg(feature = "std")]
+        shrink_metrics_file: None,
+        #[cfg(feature = "std")]
+        failure_report_path: None,
         max_default_size_range: 100,
+        min_recursive_depth: 0,
+        budget: None,
         result_cache: noop_result_cache,
         #[cfg(feature = "std")]
         verbose: 0,
         rng_algorithm: RngAlgorithm::default(),
+        seed_set: None,
+        shard: None,
+        replay: ReplayMode::Full,
+        #[cfg(feature = "failure-registry")]
+        failure_fingerprint: None,
+        #[cfg(feature = "failure-registry")]
+        shrink_duplicate_failures: true,
+        #[cfg(feature = "chaos")]
+        chaos: None,
+        #[cfg(feature = "coverage-guided")]
+        coverage_guided: None,
+        #[cfg(feature = "std")]
+        extensions: ConfigExtensions::default(),
         _non_exhaustive: (),
     }
 }
 
+/// The part of `cases` run by the shard `index` of `total`, spreading the
+/// remainder over the first shards.
+fn shard_cases(cases: u32, (index, total): (u32, u32)) -> u32 {
+    assert!(
+        0 < index && index <= total,
+        "proptest: shard {}/{} is out of range",
+        index,
+        total
+    );
+    cases / total + u32::from(index - 1 < cases % total)
+}
+
+/// Whether `name` matches the glob `pattern`, where `*` matches any sequence
+/// of bytes and `?` any single byte.
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => {
+            (0..=name.len()).any(|skip| glob_matches(rest, &name[skip..]))
+        }
+        Some((&p, rest)) => match name.split_first() {
+            Some((&n, name)) if b'?' == p || p == n => glob_matches(rest, name),
+            _ => false,
+        },
+    }
+}
+
 // The default config, computed by combining environment variables and
 // defaults.
 #[cfg(feature = "std")]
@@ -188,7 +563,7 @@ lazy_static! {
 }
 
 /// Configuration for how a proptest test should be run.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Config {
     /// The number of successful test cases that must execute for the test as a
     /// whole to pass.
@@ -200,6 +575,63 @@ pub struct Config {
     /// when the `std` feature is enabled, which it is by default.)
     pub cases: u32,
 
+    /// The maximum number of successful test cases that must execute when
+    /// running under [Miri](https://github.com/rust-lang/miri), which is
+    /// orders of magnitude slower than native execution.
+    ///
+    /// When running under Miri, `cases()` returns the lesser of `cases` and
+    /// this value. Forking and timeouts are disabled and failure persistence
+    /// to files is replaced with in-memory persistence as well, so that
+    /// `cargo miri test` works without further configuration.
+    ///
+    /// The default is 8, which can be overridden by setting the
+    /// `PROPTEST_MIRI_CASES` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default,
+    /// and Miri only forwards it to the test if isolation is disabled or
+    /// `-Zmiri-env-forward=PROPTEST_MIRI_CASES` is passed.)
+    pub miri_cases: u32,
+
+    /// Overrides of `cases` for particular tests, as pairs of a pattern and
+    /// the number of cases for the tests it matches.
+    ///
+    /// The patterns are matched against `test_name`, both as given and
+    /// without its first path segment, which for tests defined with
+    /// `proptest!` is the name of the crate. So `slow_module::*` matches all
+    /// tests in `slow_module` in the same way as in the output of
+    /// `cargo test`. In patterns, `*` stands for any number of characters,
+    /// including `::`, and `?` for any one character. The first matching
+    /// pattern wins, and takes precedence over `cases` however that was set.
+    /// This allows e.g. CI to throttle known-slow tests without changing
+    /// their code.
+    ///
+    /// The default is empty, which can be overridden by setting the
+    /// `PROPTEST_CASES_OVERRIDES` environment variable to a comma-separated
+    /// list like `slow_module::*=16,fast::*=1024`. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    pub cases_overrides: Vec<(String, u32)>,
+
+    /// If set, the number of successful test cases is chosen adaptively
+    /// instead of being fixed by `cases`.
+    ///
+    /// The runner then stops once at least `min` cases have passed and none
+    /// of the last `window` of them showed novel behaviour, or once `max`
+    /// cases have passed. Unlike for `cases`, inputs skipped because they hit
+    /// the `result_cache` count as passed cases here, without novel
+    /// behaviour. A case shows novel behaviour if it
+    ///
+    /// - is a new input according to `result_cache` (so this requires a
+    ///   caching `result_cache` such as `basic_result_cache`),
+    /// - generated a value under a label which no earlier case used (see
+    ///   `Labeled`), or
+    /// - reached a point passed to `cover()` which no earlier case reached.
+    ///
+    /// This bounds the runtime of tests whose strategies run out of new
+    /// behaviours quickly, while tests whose strategies keep finding new ones
+    /// still run up to `max` cases. See `AdaptiveCases`.
+    ///
+    /// The default is `None`, which uses the fixed number of `cases`.
+    pub adaptive_cases: Option<AdaptiveCases>,
+
     /// The maximum number of individual inputs that may be rejected before the
     /// test as a whole aborts.
     ///
@@ -232,10 +664,14 @@ pub struct Config {
     /// When compiling with "std" feature (i.e. the standard library is available), the default
     /// is `Some(Box::new(FileFailurePersistence::SourceParallel("proptest-regressions")))`.
     ///
-    /// Without the standard library, the default is `None`, and no persistence occurs.
+    /// Without the standard library, the default is whatever was passed to
+    /// `set_default_failure_persistence()`, or `None` if that was never
+    /// called, in which case no persistence occurs.
     ///
-    /// See the docs of [`FileFailurePersistence`](enum.FileFailurePersistence.html)
-    /// and [`MapFailurePersistence`](struct.MapFailurePersistence.html) for more information.
+    /// See the docs of [`FileFailurePersistence`](enum.FileFailurePersistence.html),
+    /// [`MapFailurePersistence`](struct.MapFailurePersistence.html) and
+    /// [`ArenaFailurePersistence`](struct.ArenaFailurePersistence.html) for more
+    /// information.
     ///
     /// You can disable failure persistence with the `PROPTEST_DISABLE_FAILURE_PERSISTENCE`
     /// environment variable but its not currently possible to set the persistence file
@@ -283,6 +719,18 @@ pub struct Config {
     #[cfg_attr(docsrs, doc(cfg(feature = "fork")))]
     pub fork: bool,
 
+    /// How subprocesses are started when forking. See `ForkBackend`.
+    ///
+    /// This requires the "fork" feature, enabled by default.
+    ///
+    /// The default is `ForkBackend::RustyFork`, which can be overridden by
+    /// setting the `PROPTEST_FORK_BACKEND` environment variable to
+    /// `rusty-fork` or `reexec`. (The variable is only considered when the
+    /// `std` feature is enabled, which it is by default.)
+    #[cfg(feature = "fork")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "fork")))]
+    pub fork_backend: ForkBackend,
+
     /// If non-zero, tests are run in a subprocess and each generated case
     /// fails if it takes longer than this number of milliseconds.
     ///
@@ -327,7 +775,9 @@ pub struct Config {
     /// Setting this to `std::u32::MAX` causes the actual limit to be four
     /// times the number of test cases.
     ///
-    /// Setting this value to `0` disables shrinking altogether.
+    /// Setting this value to `0` disables shrinking altogether. To limit
+    /// shrinking within part of the input instead, see
+    /// `Strategy::prop_shrink_budget()`.
     ///
     /// Note that the type of this field will change in a future version of
     /// proptest to better accommodate its special values.
@@ -337,6 +787,91 @@ pub struct Config {
     /// considered when the `std` feature is enabled, which it is by default.)
     pub max_shrink_iters: u32,
 
+    /// Give up on shrinking if this many consecutive iterations run the test
+    /// against values with identical `Debug` output.
+    ///
+    /// This is a watchdog for value trees which get stuck bouncing between
+    /// `simplify()` and `complicate()` without making progress. When it
+    /// triggers, a warning naming the value tree type is printed and the
+    /// failure found so far is reported. `TestRunner::shrink_stats()` exposes
+    /// the counters involved.
+    ///
+    /// Values can legitimately repeat, e.g. when `prop_map()` maps different
+    /// states of the source to the same value, so this should be well above
+    /// the number of such repeats expected. Enabling it also formats every
+    /// value tried while shrinking.
+    ///
+    /// The default is `0`, which disables the watchdog, and can be overridden
+    /// by setting the `PROPTEST_MAX_SHRINK_REPEATS` environment variable.
+    /// (The variable is only considered when the `std` feature is enabled,
+    /// which it is by default.)
+    pub max_shrink_repeats: u32,
+
+    /// If set, restricts shrinking to inputs for which this returns `true`.
+    ///
+    /// Shrinking sometimes converges on a degenerate input, such as an empty
+    /// `Vec`, which fails for an uninteresting reason and hides the bug
+    /// behind the original failure. Candidates rejected by this function are
+    /// treated as if the test had passed on them without running it, so the
+    /// reported minimal input stays within the regime it describes. The
+    /// function is passed the input itself, and would typically inspect its
+    /// `Debug` form. It is not applied to the input which failed first.
+    ///
+    /// The default is `None`, which accepts every candidate. This field is
+    /// not taken into account when comparing `Config`s.
+    pub shrink_predicate: Option<fn(&dyn fmt::Debug) -> bool>,
+
+    /// Run the test this many more times on the minimal failing input once
+    /// shrinking is done, and append how many of those runs failed to the
+    /// failure message.
+    ///
+    /// A failure which doesn't reproduce every time points at a race, hash
+    /// randomisation or other nondeterminism in the code under test, in which
+    /// case the persisted seed may not reproduce it either. The runs bypass
+    /// the result cache. They are not done when forking, since the minimal
+    /// input is then only known to the parent process.
+    ///
+    /// The default is `0`, which disables the checks, and can be overridden
+    /// by setting the `PROPTEST_FAILURE_STABILITY_CHECKS` environment
+    /// variable. (The variable is only considered when the `std` feature is
+    /// enabled, which it is by default.)
+    pub failure_stability_checks: u32,
+
+    /// If set, append the `ShrinkStats` of every shrinking done to this file,
+    /// one line of JSON per failing test.
+    ///
+    /// This is meant for benchmark suites tracking how long strategies take
+    /// to shrink across releases; see `append_shrink_metrics()` for the
+    /// format. Failing to write the file is reported but does not affect the
+    /// test.
+    ///
+    /// This configuration is only available when the `std` feature is enabled
+    /// (which it is by default).
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_SHRINK_METRICS` environment variable to a path.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub shrink_metrics_file: Option<std::path::PathBuf>,
+
+    /// If set, append a report of every failing test to this file, one line
+    /// of JSON per failure.
+    ///
+    /// The report has the seed and RNG algorithm to reproduce the failure,
+    /// the number of shrinking iterations, the failure message and the
+    /// `Debug` output of the minimal failing input, so that CI tooling can
+    /// surface counterexamples without parsing panic messages. Failing to
+    /// write the file is reported but does not affect the test.
+    ///
+    /// This configuration is only available when the `std` feature is enabled
+    /// (which it is by default).
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_FAILURE_REPORT` environment variable to a path.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub failure_report_path: Option<std::path::PathBuf>,
+
     /// The default maximum size to `proptest::collection::SizeRange`. The default
     /// strategy for collections (like `Vec`) use collections in the range of
     /// `0..max_default_size_range`.
@@ -347,6 +882,38 @@ pub struct Config {
     /// default.)
     pub max_default_size_range: usize,
 
+    /// Make about half of the values of `prop_recursive()` and
+    /// `prop_recursive_tracked()` strategies at least this deep.
+    ///
+    /// Randomly generated recursive values are usually shallow, so bugs which
+    /// only show up with deep nesting are easily missed. When this is
+    /// non-zero, every other value (picked at random) is regenerated a bounded
+    /// number of times until it reaches this depth (capped to the `depth`
+    /// given to the strategy), keeping the deepest value otherwise.
+    ///
+    /// The default is `0`, which can be overridden by setting the
+    /// `PROPTEST_MIN_RECURSIVE_DEPTH` environment variable. (The variable is
+    /// only considered when the `std` feature is enabled, which it is by
+    /// default.)
+    pub min_recursive_depth: u32,
+
+    /// The complexity budget each test case starts with, or `None` to not
+    /// bound the overall complexity of generated values.
+    ///
+    /// While a case is generated, collections draw their size from this
+    /// budget and recursive strategies draw one unit for every level of
+    /// nesting, so it caps the total of all collection and string lengths
+    /// plus recursion depth across the whole value, however deeply the
+    /// strategies are nested. Once it is used up, strategies fall back to
+    /// their minimal values, i.e. collections get their minimum size and
+    /// recursive strategies produce leaves. See `Budget` for drawing from it
+    /// in custom strategies.
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_BUDGET` environment variable. (The variable is only
+    /// considered when the `std` feature is enabled, which it is by default.)
+    pub budget: Option<u32>,
+
     /// A function to create new result caches.
     ///
     /// The default is to do no caching. The easiest way to enable caching is
@@ -379,6 +946,12 @@ pub struct Config {
     /// This is only available with the `std` feature (enabled by default)
     /// since on nostd proptest has no way to produce output.
     ///
+    /// With the `tracing` feature, these messages are instead emitted as
+    /// `tracing` events under the `proptest` target (level 0 messages as
+    /// warnings, level 1 as info and level 2 as trace), along with spans for
+    /// each run, case and shrinking, regardless of this setting. Which of them
+    /// are shown is then up to the subscriber, e.g. through `RUST_LOG`.
+    ///
     /// The default is `0`, which can be overridden by setting the
     /// `PROPTEST_VERBOSE` environment variable. (The variable is only considered
     /// when the `std` feature is enabled, which it is by default.)
@@ -398,11 +971,193 @@ pub struct Config {
     /// which it is by default.)
     pub rng_algorithm: RngAlgorithm,
 
+    /// A "golden" set of seeds from which the RNGs of new test cases are
+    /// derived, making runs fully reproducible across machines while still
+    /// covering more than one seed.
+    ///
+    /// When set, case `n` is generated from the `n % len`th seed, with each
+    /// seed driving its own deterministic RNG stream. Seeds are thus cycled
+    /// through when there are more cases than seeds; when there are fewer,
+    /// the excess seeds are unused. Persisted failures are replayed as usual.
+    /// An empty set is ignored, as is the set as a whole, with a warning, if
+    /// `rng_algorithm` is `PassThrough`, whose data can't be derived from a
+    /// seed.
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_SEED_SET` environment variable to a comma-separated list of
+    /// integers, e.g. `1,2,3`. (The variable is only considered when the
+    /// `std` feature is enabled, which it is by default.)
+    pub seed_set: Option<Vec<u64>>,
+
+    /// Which part of the cases of each test this run executes, as the 1-based
+    /// `index` of this shard among `total` shards, to spread a test suite
+    /// over several CI machines.
+    ///
+    /// When set, the number of cases given by `cases()` is split between the
+    /// shards, and the RNG of each shard is derived deterministically from
+    /// the shard and the test, as identified by `source_file` and
+    /// `test_name`, so that the shards collectively run a reproducible set of
+    /// cases, no two of them running the same ones. An explicit `seed_set`
+    /// takes precedence over the derived RNG, and no RNG is derived if
+    /// `rng_algorithm` is `PassThrough`. Persisted failures are replayed by
+    /// every shard.
+    ///
+    /// Sharded runs are therefore deterministic: every run of a shard tests
+    /// the same cases, rather than exploring new ones as unsharded runs do.
+    ///
+    /// The default is `None`, which can be overridden by setting the
+    /// `PROPTEST_SHARD` environment variable to `index/total`, e.g. `2/8`.
+    /// (The variable is only considered when the `std` feature is enabled,
+    /// which it is by default.)
+    pub shard: Option<(u32, u32)>,
+
+    /// How much work replaying a persisted failure does.
+    ///
+    /// Along with each failing seed, proptest persists the path shrinking took
+    /// to the minimal failing value. See `ReplayMode` for how it can be used.
+    /// Seeds persisted without a path are always replayed in full.
+    ///
+    /// The default is `ReplayMode::Full`, which can be overridden by setting
+    /// the `PROPTEST_REPLAY` environment variable to `full`, `reshrink` or
+    /// `verify`. (The variable is only considered when the `std` feature is
+    /// enabled, which it is by default.)
+    pub replay: ReplayMode,
+
+    /// A function normalising a failing input into a fingerprint, used to
+    /// recognise semantically equivalent failures across the tests of one
+    /// binary.
+    ///
+    /// When set, the fingerprints of failing inputs are recorded in a
+    /// process-wide registry. A later test whose failing input has the same
+    /// fingerprint as an earlier failure, either before or after shrinking,
+    /// reports its failure as a duplicate of the earlier one. The function is
+    /// passed the failing input itself, and would typically map its `Debug`
+    /// form to the part of it which identifies the underlying bug.
+    ///
+    /// This requires the "failure-registry" feature.
+    ///
+    /// The default is `None`, which disables the registry.
+    #[cfg(feature = "failure-registry")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "failure-registry")))]
+    pub failure_fingerprint: Option<fn(&dyn fmt::Debug) -> String>,
+
+    /// Whether to shrink failures recognised as duplicates of earlier ones
+    /// through `failure_fingerprint`. Since the earlier test already reported
+    /// a minimal input, turning this off saves the time shrinking would take.
+    ///
+    /// This requires the "failure-registry" feature.
+    ///
+    /// The default is `true`, which can be overridden by setting the
+    /// `PROPTEST_SHRINK_DUPLICATE_FAILURES` environment variable.
+    #[cfg(feature = "failure-registry")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "failure-registry")))]
+    pub shrink_duplicate_failures: bool,
+
+    /// Perturbations to inject into each test case, such as sleeps and
+    /// panics at chaos points. See the `chaos` module for details.
+    ///
+    /// This requires the "chaos" feature.
+    ///
+    /// The default is `None`, which disables chaos mode. Setting the
+    /// `PROPTEST_CHAOS` environment variable to `true` enables it with
+    /// `Chaos::default()`, and setting it to `false` disables it.
+    #[cfg(feature = "chaos")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "chaos")))]
+    pub chaos: Option<crate::chaos::Chaos>,
+
+    /// If set, test cases are derived from earlier ones which reached new
+    /// coverage, like in a fuzzer, instead of all being generated from fresh
+    /// seeds. See `CoverageGuided` for how coverage is measured.
+    ///
+    /// This requires the "coverage-guided" feature.
+    ///
+    /// The default is `None`, which disables coverage guidance. Setting the
+    /// `PROPTEST_COVERAGE_GUIDED` environment variable to `true` enables it
+    /// with `CoverageGuided::default()`, and setting it to `false` disables
+    /// it.
+    #[cfg(feature = "coverage-guided")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "coverage-guided")))]
+    pub coverage_guided: Option<CoverageGuided>,
+
+    /// Settings of crates building on proptest, parsed from the env-vars they
+    /// registered with `register_extension()`.
+    ///
+    /// This configuration is only available when the `std` feature is enabled
+    /// (which it is by default).
+    ///
+    /// The default is empty, and `contextualize_config()` adds the value of
+    /// each registered env-var which is set.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub extensions: ConfigExtensions,
+
     // Needs to be public so FRU syntax can be used.
     #[doc(hidden)]
     pub _non_exhaustive: (),
 }
 
+// `shrink_predicate` is left out, since the same function may have several
+// addresses, and `core::ptr::fn_addr_eq()` is too recent to use here. The
+// other function pointers are still compared by address, as they always were.
+impl PartialEq for Config {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "fork")]
+        if self.fork != other.fork || self.fork_backend != other.fork_backend {
+            return false;
+        }
+        #[cfg(feature = "timeout")]
+        if self.timeout != other.timeout {
+            return false;
+        }
+        #[cfg(feature = "std")]
+        if self.max_shrink_time != other.max_shrink_time
+            || self.shrink_metrics_file != other.shrink_metrics_file
+            || self.failure_report_path != other.failure_report_path
+            || self.verbose != other.verbose
+            || self.extensions != other.extensions
+        {
+            return false;
+        }
+        #[cfg(feature = "failure-registry")]
+        if self.failure_fingerprint.map(|f| f as usize)
+            != other.failure_fingerprint.map(|f| f as usize)
+            || self.shrink_duplicate_failures != other.shrink_duplicate_failures
+        {
+            return false;
+        }
+        #[cfg(feature = "chaos")]
+        if self.chaos != other.chaos {
+            return false;
+        }
+        #[cfg(feature = "coverage-guided")]
+        if self.coverage_guided != other.coverage_guided {
+            return false;
+        }
+
+        self.cases == other.cases
+            && self.miri_cases == other.miri_cases
+            && self.cases_overrides == other.cases_overrides
+            && self.adaptive_cases == other.adaptive_cases
+            && self.max_local_rejects == other.max_local_rejects
+            && self.max_global_rejects == other.max_global_rejects
+            && self.max_flat_map_regens == other.max_flat_map_regens
+            && self.failure_persistence == other.failure_persistence
+            && self.source_file == other.source_file
+            && self.test_name == other.test_name
+            && self.max_shrink_iters == other.max_shrink_iters
+            && self.max_shrink_repeats == other.max_shrink_repeats
+            && self.failure_stability_checks == other.failure_stability_checks
+            && self.max_default_size_range == other.max_default_size_range
+            && self.min_recursive_depth == other.min_recursive_depth
+            && self.budget == other.budget
+            && self.result_cache as usize == other.result_cache as usize
+            && self.rng_algorithm == other.rng_algorithm
+            && self.seed_set == other.seed_set
+            && self.shard == other.shard
+            && self.replay == other.replay
+    }
+}
+
 impl Config {
     /// Constructs a `Config` only differing from the `default()` in the
     /// number of test cases required to pass the test successfully.
@@ -424,6 +1179,32 @@ impl Config {
         }
     }
 
+    /// Constructs a `Config` only differing from the `default()` in choosing
+    /// the number of test cases adaptively, running between `min` and `max`
+    /// cases and stopping after `window` consecutive cases without novel
+    /// behaviour. See `adaptive_cases`.
+    ///
+    /// ```
+    /// # use proptest::test_runner::{AdaptiveCases, Config};
+    /// assert_eq!(
+    ///     Config::with_adaptive_cases(32, 4096, 64),
+    ///     Config {
+    ///         adaptive_cases: Some(AdaptiveCases {
+    ///             min: 32,
+    ///             max: 4096,
+    ///             window: 64,
+    ///         }),
+    ///         .. Config::default()
+    ///     }
+    /// );
+    /// ```
+    pub fn with_adaptive_cases(min: u32, max: u32, window: u32) -> Self {
+        Self {
+            adaptive_cases: Some(AdaptiveCases { min, max, window }),
+            ..Config::default()
+        }
+    }
+
     /// Constructs a `Config` only differing from the `default()` in the
     /// source_file of the present test.
     ///
@@ -505,7 +1286,8 @@ impl Config {
 
     #[cfg(feature = "fork")]
     fn _fork(&self) -> bool {
-        self.fork
+        // Miri can't spawn processes
+        self.fork && !cfg!(miri)
     }
 
     #[cfg(not(feature = "fork"))]
@@ -516,10 +1298,15 @@ impl Config {
     /// Returns the configured timeout.
     ///
     /// This method exists even if the "timeout" feature is disabled, in which
-    /// case it simply returns 0.
+    /// case it simply returns 0. It also returns 0 when running under Miri,
+    /// which can't fork.
     #[cfg(feature = "timeout")]
     pub fn timeout(&self) -> u32 {
-        self.timeout
+        if cfg!(miri) {
+            0
+        } else {
+            self.timeout
+        }
     }
 
     /// Returns the configured timeout.
@@ -531,17 +1318,74 @@ impl Config {
         0
     }
 
+    /// Returns the number of successful test cases that must execute.
+    ///
+    /// This is `cases`, or the first entry of `cases_overrides` matching
+    /// `test_name`, except when running under Miri, where it is capped at
+    /// `miri_cases`. With `shard`, it is this shard's part of that number.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `shard` is set but its index isn't between 1 and its total.
+    pub fn cases(&self) -> u32 {
+        let cases = self.cases_override().unwrap_or(self.cases);
+        let cases = if cfg!(miri) {
+            cases.min(self.miri_cases)
+        } else {
+            cases
+        };
+        match self.shard {
+            Some(shard) => shard_cases(cases, shard),
+            None => cases,
+        }
+    }
+
+    /// Returns the number of cases of the first entry of `cases_overrides`
+    /// matching `test_name`, if any.
+    fn cases_override(&self) -> Option<u32> {
+        let name = self.test_name?;
+        let relative = name.split_once("::").map(|(_, rest)| rest);
+        self.cases_overrides
+            .iter()
+            .find(|(pattern, _)| {
+                glob_matches(pattern.as_bytes(), name.as_bytes())
+                    || relative.map_or(false, |relative| {
+                        glob_matches(pattern.as_bytes(), relative.as_bytes())
+                    })
+            })
+            .map(|&(_, cases)| cases)
+    }
+
     /// Returns the configured limit on shrinking iterations.
     ///
     /// This takes into account the special "automatic" behaviour.
     pub fn max_shrink_iters(&self) -> u32 {
         if u32::MAX == self.max_shrink_iters {
-            self.cases.saturating_mul(4)
+            self.cases().saturating_mul(4)
         } else {
             self.max_shrink_iters
         }
     }
 
+    /// Replace failure persistence to files, which Miri can't access by
+    /// default, with in-memory persistence.
+    #[cfg(all(feature = "std", miri))]
+    pub(crate) fn without_file_persistence(mut self) -> Self {
+        use crate::test_runner::{
+            FileFailurePersistence, MapFailurePersistence,
+        };
+
+        let uses_files = self
+            .failure_persistence
+            .as_ref()
+            .map_or(false, |p| p.as_any().is::<FileFailurePersistence>());
+        if uses_files {
+            self.failure_persistence =
+                Some(Box::new(MapFailurePersistence::default()));
+        }
+        self
+    }
+
     // Used by macros to force the config to be owned without depending on
     // certain traits being `use`d.
     #[allow(missing_docs)]
@@ -561,6 +1405,230 @@ impl Default for Config {
 #[cfg(not(feature = "std"))]
 impl Default for Config {
     fn default() -> Self {
-        default_default_config()
+        let mut config = default_default_config();
+        config.failure_persistence = default_failure_persistence();
+        config
+    }
+}
+
+// The failure persistence of the default config without `std`. Once set, it
+// is never freed, so the pointer stays valid forever.
+#[cfg(not(feature = "std"))]
+static DEFAULT_FAILURE_PERSISTENCE: core::sync::atomic::AtomicPtr<
+    Box<dyn FailurePersistence>,
+> = core::sync::atomic::AtomicPtr::new(core::ptr::null_mut());
+
+/// Make `Config::default()` use a copy of `persistence` for failure
+/// persistence, e.g. an `ArenaFailurePersistence` backed by flash on an
+/// embedded target.
+///
+/// Without the `std` feature, there is no other way to give the default config
+/// a failure persistence, so this should be called once early on, before any
+/// tests run. It can only be called once; later calls return `persistence`
+/// back as an error.
+///
+/// This is only available without the `std` feature, where the default is to
+/// persist to files instead.
+#[cfg(not(feature = "std"))]
+#[cfg_attr(docsrs, doc(cfg(not(feature = "std"))))]
+pub fn set_default_failure_persistence(
+    persistence: Box<dyn FailurePersistence>,
+) -> Result<(), Box<dyn FailurePersistence>> {
+    use core::sync::atomic::Ordering::SeqCst;
+
+    let new = Box::into_raw(Box::new(persistence));
+    DEFAULT_FAILURE_PERSISTENCE
+        .compare_exchange(core::ptr::null_mut(), new, SeqCst, SeqCst)
+        .map(|_| ())
+        // Safety: `new` was not shared, so this is its only owner.
+        .map_err(|_| *unsafe { Box::from_raw(new) })
+}
+
+#[cfg(not(feature = "std"))]
+fn default_failure_persistence() -> Option<Box<dyn FailurePersistence>> {
+    let current =
+        DEFAULT_FAILURE_PERSISTENCE.load(core::sync::atomic::Ordering::SeqCst);
+    // Safety: a non-null pointer came from `Box::into_raw()` and is never
+    // freed.
+    unsafe { current.as_ref() }.map(|persistence| persistence.box_clone())
+}
+
+/// How a `TestRunner` starts the subprocesses that test cases run in when
+/// forking. See `Config::fork_backend`.
+///
+/// Both backends run the current test binary again with arguments selecting
+/// just the test being run, passing the seed and the results of the cases
+/// run so far in a temporary file named by an environment variable.
+#[cfg(feature = "fork")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fork")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ForkBackend {
+    /// Start subprocesses with the `rusty-fork` crate, which forwards the
+    /// arguments the test binary was started with and recognises the
+    /// subprocess by a hash of the call site.
+    #[default]
+    RustyFork,
+    /// Start subprocesses directly, with only the arguments
+    /// `--exact <test name> --nocapture --test-threads 1`. The subprocess is
+    /// recognised by an environment variable holding the test name, which
+    /// the `TestRunner` checks before forking again.
+    ///
+    /// This works with custom test harnesses accepting these arguments, and
+    /// with runners such as `cargo nextest` which pass arguments the test
+    /// binary shouldn't see again.
+    Reexec,
+}
+
+#[cfg(feature = "fork")]
+impl fmt::Display for ForkBackend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ForkBackend::RustyFork => "rusty-fork",
+            ForkBackend::Reexec => "reexec",
+        })
+    }
+}
+
+#[cfg(feature = "fork")]
+impl str::FromStr for ForkBackend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "rusty-fork" => Ok(ForkBackend::RustyFork),
+            "reexec" => Ok(ForkBackend::Reexec),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The stopping rule of a `TestRunner` choosing the number of test cases
+/// adaptively. See `Config::adaptive_cases`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdaptiveCases {
+    /// The number of successful cases to run even if none of them shows
+    /// novel behaviour.
+    pub min: u32,
+    /// The number of successful cases after which to stop even if they keep
+    /// showing novel behaviour.
+    ///
+    /// When running under Miri, this is capped at `Config::miri_cases`.
+    pub max: u32,
+    /// The number of consecutive successful cases without novel behaviour
+    /// after which to stop.
+    pub window: u32,
+}
+
+/// How a `TestRunner` replays persisted failures. See `Config::replay`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Run the test against the value generated from the seed and, if it
+    /// still fails, shrink it from scratch.
+    #[default]
+    Full,
+    /// Recreate the minimal failing value found last time without running
+    /// the test, then run the test against it and, if it still fails, shrink
+    /// it further.
+    ///
+    /// If the minimal value no longer fails, the seed is replayed in full.
+    Reshrink,
+    /// Recreate the minimal failing value found last time without running
+    /// the test, and only check that the test still fails on it, reporting it
+    /// as is.
+    ///
+    /// If the minimal value no longer fails, the seed is replayed in full.
+    Verify,
+}
+
+impl fmt::Display for ReplayMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            ReplayMode::Full => "full",
+            ReplayMode::Reshrink => "reshrink",
+            ReplayMode::Verify => "verify",
+        })
+    }
+}
+
+impl str::FromStr for ReplayMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "full" => Ok(ReplayMode::Full),
+            "reshrink" => Ok(ReplayMode::Reshrink),
+            "verify" => Ok(ReplayMode::Verify),
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std", not(target_arch = "wasm32")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn registered_env_vars_are_parsed_into_extensions() {
+        register_extension("PROPTEST_CONFIG_TEST_GOOD", |v| v.parse::<u32>());
+        register_extension("PROPTEST_CONFIG_TEST_BAD", |v| v.parse::<u32>());
+        std::env::set_var("PROPTEST_CONFIG_TEST_GOOD", "42");
+        std::env::set_var("PROPTEST_CONFIG_TEST_BAD", "forty-two");
+
+        let config = contextualize_config(Config::default());
+        let extensions = &config.extensions;
+        assert_eq!(
+            Some(&42),
+            extensions.get::<u32>("PROPTEST_CONFIG_TEST_GOOD")
+        );
+        assert_eq!(None, extensions.get::<u64>("PROPTEST_CONFIG_TEST_GOOD"));
+        assert_eq!(None, extensions.get::<u32>("PROPTEST_CONFIG_TEST_BAD"));
+
+        let mut other = Config::default();
+        assert_ne!(config, other);
+        other.extensions.insert("PROPTEST_CONFIG_TEST_GOOD", 42u32);
+        assert_eq!(config.extensions, other.extensions);
+        assert!(other.extensions.remove("PROPTEST_CONFIG_TEST_GOOD"));
+        assert_eq!(ConfigExtensions::default(), other.extensions);
+    }
+
+    #[test]
+    fn equality_ignores_shrink_predicate() {
+        fn short(value: &dyn fmt::Debug) -> bool {
+            format!("{:?}", value).len() < 10
+        }
+
+        let config = Config {
+            shrink_predicate: Some(short),
+            ..Config::default()
+        };
+        assert_eq!(Config::default(), config);
+        assert_ne!(
+            Config {
+                max_shrink_repeats: 1,
+                ..config.clone()
+            },
+            config
+        );
+    }
+
+    #[test]
+    fn shards_are_parsed_and_split_cases() {
+        assert_eq!(Some((2, 8)), parse_shard("2/8"));
+        assert_eq!(Some((8, 8)), parse_shard(" 8 / 8 "));
+        assert_eq!(None, parse_shard("0/8"));
+        assert_eq!(None, parse_shard("9/8"));
+        assert_eq!(None, parse_shard("2"));
+
+        let shard = |index| Config {
+            cases: 10,
+            shard: Some((index, 4)),
+            ..Config::default()
+        };
+        assert_eq!(
+            vec![3, 3, 2, 2],
+            (1..=4)
+                .map(|index| shard(index).cases())
+                .collect::<Vec<_>>()
+        );
     }
 }
@@ -0,0 +1,260 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Coverage reached by test cases, used as a novelty signal by
+//! `Config::adaptive_cases` and to guide generation by
+//! `Config::coverage_guided`.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::string::String;
+#[cfg(feature = "coverage-guided")]
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering::Relaxed};
+#[cfg(feature = "coverage-guided")]
+use std::vec::Vec;
+
+#[cfg(feature = "coverage-guided")]
+use crate::test_runner::failure_persistence::{PersistedSeed, ShrinkPath};
+#[cfg(feature = "coverage-guided")]
+use crate::test_runner::rng::TestRng;
+
+thread_local! {
+    static COVERED: RefCell<BTreeSet<String>> = RefCell::new(BTreeSet::new());
+}
+
+/// Record that the test case currently running on this thread reached
+/// `point`, such as a branch of the code under test.
+///
+/// With `Config::adaptive_cases`, a case which reaches a point no earlier
+/// case of the same run reached shows novel behaviour, so the runner keeps
+/// going. Otherwise, this has no effect.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::test_runner::{cover, Config};
+///
+/// fn sign(v: i32) -> i32 {
+///     if v < 0 {
+///         cover("negative");
+///         -1
+///     } else {
+///         cover("non-negative");
+///         1
+///     }
+/// }
+///
+/// proptest! {
+///     #![proptest_config(Config::with_adaptive_cases(16, 1024, 32))]
+///     # /*
+///     #[test]
+///     # */
+///     fn sign_squares_to_one(v in any::<i32>()) {
+///         prop_assert_eq!(1, sign(v) * sign(v));
+///     }
+/// }
+/// # fn main() { sign_squares_to_one(); }
+/// ```
+pub fn cover(point: &str) {
+    COVERED.with(|covered| {
+        let mut covered = covered.borrow_mut();
+        if !covered.contains(point) {
+            covered.insert(point.into());
+        }
+    });
+}
+
+/// Remove and return all points covered on this thread since the last call
+/// to `take()`.
+pub(crate) fn take() -> BTreeSet<String> {
+    COVERED.with(|covered| core::mem::take(&mut *covered.borrow_mut()))
+}
+
+/// The settings of coverage-guided generation. See
+/// `Config::coverage_guided`.
+///
+/// The runner keeps a corpus of the seeds of test cases which reached new
+/// coverage. Every other case is then derived from a random seed in the
+/// corpus instead of a fresh one, by generating its value again and calling
+/// `ValueTree::simplify()` or `ValueTree::complicate()` on it a random number
+/// of times. Cases derived this way which reach new coverage in turn join the
+/// corpus.
+///
+/// Coverage is measured by the points passed to `cover()`, and by the edge
+/// counters of LLVM's SanitizerCoverage if the code under test is built with
+/// them. Proptest provides the `trace-pc-guard` callbacks for this, so build
+/// with e.g.
+///
+/// ```text
+/// RUSTFLAGS="-C passes=sancov-module \
+///     -C llvm-args=-sanitizer-coverage-level=3 \
+///     -C llvm-args=-sanitizer-coverage-trace-pc-guard" cargo test
+/// ```
+///
+/// Since the callbacks are process-wide, this cannot be combined with other
+/// users of SanitizerCoverage such as libFuzzer, and tests running
+/// concurrently on other threads count towards each other's coverage; run
+/// with `--test-threads=1` for precise guidance.
+///
+/// This requires the "coverage-guided" feature.
+#[cfg(feature = "coverage-guided")]
+#[cfg_attr(docsrs, doc(cfg(feature = "coverage-guided")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoverageGuided {
+    /// The maximum number of seeds in the corpus. Once it is full, new seeds
+    /// replace random earlier ones.
+    pub max_corpus: u32,
+    /// The maximum number of calls to `simplify()` or `complicate()` made to
+    /// derive a case from a seed in the corpus.
+    pub max_mutations: u32,
+}
+
+#[cfg(feature = "coverage-guided")]
+impl Default for CoverageGuided {
+    fn default() -> Self {
+        CoverageGuided {
+            max_corpus: 256,
+            max_mutations: 16,
+        }
+    }
+}
+
+/// The number of edge counters. Instrumented programs with more edges than
+/// this share counters between some of them.
+#[cfg(feature = "coverage-guided")]
+const EDGES: usize = 1 << 16;
+
+/// The number of edges assigned a counter so far.
+#[cfg(feature = "coverage-guided")]
+static GUARDS: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of times each edge was hit since the counters were last
+/// taken, saturating. Edge 0 is never hit since guards of 0 are disabled.
+#[cfg(feature = "coverage-guided")]
+static HITS: [AtomicU8; EDGES] = {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU8 = AtomicU8::new(0);
+    [ZERO; EDGES]
+};
+
+/// Called by SanitizerCoverage once for each instrumented module, to assign
+/// the guards from `start` to `stop` to edge counters.
+///
+/// # Safety
+///
+/// `start..stop` must be a valid range of guards.
+#[cfg(feature = "coverage-guided")]
+#[no_mangle]
+pub unsafe extern "C" fn __sanitizer_cov_trace_pc_guard_init(
+    start: *mut u32,
+    stop: *mut u32,
+) {
+    if start == stop || *start != 0 {
+        return;
+    }
+
+    let mut guard = start;
+    while guard < stop {
+        let edge = GUARDS.fetch_add(1, Relaxed) % (EDGES - 1) + 1;
+        *guard = edge as u32;
+        guard = guard.add(1);
+    }
+}
+
+/// Called by SanitizerCoverage whenever the edge of `guard` is hit.
+///
+/// # Safety
+///
+/// `guard` must be a valid pointer to a guard.
+#[cfg(feature = "coverage-guided")]
+#[no_mangle]
+pub unsafe extern "C" fn __sanitizer_cov_trace_pc_guard(guard: *mut u32) {
+    if let Some(hits) = HITS.get(*guard as usize) {
+        // Racing increments may be lost, which only makes the count less
+        // precise.
+        let count = hits.load(Relaxed);
+        hits.store(count.saturating_add(1), Relaxed);
+    }
+}
+
+/// The corpus of coverage-guided generation and the coverage reached by the
+/// cases of a run so far. See `CoverageGuided`.
+#[cfg(feature = "coverage-guided")]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Corpus {
+    /// The seeds of the cases which reached new coverage, with the calls
+    /// deriving them from the value generated from the seed.
+    pub(crate) seeds: Vec<PersistedSeed>,
+    /// The RNG choosing the seeds and mutations. It is separate from that of
+    /// the runner so that cases generated from fresh seeds can be replayed
+    /// from just their seed.
+    pub(crate) rng: Option<TestRng>,
+    /// The calls deriving the current case from its seed.
+    pub(crate) mutation: ShrinkPath,
+    /// The RNG of the runner to continue with after the current case, if
+    /// that was derived from a seed in the corpus.
+    pub(crate) resume: Option<TestRng>,
+    /// Whether the current case reached new coverage.
+    pub(crate) new_coverage: bool,
+    /// For each edge, the hit count buckets it reached so far.
+    edges: Vec<u8>,
+}
+
+#[cfg(feature = "coverage-guided")]
+impl Corpus {
+    /// Takes the edge counters of the current case, returning whether it
+    /// reached an edge, or an edge as many times, as no earlier case did.
+    pub(crate) fn take_edges(&mut self) -> bool {
+        if self.edges.is_empty() {
+            self.edges = std::vec![0; EDGES];
+        }
+
+        let mut new_coverage = false;
+        for (hits, seen) in HITS.iter().zip(&mut self.edges) {
+            // Checking first avoids writing to every counter.
+            if hits.load(Relaxed) == 0 {
+                continue;
+            }
+            let bucket = hit_bucket(hits.swap(0, Relaxed));
+            if *seen & bucket == 0 {
+                *seen |= bucket;
+                new_coverage = true;
+            }
+        }
+        self.new_coverage |= new_coverage;
+        new_coverage
+    }
+}
+
+/// Resets the edge counters, e.g. to discard edges hit outside of a test
+/// case.
+#[cfg(feature = "coverage-guided")]
+pub(crate) fn reset_edges() {
+    for hits in HITS.iter() {
+        if hits.load(Relaxed) != 0 {
+            hits.store(0, Relaxed);
+        }
+    }
+}
+
+/// Maps a hit count to a bit of its bucket, so that an edge counts as new
+/// coverage if it is hit a novel order of magnitude of times, like in AFL.
+#[cfg(feature = "coverage-guided")]
+fn hit_bucket(hits: u8) -> u8 {
+    match hits {
+        0 => 0,
+        1 => 1,
+        2 => 2,
+        3 => 4,
+        4..=7 => 8,
+        8..=15 => 16,
+        16..=31 => 32,
+        32..=127 => 64,
+        128..=255 => 128,
+    }
+}
@@ -7,7 +7,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::fmt;
+use crate::std_facade::{fmt, Vec};
 
 #[cfg(feature = "std")]
 use std::string::ToString;
@@ -132,3 +132,17 @@ impl<T: fmt::Debug> ::std::error::Error for TestError<T> {
         }
     }
 }
+
+/// The minimal failing input of `TestRunner::run_matrix()`, along with which
+/// of the properties fail on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatrixFailure<T> {
+    /// The minimal input on which at least one of the properties fails.
+    pub input: T,
+    /// The properties which fail on `input`, with why they fail, in the order
+    /// they were given.
+    pub failed: Vec<(&'static str, Reason)>,
+    /// The properties which don't fail on `input`, in the order they were
+    /// given.
+    pub passed: Vec<&'static str>,
+}
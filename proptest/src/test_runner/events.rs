@@ -0,0 +1,142 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Structured events emitted by a `TestRunner` as it runs, for tools such as
+//! IDE plugins or TUIs which want to visualise a run live.
+
+use core::fmt;
+
+use crate::test_runner::failure_persistence::PersistedSeed;
+use crate::test_runner::reason::Reason;
+
+/// An event emitted by a `TestRunner` to its `EventSink`.
+///
+/// ## Stability
+///
+/// This enum and `CaseOutcome` are exempt from semver for now: variants and
+/// fields may be added, changed, or removed in minor releases while the API
+/// settles.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum RunnerEvent<'a> {
+    /// A test case is about to be generated and run.
+    CaseStarted {
+        /// The seed the case is generated from, if known. Its `Display` form
+        /// is what would be written to the failure persistence file.
+        seed: Option<PersistedSeed>,
+    },
+    /// The test case finished running. For failing cases, this is emitted
+    /// before shrinking starts.
+    CaseFinished {
+        /// How the case went.
+        outcome: CaseOutcome,
+    },
+    /// The test was run against a simplified input while shrinking.
+    ShrinkStep {
+        /// The number of this step, starting at 1.
+        iteration: u32,
+        /// The input the test was run against.
+        value: &'a dyn fmt::Debug,
+        /// Whether the test still failed on `value`.
+        failed: bool,
+    },
+    /// Shrinking finished and the test as a whole fails.
+    Failure {
+        /// The minimal failing input that was found.
+        minimal: &'a dyn fmt::Debug,
+        /// Why the test failed on `minimal`.
+        reason: &'a Reason,
+    },
+}
+
+#[cfg(feature = "tracing")]
+impl RunnerEvent<'_> {
+    /// Emits this event to `tracing`, under the `proptest` target.
+    pub(crate) fn trace(&self) {
+        match *self {
+            RunnerEvent::CaseStarted { ref seed } => tracing::debug!(
+                target: "proptest",
+                seed = seed.as_ref().map(tracing::field::display),
+                "case started"
+            ),
+            RunnerEvent::CaseFinished { outcome } => tracing::debug!(
+                target: "proptest",
+                outcome = ?outcome,
+                "case finished"
+            ),
+            RunnerEvent::ShrinkStep {
+                iteration,
+                value,
+                failed,
+            } => tracing::debug!(
+                target: "proptest",
+                iteration,
+                failed,
+                value = ?value,
+                "shrink step"
+            ),
+            RunnerEvent::Failure { minimal, reason } => tracing::info!(
+                target: "proptest",
+                minimal = ?minimal,
+                reason = %reason,
+                "test failed"
+            ),
+        }
+    }
+}
+
+/// How a single test case went. See `RunnerEvent::CaseFinished`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CaseOutcome {
+    /// The test passed.
+    Passed,
+    /// The input was rejected, e.g. by `prop_assume!`.
+    Rejected,
+    /// The test failed.
+    Failed,
+}
+
+/// Receives the events of a `TestRunner`, set via
+/// `TestRunner::set_event_sink`.
+///
+/// Events are delivered synchronously on the thread running the test, so
+/// implementations should return quickly, e.g. by forwarding to a channel.
+///
+/// This is implemented for all suitable closures:
+///
+/// ```
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use std::sync::Arc;
+///
+/// use proptest::test_runner::{Config, RunnerEvent, TestRunner};
+///
+/// let cases = Arc::new(AtomicU32::new(0));
+/// let counter = Arc::clone(&cases);
+///
+/// let mut runner = TestRunner::new(Config::with_cases(10));
+/// runner.set_event_sink(move |event: &RunnerEvent<'_>| {
+///     if let RunnerEvent::CaseStarted { .. } = event {
+///         counter.fetch_add(1, Ordering::SeqCst);
+///     }
+/// });
+/// runner.run(&(0..10u32), |_| Ok(())).unwrap();
+///
+/// assert_eq!(10, cases.load(Ordering::SeqCst));
+/// ```
+pub trait EventSink: Send + Sync {
+    /// Handle one `event`.
+    fn on_event(&self, event: &RunnerEvent<'_>);
+}
+
+impl<F: Fn(&RunnerEvent<'_>) + Send + Sync> EventSink for F {
+    fn on_event(&self, event: &RunnerEvent<'_>) {
+        self(event)
+    }
+}
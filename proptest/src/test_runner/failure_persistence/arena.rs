@@ -0,0 +1,309 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, Arc, Box, String, Vec};
+use core::any::Any;
+use core::cell::UnsafeCell;
+use core::convert::TryFrom;
+use core::fmt::Write;
+use core::str;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::test_runner::failure_persistence::{
+    FailurePersistence, PersistedSeed, ShrinkPath,
+};
+
+/// Failure persistence option that stores seeds in a byte arena provided by
+/// the user, such as a buffer which is mirrored to flash or EEPROM on an
+/// embedded target. Unlike `FileFailurePersistence`, this does not need the
+/// `std` feature.
+///
+/// The arena holds a sequence of records, each being a 2-byte little-endian
+/// length followed by that many bytes of UTF-8 text: the source file, the
+/// seed and the shrink path, separated by newlines. A length of `0` or
+/// `0xFFFF`, or one running past the end of the arena, ends the sequence, so
+/// both a zeroed and an erased flash arena hold no records. Seeds which no
+/// longer fit into the arena are dropped.
+///
+/// Clones share the same arena, so this can be set as the failure persistence
+/// of many configs at once.
+///
+/// ```
+/// use proptest::test_runner::{ArenaFailurePersistence, Config};
+///
+/// let arena = Box::leak(vec![0u8; 1024].into_boxed_slice());
+/// let persistence = ArenaFailurePersistence::new(arena);
+/// let config = Config {
+///     failure_persistence: Some(Box::new(persistence.clone())),
+///     ..Config::default()
+/// };
+/// # let _ = config;
+///
+/// // ... run tests with `config`, then copy the records to flash:
+/// let used = persistence.used();
+/// persistence.with_arena(|bytes| {
+///     let records = &bytes[..used];
+///     # let _ = records;
+/// });
+/// ```
+#[derive(Clone)]
+pub struct ArenaFailurePersistence {
+    arena: Arc<Arena>,
+}
+
+/// The arena, guarded by a spin lock since `FailurePersistence` must be
+/// `Sync` and there may be no OS to provide a mutex.
+struct Arena {
+    locked: AtomicBool,
+    bytes: UnsafeCell<&'static mut [u8]>,
+}
+
+// The bytes are only ever accessed while holding the lock.
+unsafe impl Sync for Arena {}
+
+struct ArenaGuard<'a>(&'a Arena);
+
+impl Drop for ArenaGuard<'_> {
+    fn drop(&mut self) {
+        self.0.locked.store(false, Ordering::Release);
+    }
+}
+
+impl Arena {
+    fn with<R>(&self, f: impl FnOnce(&mut [u8]) -> R) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(
+                false,
+                true,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        let _guard = ArenaGuard(self);
+        // Safety: holding the lock gives exclusive access to the bytes.
+        f(unsafe { &mut **self.bytes.get() })
+    }
+}
+
+/// Returns the offset and text of each record in `bytes`.
+fn records(bytes: &[u8]) -> impl Iterator<Item = (usize, &[u8])> {
+    let mut offset = 0;
+    core::iter::from_fn(move || {
+        let header = bytes.get(offset..offset + 2)?;
+        let len = u16::from_le_bytes([header[0], header[1]]);
+        if 0 == len || u16::MAX == len {
+            return None;
+        }
+        let start = offset + 2;
+        let text = bytes.get(start..start + usize::from(len))?;
+        let record = (offset, text);
+        offset = start + usize::from(len);
+        Some(record)
+    })
+}
+
+/// Returns the number of bytes taken by the records in `bytes`.
+fn used(bytes: &[u8]) -> usize {
+    records(bytes)
+        .last()
+        .map_or(0, |(offset, text)| offset + 2 + text.len())
+}
+
+impl ArenaFailurePersistence {
+    /// Create a persistence storing seeds in `arena`.
+    ///
+    /// Records already in `arena`, e.g. because it was loaded from flash, are
+    /// kept and loaded like any others.
+    pub fn new(arena: &'static mut [u8]) -> Self {
+        ArenaFailurePersistence {
+            arena: Arc::new(Arena {
+                locked: AtomicBool::new(false),
+                bytes: UnsafeCell::new(arena),
+            }),
+        }
+    }
+
+    /// Call `f` with the whole arena, e.g. to copy it to persistent storage.
+    ///
+    /// The arena is locked while `f` runs, so `f` must not use this
+    /// persistence (or a clone of it) itself.
+    pub fn with_arena<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        self.arena.with(|bytes| f(bytes))
+    }
+
+    /// Return the number of bytes at the start of the arena taken by records.
+    pub fn used(&self) -> usize {
+        self.arena.with(|bytes| used(bytes))
+    }
+
+    /// Remove all records from the arena.
+    pub fn clear(&self) {
+        self.arena.with(|bytes| {
+            for byte in bytes.iter_mut().take(2) {
+                *byte = 0;
+            }
+        })
+    }
+}
+
+impl fmt::Debug for ArenaFailurePersistence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (used, len) = self.arena.with(|bytes| (used(bytes), bytes.len()));
+        f.debug_struct("ArenaFailurePersistence")
+            .field("used", &used)
+            .field("len", &len)
+            .finish()
+    }
+}
+
+impl FailurePersistence for ArenaFailurePersistence {
+    fn load_persisted_failures2(
+        &self,
+        source_file: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        let source_file = match source_file {
+            Some(sf) => sf,
+            None => return Vec::new(),
+        };
+        self.arena.with(|bytes| {
+            records(bytes)
+                .filter_map(|(_, text)| {
+                    let mut fields = str::from_utf8(text).ok()?.split('\n');
+                    if fields.next()? != source_file {
+                        return None;
+                    }
+                    let mut seed =
+                        fields.next()?.parse::<PersistedSeed>().ok()?;
+                    seed.1 = fields.next()?.parse::<ShrinkPath>().ok()?;
+                    Some(seed)
+                })
+                .collect()
+        })
+    }
+
+    fn save_persisted_failure2(
+        &mut self,
+        source_file: Option<&'static str>,
+        seed: PersistedSeed,
+        _shrunken_value: &dyn fmt::Debug,
+    ) {
+        let source_file = match source_file {
+            Some(sf) => sf,
+            None => return,
+        };
+        let mut text = String::new();
+        let _ = write!(text, "{}\n{}\n{}", source_file, seed, seed.1);
+        let len = match u16::try_from(text.len()) {
+            Ok(len) if len < u16::MAX => len,
+            _ => return,
+        };
+
+        self.arena.with(|bytes| {
+            if records(bytes).any(|(_, record)| record == text.as_bytes()) {
+                return;
+            }
+            let start = used(bytes);
+            let end = start + 2 + text.len();
+            if end > bytes.len() {
+                return;
+            }
+            bytes[start..start + 2].copy_from_slice(&len.to_le_bytes());
+            bytes[start + 2..end].copy_from_slice(text.as_bytes());
+            if let Some(terminator) = bytes.get_mut(end..end + 2) {
+                terminator.copy_from_slice(&[0, 0]);
+            }
+        })
+    }
+
+    fn box_clone(&self) -> Box<dyn FailurePersistence> {
+        Box::new(self.clone())
+    }
+
+    fn eq(&self, other: &dyn FailurePersistence) -> bool {
+        other
+            .as_any()
+            .downcast_ref::<Self>()
+            .map_or(false, |x| Arc::ptr_eq(&x.arena, &self.arena))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_runner::failure_persistence::tests::*;
+
+    fn arena(len: usize, fill: u8) -> &'static mut [u8] {
+        Box::leak(vec![fill; len].into_boxed_slice())
+    }
+
+    #[test]
+    fn blank_arenas_are_empty() {
+        for &fill in &[0, 0xFF] {
+            let p = ArenaFailurePersistence::new(arena(64, fill));
+            assert!(p.load_persisted_failures2(HI_PATH).is_empty());
+            assert_eq!(0, p.used());
+        }
+    }
+
+    #[test]
+    fn seeds_recoverable() {
+        let mut p = ArenaFailurePersistence::new(arena(256, 0xFF));
+        let mut seed = INC_SEED;
+        seed.1 = "s3c1s2".parse().unwrap();
+        p.save_persisted_failure2(HI_PATH, seed.clone(), &"");
+        let restored = p.load_persisted_failures2(HI_PATH);
+        assert_eq!(vec![seed], restored);
+
+        assert!(p.load_persisted_failures2(None).is_empty());
+        assert!(p.load_persisted_failures2(UNREL_PATH).is_empty());
+    }
+
+    #[test]
+    fn seeds_deduplicated() {
+        let mut p = ArenaFailurePersistence::new(arena(256, 0));
+        p.save_persisted_failure2(HI_PATH, INC_SEED, &"");
+        let used = p.used();
+        p.save_persisted_failure2(HI_PATH, INC_SEED, &"");
+        assert_eq!(used, p.used());
+        assert_eq!(1, p.load_persisted_failures2(HI_PATH).len());
+    }
+
+    #[test]
+    fn records_survive_reloading_arena() {
+        let mut p = ArenaFailurePersistence::new(arena(256, 0xFF));
+        p.save_persisted_failure2(HI_PATH, INC_SEED, &"");
+        p.save_persisted_failure2(UNREL_PATH, INC_SEED, &"");
+        let copy = p.with_arena(|bytes| Box::leak(bytes.to_vec().into()));
+
+        let reloaded = ArenaFailurePersistence::new(copy);
+        assert_eq!(p.used(), reloaded.used());
+        assert_eq!(vec![INC_SEED], reloaded.load_persisted_failures2(HI_PATH));
+    }
+
+    #[test]
+    fn full_arena_drops_seeds() {
+        let mut p = ArenaFailurePersistence::new(arena(16, 0));
+        p.save_persisted_failure2(HI_PATH, INC_SEED, &"");
+        assert!(p.load_persisted_failures2(HI_PATH).is_empty());
+        assert_eq!(0, p.used());
+
+        p.clear();
+        let other = ArenaFailurePersistence::new(arena(16, 0));
+        assert!(FailurePersistence::eq(&*p.box_clone(), &p));
+        assert!(!FailurePersistence::eq(&p, &other));
+    }
+}
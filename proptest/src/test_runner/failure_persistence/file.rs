@@ -12,6 +12,7 @@ use core::fmt::Debug;
 use std::borrow::{Cow, ToOwned};
 use std::boxed::Box;
 use std::env;
+use std::ffi::OsString;
 use std::fs;
 use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
@@ -21,8 +22,9 @@ use std::vec::Vec;
 
 use self::FileFailurePersistence::*;
 use crate::test_runner::failure_persistence::{
-    FailurePersistence, PersistedSeed,
+    FailurePersistence, PersistedSeed, ShrinkPath,
 };
+use crate::Error;
 
 /// Describes how failing test cases are persisted.
 ///
@@ -69,6 +71,33 @@ pub enum FileFailurePersistence {
     /// The string given in this option is directly used as a file path without
     /// any further processing.
     Direct(&'static str),
+    /// The file path is built from `template` by replacing the following
+    /// placeholders:
+    ///
+    /// - `{source_dir}`: the directory of the source file under test.
+    /// - `{source_name}`: the name of the source file under test, without its
+    ///   extension.
+    /// - `{crate}`: the name of the crate the test is in.
+    /// - `{test_name}`: the path of the test within its crate, with one
+    ///   directory per module, e.g. `foo/bar/my_test` for a test
+    ///   `my_test` in module `foo::bar`.
+    ///
+    /// Characters other than ASCII letters, digits, `_` and `-` in the crate
+    /// and test names are replaced with `_`. A relative result is relative to
+    /// the working directory, like with `Direct`.
+    ///
+    /// For example, `Custom { template:
+    /// "{source_dir}/../regressions/{crate}/{test_name}.txt" }` keeps a
+    /// separate file for every test, in a directory tree next to the source
+    /// directory.
+    ///
+    /// If the template refers to a source file or test name which is not
+    /// known, or contains an unknown placeholder, a warning is printed and
+    /// this behaves like `Off`.
+    Custom {
+        /// The template of the file path.
+        template: &'static str,
+    },
     #[doc(hidden)]
     #[allow(missing_docs)]
     _NonExhaustive,
@@ -85,11 +114,20 @@ impl FailurePersistence for FileFailurePersistence {
         &self,
         source_file: Option<&'static str>,
     ) -> Vec<PersistedSeed> {
-        let p = self.resolve(
+        self.load_persisted_failures3(source_file, None)
+    }
+
+    fn load_persisted_failures3(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        let p = self.resolve_for_test(
             source_file
                 .and_then(|s| absolutize_source_file(Path::new(s)))
                 .as_ref()
                 .map(|cow| &**cow),
+            test_name,
         );
 
         let path: Option<&PathBuf> = p.as_ref();
@@ -129,7 +167,17 @@ impl FailurePersistence for FileFailurePersistence {
         seed: PersistedSeed,
         shrunken_value: &dyn Debug,
     ) {
-        let path = self.resolve(source_file.map(Path::new));
+        self.save_persisted_failure3(source_file, None, seed, shrunken_value)
+    }
+
+    fn save_persisted_failure3(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn Debug,
+    ) {
+        let path = self.resolve_for_test(source_file.map(Path::new), test_name);
         if let Some(path) = path {
             // .ok() instead of .unwrap() so we don't propagate panics here
             let _lock = PERSISTENCE_LOCK.write().ok();
@@ -254,13 +302,23 @@ fn parse_seed_line(
     path: &Path,
     lineno: usize,
 ) -> Option<PersistedSeed> {
-    // Remove anything after and including '#':
+    // Remove anything after and including '#', except for a shrink path
+    // written by `write_seed_line` at the start of the comment:
+    let mut shrink_path = None;
     if let Some(comment_start) = line.find('#') {
+        shrink_path = line[comment_start + 1..]
+            .trim_start()
+            .strip_prefix("shrink-path ")
+            .and_then(|path| path.split_whitespace().next())
+            .and_then(|path| path.parse::<ShrinkPath>().ok());
         line.truncate(comment_start);
     }
 
     if line.len() > 0 {
-        let ret = line.parse::<PersistedSeed>().ok();
+        let ret = line.parse::<PersistedSeed>().ok().map(|mut seed| {
+            seed.1 = shrink_path.unwrap_or_default();
+            seed
+        });
         if !ret.is_some() {
             eprintln!(
                 "proptest: {}:{}: unparsable line, ignoring",
@@ -282,7 +340,11 @@ fn write_seed_line(
     // Write the seed itself
     write!(buf, "{}", seed.to_string())?;
 
-    // Write out comment:
+    // Write out comments, the shrink path first so that it can be found
+    // regardless of what the value looks like:
+    if !seed.1.is_empty() {
+        write!(buf, " # shrink-path {}", seed.1)?;
+    }
     let debug_start = buf.len();
     write!(buf, " # shrinks to {:?}", shrunken_value)?;
 
@@ -298,6 +360,79 @@ fn write_seed_line(
     Ok(())
 }
 
+/// Read the lines of the file at `path`, which is empty if it doesn't exist.
+fn read_lines(path: &Path) -> io::Result<Vec<String>> {
+    match fs::File::open(path) {
+        Ok(file) => io::BufReader::new(file).lines().collect(),
+        Err(err) if io::ErrorKind::NotFound == err.kind() => Ok(vec![]),
+        Err(err) => Err(err),
+    }
+}
+
+/// Backs `FileFailurePersistence::prune()`.
+fn prune_file(
+    path: &Path,
+    mut still_fails: impl FnMut(&PersistedSeed) -> bool,
+) -> io::Result<usize> {
+    // .ok() instead of .unwrap() so we don't propagate panics here
+    let _lock = PERSISTENCE_LOCK.write().ok();
+    let lines = read_lines(path)?;
+    let total = lines.len();
+
+    let mut kept = Vec::<u8>::new();
+    let mut removed = 0;
+    for (lineno, line) in lines.into_iter().enumerate() {
+        let stale = parse_seed_line(line.clone(), path, lineno)
+            .map_or(false, |seed| !still_fails(&seed));
+        if stale {
+            removed += 1;
+        } else {
+            writeln!(kept, "{}", line)?;
+        }
+    }
+
+    if removed > 0 && total > 0 {
+        fs::write(path, kept)?;
+    }
+    Ok(removed)
+}
+
+/// Backs `FileFailurePersistence::merge()`.
+fn merge_files(dst: &Path, other: &[&Path]) -> io::Result<usize> {
+    // .ok() instead of .unwrap() so we don't propagate panics here
+    let _lock = PERSISTENCE_LOCK.write().ok();
+    let existing = read_lines(dst)?;
+    let mut seen = existing
+        .into_iter()
+        .enumerate()
+        .filter_map(|(lineno, line)| parse_seed_line(line, dst, lineno))
+        .map(|seed| seed.0)
+        .collect::<Vec<_>>();
+
+    let mut to_write = Vec::<u8>::new();
+    if !dst.is_file() {
+        write_header(&mut to_write)?;
+    }
+
+    let mut added = 0;
+    for &src in other {
+        for (lineno, line) in read_lines(src)?.into_iter().enumerate() {
+            if let Some(seed) = parse_seed_line(line.clone(), src, lineno) {
+                if !seen.contains(&seed.0) {
+                    writeln!(to_write, "{}", line)?;
+                    seen.push(seed.0);
+                    added += 1;
+                }
+            }
+        }
+    }
+
+    if added > 0 {
+        write_seed_data_to_file(dst, &to_write)?;
+    }
+    Ok(added)
+}
+
 fn write_header(buf: &mut Vec<u8>) -> io::Result<()> {
     writeln!(
         buf,
@@ -325,9 +460,91 @@ fn write_seed_data_to_file(dst: &Path, data: &[u8]) -> io::Result<()> {
 }
 
 impl FileFailurePersistence {
+    /// Rewrite the file of failures persisted for `source_file`, keeping only
+    /// the seeds for which `still_fails` returns `true`, and return how many
+    /// seeds were removed.
+    ///
+    /// This is meant for clearing out entries which no longer reproduce, e.g.
+    /// because the bug was fixed and the strategy changed since. Comments and
+    /// lines which can't be parsed are kept as is. Since a file holds the
+    /// failures of all tests in `source_file` (unless a `Custom` template
+    /// with `{test_name}` separates them), `still_fails` has to check a seed
+    /// against each of those tests, usually with `TestRunner::reproduces()`:
+    ///
+    /// ```no_run
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::{FileFailurePersistence, TestRunner};
+    ///
+    /// fn test_add(a: u32, b: u32) -> Result<(), TestCaseError> {
+    ///     prop_assert!(a.checked_add(b).is_some());
+    ///     Ok(())
+    /// }
+    ///
+    /// # /*
+    /// #[test]
+    /// #[ignore]
+    /// # */
+    /// fn prune_regressions() {
+    ///     let mut runner = TestRunner::default();
+    ///     let removed = FileFailurePersistence::default()
+    ///         .prune(file!(), None, |seed| {
+    ///             runner.reproduces(&(any::<u32>(), any::<u32>()), seed,
+    ///                               |(a, b)| test_add(a, b))
+    ///         })
+    ///         .unwrap();
+    ///     println!("Removed {} stale seeds", removed);
+    /// }
+    /// # fn main() { prune_regressions(); }
+    /// ```
+    ///
+    /// `test_name` is only needed for `Custom` templates using it. A missing
+    /// file counts as empty.
+    pub fn prune(
+        &self,
+        source_file: &'static str,
+        test_name: Option<&'static str>,
+        still_fails: impl FnMut(&PersistedSeed) -> bool,
+    ) -> Result<usize, Error> {
+        match self.resolve_for_test(Some(Path::new(source_file)), test_name) {
+            Some(path) => Ok(prune_file(&path, still_fails)?),
+            None => Ok(0),
+        }
+    }
+
+    /// Append the seeds of the `other` failure persistence files to the file
+    /// of failures persisted for `source_file`, skipping seeds it already
+    /// contains, and return how many seeds were added.
+    ///
+    /// This is meant for consolidating the files written by several machines,
+    /// such as CI runners, into the one checked in to source control. Seed
+    /// lines are copied along with their comments. The file is created if
+    /// necessary, while missing `other` files count as empty.
+    ///
+    /// `test_name` is only needed for `Custom` templates using it.
+    pub fn merge(
+        &self,
+        source_file: &'static str,
+        test_name: Option<&'static str>,
+        other: &[&Path],
+    ) -> Result<usize, Error> {
+        match self.resolve_for_test(Some(Path::new(source_file)), test_name) {
+            Some(path) => Ok(merge_files(&path, other)?),
+            None => Ok(0),
+        }
+    }
+
     /// Given the nominal source path, determine the location of the failure
     /// persistence file, if any.
     pub(super) fn resolve(&self, source: Option<&Path>) -> Option<PathBuf> {
+        self.resolve_for_test(source, None)
+    }
+
+    /// Like `resolve`, but also given the name of the test, if known.
+    fn resolve_for_test(
+        &self,
+        source: Option<&Path>,
+        test_name: Option<&str>,
+    ) -> Option<PathBuf> {
         let source = source.and_then(absolutize_source_file);
 
         match *self {
@@ -395,6 +612,20 @@ impl FileFailurePersistence {
 
             Direct(path) => Some(Path::new(path).to_owned()),
 
+            Custom { template } => {
+                match expand_template(template, source.as_deref(), test_name) {
+                    Ok(path) => Some(path),
+                    Err(err) => {
+                        eprintln!(
+                            "proptest: FileFailurePersistence::Custom set, \
+                             but {}",
+                            err
+                        );
+                        None
+                    }
+                }
+            }
+
             _NonExhaustive => {
                 panic!("FailurePersistence set to _NonExhaustive")
             }
@@ -402,6 +633,69 @@ impl FileFailurePersistence {
     }
 }
 
+/// Replaces the placeholders in the template of `FileFailurePersistence::Custom`.
+fn expand_template(
+    template: &str,
+    source: Option<&Path>,
+    test_name: Option<&str>,
+) -> Result<PathBuf, String> {
+    // Keep crate and test names from escaping into other directories or
+    // using characters some file systems don't allow.
+    fn sanitize(name: &str) -> String {
+        name.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || '_' == c || '-' == c {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+
+    let mut path = OsString::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        path.push(&rest[..start]);
+        let len = rest[start..].find('}').ok_or_else(|| {
+            format!("template {:?} has an unterminated placeholder", template)
+        })?;
+        let placeholder = &rest[start..start + len + 1];
+        rest = &rest[start + len + 1..];
+
+        match placeholder {
+            "{source_dir}" | "{source_name}" => {
+                let source = source.ok_or("no source file known")?;
+                if "{source_dir}" == placeholder {
+                    path.push(source.parent().unwrap_or_else(|| Path::new("")));
+                } else {
+                    path.push(source.file_stem().unwrap_or_default());
+                }
+            }
+            "{crate}" | "{test_name}" => {
+                let test_name = test_name.ok_or("no test name known")?;
+                let (krate, test) =
+                    test_name.split_once("::").unwrap_or(("", test_name));
+                if "{crate}" == placeholder {
+                    path.push(sanitize(krate));
+                } else {
+                    let test = test.split("::").map(sanitize);
+                    path.push(test.collect::<Vec<_>>().join("/"));
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "template {:?} has unknown placeholder {}",
+                    template, placeholder
+                ))
+            }
+        }
+    }
+    path.push(rest);
+
+    Ok(path.into())
+}
+
 lazy_static! {
     /// Used to guard access to the persistence file(s) so that a single
     /// process will not step on its own toes.
@@ -499,6 +793,45 @@ mod tests {
         assert_eq!(None, SourceParallel("ext").resolve(None));
     }
 
+    #[test]
+    fn custom_template_expanded() {
+        let template =
+            "{source_dir}/../regressions/{crate}/{test_name}.{source_name}";
+        let custom = Custom { template };
+        assert_eq!(
+            Some(
+                TEST_PATHS
+                    .subdir_file
+                    .parent()
+                    .unwrap()
+                    .join("../regressions/my-crate/a/b_c_/t.foo")
+            ),
+            custom.resolve_for_test(
+                Some(&TEST_PATHS.subdir_file),
+                Some("my-crate::a::b<c>::t")
+            )
+        );
+
+        // Anything which can't be expanded disables persistence
+        assert_eq!(None, custom.resolve_for_test(None, Some("c::t")));
+        assert_eq!(
+            None,
+            custom.resolve_for_test(Some(&TEST_PATHS.subdir_file), None)
+        );
+        let unknown = Custom {
+            template: "{nope}.txt",
+        };
+        assert_eq!(None, unknown.resolve_for_test(None, None));
+        let unterminated = Custom { template: "{crate" };
+        assert_eq!(None, unterminated.resolve_for_test(None, Some("c::t")));
+
+        // Without placeholders, this is just like Direct
+        let direct = Custom {
+            template: "bar.txt",
+        };
+        assert_eq!(Some(Path::new("bar.txt").to_owned()), direct.resolve(None));
+    }
+
     #[test]
     fn relative_source_files_absolutified() {
         const TEST_RUNNER_PATH: &[&str] = &["src", "test_runner", "mod.rs"];
@@ -532,4 +865,97 @@ mod tests {
             .unwrap()
         );
     }
+
+    fn seed_line(byte: u8) -> String {
+        let seed = PersistedSeed(
+            crate::test_runner::Seed::ChaCha([byte; 32]),
+            ShrinkPath::default(),
+        );
+        let mut buf = Vec::new();
+        write_seed_line(&mut buf, &seed, &byte).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    fn temp_file(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!(
+            "proptest-{}-{}.txt",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn prune_keeps_only_reproducing_seeds() {
+        let path = temp_file("prune");
+        let mut contents = Vec::new();
+        write_header(&mut contents).unwrap();
+        let contents = String::from_utf8(contents).unwrap()
+            + &seed_line(1)
+            + &seed_line(2)
+            + &seed_line(3);
+        fs::write(&path, &contents).unwrap();
+
+        let keep = |seed: &PersistedSeed| {
+            crate::test_runner::Seed::ChaCha([2; 32]) != seed.0
+        };
+        assert_eq!(1, prune_file(&path, keep).unwrap());
+
+        let pruned = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.replace(&seed_line(2), ""), pruned);
+        // Nothing more to remove
+        assert_eq!(0, prune_file(&path, keep).unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(0, prune_file(&path, |_| false).unwrap());
+        assert!(!path.is_file());
+    }
+
+    #[test]
+    fn merge_adds_only_new_seeds() {
+        let dst = temp_file("merge-dst");
+        let a = temp_file("merge-a");
+        let b = temp_file("merge-b");
+        fs::write(&a, seed_line(1) + &seed_line(2)).unwrap();
+        fs::write(&b, seed_line(2) + "# comment\n" + &seed_line(3)).unwrap();
+
+        assert_eq!(
+            3,
+            merge_files(&dst, &[&a, &b, &temp_file("none")]).unwrap()
+        );
+        assert_eq!(0, merge_files(&dst, &[&a, &b]).unwrap());
+
+        let merged = fs::read_to_string(&dst).unwrap();
+        assert!(merged.starts_with("# Seeds for failure cases"));
+        assert!(
+            merged.ends_with(&(seed_line(1) + &seed_line(2) + &seed_line(3)))
+        );
+        for path in [dst, a, b] {
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn shrink_path_survives_seed_line() {
+        let mut seed = PersistedSeed(
+            crate::test_runner::Seed::ChaCha([1; 32]),
+            "s3c1s2".parse().unwrap(),
+        );
+
+        let mut buf = Vec::new();
+        write_seed_line(&mut buf, &seed, &"# not a\nshrink-path c9").unwrap();
+        let line = String::from_utf8(buf).unwrap();
+        assert!(line.contains(" # shrink-path s3c1s2 # shrinks to "));
+        let parsed = parse_seed_line(line, Path::new("x"), 0);
+        assert_eq!(Some(seed.clone()), parsed);
+
+        // Lines without a shrink path still parse
+        let mut buf = Vec::new();
+        seed.1 = ShrinkPath::default();
+        write_seed_line(&mut buf, &seed, &0).unwrap();
+        let line = String::from_utf8(buf).unwrap();
+        assert!(!line.contains("shrink-path"));
+        assert_eq!(Some(seed), parse_seed_line(line, Path::new("x"), 0));
+    }
 }
@@ -13,24 +13,34 @@ use core::fmt::Display;
 use core::result::Result;
 use core::str::FromStr;
 
+mod arena;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 mod file;
 mod map;
 mod noop;
 
+pub use self::arena::*;
 #[cfg(feature = "std")]
 pub use self::file::*;
 pub use self::map::*;
 
+use crate::strategy::ValueTree;
 use crate::test_runner::Seed;
 
 /// Opaque struct representing a seed which can be persisted.
 ///
 /// The `Display` and `FromStr` implementations go to and from the format
-/// Proptest uses for its persistence file.
+/// Proptest uses for its persistence file. Along with the seed, this may carry
+/// the path shrinking took from the generated value to the minimal failing
+/// one, which is not part of those formats.
+///
+/// The format starts with a key naming the RNG algorithm the seed is for, such
+/// as `cc` for ChaCha or `xs` for XorShift, and persisted cases are always
+/// replayed with that algorithm, whatever `Config::rng_algorithm` is set to.
+/// Changing the algorithm thus keeps old persisted cases working.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct PersistedSeed(pub(crate) Seed);
+pub struct PersistedSeed(pub(crate) Seed, pub(crate) ShrinkPath);
 
 impl Display for PersistedSeed {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -42,7 +52,80 @@ impl FromStr for PersistedSeed {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, ()> {
-        Seed::from_persistence(s).map(PersistedSeed).ok_or(())
+        Seed::from_persistence(s)
+            .map(|seed| PersistedSeed(seed, ShrinkPath::default()))
+            .ok_or(())
+    }
+}
+
+/// The calls to `ValueTree::simplify()` and `ValueTree::complicate()` which
+/// took the value tree generated from a seed to its minimal failing value, so
+/// that the minimal value can be recreated without running the test.
+///
+/// The `Display` and `FromStr` forms are run-length encoded, e.g. `s3c1s2` for
+/// three simplifications, one complication and two more simplifications. An
+/// empty path means that it is unknown, or that no shrinking happened.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct ShrinkPath(Vec<(bool, u32)>);
+
+impl ShrinkPath {
+    /// Records a call to `simplify()` if `simplify` is true, or to
+    /// `complicate()` otherwise.
+    pub(crate) fn push(&mut self, simplify: bool) {
+        match self.0.last_mut() {
+            Some(&mut (last, ref mut count)) if last == simplify => *count += 1,
+            _ => self.0.push((simplify, 1)),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Makes the same calls on `tree`, which must be generated from the same
+    /// seed as the tree this path was recorded on.
+    pub(crate) fn replay<V: ValueTree>(&self, tree: &mut V) {
+        for &(simplify, count) in &self.0 {
+            for _ in 0..count {
+                if simplify {
+                    tree.simplify();
+                } else {
+                    tree.complicate();
+                }
+            }
+        }
+    }
+}
+
+impl Display for ShrinkPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(simplify, count) in &self.0 {
+            write!(f, "{}{}", if simplify { 's' } else { 'c' }, count)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ShrinkPath {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let mut path = Vec::new();
+        let mut rest = s;
+        while let Some(op) = rest.chars().next() {
+            let simplify = match op {
+                's' => true,
+                'c' => false,
+                _ => return Err(()),
+            };
+            rest = &rest[1..];
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            path.push((simplify, rest[..digits].parse().map_err(|_| ())?));
+            rest = &rest[digits..];
+        }
+        Ok(ShrinkPath(path))
     }
 }
 
@@ -51,7 +134,8 @@ impl FromStr for PersistedSeed {
 /// **Note**: Implementing `load_persisted_failures` and
 /// `save_persisted_failures` is **deprecated** and these methods will be
 /// removed in proptest 0.10.0. Instead, implement `load_persisted_failures2`
-/// and `save_persisted_failures2`.
+/// and `save_persisted_failures2`, or `load_persisted_failures3` and
+/// `save_persisted_failure3` if persistence depends on the test name.
 pub trait FailurePersistence: Send + Sync + fmt::Debug {
     /// Supply seeds associated with the given `source_file` that may be used
     /// by a `TestRunner`'s random number generator in order to consistently
@@ -67,10 +151,27 @@ pub trait FailurePersistence: Send + Sync + fmt::Debug {
     ) -> Vec<PersistedSeed> {
         self.load_persisted_failures(source_file)
             .into_iter()
-            .map(|seed| PersistedSeed(Seed::XorShift(seed)))
+            .map(|seed| {
+                PersistedSeed(Seed::XorShift(seed), ShrinkPath::default())
+            })
             .collect()
     }
 
+    /// Supply seeds associated with the given `source_file` and `test_name`,
+    /// like `load_persisted_failures2` does.
+    ///
+    /// This is what `TestRunner` calls, so that persistence can be kept per
+    /// test. The default implementation ignores `test_name` and delegates to
+    /// `load_persisted_failures2`.
+    #[allow(unused_variables)]
+    fn load_persisted_failures3(
+        &self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+    ) -> Vec<PersistedSeed> {
+        self.load_persisted_failures2(source_file)
+    }
+
     /// Use `load_persisted_failures2` instead.
     ///
     /// This function inadvertently exposes the implementation of seeds prior
@@ -103,6 +204,23 @@ pub trait FailurePersistence: Send + Sync + fmt::Debug {
         }
     }
 
+    /// Store a new failure-generating seed associated with the given
+    /// `source_file` and `test_name`, like `save_persisted_failure2` does.
+    ///
+    /// This is what `TestRunner` calls, so that persistence can be kept per
+    /// test. The default implementation ignores `test_name` and delegates to
+    /// `save_persisted_failure2`.
+    #[allow(unused_variables)]
+    fn save_persisted_failure3(
+        &mut self,
+        source_file: Option<&'static str>,
+        test_name: Option<&'static str>,
+        seed: PersistedSeed,
+        shrunken_value: &dyn fmt::Debug,
+    ) {
+        self.save_persisted_failure2(source_file, seed, shrunken_value)
+    }
+
     /// Use `save_persisted_failures2` instead.
     ///
     /// This function inadvertently exposes the implementation of seeds prior
@@ -144,13 +262,30 @@ impl Clone for Box<dyn FailurePersistence> {
 
 #[cfg(test)]
 mod tests {
-    use super::PersistedSeed;
+    use super::{PersistedSeed, ShrinkPath};
+    use crate::std_facade::Vec;
     use crate::test_runner::rng::Seed;
 
-    pub const INC_SEED: PersistedSeed = PersistedSeed(Seed::XorShift([
-        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
-    ]));
+    pub const INC_SEED: PersistedSeed = PersistedSeed(
+        Seed::XorShift([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]),
+        ShrinkPath(Vec::new()),
+    );
 
     pub const HI_PATH: Option<&str> = Some("hi");
     pub const UNREL_PATH: Option<&str> = Some("unrelated");
+
+    #[test]
+    fn shrink_path_round_trips() {
+        use std::string::ToString;
+
+        let mut path = ShrinkPath::default();
+        for &simplify in &[true, true, true, false, true, true] {
+            path.push(simplify);
+        }
+        assert_eq!("s3c1s2", path.to_string());
+        assert_eq!(Ok(path), "s3c1s2".parse());
+        assert_eq!(Ok(ShrinkPath::default()), "".parse());
+        assert_eq!(Err(()), "s3x1".parse::<ShrinkPath>());
+        assert_eq!(Err(()), "s".parse::<ShrinkPath>());
+    }
 }
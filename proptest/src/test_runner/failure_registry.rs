@@ -0,0 +1,112 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::std_facade::{fmt, String};
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::test_runner::{Config, Reason};
+
+/// The fingerprints of failures, mapped to the name of the test which failed
+/// with each first.
+type Failures = HashMap<String, Option<&'static str>>;
+
+lazy_static! {
+    /// All failures seen so far in this process.
+    static ref FAILURES: Mutex<Failures> = Mutex::new(HashMap::new());
+}
+
+/// A failure of the current test which is yet to be recorded in the registry.
+pub(crate) struct PendingFailure {
+    fingerprint: fn(&dyn fmt::Debug) -> String,
+    test: Option<&'static str>,
+    shrink_duplicates: bool,
+    original: String,
+    /// The test which first failed with the fingerprint of the original
+    /// input, if it was an earlier one.
+    earlier: Option<Option<&'static str>>,
+}
+
+impl PendingFailure {
+    /// Looks up the originally failing input of a test in the registry, if
+    /// `config` enables it.
+    pub(crate) fn new(
+        config: &Config,
+        original: &dyn fmt::Debug,
+    ) -> Option<Self> {
+        let fingerprint = config.failure_fingerprint?;
+        let test = config.test_name;
+        let original = fingerprint(original);
+        let earlier = lookup(&lock(), &original, test);
+        Some(PendingFailure {
+            fingerprint,
+            test,
+            shrink_duplicates: config.shrink_duplicate_failures,
+            original,
+            earlier,
+        })
+    }
+
+    /// Whether the failing input is to be shrunk.
+    pub(crate) fn should_shrink(&self) -> bool {
+        self.earlier.is_none() || self.shrink_duplicates
+    }
+
+    /// Records both the original and the minimal failing input in the
+    /// registry, and returns `why` amended to point at the earlier failure
+    /// if either was a duplicate.
+    pub(crate) fn finish(
+        self,
+        minimal: &dyn fmt::Debug,
+        why: Reason,
+    ) -> Reason {
+        let minimal = (self.fingerprint)(minimal);
+        let mut failures = lock();
+        let earlier = self
+            .earlier
+            .or_else(|| lookup(&failures, &minimal, self.test));
+        for fingerprint in [self.original, minimal] {
+            failures.entry(fingerprint).or_insert(self.test);
+        }
+
+        match earlier {
+            None => why,
+            Some(Some(test)) => format!(
+                "{}; duplicate of earlier failure in `{}`",
+                why.message(),
+                test
+            )
+            .into(),
+            Some(None) => {
+                format!("{}; duplicate of earlier failure", why.message())
+                    .into()
+            }
+        }
+    }
+}
+
+fn lock() -> MutexGuard<'static, Failures> {
+    // A panic while holding the lock can't leave the map inconsistent.
+    FAILURES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Returns the test which first failed with `fingerprint`, unless it is
+/// `test` itself.
+fn lookup(
+    failures: &Failures,
+    fingerprint: &str,
+    test: Option<&'static str>,
+) -> Option<Option<&'static str>> {
+    failures
+        .get(fingerprint)
+        .copied()
+        .filter(|&earlier| test.is_none() || earlier != test)
+}
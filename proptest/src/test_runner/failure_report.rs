@@ -0,0 +1,107 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::string::{String, ToString};
+
+use crate::test_runner::failure_persistence::PersistedSeed;
+use crate::test_runner::shrink_metrics::{append_json_line, json_string};
+use crate::test_runner::Reason;
+use crate::Error;
+
+/// What is known about a failing test once its input has been shrunk, as
+/// written to `Config::failure_report_path`.
+pub(crate) struct FailureReport<'a> {
+    pub(crate) test_name: Option<&'a str>,
+    pub(crate) source_file: Option<&'a str>,
+    pub(crate) seed: &'a PersistedSeed,
+    pub(crate) reason: &'a Reason,
+    pub(crate) shrink_iterations: u32,
+    pub(crate) minimal: &'a dyn fmt::Debug,
+}
+
+impl FailureReport<'_> {
+    /// Write the report to `out` as a single line of JSON.
+    ///
+    /// The line is an object with the keys `test` and `source_file` (strings,
+    /// or `null` if not known), `seed` (as it would be persisted, e.g.
+    /// `"cc 1a2b..."`), `rng_algorithm` (as accepted by
+    /// `PROPTEST_RNG_ALGORITHM`), `shrink_path`, `shrink_iterations`,
+    /// `reason` and `minimal_input`, the `Debug` output of the minimal
+    /// failing input.
+    fn write(&self, out: &mut dyn Write) -> Result<(), Error> {
+        let optional = |s: Option<&str>| match s {
+            Some(s) => json_string(s),
+            None => String::from("null"),
+        };
+        let PersistedSeed(ref seed, ref shrink_path) = *self.seed;
+        writeln!(
+            out,
+            "{{\"test\":{},\"source_file\":{},\"seed\":{},\
+             \"rng_algorithm\":{},\"shrink_path\":{},\
+             \"shrink_iterations\":{},\"reason\":{},\"minimal_input\":{}}}",
+            optional(self.test_name),
+            optional(self.source_file),
+            json_string(&seed.to_persistence()),
+            json_string(seed.algorithm().persistence_key()),
+            json_string(&shrink_path.to_string()),
+            self.shrink_iterations,
+            json_string(self.reason.message()),
+            json_string(&format!("{:?}", self.minimal)),
+        )?;
+        Ok(())
+    }
+}
+
+/// Append `report` as one line of JSON to the file at `path`, creating the
+/// file if it does not exist.
+pub(crate) fn append_failure_report(
+    path: &Path,
+    report: &FailureReport<'_>,
+) -> Result<(), Error> {
+    append_json_line(path, |out| report.write(out))
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+
+    use super::*;
+    use crate::test_runner::failure_persistence::ShrinkPath;
+    use crate::test_runner::rng::Seed;
+    use crate::test_runner::RngAlgorithm;
+
+    #[test]
+    fn writes_one_json_line() {
+        let seed = PersistedSeed(
+            Seed::from_bytes(RngAlgorithm::XorShift, &[0; 16]),
+            ShrinkPath::default(),
+        );
+        let report = FailureReport {
+            test_name: Some("my_crate::my_test"),
+            source_file: None,
+            seed: &seed,
+            reason: &Reason::from("assertion failed: \"x\" < 5"),
+            shrink_iterations: 7,
+            minimal: &vec![5],
+        };
+        let mut out = Vec::new();
+        report.write(&mut out).unwrap();
+        assert_eq!(
+            "{\"test\":\"my_crate::my_test\",\"source_file\":null,\
+             \"seed\":\"xs 0 0 0 0\",\"rng_algorithm\":\"xs\",\
+             \"shrink_path\":\"\",\"shrink_iterations\":7,\
+             \"reason\":\"assertion failed: \\\"x\\\" < 5\",\
+             \"minimal_input\":\"[5]\"}\n",
+            std::str::from_utf8(&out).unwrap()
+        );
+    }
+}
@@ -12,9 +12,20 @@
 //! You do not normally need to access things in this module directly except
 //! when implementing new low-level strategies.
 
+#[cfg(feature = "std")]
+mod block_on;
+mod budget;
+mod choice;
 mod config;
+#[cfg(feature = "std")]
+mod coverage;
 mod errors;
+mod events;
 mod failure_persistence;
+#[cfg(feature = "failure-registry")]
+mod failure_registry;
+#[cfg(feature = "std")]
+mod failure_report;
 mod reason;
 #[cfg(feature = "fork")]
 mod replay;
@@ -22,11 +33,26 @@ mod result_cache;
 mod rng;
 mod runner;
 mod scoped_panic_hook;
+mod scratch;
+#[cfg(feature = "std")]
+mod shrink_metrics;
 
+#[cfg(feature = "std")]
+pub use self::block_on::*;
+pub use self::budget::*;
+pub use self::choice::*;
 pub use self::config::*;
+#[cfg(feature = "std")]
+pub use self::coverage::cover;
+#[cfg(feature = "coverage-guided")]
+pub use self::coverage::CoverageGuided;
 pub use self::errors::*;
+pub use self::events::*;
 pub use self::failure_persistence::*;
 pub use self::reason::*;
 pub use self::result_cache::*;
 pub use self::rng::*;
 pub use self::runner::*;
+pub use self::scratch::*;
+#[cfg(feature = "std")]
+pub use self::shrink_metrics::*;
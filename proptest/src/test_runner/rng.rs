@@ -7,7 +7,7 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::std_facade::{Arc, String, ToOwned, Vec};
+use crate::std_facade::{Arc, Box, String, ToOwned, Vec};
 use core::result::Result;
 use core::{fmt, str, u8, convert::TryInto};
 
@@ -15,6 +15,8 @@ use rand::{self, Rng, RngCore, SeedableRng};
 use rand_chacha::ChaChaRng;
 use rand_xorshift::XorShiftRng;
 
+use crate::test_runner::choice::ChoiceSource;
+
 /// Identifies a particular RNG algorithm supported by proptest.
 ///
 /// Proptest supports dynamic configuration of algorithms to allow it to
@@ -124,6 +126,7 @@ enum TestRngImpl {
         rng: ChaChaRng,
         record: Vec<u8>,
     },
+    Choices(Box<dyn ChoiceSource>),
 }
 
 impl RngCore for TestRng {
@@ -147,6 +150,10 @@ impl RngCore for TestRng {
                 record.extend_from_slice(&read.to_le_bytes());
                 read
             }
+
+            &mut TestRngImpl::Choices(ref mut source) => {
+                source.choose_int(u32::MAX.into()) as u32
+            }
         }
     }
 
@@ -170,6 +177,10 @@ impl RngCore for TestRng {
                 record.extend_from_slice(&read.to_le_bytes());
                 read
             }
+
+            &mut TestRngImpl::Choices(ref mut source) => {
+                source.choose_int(u64::MAX)
+            }
         }
     }
 
@@ -201,6 +212,12 @@ impl RngCore for TestRng {
                 record.extend_from_slice(&dest);
                 res
             }
+
+            &mut TestRngImpl::Choices(ref mut source) => {
+                for byte in dest {
+                    *byte = source.choose_int(u8::MAX.into()) as u8;
+                }
+            }
         }
     }
 
@@ -225,6 +242,11 @@ impl RngCore for TestRng {
                 }
                 res
             }
+
+            TestRngImpl::Choices(..) => {
+                self.fill_bytes(dest);
+                Ok(())
+            }
         }
     }
 }
@@ -267,6 +289,17 @@ impl Seed {
         }
     }
 
+    /// Returns the algorithm of the RNG this seeds.
+    #[cfg(feature = "std")]
+    pub(crate) fn algorithm(&self) -> RngAlgorithm {
+        match *self {
+            Seed::XorShift(_) => RngAlgorithm::XorShift,
+            Seed::ChaCha(_) => RngAlgorithm::ChaCha,
+            Seed::PassThrough(..) => RngAlgorithm::PassThrough,
+            Seed::Recorder(_) => RngAlgorithm::Recorder,
+        }
+    }
+
     pub(crate) fn from_persistence(string: &str) -> Option<Seed> {
         fn from_base16(dst: &mut [u8], src: &str) -> Option<()> {
             if dst.len() * 2 != src.len() {
@@ -414,6 +447,23 @@ impl TestRng {
         TestRng::from_seed_internal(Seed::from_bytes(algorithm, seed))
     }
 
+    /// Create a new RNG which answers every request for randomness from the
+    /// given `ChoiceSource`.
+    ///
+    /// This allows any existing strategy to be driven by an external source
+    /// of choices, such as a fuzzer via `ByteChoiceSource`. RNGs derived from
+    /// this one (e.g., by `TestRunner::new_rng()`) fork the source.
+    ///
+    /// Since a choice source cannot be captured in a seed, an RNG created
+    /// this way does not support failure persistence or forking; use it with
+    /// `Strategy::new_tree()` and `TestRunner::run_one()` rather than
+    /// `TestRunner::run()`.
+    pub fn from_choice_source(source: impl ChoiceSource + 'static) -> Self {
+        TestRng {
+            rng: TestRngImpl::Choices(Box::new(source)),
+        }
+    }
+
     /// Dumps the bytes obtained from the RNG so far (only works if the RNG is
     /// set to `Recorder`).
     ///
@@ -552,9 +602,76 @@ impl TestRng {
         })
     }
 
+    /// Returns a `TestRng` derived from one entry of `Config::seed_set`.
+    ///
+    /// This is the deterministic RNG with `seed` mixed into its seed, so the
+    /// same `seed` always produces the same sequence for a particular version
+    /// of Proptest and algorithm.
+    pub(crate) fn from_golden_seed(algorithm: RngAlgorithm, seed: u64) -> Self {
+        Self::from_seed_internal(match algorithm {
+            RngAlgorithm::XorShift => {
+                Seed::XorShift(mix(TestRng::SEED_FOR_XOR_SHIFT, seed))
+            }
+            RngAlgorithm::ChaCha => {
+                Seed::ChaCha(mix(TestRng::SEED_FOR_CHA_CHA, seed))
+            }
+            RngAlgorithm::PassThrough => {
+                panic!("seed sets are not available for PassThrough")
+            }
+            RngAlgorithm::Recorder => {
+                Seed::Recorder(mix(TestRng::SEED_FOR_CHA_CHA, seed))
+            }
+            RngAlgorithm::_NonExhaustive => unreachable!(),
+        })
+    }
+
+    /// Returns an RNG for the stream called `name` which only depends on the
+    /// algorithm of this one, not on its state.
+    ///
+    /// Returns `None` if this RNG is backed by a `ChoiceSource` or records or
+    /// replays its data, since those streams can't be replaced.
+    #[cfg(feature = "std")]
+    pub(crate) fn detached(&self, name: &str) -> Option<Self> {
+        let algorithm = match self.rng {
+            TestRngImpl::XorShift(_) => RngAlgorithm::XorShift,
+            TestRngImpl::ChaCha(_) => RngAlgorithm::ChaCha,
+            _ => return None,
+        };
+        Some(Self::deterministic_rng(algorithm).fork_named(name))
+    }
+
+    /// Construct a TestRng for the stream called `name` forked from (a copy
+    /// of) this one. See `SubRngScope`.
+    fn fork_named(&self, name: &str) -> Self {
+        let mut base = self.clone();
+        if let TestRngImpl::Choices(..) = base.rng {
+            return base.gen_rng();
+        }
+
+        // FNV-1a, which unlike `Hash` is stable across platforms and
+        // releases.
+        let name = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        });
+        Self::from_seed_internal(match base.new_rng_seed() {
+            Seed::XorShift(seed) => Seed::XorShift(mix(seed, name)),
+            Seed::ChaCha(seed) => Seed::ChaCha(mix(seed, name)),
+            Seed::Recorder(seed) => Seed::Recorder(mix(seed, name)),
+            // Pass-through data can't be perturbed, so all names share the
+            // same stream.
+            seed @ Seed::PassThrough(..) => seed,
+        })
+    }
+
     /// Construct a TestRng by the perturbed randomized seed
     /// from an existing TestRng.
     pub(crate) fn gen_rng(&mut self) -> Self {
+        if let TestRngImpl::Choices(ref mut source) = self.rng {
+            return TestRng {
+                rng: TestRngImpl::Choices(source.fork()),
+            };
+        }
+
         Self::from_seed_internal(self.new_rng_seed())
     }
 
@@ -610,6 +727,11 @@ impl TestRng {
             TestRngImpl::Recorder { ref mut rng, .. } => {
                 Seed::Recorder(rng.gen())
             }
+
+            TestRngImpl::Choices(..) => panic!(
+                "cannot derive a persistable seed from an RNG backed by a \
+                 ChoiceSource"
+            ),
         }
     }
 
@@ -643,6 +765,146 @@ impl TestRng {
     }
 }
 
+/// XOR `value` into the first bytes of `bytes`.
+fn mix<const N: usize>(mut bytes: [u8; N], value: u64) -> [u8; N] {
+    for (b, v) in bytes.iter_mut().zip(value.to_le_bytes().iter()) {
+        *b ^= v;
+    }
+    bytes
+}
+
+/// A fork point from which any number of independent, named RNG streams can
+/// be derived, created by `TestRunner::sub_rng_scope()`.
+///
+/// Strategies normally draw all of their randomness from `TestRunner::rng()`,
+/// in order. When a strategy has several orthogonal components, e.g. the
+/// nodes and the edges of a graph, this entangles them: drawing one more
+/// value for the nodes shifts every value drawn for the edges afterwards.
+/// Drawing each component from its own stream decouples them, so that the
+/// edges stay the same whatever happens to the nodes.
+///
+/// The stream for a given name only depends on the state of the runner's RNG
+/// when the scope was created, and asking for the same name twice yields the
+/// same stream. Creating the scope advances the runner's RNG by a fixed
+/// amount, independent of how much randomness is drawn from the streams.
+///
+/// ```
+/// use proptest::test_runner::TestRunner;
+/// use rand::Rng;
+///
+/// let mut runner = TestRunner::deterministic();
+/// let scope = runner.sub_rng_scope();
+/// let node_count = scope.rng("nodes").gen_range(1..10);
+/// let mut edges = scope.rng("edges");
+/// let first_edge: (u8, u8) = (edges.gen(), edges.gen());
+///
+/// // The edges don't depend on how much was drawn for the nodes.
+/// let mut edges_again = scope.rng("edges");
+/// assert_eq!(first_edge, (edges_again.gen(), edges_again.gen()));
+/// # let _ = node_count;
+/// ```
+#[derive(Clone, Debug)]
+pub struct SubRngScope {
+    base: TestRng,
+}
+
+impl SubRngScope {
+    pub(crate) fn new(base: TestRng) -> Self {
+        SubRngScope { base }
+    }
+
+    /// Returns the RNG stream called `name` in this scope.
+    ///
+    /// For RNGs backed by a `ChoiceSource` or pass-through data, all names
+    /// share one stream, since those cannot be perturbed.
+    pub fn rng(&self, name: &str) -> TestRng {
+        self.base.fork_named(name)
+    }
+}
+
+/// The random number generation proptest provides to strategies, as a
+/// stable interface independent of the version of `rand` proptest uses.
+///
+/// Crates defining strategies usually need nothing more than a few kinds of
+/// random values from the `TestRng` of a runner (or the one passed to
+/// `prop_perturb()`). Using `rand::Rng` for this requires depending on the
+/// same major version of `rand` as proptest does, so that these crates have
+/// to release in lockstep with proptest whenever it upgrades `rand`. The
+/// methods of this trait, by contrast, remain the same across such upgrades,
+/// so a crate using only them needs no dependency on `rand` at all.
+///
+/// The `RngCore` supertrait, like the `Rng` and `RngCore` re-exports of the
+/// prelude, always is that of the version of `rand` proptest currently uses,
+/// so code naming it is not insulated from upgrades.
+///
+/// This is implemented for every `RngCore`, including `TestRng`.
+///
+/// ```
+/// use proptest::strategy::{NewTree, Strategy};
+/// use proptest::test_runner::{ProptestRng, TestRunner};
+///
+/// /// Generates dice rolls without depending on `rand`.
+/// #[derive(Debug)]
+/// struct Die;
+///
+/// impl Strategy for Die {
+///     type Tree = proptest::strategy::Just<u8>;
+///     type Value = u8;
+///
+///     fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+///         let roll = 1 + runner.rng().below(6) as u8;
+///         Ok(proptest::strategy::Just(roll))
+///     }
+/// }
+///
+/// let mut runner = TestRunner::deterministic();
+/// let roll = Die.new_tree(&mut runner).unwrap();
+/// # use proptest::strategy::ValueTree;
+/// assert!((1..=6).contains(&roll.current()));
+/// ```
+pub trait ProptestRng: RngCore {
+    /// Returns a random `u32`.
+    fn u32(&mut self) -> u32 {
+        self.next_u32()
+    }
+
+    /// Returns a random `u64`.
+    fn u64(&mut self) -> u64 {
+        self.next_u64()
+    }
+
+    /// Fills `dest` with random bytes.
+    fn bytes(&mut self, dest: &mut [u8]) {
+        self.fill_bytes(dest)
+    }
+
+    /// Returns `true` with probability `p`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `p` is not between 0 and 1 inclusive.
+    fn bool(&mut self, p: f64) -> bool {
+        self.gen_bool(p)
+    }
+
+    /// Returns a value below `n`, uniformly distributed.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `n` is 0.
+    fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "ProptestRng::below() called with n = 0");
+        self.gen_range(0..n)
+    }
+
+    /// Returns a value in `[0, 1)`, uniformly distributed.
+    fn unit_f64(&mut self) -> f64 {
+        self.gen()
+    }
+}
+
+impl<R: RngCore + ?Sized> ProptestRng for R {}
+
 #[cfg(test)]
 mod test {
     use crate::std_facade::Vec;
@@ -707,6 +969,22 @@ mod test {
         }
     }
 
+    #[test]
+    fn sub_rng_scope_streams_are_named_and_stable() {
+        use super::SubRngScope;
+
+        for algorithm in [RngAlgorithm::XorShift, RngAlgorithm::ChaCha] {
+            let scope = SubRngScope::new(TestRng::deterministic_rng(algorithm));
+            let a = scope.rng("a").gen::<[u8; 32]>();
+            assert_eq!(a, scope.rng("a").gen::<[u8; 32]>());
+            assert_ne!(a, scope.rng("b").gen::<[u8; 32]>());
+            assert_ne!(
+                a,
+                TestRng::deterministic_rng(algorithm).gen::<[u8; 32]>()
+            );
+        }
+    }
+
     #[test]
     fn passthrough_rng_behaves_properly() {
         let mut rng = TestRng::from_seed(
@@ -726,4 +1004,23 @@ mod test {
         rng.try_fill_bytes(&mut buf[0..4]).unwrap();
         assert_eq!([0, 0, 0, 0], buf);
     }
+
+    #[test]
+    fn proptest_rng_matches_test_rng() {
+        use super::ProptestRng;
+
+        let mut expected = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+        let mut rng = TestRng::deterministic_rng(RngAlgorithm::ChaCha);
+        let rng: &mut dyn ProptestRng = &mut rng;
+
+        assert_eq!(expected.next_u32(), rng.u32());
+        assert_eq!(expected.next_u64(), rng.u64());
+        for _ in 0..256 {
+            assert!(rng.below(3) < 3);
+            let f = rng.unit_f64();
+            assert!((0.0..1.0).contains(&f));
+        }
+        assert!(rng.bool(1.0));
+        assert!(!rng.bool(0.0));
+    }
 }
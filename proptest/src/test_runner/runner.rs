@@ -7,10 +7,14 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+#[cfg(feature = "std")]
+use crate::std_facade::BTreeSet;
 use crate::std_facade::{Arc, BTreeMap, Box, String, Vec};
+use core::borrow::Borrow;
 use core::sync::atomic::AtomicUsize;
 use core::sync::atomic::Ordering::SeqCst;
-use core::{fmt, iter};
+use core::time::Duration;
+use core::{fmt, iter, mem};
 #[cfg(feature = "std")]
 use std::panic::{self, AssertUnwindSafe};
 
@@ -23,20 +27,40 @@ use std::env;
 #[cfg(feature = "fork")]
 use std::fs;
 #[cfg(feature = "fork")]
+use std::io::{self, Read, Seek};
+#[cfg(feature = "fork")]
+use std::process;
+#[cfg(feature = "fork")]
 use tempfile;
 
 use crate::strategy::*;
+use crate::test_runner::budget::Budget;
 use crate::test_runner::config::*;
 use crate::test_runner::errors::*;
-use crate::test_runner::failure_persistence::PersistedSeed;
+use crate::test_runner::events::*;
+use crate::test_runner::failure_persistence::{PersistedSeed, ShrinkPath};
+#[cfg(feature = "failure-registry")]
+use crate::test_runner::failure_registry::PendingFailure;
+#[cfg(feature = "std")]
+use crate::test_runner::failure_report::{
+    append_failure_report, FailureReport,
+};
 use crate::test_runner::reason::*;
 #[cfg(feature = "fork")]
 use crate::test_runner::replay;
 use crate::test_runner::result_cache::*;
-use crate::test_runner::rng::TestRng;
+#[cfg(feature = "std")]
+use crate::test_runner::rng::Seed;
+use crate::test_runner::rng::{RngAlgorithm, SubRngScope, TestRng};
+use crate::test_runner::scratch::RunnerScratch;
+#[cfg(feature = "std")]
+use crate::test_runner::shrink_metrics::append_shrink_metrics;
 
 #[cfg(feature = "fork")]
 const ENV_FORK_FILE: &'static str = "_PROPTEST_FORKFILE";
+/// Set to the name of the test in processes started by `ForkBackend::Reexec`.
+#[cfg(feature = "fork")]
+const ENV_REEXEC_TEST: &'static str = "_PROPTEST_REEXEC_TEST";
 
 const ALWAYS: u32 = 0;
 /// Verbose level 1 to show failures. In state machine tests this level is used
@@ -44,7 +68,7 @@ const ALWAYS: u32 = 0;
 pub const INFO_LOG: u32 = 1;
 const TRACE: u32 = 2;
 
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", not(feature = "tracing")))]
 macro_rules! verbose_message {
     ($runner:expr, $level:expr, $fmt:tt $($arg:tt)*) => { {
         #[allow(unused_comparisons)]
@@ -57,6 +81,20 @@ macro_rules! verbose_message {
     } }
 }
 
+// With `tracing`, the subscriber decides what is shown, so `verbose` only
+// picks the level of each message.
+#[cfg(feature = "tracing")]
+macro_rules! verbose_message {
+    ($runner:expr, $level:expr, $fmt:tt $($arg:tt)*) => { {
+        let _ = &$runner;
+        match $level {
+            ALWAYS => tracing::warn!(target: "proptest", $fmt $($arg)*),
+            INFO_LOG => tracing::info!(target: "proptest", $fmt $($arg)*),
+            _ => tracing::trace!(target: "proptest", $fmt $($arg)*),
+        }
+    } }
+}
+
 #[cfg(not(feature = "std"))]
 macro_rules! verbose_message {
     ($runner:expr, $level:expr, $fmt:tt $($arg:tt)*) => {
@@ -66,6 +104,150 @@ macro_rules! verbose_message {
 
 type RejectionDetail = BTreeMap<Reason, u32>;
 
+/// Passes calls through to `tree`, recording the path shrinking takes and
+/// counting the calls.
+struct RecordShrinkPath<'a, V> {
+    tree: &'a mut V,
+    path: ShrinkPath,
+    simplify_calls: u32,
+    complicate_calls: u32,
+}
+
+impl<V: ValueTree> ValueTree for RecordShrinkPath<'_, V> {
+    type Value = V::Value;
+
+    fn current(&self) -> V::Value {
+        self.tree.current()
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.path.push(true);
+        self.simplify_calls += 1;
+        self.tree.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.path.push(false);
+        self.complicate_calls += 1;
+        self.tree.complicate()
+    }
+}
+
+/// Counters describing how the most recent shrinking of a failing case went,
+/// returned by `TestRunner::shrink_stats()`.
+///
+/// These are mainly useful to authors of `ValueTree` implementations, to
+/// check in tests that their trees shrink without spinning.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ShrinkStats {
+    /// The number of times the test was run against a shrunk value.
+    pub iterations: u32,
+    /// The number of calls to `simplify()` which returned `true`.
+    pub simplifications: u32,
+    /// The number of calls to `complicate()` which returned `true`.
+    pub complications: u32,
+    /// The number of calls to `simplify()`, including those which returned
+    /// `false`.
+    pub simplify_calls: u32,
+    /// The number of calls to `complicate()`, including those which returned
+    /// `false`.
+    pub complicate_calls: u32,
+    /// The longest run of consecutive iterations whose values had the same
+    /// `Debug` output as the one before, if `Config::max_shrink_repeats` is
+    /// non-zero.
+    pub max_repeats: u32,
+    /// Whether shrinking was cut short by `Config::max_shrink_repeats`.
+    pub stuck: bool,
+    /// The wall time shrinking took. This is only measured with the `std`
+    /// feature and outside of WebAssembly, and is zero otherwise.
+    pub elapsed: Duration,
+    /// The length in bytes of the `Debug` output of the failing value
+    /// shrinking started from. Like `minimal_size`, this is only measured if
+    /// `Config::shrink_metrics_file` is set, and is zero otherwise.
+    pub original_size: usize,
+    /// The length in bytes of the `Debug` output of the minimal failing value
+    /// shrinking ended with.
+    pub minimal_size: usize,
+}
+
+impl ShrinkStats {
+    /// Returns by how many bytes shrinking reduced the `Debug` output of the
+    /// failing value, i.e., `original_size - minimal_size`, or 0 if it grew.
+    ///
+    /// Together with `iterations`, this allows tests of strategies to assert
+    /// that shrinking still gets as far as it used to, and as quickly.
+    pub fn size_reduction(&self) -> usize {
+        self.original_size.saturating_sub(self.minimal_size)
+    }
+}
+
+/// Adapts a test taking a borrow of its input, such as `&str` or `&[u8]`, to
+/// one taking the owned value, such as `String` or `Vec<u8>`, as expected by
+/// `TestRunner::run()` and friends.
+///
+/// The owned value is only dropped after the test returns, so this never
+/// clones it.
+///
+/// ```
+/// use proptest::prelude::*;
+/// use proptest::test_runner::{with_borrowed, TestRunner};
+///
+/// let mut runner = TestRunner::default();
+/// let test = with_borrowed(|bytes: &[u8]| {
+///     prop_assert!(bytes.len() < 10);
+///     Ok(())
+/// });
+/// assert_eq!(Ok(true), runner.run_value(vec![1u8, 2, 3], test));
+/// ```
+pub fn with_borrowed<T: Borrow<B>, B: ?Sized>(
+    test: impl Fn(&B) -> TestCaseResult,
+) -> impl Fn(T) -> TestCaseResult {
+    move |value| test(value.borrow())
+}
+
+/// Hashes the `Debug` output of a value without allocating, using FNV-1a.
+struct DebugHasher(u64);
+
+impl DebugHasher {
+    fn hash(value: &dyn fmt::Debug) -> u64 {
+        use core::fmt::Write;
+
+        let mut hasher = DebugHasher(0xcbf2_9ce4_8422_2325);
+        let _ = write!(hasher, "{:?}", value);
+        hasher.0
+    }
+}
+
+impl fmt::Write for DebugHasher {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for b in s.bytes() {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3);
+        }
+        Ok(())
+    }
+}
+
+/// Counts the bytes of the `Debug` output of a value without allocating.
+struct DebugLen(usize);
+
+impl DebugLen {
+    fn of(value: &dyn fmt::Debug) -> usize {
+        use core::fmt::Write;
+
+        let mut len = DebugLen(0);
+        let _ = write!(len, "{:?}", value);
+        len.0
+    }
+}
+
+impl fmt::Write for DebugLen {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
 /// State used when running a proptest test.
 #[derive(Clone)]
 pub struct TestRunner {
@@ -75,23 +257,82 @@ pub struct TestRunner {
     global_rejects: u32,
     rng: TestRng,
     flat_map_regens: Arc<AtomicUsize>,
+    budget: Budget,
 
     local_reject_detail: RejectionDetail,
     global_reject_detail: RejectionDetail,
+    label_counts: BTreeMap<&'static str, u32>,
+    /// The local rejections by each filter with a budget, by name.
+    filter_rejects: BTreeMap<&'static str, u32>,
+    /// Whether the current case showed novel behaviour, for
+    /// `Config::adaptive_cases`.
+    novel_case: bool,
+    /// The number of successful cases including cache hits, which
+    /// `Config::adaptive_cases` counts.
+    adaptive_successes: u32,
+    /// The number of consecutive successful cases without novel behaviour.
+    stale_cases: u32,
+    /// The options picked by `AdaptiveUnion`s while generating the current
+    /// case, which are told if the case is rejected. `None` outside of
+    /// generating a case.
+    union_picks: Option<Vec<(Arc<UnionFeedback>, usize)>>,
+    /// The counters of the `MemoFlatten`s used with this runner.
+    #[cfg(feature = "std")]
+    memo_stats: Vec<Arc<MemoStats>>,
+    /// The values memoized through `scratch()`.
+    scratch: RunnerScratch,
+    /// A fingerprint of the seed this runner was created with, which the
+    /// values of `scratch` are tied to.
+    scratch_seed: u64,
+    /// The points passed to `cover()` by the cases run so far.
+    #[cfg(feature = "std")]
+    covered: BTreeSet<String>,
+
+    event_sink: Option<Arc<dyn EventSink>>,
+    shrink_stats: ShrinkStats,
+    shrink_path: ShrinkPath,
+    /// The RNG driving chaos mode in the current case.
+    #[cfg(feature = "chaos")]
+    chaos_rng: Option<TestRng>,
+    /// The corpus of `Config::coverage_guided`.
+    #[cfg(feature = "coverage-guided")]
+    corpus: super::coverage::Corpus,
 }
 
 impl fmt::Debug for TestRunner {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("TestRunner")
+        let mut debug = f.debug_struct("TestRunner");
+        debug
             .field("config", &self.config)
             .field("successes", &self.successes)
             .field("local_rejects", &self.local_rejects)
             .field("global_rejects", &self.global_rejects)
             .field("rng", &"<TestRng>")
             .field("flat_map_regens", &self.flat_map_regens)
+            .field("budget", &self.budget)
             .field("local_reject_detail", &self.local_reject_detail)
             .field("global_reject_detail", &self.global_reject_detail)
-            .finish()
+            .field("label_counts", &self.label_counts)
+            .field("filter_rejects", &self.filter_rejects)
+            .field("novel_case", &self.novel_case)
+            .field("union_picks", &self.union_picks)
+            .field("scratch", &self.scratch)
+            .field("scratch_seed", &self.scratch_seed)
+            .field("adaptive_successes", &self.adaptive_successes)
+            .field("stale_cases", &self.stale_cases)
+            .field(
+                "event_sink",
+                &self.event_sink.as_ref().map(|_| "<EventSink>"),
+            )
+            .field("shrink_stats", &self.shrink_stats)
+            .field("shrink_path", &self.shrink_path);
+        #[cfg(feature = "std")]
+        debug.field("memo_stats", &self.memo_stats);
+        #[cfg(feature = "chaos")]
+        debug.field("chaos_rng", &self.chaos_rng.as_ref().map(|_| "<TestRng>"));
+        #[cfg(feature = "coverage-guided")]
+        debug.field("corpus", &self.corpus.seeds.len());
+        debug.finish()
     }
 }
 
@@ -106,10 +347,22 @@ impl fmt::Display for TestRunner {
         for (whence, count) in &self.local_reject_detail {
             writeln!(f, "\t\t{} times at {}", count, whence)?;
         }
+        if !self.filter_rejects.is_empty() {
+            writeln!(f, "\tfilter rejects:")?;
+            for (name, count) in &self.filter_rejects {
+                writeln!(f, "\t\t{} times by {}", count, name)?;
+            }
+        }
         writeln!(f, "\tglobal rejects: {}", self.global_rejects)?;
         for (whence, count) in &self.global_reject_detail {
             writeln!(f, "\t\t{} times at {}", count, whence)?;
         }
+        if !self.label_counts.is_empty() {
+            writeln!(f, "\tlabels:")?;
+            for (label, count) in &self.label_counts {
+                writeln!(f, "\t\t{} times from {}", count, label)?;
+            }
+        }
 
         Ok(())
     }
@@ -177,7 +430,7 @@ impl ForkOutput {
 
 #[cfg(not(feature = "std"))]
 fn call_test<V, F, R>(
-    _runner: &mut TestRunner,
+    runner: &mut TestRunner,
     case: V,
     test: &F,
     replay_from_fork: &mut R,
@@ -201,6 +454,7 @@ where
 
     let result = test(case);
     result_cache.put(cache_key, &result);
+    runner.note_cache_miss(result_cache, cache_key);
     result.map(|_| {
         if is_from_persisted_seed {
             TestCaseOk::PersistedCaseSuccess
@@ -252,10 +506,29 @@ where
     #[cfg(feature = "timeout")]
     let time_start = std::time::Instant::now();
 
+    // Discard anything probed outside of the test, e.g. by strategies.
+    let _ = crate::probe::take();
+    let _ = super::coverage::take();
+    #[cfg(feature = "coverage-guided")]
+    if runner.config.coverage_guided.is_some() {
+        super::coverage::reset_edges();
+    }
+
+    #[cfg(feature = "chaos")]
+    if let Some((chaos, rng)) =
+        runner.config.chaos.zip(runner.chaos_rng.clone())
+    {
+        crate::chaos::begin(chaos, rng);
+    }
+
     let mut result = unwrap_or!(
         super::scoped_panic_hook::with_hook(
             |_| { /* Silence out panic backtrace */ },
-            || panic::catch_unwind(AssertUnwindSafe(|| test(case)))
+            || panic::catch_unwind(AssertUnwindSafe(|| {
+                #[cfg(feature = "chaos")]
+                crate::chaos::point();
+                test(case)
+            }))
         ),
         what => Err(TestCaseError::Fail(
             what.downcast::<&'static str>().map(|s| (*s).into())
@@ -280,7 +553,34 @@ where
         }
     }
 
+    #[cfg(feature = "chaos")]
+    let injections = crate::chaos::end();
+    let probes = crate::probe::take();
+    if let Err(TestCaseError::Fail(ref mut reason)) = result {
+        if !probes.is_empty() {
+            *reason = format!("{}\n{}", reason, probes).into();
+        }
+        #[cfg(feature = "chaos")]
+        if !injections.is_empty() {
+            *reason = format!("{}\n{}", reason, injections).into();
+        }
+    }
+
     result_cache.put(cache_key, &result);
+    runner.note_cache_miss(result_cache, cache_key);
+    for point in super::coverage::take() {
+        if runner.covered.insert(point) {
+            runner.novel_case = true;
+            #[cfg(feature = "coverage-guided")]
+            {
+                runner.corpus.new_coverage = true;
+            }
+        }
+    }
+    #[cfg(feature = "coverage-guided")]
+    if runner.config.coverage_guided.is_some() && runner.corpus.take_edges() {
+        runner.novel_case = true;
+    }
     fork_output.append(&result);
 
     match result {
@@ -341,7 +641,14 @@ impl TestRunner {
 
     /// Create a fresh `TestRunner` with the given configuration and RNG.
     pub fn new_with_rng(config: Config, rng: TestRng) -> Self {
+        use rand::RngCore;
+
+        #[cfg(all(feature = "std", miri))]
+        let config = config.without_file_persistence();
+        let scratch_seed = rng.clone().next_u64();
+
         TestRunner {
+            budget: case_budget(&config),
             config: config,
             successes: 0,
             local_rejects: 0,
@@ -350,6 +657,25 @@ impl TestRunner {
             flat_map_regens: Arc::new(AtomicUsize::new(0)),
             local_reject_detail: BTreeMap::new(),
             global_reject_detail: BTreeMap::new(),
+            label_counts: BTreeMap::new(),
+            filter_rejects: BTreeMap::new(),
+            novel_case: false,
+            adaptive_successes: 0,
+            stale_cases: 0,
+            union_picks: None,
+            #[cfg(feature = "std")]
+            memo_stats: Vec::new(),
+            scratch: RunnerScratch::default(),
+            scratch_seed,
+            #[cfg(feature = "std")]
+            covered: BTreeSet::new(),
+            event_sink: None,
+            shrink_stats: ShrinkStats::default(),
+            shrink_path: ShrinkPath::default(),
+            #[cfg(feature = "chaos")]
+            chaos_rng: None,
+            #[cfg(feature = "coverage-guided")]
+            corpus: Default::default(),
         }
     }
 
@@ -364,27 +690,155 @@ impl TestRunner {
             global_rejects: 0,
             rng: self.new_rng(),
             flat_map_regens: Arc::clone(&self.flat_map_regens),
+            budget: self.budget.clone(),
             local_reject_detail: BTreeMap::new(),
             global_reject_detail: BTreeMap::new(),
+            label_counts: BTreeMap::new(),
+            filter_rejects: BTreeMap::new(),
+            novel_case: false,
+            adaptive_successes: 0,
+            stale_cases: 0,
+            union_picks: None,
+            #[cfg(feature = "std")]
+            memo_stats: Vec::new(),
+            scratch: self.scratch.clone(),
+            scratch_seed: self.scratch_seed,
+            #[cfg(feature = "std")]
+            covered: BTreeSet::new(),
+            event_sink: self.event_sink.clone(),
+            shrink_stats: ShrinkStats::default(),
+            shrink_path: ShrinkPath::default(),
+            #[cfg(feature = "chaos")]
+            chaos_rng: None,
+            #[cfg(feature = "coverage-guided")]
+            corpus: Default::default(),
         }
     }
 
     /// Returns the RNG for this test run.
+    ///
+    /// All values drawn from this RNG form a single stream, so a strategy
+    /// which draws more or fewer values shifts everything generated after it.
+    /// Use `new_rng()`, `sub_rng_scope()` or `named_rng()` to give a component
+    /// its own stream instead.
     pub fn rng(&mut self) -> &mut TestRng {
         &mut self.rng
     }
 
     /// Create a new, independent but deterministic RNG from the RNG in this
     /// runner.
+    ///
+    /// This advances the runner's RNG by a fixed amount, regardless of how
+    /// much randomness is later drawn from the new RNG.
     pub fn new_rng(&mut self) -> TestRng {
         self.rng.gen_rng()
     }
 
+    /// Create a `SubRngScope` from the RNG in this runner, from which
+    /// independent RNG streams can be forked by name.
+    ///
+    /// Like `new_rng()`, this advances the runner's RNG by a fixed amount.
+    pub fn sub_rng_scope(&mut self) -> SubRngScope {
+        SubRngScope::new(self.new_rng())
+    }
+
+    /// Create a new, independent but deterministic RNG for the stream called
+    /// `name`.
+    ///
+    /// This is shorthand for `self.sub_rng_scope().rng(name)`. Each call
+    /// forks a new scope, so when a strategy needs several streams, prefer
+    /// creating one `SubRngScope` and taking the streams from it.
+    pub fn named_rng(&mut self, name: &str) -> TestRng {
+        self.sub_rng_scope().rng(name)
+    }
+
+    /// Returns the value of type `T` memoized by this runner, building it
+    /// with `build` the first time it is requested.
+    ///
+    /// This lets strategies share auxiliary data which is expensive to build,
+    /// such as lookup tables, across all the values they generate with this
+    /// runner, instead of rebuilding it in every `new_tree()`. Values are
+    /// identified by their type, so a strategy should store its data in a
+    /// type of its own. They are kept until the runner is dropped or its
+    /// scratch is taken with `take_scratch()`.
+    ///
+    /// Since whether `build` is called depends on what was generated before,
+    /// the value it returns must only depend on its own inputs, such as the
+    /// parameters of the strategy or `config()`, for tests to remain
+    /// reproducible. In particular, it must not draw from this runner's RNG;
+    /// if the data needs to be random, derive it from a fixed seed.
+    ///
+    /// ```
+    /// use proptest::test_runner::TestRunner;
+    ///
+    /// struct Squares(Vec<u64>);
+    ///
+    /// let mut runner = TestRunner::default();
+    /// let squares =
+    ///     runner.scratch(|| Squares((0..1000).map(|x| x * x).collect()));
+    /// assert_eq!(81, squares.0[9]);
+    /// ```
+    pub fn scratch<T, F>(&mut self, build: F) -> Arc<T>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> T,
+    {
+        self.scratch
+            .get_or_build(&self.config, self.scratch_seed, build)
+    }
+
+    /// Takes the values memoized through `scratch()`, leaving this runner
+    /// with an empty scratch.
+    ///
+    /// The returned scratch can be given to another runner with
+    /// `set_scratch()`, which reuses its values if that runner has the same
+    /// configuration and seed.
+    pub fn take_scratch(&mut self) -> RunnerScratch {
+        mem::take(&mut self.scratch)
+    }
+
+    /// Replaces the values memoized through `scratch()` with `scratch`,
+    /// typically taken from another runner with `take_scratch()`.
+    ///
+    /// If `scratch` was built for a runner with a different configuration or
+    /// seed, its values are discarded when this runner first accesses them.
+    pub fn set_scratch(&mut self, scratch: RunnerScratch) {
+        self.scratch = scratch;
+    }
+
+    /// Returns the counters of the most recent shrinking done by this runner,
+    /// or all zeroes if no failing case has been shrunk yet.
+    pub fn shrink_stats(&self) -> ShrinkStats {
+        self.shrink_stats
+    }
+
     /// Returns the configuration of this runner.
     pub fn config(&self) -> &Config {
         &self.config
     }
 
+    /// Send the events of all subsequent runs to `sink`, replacing any
+    /// previously set sink.
+    ///
+    /// By default, no events are emitted at all. See `RunnerEvent` for what
+    /// is reported and its stability guarantees.
+    pub fn set_event_sink(&mut self, sink: impl EventSink + 'static) {
+        self.event_sink = Some(Arc::new(sink));
+    }
+
+    /// Whether events which are costly to build need to be built at all.
+    fn wants_events(&self) -> bool {
+        self.event_sink.is_some() || cfg!(feature = "tracing")
+    }
+
+    fn emit(&self, event: RunnerEvent<'_>) {
+        #[cfg(feature = "tracing")]
+        event.trace();
+        if let Some(ref sink) = self.event_sink {
+            sink.on_event(&event);
+        }
+    }
+
     /// Dumps the bytes obtained from the RNG so far (only works if the RNG is
     /// set to `Recorder`).
     ///
@@ -418,6 +872,41 @@ impl TestRunner {
         }
     }
 
+    /// Run test cases against `test`, which receives a borrow of each value
+    /// generated by `strategy` rather than the value itself.
+    ///
+    /// This is a convenience for testing functions which take borrowed data,
+    /// such as `&str` or `&[u8]`, from strategies producing the owned
+    /// equivalent. It is otherwise identical to `run()`; see `with_borrowed()`
+    /// for use with the other `run_*` methods.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::TestRunner;
+    ///
+    /// fn is_shouting(s: &str) -> bool {
+    ///     s.chars().all(|c| !c.is_lowercase())
+    /// }
+    ///
+    /// let mut runner = TestRunner::default();
+    /// runner
+    ///     .run_borrowed(&"[A-Z ]*", |s: &str| {
+    ///         prop_assert!(is_shouting(s));
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn run_borrowed<S: Strategy, B: ?Sized>(
+        &mut self,
+        strategy: &S,
+        test: impl Fn(&B) -> TestCaseResult,
+    ) -> TestRunResult<S>
+    where
+        S::Value: Borrow<B>,
+    {
+        self.run(strategy, with_borrowed(test))
+    }
+
     #[cfg(not(feature = "fork"))]
     fn run_in_fork<S: Strategy>(
         &mut self,
@@ -433,13 +922,27 @@ impl TestRunner {
         strategy: &S,
         test: impl Fn(S::Value) -> TestCaseResult,
     ) -> TestRunResult<S> {
-        let mut test = Some(test);
-
         let test_name = rusty_fork::fork_test::fix_module_path(
             self.config
                 .test_name
                 .expect("Must supply test_name when forking enabled"),
         );
+        let backend = self.config.fork_backend;
+        if ForkBackend::Reexec == backend
+            && env::var_os(ENV_REEXEC_TEST).map_or(false, |var| var == test_name)
+        {
+            // This is the subprocess started below, so run the cases the
+            // replay file calls for.
+            return match self.run_in_process(strategy, test) {
+                Ok(_) => Ok(()),
+                Err(e) => panic!(
+                    "Test failed normally in child process.\n{}\n{}",
+                    e, self
+                ),
+            };
+        }
+
+        let mut test = Some(test);
         let forkfile: RefCell<Option<tempfile::NamedTempFile>> =
             RefCell::new(None);
         let init_forkfile_size = Cell::new(0u64);
@@ -458,41 +961,58 @@ impl TestRunner {
         }
 
         loop {
-            let (child_error, last_fork_file_len) = rusty_fork::fork(
-                test_name,
-                rusty_fork_id!(),
-                |cmd| {
-                    let mut forkfile = forkfile.borrow_mut();
-                    if forkfile.is_none() {
-                        *forkfile =
-                            Some(tempfile::NamedTempFile::new().expect(
-                                "Failed to create temporary file for fork",
-                            ));
-                        replay.init_file(forkfile.as_mut().unwrap()).expect(
-                            "Failed to initialise temporary file for fork",
-                        );
-                    }
-
-                    init_forkfile_size.set(forkfile_size(&forkfile));
+            let prepare_child = |cmd: &mut process::Command| {
+                let mut forkfile = forkfile.borrow_mut();
+                if forkfile.is_none() {
+                    *forkfile = Some(tempfile::NamedTempFile::new().expect(
+                        "Failed to create temporary file for fork",
+                    ));
+                    replay.init_file(forkfile.as_mut().unwrap()).expect(
+                        "Failed to initialise temporary file for fork",
+                    );
+                }
 
-                    cmd.env(ENV_FORK_FILE, forkfile.as_ref().unwrap().path());
-                },
-                |child, _| {
+                init_forkfile_size.set(forkfile_size(&forkfile));
+
+                cmd.env(ENV_FORK_FILE, forkfile.as_ref().unwrap().path());
+            };
+            let (child_error, last_fork_file_len) = match backend {
+                ForkBackend::RustyFork => rusty_fork::fork(
+                    test_name,
+                    rusty_fork_id!(),
+                    prepare_child,
+                    |child, _| {
+                        await_child(
+                            child,
+                            &mut forkfile.borrow_mut().as_mut().unwrap(),
+                            timeout,
+                        )
+                    },
+                    || match self
+                        .run_in_process(strategy, test.take().unwrap())
+                    {
+                        Ok(_) => (),
+                        Err(e) => panic!(
+                            "Test failed normally in child process.\n{}\n{}",
+                            e, self
+                        ),
+                    },
+                )
+                .expect("Fork failed"),
+
+                ForkBackend::Reexec => {
+                    let mut cmd = reexec_command(test_name)
+                        .expect("Failed to re-execute test binary");
+                    prepare_child(&mut cmd);
+                    let mut child = ReexecChild::spawn(cmd)
+                        .expect("Failed to re-execute test binary");
                     await_child(
-                        child,
+                        &mut child,
                         &mut forkfile.borrow_mut().as_mut().unwrap(),
                         timeout,
                     )
-                },
-                || match self.run_in_process(strategy, test.take().unwrap()) {
-                    Ok(_) => (),
-                    Err(e) => panic!(
-                        "Test failed normally in child process.\n{}\n{}",
-                        e, self
-                    ),
-                },
-            )
-            .expect("Fork failed");
+                }
+            };
 
             let parsed = replay::Replay::parse_from(
                 &mut forkfile.borrow_mut().as_mut().unwrap(),
@@ -586,57 +1106,141 @@ impl TestRunner {
         mut replay_from_fork: impl Iterator<Item = TestCaseResult>,
         mut fork_output: ForkOutput,
     ) -> TestRunResult<S> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!(
+            target: "proptest",
+            "proptest",
+            test = self.config.test_name,
+        )
+        .entered();
+
         let old_rng = self.rng.clone();
 
         let persisted_failure_seeds: Vec<PersistedSeed> = self
             .config
             .failure_persistence
             .as_ref()
-            .map(|f| f.load_persisted_failures2(self.config.source_file))
+            .map(|f| {
+                f.load_persisted_failures3(
+                    self.config.source_file,
+                    self.config.test_name,
+                )
+            })
             .unwrap_or_default();
 
         let mut result_cache = self.new_cache();
 
-        for PersistedSeed(persisted_seed) in
-            persisted_failure_seeds.into_iter().rev()
-        {
-            self.rng.set_seed(persisted_seed);
-            self.gen_and_run_case(
+        for persisted_seed in persisted_failure_seeds.into_iter().rev() {
+            if self.wants_events() {
+                let seed = Some(persisted_seed.clone());
+                self.emit(RunnerEvent::CaseStarted { seed });
+            }
+            let PersistedSeed(seed, shrink_path) = persisted_seed;
+            self.rng.set_seed(seed.clone());
+            let result = self.gen_and_run_case(
                 strategy,
                 &test,
                 &mut replay_from_fork,
                 &mut *result_cache,
                 &mut fork_output,
-                true,
-            )?;
+                Some(&shrink_path),
+            );
+            #[cfg(feature = "std")]
+            if let Err(TestError::Fail(ref why, ref value)) = result {
+                if !fork_output.is_in_fork() {
+                    self.record_failure_report(seed, why, value);
+                }
+            }
+            result?;
         }
         self.rng = old_rng;
 
-        while self.successes < self.config.cases {
+        let algorithm = self.config.rng_algorithm;
+        // Pass-through data can't be derived from a seed, so it is used as is.
+        let golden = algorithm != RngAlgorithm::PassThrough;
+        if !golden && self.config.seed_set.is_some() {
+            verbose_message!(
+                self,
+                ALWAYS,
+                "Config::seed_set is not available with the PassThrough RNG \
+                 algorithm, ignoring it"
+            );
+        }
+        let mut golden_rngs = self
+            .config
+            .seed_set
+            .iter()
+            .flatten()
+            .filter(|_| golden)
+            .map(|&seed| TestRng::from_golden_seed(algorithm, seed))
+            .collect::<Vec<_>>();
+        if let (true, Some((index, total))) =
+            (golden && golden_rngs.is_empty(), self.config.shard)
+        {
+            // The test is mixed in so that the tests of a shard don't all run
+            // the same stream of cases.
+            let seed = DebugHasher::hash(&(
+                self.config.source_file,
+                self.config.test_name,
+                index,
+                total,
+            ));
+            golden_rngs.push(TestRng::from_golden_seed(algorithm, seed));
+        }
+        let mut golden_ix = 0;
+        #[cfg(feature = "coverage-guided")]
+        if self.config.coverage_guided.is_some() {
+            self.corpus.rng = Some(self.new_rng());
+        }
+
+        while !self.ran_enough_cases() {
             // Generate a new seed and make an RNG from that so that we know
             // what seed to persist if this case fails.
-            let seed = self.rng.gen_get_seed();
+            let seed = if golden_rngs.is_empty() {
+                self.rng.gen_get_seed()
+            } else {
+                let seed = golden_rngs[golden_ix].new_rng_seed();
+                golden_ix = (golden_ix + 1) % golden_rngs.len();
+                self.rng.set_seed(seed.clone());
+                seed
+            };
+            #[cfg(feature = "coverage-guided")]
+            let seed = self.next_guided_seed(seed);
+            if self.wants_events() {
+                let seed = PersistedSeed(seed.clone(), ShrinkPath::default());
+                self.emit(RunnerEvent::CaseStarted { seed: Some(seed) });
+            }
             let result = self.gen_and_run_case(
                 strategy,
                 &test,
                 &mut replay_from_fork,
                 &mut *result_cache,
                 &mut fork_output,
-                false,
+                None,
             );
+            #[cfg(feature = "coverage-guided")]
+            self.end_guided_case(&seed);
+            #[cfg(feature = "std")]
+            if let Err(TestError::Fail(ref why, ref value)) = result {
+                if !fork_output.is_in_fork() {
+                    self.record_failure_report(seed.clone(), why, value);
+                }
+            }
             if let Err(TestError::Fail(_, ref value)) = result {
                 if let Some(ref mut failure_persistence) =
                     self.config.failure_persistence
                 {
                     let source_file = &self.config.source_file;
+                    let test_name = &self.config.test_name;
 
                     // Don't update the persistence file if we're a child
                     // process. The parent relies on it remaining consistent
                     // and will take care of updating it itself.
                     if !fork_output.is_in_fork() {
-                        failure_persistence.save_persisted_failure2(
+                        failure_persistence.save_persisted_failure3(
                             *source_file,
-                            PersistedSeed(seed),
+                            *test_name,
+                            PersistedSeed(seed, self.shrink_path.clone()),
                             value,
                         );
                     }
@@ -649,10 +1253,33 @@ impl TestRunner {
             }
         }
 
+        #[cfg(feature = "std")]
+        for (label, count) in &self.label_counts {
+            verbose_message!(
+                self,
+                INFO_LOG,
+                "Generated {} values from {}",
+                count,
+                label
+            );
+        }
+        #[cfg(feature = "std")]
+        for stats in &self.memo_stats {
+            verbose_message!(
+                self,
+                INFO_LOG,
+                "Memoized flat map: {} hits, {} misses, {} evictions",
+                stats.hits(),
+                stats.misses(),
+                stats.evictions()
+            );
+        }
         fork_output.terminate();
         Ok(())
     }
 
+    /// Generates and runs one case. `persisted` is the shrink path of the
+    /// case if it is replayed from a persisted seed.
     fn gen_and_run_case<S: Strategy>(
         &mut self,
         strategy: &S,
@@ -660,10 +1287,77 @@ impl TestRunner {
         replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
         result_cache: &mut dyn ResultCache,
         fork_output: &mut ForkOutput,
-        is_from_persisted_seed: bool,
+        persisted: Option<&ShrinkPath>,
     ) -> TestRunResult<S> {
-        let case = unwrap_or!(strategy.new_tree(self), msg =>
-                return Err(TestError::Abort(msg)));
+        let is_from_persisted_seed = persisted.is_some();
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            target: "proptest",
+            "case",
+            persisted = is_from_persisted_seed,
+        )
+        .entered();
+
+        let shrink_path = persisted.filter(|path| {
+            !path.is_empty() && ReplayMode::Full != self.config.replay
+        });
+        if let Some(shrink_path) = shrink_path {
+            let rng = self.rng.clone();
+            self.reset_budget();
+            let mut minimal = unwrap_or!(strategy.new_tree(self), msg =>
+                    return Err(TestError::Abort(msg)));
+            #[cfg(feature = "chaos")]
+            self.new_case_chaos();
+            verbose_message!(
+                self,
+                TRACE,
+                "Replaying shrink path {} of persisted case",
+                shrink_path
+            );
+            shrink_path.replay(&mut minimal);
+            // Any further shrinking continues the persisted path.
+            self.shrink_path = shrink_path.clone();
+            if ReplayMode::Reshrink == self.config.replay {
+                self.run_one_with_replay(
+                    minimal,
+                    f,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    true,
+                )?;
+            } else {
+                self.run_one_with_replay(
+                    NoShrink(minimal),
+                    f,
+                    replay_from_fork,
+                    result_cache,
+                    fork_output,
+                    true,
+                )?;
+            }
+
+            // The minimal value no longer fails, but the original one still
+            // might, so replay that too.
+            verbose_message!(
+                self,
+                INFO_LOG,
+                "Minimal value of persisted case passed; replaying it in full"
+            );
+            self.rng = rng;
+        }
+
+        self.reset_budget();
+        self.novel_case = false;
+        self.union_picks = Some(Vec::new());
+        let case = strategy.new_tree(self);
+        let union_picks = self.union_picks.take().unwrap_or_default();
+        let case = unwrap_or!(case, msg => return Err(TestError::Abort(msg)));
+        #[cfg(feature = "chaos")]
+        self.new_case_chaos();
+        self.shrink_path = ShrinkPath::default();
+        #[cfg(feature = "coverage-guided")]
+        let case = self.mutate_case(case);
 
         // We only count new cases to our set of successful runs against
         // `PROPTEST_CASES` config.
@@ -677,16 +1371,119 @@ impl TestRunner {
         )?;
         match ok_type {
             TestCaseOk::NewCaseSuccess | TestCaseOk::ReplayFromForkSuccess => {
-                self.successes += 1
+                self.successes += 1;
+                self.count_adaptive_success();
             }
-            TestCaseOk::PersistedCaseSuccess
-            | TestCaseOk::CacheHitSuccess
-            | TestCaseOk::Reject => (),
+            // Inputs seen before are never novel, but have to count for
+            // `adaptive_cases` so that small input spaces end the run.
+            TestCaseOk::CacheHitSuccess => self.count_adaptive_success(),
+            TestCaseOk::Reject => {
+                for (feedback, option) in union_picks {
+                    feedback.reject(option);
+                }
+            }
+            TestCaseOk::PersistedCaseSuccess => (),
         }
 
         Ok(())
     }
 
+    /// Derives the chaos RNG of a new case from the RNG of the runner, if
+    /// chaos mode is enabled.
+    #[cfg(feature = "chaos")]
+    fn new_case_chaos(&mut self) {
+        self.chaos_rng = match self.config.chaos {
+            Some(_) => Some(self.new_rng()),
+            None => None,
+        };
+    }
+
+    /// Picks the seed of the next case for `Config::coverage_guided`: every
+    /// other case is derived from a random seed in the corpus, if any, rather
+    /// than from the fresh `seed`.
+    #[cfg(feature = "coverage-guided")]
+    fn next_guided_seed(&mut self, seed: Seed) -> Seed {
+        use rand::Rng;
+
+        self.corpus.new_coverage = false;
+        let (guided, rng) =
+            match (self.config.coverage_guided, self.corpus.rng.as_mut()) {
+                (Some(guided), Some(rng)) => (guided, rng),
+                _ => return seed,
+            };
+        if self.corpus.seeds.is_empty() || rng.gen() {
+            return seed;
+        }
+
+        let ix = rng.gen_range(0..self.corpus.seeds.len());
+        let PersistedSeed(base, mut mutation) = self.corpus.seeds[ix].clone();
+        for _ in 0..rng.gen_range(1..=guided.max_mutations.max(1)) {
+            // Value trees need not support `complicate()` before the first
+            // `simplify()`.
+            let simplify = mutation.is_empty() || rng.gen();
+            mutation.push(simplify);
+        }
+        verbose_message!(
+            self,
+            TRACE,
+            "Deriving case from corpus seed by {}",
+            mutation
+        );
+        self.corpus.mutation = mutation;
+        self.corpus.resume = Some(self.rng.clone());
+        self.rng.set_seed(base.clone());
+        base
+    }
+
+    /// Makes the calls deriving the current case from its seed in the corpus
+    /// of `Config::coverage_guided`, if it is derived from one. Any shrinking
+    /// continues this path, so that the persisted path of a failure leads to
+    /// its minimal value.
+    #[cfg(feature = "coverage-guided")]
+    fn mutate_case<V: ValueTree>(&mut self, mut case: V) -> V {
+        if !self.corpus.mutation.is_empty() {
+            self.corpus.mutation.replay(&mut case);
+            self.shrink_path = self.corpus.mutation.clone();
+        }
+        case
+    }
+
+    /// Adds the case generated from `seed` to the corpus of
+    /// `Config::coverage_guided` if it reached new coverage.
+    #[cfg(feature = "coverage-guided")]
+    fn end_guided_case(&mut self, seed: &Seed) {
+        use rand::Rng;
+
+        let guided = match self.config.coverage_guided {
+            Some(guided) => guided,
+            None => return,
+        };
+        if let Some(rng) = self.corpus.resume.take() {
+            self.rng = rng;
+        }
+        let mutation = mem::take(&mut self.corpus.mutation);
+        if !mem::replace(&mut self.corpus.new_coverage, false) {
+            return;
+        }
+
+        let entry = PersistedSeed(seed.clone(), mutation);
+        let seeds = &mut self.corpus.seeds;
+        if seeds.len() < guided.max_corpus as usize {
+            seeds.push(entry);
+        } else if let Some(rng) = self.corpus.rng.as_mut() {
+            if !seeds.is_empty() {
+                let ix = rng.gen_range(0..seeds.len());
+                seeds[ix] = entry;
+            }
+        }
+        verbose_message!(
+            self,
+            INFO_LOG,
+            "Case reached new coverage; corpus has {} seeds",
+            self.corpus.seeds.len()
+        );
+    }
+
     /// Run one specific test case against this runner.
     ///
     /// If the test fails, finds the minimal failing test case. If the test
@@ -702,6 +1499,10 @@ impl TestRunner {
         test: impl Fn(V::Value) -> TestCaseResult,
     ) -> Result<bool, TestError<V::Value>> {
         let mut result_cache = self.new_cache();
+        self.emit(RunnerEvent::CaseStarted { seed: None });
+        self.shrink_path = ShrinkPath::default();
+        #[cfg(feature = "chaos")]
+        self.new_case_chaos();
         self.run_one_with_replay(
             case,
             test,
@@ -716,50 +1517,293 @@ impl TestRunner {
         })
     }
 
-    fn run_one_with_replay<V: ValueTree>(
+    /// Run one specific, literal value against this runner.
+    ///
+    /// This is intended for pinning known regression inputs that should be
+    /// tested on every run in addition to the random cases. Since there is no
+    /// value tree behind `value`, no shrinking takes place; if the test fails,
+    /// `value` itself is reported as the failing input. Otherwise, returns
+    /// whether the test succeeded or was filtered out.
+    ///
+    /// The same caveats regarding `fork` and `timeout` as for `run_one` apply.
+    pub fn run_value<V: Clone + fmt::Debug>(
         &mut self,
-        mut case: V,
-        test: impl Fn(V::Value) -> TestCaseResult,
-        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
-        result_cache: &mut dyn ResultCache,
-        fork_output: &mut ForkOutput,
-        is_from_persisted_seed: bool,
-    ) -> Result<TestCaseOk, TestError<V::Value>> {
-        let result = call_test(
-            self,
-            case.current(),
-            &test,
-            replay_from_fork,
-            result_cache,
-            fork_output,
-            is_from_persisted_seed,
-        );
-
-        match result {
-            Ok(success_type) => Ok(success_type),
-            Err(TestCaseError::Fail(why)) => {
-                let why = self
-                    .shrink(
-                        &mut case,
-                        test,
-                        replay_from_fork,
-                        result_cache,
-                        fork_output,
-                        is_from_persisted_seed,
-                    )
-                    .unwrap_or(why);
-                Err(TestError::Fail(why, case.current()))
-            }
-            Err(TestCaseError::Reject(whence)) => {
-                self.reject_global(whence)?;
-                Ok(TestCaseOk::Reject)
-            }
-        }
+        value: V,
+        test: impl Fn(V) -> TestCaseResult,
+    ) -> Result<bool, TestError<V>> {
+        self.run_one(Just(value), test)
     }
 
-    fn shrink<V: ValueTree>(
+    /// Returns whether the case generated from the persisted `seed` still
+    /// fails `test`, e.g. to decide which seeds
+    /// `FileFailurePersistence::prune()` keeps.
+    ///
+    /// The case is generated from `strategy` just like when the seed is
+    /// replayed at the start of a run, but is not shrunk. Rejected cases and
+    /// strategies failing to generate a value count as not failing.
+    pub fn reproduces<S: Strategy>(
         &mut self,
-        case: &mut V,
+        strategy: &S,
+        seed: &PersistedSeed,
+        test: impl Fn(S::Value) -> TestCaseResult,
+    ) -> bool {
+        let old_rng = self.rng.clone();
+        self.rng.set_seed(seed.0.clone());
+        self.reset_budget();
+        let fails = match strategy.new_tree(self) {
+            Ok(case) => matches!(
+                self.run_one(NoShrink(case), test),
+                Err(TestError::Fail(..))
+            ),
+            Err(_) => false,
+        };
+        self.rng = old_rng;
+        fails
+    }
+
+    /// Run test cases against several related properties at once, generating
+    /// each input from `strategy` only once and running every property on
+    /// it.
+    ///
+    /// This saves generation cost when expensive strategies are shared by
+    /// many properties. A case fails if any of the properties fails, and is
+    /// rejected only if all of them reject it. Failing cases are shrunk
+    /// against all properties together, i.e. to a minimal input on which at
+    /// least one of them still fails; the properties are then run once more
+    /// on that input to report which of them fail on it, highlighting
+    /// correlated failures.
+    ///
+    /// ```
+    /// use proptest::prelude::*;
+    /// use proptest::test_runner::{TestError, TestRunner};
+    ///
+    /// fn is_short(s: String) -> Result<(), TestCaseError> {
+    ///     prop_assert!(s.len() < 4);
+    ///     Ok(())
+    /// }
+    ///
+    /// fn is_ascii(s: String) -> Result<(), TestCaseError> {
+    ///     prop_assert!(s.is_ascii());
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut runner = TestRunner::default();
+    /// let result = runner.run_matrix(
+    ///     &"[a-z]{0,8}",
+    ///     &[("is_short", is_short), ("is_ascii", is_ascii)],
+    /// );
+    /// match result {
+    ///     Err(TestError::Fail(_, failure)) => {
+    ///         assert_eq!("aaaa", failure.input);
+    ///         assert_eq!(vec!["is_ascii"], failure.passed);
+    ///     }
+    ///     r => panic!("unexpected result: {:?}", r),
+    /// }
+    /// ```
+    ///
+    /// If failure persistence is enabled, persisted seeds are replayed and
+    /// new failures persisted as with `run()`. The final run of each property
+    /// on the minimal input doesn't honour the `fork` config.
+    pub fn run_matrix<S: Strategy>(
+        &mut self,
+        strategy: &S,
+        properties: &[(&'static str, fn(S::Value) -> TestCaseResult)],
+    ) -> Result<(), TestError<MatrixFailure<S::Value>>>
+    where
+        S::Value: Clone,
+    {
+        let result = self.run(strategy, |value| {
+            let mut failures = Vec::new();
+            let mut rejection = None;
+            let mut accepted = false;
+            for (name, property) in properties {
+                match property(value.clone()) {
+                    Ok(()) => accepted = true,
+                    Err(TestCaseError::Reject(why)) => {
+                        rejection.get_or_insert(why);
+                    }
+                    Err(TestCaseError::Fail(why)) => {
+                        failures.push(format!("{}: {}", name, why))
+                    }
+                }
+            }
+            match rejection {
+                _ if !failures.is_empty() => {
+                    Err(TestCaseError::fail(failures.join("; ")))
+                }
+                Some(why) if !accepted => Err(TestCaseError::Reject(why)),
+                _ => Ok(()),
+            }
+        });
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(TestError::Abort(why)) => Err(TestError::Abort(why)),
+            Err(TestError::Fail(why, input)) => {
+                let mut failed = Vec::new();
+                let mut passed = Vec::new();
+                for (name, property) in properties {
+                    match self.run_value(input.clone(), *property) {
+                        Err(TestError::Fail(why, _)) => {
+                            failed.push((*name, why))
+                        }
+                        _ => passed.push(*name),
+                    }
+                }
+                Err(TestError::Fail(
+                    why,
+                    MatrixFailure {
+                        input,
+                        failed,
+                        passed,
+                    },
+                ))
+            }
+        }
+    }
+
+    fn run_one_with_replay<V: ValueTree>(
+        &mut self,
+        mut case: V,
+        test: impl Fn(V::Value) -> TestCaseResult,
+        replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
+        result_cache: &mut dyn ResultCache,
+        fork_output: &mut ForkOutput,
+        is_from_persisted_seed: bool,
+    ) -> Result<TestCaseOk, TestError<V::Value>> {
+        let value = case.current();
+        // Measured before the test consumes the value, since some trees, such
+        // as those of state machine tests, can't produce their value again
+        // after a failing run until they are simplified.
+        let original_size = if self.measures_sizes() {
+            DebugLen::of(&value)
+        } else {
+            0
+        };
+        let result = call_test(
+            self,
+            value,
+            &test,
+            replay_from_fork,
+            result_cache,
+            fork_output,
+            is_from_persisted_seed,
+        );
+
+        let outcome = match result {
+            Ok(TestCaseOk::Reject) | Err(TestCaseError::Reject(_)) => {
+                CaseOutcome::Rejected
+            }
+            Ok(_) => CaseOutcome::Passed,
+            Err(TestCaseError::Fail(_)) => CaseOutcome::Failed,
+        };
+        self.emit(RunnerEvent::CaseFinished { outcome });
+
+        match result {
+            Ok(success_type) => Ok(success_type),
+            Err(TestCaseError::Fail(why)) => {
+                #[cfg(feature = "failure-registry")]
+                let pending =
+                    PendingFailure::new(&self.config, &case.current());
+                #[cfg(feature = "failure-registry")]
+                let should_shrink = pending
+                    .as_ref()
+                    .map_or(true, PendingFailure::should_shrink);
+                #[cfg(not(feature = "failure-registry"))]
+                let should_shrink = true;
+
+                self.shrink_stats.original_size = original_size;
+                let why = if should_shrink {
+                    self.shrink(
+                        &mut case,
+                        &test,
+                        replay_from_fork,
+                        result_cache,
+                        fork_output,
+                        is_from_persisted_seed,
+                    )
+                    .unwrap_or(why)
+                } else {
+                    why
+                };
+                let why = self.check_failure_stability(&case, &test, why);
+                let minimal = case.current();
+                #[cfg(feature = "failure-registry")]
+                let why = match pending {
+                    Some(pending) => pending.finish(&minimal, why),
+                    None => why,
+                };
+                self.emit(RunnerEvent::Failure {
+                    minimal: &minimal,
+                    reason: &why,
+                });
+                Err(TestError::Fail(why, minimal))
+            }
+            Err(TestCaseError::Reject(whence)) => {
+                self.reject_global(whence)?;
+                Ok(TestCaseOk::Reject)
+            }
+        }
+    }
+
+    /// Runs `test` `Config::failure_stability_checks` more times on the
+    /// minimal failing input in `case`, appending to `why` how many of those
+    /// runs failed.
+    fn check_failure_stability<V: ValueTree>(
+        &mut self,
+        case: &V,
+        test: &impl Fn(V::Value) -> TestCaseResult,
+        why: Reason,
+    ) -> Reason {
+        let checks = self.config.failure_stability_checks;
+        if checks == 0 || self.config.fork() {
+            return why;
+        }
+
+        // A fresh cache, so that the failure already seen isn't replayed.
+        let mut result_cache = noop_result_cache();
+        let mut fork_output = ForkOutput::empty();
+        let mut failures = 0;
+        for _ in 0..checks {
+            let result = call_test(
+                self,
+                case.current(),
+                test,
+                &mut iter::empty(),
+                &mut *result_cache,
+                &mut fork_output,
+                false,
+            );
+            if let Err(TestCaseError::Fail(_)) = result {
+                failures += 1;
+            }
+        }
+
+        verbose_message!(
+            self,
+            INFO_LOG,
+            "Minimal failing input failed {} of {} re-runs",
+            failures,
+            checks
+        );
+        format!(
+            "{}\nThe minimal failing input failed {} of {} re-runs{}",
+            why,
+            failures,
+            checks,
+            if failures < checks {
+                "; the failure is flaky, so its persisted seed may not \
+                 reproduce it"
+            } else {
+                ""
+            }
+        )
+        .into()
+    }
+
+    fn shrink<V: ValueTree>(
+        &mut self,
+        case: &mut V,
         test: impl Fn(V::Value) -> TestCaseResult,
         replay_from_fork: &mut impl Iterator<Item = TestCaseResult>,
         result_cache: &mut dyn ResultCache,
@@ -776,14 +1820,34 @@ impl TestRunner {
             return None
         }
 
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!(target: "proptest", "shrink").entered();
+
         #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
         let start_time = std::time::Instant::now();
         let mut last_failure = None;
         let mut iterations = 0;
+        let mut last_failed = true;
+        let mut last_hash = None;
+        let mut repeats = 0;
+        let original_size = self.shrink_stats.original_size;
+        self.shrink_stats = ShrinkStats {
+            original_size,
+            minimal_size: original_size,
+            ..ShrinkStats::default()
+        };
+        let mut case = RecordShrinkPath {
+            tree: case,
+            path: mem::take(&mut self.shrink_path),
+            simplify_calls: 0,
+            complicate_calls: 0,
+        };
 
         verbose_message!(self, TRACE, "Starting shrinking");
 
         if case.simplify() {
+            self.shrink_stats.simplifications += 1;
             loop {
                 let mut timed_out: Option<u64> = None;
                 #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
@@ -850,11 +1914,74 @@ impl TestRunner {
                     break;
                 }
 
+                let current = case.current();
+                if self.config.max_shrink_repeats > 0 {
+                    let hash = DebugHasher::hash(&current);
+                    repeats = if Some(hash) == last_hash {
+                        repeats + 1
+                    } else {
+                        0
+                    };
+                    last_hash = Some(hash);
+                    self.shrink_stats.max_repeats =
+                        self.shrink_stats.max_repeats.max(repeats);
+
+                    if repeats >= self.config.max_shrink_repeats {
+                        verbose_message!(
+                            self,
+                            ALWAYS,
+                            "Aborting shrinking after {} consecutive \
+                             iterations with the same value; the value tree \
+                             {} is likely stuck alternating between simplify \
+                             and complicate",
+                            repeats,
+                            core::any::type_name::<V>()
+                        );
+                        self.shrink_stats.stuck = true;
+                        // The current value is the same as the last one
+                        // tested, so only move back if that one passed. A
+                        // stuck tree may never stop complicating, so bound
+                        // how far to go.
+                        if !last_failed {
+                            for _ in 0..self.config.max_shrink_repeats {
+                                if !case.complicate() {
+                                    break;
+                                }
+                                fork_output.append(&Ok(()));
+                            }
+                        }
+                        break;
+                    }
+                }
+
                 iterations += 1;
+                self.shrink_stats.iterations = iterations;
+
+                if let Some(predicate) = self.config.shrink_predicate {
+                    if !predicate(&current) {
+                        verbose_message!(
+                            self,
+                            TRACE,
+                            "Shrink candidate rejected by shrink_predicate"
+                        );
+                        last_failed = false;
+                        if !case.complicate() {
+                            break;
+                        }
+                        self.shrink_stats.complications += 1;
+                        continue;
+                    }
+                }
 
+                let size = if self.measures_sizes() {
+                    DebugLen::of(&current)
+                } else {
+                    0
+                };
+                let value = self.wants_events().then(|| case.current());
                 let result = call_test(
                     self,
-                    case.current(),
+                    current,
                     &test,
                     replay_from_fork,
                     result_cache,
@@ -862,11 +1989,20 @@ impl TestRunner {
                     is_from_persisted_seed,
                 );
 
+                if let Some(ref value) = value {
+                    self.emit(RunnerEvent::ShrinkStep {
+                        iteration: iterations,
+                        value,
+                        failed: matches!(result, Err(TestCaseError::Fail(_))),
+                    });
+                }
+
                 match result {
                     // Rejections are effectively a pass here,
                     // since they indicate that any behaviour of
                     // the function under test is acceptable.
                     Ok(_) | Err(TestCaseError::Reject(..)) => {
+                        last_failed = false;
                         if !case.complicate() {
                             verbose_message!(
                                 self,
@@ -876,9 +2012,12 @@ impl TestRunner {
 
                             break;
                         }
+                        self.shrink_stats.complications += 1;
                     }
                     Err(TestCaseError::Fail(why)) => {
                         last_failure = Some(why);
+                        last_failed = true;
+                        self.shrink_stats.minimal_size = size;
                         if !case.simplify() {
                             verbose_message!(
                                 self,
@@ -888,14 +2027,90 @@ impl TestRunner {
 
                             break;
                         }
+                        self.shrink_stats.simplifications += 1;
                     }
                 }
             }
         }
 
+        self.shrink_stats.simplify_calls = case.simplify_calls;
+        self.shrink_stats.complicate_calls = case.complicate_calls;
+        #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+        {
+            self.shrink_stats.elapsed = start_time.elapsed();
+        }
+        #[cfg(feature = "std")]
+        self.record_shrink_metrics();
+
+        self.shrink_path = case.path;
         last_failure
     }
 
+    /// Appends a report of the failure of the case generated from `seed`,
+    /// just shrunk to `minimal`, to `Config::failure_report_path`, if set.
+    #[cfg(feature = "std")]
+    fn record_failure_report(
+        &self,
+        seed: Seed,
+        why: &Reason,
+        minimal: &dyn fmt::Debug,
+    ) {
+        if let Some(ref path) = self.config.failure_report_path {
+            let report = FailureReport {
+                test_name: self.config.test_name,
+                source_file: self.config.source_file,
+                seed: &PersistedSeed(seed, self.shrink_path.clone()),
+                reason: why,
+                shrink_iterations: self.shrink_stats.iterations,
+                minimal,
+            };
+            if let Err(err) = append_failure_report(path, &report) {
+                verbose_message!(
+                    self,
+                    ALWAYS,
+                    "Failed to write failure report to {}: {}",
+                    path.display(),
+                    err
+                );
+            }
+        }
+    }
+
+    /// Returns whether the sizes in `ShrinkStats` are measured, which takes
+    /// formatting every value tested and is only done for
+    /// `Config::shrink_metrics_file`.
+    fn measures_sizes(&self) -> bool {
+        #[cfg(feature = "std")]
+        {
+            self.config.shrink_metrics_file.is_some()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            false
+        }
+    }
+
+    /// Appends the stats of the shrinking just done to
+    /// `Config::shrink_metrics_file`, if set.
+    #[cfg(feature = "std")]
+    fn record_shrink_metrics(&self) {
+        if let Some(ref path) = self.config.shrink_metrics_file {
+            if let Err(err) = append_shrink_metrics(
+                path,
+                self.config.test_name,
+                &self.shrink_stats,
+            ) {
+                verbose_message!(
+                    self,
+                    ALWAYS,
+                    "Failed to write shrink metrics to {}: {}",
+                    path.display(),
+                    err
+                );
+            }
+        }
+    }
+
     /// Update the state to account for a local rejection from `whence`, and
     /// return `Ok` if the caller should keep going or `Err` to abort.
     pub fn reject_local(
@@ -903,7 +2118,7 @@ impl TestRunner {
         whence: impl Into<Reason>,
     ) -> Result<(), Reason> {
         if self.local_rejects >= self.config.max_local_rejects {
-            Err("Too many local rejects".into())
+            Err(self.too_many_rejects("Too many local rejects"))
         } else {
             self.local_rejects += 1;
             Self::insert_or_increment(
@@ -914,6 +2129,50 @@ impl TestRunner {
         }
     }
 
+    /// Update the state to account for a local rejection by the filter
+    /// `name`, which may reject at most `max_rejects` values, and return `Ok`
+    /// if the caller should keep going or `Err` to abort.
+    ///
+    /// The rejection also counts towards `Config::max_local_rejects`. This is
+    /// what filters created by `Strategy::prop_filter_with_budget()` do.
+    pub fn reject_filtered(
+        &mut self,
+        name: &'static str,
+        max_rejects: u32,
+    ) -> Result<(), Reason> {
+        let count = self.filter_rejects.get(name).copied().unwrap_or(0);
+        if count >= max_rejects {
+            return Err(self.too_many_rejects(format!(
+                "Too many rejects by filter {}",
+                name
+            )));
+        }
+        self.reject_local(name)?;
+        *self.filter_rejects.entry(name).or_insert(0) += 1;
+        Ok(())
+    }
+
+    /// Returns how many values each filter with a budget rejected so far, by
+    /// name. See `Strategy::prop_filter_with_budget()`.
+    pub fn filter_rejects(&self) -> &BTreeMap<&'static str, u32> {
+        &self.filter_rejects
+    }
+
+    /// Builds the reason to abort after too many rejections, breaking down
+    /// the rejections by each filter with a budget.
+    fn too_many_rejects(&self, message: impl Into<String>) -> Reason {
+        let mut message = message.into();
+        if !self.filter_rejects.is_empty() {
+            message.push_str(" (rejects by filter:");
+            for (name, count) in &self.filter_rejects {
+                message.push_str(&format!(" {}: {},", name, count));
+            }
+            message.pop();
+            message.push(')');
+        }
+        message.into()
+    }
+
     /// Update the state to account for a global rejection from `whence`, and
     /// return `Ok` if the caller should keep going or `Err` to abort.
     fn reject_global<T>(&mut self, whence: Reason) -> Result<(), TestError<T>> {
@@ -933,6 +2192,52 @@ impl TestRunner {
             .or_insert(1);
     }
 
+    /// Count one more value generated under `label`. This is what `Labeled`
+    /// strategies do.
+    pub fn count_label(&mut self, label: &'static str) {
+        let count = self.label_counts.entry(label).or_insert(0);
+        if 0 == *count {
+            self.novel_case = true;
+        }
+        *count += 1;
+    }
+
+    /// Returns the number of local rejections so far.
+    pub(crate) fn local_rejects(&self) -> u32 {
+        self.local_rejects
+    }
+
+    /// Records that an `AdaptiveUnion` picked `option` for the case being
+    /// generated, so that it is told if the case is rejected.
+    pub(crate) fn track_union_pick(
+        &mut self,
+        feedback: &Arc<UnionFeedback>,
+        option: usize,
+    ) {
+        if let Some(ref mut picks) = self.union_picks {
+            picks.push((Arc::clone(feedback), option));
+        }
+    }
+
+    /// Records that a `MemoFlatten` with the counters `stats` generated a
+    /// value, so that they are logged at the end of the run.
+    #[cfg(feature = "std")]
+    pub(crate) fn track_memo(&mut self, stats: &Arc<MemoStats>) {
+        if !self
+            .memo_stats
+            .iter()
+            .any(|known| Arc::ptr_eq(known, stats))
+        {
+            self.memo_stats.push(Arc::clone(stats));
+        }
+    }
+
+    /// Returns how many values were generated under each label by this
+    /// runner. See `Labeled`.
+    pub fn label_counts(&self) -> &BTreeMap<&'static str, u32> {
+        &self.label_counts
+    }
+
     /// Increment the counter of flat map regenerations and return whether it
     /// is still under the configured limit.
     pub fn flat_map_regen(&self) -> bool {
@@ -940,9 +2245,86 @@ impl TestRunner {
             < self.config.max_flat_map_regens as usize
     }
 
+    /// Returns the complexity budget of the case being generated.
+    ///
+    /// Strategies which can produce values of unbounded size should draw
+    /// from this so that `Config::budget` bounds the whole value they are part
+    /// of. See `Budget` for details.
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    /// Refill the complexity budget as configured by `Config::budget`.
+    ///
+    /// The runner does this before generating each test case. Call this when
+    /// generating values by hand with `Strategy::new_tree()` which should each
+    /// get the full budget.
+    pub fn reset_budget(&mut self) {
+        self.budget = case_budget(&self.config);
+    }
+
     fn new_cache(&self) -> Box<dyn ResultCache> {
         (self.config.result_cache)()
     }
+
+    /// Mark the current case as novel if its input was new to `result_cache`,
+    /// which is only known if the cache kept the result just `put()` under
+    /// `key`.
+    fn note_cache_miss(&mut self, result_cache: &dyn ResultCache, key: u64) {
+        if result_cache.get(key).is_some() {
+            self.novel_case = true;
+        }
+    }
+
+    /// Count a successful case for `Config::adaptive_cases`.
+    fn count_adaptive_success(&mut self) {
+        self.adaptive_successes += 1;
+        self.stale_cases = if self.novel_case {
+            0
+        } else {
+            self.stale_cases + 1
+        };
+    }
+
+    /// Returns whether enough cases have succeeded to end the run, either
+    /// `Config::cases()` or as decided by `Config::adaptive_cases`.
+    fn ran_enough_cases(&self) -> bool {
+        let adaptive = match self.config.adaptive_cases {
+            Some(adaptive) => adaptive,
+            None => return self.successes >= self.config.cases(),
+        };
+
+        let max = if cfg!(miri) {
+            adaptive.max.min(self.config.miri_cases)
+        } else {
+            adaptive.max
+        };
+        if self.adaptive_successes >= max {
+            return true;
+        }
+
+        let stop = self.adaptive_successes >= adaptive.min
+            && self.stale_cases >= adaptive.window;
+        if stop {
+            verbose_message!(
+                self,
+                INFO_LOG,
+                "Stopping after {} cases, the last {} of which showed no \
+                 novel behaviour",
+                self.adaptive_successes,
+                self.stale_cases
+            );
+        }
+        stop
+    }
+}
+
+/// Returns a full budget for one test case under `config`.
+fn case_budget(config: &Config) -> Budget {
+    match config.budget {
+        Some(limit) => Budget::limited(limit as usize),
+        None => Budget::unlimited(),
+    }
 }
 
 #[cfg(feature = "fork")]
@@ -977,13 +2359,136 @@ fn init_replay(
     (iter::empty(), ForkOutput::empty())
 }
 
+/// A subprocess running test cases, as awaited by `await_child()`.
+#[cfg(feature = "fork")]
+trait ForkChild {
+    type Status: fmt::Display;
+
+    fn wait(&mut self) -> io::Result<Self::Status>;
+
+    #[cfg(feature = "timeout")]
+    fn wait_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> io::Result<Option<Self::Status>>;
+
+    fn success(status: &Self::Status) -> bool;
+}
+
+#[cfg(feature = "fork")]
+impl ForkChild for rusty_fork::ChildWrapper {
+    type Status = rusty_fork::ExitStatusWrapper;
+
+    fn wait(&mut self) -> io::Result<Self::Status> {
+        rusty_fork::ChildWrapper::wait(self)
+    }
+
+    #[cfg(feature = "timeout")]
+    fn wait_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> io::Result<Option<Self::Status>> {
+        rusty_fork::ChildWrapper::wait_timeout(self, timeout)
+    }
+
+    fn success(status: &Self::Status) -> bool {
+        status.success()
+    }
+}
+
+/// A subprocess started by `ForkBackend::Reexec`, which is killed when
+/// dropped.
+///
+/// Like `rusty-fork` does, this collects the output of the subprocess in a
+/// temporary file and prints it when dropped, so that the test harness
+/// captures it as output of the parent.
+#[cfg(feature = "fork")]
+struct ReexecChild {
+    child: process::Child,
+    output: fs::File,
+}
+
+#[cfg(feature = "fork")]
+impl ReexecChild {
+    fn spawn(mut cmd: process::Command) -> io::Result<Self> {
+        let output = tempfile::tempfile()?;
+        let child = cmd
+            .stdin(process::Stdio::null())
+            .stdout(output.try_clone()?)
+            .stderr(output.try_clone()?)
+            .spawn()?;
+        Ok(ReexecChild { child, output })
+    }
+}
+
+#[cfg(feature = "fork")]
+impl Drop for ReexecChild {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+
+        let mut output = Vec::new();
+        if self.output.seek(io::SeekFrom::Start(0)).is_ok()
+            && self.output.read_to_end(&mut output).is_ok()
+        {
+            print!("{}", String::from_utf8_lossy(&output));
+        }
+    }
+}
+
+#[cfg(feature = "fork")]
+impl ForkChild for ReexecChild {
+    type Status = process::ExitStatus;
+
+    fn wait(&mut self) -> io::Result<Self::Status> {
+        self.child.wait()
+    }
+
+    #[cfg(feature = "timeout")]
+    fn wait_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> io::Result<Option<Self::Status>> {
+        use std::thread;
+        use std::time::Instant;
+
+        // `std` can't wait with a timeout, so poll instead.
+        let start = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Ok(Some(status));
+            }
+            let elapsed = start.elapsed();
+            if elapsed >= timeout {
+                return Ok(None);
+            }
+            thread::sleep((timeout - elapsed).min(Duration::from_millis(10)));
+        }
+    }
+
+    fn success(status: &Self::Status) -> bool {
+        status.success()
+    }
+}
+
+/// Returns the command running the test called `test_name` in a fresh copy of
+/// the current test binary, for `ForkBackend::Reexec`.
+#[cfg(feature = "fork")]
+fn reexec_command(test_name: &str) -> io::Result<process::Command> {
+    let mut cmd = process::Command::new(env::current_exe()?);
+    cmd.args(["--quiet", "--test-threads", "1", "--nocapture", "--exact"])
+        .arg(test_name)
+        .env(ENV_REEXEC_TEST, test_name);
+    Ok(cmd)
+}
+
 #[cfg(feature = "fork")]
-fn await_child_without_timeout(
-    child: &mut rusty_fork::ChildWrapper,
+fn await_child_without_timeout<C: ForkChild>(
+    child: &mut C,
 ) -> (Option<TestCaseError>, Option<u64>) {
     let status = child.wait().expect("Failed to wait for child process");
 
-    if status.success() {
+    if C::success(&status) {
         (None, None)
     } else {
         (
@@ -997,8 +2502,8 @@ fn await_child_without_timeout(
 }
 
 #[cfg(all(feature = "fork", not(feature = "timeout")))]
-fn await_child(
-    child: &mut rusty_fork::ChildWrapper,
+fn await_child<C: ForkChild>(
+    child: &mut C,
     _: &mut tempfile::NamedTempFile,
     _timeout: u32,
 ) -> (Option<TestCaseError>, Option<u64>) {
@@ -1006,13 +2511,11 @@ fn await_child(
 }
 
 #[cfg(all(feature = "fork", feature = "timeout"))]
-fn await_child(
-    child: &mut rusty_fork::ChildWrapper,
+fn await_child<C: ForkChild>(
+    child: &mut C,
     forkfile: &mut tempfile::NamedTempFile,
     timeout: u32,
 ) -> (Option<TestCaseError>, Option<u64>) {
-    use std::time::Duration;
-
     if 0 == timeout {
         return await_child_without_timeout(child);
     }
@@ -1032,7 +2535,7 @@ fn await_child(
             .wait_timeout(Duration::from_millis(timeout.into()))
             .expect("Failed to wait for child process")
         {
-            if status.success() {
+            if C::success(&status) {
                 return (None, None);
             } else {
                 return (
@@ -1087,34 +2590,684 @@ mod test {
             Err(TestError::Abort(_)) => (),
             e => panic!("Unexpected result: {:?}", e),
         }
-        assert_eq!(config.max_global_rejects + 1, runs.get());
+        assert_eq!(config.max_global_rejects + 1, runs.get());
+    }
+
+    #[test]
+    fn test_pass() {
+        let mut runner = TestRunner::default();
+        let result = runner.run(&(1u32..), |v| {
+            assert!(v > 0);
+            Ok(())
+        });
+        assert_eq!(Ok(()), result);
+    }
+
+    #[test]
+    fn cases_overrides_match_test_name() {
+        let overrides = vec![
+            ("other::*".into(), 3),
+            ("slow_module::*_test".into(), 5),
+            ("*".into(), 7),
+        ];
+        let cases = |test_name| {
+            let config = Config {
+                cases_overrides: overrides.clone(),
+                test_name,
+                ..Config::with_cases(11)
+            };
+            let runs = Cell::new(0);
+            TestRunner::new(config)
+                .run(&Just(()), |_| {
+                    runs.set(runs.get() + 1);
+                    Ok(())
+                })
+                .unwrap();
+            runs.get()
+        };
+
+        assert_eq!(5, cases(Some("my_crate::slow_module::big_test")));
+        assert_eq!(5, cases(Some("slow_module::big_test")));
+        assert_eq!(7, cases(Some("my_crate::slow_module::big")));
+        assert_eq!(11, cases(None));
+    }
+
+    #[test]
+    fn budget_bounds_whole_value_of_each_case() {
+        use crate::collection::vec;
+
+        let mut runner = TestRunner::new(Config {
+            budget: Some(30),
+            ..Config::default()
+        });
+        let largest = Cell::new(0);
+        runner
+            .run(&vec(vec(0u8..10, 0..20), 0..20), |value| {
+                let size =
+                    value.len() + value.iter().map(Vec::len).sum::<usize>();
+                assert!(size <= 30, "{:?}", value);
+                largest.set(largest.get().max(size));
+                Ok(())
+            })
+            .unwrap();
+        // Every case gets the full budget anew.
+        assert_eq!(30, largest.get());
+    }
+
+    #[test]
+    fn test_fail_via_result() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..10u32), |v| {
+            if v < 5 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("not less than 5"))
+            }
+        });
+
+        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+    }
+
+    #[test]
+    fn reproduces_replays_persisted_seeds() {
+        use rand::Rng;
+
+        let mut runner = TestRunner::deterministic();
+        let test = |v: u32| {
+            prop_assert!(v < 50);
+            Ok(())
+        };
+
+        let (mut failing, mut passing) = (0, 0);
+        for _ in 0..64 {
+            let seed = runner.rng.new_rng_seed();
+            let persisted = PersistedSeed(seed, ShrinkPath::default());
+            let mut before = runner.rng.clone();
+            let fails = runner.reproduces(&(0u32..100), &persisted, test);
+            if fails {
+                failing += 1;
+            } else {
+                passing += 1;
+            }
+            // Replaying is deterministic and leaves the runner's own random
+            // state untouched
+            assert_eq!(
+                fails,
+                runner.reproduces(&(0u32..100), &persisted, test)
+            );
+            assert_eq!(before.gen::<u64>(), runner.rng.gen::<u64>());
+        }
+        assert!(failing > 0 && passing > 0);
+    }
+
+    #[test]
+    fn run_matrix_reports_properties_failing_on_minimal_input() {
+        fn below_100(v: u32) -> TestCaseResult {
+            prop_assert!(v < 100);
+            Ok(())
+        }
+        fn below_200(v: u32) -> TestCaseResult {
+            prop_assert!(v < 200);
+            Ok(())
+        }
+        fn not_zero(v: u32) -> TestCaseResult {
+            prop_assume!(v != 0);
+            Ok(())
+        }
+        let properties = [
+            ("below_100", below_100 as fn(u32) -> TestCaseResult),
+            ("below_200", below_200),
+            ("not_zero", not_zero),
+        ];
+
+        let mut runner = TestRunner::deterministic();
+        match runner.run_matrix(&(0u32..1000), &properties) {
+            Err(TestError::Fail(why, failure)) => {
+                assert!(why.message().starts_with("below_100: "), "{}", why);
+                assert_eq!(100, failure.input);
+                assert_eq!(
+                    vec!["below_100"],
+                    failure.failed.iter().map(|f| f.0).collect::<Vec<_>>()
+                );
+                assert_eq!(vec!["below_200", "not_zero"], failure.passed);
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        let mut runner = TestRunner::deterministic();
+        assert!(runner.run_matrix(&(0u32..100), &properties).is_ok());
+    }
+
+    #[test]
+    fn run_value_reports_literal_without_shrinking() {
+        let mut runner = TestRunner::default();
+        let test = |v: u32| {
+            if 3 == v {
+                Err(TestCaseError::reject("three"))
+            } else if v < 5 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("not less than 5"))
+            }
+        };
+
+        assert_eq!(Ok(true), runner.run_value(2, test));
+        assert_eq!(Ok(false), runner.run_value(3, test));
+        assert_eq!(
+            Err(TestError::Fail("not less than 5".into(), 1000)),
+            runner.run_value(1000, test)
+        );
+    }
+
+    #[test]
+    fn event_sink_sees_cases_shrinking_and_failure() {
+        use std::string::ToString;
+        use std::sync::Mutex;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&events);
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner.set_event_sink(move |event: &RunnerEvent<'_>| {
+            let event = match event {
+                RunnerEvent::CaseStarted { seed } => {
+                    assert!(seed.is_some());
+                    "start".to_string()
+                }
+                RunnerEvent::CaseFinished { outcome } => {
+                    format!("{:?}", outcome)
+                }
+                RunnerEvent::ShrinkStep { value, failed, .. } => {
+                    format!("shrink {:?} {}", value, failed)
+                }
+                RunnerEvent::Failure { minimal, reason } => {
+                    format!("failure {:?} {}", minimal, reason)
+                }
+            };
+            sink.lock().unwrap().push(event);
+        });
+
+        let result = runner.run(&(0u32..1000u32), |v| {
+            if v < 500 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("too big"))
+            }
+        });
+        assert_eq!(Err(TestError::Fail("too big".into(), 500)), result);
+
+        let events = events.lock().unwrap();
+        let failed = events.iter().position(|e| "Failed" == e).unwrap();
+        assert_eq!("start", events[failed - 1]);
+        assert!(events[..failed - 1]
+            .chunks(2)
+            .all(|pair| pair == ["start", "Passed"]));
+        assert!(events[failed + 1..events.len() - 1]
+            .iter()
+            .all(|e| e.starts_with("shrink ")));
+        assert!(events.contains(&"shrink 500 true".to_string()));
+        assert_eq!("failure 500 too big", events[events.len() - 1]);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_sees_spans_and_events() {
+        use std::string::String;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Mutex;
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{subscriber, Event, Metadata, Subscriber};
+
+        /// Records the names of new spans and the messages of events.
+        #[derive(Clone, Default)]
+        struct Recorder {
+            next_id: Arc<AtomicU64>,
+            lines: Arc<Mutex<Vec<String>>>,
+        }
+
+        struct Message<'a>(&'a mut String);
+
+        impl Visit for Message<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                if "message" == field.name() {
+                    *self.0 = format!("{:?}", value);
+                }
+            }
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let name = format!("span {}", span.metadata().name());
+                self.lines.lock().unwrap().push(name);
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::SeqCst) + 1)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut message = String::new();
+                event.record(&mut Message(&mut message));
+                self.lines.lock().unwrap().push(message);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let recorder = Recorder::default();
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = subscriber::with_default(recorder.clone(), || {
+            runner.run(&(0u32..1000u32), |v| {
+                prop_assume!(v != 1);
+                prop_assert!(v < 500, "too big");
+                Ok(())
+            })
+        });
+        assert!(matches!(result, Err(TestError::Fail(_, 500))));
+
+        let lines = recorder.lines.lock().unwrap();
+        assert_eq!("span proptest", lines[0]);
+        for expected in [
+            "span case",
+            "case started",
+            "case finished",
+            "span shrink",
+            "shrink step",
+            "test failed",
+        ] {
+            assert!(
+                lines.iter().any(|line| expected == line),
+                "no {:?} in {:?}",
+                expected,
+                lines
+            );
+        }
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("Test case failed: too big")));
+    }
+
+    #[test]
+    fn miri_runs_fewer_cases_without_files() {
+        let mut runner = TestRunner::new(Config {
+            cases: 100,
+            miri_cases: 3,
+            failure_persistence: Some(Box::new(
+                FileFailurePersistence::Direct("never-written.txt"),
+            )),
+            ..Config::default()
+        });
+        let runs = Cell::new(0);
+        runner
+            .run(&(0u32..10), |_| {
+                runs.set(runs.get() + 1);
+                Ok(())
+            })
+            .unwrap();
+
+        let persistence = runner.config().failure_persistence.as_ref().unwrap();
+        if cfg!(miri) {
+            assert_eq!(3, runs.get());
+            assert!(persistence
+                .as_any()
+                .is::<crate::test_runner::MapFailurePersistence>());
+        } else {
+            assert_eq!(100, runs.get());
+            assert!(persistence.as_any().is::<FileFailurePersistence>());
+        }
+    }
+
+    #[test]
+    fn run_borrowed_passes_borrows_and_shrinks_owned_values() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let result = runner.run_borrowed(
+            &crate::collection::vec(0u8..10, 0..100),
+            |bytes: &[u8]| {
+                prop_assert!(bytes.len() < 5);
+                Ok(())
+            },
+        );
+
+        match result {
+            Err(TestError::Fail(_, value)) => assert_eq!(vec![0; 5], value),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn shrinking_stops_when_value_tree_is_stuck() {
+        #[derive(Debug)]
+        struct Stuck;
+
+        impl ValueTree for Stuck {
+            type Value = u32;
+
+            fn current(&self) -> u32 {
+                42
+            }
+
+            fn simplify(&mut self) -> bool {
+                true
+            }
+
+            fn complicate(&mut self) -> bool {
+                true
+            }
+        }
+
+        #[derive(Debug)]
+        struct StuckStrategy;
+
+        impl Strategy for StuckStrategy {
+            type Tree = Stuck;
+            type Value = u32;
+
+            fn new_tree(&self, _: &mut TestRunner) -> NewTree<Self> {
+                Ok(Stuck)
+            }
+        }
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            max_shrink_repeats: 10,
+            ..Config::default()
+        });
+        let result = runner.run(&StuckStrategy, |v| {
+            prop_assert!(v < 10);
+            Ok(())
+        });
+
+        assert!(matches!(result, Err(TestError::Fail(_, 42))));
+        let stats = runner.shrink_stats();
+        assert!(stats.stuck);
+        assert_eq!(10, stats.max_repeats);
+        assert_eq!(10, stats.iterations);
+        assert_eq!(0, stats.complications);
+        // The first call, and one after each of the failing iterations.
+        assert_eq!(11, stats.simplifications);
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let tree = crate::num::u32::BinarySearch::new(1000);
+        let _ = runner.run_one(tree, |v| {
+            prop_assert!(v < 500);
+            Ok(())
+        });
+
+        let stats = runner.shrink_stats();
+        // Simplifies to 500 and 250, then complicates back up towards 500.
+        assert!(!stats.stuck);
+        assert_eq!(10, stats.iterations);
+        assert_eq!(2, stats.simplifications);
+        assert_eq!(8, stats.complications);
+        assert_eq!(3, stats.simplify_calls);
+        assert_eq!(8, stats.complicate_calls);
+        // Every iteration follows one call, and the last call found nothing
+        // more to try.
+        assert_eq!(
+            stats.iterations + 1,
+            stats.simplify_calls + stats.complicate_calls
+        );
+    }
+
+    #[test]
+    fn repeated_values_do_not_stop_shrinking_by_default() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        // Shrinking the vector only repeats the same length for a while
+        // before the second component gets its turn.
+        let input = (crate::collection::vec(0u32..1000, 20), 0u32..1000)
+            .prop_map(|(v, n)| (v.len(), n));
+        let result = runner.run(&input, |(_, n)| {
+            prop_assert!(n < 100);
+            Ok(())
+        });
+
+        assert!(matches!(result, Err(TestError::Fail(_, (20, 100)))));
+        assert!(!runner.shrink_stats().stuck);
+    }
+
+    #[test]
+    fn shrink_stats_record_size_of_original_and_minimal_values() {
+        let path = std::env::temp_dir().join(format!(
+            "proptest-shrink-sizes-{}.jsonl",
+            std::process::id()
+        ));
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            shrink_metrics_file: Some(path.clone()),
+            ..Config::default()
+        });
+        let input = crate::collection::vec(0u32..1000, 100..200);
+        let result = runner.run(&input, |v| {
+            prop_assert!(v.len() < 50);
+            Ok(())
+        });
+
+        let minimal = match result {
+            Err(TestError::Fail(_, minimal)) => minimal,
+            result => panic!("Unexpected result: {:?}", result),
+        };
+        assert_eq!(vec![0; 100], minimal);
+        let stats = runner.shrink_stats();
+        assert_eq!(format!("{:?}", minimal).len(), stats.minimal_size);
+        assert!(stats.original_size > stats.minimal_size);
+        assert_eq!(
+            stats.original_size - stats.minimal_size,
+            stats.size_reduction()
+        );
+        fs::remove_file(&path).unwrap();
+
+        // Without a metrics file, no values are formatted.
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::default()
+        });
+        let _ = runner.run(&input, |v| {
+            prop_assert!(v.len() < 50);
+            Ok(())
+        });
+        assert_eq!(0, runner.shrink_stats().original_size);
+        assert_eq!(0, runner.shrink_stats().minimal_size);
+    }
+
+    #[test]
+    fn shrink_metrics_are_appended_to_file() {
+        let path = std::env::temp_dir().join(format!(
+            "proptest-shrink-metrics-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let config = Config {
+            failure_persistence: None,
+            shrink_metrics_file: Some(path.clone()),
+            test_name: Some("my_crate::my_test"),
+            ..Config::default()
+        };
+        for _ in 0..2 {
+            let mut runner = TestRunner::new(config.clone());
+            let _ = runner.run(&(0u32..1000u32), |v| {
+                prop_assert!(v < 500);
+                Ok(())
+            });
+        }
+
+        let metrics = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = metrics.lines().collect();
+        assert_eq!(2, lines.len());
+        assert!(lines
+            .iter()
+            .all(|line| line.starts_with("{\"test\":\"my_crate::my_test\",")));
+    }
+
+    #[test]
+    fn failure_report_is_appended_to_file() {
+        let path = std::env::temp_dir().join(format!(
+            "proptest-failure-report-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            failure_report_path: Some(path.clone()),
+            test_name: Some("my_crate::my_test"),
+            ..Config::default()
+        });
+        let result = runner.run(&(0u32..1000u32), |v| {
+            prop_assert!(v < 500, "too big");
+            Ok(())
+        });
+        assert!(matches!(result, Err(TestError::Fail(_, 500))));
+
+        let report = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(1, lines.len());
+        assert!(lines[0].starts_with(
+            "{\"test\":\"my_crate::my_test\",\"source_file\":null,"
+        ));
+        assert!(lines[0].contains(&format!(
+            "\"shrink_iterations\":{},",
+            runner.shrink_stats().iterations
+        )));
+        assert!(lines[0].ends_with("\"minimal_input\":\"500\"}"));
+
+        let seed = lines[0].split("\"seed\":\"").nth(1).unwrap();
+        let seed = &seed[..seed.find('"').unwrap()];
+        assert!(seed.parse::<PersistedSeed>().is_ok());
+    }
+
+    #[test]
+    fn named_rng_advances_runner_independently_of_use() {
+        use rand::Rng;
+
+        let mut a = TestRunner::deterministic();
+        let mut b = TestRunner::deterministic();
+
+        let mut edges_a = a.named_rng("edges");
+        let mut edges_b = b.named_rng("edges");
+        assert_eq!(edges_a.gen::<u64>(), edges_b.gen::<u64>());
+        for _ in 0..100 {
+            edges_b.gen::<u64>();
+        }
+
+        assert_eq!(a.rng().gen::<u64>(), b.rng().gen::<u64>());
+        assert_ne!(
+            a.named_rng("edges").gen::<u64>(),
+            b.named_rng("nodes").gen::<u64>()
+        );
     }
 
     #[test]
-    fn test_pass() {
-        let mut runner = TestRunner::default();
-        let result = runner.run(&(1u32..), |v| {
-            assert!(v > 0);
-            Ok(())
-        });
-        assert_eq!(Ok(()), result);
+    fn seed_set_makes_runs_reproducible() {
+        use std::sync::Mutex;
+
+        fn values(seed_set: Vec<u64>, cases: u32) -> Vec<u64> {
+            let seen = Mutex::new(Vec::new());
+            let mut runner = TestRunner::new(Config {
+                cases,
+                failure_persistence: None,
+                seed_set: Some(seed_set),
+                ..Config::default()
+            });
+            runner
+                .run(&crate::num::u64::ANY, |v| {
+                    seen.lock().unwrap().push(v);
+                    Ok(())
+                })
+                .unwrap();
+            seen.into_inner().unwrap()
+        }
+
+        let run = values(vec![1, 2, 3], 12);
+        assert_eq!(run, values(vec![1, 2, 3], 12));
+        assert_ne!(run, values(vec![1, 2, 4], 12));
+
+        // Every third case comes from the same seed's stream.
+        let first_seed = values(vec![1], 4);
+        assert_eq!(
+            first_seed,
+            run.iter().step_by(3).copied().collect::<Vec<_>>()
+        );
+
+        // Fewer cases than seeds just leaves the rest unused.
+        assert_eq!(run[..2], values(vec![1, 2, 3], 2)[..]);
     }
 
     #[test]
-    fn test_fail_via_result() {
-        let mut runner = TestRunner::new(Config {
+    fn seed_set_is_ignored_with_pass_through() {
+        let config = Config {
+            cases: 4,
             failure_persistence: None,
+            rng_algorithm: RngAlgorithm::PassThrough,
+            seed_set: Some(vec![1, 2]),
+            shard: Some((1, 2)),
             ..Config::default()
-        });
-        let result = runner.run(&(0u32..10u32), |v| {
-            if v < 5 {
-                Ok(())
-            } else {
-                Err(TestCaseError::fail("not less than 5"))
-            }
-        });
+        };
+        let rng = TestRng::from_seed(RngAlgorithm::PassThrough, &[7; 64]);
+        let mut runner = TestRunner::new_with_rng(config, rng);
+        assert!(runner.run(&crate::num::u8::ANY, |_| Ok(())).is_ok());
+    }
 
-        assert_eq!(Err(TestError::Fail("not less than 5".into(), 5)), result);
+    #[test]
+    fn shards_split_cases_reproducibly() {
+        use std::sync::Mutex;
+
+        fn values_of(test_name: &'static str, shard: (u32, u32)) -> Vec<u64> {
+            let seen = Mutex::new(Vec::new());
+            let mut runner = TestRunner::new(Config {
+                cases: 10,
+                failure_persistence: None,
+                test_name: Some(test_name),
+                shard: Some(shard),
+                ..Config::default()
+            });
+            runner
+                .run(&crate::num::u64::ANY, |v| {
+                    seen.lock().unwrap().push(v);
+                    Ok(())
+                })
+                .unwrap();
+            seen.into_inner().unwrap()
+        }
+        let values = |shard| values_of("my_crate::my_test", shard);
+
+        let shards =
+            (1..=3).map(|index| values((index, 3))).collect::<Vec<_>>();
+        assert_eq!(
+            vec![4, 3, 3],
+            shards.iter().map(Vec::len).collect::<Vec<_>>()
+        );
+        assert_eq!(shards[1], values((2, 3)));
+        assert!(shards[0].iter().all(|v| !shards[1].contains(v)));
+        assert_ne!(shards[0], values((1, 4))[..3]);
+        assert_ne!(shards[0], values_of("my_crate::other_test", (1, 3)));
     }
 
     #[test]
@@ -1239,6 +3392,115 @@ mod test {
         assert_eq!(first_super_failure, second_super_failure);
     }
 
+    #[test]
+    fn replay_modes_control_work_on_persisted_cases() {
+        use crate::test_runner::MapFailurePersistence;
+
+        let input = 0u32..1_000_000;
+        let config = Config {
+            failure_persistence: Some(Box::new(
+                MapFailurePersistence::default(),
+            )),
+            source_file: Some("replay"),
+            ..Config::default()
+        };
+        let mut runner = TestRunner::new(config.clone());
+        let result = runner.run(&input, |v| {
+            prop_assert!(v < 500);
+            Ok(())
+        });
+        assert!(matches!(result, Err(TestError::Fail(_, 500))));
+
+        let persistence = runner.config().failure_persistence.clone();
+        let seeds = persistence
+            .as_ref()
+            .unwrap()
+            .load_persisted_failures2(Some("replay"));
+        assert_eq!(1, seeds.len());
+        assert!(!seeds[0].1.is_empty());
+
+        // Replays only the persisted case with a test failing from `limit`,
+        // returning the minimal failing value and the number of times the
+        // test ran.
+        let replay = |replay, limit| {
+            let calls = Cell::new(0);
+            let result = TestRunner::new(Config {
+                failure_persistence: persistence.clone(),
+                cases: 0,
+                max_shrink_iters: 10_000,
+                replay,
+                ..config.clone()
+            })
+            .run(&input, |v| {
+                calls.set(calls.get() + 1);
+                prop_assert!(v < limit);
+                Ok(())
+            });
+            let minimal = match result {
+                Err(TestError::Fail(_, v)) => Some(v),
+                _ => None,
+            };
+            (minimal, calls.get())
+        };
+
+        let (minimal, full_calls) = replay(ReplayMode::Full, 500);
+        assert_eq!(Some(500), minimal);
+        assert!(full_calls > 10);
+
+        let (minimal, calls) = replay(ReplayMode::Verify, 500);
+        assert_eq!((Some(500), 1), (minimal, calls));
+
+        let (minimal, calls) = replay(ReplayMode::Reshrink, 500);
+        assert_eq!(Some(500), minimal);
+        assert!(calls < full_calls, "{} >= {}", calls, full_calls);
+
+        // Once the minimal value passes, the whole case is replayed
+        let (minimal, calls) = replay(ReplayMode::Verify, 501);
+        assert_eq!(Some(501), minimal);
+        assert!(calls > 10);
+    }
+
+    #[test]
+    fn persisted_seeds_replay_with_their_own_algorithm() {
+        use crate::test_runner::{FailurePersistence, MapFailurePersistence};
+
+        let input = 0u32..1_000_000;
+        let config =
+            |rng_algorithm, persistence: Box<dyn FailurePersistence>| Config {
+                failure_persistence: Some(persistence),
+                source_file: Some("algorithm"),
+                rng_algorithm,
+                replay: ReplayMode::Verify,
+                ..Config::default()
+            };
+        let test = |v: u32| {
+            prop_assert!(v < 500);
+            Ok(())
+        };
+
+        let mut runner = TestRunner::new(config(
+            RngAlgorithm::XorShift,
+            Box::<MapFailurePersistence>::default(),
+        ));
+        assert!(matches!(runner.run(&input, test), Err(TestError::Fail(..))));
+        let persistence = runner.config().failure_persistence.clone().unwrap();
+        let seeds = persistence.load_persisted_failures2(Some("algorithm"));
+        assert_eq!(1, seeds.len());
+        assert!(format!("{}", seeds[0]).starts_with("xs "));
+
+        let calls = Cell::new(0);
+        let result = TestRunner::new(Config {
+            cases: 0,
+            ..config(RngAlgorithm::ChaCha, persistence)
+        })
+        .run(&input, |v| {
+            calls.set(calls.get() + 1);
+            test(v)
+        });
+        assert!(matches!(result, Err(TestError::Fail(_, 500))));
+        assert_eq!(1, calls.get());
+    }
+
     #[test]
     fn new_rng_makes_separate_rng() {
         use rand::Rng;
@@ -1369,6 +3631,93 @@ mod test {
         }
     }
 
+    #[cfg(feature = "fork")]
+    #[test]
+    fn normal_failure_in_reexec_results_in_correct_failure() {
+        let mut runner = TestRunner::new(Config {
+            fork: true,
+            fork_backend: ForkBackend::Reexec,
+            test_name: Some(concat!(
+                module_path!(),
+                "::normal_failure_in_reexec_results_in_correct_failure"
+            )),
+            ..Config::default()
+        });
+
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                prop_assert!(v < 500);
+                Ok(())
+            })
+            .err()
+            .unwrap();
+
+        match failure {
+            TestError::Fail(_, value) => assert_eq!(500, value),
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
+    }
+
+    #[cfg(feature = "fork")]
+    #[test]
+    fn nonsuccessful_exit_in_reexec_finds_correct_failure() {
+        let mut runner = TestRunner::new(Config {
+            fork: true,
+            fork_backend: ForkBackend::Reexec,
+            test_name: Some(concat!(
+                module_path!(),
+                "::nonsuccessful_exit_in_reexec_finds_correct_failure"
+            )),
+            ..Config::default()
+        });
+
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                if v >= 500 {
+                    ::std::process::exit(1);
+                }
+                Ok(())
+            })
+            .err()
+            .unwrap();
+
+        match failure {
+            TestError::Fail(_, value) => assert_eq!(500, value),
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
+    }
+
+    #[cfg(feature = "timeout")]
+    #[test]
+    fn timeout_in_reexec_finds_correct_failure() {
+        let mut runner = TestRunner::new(Config {
+            timeout: 500,
+            fork_backend: ForkBackend::Reexec,
+            test_name: Some(concat!(
+                module_path!(),
+                "::timeout_in_reexec_finds_correct_failure"
+            )),
+            ..Config::default()
+        });
+
+        let failure = runner
+            .run(&(0u32..1000), |v| {
+                if v >= 500 {
+                    ::std::thread::sleep(::std::time::Duration::from_millis(
+                        10_000,
+                    ));
+                }
+                Ok(())
+            })
+            .err()
+            .unwrap();
+
+        match failure {
+            TestError::Fail(_, value) => assert_eq!(500, value),
+            failure => panic!("Unexpected failure: {:?}", failure),
+        }
+    }
+
     #[cfg(feature = "timeout")]
     #[test]
     fn long_sleep_timeout_finds_correct_failure() {
@@ -1476,6 +3825,175 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn adaptive_cases_stop_once_cache_sees_nothing_new() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            result_cache: crate::test_runner::result_cache::basic_result_cache,
+            ..Config::with_adaptive_cases(16, 10_000, 32)
+        });
+        runner.run(&(0u32..8), |_| Ok(())).unwrap();
+        assert_eq!(8, runner.successes);
+        assert!(runner.adaptive_successes >= 16);
+        assert!(runner.adaptive_successes < 10_000);
+    }
+
+    #[test]
+    fn adaptive_cases_without_novelty_run_min_cases() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::with_adaptive_cases(16, 10_000, 4)
+        });
+        runner
+            .run(&crate::arbitrary::any::<u32>(), |_| Ok(()))
+            .unwrap();
+        assert_eq!(16, runner.successes);
+    }
+
+    #[test]
+    fn adaptive_cases_continue_while_covering_new_points() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            ..Config::with_adaptive_cases(1, 10_000, 128)
+        });
+        runner
+            .run(&crate::arbitrary::any::<u32>(), |v| {
+                crate::test_runner::cover(&format!("{}", v % 8));
+                Ok(())
+            })
+            .unwrap();
+        // Missing one of the 8 points for 128 cases in a row is practically
+        // impossible, so the run only stops after covering all of them.
+        assert_eq!(8, runner.covered.len());
+        assert!(runner.successes >= 128 + 8);
+        assert!(runner.successes < 10_000);
+    }
+
+    #[test]
+    fn shrink_predicate_keeps_minimal_input_interesting() {
+        // Without the predicate, the input would shrink to the empty `Vec`.
+        fn has_two_elements(value: &dyn fmt::Debug) -> bool {
+            format!("{:?}", value).contains(',')
+        }
+
+        let config = Config {
+            failure_persistence: None,
+            shrink_predicate: Some(has_two_elements),
+            ..Config::default()
+        };
+        // The first case of the deterministic RNG has several elements.
+        let mut runner = TestRunner::new_with_rng(
+            config,
+            TestRng::deterministic_rng(RngAlgorithm::default()),
+        );
+        let result = runner
+            .run(&crate::collection::vec(0u8..100, 0..10), |_| {
+                Err(TestCaseError::fail("always fails"))
+            });
+        match result {
+            Err(TestError::Fail(_, value)) => assert_eq!(vec![0, 0], value),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn failure_stability_checks_count_failing_reruns() {
+        let config = Config {
+            failure_persistence: None,
+            failure_stability_checks: 4,
+            result_cache: crate::test_runner::result_cache::basic_result_cache,
+            ..Config::default()
+        };
+
+        let mut runner = TestRunner::new(config.clone());
+        match runner.run(&(0u32..1000), |v| {
+            prop_assert!(v < 500, "too big");
+            Ok(())
+        }) {
+            Err(TestError::Fail(why, 500)) => {
+                let why = why.message();
+                assert!(why.contains("failed 4 of 4 re-runs"), "{}", why);
+                assert!(!why.contains("flaky"), "{}", why);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        // Passes every third run, whatever the input.
+        let runs = Cell::new(0);
+        let mut runner = TestRunner::new(config);
+        match runner.run(&(0u32..1000), |v| {
+            runs.set(runs.get() + 1);
+            prop_assert!(v < 500 || runs.get() % 3 == 0, "too big");
+            Ok(())
+        }) {
+            Err(TestError::Fail(why, _)) => {
+                let why = why.message();
+                assert!(!why.contains("failed 4 of 4"), "{}", why);
+                assert!(why.contains("the failure is flaky"), "{}", why);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn adaptive_cases_stop_at_max() {
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            result_cache: crate::test_runner::result_cache::basic_result_cache,
+            ..Config::with_adaptive_cases(1, 100, 4)
+        });
+        runner
+            .run(&crate::arbitrary::any::<u64>(), |_| Ok(()))
+            .unwrap();
+        assert_eq!(100, runner.successes);
+    }
+
+    #[cfg(feature = "coverage-guided")]
+    #[test]
+    fn coverage_guided_keeps_cases_reaching_new_coverage() {
+        use crate::test_runner::coverage::{
+            __sanitizer_cov_trace_pc_guard, __sanitizer_cov_trace_pc_guard_init,
+        };
+        use crate::test_runner::CoverageGuided;
+
+        // Each of the 8 points is new once, but only 4 seeds are kept.
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            coverage_guided: Some(CoverageGuided {
+                max_corpus: 4,
+                max_mutations: 8,
+            }),
+            ..Config::default()
+        });
+        runner
+            .run(&crate::arbitrary::any::<u32>(), |v| {
+                crate::test_runner::cover(&format!("{}", v % 8));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(8, runner.covered.len());
+        assert_eq!(4, runner.corpus.seeds.len());
+
+        // Instrument 4 edges by hand, each of which is new once.
+        let mut guards = [0u32; 4];
+        let guards = guards.as_mut_ptr();
+        unsafe { __sanitizer_cov_trace_pc_guard_init(guards, guards.add(4)) };
+        let mut runner = TestRunner::new(Config {
+            failure_persistence: None,
+            coverage_guided: Some(CoverageGuided::default()),
+            ..Config::default()
+        });
+        runner
+            .run(&crate::arbitrary::any::<u32>(), |v| {
+                unsafe {
+                    __sanitizer_cov_trace_pc_guard(guards.add(v as usize % 4))
+                };
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(4, runner.corpus.seeds.len());
+    }
 }
 
 #[cfg(all(feature = "fork", feature = "timeout", test))]
@@ -1538,6 +4056,56 @@ mod timeout_tests {
         }
     }
 
+    #[cfg(feature = "failure-registry")]
+    #[test]
+    fn duplicate_failures_are_reported() {
+        fn fingerprint(_: &dyn fmt::Debug) -> String {
+            "duplicate_failures_are_reported".into()
+        }
+
+        let run = |test_name, shrink_duplicate_failures| {
+            let config = Config {
+                failure_persistence: None,
+                test_name: Some(test_name),
+                failure_fingerprint: Some(fingerprint),
+                shrink_duplicate_failures,
+                ..Config::default()
+            };
+            let rng = TestRng::deterministic_rng(config.rng_algorithm);
+            TestRunner::new_with_rng(config, rng).run(&(0u32..1_000_000), |v| {
+                prop_assert!(v < 500, "too big");
+                Ok(())
+            })
+        };
+
+        match run("first", true) {
+            Err(TestError::Fail(why, 500)) => {
+                assert!(!why.message().contains("duplicate"))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        match run("second", true) {
+            Err(TestError::Fail(why, 500)) => assert!(why
+                .message()
+                .ends_with("; duplicate of earlier failure in `first`")),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        match run("third", false) {
+            Err(TestError::Fail(why, value)) => {
+                assert!(value > 500);
+                assert!(why.message().contains("duplicate"));
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        // A test failing again is no duplicate of itself.
+        match run("first", true) {
+            Err(TestError::Fail(why, 500)) => {
+                assert!(!why.message().contains("duplicate"))
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
     fn test_shrink_bail(config: Config) {
         let mut runner = TestRunner::new(config);
         let result = runner.run(&crate::num::u64::ANY, |v| {
@@ -0,0 +1,146 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use core::any::{Any, TypeId};
+use core::fmt;
+
+use crate::std_facade::{Arc, BTreeMap};
+use crate::test_runner::Config;
+
+/// Auxiliary data memoized by a `TestRunner` on behalf of strategies, such as
+/// lookup tables which are expensive to build and would otherwise be rebuilt
+/// by every call to `new_tree()`.
+///
+/// Each runner has its own scratch storage, holding at most one value of
+/// each type, which strategies access through `TestRunner::scratch()`. The
+/// values are tied to the configuration and seed of the runner they were
+/// built for: a scratch moved to another runner with `TestRunner::set_scratch()`
+/// is only reused if that runner has the same configuration and seed, and is
+/// discarded otherwise.
+#[derive(Clone, Default)]
+pub struct RunnerScratch {
+    /// The configuration and seed fingerprint of the runner the entries were
+    /// built for.
+    key: Option<(Config, u64)>,
+    entries: BTreeMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl fmt::Debug for RunnerScratch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RunnerScratch")
+            .field("seed", &self.key.as_ref().map(|&(_, seed)| seed))
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+impl RunnerScratch {
+    /// Returns whether no values are stored.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Discards all stored values.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the stored value of type `T`, building and storing it with
+    /// `build` if there is none yet or the stored values were built for
+    /// another configuration or seed.
+    pub(crate) fn get_or_build<T, F>(
+        &mut self,
+        config: &Config,
+        seed: u64,
+        build: F,
+    ) -> Arc<T>
+    where
+        T: Send + Sync + 'static,
+        F: FnOnce() -> T,
+    {
+        let current = match self.key {
+            Some((ref built_config, built_seed)) => {
+                built_seed == seed && built_config == config
+            }
+            None => false,
+        };
+        if !current {
+            self.entries.clear();
+            self.key = Some((config.clone(), seed));
+        }
+
+        let entry = self
+            .entries
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Arc::new(build()));
+        match Arc::clone(entry).downcast::<T>() {
+            Ok(value) => value,
+            Err(_) => unreachable!("scratch entries are keyed by their type"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::std_facade::Vec;
+    use crate::test_runner::{RngAlgorithm, TestRng, TestRunner};
+
+    #[derive(Debug, PartialEq)]
+    struct Table(Vec<u32>);
+
+    #[test]
+    fn value_is_built_once_per_runner() {
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            Table((0..100).map(|x| x * x).collect())
+        };
+
+        let mut runner = TestRunner::deterministic();
+        let first = runner.scratch(build);
+        let second = runner.scratch(build);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(1, builds.get());
+
+        // other types have their own entries
+        assert_eq!(7, *runner.scratch(|| 7u8));
+        assert_eq!(1, builds.get());
+    }
+
+    #[test]
+    fn moved_scratch_is_reused_by_same_runner_only() {
+        let mut runner = TestRunner::deterministic();
+        let table = runner.scratch(|| Table(vec![1, 2, 3]));
+        let scratch = runner.take_scratch();
+        assert!(!scratch.is_empty());
+        assert!(runner.take_scratch().is_empty());
+
+        let mut same = TestRunner::deterministic();
+        same.set_scratch(scratch.clone());
+        let reused = same.scratch(|| Table(vec![]));
+        assert!(Arc::ptr_eq(&table, &reused));
+
+        let mut other_config = TestRunner::new_with_rng(
+            Config::with_cases(1),
+            TestRng::deterministic_rng(RngAlgorithm::ChaCha),
+        );
+        other_config.set_scratch(scratch.clone());
+        assert_eq!(Table(vec![]), *other_config.scratch(|| Table(vec![])));
+
+        let mut other_seed = TestRunner::new_with_rng(
+            Config::default(),
+            TestRng::from_seed(RngAlgorithm::ChaCha, &[42; 32]),
+        );
+        other_seed.set_scratch(scratch);
+        assert_eq!(Table(vec![]), *other_seed.scratch(|| Table(vec![])));
+    }
+}
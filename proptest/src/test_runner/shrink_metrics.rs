@@ -0,0 +1,143 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::test_runner::ShrinkStats;
+use crate::Error;
+
+/// Write `stats` of shrinking the failing case of `test_name` to `out`, as a
+/// single line of JSON.
+///
+/// The line is an object with the keys `test` (a string, or `null` if the
+/// name is not known), `iterations`, `simplify_calls`, `complicate_calls`,
+/// `simplifications`, `complications`, `elapsed_us`, the wall time of
+/// shrinking in microseconds, and `original_size` and `minimal_size`, the
+/// lengths of the `Debug` output of the original and minimal failing values.
+///
+/// ## Stability
+///
+/// This function, `append_shrink_metrics()` and the format they write exist
+/// for the benchmark suites of proptest and of strategy authors. They are
+/// exempt from semver: keys may be added in minor releases.
+pub fn write_shrink_metrics(
+    out: &mut dyn Write,
+    test_name: Option<&str>,
+    stats: &ShrinkStats,
+) -> Result<(), Error> {
+    let test = match test_name {
+        Some(name) => json_string(name),
+        None => String::from("null"),
+    };
+    writeln!(
+        out,
+        "{{\"test\":{},\"iterations\":{},\"simplify_calls\":{},\
+         \"complicate_calls\":{},\"simplifications\":{},\
+         \"complications\":{},\"elapsed_us\":{},\"original_size\":{},\
+         \"minimal_size\":{}}}",
+        test,
+        stats.iterations,
+        stats.simplify_calls,
+        stats.complicate_calls,
+        stats.simplifications,
+        stats.complications,
+        stats.elapsed.as_micros(),
+        stats.original_size,
+        stats.minimal_size,
+    )?;
+    Ok(())
+}
+
+/// Append the line written by `write_shrink_metrics()` to the file at `path`,
+/// creating the file if it does not exist.
+///
+/// This is what `Config::shrink_metrics_file` uses. The whole line is written
+/// at once, so tests running in parallel can share one file.
+pub fn append_shrink_metrics(
+    path: &Path,
+    test_name: Option<&str>,
+    stats: &ShrinkStats,
+) -> Result<(), Error> {
+    append_json_line(path, |out| write_shrink_metrics(out, test_name, stats))
+}
+
+/// Append the line `write` writes to the file at `path`, creating the file if
+/// it does not exist.
+///
+/// The line is buffered and written at once, so tests running in parallel can
+/// share one file.
+pub(crate) fn append_json_line(
+    path: &Path,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut line = Vec::new();
+    write(&mut line)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)?;
+    Ok(())
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c < ' ' => {
+                quoted.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn writes_one_json_line() {
+        let stats = ShrinkStats {
+            iterations: 3,
+            simplify_calls: 4,
+            simplifications: 2,
+            original_size: 12,
+            minimal_size: 1,
+            ..ShrinkStats::default()
+        };
+        let mut out = Vec::new();
+        write_shrink_metrics(&mut out, Some("a::\"b\"\n"), &stats).unwrap();
+        assert_eq!(
+            "{\"test\":\"a::\\\"b\\\"\\u000a\",\"iterations\":3,\
+             \"simplify_calls\":4,\"complicate_calls\":0,\
+             \"simplifications\":2,\"complications\":0,\"elapsed_us\":0,\
+             \"original_size\":12,\"minimal_size\":1}\n",
+            std::str::from_utf8(&out).unwrap()
+        );
+    }
+
+    #[test]
+    fn append_reports_io_errors() {
+        let dir = std::env::temp_dir();
+        match append_shrink_metrics(&dir, None, &ShrinkStats::default()) {
+            Err(Error::Io(_)) => (),
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,797 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating durations, system times and civil date-times.
+//!
+//! `any::<Duration>()` and `any::<SystemTime>()` cover their whole types,
+//! which rarely fits the code under test. `duration()` instead generates
+//! `Duration`s within a range, and `system_time_around()` generates
+//! `SystemTime`s within some distance of an epoch, shrinking towards it.
+//! Bugs in time handling cluster around discontinuities of the calendar, so
+//! `SystemTimeStrategy::with_edges()` biases generation towards instants
+//! such as those of `leap_seconds()` and `eu_dst_transitions()`.
+//!
+//! `civil_date_time()` generates the fields of a date and time without a
+//! time zone, as parsers and formatters of dates handle them, favouring leap
+//! days, ends of months, leap seconds and the hours around daylight saving
+//! time transitions.
+//!
+//! ```
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! use proptest::prelude::*;
+//! use proptest::time::{self, leap_seconds};
+//!
+//! proptest! {
+//!     # /*
+//!     #[test]
+//!     # */
+//!     fn timeouts_are_ordered(
+//!         timeout in time::duration(Duration::from_millis(1)..Duration::from_secs(5)),
+//!         start in time::system_time_around(
+//!             UNIX_EPOCH + Duration::from_secs(1_500_000_000),
+//!             Duration::from_secs(86_400 * 365 * 10),
+//!         ).with_edges(leap_seconds()),
+//!     ) {
+//!         prop_assert!(start + timeout > start);
+//!     }
+//! }
+//! # fn main() { timeouts_are_ordered(); }
+//! ```
+
+use core::fmt;
+use core::ops::{Bound, RangeBounds, RangeInclusive};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+
+use crate::arbitrary::Arbitrary;
+use crate::num::{self, sample_uniform, sample_uniform_incl};
+use crate::strategy::*;
+use crate::test_runner::*;
+use crate::tuple::TupleValueTree;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+const SECS_PER_DAY: i64 = 86_400;
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    Duration::new(
+        (nanos / NANOS_PER_SEC) as u64,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+}
+
+/// Strategy returned by `duration()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct DurationStrategy {
+    nanos: RangeInclusive<u128>,
+}
+
+/// Creates a strategy which generates `Duration`s within `range`, with
+/// nanosecond precision.
+///
+/// Values shrink towards the start of the range.
+///
+/// ## Panics
+///
+/// Panics if `range` is empty.
+pub fn duration(range: impl RangeBounds<Duration>) -> DurationStrategy {
+    let start = match range.start_bound() {
+        Bound::Included(start) => start.as_nanos(),
+        Bound::Excluded(start) => start.as_nanos() + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => end.as_nanos(),
+        Bound::Excluded(end) => end
+            .as_nanos()
+            .checked_sub(1)
+            .expect("duration() requires a non-empty range"),
+        Bound::Unbounded => Duration::MAX.as_nanos(),
+    };
+    assert!(start <= end, "duration() requires a non-empty range");
+
+    DurationStrategy { nanos: start..=end }
+}
+
+impl Strategy for DurationStrategy {
+    type Tree = DurationValueTree;
+    type Value = Duration;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (start, end) = (*self.nanos.start(), *self.nanos.end());
+        let nanos = sample_uniform_incl(runner, start, end);
+        Ok(DurationValueTree(num::u128::BinarySearch::new_above(
+            start, nanos,
+        )))
+    }
+}
+
+/// `ValueTree` corresponding to `DurationStrategy`.
+#[derive(Clone, Copy, Debug)]
+pub struct DurationValueTree(num::u128::BinarySearch);
+
+impl ValueTree for DurationValueTree {
+    type Value = Duration;
+
+    fn current(&self) -> Duration {
+        duration_from_nanos(self.0.current())
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.0.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.0.complicate()
+    }
+}
+
+/// Returns the signed number of nanoseconds from `epoch` to `time`.
+fn offset_nanos(epoch: SystemTime, time: SystemTime) -> i128 {
+    match time.duration_since(epoch) {
+        Ok(after) => after.as_nanos() as i128,
+        Err(before) => -(before.duration().as_nanos() as i128),
+    }
+}
+
+/// Returns the `SystemTime` `offset` nanoseconds from `epoch`.
+fn add_offset_nanos(epoch: SystemTime, offset: i128) -> SystemTime {
+    let distance = duration_from_nanos(offset.unsigned_abs());
+    if offset >= 0 {
+        epoch + distance
+    } else {
+        epoch - distance
+    }
+}
+
+/// Strategy returned by `system_time_around()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct SystemTimeStrategy {
+    epoch: SystemTime,
+    offsets: RangeInclusive<i128>,
+    edges: Arc<[i128]>,
+}
+
+/// Creates a strategy which generates `SystemTime`s at most `spread` before
+/// or after `epoch`, with nanosecond precision.
+///
+/// Values shrink towards `epoch`.
+///
+/// ## Panics
+///
+/// Panics if `SystemTime` cannot represent the times `spread` away from
+/// `epoch`.
+pub fn system_time_around(
+    epoch: SystemTime,
+    spread: Duration,
+) -> SystemTimeStrategy {
+    assert!(
+        epoch.checked_add(spread).is_some()
+            && epoch.checked_sub(spread).is_some(),
+        "system_time_around() spread overflows SystemTime"
+    );
+
+    let spread = spread.as_nanos() as i128;
+    SystemTimeStrategy {
+        epoch,
+        offsets: -spread..=spread,
+        edges: Arc::new([]),
+    }
+}
+
+impl SystemTimeStrategy {
+    /// Biases generation towards the instants `edges`, such as the ones
+    /// returned by `leap_seconds()` or `eu_dst_transitions()`.
+    ///
+    /// A quarter of the generated values then lie within a second of one of
+    /// the edges, if any of them is in range. Replaces any edges set
+    /// earlier.
+    pub fn with_edges(
+        mut self,
+        edges: impl IntoIterator<Item = SystemTime>,
+    ) -> Self {
+        let epoch = self.epoch;
+        self.edges = edges
+            .into_iter()
+            .map(|edge| offset_nanos(epoch, edge))
+            .filter(|offset| self.offsets.contains(offset))
+            .collect();
+        self
+    }
+}
+
+impl Strategy for SystemTimeStrategy {
+    type Tree = SystemTimeValueTree;
+    type Value = SystemTime;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (start, end) = (*self.offsets.start(), *self.offsets.end());
+        let offset = if !self.edges.is_empty() && runner.rng().gen_ratio(1, 4) {
+            let edge = self.edges[sample_uniform(runner, 0, self.edges.len())];
+            let second = NANOS_PER_SEC as i128;
+            let jitter = sample_uniform_incl(runner, -second, second);
+            (edge + jitter).max(start).min(end)
+        } else {
+            sample_uniform_incl(runner, start, end)
+        };
+
+        Ok(SystemTimeValueTree {
+            epoch: self.epoch,
+            offset: num::i128::BinarySearch::new_clamped(
+                start,
+                offset,
+                end + 1,
+            ),
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `SystemTimeStrategy`.
+#[derive(Clone, Copy, Debug)]
+pub struct SystemTimeValueTree {
+    epoch: SystemTime,
+    offset: num::i128::BinarySearch,
+}
+
+impl ValueTree for SystemTimeValueTree {
+    type Value = SystemTime;
+
+    fn current(&self) -> SystemTime {
+        add_offset_nanos(self.epoch, self.offset.current())
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.offset.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.offset.complicate()
+    }
+}
+
+/// The months at the end of which a leap second was inserted.
+const LEAP_SECOND_MONTHS: &[(i32, u8)] = &[
+    (1972, 6),
+    (1972, 12),
+    (1973, 12),
+    (1974, 12),
+    (1975, 12),
+    (1976, 12),
+    (1977, 12),
+    (1978, 12),
+    (1979, 12),
+    (1981, 6),
+    (1982, 6),
+    (1983, 6),
+    (1985, 6),
+    (1987, 12),
+    (1989, 12),
+    (1990, 12),
+    (1992, 6),
+    (1993, 6),
+    (1994, 6),
+    (1995, 12),
+    (1997, 6),
+    (1998, 12),
+    (2005, 12),
+    (2008, 12),
+    (2012, 6),
+    (2015, 6),
+    (2016, 12),
+];
+
+fn system_time_from_unix(secs: i64) -> SystemTime {
+    add_offset_nanos(UNIX_EPOCH, i128::from(secs) * NANOS_PER_SEC as i128)
+}
+
+/// Returns the instants right after each leap second inserted into UTC so
+/// far.
+///
+/// UNIX time repeats a second at these instants, which is where code
+/// computing with it is the most likely to go wrong.
+pub fn leap_seconds() -> impl Iterator<Item = SystemTime> {
+    LEAP_SECOND_MONTHS.iter().map(|&(year, month)| {
+        let leap_second = CivilDateTime {
+            year,
+            month,
+            day: days_in_month(year, month),
+            hour: 23,
+            minute: 59,
+            second: 60,
+            nanosecond: 0,
+        };
+        system_time_from_unix(leap_second.unix_timestamp())
+    })
+}
+
+/// Returns the instants of the daylight saving time transitions of the
+/// European Union in `years`.
+///
+/// These are at 01:00 UTC on the last Sundays of March and October, as the
+/// rules in force since 1996 have them.
+pub fn eu_dst_transitions(
+    years: RangeInclusive<i32>,
+) -> impl Iterator<Item = SystemTime> {
+    years.flat_map(|year| {
+        [3, 10].iter().map(move |&month| {
+            let transition = CivilDateTime {
+                year,
+                month,
+                day: last_sunday(year, month),
+                hour: 1,
+                minute: 0,
+                second: 0,
+                nanosecond: 0,
+            };
+            system_time_from_unix(transition.unix_timestamp())
+        })
+    })
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days from 1970-01-01 to the given date of the
+/// proleptic Gregorian calendar.
+fn days_from_civil(year: i32, month: u8, day: u8) -> i64 {
+    // Count years from March, so that leap days end them.
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (i64::from(month) + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the day of the week of the given date, from 0 for Sunday to 6 for
+/// Saturday.
+fn weekday(year: i32, month: u8, day: u8) -> u8 {
+    // 1970-01-01 was a Thursday.
+    (days_from_civil(year, month, day) + 4).rem_euclid(7) as u8
+}
+
+fn last_sunday(year: i32, month: u8) -> u8 {
+    let last = days_in_month(year, month);
+    last - weekday(year, month, last)
+}
+
+fn nth_sunday(year: i32, month: u8, n: u8) -> u8 {
+    1 + (7 - weekday(year, month, 1)) % 7 + 7 * (n - 1)
+}
+
+/// A date and time of the proleptic Gregorian calendar without a time zone,
+/// as generated by `civil_date_time()`.
+///
+/// Like chrono's `NaiveDateTime`, this represents a leap second by `second`
+/// being 60. `civil_date_time()` only generates those at 23:59 on the last
+/// day of June or December, when leap seconds are inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilDateTime {
+    /// The year, where 0 is 1 BC.
+    pub year: i32,
+    /// The month, from 1 to 12.
+    pub month: u8,
+    /// The day of the month, from 1.
+    pub day: u8,
+    /// The hour, from 0 to 23.
+    pub hour: u8,
+    /// The minute, from 0 to 59.
+    pub minute: u8,
+    /// The second, from 0 to 59, or 60 for a leap second.
+    pub second: u8,
+    /// The nanosecond within the second.
+    pub nanosecond: u32,
+}
+
+impl CivilDateTime {
+    /// Returns the number of seconds from the UNIX epoch to this date and
+    /// time in UTC.
+    ///
+    /// As in UNIX time, a leap second counts as the first second of the next
+    /// minute.
+    pub fn unix_timestamp(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * SECS_PER_DAY
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+    }
+
+    /// Returns the day of the week, from 0 for Sunday to 6 for Saturday.
+    pub fn weekday(&self) -> u8 {
+        weekday(self.year, self.month, self.day)
+    }
+}
+
+/// Formats as ISO 8601, e.g. `2016-12-31T23:59:60`, with nine fractional
+/// digits if the nanosecond is not 0.
+impl fmt::Display for CivilDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if (0..=9999).contains(&self.year) {
+            write!(f, "{:04}", self.year)?;
+        } else {
+            write!(f, "{:+05}", self.year)?;
+        }
+        write!(
+            f,
+            "-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.month, self.day, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond != 0 {
+            write!(f, ".{:09}", self.nanosecond)?;
+        }
+        Ok(())
+    }
+}
+
+/// Strategy returned by `civil_date_time()`.
+#[derive(Clone, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct CivilDateTimeStrategy {
+    years: RangeInclusive<i32>,
+    edges: bool,
+}
+
+/// Creates a strategy which generates valid `CivilDateTime`s in `years`.
+///
+/// A quarter of the values are edge cases: the last day of February, the
+/// last second of a month, a leap second, or the hours around a daylight
+/// saving time transition by the rules of the European Union or the United
+/// States. `CivilDateTimeStrategy::without_edges()` turns this off.
+///
+/// Values shrink field by field, towards the start of `years` (or year 0 if
+/// in range) and then towards January 1st at midnight.
+///
+/// ## Panics
+///
+/// Panics if `years` is empty.
+pub fn civil_date_time(years: RangeInclusive<i32>) -> CivilDateTimeStrategy {
+    assert!(
+        years.start() <= years.end(),
+        "civil_date_time() requires a non-empty range of years"
+    );
+    CivilDateTimeStrategy { years, edges: true }
+}
+
+impl CivilDateTimeStrategy {
+    /// Generates all date-times with the same probability, without favouring
+    /// edge cases.
+    pub fn without_edges(mut self) -> Self {
+        self.edges = false;
+        self
+    }
+}
+
+/// Times of day around daylight saving time transitions, which happen at
+/// 02:00 or 03:00 local time.
+const DST_EDGE_TIMES: &[(u8, u8, u8)] =
+    &[(1, 59, 59), (2, 0, 0), (2, 30, 0), (2, 59, 59), (3, 0, 0)];
+
+impl Strategy for CivilDateTimeStrategy {
+    type Tree = CivilDateTimeValueTree;
+    type Value = CivilDateTime;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let (start, end) = (*self.years.start(), *self.years.end());
+        let year = sample_uniform_incl(runner, start, end);
+        let mut month = sample_uniform_incl(runner, 1, 12);
+        let mut day =
+            sample_uniform_incl(runner, 1, days_in_month(year, month));
+        let mut hour = sample_uniform(runner, 0, 24);
+        let mut minute = sample_uniform(runner, 0, 60);
+        let mut second = sample_uniform(runner, 0, 60);
+        let mut nanosecond = sample_uniform(runner, 0, NANOS_PER_SEC as u32);
+
+        if self.edges && runner.rng().gen_ratio(1, 4) {
+            match sample_uniform(runner, 0, 4) {
+                // The last day of February, which is the leap day in leap
+                // years.
+                0 => {
+                    month = 2;
+                    day = days_in_month(year, month);
+                }
+                // The last second of a month.
+                1 => {
+                    day = days_in_month(year, month);
+                    hour = 23;
+                    minute = 59;
+                    second = 59;
+                    nanosecond = NANOS_PER_SEC as u32 - 1;
+                }
+                // A leap second.
+                2 => {
+                    month = if runner.rng().gen() { 6 } else { 12 };
+                    day = days_in_month(year, month);
+                    hour = 23;
+                    minute = 59;
+                    second = 60;
+                    nanosecond = 0;
+                }
+                // Around a daylight saving time transition.
+                _ => {
+                    let (dst_month, dst_day) =
+                        match sample_uniform(runner, 0, 4) {
+                            0 => (3, last_sunday(year, 3)),
+                            1 => (10, last_sunday(year, 10)),
+                            2 => (3, nth_sunday(year, 3, 2)),
+                            _ => (11, nth_sunday(year, 11, 1)),
+                        };
+                    month = dst_month;
+                    day = dst_day;
+                    let time = DST_EDGE_TIMES
+                        [sample_uniform(runner, 0, DST_EDGE_TIMES.len())];
+                    hour = time.0;
+                    minute = time.1;
+                    second = time.2;
+                    nanosecond = 0;
+                }
+            }
+        }
+
+        Ok(CivilDateTimeValueTree(TupleValueTree::new((
+            num::i32::BinarySearch::new_clamped(
+                start,
+                year,
+                end.saturating_add(1),
+            ),
+            num::u8::BinarySearch::new_above(1, month),
+            num::u8::BinarySearch::new_above(1, day),
+            num::u8::BinarySearch::new(hour),
+            num::u8::BinarySearch::new(minute),
+            num::u8::BinarySearch::new(second),
+            num::u32::BinarySearch::new(nanosecond),
+        ))))
+    }
+}
+
+/// `ValueTree` corresponding to `CivilDateTimeStrategy`.
+#[derive(Clone, Debug)]
+pub struct CivilDateTimeValueTree(
+    TupleValueTree<(
+        num::i32::BinarySearch,
+        num::u8::BinarySearch,
+        num::u8::BinarySearch,
+        num::u8::BinarySearch,
+        num::u8::BinarySearch,
+        num::u8::BinarySearch,
+        num::u32::BinarySearch,
+    )>,
+);
+
+impl ValueTree for CivilDateTimeValueTree {
+    type Value = CivilDateTime;
+
+    fn current(&self) -> CivilDateTime {
+        let (year, month, day, hour, minute, second, nanosecond) =
+            self.0.current();
+        // Shrinking the year or month may leave the day past the end of the
+        // month, or the leap second off the end of June or December.
+        let last_day = days_in_month(year, month);
+        let day = day.min(last_day);
+        let leap_second_allowed = (month == 6 || month == 12)
+            && day == last_day
+            && hour == 23
+            && minute == 59;
+        let second = if leap_second_allowed {
+            second
+        } else {
+            second.min(59)
+        };
+
+        CivilDateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+        }
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.0.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.0.complicate()
+    }
+}
+
+impl Arbitrary for CivilDateTime {
+    type Parameters = ();
+    type Strategy = CivilDateTimeStrategy;
+
+    /// Generates date-times in the years 1 to 9999, favouring edge cases as
+    /// `civil_date_time()` does.
+    fn arbitrary_with(_: ()) -> CivilDateTimeStrategy {
+        civil_date_time(1..=9999)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::string::ToString;
+    use std::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn durations_stay_in_range_and_shrink_to_start() {
+        let input =
+            duration(Duration::from_millis(1500)..Duration::from_secs(2));
+        let mut runner = TestRunner::deterministic();
+
+        for _ in 0..256 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            loop {
+                let value = tree.current();
+                assert!(
+                    value >= Duration::from_millis(1500)
+                        && value < Duration::from_secs(2),
+                    "{:?}",
+                    value
+                );
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!(Duration::from_millis(1500), tree.current());
+        }
+    }
+
+    #[test]
+    fn system_times_stay_around_epoch_and_favour_edges() {
+        let epoch = UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+        let spread = Duration::from_secs(86_400 * 365 * 5);
+        let input = system_time_around(epoch, spread)
+            .with_edges(leap_seconds().chain(eu_dst_transitions(2010..=2020)));
+        let edges: Vec<_> = leap_seconds()
+            .chain(eu_dst_transitions(2010..=2020))
+            .collect();
+        let mut runner = TestRunner::deterministic();
+
+        let mut near_edges = 0;
+        for _ in 0..1024 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let value = tree.current();
+            assert!(
+                value >= epoch - spread && value <= epoch + spread,
+                "{:?}",
+                value
+            );
+            if edges.iter().any(|&edge| {
+                offset_nanos(edge, value).unsigned_abs() <= NANOS_PER_SEC
+            }) {
+                near_edges += 1;
+            }
+
+            while tree.simplify() {}
+            assert_eq!(epoch, tree.current());
+        }
+        assert!(near_edges > 128, "{} values near edges", near_edges);
+    }
+
+    #[test]
+    fn leap_seconds_and_transitions_are_at_the_right_instants() {
+        let unix = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH).unwrap().as_secs()
+        };
+        let leap_seconds: Vec<_> = leap_seconds().map(unix).collect();
+        assert_eq!(27, leap_seconds.len());
+        // 1972-07-01 and 2017-01-01.
+        assert_eq!(78_796_800, leap_seconds[0]);
+        assert_eq!(1_483_228_800, leap_seconds[26]);
+
+        // 2024-03-31 and 2024-10-27 at 01:00 UTC.
+        assert_eq!(
+            vec![1_711_846_800, 1_729_990_800],
+            eu_dst_transitions(2024..=2024)
+                .map(unix)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn civil_date_times_are_valid_and_cover_edges() {
+        let mut runner = TestRunner::deterministic();
+        let input = civil_date_time(1900..=2100);
+
+        let (mut leap_days, mut leap_seconds, mut dst_edges) = (0, 0, 0);
+        for _ in 0..1024 {
+            let mut tree = input.new_tree(&mut runner).unwrap();
+            let value = tree.current();
+            if value.month == 2 && value.day == 29 {
+                leap_days += 1;
+            }
+            if value.second == 60 {
+                leap_seconds += 1;
+            }
+            if value.hour == 2 && value.weekday() == 0 {
+                dst_edges += 1;
+            }
+
+            loop {
+                let value = tree.current();
+                assert!((1900..=2100).contains(&value.year), "{}", value);
+                assert!((1..=12).contains(&value.month), "{}", value);
+                assert!(
+                    value.day >= 1
+                        && value.day <= days_in_month(value.year, value.month),
+                    "{}",
+                    value
+                );
+                assert!(value.hour < 24 && value.minute < 60, "{}", value);
+                assert!(
+                    value.second < 60
+                        || value.hour == 23
+                            && value.minute == 59
+                            && (value.month == 6 || value.month == 12),
+                    "{}",
+                    value
+                );
+                if !tree.simplify() {
+                    break;
+                }
+            }
+            assert_eq!("1900-01-01T00:00:00", tree.current().to_string());
+        }
+
+        assert!(leap_days > 0 && leap_seconds > 0 && dst_edges > 0);
+    }
+
+    #[test]
+    fn civil_date_time_timestamps_and_formatting() {
+        let value = CivilDateTime {
+            year: 2000,
+            month: 3,
+            day: 1,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+        };
+        assert_eq!(951_868_800, value.unix_timestamp());
+        assert_eq!(3, value.weekday());
+        assert_eq!("2000-03-01T00:00:00", value.to_string());
+
+        let value = CivilDateTime {
+            year: -1,
+            second: 5,
+            nanosecond: 120,
+            ..value
+        };
+        assert_eq!("-0001-03-01T00:00:05.000000120", value.to_string());
+    }
+
+    #[test]
+    fn test_sanity() {
+        check_strategy_sanity(duration(..Duration::from_secs(1)), None);
+        check_strategy_sanity(
+            system_time_around(UNIX_EPOCH, Duration::from_secs(1_000)),
+            None,
+        );
+        check_strategy_sanity(civil_date_time(-10..=3000), None);
+    }
+}
@@ -0,0 +1,232 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strongly typed quantities for catching unit-mismatch bugs.
+//!
+//! Each type in this module wraps a plain number, such as an `f64` or `i64`,
+//! and marks it with a unit of measure. Quantities can only be added to and
+//! subtracted from quantities of the same unit, and can be converted to a
+//! finer unit of the same dimension with `From`, so code under test which
+//! passes millimeters where meters are expected fails to compile instead of
+//! silently producing wrong results.
+//!
+//! Every unit has a function of the same name in lower case which wraps the
+//! values of a numeric strategy, such as a range, and the unit types
+//! implement `Arbitrary` by wrapping the `Arbitrary` strategy of the number
+//! type. Values shrink exactly like the wrapped numbers do. Since the types
+//! implement `Arbitrary`, structs using them can `#[derive(Arbitrary)]`, and
+//! fields can select a range with
+//! `#[proptest(strategy = "units::millimeters(0.0..1e3)")]`.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use proptest::units::{self, Millimeters};
+//!
+//! fn total_length(parts: &[Millimeters<f64>]) -> Millimeters<f64> {
+//!     parts.iter().fold(Millimeters(0.0), |sum, &part| sum + part)
+//! }
+//!
+//! proptest! {
+//!     # /*
+//!     #[test]
+//!     # */
+//!     fn total_length_is_at_least_each_part(
+//!         parts in prop::collection::vec(units::millimeters(0.0..1e3), 1..10),
+//!         extra in units::meters(0.0..1.0),
+//!     ) {
+//!         let mut parts = parts;
+//!         parts.push(Millimeters::from(extra));
+//!         let total = total_length(&parts);
+//!         for &part in &parts {
+//!             prop_assert!(total >= part);
+//!         }
+//!     }
+//! }
+//! # fn main() { total_length_is_at_least_each_part(); }
+//! ```
+
+use core::fmt;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::arbitrary::{any_with, Arbitrary};
+use crate::strategy::{Map, Strategy};
+
+macro_rules! units {
+    ($(
+        $(#[$meta:meta])*
+        $typ:ident, $fun:ident, $symbol:expr;
+    )*) => {$(
+        $(#[$meta])*
+        #[derive(
+            Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+        )]
+        pub struct $typ<T>(pub T);
+
+        impl<T> $typ<T> {
+            /// The symbol of this unit, as used by the `Display` format.
+            pub const SYMBOL: &'static str = $symbol;
+
+            /// Returns the number of units in this quantity.
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T: fmt::Display> fmt::Display for $typ<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)?;
+                write!(f, " {}", Self::SYMBOL)
+            }
+        }
+
+        impl<T: Add<Output = T>> Add for $typ<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                $typ(self.0 + rhs.0)
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $typ<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                $typ(self.0 - rhs.0)
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $typ<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $typ(-self.0)
+            }
+        }
+
+        impl<T: Mul<Output = T>> Mul<T> for $typ<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                $typ(self.0 * rhs)
+            }
+        }
+
+        impl<T: Div<Output = T>> Div<T> for $typ<T> {
+            type Output = Self;
+
+            fn div(self, rhs: T) -> Self {
+                $typ(self.0 / rhs)
+            }
+        }
+
+        impl<T: Arbitrary> Arbitrary for $typ<T> {
+            type Parameters = T::Parameters;
+            type Strategy = Map<T::Strategy, fn(T) -> Self>;
+
+            fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+                any_with::<T>(args).prop_map($typ as fn(T) -> Self)
+            }
+        }
+
+        #[doc = concat!(
+            "Creates a strategy which generates `", stringify!($typ),
+            "` quantities of the numbers generated by `values`, which is \
+             usually a range.\n\nValues shrink like those of `values`.")]
+        pub fn $fun<S: Strategy>(
+            values: S,
+        ) -> Map<S, fn(S::Value) -> $typ<S::Value>> {
+            values.prop_map($typ as fn(S::Value) -> $typ<S::Value>)
+        }
+    )*};
+}
+
+macro_rules! conversions {
+    ($($coarse:ident => $fine:ident * $factor:expr;)*) => {$(
+        /// Converts to the finer unit by multiplication, which is exact for
+        /// integers (barring overflow) and floats alike.
+        ///
+        /// There is no conversion in the other direction, since it may lose
+        /// precision; divide the wrapped number explicitly instead.
+        impl<T: Mul<Output = T> + From<u16>> From<$coarse<T>> for $fine<T> {
+            fn from(value: $coarse<T>) -> Self {
+                $fine(value.0 * T::from($factor))
+            }
+        }
+    )*};
+}
+
+units! {
+    /// A length in millimeters.
+    Millimeters, millimeters, "mm";
+    /// A length in meters.
+    Meters, meters, "m";
+    /// A mass in grams.
+    Grams, grams, "g";
+    /// A mass in kilograms.
+    Kilograms, kilograms, "kg";
+    /// A duration in milliseconds.
+    Milliseconds, milliseconds, "ms";
+    /// A duration in seconds.
+    Seconds, seconds, "s";
+    /// An amount of money in hundredths of its currency unit.
+    Cents, cents, "¢";
+    /// An amount of money in whole currency units.
+    Dollars, dollars, "$";
+}
+
+conversions! {
+    Meters => Millimeters * 1000;
+    Kilograms => Grams * 1000;
+    Seconds => Milliseconds * 1000;
+    Dollars => Cents * 100;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arbitrary::any;
+    use crate::strategy::ValueTree;
+    use crate::test_runner::TestRunner;
+    use std::string::ToString;
+
+    #[test]
+    fn generates_values_in_range() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = cents(-500i64..500);
+        for _ in 0..256 {
+            let Cents(value) =
+                strategy.new_tree(&mut runner).unwrap().current();
+            assert!((-500..500).contains(&value));
+        }
+    }
+
+    #[test]
+    fn shrinks_like_wrapped_numbers() {
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let mut tree =
+                any::<Millimeters<u32>>().new_tree(&mut runner).unwrap();
+            while tree.simplify() {}
+            assert_eq!(Millimeters(0), tree.current());
+        }
+    }
+
+    #[test]
+    fn converts_to_finer_units() {
+        assert_eq!(Millimeters(2500.0), Millimeters::from(Meters(2.5)));
+        assert_eq!(Cents(-1200i64), Cents::from(Dollars(-12i64)));
+        assert_eq!(Milliseconds(3000u32), Milliseconds::from(Seconds(3u32)));
+    }
+
+    #[test]
+    fn displays_with_symbol() {
+        assert_eq!("1.5 kg", Kilograms(1.5).to_string());
+        assert_eq!("42 ms", Milliseconds(42).to_string());
+    }
+}
@@ -0,0 +1,107 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::strategy::Strategy;
+use crate::test_runner::{
+    Config, TestCaseError, TestCaseResult, TestError, TestRunner,
+};
+
+/// Types which a property passed to `verify()` may return.
+///
+/// A `bool` is a failure if it is `false`. `()` always passes, so the
+/// property can simply panic (e.g., via `assert!`) on failure.
+pub trait IntoTestCaseResult {
+    /// Convert `self` into the result of a test case.
+    fn into_test_case_result(self) -> TestCaseResult;
+}
+
+impl IntoTestCaseResult for TestCaseResult {
+    fn into_test_case_result(self) -> TestCaseResult {
+        self
+    }
+}
+
+impl IntoTestCaseResult for bool {
+    fn into_test_case_result(self) -> TestCaseResult {
+        if self {
+            Ok(())
+        } else {
+            Err(TestCaseError::fail("property returned false"))
+        }
+    }
+}
+
+impl IntoTestCaseResult for () {
+    fn into_test_case_result(self) -> TestCaseResult {
+        Ok(())
+    }
+}
+
+/// Check that `property` holds for values of `strategy`, without any of the
+/// ceremony of constructing a `TestRunner`.
+///
+/// This is meant for quick experiments, doc examples, and self-checks in
+/// debug builds of applications, rather than for tests, where `proptest!` is
+/// the better choice. The runner uses the default configuration, except that
+/// failures are never persisted.
+///
+/// Returns the minimal failing input if the property does not hold. See
+/// `IntoTestCaseResult` for what `property` may return.
+///
+/// ## Example
+///
+/// ```
+/// use proptest::test_runner::TestError;
+///
+/// assert!(proptest::verify(0..100u32, |v| v * 2 >= v).is_ok());
+///
+/// match proptest::verify(0..100u32, |v| v < 50) {
+///     Err(TestError::Fail(_, v)) => assert_eq!(50, v),
+///     r => panic!("unexpected result: {:?}", r),
+/// }
+/// ```
+pub fn verify<S: Strategy, R: IntoTestCaseResult>(
+    strategy: S,
+    property: impl Fn(S::Value) -> R,
+) -> Result<(), TestError<S::Value>> {
+    let mut runner = TestRunner::new(Config {
+        failure_persistence: None,
+        ..Config::default()
+    });
+    runner.run(&strategy, |v| property(v).into_test_case_result())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn verify_accepts_results_and_units() {
+        assert!(verify(0..10u8, |v| {
+            if v < 10 {
+                Ok(())
+            } else {
+                Err(TestCaseError::fail("too big"))
+            }
+        })
+        .is_ok());
+        assert!(verify(0..10u8, |v| assert!(v < 10)).is_ok());
+    }
+
+    #[test]
+    fn verify_reports_minimal_failure() {
+        match verify(0..1000i32, |v| v < 300) {
+            Err(TestError::Fail(why, v)) => {
+                assert_eq!(300, v);
+                assert_eq!("property returned false", why.message());
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+}
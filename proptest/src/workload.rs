@@ -0,0 +1,385 @@
+//-
+// Copyright 2026 The proptest developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Strategies for generating arrival times of events with realistic workload
+//! shapes, for testing schedulers, rate limiters and the like.
+//!
+//! Each strategy generates a `Vec<Duration>` of non-decreasing arrival times,
+//! measured from the start of the workload, with the number of events drawn
+//! from a `SizeRange`. The gaps between consecutive events follow the shape
+//! of the workload:
+//!
+//! - `poisson()`: independent arrivals at a given rate, as from many
+//!   independent clients;
+//! - `bursty()`: bursts of arrivals at a high rate separated by idle periods;
+//! - `periodic()`: arrivals at a fixed period with some jitter;
+//! - `adversarial()`: gaps of zero or just around a period, such as the
+//!   window of a rate limiter, to hit off-by-one errors at its boundaries.
+//!
+//! Arrival times shrink towards fewer events, then towards events evenly
+//! spaced by the mean gap of the workload.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use proptest::prelude::*;
+//! use proptest::workload;
+//!
+//! /// Returns how many of the events arriving at `arrivals` are let through
+//! /// by a limiter allowing one event per `window`.
+//! fn admitted(arrivals: &[Duration], window: Duration) -> usize {
+//!     let mut admitted = 0;
+//!     let mut next_allowed = Duration::ZERO;
+//!     for &arrival in arrivals {
+//!         if arrival >= next_allowed {
+//!             admitted += 1;
+//!             next_allowed = arrival + window;
+//!         }
+//!     }
+//!     admitted
+//! }
+//!
+//! proptest! {
+//!     # /*
+//!     #[test]
+//!     # */
+//!     fn limiter_never_exceeds_its_rate(
+//!         arrivals in workload::bursty(1000.0, 10.0, 20.0, 0..100),
+//!     ) {
+//!         let window = Duration::from_millis(10);
+//!         let span = arrivals.last().copied().unwrap_or_default();
+//!         let max = span.as_nanos() / window.as_nanos() + 1;
+//!         prop_assert!(admitted(&arrivals, window) as u128 <= max);
+//!     }
+//! }
+//! # fn main() { limiter_never_exceeds_its_rate(); }
+//! ```
+
+use core::convert::TryFrom;
+use core::time::Duration;
+
+use rand::Rng;
+
+use crate::collection::{vec, SizeRange, VecStrategy};
+use crate::num;
+use crate::std_facade::Vec;
+use crate::strategy::{Map, NewTree, Strategy, ValueTree};
+use crate::test_runner::TestRunner;
+
+/// Strategy of arrival times, as returned by the functions of this module.
+pub type Arrivals = Map<VecStrategy<Gaps>, fn(Vec<Duration>) -> Vec<Duration>>;
+
+/// The distribution of the gaps between events.
+#[derive(Clone, Copy, Debug)]
+enum Shape {
+    /// Exponentially distributed gaps with the given mean, in nanoseconds
+    Exponential(f64),
+    /// Exponentially distributed gaps with the mean `burst` within bursts, or
+    /// `idle` with the probability `idle_probability` between bursts
+    Bursty {
+        burst: f64,
+        idle: f64,
+        idle_probability: f64,
+    },
+    /// The period plus or minus up to the given jitter, in nanoseconds
+    Jittered(i64),
+    /// Either zero, or the period plus -1, 0 or 1 nanoseconds
+    Adversarial,
+}
+
+/// Strategy of the gaps between consecutive events of a workload.
+///
+/// Gaps shrink towards the mean gap of the workload.
+#[derive(Clone, Copy, Debug)]
+#[must_use = "strategies do nothing unless used"]
+pub struct Gaps {
+    shape: Shape,
+    /// The mean gap, in nanoseconds
+    mean: i64,
+}
+
+impl Strategy for Gaps {
+    type Tree = GapValueTree;
+    type Value = Duration;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let rng = runner.rng();
+        let gap = match self.shape {
+            Shape::Exponential(mean) => exponential(rng.gen(), mean),
+            Shape::Bursty {
+                burst,
+                idle,
+                idle_probability,
+            } => {
+                let mean = if rng.gen_bool(idle_probability) {
+                    idle
+                } else {
+                    burst
+                };
+                exponential(rng.gen(), mean)
+            }
+            Shape::Jittered(jitter) => {
+                self.mean.saturating_add(rng.gen_range(-jitter..=jitter))
+            }
+            Shape::Adversarial => match rng.gen_range(0..4) {
+                0 => 0,
+                offset => self.mean + offset - 2,
+            },
+        };
+        Ok(GapValueTree {
+            mean: self.mean,
+            deviation: num::i64::BinarySearch::new(gap.max(0) - self.mean),
+        })
+    }
+}
+
+/// `ValueTree` corresponding to `Gaps`.
+#[derive(Clone, Copy, Debug)]
+pub struct GapValueTree {
+    /// The mean gap, in nanoseconds
+    mean: i64,
+    /// The deviation of the gap from the mean, in nanoseconds
+    deviation: num::i64::BinarySearch,
+}
+
+impl ValueTree for GapValueTree {
+    type Value = Duration;
+
+    fn current(&self) -> Duration {
+        Duration::from_nanos((self.mean + self.deviation.current()) as u64)
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.deviation.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.deviation.complicate()
+    }
+}
+
+/// Samples an exponentially distributed gap with the given `mean` in
+/// nanoseconds, from `uniform` in `[0, 1)`.
+fn exponential(uniform: f64, mean: f64) -> i64 {
+    // Saturates at the bounds of `i64`
+    (-(1.0 - uniform).ln() * mean) as i64
+}
+
+/// Converts a `rate` in events per second to the mean gap in nanoseconds.
+fn mean_gap(rate: f64, name: &str) -> f64 {
+    assert!(
+        rate.is_finite() && rate > 0.0,
+        "{} must be positive, got {}",
+        name,
+        rate
+    );
+    1e9 / rate
+}
+
+/// Returns the nanoseconds of `duration`, which must fit an `i64`.
+fn nanos(duration: Duration, name: &str) -> i64 {
+    i64::try_from(duration.as_nanos())
+        .unwrap_or_else(|_| panic!("{} is too long: {:?}", name, duration))
+}
+
+/// Converts the gaps between events into their arrival times.
+fn arrival_times(mut gaps: Vec<Duration>) -> Vec<Duration> {
+    let mut time = Duration::ZERO;
+    for gap in &mut gaps {
+        time = time.saturating_add(*gap);
+        *gap = time;
+    }
+    gaps
+}
+
+fn arrivals(shape: Shape, mean: i64, count: impl Into<SizeRange>) -> Arrivals {
+    vec(Gaps { shape, mean }, count)
+        .prop_map(arrival_times as fn(Vec<Duration>) -> Vec<Duration>)
+}
+
+/// Creates a strategy for the arrival times of `count` events arriving
+/// independently at `rate` events per second on average, i.e. a Poisson
+/// process.
+///
+/// ## Panics
+///
+/// Panics if `rate` is not positive.
+pub fn poisson(rate: f64, count: impl Into<SizeRange>) -> Arrivals {
+    let mean = mean_gap(rate, "rate");
+    arrivals(Shape::Exponential(mean), mean as i64, count)
+}
+
+/// Creates a strategy for the arrival times of `count` events arriving in
+/// bursts at `burst_rate` events per second, separated by idle periods
+/// lasting `1 / idle_rate` seconds on average. Bursts have `mean_burst_len`
+/// events on average.
+///
+/// ## Panics
+///
+/// Panics if `burst_rate` or `idle_rate` is not positive, or if
+/// `mean_burst_len` is less than 1.
+pub fn bursty(
+    burst_rate: f64,
+    idle_rate: f64,
+    mean_burst_len: f64,
+    count: impl Into<SizeRange>,
+) -> Arrivals {
+    let burst = mean_gap(burst_rate, "burst_rate");
+    let idle = mean_gap(idle_rate, "idle_rate");
+    assert!(
+        mean_burst_len >= 1.0,
+        "mean_burst_len must be at least 1, got {}",
+        mean_burst_len
+    );
+    let idle_probability = 1.0 / mean_burst_len;
+    let mean = idle_probability * idle + (1.0 - idle_probability) * burst;
+    let shape = Shape::Bursty {
+        burst,
+        idle,
+        idle_probability,
+    };
+    arrivals(shape, mean as i64, count)
+}
+
+/// Creates a strategy for the arrival times of `count` events arriving every
+/// `period`, each gap being off by up to `jitter` either way.
+///
+/// ## Panics
+///
+/// Panics if `jitter` is longer than `period`, or if `period` is longer than
+/// `i64::MAX` nanoseconds.
+pub fn periodic(
+    period: Duration,
+    jitter: Duration,
+    count: impl Into<SizeRange>,
+) -> Arrivals {
+    assert!(
+        jitter <= period,
+        "jitter {:?} is longer than the period {:?}",
+        jitter,
+        period
+    );
+    let shape = Shape::Jittered(nanos(jitter, "jitter"));
+    arrivals(shape, nanos(period, "period"), count)
+}
+
+/// Creates a strategy for the arrival times of `count` events whose gaps are
+/// either zero, i.e. simultaneous events, or `period` give or take a
+/// nanosecond, to hit the boundaries of windows of that `period`.
+///
+/// ## Panics
+///
+/// Panics if `period` is zero or longer than `i64::MAX - 1` nanoseconds.
+pub fn adversarial(period: Duration, count: impl Into<SizeRange>) -> Arrivals {
+    let period = nanos(period, "period");
+    assert!(
+        period > 0 && period < i64::MAX,
+        "period must be between 1 and i64::MAX - 1 nanoseconds"
+    );
+    arrivals(Shape::Adversarial, period, count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_runner::{Config, TestError};
+
+    fn gaps(arrivals: &[Duration]) -> Vec<Duration> {
+        arrivals
+            .iter()
+            .scan(Duration::ZERO, |last, &time| {
+                let gap = time - *last;
+                *last = time;
+                Some(gap)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn poisson_arrivals_have_the_given_rate() {
+        let mut runner = TestRunner::deterministic();
+        let arrivals = poisson(100.0, 10_000)
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+        assert_eq!(10_000, arrivals.len());
+        assert!(arrivals.windows(2).all(|w| w[0] <= w[1]));
+        let span = arrivals.last().unwrap().as_secs_f64();
+        assert!(span > 95.0 && span < 105.0, "span {}", span);
+    }
+
+    #[test]
+    fn bursty_arrivals_alternate_bursts_and_idle_periods() {
+        let mut runner = TestRunner::deterministic();
+        let arrivals = bursty(1e6, 1.0, 10.0, 10_000)
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+        let gaps = gaps(&arrivals);
+        let idle = gaps.iter().filter(|&&gap| gap > Duration::from_millis(1));
+        let idle = idle.count();
+        assert!(idle > 800 && idle < 1200, "{} idle periods", idle);
+    }
+
+    #[test]
+    fn periodic_arrivals_stay_within_jitter() {
+        let period = Duration::from_millis(10);
+        let jitter = Duration::from_millis(1);
+        let mut runner = TestRunner::deterministic();
+        for _ in 0..64 {
+            let arrivals = periodic(period, jitter, 1..50)
+                .new_tree(&mut runner)
+                .unwrap()
+                .current();
+            for gap in gaps(&arrivals) {
+                assert!(gap >= period - jitter && gap <= period + jitter);
+            }
+        }
+    }
+
+    #[test]
+    fn adversarial_gaps_are_around_the_period() {
+        let period = Duration::from_secs(1);
+        let nanosecond = Duration::from_nanos(1);
+        let mut runner = TestRunner::deterministic();
+        let arrivals = adversarial(period, 1000)
+            .new_tree(&mut runner)
+            .unwrap()
+            .current();
+        for gap in gaps(&arrivals) {
+            assert!(
+                gap == Duration::ZERO
+                    || gap == period - nanosecond
+                    || gap == period
+                    || gap == period + nanosecond,
+                "unexpected gap {:?}",
+                gap
+            );
+        }
+    }
+
+    #[test]
+    fn shrinks_towards_fewer_evenly_spaced_events() {
+        let mut runner = TestRunner::new(Config::default());
+        let result = runner.run(&poisson(10.0, 0..100), |arrivals| {
+            prop_assert!(arrivals.len() < 3);
+            Ok(())
+        });
+        let millis = Duration::from_millis;
+        match result {
+            Err(TestError::Fail(_, arrivals)) => {
+                assert_eq!(
+                    vec![millis(100), millis(200), millis(300)],
+                    arrivals
+                )
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
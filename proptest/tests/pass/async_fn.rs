@@ -0,0 +1,16 @@
+fn main() {}
+
+async fn double(x: u32) -> u32 {
+    x.wrapping_mul(2)
+}
+
+#[proptest::property_test]
+async fn awaits_in_body(x: u32) {
+    assert_eq!(x.wrapping_add(x), double(x).await);
+}
+
+#[proptest::property_test(also_exhaustive_small)]
+async fn small_values(x: u8, timeout: bool) {
+    assert_eq!(u32::from(x) * 2, double(u32::from(x)).await);
+    let _ = timeout;
+}
@@ -0,0 +1,11 @@
+fn main() {}
+
+#[proptest::property_test(also_exhaustive_small)]
+fn edge_values(x: u8, s: String, pair: (u8, bool), #[strategy = 1..10i32] y: i32) {
+    assert!(s.chars().count() < 1000);
+    assert!((1..10).contains(&y));
+    let _ = (x, pair);
+}
+
+#[proptest::property_test(also_exhaustive_small)]
+fn no_params() {}
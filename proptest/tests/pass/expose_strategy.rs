@@ -0,0 +1,25 @@
+use proptest::strategy::{Strategy, ValueTree};
+use proptest::test_runner::TestRunner;
+
+fn main() {
+    let mut runner = TestRunner::default();
+    let args = exposed::strategy().new_tree(&mut runner).unwrap().current();
+    assert!(args.field0 < 10);
+    assert!(args.field1.len() <= 3);
+
+    let grouped::GroupedArgs { group0, group1 } =
+        grouped::strategy().new_tree(&mut runner).unwrap().current();
+    assert!(group0.field1);
+    let _ = group1.field2;
+}
+
+#[proptest::property_test(expose_strategy)]
+fn exposed(#[strategy = 0..10u32] x: u32, #[strategy = "[a-z]{0,3}"] s: String) {
+    assert!(x < 10 && s.len() <= 3);
+}
+
+#[proptest::property_test(expose_strategy, group(a, b))]
+fn grouped(a: u8, #[strategy = proptest::strategy::Just(true)] b: bool, c: i64) {
+    assert!(b);
+    let _ = (a, c);
+}
@@ -0,0 +1,29 @@
+fn main() {}
+
+#[proptest::property_test]
+fn many_params(
+    a0: u8,
+    a1: u8,
+    a2: u8,
+    a3: u8,
+    a4: u8,
+    a5: u8,
+    a6: u8,
+    a7: u8,
+    a8: u8,
+    a9: u8,
+    a10: u8,
+    a11: u8,
+) {
+    let sum = [a0, a1, a2, a3, a4, a5, a6, a7, a8, a9, a10, a11]
+        .iter()
+        .map(|&v| u32::from(v))
+        .sum::<u32>();
+    assert!(sum <= 12 * 255);
+}
+
+#[proptest::property_test(group(x, y))]
+fn explicit_groups(x: i32, #[strategy = "[a-z]{1,3}"] y: String, z: u8) {
+    assert!((1..=3).contains(&y.len()));
+    let _ = (x, z);
+}